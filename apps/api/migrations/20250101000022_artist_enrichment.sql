@@ -0,0 +1,22 @@
+-- Resonance: Artist enrichment from Last.fm
+-- Migration: 20250101000022_artist_enrichment
+--
+-- This migration adds the columns used by the worker's artist enrichment job:
+-- 1. tags - descriptive tags (genres, moods, eras) from Last.fm
+-- 2. lastfm_enriched_at - when the artist was last looked up on Last.fm
+-- 3. lastfm_enrichment_error - why the last lookup failed, if it did
+--
+-- The biography is stored in the existing biography column and the Last.fm
+-- MusicBrainz ID in the existing mbid column.
+
+ALTER TABLE artists
+    ADD COLUMN tags TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN lastfm_enriched_at TIMESTAMPTZ,
+    ADD COLUMN lastfm_enrichment_error TEXT;
+
+-- Index for finding artists due for (re-)enrichment
+CREATE INDEX idx_artists_lastfm_enriched_at ON artists(lastfm_enriched_at NULLS FIRST);
+
+COMMENT ON COLUMN artists.tags IS 'Top Last.fm tags for the artist, most popular first';
+COMMENT ON COLUMN artists.lastfm_enriched_at IS 'When the artist was last looked up on Last.fm (NULL = never)';
+COMMENT ON COLUMN artists.lastfm_enrichment_error IS 'Error from the most recent failed Last.fm lookup';
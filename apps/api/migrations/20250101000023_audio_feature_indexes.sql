@@ -0,0 +1,13 @@
+-- Resonance: Expression indexes for audio feature filtering
+-- Migration: 20250101000023_audio_feature_indexes
+--
+-- The tracksByAudioFeatures query filters on numeric values inside the
+-- tracks.audio_features JSONB column using (audio_features->>'<key>')::float8.
+-- These expression indexes match those predicates so range filters on the
+-- most commonly used features don't require a full table scan.
+
+CREATE INDEX idx_tracks_audio_energy ON tracks (((audio_features->>'energy')::float8));
+CREATE INDEX idx_tracks_audio_danceability ON tracks (((audio_features->>'danceability')::float8));
+CREATE INDEX idx_tracks_audio_valence ON tracks (((audio_features->>'valence')::float8));
+CREATE INDEX idx_tracks_audio_bpm ON tracks (((audio_features->>'bpm')::float8));
+CREATE INDEX idx_tracks_audio_acousticness ON tracks (((audio_features->>'acousticness')::float8));
@@ -0,0 +1,16 @@
+-- Resonance: Worker job checkpoints
+-- Migration: 20250101000024_job_checkpoints
+--
+-- Long-running worker jobs (such as the embedding backfill) walk tracks in id
+-- order and record the last id they processed here, so a restarted job resumes
+-- where it stopped instead of starting over. A job deletes its checkpoint once
+-- it completes a full pass.
+
+CREATE TABLE job_checkpoints (
+    job_name TEXT PRIMARY KEY,
+    last_id UUID NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE job_checkpoints IS 'Resume position of long-running worker jobs';
+COMMENT ON COLUMN job_checkpoints.last_id IS 'Last id processed; the job resumes after this id';
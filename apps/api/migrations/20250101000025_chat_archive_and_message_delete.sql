@@ -0,0 +1,38 @@
+-- Resonance: Chat conversation archiving and message soft delete
+-- Migration: 20250101000025_chat_archive_and_message_delete
+--
+-- Archived conversations are hidden from the default conversation list and
+-- from the AI context, but remain available on request. Individual messages
+-- can be soft deleted without deleting their conversation. Also fixes the
+-- search_path of the message timestamp trigger function.
+
+ALTER TABLE chat_conversations ADD COLUMN archived_at TIMESTAMPTZ;
+ALTER TABLE chat_messages ADD COLUMN deleted_at TIMESTAMPTZ;
+
+-- Replace the active-conversation index so the default list skips archived rows
+DROP INDEX IF EXISTS idx_chat_conversations_active_by_user;
+CREATE INDEX idx_chat_conversations_active_by_user
+    ON chat_conversations(user_id, updated_at DESC)
+    WHERE deleted_at IS NULL AND archived_at IS NULL;
+
+COMMENT ON COLUMN chat_conversations.archived_at IS 'When the conversation was archived (NULL if active)';
+COMMENT ON COLUMN chat_messages.deleted_at IS 'Soft delete timestamp (NULL if not deleted)';
+
+-- The message trigger function was created with `search_path = 'pg_catalog, public'`,
+-- which names a single nonexistent schema, so every message insert failed to
+-- resolve chat_conversations. Recreate it with a proper schema list.
+CREATE OR REPLACE FUNCTION update_chat_conversation_updated_at()
+RETURNS TRIGGER AS $$
+BEGIN
+    UPDATE chat_conversations
+    SET updated_at = NOW()
+    WHERE id = NEW.conversation_id
+      AND user_id = NEW.user_id;
+
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Conversation does not belong to user';
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SECURITY DEFINER SET search_path = pg_catalog, public;
@@ -0,0 +1,32 @@
+-- Resonance: Manually requested library scans
+-- Migration: 20250101000026_library_scan_requests
+--
+-- Admins can request a library rescan from the API instead of waiting for the
+-- next scheduled scan. The worker polls this table, claims pending requests,
+-- and records the outcome, so clients can poll a request by id for status.
+-- At most one request may be pending or running at a time; concurrent
+-- triggers resolve to the in-flight request.
+
+CREATE TABLE library_scan_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    full_rescan BOOLEAN NOT NULL DEFAULT FALSE,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+-- Only one in-flight scan request at a time
+CREATE UNIQUE INDEX idx_library_scan_requests_in_flight
+    ON library_scan_requests ((TRUE))
+    WHERE status IN ('pending', 'running');
+
+CREATE INDEX idx_library_scan_requests_created_at
+    ON library_scan_requests(created_at DESC);
+
+COMMENT ON TABLE library_scan_requests IS 'Library scans requested by admins, processed by the worker';
+COMMENT ON COLUMN library_scan_requests.full_rescan IS 'Re-read metadata of unchanged files as well';
+COMMENT ON COLUMN library_scan_requests.status IS 'pending, running, completed or failed';
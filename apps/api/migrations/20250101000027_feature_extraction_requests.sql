@@ -0,0 +1,32 @@
+-- Resonance: Manually requested audio feature recomputes
+-- Migration: 20250101000027_feature_extraction_requests
+--
+-- Admins can ask for one track's audio features to be recomputed (e.g. after
+-- the analysis code changes) without a full rescan. The worker polls this
+-- table, claims pending requests, and records the outcome, so clients can
+-- poll a request by id for status. At most one request per track may be
+-- pending or running; repeated requests resolve to the in-flight one.
+
+CREATE TABLE feature_extraction_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+-- Only one in-flight request per track
+CREATE UNIQUE INDEX idx_feature_extraction_requests_in_flight
+    ON feature_extraction_requests(track_id)
+    WHERE status IN ('pending', 'running');
+
+CREATE INDEX idx_feature_extraction_requests_pending
+    ON feature_extraction_requests(created_at)
+    WHERE status = 'pending';
+
+COMMENT ON TABLE feature_extraction_requests IS 'Audio feature recomputes requested by admins, processed by the worker';
+COMMENT ON COLUMN feature_extraction_requests.status IS 'pending, running, completed or failed';
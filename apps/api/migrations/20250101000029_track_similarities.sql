@@ -0,0 +1,64 @@
+-- Resonance: Precomputed track similarities
+-- Migration: 20250101000029_track_similarities
+--
+-- Combined similarity runs three queries per lookup. The worker's
+-- SimilarityPrecompute job stores each track's top neighbors here, with the
+-- per-dimension scores, so lookups can read them back directly. A track's
+-- rows are deleted whenever its audio features, tags, or embeddings change;
+-- the API falls back to live computation until the worker refills them.
+
+CREATE TABLE track_similarities (
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    similar_track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    semantic_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    acoustic_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    categorical_score DOUBLE PRECISION NOT NULL DEFAULT 0,
+    score DOUBLE PRECISION NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (track_id, similar_track_id),
+    CONSTRAINT track_similarities_not_self CHECK (track_id <> similar_track_id)
+);
+
+CREATE INDEX idx_track_similarities_track_score
+    ON track_similarities(track_id, score DESC);
+
+-- Deleting a track cascades through similar_track_id; index it for that
+CREATE INDEX idx_track_similarities_similar_track
+    ON track_similarities(similar_track_id);
+
+COMMENT ON TABLE track_similarities IS 'Top-N combined similarity neighbors per track, refreshed by the worker';
+COMMENT ON COLUMN track_similarities.score IS 'Combined score using the weights in effect when computed';
+
+-- Invalidate a track's precomputed neighbors when its similarity inputs change
+CREATE OR REPLACE FUNCTION invalidate_track_similarities()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM track_similarities WHERE track_id = NEW.track_id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SET search_path = pg_catalog, public;
+
+CREATE OR REPLACE FUNCTION invalidate_track_similarities_for_track()
+RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM track_similarities WHERE track_id = NEW.id;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql SET search_path = pg_catalog, public;
+
+CREATE TRIGGER invalidate_similarities_on_track_features
+    AFTER UPDATE OF audio_features, genres, ai_mood, ai_tags ON tracks
+    FOR EACH ROW
+    WHEN (
+        OLD.audio_features IS DISTINCT FROM NEW.audio_features
+        OR OLD.genres IS DISTINCT FROM NEW.genres
+        OR OLD.ai_mood IS DISTINCT FROM NEW.ai_mood
+        OR OLD.ai_tags IS DISTINCT FROM NEW.ai_tags
+    )
+    EXECUTE FUNCTION invalidate_track_similarities_for_track();
+
+CREATE TRIGGER invalidate_similarities_on_embedding_insert
+    AFTER INSERT ON track_embeddings
+    FOR EACH ROW EXECUTE FUNCTION invalidate_track_similarities();
+
+-- The trigger for updated embeddings is created by the pgvector migrations
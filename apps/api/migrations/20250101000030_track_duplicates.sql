@@ -0,0 +1,17 @@
+-- Resonance: Duplicate track references
+-- Migration: 20250101000030_track_duplicates
+--
+-- The worker's library scan groups tracks that are likely the same recording
+-- (same content hash, or same normalized artist/title with near-equal
+-- duration) and points each non-canonical copy at the canonical one. Nothing
+-- is deleted; the user resolves groups through the API.
+
+ALTER TABLE tracks
+    ADD COLUMN duplicate_of UUID REFERENCES tracks(id) ON DELETE SET NULL,
+    ADD CONSTRAINT tracks_duplicate_of_not_self CHECK (duplicate_of <> id);
+
+CREATE INDEX idx_tracks_duplicate_of
+    ON tracks(duplicate_of)
+    WHERE duplicate_of IS NOT NULL;
+
+COMMENT ON COLUMN tracks.duplicate_of IS 'Canonical copy of this track when flagged as a likely duplicate';
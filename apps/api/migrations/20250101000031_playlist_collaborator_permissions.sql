@@ -0,0 +1,19 @@
+-- Resonance: Playlist collaborator permission levels
+-- Migration: 20250101000031_playlist_collaborator_permissions
+--
+-- Collaborators get a permission level instead of a single edit flag:
+-- viewers can read, editors can change the track list, and admins can also
+-- rename or delete the playlist and manage collaborators. Existing
+-- collaborators keep their access: editors, or viewers where editing was off.
+--
+-- can_edit is kept (true for editors and admins) because the row level
+-- security policies still check it.
+
+CREATE TYPE collaborator_permission AS ENUM ('viewer', 'editor', 'admin');
+
+ALTER TABLE playlist_collaborators
+    ADD COLUMN permission collaborator_permission NOT NULL DEFAULT 'editor';
+
+UPDATE playlist_collaborators SET permission = 'viewer' WHERE can_edit = false;
+
+COMMENT ON COLUMN playlist_collaborators.permission IS 'Collaborator access level: viewer, editor or admin';
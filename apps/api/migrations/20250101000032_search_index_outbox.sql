@@ -0,0 +1,22 @@
+-- Resonance: Incremental search index updates
+-- Migration: 20250101000032_search_index_outbox
+--
+-- Every track insert, metadata update and delete writes a row here in the
+-- same transaction as the track change, so the search index can never miss
+-- an update. The worker drains the table in id order, re-indexing upserted
+-- tracks and removing deleted ones, and deletes rows once the index has
+-- accepted them.
+--
+-- track_id has no foreign key: removal events must outlive the track.
+
+CREATE TABLE search_index_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    track_id UUID NOT NULL,
+    action TEXT NOT NULL CHECK (action IN ('upsert', 'delete')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_search_index_outbox_track_id ON search_index_outbox(track_id);
+
+COMMENT ON TABLE search_index_outbox IS 'Pending search index updates for tracks, drained by the worker';
+COMMENT ON COLUMN search_index_outbox.action IS 'upsert (re-index the track) or delete (remove it from the index)';
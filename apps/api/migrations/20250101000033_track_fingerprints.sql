@@ -0,0 +1,16 @@
+-- Resonance: Acoustic fingerprints
+-- Migration: 20250101000033_track_fingerprints
+--
+-- Feature extraction stores a Chromaprint-style fingerprint of each track's
+-- first 45 seconds: one 32-bit sub-fingerprint per ~46ms frame, encoded as
+-- 8 hex digits each. Tracks with identical audio have fingerprints that
+-- differ in few bits, whatever their tags or encoding, which enables
+-- matching mystery files and AcoustID lookups.
+--
+-- NULL until a fingerprint has been generated successfully.
+
+ALTER TABLE tracks ADD COLUMN fingerprint TEXT;
+
+CREATE INDEX idx_tracks_has_fingerprint ON tracks(id) WHERE fingerprint IS NOT NULL;
+
+COMMENT ON COLUMN tracks.fingerprint IS 'Acoustic fingerprint: 32-bit sub-fingerprints as concatenated 8-digit hex';
@@ -0,0 +1,26 @@
+-- Resonance: Track analysis run history
+-- Migration: 20250101000034_track_analysis_runs
+--
+-- The worker records one row each time it analyzes a track: audio feature
+-- extraction ('features') or embedding generation ('embedding'), how long
+-- it took, and whether it succeeded. The API averages recent successful
+-- runs to estimate how long analysis of the rest of the library will take,
+-- and reports tracks whose latest run failed.
+
+CREATE TABLE track_analysis_runs (
+    id BIGSERIAL PRIMARY KEY,
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('features', 'embedding')),
+    duration_ms INTEGER NOT NULL CHECK (duration_ms >= 0),
+    succeeded BOOLEAN NOT NULL,
+    finished_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Recent successful runs of a kind, for the rolling average
+CREATE INDEX idx_track_analysis_runs_recent ON track_analysis_runs(kind, finished_at DESC)
+    WHERE succeeded;
+
+-- Latest run per track and kind, for failure counts
+CREATE INDEX idx_track_analysis_runs_track ON track_analysis_runs(track_id, kind, finished_at DESC);
+
+COMMENT ON TABLE track_analysis_runs IS 'Per-track feature extraction and embedding generation timings recorded by the worker';
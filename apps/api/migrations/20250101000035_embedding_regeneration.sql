@@ -0,0 +1,40 @@
+-- Resonance: Embedding versions and full regeneration requests
+-- Migration: 20250101000035_embedding_regeneration
+--
+-- Embeddings built with one model or text template are not comparable with
+-- those built with another, so the worker records the version that produced
+-- each row ("<model>:<dimension>:t<template>"). Rows whose version differs
+-- from the worker's current one are stale and rebuilt by the embedding
+-- backfill. NULL means unknown: embeddings from before versions were
+-- recorded, or ones marked stale by a regeneration request.
+--
+-- Admins can request that every embedding be regenerated, e.g. after
+-- changing the embedding model. The request marks all embeddings stale and
+-- the worker rebuilds them, keeping the old vectors searchable until each is
+-- replaced. At most one regeneration may be pending or running at a time.
+
+ALTER TABLE track_embeddings ADD COLUMN embedding_version TEXT;
+
+COMMENT ON COLUMN track_embeddings.embedding_version IS 'Model, dimension and text template that produced the embeddings; NULL if unknown or marked stale';
+
+CREATE TABLE embedding_regeneration_requests (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    requested_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'running', 'completed', 'failed')),
+    error TEXT,
+    embedded INTEGER NOT NULL DEFAULT 0,
+    skipped INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ
+);
+
+-- Only one in-flight regeneration at a time
+CREATE UNIQUE INDEX idx_embedding_regeneration_requests_in_flight
+    ON embedding_regeneration_requests ((TRUE))
+    WHERE status IN ('pending', 'running');
+
+COMMENT ON TABLE embedding_regeneration_requests IS 'Full embedding regenerations requested by admins, processed by the worker';
+COMMENT ON COLUMN embedding_regeneration_requests.embedded IS 'Tracks re-embedded, updated when the worker finishes or pauses';
+COMMENT ON COLUMN embedding_regeneration_requests.skipped IS 'Tracks whose embedding generation failed';
@@ -0,0 +1,42 @@
+-- Resonance: Track skip events
+-- Migration: 20250101000036_track_skips
+--
+-- Records when a user skips a track and where it was playing from, so the
+-- discover playlist and prefetch jobs can down-weight tracks a user keeps
+-- skipping. A quick skip (the track was abandoned within the quick-skip
+-- threshold) is a strong negative signal; a natural next near the end of a
+-- track is kept for completeness but not penalised.
+
+CREATE TYPE skip_context AS ENUM ('recommendation', 'discover_playlist', 'autoplay', 'other');
+
+CREATE TABLE track_skips (
+    id BIGSERIAL PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    track_id UUID NOT NULL REFERENCES tracks(id) ON DELETE CASCADE,
+    played_ms INTEGER NOT NULL CHECK (played_ms >= 0),
+    quick BOOLEAN NOT NULL,
+    context skip_context NOT NULL,
+    skipped_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Recent skips for a user
+CREATE INDEX idx_track_skips_user_time ON track_skips(user_id, skipped_at DESC);
+-- Quick-skip counts per user and track for recommendation down-weighting
+CREATE INDEX idx_track_skips_user_track_quick
+    ON track_skips(user_id, track_id)
+    WHERE quick;
+
+-- Enable RLS
+ALTER TABLE track_skips ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY track_skips_select_policy ON track_skips
+    FOR SELECT USING (user_id = current_setting('app.current_user_id', true)::UUID);
+
+CREATE POLICY track_skips_insert_policy ON track_skips
+    FOR INSERT WITH CHECK (user_id = current_setting('app.current_user_id', true)::UUID);
+
+CREATE POLICY track_skips_delete_policy ON track_skips
+    FOR DELETE USING (user_id = current_setting('app.current_user_id', true)::UUID);
+
+COMMENT ON TABLE track_skips IS 'Per-user track skip events used to down-weight frequently skipped recommendations';
+COMMENT ON COLUMN track_skips.quick IS 'True when the track was skipped before the quick-skip threshold';
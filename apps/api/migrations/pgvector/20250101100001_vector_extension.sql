@@ -0,0 +1,10 @@
+-- Resonance: pgvector extension
+-- Migration: pgvector/20250101100001_vector_extension
+--
+-- Semantic search, similarity, and embedding-based playlists need pgvector.
+-- The migrations in this directory run after the core migrations, and only
+-- when the server offers the extension; without it Resonance starts with
+-- semantic search disabled. Every statement here is idempotent so databases
+-- created before the vector schema was split out migrate cleanly.
+
+CREATE EXTENSION IF NOT EXISTS "vector";
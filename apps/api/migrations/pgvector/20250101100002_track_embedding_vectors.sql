@@ -0,0 +1,32 @@
+-- Resonance: Embedding vector columns
+-- Migration: pgvector/20250101100002_track_embedding_vectors
+--
+-- Adds the embedding columns to track_embeddings and user_taste_clusters,
+-- with HNSW indexes for fast similarity search.
+
+ALTER TABLE track_embeddings
+ADD COLUMN IF NOT EXISTS title_embedding vector(768),
+ADD COLUMN IF NOT EXISTS description_embedding vector(768),
+ADD COLUMN IF NOT EXISTS audio_embedding vector(128);
+
+COMMENT ON COLUMN track_embeddings.title_embedding IS 'Text embedding of track title (768-dim)';
+COMMENT ON COLUMN track_embeddings.description_embedding IS 'Text embedding of AI description (768-dim)';
+COMMENT ON COLUMN track_embeddings.audio_embedding IS 'Audio feature embedding (128-dim)';
+
+-- HNSW indexes on vector columns for fast similarity search
+CREATE INDEX IF NOT EXISTS idx_track_embeddings_title ON track_embeddings
+    USING hnsw(title_embedding vector_cosine_ops)
+    WITH (m = 16, ef_construction = 64);
+
+CREATE INDEX IF NOT EXISTS idx_track_embeddings_description ON track_embeddings
+    USING hnsw(description_embedding vector_cosine_ops)
+    WITH (m = 16, ef_construction = 64);
+
+CREATE INDEX IF NOT EXISTS idx_track_embeddings_audio ON track_embeddings
+    USING hnsw(audio_embedding vector_cosine_ops)
+    WITH (m = 16, ef_construction = 64);
+
+ALTER TABLE user_taste_clusters
+ADD COLUMN IF NOT EXISTS centroid_embedding vector(768);
+
+COMMENT ON COLUMN user_taste_clusters.centroid_embedding IS '768-dimensional centroid vector for similarity search against track embeddings';
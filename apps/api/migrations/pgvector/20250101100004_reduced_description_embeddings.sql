@@ -0,0 +1,19 @@
+-- Resonance: Reduced-dimension description embeddings for fast semantic search
+-- Migration: pgvector/20250101100004_reduced_description_embeddings
+--
+-- Exact cosine search over 768-dim description embeddings gets slow on large
+-- libraries. The worker stores a 128-dim random projection of each
+-- description embedding alongside it; fast search shortlists candidates on
+-- the reduced vector and re-ranks them with the full one. The projection is
+-- computed in Rust (resonance_ollama_client::reduce_embedding), so existing
+-- rows are backfilled by the worker's EmbeddingReduction job, not here.
+
+ALTER TABLE track_embeddings
+ADD COLUMN IF NOT EXISTS description_embedding_reduced vector(128);
+
+COMMENT ON COLUMN track_embeddings.description_embedding_reduced IS 'Random projection of description_embedding (128-dim) for candidate filtering';
+
+-- Parameters match existing HNSW indexes: m=16, ef_construction=64
+CREATE INDEX IF NOT EXISTS idx_track_embeddings_description_reduced ON track_embeddings
+    USING hnsw(description_embedding_reduced vector_cosine_ops)
+    WITH (m = 16, ef_construction = 64);
@@ -0,0 +1,16 @@
+-- Resonance: Invalidate precomputed similarities when embeddings change
+-- Migration: pgvector/20250101100005_similarity_invalidation_on_embedding_update
+--
+-- Completes the invalidation triggers from the core track_similarities
+-- migration for the vector columns, which only exist with pgvector.
+
+DROP TRIGGER IF EXISTS invalidate_similarities_on_embedding_update ON track_embeddings;
+
+CREATE TRIGGER invalidate_similarities_on_embedding_update
+    AFTER UPDATE OF description_embedding, audio_features_vector ON track_embeddings
+    FOR EACH ROW
+    WHEN (
+        OLD.description_embedding IS DISTINCT FROM NEW.description_embedding
+        OR OLD.audio_features_vector IS DISTINCT FROM NEW.audio_features_vector
+    )
+    EXECUTE FUNCTION invalidate_track_similarities();
@@ -0,0 +1,10 @@
+-- Resonance: PostgreSQL Extensions
+-- Migration: 20250101000001_extensions
+
+-- Enable required PostgreSQL extensions
+--
+-- pgvector is optional: it and every vector column are created by the
+-- migrations in pgvector/, which run only when the extension is available.
+CREATE EXTENSION IF NOT EXISTS "uuid-ossp";
+CREATE EXTENSION IF NOT EXISTS "pgcrypto";
+CREATE EXTENSION IF NOT EXISTS "pg_trgm";
@@ -0,0 +1,8 @@
+-- Resonance: Add pre-computed audio features vector for fast acoustic similarity search
+-- Migration: 20250101000017_add_audio_features_vector
+--
+-- The audio features vector needs pgvector; without it there is nothing to
+-- add. pgvector/20250101100003_audio_features_vector adds the column, index
+-- and backfill once the extension becomes available.
+
+SELECT 1;
@@ -1,16 +1,40 @@
 //! API server configuration
 
 use std::env;
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::{bail, Context, Result};
 use resonance_shared_config::{
-    CommonConfig, DatabaseConfig, Environment, LidarrConfig, OllamaConfig, RedisConfig,
+    parse_duration_env, CommonConfig, DatabaseConfig, Environment, FeatureFlags, LidarrConfig,
+    OllamaConfig, Redacted, RedisConfig,
+};
+
+use crate::middleware::cors::{DEFAULT_CORS_EXPOSE_HEADERS, DEFAULT_CORS_MAX_AGE_SECS};
+use crate::middleware::AuthRateLimitConfig;
+use crate::models::AudioFormat;
+use crate::routes::streaming::{
+    DEFAULT_RAW_STREAM_FORMATS, DEFAULT_STREAM_CHUNK_BYTES, MAX_STREAM_CHUNK_BYTES,
+    MIN_STREAM_CHUNK_BYTES,
+};
+use crate::services::transcoder::{
+    default_max_concurrent_transcodes, TranscodeFormat, DEFAULT_MAX_QUEUED_TRANSCODES,
+    DEFAULT_TRANSCODE_QUEUE_TIMEOUT,
 };
 
 /// Minimum required length for JWT_SECRET to be considered secure
 const MIN_JWT_SECRET_LENGTH: usize = 32;
 
+/// Default maximum size of an inbound WebSocket message (64 KiB)
+pub const DEFAULT_WS_MAX_MESSAGE_BYTES: usize = 64 * 1024;
+
+/// Default sustained inbound WebSocket message rate per connection
+pub const DEFAULT_WS_MESSAGES_PER_SEC: u32 = 20;
+
+/// Default number of WebSocket messages a connection may send in a burst
+pub const DEFAULT_WS_MESSAGE_BURST: u32 = 40;
+
 /// API server configuration loaded from environment variables
 #[derive(Debug, Clone)]
 #[allow(dead_code)]
@@ -27,14 +51,20 @@ pub struct Config {
     /// Meilisearch API key
     pub meilisearch_key: String,
 
-    /// JWT secret for authentication
-    pub jwt_secret: String,
+    /// JWT secret for authentication (redacted in logs)
+    pub jwt_secret: Redacted<String>,
 
     /// JWT access token expiry (default: 15m)
-    pub jwt_access_expiry: String,
+    pub jwt_access_expiry: Duration,
 
     /// JWT refresh token expiry (default: 7d)
-    pub jwt_refresh_expiry: String,
+    pub jwt_refresh_expiry: Duration,
+
+    /// JWT issuer minted into and required of tokens (optional, unchecked if unset)
+    pub jwt_issuer: Option<String>,
+
+    /// JWT audience minted into and required of tokens (optional, unchecked if unset)
+    pub jwt_audience: Option<String>,
 
     /// ListenBrainz API key (optional)
     pub listenbrainz_api_key: Option<String>,
@@ -44,6 +74,60 @@ pub struct Config {
 
     /// CORS allowed origins (optional)
     pub cors_allowed_origins: Option<Vec<String>>,
+
+    /// How long browsers may cache CORS preflight responses (default: 3600s)
+    pub cors_max_age_secs: u64,
+
+    /// Response headers readable by cross-origin clients
+    /// (default: request id, range and caching headers)
+    pub cors_expose_headers: Vec<String>,
+
+    /// Directory for cached transcodes (optional, caching disabled if unset)
+    pub transcode_cache_path: Option<PathBuf>,
+
+    /// Maximum transcode cache size in gigabytes (default: 10)
+    pub transcode_cache_size_gb: u64,
+
+    /// Maximum concurrent transcodes (default: number of CPUs)
+    pub transcode_max_concurrent: usize,
+
+    /// Transcodes that may wait for a free slot before requests get 503 (default: 16)
+    pub transcode_max_queued: usize,
+
+    /// How long a queued transcode waits for a free slot (default: 10s)
+    pub transcode_queue_timeout_secs: u64,
+
+    /// Original formats that may be streamed untranscoded with `?raw=true`
+    /// (default: flac, mp3, aac, opus, ogg)
+    pub raw_stream_formats: Vec<AudioFormat>,
+
+    /// Bytes read per chunk when streaming files and transcodes (default: 64 KiB)
+    pub stream_chunk_bytes: usize,
+
+    /// Transcode format served when a stream request's `Accept` header allows
+    /// no format the server can produce (default: none, serve the original)
+    pub stream_default_format: Option<TranscodeFormat>,
+
+    /// Directory for resized album art (default: `resonance-art` in the system temp dir)
+    pub art_cache_path: PathBuf,
+
+    /// Only serve `/metrics` to loopback and private network peers (default: false)
+    pub metrics_internal_only: bool,
+
+    /// How long in-flight requests may run after a shutdown signal (default: 30s)
+    pub shutdown_drain_timeout_secs: u64,
+
+    /// Largest WebSocket message a client may send (default: 64 KiB)
+    pub ws_max_message_bytes: usize,
+
+    /// Sustained WebSocket messages per second per connection, 0 = unlimited (default: 20)
+    pub ws_messages_per_sec: u32,
+
+    /// WebSocket messages a connection may send in a burst (default: 40)
+    pub ws_message_burst: u32,
+
+    /// Login and registration rate limits (default: 5/60s and 3/3600s per IP)
+    pub auth_rate_limits: AuthRateLimitConfig,
 }
 
 impl Config {
@@ -88,11 +172,15 @@ impl Config {
 
             meilisearch_key,
 
-            jwt_secret,
+            jwt_secret: Redacted::new(jwt_secret),
+
+            jwt_access_expiry: parse_duration_env("JWT_ACCESS_EXPIRY", "15m")?,
 
-            jwt_access_expiry: env::var("JWT_ACCESS_EXPIRY").unwrap_or_else(|_| "15m".to_string()),
+            jwt_refresh_expiry: parse_duration_env("JWT_REFRESH_EXPIRY", "7d")?,
 
-            jwt_refresh_expiry: env::var("JWT_REFRESH_EXPIRY").unwrap_or_else(|_| "7d".to_string()),
+            jwt_issuer: env::var("JWT_ISSUER").ok().filter(|s| !s.is_empty()),
+
+            jwt_audience: env::var("JWT_AUDIENCE").ok().filter(|s| !s.is_empty()),
 
             listenbrainz_api_key: env::var("LISTENBRAINZ_API_KEY")
                 .ok()
@@ -106,9 +194,109 @@ impl Config {
                     .filter(|s| !s.is_empty())
                     .collect()
             }),
+
+            cors_max_age_secs: env::var("CORS_MAX_AGE_SECS")
+                .unwrap_or_else(|_| DEFAULT_CORS_MAX_AGE_SECS.to_string())
+                .parse()
+                .context("Invalid CORS_MAX_AGE_SECS value")?,
+
+            cors_expose_headers: env::var("CORS_EXPOSE_HEADERS")
+                .map(|s| {
+                    s.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_else(|_| {
+                    DEFAULT_CORS_EXPOSE_HEADERS
+                        .iter()
+                        .map(|h| h.to_string())
+                        .collect()
+                }),
+
+            transcode_cache_path: env::var("TRANSCODE_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from),
+
+            transcode_cache_size_gb: env::var("TRANSCODE_CACHE_SIZE_GB")
+                .unwrap_or_else(|_| "10".to_string())
+                .parse()
+                .context("Invalid TRANSCODE_CACHE_SIZE_GB value")?,
+
+            transcode_max_concurrent: Self::load_transcode_max_concurrent()?,
+
+            transcode_max_queued: env::var("TRANSCODE_MAX_QUEUED")
+                .unwrap_or_else(|_| DEFAULT_MAX_QUEUED_TRANSCODES.to_string())
+                .parse()
+                .context("Invalid TRANSCODE_MAX_QUEUED value")?,
+
+            transcode_queue_timeout_secs: env::var("TRANSCODE_QUEUE_TIMEOUT_SECS")
+                .unwrap_or_else(|_| DEFAULT_TRANSCODE_QUEUE_TIMEOUT.as_secs().to_string())
+                .parse()
+                .context("Invalid TRANSCODE_QUEUE_TIMEOUT_SECS value")?,
+
+            raw_stream_formats: match env::var("RAW_STREAM_FORMATS") {
+                Ok(value) => {
+                    Self::parse_audio_formats(&value).context("Invalid RAW_STREAM_FORMATS value")?
+                }
+                Err(_) => DEFAULT_RAW_STREAM_FORMATS.to_vec(),
+            },
+
+            stream_chunk_bytes: Self::load_stream_chunk_bytes()?,
+
+            stream_default_format: Self::load_stream_default_format()?,
+
+            art_cache_path: env::var("ART_CACHE_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| env::temp_dir().join("resonance-art")),
+
+            metrics_internal_only: env::var("METRICS_INTERNAL_ONLY")
+                .map(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(false),
+
+            shutdown_drain_timeout_secs: env::var("SHUTDOWN_DRAIN_TIMEOUT_SECS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .context("Invalid SHUTDOWN_DRAIN_TIMEOUT_SECS value")?,
+
+            ws_max_message_bytes: env::var("WS_MAX_MESSAGE_BYTES")
+                .unwrap_or_else(|_| DEFAULT_WS_MAX_MESSAGE_BYTES.to_string())
+                .parse()
+                .context("Invalid WS_MAX_MESSAGE_BYTES value")?,
+
+            ws_messages_per_sec: env::var("WS_MESSAGES_PER_SEC")
+                .unwrap_or_else(|_| DEFAULT_WS_MESSAGES_PER_SEC.to_string())
+                .parse()
+                .context("Invalid WS_MESSAGES_PER_SEC value")?,
+
+            ws_message_burst: env::var("WS_MESSAGE_BURST")
+                .unwrap_or_else(|_| DEFAULT_WS_MESSAGE_BURST.to_string())
+                .parse()
+                .context("Invalid WS_MESSAGE_BURST value")?,
+
+            auth_rate_limits: AuthRateLimitConfig::from_env()
+                .map_err(|e| anyhow::anyhow!("Invalid auth rate limit: {}", e))?,
         })
     }
 
+    /// Parse a comma-separated list of audio format names
+    ///
+    /// An empty list is allowed (it disables whatever the list enables).
+    fn parse_audio_formats(value: &str) -> Result<Vec<AudioFormat>> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|name| {
+                AudioFormat::parse(name)
+                    .ok_or_else(|| anyhow::anyhow!("unknown audio format '{}'", name))
+            })
+            .collect()
+    }
+
     /// Load and validate JWT_SECRET
     ///
     /// In production:
@@ -146,6 +334,62 @@ impl Config {
         }
     }
 
+    /// Load TRANSCODE_MAX_CONCURRENT, defaulting to the number of CPUs
+    fn load_transcode_max_concurrent() -> Result<usize> {
+        match env::var("TRANSCODE_MAX_CONCURRENT") {
+            Ok(value) => {
+                let max: usize = value
+                    .trim()
+                    .parse()
+                    .context("Invalid TRANSCODE_MAX_CONCURRENT value")?;
+                if max == 0 {
+                    bail!("TRANSCODE_MAX_CONCURRENT must be at least 1");
+                }
+                Ok(max)
+            }
+            Err(_) => Ok(default_max_concurrent_transcodes()),
+        }
+    }
+
+    /// Load STREAM_CHUNK_BYTES, rejecting sizes outside the supported range
+    fn load_stream_chunk_bytes() -> Result<usize> {
+        let chunk_bytes: usize = match env::var("STREAM_CHUNK_BYTES") {
+            Ok(value) => value
+                .trim()
+                .parse()
+                .context("Invalid STREAM_CHUNK_BYTES value")?,
+            Err(_) => return Ok(DEFAULT_STREAM_CHUNK_BYTES),
+        };
+        if !(MIN_STREAM_CHUNK_BYTES..=MAX_STREAM_CHUNK_BYTES).contains(&chunk_bytes) {
+            bail!(
+                "STREAM_CHUNK_BYTES must be between {} and {} (got {})",
+                MIN_STREAM_CHUNK_BYTES,
+                MAX_STREAM_CHUNK_BYTES,
+                chunk_bytes
+            );
+        }
+        Ok(chunk_bytes)
+    }
+
+    /// Load STREAM_DEFAULT_FORMAT; unset, empty or `original` means none
+    fn load_stream_default_format() -> Result<Option<TranscodeFormat>> {
+        match env::var("STREAM_DEFAULT_FORMAT") {
+            Ok(value) => {
+                let value = value.trim();
+                if value.is_empty() || value.eq_ignore_ascii_case("original") {
+                    return Ok(None);
+                }
+                TranscodeFormat::parse(value).map(Some).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Invalid STREAM_DEFAULT_FORMAT value '{}': expected mp3, aac, opus, flac or original",
+                        value
+                    )
+                })
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
     /// Load and validate MEILISEARCH_KEY
     ///
     /// In production: MEILISEARCH_KEY must be explicitly set
@@ -202,6 +446,11 @@ impl Config {
         &self.common.ollama
     }
 
+    /// Get feature flags
+    pub fn features(&self) -> FeatureFlags {
+        self.common.features
+    }
+
     /// Get Lidarr configuration (if configured)
     #[allow(dead_code)]
     pub fn lidarr(&self) -> Option<&LidarrConfig> {
@@ -232,6 +481,12 @@ impl Config {
         self.discord_client_id.is_some()
     }
 
+    /// Get the transcode cache size budget in bytes
+    pub fn transcode_cache_max_bytes(&self) -> u64 {
+        self.transcode_cache_size_gb
+            .saturating_mul(1024 * 1024 * 1024)
+    }
+
     /// Check if running in production
     #[allow(dead_code)]
     pub fn is_production(&self) -> bool {
@@ -412,4 +667,58 @@ mod tests {
         let result = Config::validate_database_url();
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_audio_formats() {
+        assert_eq!(
+            Config::parse_audio_formats(" FLAC, mp3 ,,wav").unwrap(),
+            vec![AudioFormat::Flac, AudioFormat::Mp3, AudioFormat::Wav]
+        );
+        assert!(Config::parse_audio_formats("").unwrap().is_empty());
+        assert!(Config::parse_audio_formats("flac,wma").is_err());
+    }
+
+    #[test]
+    fn test_stream_chunk_bytes_range() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+
+        let _guard = EnvGuard::remove_vars(&["STREAM_CHUNK_BYTES"]);
+        assert_eq!(
+            Config::load_stream_chunk_bytes().unwrap(),
+            DEFAULT_STREAM_CHUNK_BYTES
+        );
+
+        let _guard = EnvGuard::new(&[("STREAM_CHUNK_BYTES", "262144")]);
+        assert_eq!(Config::load_stream_chunk_bytes().unwrap(), 262144);
+
+        for invalid in ["1024", "16777216", "0", "lots"] {
+            let _guard = EnvGuard::new(&[("STREAM_CHUNK_BYTES", invalid)]);
+            assert!(
+                Config::load_stream_chunk_bytes().is_err(),
+                "{} should be rejected",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn test_stream_default_format() {
+        let _lock = ENV_MUTEX.lock().unwrap();
+
+        let _guard = EnvGuard::remove_vars(&["STREAM_DEFAULT_FORMAT"]);
+        assert_eq!(Config::load_stream_default_format().unwrap(), None);
+
+        for (value, expected) in [
+            ("opus", Some(TranscodeFormat::Opus)),
+            (" MP3 ", Some(TranscodeFormat::Mp3)),
+            ("original", None),
+            ("", None),
+        ] {
+            let _guard = EnvGuard::new(&[("STREAM_DEFAULT_FORMAT", value)]);
+            assert_eq!(Config::load_stream_default_format().unwrap(), expected);
+        }
+
+        let _guard = EnvGuard::new(&[("STREAM_DEFAULT_FORMAT", "wma")]);
+        assert!(Config::load_stream_default_format().is_err());
+    }
 }
@@ -3,14 +3,18 @@
 //! This module provides a unified error type hierarchy using thiserror,
 //! with automatic HTTP status code mapping via Axum's IntoResponse trait.
 
+use async_graphql::ErrorExtensions;
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
+use resonance_media::CoverArtError;
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::middleware::request_id::current_request_id;
+
 /// API error response body
 #[derive(Debug, Serialize)]
 pub struct ErrorResponse {
@@ -21,6 +25,9 @@ pub struct ErrorResponse {
     /// Optional additional details
     #[serde(skip_serializing_if = "Option::is_none")]
     pub details: Option<serde_json::Value>,
+    /// Correlation id of the failed request (matches the `X-Request-Id` header)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 /// Main API error type with comprehensive error variants
@@ -35,6 +42,10 @@ pub enum ApiError {
     #[error("invalid authentication token: {0}")]
     InvalidToken(String),
 
+    /// Token was issued by another issuer or for another audience
+    #[error("token issuer or audience mismatch: {0}")]
+    TokenIssuerMismatch(String),
+
     /// User lacks permission for the requested operation
     #[error("insufficient permissions: {0}")]
     Forbidden(String),
@@ -80,9 +91,9 @@ pub enum ApiError {
     #[error("database connection unavailable")]
     DatabaseUnavailable,
 
-    /// Service temporarily unavailable (at capacity)
-    #[error("service temporarily unavailable: {0}")]
-    ServiceBusy(String),
+    /// Service temporarily unavailable (at capacity); retry after `retry_after` seconds
+    #[error("service temporarily unavailable: {message}")]
+    ServiceBusy { message: String, retry_after: u64 },
 
     // ========== External Service Errors ==========
     /// Redis operation failed
@@ -97,6 +108,19 @@ pub enum ApiError {
     #[error("AI service error: {0}")]
     AiService(String),
 
+    /// Ollama can't be reached (503); `retry_after` is the circuit breaker's
+    /// remaining cool-down in seconds, when known
+    #[error("AI service temporarily unavailable")]
+    AiUnavailable { retry_after: Option<u64> },
+
+    /// Ollama is still loading the AI model (503, retry shortly)
+    #[error("AI model is warming up, try again shortly")]
+    AiModelLoading,
+
+    /// The database lacks the pgvector extension, so embedding search is off
+    #[error("semantic search unavailable: the pgvector extension is not installed")]
+    SemanticSearchUnavailable,
+
     /// Lidarr integration error
     #[error("Lidarr integration error: {0}")]
     Lidarr(String),
@@ -149,6 +173,10 @@ pub enum ApiError {
     #[error("configuration error: {0}")]
     Configuration(String),
 
+    /// The feature is switched off for this deployment
+    #[error("feature disabled: {0}")]
+    FeatureDisabled(&'static str),
+
     // ========== Internal Errors ==========
     /// Internal server error (catch-all for unexpected errors)
     #[error("internal server error: {0}")]
@@ -172,7 +200,9 @@ impl ApiError {
     pub fn status_code(&self) -> StatusCode {
         match self {
             // 401 Unauthorized
-            Self::Unauthorized | Self::InvalidToken(_) => StatusCode::UNAUTHORIZED,
+            Self::Unauthorized | Self::InvalidToken(_) | Self::TokenIssuerMismatch(_) => {
+                StatusCode::UNAUTHORIZED
+            }
 
             // 403 Forbidden
             Self::Forbidden(_) => StatusCode::FORBIDDEN,
@@ -200,11 +230,18 @@ impl ApiError {
             // 429 Too Many Requests
             Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
 
+            // 501 Not Implemented (switched off for this deployment)
+            Self::FeatureDisabled(_) => StatusCode::NOT_IMPLEMENTED,
+
             // 504 Gateway Timeout
             Self::QueryTimeout { .. } => StatusCode::GATEWAY_TIMEOUT,
 
             // 503 Service Unavailable
-            Self::DatabaseUnavailable | Self::ServiceBusy(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::DatabaseUnavailable
+            | Self::ServiceBusy { .. }
+            | Self::AiUnavailable { .. }
+            | Self::AiModelLoading
+            | Self::SemanticSearchUnavailable => StatusCode::SERVICE_UNAVAILABLE,
 
             // 502 Bad Gateway (external service errors)
             Self::Search(_)
@@ -230,6 +267,7 @@ impl ApiError {
         match self {
             Self::Unauthorized => "UNAUTHORIZED",
             Self::InvalidToken(_) => "INVALID_TOKEN",
+            Self::TokenIssuerMismatch(_) => "TOKEN_ISSUER_MISMATCH",
             Self::Forbidden(_) => "FORBIDDEN",
             Self::NotFound { .. } => "NOT_FOUND",
             Self::Conflict { .. } => "CONFLICT",
@@ -239,10 +277,13 @@ impl ApiError {
             Self::InvalidQueryParam { .. } => "INVALID_QUERY_PARAM",
             Self::Database(_) => "DATABASE_ERROR",
             Self::DatabaseUnavailable => "DATABASE_UNAVAILABLE",
-            Self::ServiceBusy(_) => "SERVICE_BUSY",
+            Self::ServiceBusy { .. } => "SERVICE_BUSY",
             Self::Redis(_) => "CACHE_ERROR",
             Self::Search(_) => "SEARCH_ERROR",
             Self::AiService(_) => "AI_SERVICE_ERROR",
+            Self::AiUnavailable { .. } => "AI_UNAVAILABLE",
+            Self::AiModelLoading => "AI_MODEL_LOADING",
+            Self::SemanticSearchUnavailable => "SEMANTIC_SEARCH_UNAVAILABLE",
             Self::Lidarr(_) => "LIDARR_ERROR",
             Self::Lastfm(_) => "LASTFM_ERROR",
             Self::ListenBrainz(_) => "LISTENBRAINZ_ERROR",
@@ -255,6 +296,7 @@ impl ApiError {
             Self::RateLimited { .. } => "RATE_LIMITED",
             Self::QueryTimeout { .. } => "QUERY_TIMEOUT",
             Self::Configuration(_) => "CONFIGURATION_ERROR",
+            Self::FeatureDisabled(_) => "FEATURE_DISABLED",
             Self::Internal(_) => "INTERNAL_ERROR",
             Self::Serialization(_) => "SERIALIZATION_ERROR",
             Self::WebSocket(_) => "WEBSOCKET_ERROR",
@@ -316,10 +358,23 @@ impl IntoResponse for ApiError {
             code: self.error_code(),
             message: self.to_string(),
             details: None,
+            request_id: current_request_id(),
         };
 
-        // For rate limiting, add Retry-After header
-        if let Self::RateLimited { retry_after } = &self {
+        // For rate limiting and busy services, add Retry-After header
+        if let Self::RateLimited { retry_after } | Self::ServiceBusy { retry_after, .. } = &self {
+            return (
+                status,
+                [("Retry-After", retry_after.to_string())],
+                Json(error_response),
+            )
+                .into_response();
+        }
+
+        if let Self::AiUnavailable {
+            retry_after: Some(retry_after),
+        } = &self
+        {
             return (
                 status,
                 [("Retry-After", retry_after.to_string())],
@@ -342,6 +397,23 @@ impl IntoResponse for ApiError {
     }
 }
 
+impl ErrorExtensions for ApiError {
+    /// GraphQL error carrying the error code, plus `retryAfterSeconds` when
+    /// the AI service is unavailable, so clients can tell a retryable outage
+    /// from a bad response
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(self.to_string()).extend_with(|_, e| {
+            e.set("code", self.error_code());
+            if let Self::AiUnavailable {
+                retry_after: Some(retry_after),
+            } = self
+            {
+                e.set("retryAfterSeconds", *retry_after);
+            }
+        })
+    }
+}
+
 /// Result type alias for API operations
 pub type ApiResult<T> = Result<T, ApiError>;
 
@@ -369,6 +441,15 @@ impl From<std::io::Error> for ApiError {
     }
 }
 
+impl From<CoverArtError> for ApiError {
+    fn from(err: CoverArtError) -> Self {
+        match err {
+            CoverArtError::Encode(_) => Self::Internal(err.to_string()),
+            _ => Self::ValidationError(err.to_string()),
+        }
+    }
+}
+
 impl From<std::env::VarError> for ApiError {
     fn from(err: std::env::VarError) -> Self {
         Self::Configuration(err.to_string())
@@ -397,11 +478,27 @@ mod tests {
             ApiError::RateLimited { retry_after: 60 }.status_code(),
             StatusCode::TOO_MANY_REQUESTS
         );
+        assert_eq!(
+            ApiError::FeatureDisabled("semantic search").status_code(),
+            StatusCode::NOT_IMPLEMENTED
+        );
+        assert_eq!(
+            ApiError::SemanticSearchUnavailable.status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(
+            ApiError::AiUnavailable { retry_after: None }.status_code(),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
     }
 
     #[test]
     fn test_error_codes() {
         assert_eq!(ApiError::Unauthorized.error_code(), "UNAUTHORIZED");
+        assert_eq!(
+            ApiError::TokenIssuerMismatch("aud".to_string()).error_code(),
+            "TOKEN_ISSUER_MISMATCH"
+        );
         assert_eq!(
             ApiError::not_found("track", "123").error_code(),
             "NOT_FOUND"
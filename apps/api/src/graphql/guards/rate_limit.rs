@@ -12,6 +12,7 @@ use tracing::{debug, warn};
 
 use crate::middleware::rate_limit::{RateLimitConfig, RateLimiter};
 use crate::models::user::RequestMetadata;
+use crate::services::metrics::Metrics;
 
 /// Rate limiter wrapper for GraphQL context
 ///
@@ -27,10 +28,11 @@ pub struct GraphQLRateLimiter {
 }
 
 impl GraphQLRateLimiter {
-    /// Create a new GraphQL rate limiter with a Redis client
-    pub fn new(redis_client: redis::Client) -> Self {
+    /// Create a new GraphQL rate limiter with a Redis client, counting
+    /// rejections in `metrics`
+    pub fn new(redis_client: redis::Client, metrics: Metrics) -> Self {
         Self {
-            limiter: RateLimiter::new(redis_client),
+            limiter: RateLimiter::new(redis_client, metrics),
             login_config: RateLimitConfig::login(),
             register_config: RateLimitConfig::register(),
             refresh_config: RateLimitConfig::refresh_token(),
@@ -46,7 +48,7 @@ impl GraphQLRateLimiter {
         // This will cause the RateLimiter to use its in-memory fallback
         let dummy_client = redis::Client::open("redis://localhost:0").unwrap();
         Self {
-            limiter: RateLimiter::new(dummy_client),
+            limiter: RateLimiter::new(dummy_client, Metrics::new()),
             login_config: RateLimitConfig::login(),
             register_config: RateLimitConfig::register(),
             refresh_config: RateLimitConfig::refresh_token(),
@@ -0,0 +1,79 @@
+//! Idempotency-Key support for GraphQL mutations
+//!
+//! The GraphQL handler validates the `Idempotency-Key` request header and adds
+//! it to the request context. Non-idempotent mutations wrap their write in
+//! [`idempotent`], which performs it once per key and replays the recorded
+//! result for repeats. Requests without the header behave as before.
+
+use std::future::Future;
+
+use async_graphql::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::services::idempotency::{IdempotencyKey, IdempotencyStore, Reservation};
+
+/// Header carrying the client's idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Run a mutation's action at most once per idempotency key
+///
+/// `operation` names the mutation; keys are scoped to it and to `user_id`. If
+/// the request carries no key (or no store is configured) the action simply
+/// runs. A failed action releases the key so the client can retry it.
+///
+/// # Errors
+/// - Returns the action's error
+/// - Returns error if an earlier request with the same key is still running
+pub async fn idempotent<T, F, Fut>(
+    ctx: &Context<'_>,
+    user_id: Uuid,
+    operation: &str,
+    action: F,
+) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let (Some(key), Some(store)) = (
+        ctx.data_opt::<IdempotencyKey>(),
+        ctx.data_opt::<IdempotencyStore>(),
+    ) else {
+        return action().await;
+    };
+
+    match store.reserve(user_id, operation, key).await {
+        Reservation::Reserved => {}
+        Reservation::Completed(recorded) => {
+            tracing::debug!(%user_id, operation, "Replaying idempotent mutation result");
+            return serde_json::from_str(&recorded).map_err(|e| {
+                tracing::error!(error = %e, operation, "Failed to decode recorded mutation result");
+                async_graphql::Error::new("Failed to replay request for this Idempotency-Key")
+            });
+        }
+        Reservation::InProgress => {
+            return Err(async_graphql::Error::new(
+                "A request with this Idempotency-Key is already in progress",
+            ));
+        }
+    }
+
+    match action().await {
+        Ok(result) => {
+            match serde_json::to_string(&result) {
+                Ok(recorded) => store.complete(user_id, operation, key, &recorded).await,
+                Err(e) => {
+                    tracing::error!(error = %e, operation, "Failed to record mutation result");
+                    store.release(user_id, operation, key).await;
+                }
+            }
+            Ok(result)
+        }
+        Err(e) => {
+            store.release(user_id, operation, key).await;
+            Err(e)
+        }
+    }
+}
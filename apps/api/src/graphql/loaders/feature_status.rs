@@ -0,0 +1,80 @@
+//! Feature-extraction status DataLoader for batched fetching
+//!
+//! This loader batches the lookups behind `Track.featureStatus` into a single
+//! query: whether each track has extracted audio features, whether it has a
+//! `track_embeddings` row, and the outcome of its latest extraction request.
+
+use async_graphql::dataloader::Loader;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Raw extraction state for one track
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct TrackFeatureState {
+    pub track_id: Uuid,
+    /// At least one audio feature has a non-null value
+    pub has_features: bool,
+    /// A `track_embeddings` row exists
+    pub has_embedding: bool,
+    /// Error recorded by the latest extraction request, if that request failed
+    pub failure_reason: Option<String>,
+    /// Whether the latest extraction request failed
+    pub failed: bool,
+}
+
+/// DataLoader for batching feature-extraction status queries
+#[derive(Clone)]
+pub struct FeatureStatusLoader {
+    pool: PgPool,
+}
+
+impl FeatureStatusLoader {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+impl Loader<Uuid> for FeatureStatusLoader {
+    type Value = TrackFeatureState;
+    type Error = Arc<sqlx::Error>;
+
+    async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Self::Value>, Self::Error> {
+        // Guard against empty keys to avoid unnecessary database query
+        if keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let states: Vec<TrackFeatureState> = sqlx::query_as(
+            r#"
+            SELECT
+                t.id AS track_id,
+                EXISTS (
+                    SELECT 1 FROM jsonb_each(t.audio_features) f
+                    WHERE f.value <> 'null'::jsonb
+                ) AS has_features,
+                EXISTS (
+                    SELECT 1 FROM track_embeddings e WHERE e.track_id = t.id
+                ) AS has_embedding,
+                COALESCE(r.status = 'failed', FALSE) AS failed,
+                CASE WHEN r.status = 'failed' THEN r.error END AS failure_reason
+            FROM tracks t
+            LEFT JOIN LATERAL (
+                SELECT status, error
+                FROM feature_extraction_requests
+                WHERE track_id = t.id
+                ORDER BY created_at DESC
+                LIMIT 1
+            ) r ON TRUE
+            WHERE t.id = ANY($1)
+            "#,
+        )
+        .bind(keys)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(Arc::new)?;
+
+        Ok(states.into_iter().map(|s| (s.track_id, s)).collect())
+    }
+}
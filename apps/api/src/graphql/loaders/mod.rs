@@ -11,6 +11,7 @@
 mod album;
 mod albums_by_artist;
 mod artist;
+mod feature_status;
 mod track;
 mod tracks_by_album;
 mod tracks_by_artist;
@@ -18,41 +19,228 @@ mod tracks_by_artist;
 pub use album::AlbumLoader;
 pub use albums_by_artist::AlbumsByArtistLoader;
 pub use artist::ArtistLoader;
+pub use feature_status::{FeatureStatusLoader, TrackFeatureState};
 pub use track::TrackLoader;
 pub use tracks_by_album::TracksByAlbumLoader;
 pub use tracks_by_artist::TracksByArtistLoader;
 
+use std::env;
+use std::sync::Arc;
+
+use async_graphql::dataloader::{DataLoader, HashMapCache, Loader};
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextPrepareRequest,
+};
+use async_graphql::{Request, ServerResult};
 use sqlx::PgPool;
 
+/// Default maximum keys per batched query (async-graphql's own default)
+pub const DEFAULT_MAX_BATCH_SIZE: usize = 1000;
+
+/// A DataLoader whose cache can be switched on or off at construction
+///
+/// Every loader uses this type, so resolvers look up the same context type
+/// whether or not caching is enabled.
+pub type BatchLoader<T> = DataLoader<T, HashMapCache>;
+
+/// Batching and caching settings shared by all DataLoaders
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoaderConfig {
+    /// Keys per batched `ANY($1)` query; once this many loads are pending
+    /// they are dispatched, so concurrent loads are split into batches of at
+    /// most this size (a single `load_many` is not split)
+    pub max_batch_size: usize,
+    /// Cache loaded values for the rest of the request
+    pub enable_cache: bool,
+}
+
+impl Default for LoaderConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            enable_cache: false,
+        }
+    }
+}
+
+impl LoaderConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Environment variables:
+    /// - `GRAPHQL_LOADER_MAX_BATCH_SIZE` (default: 1000)
+    /// - `GRAPHQL_LOADER_CACHE` (default: false)
+    pub fn from_env() -> Self {
+        let max_batch_size = env::var("GRAPHQL_LOADER_MAX_BATCH_SIZE")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .filter(|&size| size > 0)
+            .unwrap_or(DEFAULT_MAX_BATCH_SIZE);
+
+        let enable_cache = env::var("GRAPHQL_LOADER_CACHE")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        Self {
+            max_batch_size,
+            enable_cache,
+        }
+    }
+}
+
+/// Wrap a loader in a DataLoader configured by `config`
+pub fn build_loader<T: Loader<uuid::Uuid>>(loader: T, config: &LoaderConfig) -> BatchLoader<T> {
+    let loader = DataLoader::with_cache(loader, tokio::spawn, HashMapCache::default())
+        .max_batch_size(config.max_batch_size);
+    loader.enable_all_cache(config.enable_cache);
+    loader
+}
+
 /// Create all data loaders for the GraphQL schema
 ///
-/// This is a convenience function for creating all loaders at once.
-/// Currently the schema builder creates loaders individually for more
-/// flexibility, but this function is available for simplified setup.
-#[allow(dead_code)]
-pub fn create_loaders(pool: PgPool) -> Loaders {
+/// Loaders registered on the schema are shared by every request, so the
+/// schema builder creates them with caching disabled. With caching enabled,
+/// [`PerRequestLoaders`] attaches a fresh set to each request so cached
+/// values never outlive it.
+pub fn create_loaders(pool: PgPool, config: &LoaderConfig) -> Loaders {
     Loaders {
-        artist: ArtistLoader::new(pool.clone()),
-        album: AlbumLoader::new(pool.clone()),
-        track: TrackLoader::new(pool.clone()),
-        albums_by_artist: AlbumsByArtistLoader::new(pool.clone()),
-        tracks_by_album: TracksByAlbumLoader::new(pool.clone()),
-        tracks_by_artist: TracksByArtistLoader::new(pool),
+        artist: build_loader(ArtistLoader::new(pool.clone()), config),
+        album: build_loader(AlbumLoader::new(pool.clone()), config),
+        track: build_loader(TrackLoader::new(pool.clone()), config),
+        feature_status: build_loader(FeatureStatusLoader::new(pool.clone()), config),
+        albums_by_artist: build_loader(AlbumsByArtistLoader::new(pool.clone()), config),
+        tracks_by_album: build_loader(TracksByAlbumLoader::new(pool.clone()), config),
+        tracks_by_artist: build_loader(TracksByArtistLoader::new(pool), config),
     }
 }
 
 /// Container for all DataLoader instances
-///
-/// This struct bundles all loaders for convenient passing around.
-/// Currently the schema builder injects loaders individually, but this
-/// is available for alternative patterns or future use.
-#[allow(dead_code)]
-#[derive(Clone)]
 pub struct Loaders {
-    pub artist: ArtistLoader,
-    pub album: AlbumLoader,
-    pub track: TrackLoader,
-    pub albums_by_artist: AlbumsByArtistLoader,
-    pub tracks_by_album: TracksByAlbumLoader,
-    pub tracks_by_artist: TracksByArtistLoader,
+    pub artist: BatchLoader<ArtistLoader>,
+    pub album: BatchLoader<AlbumLoader>,
+    pub track: BatchLoader<TrackLoader>,
+    pub feature_status: BatchLoader<FeatureStatusLoader>,
+    pub albums_by_artist: BatchLoader<AlbumsByArtistLoader>,
+    pub tracks_by_album: BatchLoader<TracksByAlbumLoader>,
+    pub tracks_by_artist: BatchLoader<TracksByArtistLoader>,
+}
+
+impl Loaders {
+    /// Register the loaders on a single request, overriding the schema's
+    pub fn attach(self, request: Request) -> Request {
+        request
+            .data(self.artist)
+            .data(self.album)
+            .data(self.track)
+            .data(self.feature_status)
+            .data(self.albums_by_artist)
+            .data(self.tracks_by_album)
+            .data(self.tracks_by_artist)
+    }
+}
+
+/// Schema extension giving each request its own caching loaders
+///
+/// Request data takes precedence over schema data, so these replace the
+/// schema's shared loaders for the request.
+pub struct PerRequestLoaders {
+    pool: PgPool,
+    config: LoaderConfig,
+}
+
+impl PerRequestLoaders {
+    pub fn new(pool: PgPool, config: LoaderConfig) -> Self {
+        Self { pool, config }
+    }
+}
+
+impl ExtensionFactory for PerRequestLoaders {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(PerRequestLoadersExtension {
+            pool: self.pool.clone(),
+            config: self.config,
+        })
+    }
+}
+
+struct PerRequestLoadersExtension {
+    pool: PgPool,
+    config: LoaderConfig,
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for PerRequestLoadersExtension {
+    async fn prepare_request(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        request: Request,
+        next: NextPrepareRequest<'_>,
+    ) -> ServerResult<Request> {
+        let loaders = create_loaders(self.pool.clone(), &self.config);
+        next.run(ctx, loaders.attach(request)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use uuid::Uuid;
+
+    /// Records the size of every batch it is asked to load
+    #[derive(Clone, Default)]
+    struct RecordingLoader {
+        batches: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl Loader<Uuid> for RecordingLoader {
+        type Value = Uuid;
+        type Error = Arc<sqlx::Error>;
+
+        async fn load(&self, keys: &[Uuid]) -> Result<HashMap<Uuid, Uuid>, Self::Error> {
+            self.batches.lock().unwrap().push(keys.len());
+            Ok(keys.iter().map(|k| (*k, *k)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_loads_larger_than_max_batch_size_are_split() {
+        let recorder = RecordingLoader::default();
+        let config = LoaderConfig {
+            max_batch_size: 10,
+            enable_cache: false,
+        };
+        let loader = build_loader(recorder.clone(), &config);
+
+        // Resolvers load one key each, concurrently
+        let ids: Vec<Uuid> = (0..25).map(|_| Uuid::new_v4()).collect();
+        let loaded =
+            futures_util::future::join_all(ids.iter().map(|id| loader.load_one(*id))).await;
+        assert!(loaded.iter().all(|r| matches!(r, Ok(Some(_)))));
+
+        let mut batches = recorder.batches.lock().unwrap().clone();
+        batches.sort_unstable();
+        assert_eq!(batches, vec![5, 10, 10]);
+    }
+
+    #[tokio::test]
+    async fn test_cache_setting() {
+        let id = Uuid::new_v4();
+
+        let recorder = RecordingLoader::default();
+        let loader = build_loader(recorder.clone(), &LoaderConfig::default());
+        loader.load_one(id).await.unwrap();
+        loader.load_one(id).await.unwrap();
+        assert_eq!(recorder.batches.lock().unwrap().len(), 2);
+
+        let recorder = RecordingLoader::default();
+        let config = LoaderConfig {
+            enable_cache: true,
+            ..Default::default()
+        };
+        let loader = build_loader(recorder.clone(), &config);
+        loader.load_one(id).await.unwrap();
+        loader.load_one(id).await.unwrap();
+        assert_eq!(recorder.batches.lock().unwrap().len(), 1);
+    }
 }
@@ -7,18 +7,27 @@
 //! - Guards for rate limiting and authorization
 //! - DataLoaders for batched fetching
 //! - Shared pagination utilities
+//! - Idempotency-Key handling for non-idempotent mutations
+//! - Per-operation logging with timing and complexity
 
 // Re-exports for public API - some utilities not yet consumed externally
 #![allow(unused_imports)]
 
 pub mod guards;
+pub mod idempotency;
 pub mod loaders;
 pub mod mutation;
+pub mod operation_log;
 pub mod pagination;
 pub mod query;
 pub mod schema;
 pub mod types;
 
 pub use guards::GraphQLRateLimiter;
-pub use loaders::{create_loaders, Loaders};
-pub use schema::{build_schema, build_schema_with_rate_limiting, ResonanceSchema, SchemaBuilder};
+pub use idempotency::{idempotent, IDEMPOTENCY_KEY_HEADER};
+pub use loaders::{create_loaders, LoaderConfig, Loaders};
+pub use operation_log::{OperationLogConfig, OperationLogging};
+pub use schema::{
+    attach_request_id, build_schema, build_schema_with_rate_limiting, ResonanceSchema,
+    SchemaBuilder,
+};
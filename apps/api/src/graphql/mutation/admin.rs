@@ -4,16 +4,19 @@
 //! - Updating user roles
 //! - Deleting users
 //! - Invalidating user sessions
+//! - Triggering a manual library scan
+//! - Recomputing one track's audio features
+//! - Regenerating every track's embeddings
 //!
 //! All mutations require admin role authentication.
 
-use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
+use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject, ID};
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::graphql::types::AdminUserListItem;
+use crate::graphql::types::{AdminUserListItem, EmbeddingRegeneration, LibraryScan};
 use crate::models::user::{Claims, UserRole as DbUserRole};
-use crate::repositories::{AdminOperationError, AdminRepository};
+use crate::repositories::{AdminOperationError, AdminRepository, TrackRepository};
 
 /// User role input for admin operations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
@@ -247,6 +250,158 @@ impl AdminMutation {
             sessions_invalidated: count as i64,
         })
     }
+
+    /// Trigger a library scan without waiting for the scheduled one
+    ///
+    /// The scan is queued for the worker. Only one scan runs at a time: if a
+    /// scan is already pending or running, that scan is returned instead of
+    /// queueing another. Poll `adminLibraryScan(id)` for its status.
+    ///
+    /// # Arguments
+    /// * `full_rescan` - Re-read metadata of unchanged files as well (default: false)
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    async fn trigger_library_scan(
+        &self,
+        ctx: &Context<'_>,
+        full_rescan: Option<bool>,
+    ) -> Result<LibraryScan> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let (scan, created) = repo
+            .request_library_scan(claims.sub, full_rescan.unwrap_or(false))
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to request library scan");
+                async_graphql::Error::new("Failed to trigger library scan")
+            })?;
+
+        tracing::info!(
+            admin_id = %claims.sub,
+            scan_id = %scan.id,
+            full_rescan = scan.full_rescan,
+            created,
+            "Admin triggered library scan"
+        );
+
+        Ok(scan.into())
+    }
+
+    /// Recompute the audio features of a single track
+    ///
+    /// Queues a feature extraction for the worker, which decodes the file,
+    /// reruns spectral, rhythm and key analysis and replaces the stored
+    /// features. If a recompute of the track is already pending or running,
+    /// its ID is returned instead of queueing another.
+    ///
+    /// # Arguments
+    /// * `track_id` - The track to reanalyze
+    ///
+    /// # Returns
+    /// The ID of the queued extraction job
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    /// - Returns error if the track does not exist
+    /// - Returns error if the track's audio file is missing
+    async fn recompute_audio_features(&self, ctx: &Context<'_>, track_id: ID) -> Result<ID> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let track_id: Uuid = track_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid track ID"))?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let track = TrackRepository::new(pool.clone())
+            .find_by_id(track_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, track_id = %track_id, "Failed to get track");
+                async_graphql::Error::new("Failed to retrieve track")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("Track not found"))?;
+
+        if !tokio::fs::try_exists(&track.file_path)
+            .await
+            .unwrap_or(false)
+        {
+            return Err(async_graphql::Error::new(format!(
+                "Audio file for track {} not found: {}",
+                track_id, track.file_path
+            )));
+        }
+
+        let repo = AdminRepository::new(pool.clone());
+        let (job_id, created) = repo
+            .request_feature_extraction(claims.sub, track_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, track_id = %track_id, "Failed to request feature extraction");
+                async_graphql::Error::new("Failed to queue feature extraction")
+            })?;
+
+        tracing::info!(
+            admin_id = %claims.sub,
+            track_id = %track_id,
+            job_id = %job_id,
+            created,
+            "Admin requested audio feature recompute"
+        );
+
+        Ok(ID(job_id.to_string()))
+    }
+
+    /// Regenerate the embeddings of every track
+    ///
+    /// Use after changing the embedding model or text template. All existing
+    /// embeddings are marked stale and the worker rebuilds them; the old
+    /// vectors keep serving search until each is replaced. Poll
+    /// `adminEmbeddingRegeneration(id)` for progress.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    /// - Returns error if a regeneration is already pending or running
+    async fn regenerate_all_embeddings(&self, ctx: &Context<'_>) -> Result<EmbeddingRegeneration> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let regeneration = repo
+            .request_embedding_regeneration(claims.sub)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, "Failed to request embedding regeneration");
+                async_graphql::Error::new("Failed to queue embedding regeneration")
+            })?
+            .ok_or_else(|| {
+                async_graphql::Error::new("An embedding regeneration is already in progress")
+            })?;
+
+        tracing::info!(
+            admin_id = %claims.sub,
+            regeneration_id = %regeneration.id,
+            "Admin requested embedding regeneration"
+        );
+
+        Ok(regeneration.into())
+    }
 }
 
 #[cfg(test)]
@@ -11,15 +11,21 @@
 //! - updateEmail: Change the user's email (requires password verification)
 //! - updateProfile: Update display name and/or avatar URL
 //! - deleteAccount: Permanently delete the user's account (requires password verification)
+//! - exportMyData: Get the link to download all of the user's data as JSON
+//!
+//! Sharing:
+//! - shareTrack: Create a time-limited stream link that needs no login
 
-use async_graphql::{Context, InputObject, Object, Result, SimpleObject};
+use async_graphql::{Context, InputObject, Object, Result, SimpleObject, ID};
+use uuid::Uuid;
 
 use crate::error::ApiError;
 use crate::graphql::guards::{RateLimitGuard, RateLimitType};
 use crate::graphql::types::{AuthPayload, RefreshPayload, User};
 use crate::models::user::{DeviceInfo, DeviceType, RequestMetadata};
-use crate::repositories::UserRepository;
+use crate::repositories::{TrackRepository, UserRepository};
 use crate::services::auth::AuthService;
+use crate::services::data_export::EXPORT_PATH;
 
 // =============================================================================
 // Error Sanitization
@@ -37,7 +43,7 @@ fn sanitize_auth_error(error: &ApiError) -> async_graphql::Error {
             tracing::debug!("Auth unauthorized error");
             async_graphql::Error::new("Invalid credentials")
         }
-        ApiError::InvalidToken(msg) => {
+        ApiError::InvalidToken(msg) | ApiError::TokenIssuerMismatch(msg) => {
             tracing::debug!(error = %msg, "Invalid token error");
             async_graphql::Error::new("Invalid or expired token")
         }
@@ -244,6 +250,12 @@ pub struct ChangePasswordResult {
     pub sessions_invalidated: i32,
 }
 
+/// Default lifetime of a shared stream link (24 hours)
+const DEFAULT_SHARE_TTL_SECS: i64 = 24 * 3600;
+
+/// Longest lifetime a shared stream link may be given (7 days)
+const MAX_SHARE_TTL_SECS: i64 = 7 * 24 * 3600;
+
 /// Authentication mutations
 #[derive(Default)]
 pub struct AuthMutation;
@@ -584,4 +596,72 @@ impl AuthMutation {
 
         Ok(true)
     }
+
+    /// Get the link to download everything stored about the current user
+    ///
+    /// Returns the path of the REST endpoint (`/api/account/export`) that
+    /// streams a JSON document with the user's profile, preferences,
+    /// playlists, listening history and chat conversations. Fetch it with the
+    /// same bearer token. Credentials (password hash, integration tokens) and
+    /// other users' data are never included.
+    ///
+    /// The endpoint is rate limited to 3 exports per hour per user.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    async fn export_my_data(&self, ctx: &Context<'_>) -> Result<String> {
+        ctx.data_opt::<crate::models::user::Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+
+        Ok(EXPORT_PATH.to_string())
+    }
+
+    // =========================================================================
+    // Sharing Mutations
+    // =========================================================================
+
+    /// Create a time-limited link for streaming a track without logging in
+    ///
+    /// Anyone with the link can stream the track until it expires, so share
+    /// it only with people you trust with this track.
+    ///
+    /// # Arguments
+    /// * `track_id` - The track to share
+    /// * `expires_in_secs` - Link lifetime in seconds (default 24 hours, max 7 days)
+    ///
+    /// # Returns
+    /// The stream URL path, e.g. `/api/stream/shared/<token>`
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if the lifetime is not between 1 second and 7 days
+    /// - Returns error if the track doesn't exist
+    async fn share_track(
+        &self,
+        ctx: &Context<'_>,
+        track_id: ID,
+        #[graphql(default_with = "DEFAULT_SHARE_TTL_SECS")] expires_in_secs: i64,
+    ) -> Result<String> {
+        let auth_service = ctx.data::<AuthService>()?;
+
+        ctx.data_opt::<crate::models::user::Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+
+        if !(1..=MAX_SHARE_TTL_SECS).contains(&expires_in_secs) {
+            return Err(async_graphql::Error::new(format!(
+                "expiresInSecs must be between 1 and {}",
+                MAX_SHARE_TTL_SECS
+            )));
+        }
+
+        let track_id = Uuid::parse_str(&track_id)?;
+        let track_repo = ctx.data::<TrackRepository>()?;
+        if track_repo.find_by_id(track_id).await?.is_none() {
+            return Err(async_graphql::Error::new("Track not found"));
+        }
+
+        let token =
+            auth_service.sign_stream_url(track_id, chrono::Duration::seconds(expires_in_secs));
+        Ok(format!("/api/stream/shared/{}", token))
+    }
 }
@@ -3,6 +3,8 @@
 //! This module provides mutations for chat management:
 //! - deleteConversation: Delete a chat conversation and its messages
 //! - updateConversationTitle: Update a conversation's title
+//! - archiveConversation: Hide a conversation from the default list
+//! - deleteChatMessages: Delete individual messages from a conversation
 //!
 //! Note: Chat messages are created via WebSocket, not GraphQL mutations.
 //! These mutations are for managing existing conversations.
@@ -21,6 +23,9 @@ use crate::repositories::ChatRepository;
 /// Maximum length of conversation title
 const MAX_TITLE_LENGTH: usize = 255;
 
+/// Maximum messages deleted per request
+const MAX_DELETE_MESSAGES: usize = 500;
+
 // =============================================================================
 // Input Types
 // =============================================================================
@@ -140,6 +145,90 @@ impl ChatMutation {
         Ok(ChatConversation::from(updated))
     }
 
+    /// Archive a conversation
+    ///
+    /// Archived conversations are hidden from `chatConversations` unless
+    /// `includeArchived` is set, and can no longer be continued.
+    /// Requires authentication and ownership of the conversation.
+    ///
+    /// # Arguments
+    /// * `id` - The conversation ID to archive
+    ///
+    /// # Returns
+    /// The archived conversation
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if conversation not found
+    async fn archive_conversation(&self, ctx: &Context<'_>, id: ID) -> Result<ChatConversation> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        let conversation_id: Uuid = id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid conversation ID"))?;
+
+        let repo = ctx.data::<ChatRepository>()?;
+
+        let archived = repo
+            .archive_conversation(conversation_id, claims.sub)
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Conversation not found"))?;
+
+        tracing::info!(
+            conversation_id = %conversation_id,
+            user_id = %claims.sub,
+            "Chat conversation archived"
+        );
+
+        Ok(ChatConversation::from(archived))
+    }
+
+    /// Delete chat messages
+    ///
+    /// Removes individual messages without deleting their conversation.
+    /// Messages not owned by the user are skipped.
+    ///
+    /// # Arguments
+    /// * `ids` - The message IDs to delete (max 500)
+    ///
+    /// # Returns
+    /// The number of messages deleted
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if an ID is invalid or too many IDs are given
+    async fn delete_chat_messages(&self, ctx: &Context<'_>, ids: Vec<ID>) -> Result<i64> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        if ids.len() > MAX_DELETE_MESSAGES {
+            return Err(async_graphql::Error::new(format!(
+                "Cannot delete more than {} messages at once",
+                MAX_DELETE_MESSAGES
+            )));
+        }
+
+        let message_ids = ids
+            .iter()
+            .map(|id| id.parse::<Uuid>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|_| async_graphql::Error::new("Invalid message ID"))?;
+
+        let repo = ctx.data::<ChatRepository>()?;
+        let deleted_count = repo.delete_messages(&message_ids, claims.sub).await?;
+
+        tracing::info!(
+            user_id = %claims.sub,
+            deleted_count = deleted_count,
+            "Chat messages deleted"
+        );
+
+        Ok(deleted_count)
+    }
+
     /// Delete all conversations for the authenticated user
     ///
     /// Permanently deletes all chat conversations and their messages.
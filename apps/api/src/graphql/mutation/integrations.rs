@@ -3,6 +3,8 @@
 //! This module provides mutations for external service integrations:
 //! - updateIntegrations: Update ListenBrainz/Discord settings
 //! - submitScrobble: Submit a scrobble to ListenBrainz
+//! - syncPlays: Record plays a client collected while offline
+//! - recordSkip: Record a skipped track so recommendations can avoid it
 //! - testListenbrainzConnection: Validate ListenBrainz token
 
 use async_graphql::{Context, InputObject, Object, Result, SimpleObject, ID};
@@ -11,7 +13,9 @@ use chrono::{DateTime, Utc};
 use tracing::{error, info, instrument, warn};
 use uuid::Uuid;
 
+use crate::graphql::types::SkipContext;
 use crate::models::user::Claims;
+use crate::models::ScrobbleEvent;
 use crate::repositories::{TrackRepository, UserRepository};
 use crate::services::encryption::EncryptionService;
 use crate::services::listenbrainz::{ListenBrainzService, ScrobbleTrack};
@@ -29,6 +33,12 @@ const MAX_DURATION_PLAYED_SECS: i32 = 86400;
 /// Maximum age of played_at timestamp in seconds (7 days)
 const MAX_PLAYED_AT_AGE_SECS: i64 = 604800;
 
+/// Maximum plays accepted in one syncPlays call
+const MAX_SYNC_PLAYS: usize = 1000;
+
+/// Maximum playback before a skip, in milliseconds (24 hours)
+const MAX_SKIP_PLAYED_MS: i32 = MAX_DURATION_PLAYED_SECS * 1000;
+
 // ============================================================================
 // Error Handling
 // ============================================================================
@@ -97,6 +107,24 @@ pub struct ScrobbleResult {
     pub error: Option<String>,
 }
 
+/// Result of syncing offline plays
+#[derive(Debug, SimpleObject)]
+pub struct SyncPlaysResult {
+    /// Plays received
+    pub received: i32,
+    /// Plays that met the scrobble threshold and were added to the history
+    /// (duplicates of already-synced plays don't count)
+    pub counted: i32,
+}
+
+/// Result of recording a skip
+#[derive(Debug, SimpleObject)]
+pub struct RecordSkipResult {
+    /// Whether the skip counted as a quick skip (the track was abandoned
+    /// early) rather than a natural move to the next track
+    pub quick_skip: bool,
+}
+
 /// Result of ListenBrainz connection test
 #[derive(Debug, SimpleObject)]
 #[graphql(name = "ListenBrainzConnectionTestResult")]
@@ -480,6 +508,128 @@ impl IntegrationsMutation {
         }
     }
 
+    /// Record plays a client collected while offline
+    ///
+    /// Each play counts towards the listening history and play counts only
+    /// if it meets the scrobble threshold (50% of the track or 4 minutes).
+    /// Plays already synced (same track and `played_at`) are ignored, so a
+    /// client can safely retry the whole batch. Plays are not forwarded to
+    /// ListenBrainz.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if there are more than 1000 plays, or a play has an
+    ///   invalid track ID, duration or a `played_at` in the future
+    #[instrument(skip(self, ctx, plays), fields(user_id, received = plays.len()))]
+    async fn sync_plays(
+        &self,
+        ctx: &Context<'_>,
+        plays: Vec<ScrobbleInput>,
+    ) -> Result<SyncPlaysResult> {
+        let user_id = get_user_id(ctx)?;
+        tracing::Span::current().record("user_id", user_id.to_string());
+
+        if plays.len() > MAX_SYNC_PLAYS {
+            return Err(async_graphql::Error::new(format!(
+                "at most {} plays can be synced at once",
+                MAX_SYNC_PLAYS
+            )));
+        }
+
+        let now = Utc::now();
+        let events = plays
+            .iter()
+            .map(|play| {
+                if !(0..=MAX_DURATION_PLAYED_SECS).contains(&play.duration_played) {
+                    return Err(async_graphql::Error::new(
+                        "duration_played must be between 0 and 86400 seconds",
+                    ));
+                }
+                if play.played_at > now {
+                    return Err(async_graphql::Error::new(
+                        "played_at cannot be in the future",
+                    ));
+                }
+                let track_id: Uuid = play
+                    .track_id
+                    .parse()
+                    .map_err(|_| async_graphql::Error::new("Invalid track ID format"))?;
+                Ok(ScrobbleEvent {
+                    track_id,
+                    played_ms: play.duration_played as i64 * 1000,
+                    played_at: play.played_at,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let track_repo = ctx.data::<TrackRepository>()?;
+        let counted = track_repo
+            .record_scrobbles_batch(user_id, events)
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to record synced plays");
+                async_graphql::Error::new("An error occurred while processing your request")
+            })?;
+
+        info!(user_id = %user_id, received = plays.len(), counted, "Synced offline plays");
+
+        Ok(SyncPlaysResult {
+            received: plays.len() as i32,
+            counted: counted as i32,
+        })
+    }
+
+    /// Record that the user skipped a track
+    ///
+    /// Skips within the first 30 seconds are quick skips: the discover
+    /// playlist and prefetch jobs down-weight tracks the user keeps quick
+    /// skipping. Later skips are recorded as a natural next and carry no
+    /// penalty.
+    ///
+    /// # Arguments
+    /// * `track_id` - The skipped track
+    /// * `played_ms` - How much of the track played before the skip
+    /// * `context` - Where the track was playing from
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if the track ID is invalid or the track doesn't exist
+    /// - Returns error if `played_ms` is negative or more than 24 hours
+    #[instrument(skip(self, ctx), fields(user_id))]
+    async fn record_skip(
+        &self,
+        ctx: &Context<'_>,
+        track_id: ID,
+        played_ms: i32,
+        context: SkipContext,
+    ) -> Result<RecordSkipResult> {
+        let user_id = get_user_id(ctx)?;
+        tracing::Span::current().record("user_id", user_id.to_string());
+
+        if !(0..=MAX_SKIP_PLAYED_MS).contains(&played_ms) {
+            return Err(async_graphql::Error::new(
+                "played_ms must be between 0 and 86400000",
+            ));
+        }
+        let track_id: Uuid = track_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid track ID format"))?;
+
+        let track_repo = ctx.data::<TrackRepository>()?;
+        let skip = track_repo
+            .record_skip(user_id, track_id, played_ms, context.into())
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to record skip");
+                async_graphql::Error::new("An error occurred while processing your request")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("Track not found"))?;
+
+        Ok(RecordSkipResult {
+            quick_skip: skip.quick,
+        })
+    }
+
     /// Test ListenBrainz connection
     ///
     /// Validates the provided token against the ListenBrainz API.
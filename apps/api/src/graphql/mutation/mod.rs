@@ -14,10 +14,12 @@ pub use admin::AdminMutation;
 pub use auth::AuthMutation;
 pub use chat::ChatMutation;
 pub use integrations::IntegrationsMutation;
-pub use playlist::PlaylistMutation;
+pub use playlist::{PlaylistMutation, SmartPlaylistRuleInput, SmartPlaylistRulesInput};
 pub use preferences::PreferencesMutation;
 pub use system_settings::SystemSettingsMutation;
 
+pub(crate) use playlist::{smart_rule_errors, SmartRuleError};
+
 use async_graphql::MergedObject;
 
 /// Root mutation type combining all mutation domains
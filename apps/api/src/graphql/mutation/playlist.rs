@@ -7,18 +7,31 @@
 //! - refreshSmartPlaylist: Re-evaluate smart playlist rules
 //! - addTracksToPlaylist: Add tracks to a playlist
 //! - removeTracksFromPlaylist: Remove tracks from a playlist
+//! - importPlaylist: Create a playlist from an M3U or JSON export
+//! - setPlaylistCollaborator: Share a playlist or change a collaborator's permission
+//! - removePlaylistCollaborator: Stop sharing a playlist with a user
+//!
+//! Collaborators are checked against their permission level: editors may
+//! change tracks, admins may also update, delete and share the playlist.
+//!
+//! createPlaylist, addTracksToPlaylist and importPlaylist honour the
+//! `Idempotency-Key` header, so retried requests do not repeat the write.
 
 use async_graphql::{Context, InputObject, Object, Result, ID};
 use uuid::Uuid;
 
 use crate::error::ApiError;
-use crate::graphql::types::{Playlist, PlaylistType};
+use crate::graphql::idempotent;
+use crate::graphql::types::{
+    CollaboratorPermission, Playlist, PlaylistCollaborator, PlaylistFileFormat,
+    PlaylistImportResult, PlaylistType,
+};
 use crate::models::playlist::{
     PlaylistType as DbPlaylistType, SmartPlaylistRule, SmartPlaylistRules,
 };
 use crate::models::user::Claims;
 use crate::repositories::PlaylistRepository;
-use crate::services::playlist::PlaylistService;
+use crate::services::playlist::{PlaylistOperation, PlaylistService};
 
 // =============================================================================
 // Input Validation Limits
@@ -39,6 +52,9 @@ const MAX_DESCRIPTION_LENGTH: usize = 2000;
 /// Maximum seed tracks for similarity rules
 const MAX_SIMILAR_TO_SEEDS: usize = 10;
 
+/// Maximum size of an imported playlist file in bytes
+const MAX_IMPORT_SIZE: usize = 1024 * 1024;
+
 // =============================================================================
 // Error Handling
 // =============================================================================
@@ -256,17 +272,20 @@ impl PlaylistMutation {
         // Convert smart rules using From trait
         let smart_rules: Option<SmartPlaylistRules> = input.smart_rules.map(Into::into);
 
-        let playlist = playlist_repo
-            .create(
-                claims.sub,
-                name,
-                input.description.as_deref(),
-                input.is_public,
-                input.playlist_type.into(),
-                smart_rules,
-            )
-            .await
-            .map_err(|e| to_graphql_error(e.into()))?;
+        let playlist = idempotent(ctx, claims.sub, "createPlaylist", || async {
+            playlist_repo
+                .create(
+                    claims.sub,
+                    name,
+                    input.description.as_deref(),
+                    input.is_public,
+                    input.playlist_type.into(),
+                    smart_rules,
+                )
+                .await
+                .map_err(|e| to_graphql_error(e.into()))
+        })
+        .await?;
 
         Ok(Playlist::from(playlist))
     }
@@ -285,7 +304,7 @@ impl PlaylistMutation {
     /// # Errors
     /// - Returns error if not authenticated
     /// - Returns error if playlist not found
-    /// - Returns error if user doesn't own the playlist
+    /// - Returns error if user isn't the owner or an admin collaborator
     async fn update_playlist(
         &self,
         ctx: &Context<'_>,
@@ -325,18 +344,11 @@ impl PlaylistMutation {
 
         let playlist_repo = ctx.data::<PlaylistRepository>()?;
 
-        // Check if playlist exists and user owns it
-        let existing = playlist_repo
-            .find_by_id(playlist_id)
+        // Owner or admin collaborator
+        ctx.data::<PlaylistService>()?
+            .authorize(playlist_id, claims.sub, PlaylistOperation::Manage)
             .await
-            .map_err(|e| to_graphql_error(e.into()))?
-            .ok_or_else(|| async_graphql::Error::new("Playlist not found"))?;
-
-        if existing.user_id != claims.sub {
-            return Err(async_graphql::Error::new(
-                "You don't have permission to update this playlist",
-            ));
-        }
+            .map_err(to_graphql_error)?;
 
         // Validate smart rules if provided
         if let Some(ref rules) = input.smart_rules {
@@ -366,7 +378,8 @@ impl PlaylistMutation {
 
     /// Delete a playlist
     ///
-    /// Permanently deletes the specified playlist. Only the playlist owner can delete it.
+    /// Permanently deletes the specified playlist. Only the playlist owner and
+    /// admin collaborators can delete it.
     ///
     /// # Arguments
     /// * `id` - The playlist ID to delete
@@ -377,7 +390,7 @@ impl PlaylistMutation {
     /// # Errors
     /// - Returns error if not authenticated
     /// - Returns error if playlist not found
-    /// - Returns error if user doesn't own the playlist
+    /// - Returns error if user isn't the owner or an admin collaborator
     async fn delete_playlist(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
         let claims = ctx
             .data_opt::<Claims>()
@@ -389,18 +402,11 @@ impl PlaylistMutation {
 
         let playlist_repo = ctx.data::<PlaylistRepository>()?;
 
-        // Check ownership
-        let existing = playlist_repo
-            .find_by_id(playlist_id)
+        // Owner or admin collaborator
+        ctx.data::<PlaylistService>()?
+            .authorize(playlist_id, claims.sub, PlaylistOperation::Manage)
             .await
-            .map_err(|e| to_graphql_error(e.into()))?
-            .ok_or_else(|| async_graphql::Error::new("Playlist not found"))?;
-
-        if existing.user_id != claims.sub {
-            return Err(async_graphql::Error::new(
-                "You don't have permission to delete this playlist",
-            ));
-        }
+            .map_err(to_graphql_error)?;
 
         let tracks_deleted = playlist_repo
             .delete(playlist_id)
@@ -511,24 +517,12 @@ impl PlaylistMutation {
 
         let playlist_repo = ctx.data::<PlaylistRepository>()?;
 
-        // Check permissions
-        let existing = playlist_repo
-            .find_by_id(playlist_id)
-            .await
-            .map_err(|e| to_graphql_error(e.into()))?
-            .ok_or_else(|| async_graphql::Error::new("Playlist not found"))?;
-
-        // Check if user can edit (owner or collaborator)
-        let can_edit = playlist_repo
-            .can_edit(playlist_id, claims.sub)
+        // Owner or editor collaborator
+        let existing = ctx
+            .data::<PlaylistService>()?
+            .authorize(playlist_id, claims.sub, PlaylistOperation::EditTracks)
             .await
-            .map_err(|e| to_graphql_error(e.into()))?;
-
-        if !can_edit {
-            return Err(async_graphql::Error::new(
-                "You don't have permission to edit this playlist",
-            ));
-        }
+            .map_err(to_graphql_error)?;
 
         if existing.playlist_type == DbPlaylistType::Smart {
             return Err(async_graphql::Error::new(
@@ -563,17 +557,20 @@ impl PlaylistMutation {
             }
         }
 
-        playlist_repo
-            .add_tracks(playlist_id, &track_ids, claims.sub, input.position)
-            .await
-            .map_err(|e| to_graphql_error(e.into()))?;
-
-        // Re-fetch the playlist to get updated stats
-        let updated = playlist_repo
-            .find_by_id(playlist_id)
-            .await
-            .map_err(|e| to_graphql_error(e.into()))?
-            .ok_or_else(|| async_graphql::Error::new("Playlist not found"))?;
+        let updated = idempotent(ctx, claims.sub, "addTracksToPlaylist", || async {
+            playlist_repo
+                .add_tracks(playlist_id, &track_ids, claims.sub, input.position)
+                .await
+                .map_err(|e| to_graphql_error(e.into()))?;
+
+            // Re-fetch the playlist to get updated stats
+            playlist_repo
+                .find_by_id(playlist_id)
+                .await
+                .map_err(|e| to_graphql_error(e.into()))?
+                .ok_or_else(|| async_graphql::Error::new("Playlist not found"))
+        })
+        .await?;
 
         Ok(Playlist::from(updated))
     }
@@ -610,23 +607,12 @@ impl PlaylistMutation {
 
         let playlist_repo = ctx.data::<PlaylistRepository>()?;
 
-        // Check permissions
-        let existing = playlist_repo
-            .find_by_id(playlist_id)
+        // Owner or editor collaborator
+        let existing = ctx
+            .data::<PlaylistService>()?
+            .authorize(playlist_id, claims.sub, PlaylistOperation::EditTracks)
             .await
-            .map_err(|e| to_graphql_error(e.into()))?
-            .ok_or_else(|| async_graphql::Error::new("Playlist not found"))?;
-
-        let can_edit = playlist_repo
-            .can_edit(playlist_id, claims.sub)
-            .await
-            .map_err(|e| to_graphql_error(e.into()))?;
-
-        if !can_edit {
-            return Err(async_graphql::Error::new(
-                "You don't have permission to edit this playlist",
-            ));
-        }
+            .map_err(to_graphql_error)?;
 
         if existing.playlist_type == DbPlaylistType::Smart {
             return Err(async_graphql::Error::new(
@@ -668,6 +654,110 @@ impl PlaylistMutation {
 
         Ok(Playlist::from(updated))
     }
+
+    /// Import a playlist from an M3U or JSON export
+    ///
+    /// Creates a new manual playlist owned by the authenticated user. Entries are
+    /// matched to library tracks by file path, then by artist and title; entries
+    /// with no match are returned alongside the playlist.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if the data is too large or cannot be parsed
+    async fn import_playlist(
+        &self,
+        ctx: &Context<'_>,
+        format: PlaylistFileFormat,
+        data: String,
+    ) -> Result<PlaylistImportResult> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        if data.len() > MAX_IMPORT_SIZE {
+            return Err(async_graphql::Error::new(format!(
+                "Playlist file cannot exceed {} bytes",
+                MAX_IMPORT_SIZE
+            )));
+        }
+
+        let playlist_service = ctx.data::<PlaylistService>()?;
+        let imported = idempotent(ctx, claims.sub, "importPlaylist", || async {
+            playlist_service
+                .import(claims.sub, format.into(), &data)
+                .await
+                .map_err(to_graphql_error)
+        })
+        .await?;
+
+        Ok(PlaylistImportResult::from(imported))
+    }
+
+    /// Share a playlist with a user, or change an existing collaborator's permission
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if user isn't the owner or an admin collaborator
+    /// - Returns error if the collaborator is the playlist owner
+    async fn set_playlist_collaborator(
+        &self,
+        ctx: &Context<'_>,
+        playlist_id: ID,
+        user_id: ID,
+        permission: CollaboratorPermission,
+    ) -> Result<PlaylistCollaborator> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        let playlist_id: Uuid = playlist_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid playlist ID"))?;
+        let collaborator_id: Uuid = user_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid user ID"))?;
+
+        let collaborator = ctx
+            .data::<PlaylistService>()?
+            .set_collaborator(playlist_id, claims.sub, collaborator_id, permission.into())
+            .await
+            .map_err(to_graphql_error)?;
+
+        Ok(PlaylistCollaborator::from(collaborator))
+    }
+
+    /// Stop sharing a playlist with a user
+    ///
+    /// Collaborators can always remove themselves.
+    ///
+    /// # Returns
+    /// True if the user was a collaborator
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if user isn't the owner or an admin collaborator
+    async fn remove_playlist_collaborator(
+        &self,
+        ctx: &Context<'_>,
+        playlist_id: ID,
+        user_id: ID,
+    ) -> Result<bool> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        let playlist_id: Uuid = playlist_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid playlist ID"))?;
+        let collaborator_id: Uuid = user_id
+            .parse()
+            .map_err(|_| async_graphql::Error::new("Invalid user ID"))?;
+
+        ctx.data::<PlaylistService>()?
+            .remove_collaborator(playlist_id, claims.sub, collaborator_id)
+            .await
+            .map_err(to_graphql_error)
+    }
 }
 
 // =============================================================================
@@ -733,23 +823,54 @@ fn normalize_input(s: &str) -> String {
     s.trim().to_ascii_lowercase()
 }
 
-/// Validate smart playlist rules
+/// A problem with one field of a smart playlist rules input
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub(crate) struct SmartRuleError {
+    /// Path of the offending input field, e.g. `rules[2].operator`
+    pub field: String,
+    /// What is wrong with it
+    pub message: String,
+}
+
+impl SmartRuleError {
+    fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Validate smart playlist rules, returning the first problem found
 ///
 /// Note: Validation uses normalized values (trimmed + lowercased) to match
 /// the normalization that happens in the From trait implementations.
 fn validate_smart_rules(rules: &SmartPlaylistRulesInput) -> Result<()> {
+    match smart_rule_errors(rules).into_iter().next() {
+        Some(error) => Err(async_graphql::Error::new(error.message)),
+        None => Ok(()),
+    }
+}
+
+/// Every problem with smart playlist rules, in input order
+///
+/// Field paths use the GraphQL input names (`matchMode`, `rules[0].value`).
+pub(crate) fn smart_rule_errors(rules: &SmartPlaylistRulesInput) -> Vec<SmartRuleError> {
+    let mut errors = Vec::new();
+
     // Validate rule count limit
     if rules.rules.len() > MAX_RULES {
-        return Err(async_graphql::Error::new(format!(
-            "Smart playlist cannot have more than {} rules",
-            MAX_RULES
-        )));
+        errors.push(SmartRuleError::new(
+            "rules",
+            format!("Smart playlist cannot have more than {} rules", MAX_RULES),
+        ));
     }
 
     // Validate match_mode (normalize before checking)
     let match_mode = normalize_input(&rules.match_mode);
     if match_mode != "all" && match_mode != "any" {
-        return Err(async_graphql::Error::new(
+        errors.push(SmartRuleError::new(
+            "matchMode",
             "match_mode must be 'all' or 'any'",
         ));
     }
@@ -758,11 +879,14 @@ fn validate_smart_rules(rules: &SmartPlaylistRulesInput) -> Result<()> {
     if let Some(ref sort_by) = rules.sort_by {
         let normalized = normalize_input(sort_by);
         if !VALID_FIELDS.contains(&normalized.as_str()) {
-            return Err(async_graphql::Error::new(format!(
-                "Invalid sort_by '{}'. Valid fields: {}",
-                normalized,
-                VALID_FIELDS.join(", ")
-            )));
+            errors.push(SmartRuleError::new(
+                "sortBy",
+                format!(
+                    "Invalid sort_by '{}'. Valid fields: {}",
+                    normalized,
+                    VALID_FIELDS.join(", ")
+                ),
+            ));
         }
     }
 
@@ -770,7 +894,8 @@ fn validate_smart_rules(rules: &SmartPlaylistRulesInput) -> Result<()> {
     if let Some(ref order) = rules.sort_order {
         let normalized = normalize_input(order);
         if normalized != "asc" && normalized != "desc" {
-            return Err(async_graphql::Error::new(
+            errors.push(SmartRuleError::new(
+                "sortOrder",
                 "sort_order must be 'asc' or 'desc'",
             ));
         }
@@ -779,113 +904,224 @@ fn validate_smart_rules(rules: &SmartPlaylistRulesInput) -> Result<()> {
     // Validate limit if provided
     if let Some(limit) = rules.limit {
         if limit <= 0 {
-            return Err(async_graphql::Error::new("Limit must be a positive number"));
-        }
-        if limit > 10000 {
-            return Err(async_graphql::Error::new(
+            errors.push(SmartRuleError::new(
+                "limit",
+                "Limit must be a positive number",
+            ));
+        } else if limit > 10000 {
+            errors.push(SmartRuleError::new(
+                "limit",
                 "Limit cannot exceed 10,000 tracks",
             ));
         }
     }
 
     // Validate each rule
-    for rule in &rules.rules {
-        // Normalize field and operator for validation (matches From trait behavior)
-        let field = normalize_input(&rule.field);
-        let operator = normalize_input(&rule.operator);
+    for (index, rule) in rules.rules.iter().enumerate() {
+        if let Err(error) = validate_smart_rule(rule) {
+            errors.push(SmartRuleError::new(
+                format!("rules[{}].{}", index, error.field),
+                error.message,
+            ));
+        }
+    }
 
-        // Validate field
-        if !VALID_FIELDS.contains(&field.as_str()) {
-            return Err(async_graphql::Error::new(format!(
+    errors
+}
+
+/// Validate one rule, returning its first problem with a path relative to
+/// the rule (`field`, `operator` or `value`)
+fn validate_smart_rule(rule: &SmartPlaylistRuleInput) -> std::result::Result<(), SmartRuleError> {
+    // Normalize field and operator for validation (matches From trait behavior)
+    let field = normalize_input(&rule.field);
+    let operator = normalize_input(&rule.operator);
+
+    // Validate field
+    if !VALID_FIELDS.contains(&field.as_str()) {
+        return Err(SmartRuleError::new(
+            "field",
+            format!(
                 "Invalid field '{}'. Valid fields: {}",
                 field,
                 VALID_FIELDS.join(", ")
-            )));
-        }
+            ),
+        ));
+    }
 
-        // Validate operator
-        if !VALID_OPERATORS.contains(&operator.as_str()) {
-            return Err(async_graphql::Error::new(format!(
+    // Validate operator
+    if !VALID_OPERATORS.contains(&operator.as_str()) {
+        return Err(SmartRuleError::new(
+            "operator",
+            format!(
                 "Invalid operator '{}'. Valid operators: {}",
                 operator,
                 VALID_OPERATORS.join(", ")
-            )));
+            ),
+        ));
+    }
+
+    // Define similarity operators for bidirectional validation
+    const SIMILARITY_OPERATORS: &[&str] = &["combined", "semantic", "acoustic", "categorical"];
+
+    // Validate similar_to rules have proper structure
+    if field != "similar_to" {
+        if SIMILARITY_OPERATORS.contains(&operator.as_str()) {
+            // Similarity operators can ONLY be used with similar_to field
+            return Err(SmartRuleError::new(
+                "operator",
+                format!(
+                    "Operator '{}' can only be used with the 'similar_to' field",
+                    operator
+                ),
+            ));
         }
+        return Ok(());
+    }
+    if !SIMILARITY_OPERATORS.contains(&operator.as_str()) {
+        return Err(SmartRuleError::new(
+            "operator",
+            "similar_to field requires operator: combined, semantic, acoustic, or categorical",
+        ));
+    }
 
-        // Define similarity operators for bidirectional validation
-        const SIMILARITY_OPERATORS: &[&str] = &["combined", "semantic", "acoustic", "categorical"];
+    // Check for track_ids in value
+    let track_ids = rule.value.get("track_ids").ok_or_else(|| {
+        SmartRuleError::new("value", "similar_to rule requires 'track_ids' in value")
+    })?;
 
-        // Validate similar_to rules have proper structure
-        if field == "similar_to" {
-            if !SIMILARITY_OPERATORS.contains(&operator.as_str()) {
-                return Err(async_graphql::Error::new(
-                    "similar_to field requires operator: combined, semantic, acoustic, or categorical",
+    // Validate track_ids array
+    let Some(arr) = track_ids.as_array() else {
+        return Err(SmartRuleError::new(
+            "value",
+            "track_ids must be an array of UUIDs",
+        ));
+    };
+    if arr.is_empty() {
+        return Err(SmartRuleError::new(
+            "value",
+            "similar_to rule requires at least one seed track",
+        ));
+    }
+    if arr.len() > MAX_SIMILAR_TO_SEEDS {
+        return Err(SmartRuleError::new(
+            "value",
+            format!(
+                "similar_to rule cannot have more than {} seed tracks",
+                MAX_SIMILAR_TO_SEEDS
+            ),
+        ));
+    }
+    // Validate each track_id is a valid UUID string
+    for (i, id) in arr.iter().enumerate() {
+        match id.as_str() {
+            Some(id_str) if Uuid::parse_str(id_str).is_err() => {
+                return Err(SmartRuleError::new(
+                    "value",
+                    format!("Invalid track ID at index {}: {}", i, id_str),
+                ));
+            }
+            Some(_) => {}
+            None => {
+                return Err(SmartRuleError::new(
+                    "value",
+                    format!("track_ids[{}] must be a string UUID", i),
                 ));
             }
-        } else if SIMILARITY_OPERATORS.contains(&operator.as_str()) {
-            // Similarity operators can ONLY be used with similar_to field
-            return Err(async_graphql::Error::new(format!(
-                "Operator '{}' can only be used with the 'similar_to' field",
-                operator
-            )));
         }
+    }
 
-        // Continue validation for similar_to rules
-        if field == "similar_to" {
-            // Check for track_ids in value
-            let track_ids = rule.value.get("track_ids").ok_or_else(|| {
-                async_graphql::Error::new("similar_to rule requires 'track_ids' in value")
-            })?;
-
-            // Validate track_ids array
-            if let Some(arr) = track_ids.as_array() {
-                if arr.is_empty() {
-                    return Err(async_graphql::Error::new(
-                        "similar_to rule requires at least one seed track",
-                    ));
-                }
-                if arr.len() > MAX_SIMILAR_TO_SEEDS {
-                    return Err(async_graphql::Error::new(format!(
-                        "similar_to rule cannot have more than {} seed tracks",
-                        MAX_SIMILAR_TO_SEEDS
-                    )));
-                }
-                // Validate each track_id is a valid UUID string
-                for (i, id) in arr.iter().enumerate() {
-                    if let Some(id_str) = id.as_str() {
-                        if Uuid::parse_str(id_str).is_err() {
-                            return Err(async_graphql::Error::new(format!(
-                                "Invalid track ID at index {}: {}",
-                                i, id_str
-                            )));
-                        }
-                    } else {
-                        return Err(async_graphql::Error::new(format!(
-                            "track_ids[{}] must be a string UUID",
-                            i
-                        )));
-                    }
-                }
-            } else {
-                return Err(async_graphql::Error::new(
-                    "track_ids must be an array of UUIDs",
+    // Validate min_score if provided
+    if let Some(min_score) = rule.value.get("min_score") {
+        match min_score.as_f64() {
+            Some(score) if !(0.0..=1.0).contains(&score) => {
+                return Err(SmartRuleError::new(
+                    "value",
+                    "min_score must be between 0.0 and 1.0",
                 ));
             }
-
-            // Validate min_score if provided
-            if let Some(min_score) = rule.value.get("min_score") {
-                if let Some(score) = min_score.as_f64() {
-                    if !(0.0..=1.0).contains(&score) {
-                        return Err(async_graphql::Error::new(
-                            "min_score must be between 0.0 and 1.0",
-                        ));
-                    }
-                } else {
-                    return Err(async_graphql::Error::new("min_score must be a number"));
-                }
+            Some(_) => {}
+            None => {
+                return Err(SmartRuleError::new("value", "min_score must be a number"));
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(field: &str, operator: &str, value: serde_json::Value) -> SmartPlaylistRuleInput {
+        SmartPlaylistRuleInput {
+            field: field.to_string(),
+            operator: operator.to_string(),
+            value,
+        }
+    }
+
+    fn rules(rules: Vec<SmartPlaylistRuleInput>) -> SmartPlaylistRulesInput {
+        SmartPlaylistRulesInput {
+            match_mode: "all".to_string(),
+            rules,
+            limit: None,
+            sort_by: None,
+            sort_order: None,
+        }
+    }
+
+    fn fields(errors: &[SmartRuleError]) -> Vec<&str> {
+        errors.iter().map(|e| e.field.as_str()).collect()
+    }
+
+    #[test]
+    fn test_valid_rules_have_no_errors() {
+        let input = rules(vec![
+            rule(" Genre ", "EQUALS", serde_json::json!("Rock")),
+            rule(
+                "similar_to",
+                "combined",
+                serde_json::json!({ "track_ids": [Uuid::new_v4().to_string()] }),
+            ),
+        ]);
+
+        assert!(smart_rule_errors(&input).is_empty());
+        assert!(validate_smart_rules(&input).is_ok());
+    }
+
+    #[test]
+    fn test_errors_name_every_invalid_field() {
+        let mut input = rules(vec![
+            rule("genre", "equals", serde_json::json!("Rock")),
+            rule("colour", "equals", serde_json::json!("blue")),
+            rule("energy", "semantic", serde_json::json!(0.5)),
+            rule("similar_to", "acoustic", serde_json::json!({})),
+        ]);
+        input.match_mode = "some".to_string();
+        input.limit = Some(0);
+
+        let errors = smart_rule_errors(&input);
+        assert_eq!(
+            fields(&errors),
+            vec![
+                "matchMode",
+                "limit",
+                "rules[1].field",
+                "rules[2].operator",
+                "rules[3].value",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_first_error() {
+        let input = rules(vec![
+            rule("colour", "equals", serde_json::json!("blue")),
+            rule("genre", "like", serde_json::json!("Rock")),
+        ]);
+
+        let err = validate_smart_rules(&input).unwrap_err();
+        assert!(err.message.starts_with("Invalid field 'colour'"));
+    }
+}
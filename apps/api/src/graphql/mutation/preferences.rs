@@ -3,6 +3,9 @@
 //! This module provides mutations for user preference management:
 //! - updatePreferences: Update user preferences with validation
 //! - resetPreferences: Reset preferences to default values
+//! - setDevicePreferences: Override audio preferences for a single device
+//! - setSimilarityWeights: Choose the blend of similarity dimensions behind
+//!   recommendations
 //!
 //! Preferences include:
 //! - theme: UI theme (dark/light)
@@ -14,12 +17,16 @@
 //! - private_session: Enable private listening (no scrobbling)
 //! - discord_rpc: Enable Discord Rich Presence
 //! - listenbrainz_scrobble: Enable ListenBrainz scrobbling
+//!
+//! Device overrides (transcode format, cellular bitrate cap, normalization)
+//! take precedence over the global preferences on that device.
 
 use async_graphql::{Context, InputObject, Object, Result};
 
-use crate::graphql::types::{User, UserPreferencesType};
-use crate::models::user::{Claims, UserPreferences};
+use crate::graphql::types::{DevicePreferencesType, User, UserPreferencesType};
+use crate::models::user::{Claims, DevicePrefs, SimilarityWeights, UserPreferences};
 use crate::repositories::UserRepository;
+use crate::services::transcoder::{TranscodeFormat, LOSSY_BITRATES};
 
 // =============================================================================
 // Validation Constants
@@ -34,6 +41,12 @@ const VALID_QUALITIES: &[&str] = &["low", "medium", "high", "lossless"];
 /// Maximum crossfade duration in milliseconds (12 seconds)
 const MAX_CROSSFADE_MS: u32 = 12_000;
 
+/// Maximum device ID length (matches the device_id columns)
+const MAX_DEVICE_ID_LENGTH: usize = 255;
+
+/// Maximum number of devices with stored overrides per user
+const MAX_DEVICE_OVERRIDES: usize = 50;
+
 // =============================================================================
 // Input Types
 // =============================================================================
@@ -88,6 +101,36 @@ impl UpdatePreferencesInput {
     }
 }
 
+/// Input for overriding audio preferences on a single device
+///
+/// Omitted fields fall back to the global preferences. Setting every field
+/// to null removes the device's overrides.
+#[derive(Debug, InputObject)]
+pub struct DevicePreferencesInput {
+    /// Preferred transcode format: "mp3", "aac", "opus", or "flac"
+    pub transcode_format: Option<String>,
+
+    /// Maximum streaming bitrate in kbps on cellular connections
+    pub cellular_max_bitrate_kbps: Option<u32>,
+
+    /// Normalize volume across tracks
+    pub normalize_volume: Option<bool>,
+}
+
+/// Input for the relative weights of the similarity dimensions
+///
+/// Weights must be non-negative and not all zero; they are scaled to sum to
+/// 1.0 when scoring.
+#[derive(Debug, InputObject)]
+pub struct SimilarityWeightsInput {
+    /// Weight for semantic (embedding) similarity
+    pub semantic: f64,
+    /// Weight for acoustic (audio feature) similarity
+    pub acoustic: f64,
+    /// Weight for categorical (genre/mood/tag) similarity
+    pub categorical: f64,
+}
+
 // =============================================================================
 // Validation Helpers
 // =============================================================================
@@ -129,6 +172,78 @@ fn validate_crossfade(duration_ms: u32) -> Result<()> {
     Ok(())
 }
 
+/// Validate a device ID
+fn validate_device_id(device_id: &str) -> Result<()> {
+    if device_id.trim().is_empty() {
+        return Err(async_graphql::Error::new("Device ID cannot be empty"));
+    }
+    if device_id.len() > MAX_DEVICE_ID_LENGTH {
+        return Err(async_graphql::Error::new(format!(
+            "Device ID cannot exceed {} characters",
+            MAX_DEVICE_ID_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Validate and normalize device overrides
+fn device_prefs_from_input(input: DevicePreferencesInput) -> Result<DevicePrefs> {
+    let transcode_format = match input.transcode_format {
+        Some(format) => {
+            let parsed = TranscodeFormat::parse(format.trim()).ok_or_else(|| {
+                async_graphql::Error::new(format!(
+                    "Invalid transcode format '{}'. Valid values: mp3, aac, opus, flac",
+                    format
+                ))
+            })?;
+            Some(parsed.extension().to_string())
+        }
+        None => None,
+    };
+
+    if let Some(bitrate) = input.cellular_max_bitrate_kbps {
+        if !LOSSY_BITRATES.contains(&bitrate) {
+            return Err(async_graphql::Error::new(format!(
+                "Invalid cellular bitrate {} kbps. Valid values: {:?}",
+                bitrate, LOSSY_BITRATES
+            )));
+        }
+    }
+
+    Ok(DevicePrefs {
+        transcode_format,
+        cellular_max_bitrate_kbps: input.cellular_max_bitrate_kbps,
+        normalize_volume: input.normalize_volume,
+    })
+}
+
+/// Validate similarity weights input
+fn similarity_weights_from_input(input: SimilarityWeightsInput) -> Result<SimilarityWeights> {
+    for (name, weight) in [
+        ("semantic", input.semantic),
+        ("acoustic", input.acoustic),
+        ("categorical", input.categorical),
+    ] {
+        if !weight.is_finite() || weight < 0.0 {
+            return Err(async_graphql::Error::new(format!(
+                "Invalid {} weight: {}. Weights must be non-negative",
+                name, weight
+            )));
+        }
+    }
+    if input.semantic + input.acoustic + input.categorical <= 0.0 {
+        return Err(async_graphql::Error::new(
+            "At least one similarity weight must be positive",
+        ));
+    }
+
+    Ok(SimilarityWeights {
+        semantic: input.semantic,
+        acoustic: input.acoustic,
+        categorical: input.categorical,
+    })
+}
+
 /// Validate the entire input
 fn validate_input(input: &UpdatePreferencesInput) -> Result<()> {
     if let Some(ref theme) = input.theme {
@@ -393,6 +508,149 @@ impl PreferencesMutation {
 
         Ok(UserPreferencesType::from(user.preferences))
     }
+
+    /// Override audio preferences for one of the user's devices
+    ///
+    /// Replaces the device's overrides; omitted fields fall back to the
+    /// global preferences. Passing no fields removes the overrides.
+    ///
+    /// # Arguments
+    /// * `device_id` - The device the overrides apply to
+    /// * `input` - The overrides to store
+    ///
+    /// # Returns
+    /// The preferences now in effect for the device
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if the device ID is empty or too long
+    /// - Returns error if the transcode format or bitrate is invalid
+    /// - Returns error if the user already has overrides for too many devices
+    async fn set_device_preferences(
+        &self,
+        ctx: &Context<'_>,
+        device_id: String,
+        input: DevicePreferencesInput,
+    ) -> Result<DevicePreferencesType> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        validate_device_id(&device_id)?;
+        let prefs = device_prefs_from_input(input)?;
+
+        let user_repo = ctx.data::<UserRepository>()?;
+
+        let user = user_repo
+            .find_by_id(claims.sub)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to fetch user");
+                async_graphql::Error::new("Failed to fetch user preferences")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("User not found"))?;
+
+        let overrides = &user.preferences.device_overrides;
+        if !prefs.is_empty()
+            && !overrides.contains_key(&device_id)
+            && overrides.len() >= MAX_DEVICE_OVERRIDES
+        {
+            return Err(async_graphql::Error::new(format!(
+                "Cannot store overrides for more than {} devices",
+                MAX_DEVICE_OVERRIDES
+            )));
+        }
+
+        user_repo
+            .set_device_prefs(claims.sub, &device_id, &prefs)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to update device preferences");
+                async_graphql::Error::new("Failed to update device preferences")
+            })?;
+
+        let effective = user_repo
+            .get_device_prefs(claims.sub, &device_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to fetch device preferences");
+                async_graphql::Error::new("Failed to fetch device preferences")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("User not found"))?;
+
+        tracing::info!(
+            user_id = %claims.sub,
+            device_id = %device_id,
+            "Device preferences updated"
+        );
+
+        Ok(DevicePreferencesType::new(device_id, effective))
+    }
+
+    /// Set the blend of similarity dimensions used for the user's recommendations
+    ///
+    /// Applies to the `similarTracks` query and the chat assistant's
+    /// recommendations. Passing null restores the server's weights.
+    ///
+    /// # Arguments
+    /// * `weights` - Relative weights; scaled to sum to 1.0 when scoring
+    ///
+    /// # Returns
+    /// The updated preferences
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    /// - Returns error if a weight is negative or all weights are zero
+    ///
+    /// # Example
+    /// ```graphql
+    /// mutation {
+    ///   setSimilarityWeights(weights: { semantic: 0.2, acoustic: 0.2, categorical: 0.6 }) {
+    ///     similarityWeights { semantic acoustic categorical }
+    ///   }
+    /// }
+    /// ```
+    async fn set_similarity_weights(
+        &self,
+        ctx: &Context<'_>,
+        weights: Option<SimilarityWeightsInput>,
+    ) -> Result<UserPreferencesType> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        let weights = weights.map(similarity_weights_from_input).transpose()?;
+
+        let user_repo = ctx.data::<UserRepository>()?;
+
+        let updated = user_repo
+            .set_similarity_weights(claims.sub, weights.as_ref())
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to update similarity weights");
+                async_graphql::Error::new("Failed to update preferences")
+            })?;
+        if !updated {
+            return Err(async_graphql::Error::new("User not found"));
+        }
+
+        let user = user_repo
+            .find_by_id(claims.sub)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to fetch updated user");
+                async_graphql::Error::new("Failed to fetch updated user")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("User not found"))?;
+
+        tracing::info!(
+            user_id = %claims.sub,
+            custom = weights.is_some(),
+            "Similarity weights updated"
+        );
+
+        Ok(UserPreferencesType::from(user.preferences))
+    }
 }
 
 #[cfg(test)]
@@ -444,6 +702,60 @@ mod tests {
         assert!(validate_crossfade(15000).is_err());
     }
 
+    #[test]
+    fn test_similarity_weights_from_input() {
+        let weights = similarity_weights_from_input(SimilarityWeightsInput {
+            semantic: 0.0,
+            acoustic: 1.0,
+            categorical: 3.0,
+        })
+        .unwrap();
+        assert_eq!(weights.categorical, 3.0);
+
+        for (semantic, acoustic, categorical) in
+            [(-0.1, 0.5, 0.6), (0.0, 0.0, 0.0), (f64::INFINITY, 0.0, 0.0)]
+        {
+            assert!(similarity_weights_from_input(SimilarityWeightsInput {
+                semantic,
+                acoustic,
+                categorical,
+            })
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_device_prefs_from_input_normalizes_format() {
+        let prefs = device_prefs_from_input(DevicePreferencesInput {
+            transcode_format: Some(" OGG ".to_string()),
+            cellular_max_bitrate_kbps: Some(128),
+            normalize_volume: Some(true),
+        })
+        .unwrap();
+
+        assert_eq!(prefs.transcode_format.as_deref(), Some("opus"));
+        assert_eq!(prefs.cellular_max_bitrate_kbps, Some(128));
+        assert_eq!(prefs.normalize_volume, Some(true));
+    }
+
+    #[test]
+    fn test_device_prefs_from_input_invalid() {
+        assert!(device_prefs_from_input(DevicePreferencesInput {
+            transcode_format: Some("wma".to_string()),
+            cellular_max_bitrate_kbps: None,
+            normalize_volume: None,
+        })
+        .is_err());
+        assert!(device_prefs_from_input(DevicePreferencesInput {
+            transcode_format: None,
+            cellular_max_bitrate_kbps: Some(100),
+            normalize_volume: None,
+        })
+        .is_err());
+        assert!(validate_device_id("  ").is_err());
+        assert!(validate_device_id(&"d".repeat(256)).is_err());
+    }
+
     #[test]
     fn test_apply_updates() {
         let prefs = UserPreferences::default();
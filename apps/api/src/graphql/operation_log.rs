@@ -0,0 +1,315 @@
+//! Per-operation GraphQL logging
+//!
+//! [`OperationLogging`] runs every operation inside a `graphql_operation`
+//! span and, once it finishes, emits one structured event with the operation
+//! name, authenticated user, execution time, and the complexity and depth
+//! computed during validation.
+//!
+//! Variables are only logged when [`OperationLogConfig::log_variables`] is
+//! enabled, and values under keys that look like secrets (passwords, tokens,
+//! API keys) are always redacted.
+
+use std::env;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use async_graphql::extensions::{
+    Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery, NextValidation,
+};
+use async_graphql::parser::types::{DocumentOperations, ExecutableDocument};
+use async_graphql::{Response, ServerError, ServerResult, ValidationResult, Variables};
+use tracing::Instrument;
+
+use crate::models::user::Claims;
+
+/// Replacement for redacted variable values
+const REDACTED: &str = "[REDACTED]";
+
+/// Lowercased key fragments whose values are never logged
+const SECRET_KEY_FRAGMENTS: &[&str] = &[
+    "password",
+    "token",
+    "secret",
+    "apikey",
+    "api_key",
+    "authorization",
+];
+
+/// Operation logging settings
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperationLogConfig {
+    /// Include (redacted) variables in the operation event
+    pub log_variables: bool,
+}
+
+impl OperationLogConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Environment variables:
+    /// - `GRAPHQL_LOG_VARIABLES` (default: false)
+    pub fn from_env() -> Self {
+        let log_variables = env::var("GRAPHQL_LOG_VARIABLES")
+            .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+            .unwrap_or(false);
+
+        Self { log_variables }
+    }
+}
+
+/// Schema extension logging each operation's name, user, timing and complexity
+pub struct OperationLogging {
+    config: OperationLogConfig,
+}
+
+impl OperationLogging {
+    pub fn new(config: OperationLogConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl ExtensionFactory for OperationLogging {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(OperationLoggingExtension {
+            config: self.config,
+            state: Mutex::new(OperationState::default()),
+        })
+    }
+}
+
+/// What earlier request phases learned about the operation
+#[derive(Default)]
+struct OperationState {
+    /// Name of the document's only operation, when the request didn't name one
+    document_operation: Option<String>,
+    complexity: Option<usize>,
+    depth: Option<usize>,
+    variables: Option<String>,
+}
+
+struct OperationLoggingExtension {
+    config: OperationLogConfig,
+    state: Mutex<OperationState>,
+}
+
+impl OperationLoggingExtension {
+    fn state(&self) -> MutexGuard<'_, OperationState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_graphql::async_trait::async_trait]
+impl Extension for OperationLoggingExtension {
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let document = next.run(ctx, query, variables).await?;
+
+        let mut state = self.state();
+        state.document_operation = single_operation_name(&document);
+        if self.config.log_variables {
+            state.variables = Some(redact_variables(variables).to_string());
+        }
+
+        Ok(document)
+    }
+
+    async fn validation(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        next: NextValidation<'_>,
+    ) -> Result<ValidationResult, Vec<ServerError>> {
+        let result = next.run(ctx).await?;
+
+        let mut state = self.state();
+        state.complexity = Some(result.complexity);
+        state.depth = Some(result.depth);
+
+        Ok(result)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        let (operation, complexity, depth, variables) = {
+            let state = self.state();
+            let operation = operation_name
+                .map(str::to_string)
+                .or_else(|| state.document_operation.clone())
+                .unwrap_or_else(|| "anonymous".to_string());
+            (
+                operation,
+                state.complexity,
+                state.depth,
+                state.variables.clone(),
+            )
+        };
+        let user_id = ctx.data_opt::<Claims>().map(|claims| claims.sub);
+
+        let span = tracing::info_span!(
+            "graphql_operation",
+            operation = %operation,
+            user_id = ?user_id,
+            complexity = ?complexity,
+        );
+
+        let started = Instant::now();
+        let response = next.run(ctx, operation_name).instrument(span).await;
+        let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+        tracing::info!(
+            operation = %operation,
+            user_id = ?user_id,
+            duration_ms,
+            complexity = ?complexity,
+            depth = ?depth,
+            errors = response.errors.len(),
+            variables = variables.as_deref(),
+            "GraphQL operation finished"
+        );
+
+        response
+    }
+}
+
+/// Name of the document's operation, if it has exactly one and it is named
+fn single_operation_name(document: &ExecutableDocument) -> Option<String> {
+    match &document.operations {
+        DocumentOperations::Single(_) => None,
+        DocumentOperations::Multiple(operations) if operations.len() == 1 => {
+            operations.keys().next().map(|name| name.to_string())
+        }
+        DocumentOperations::Multiple(_) => None,
+    }
+}
+
+/// Variables as JSON, with values under secret-looking keys replaced
+fn redact_variables(variables: &Variables) -> serde_json::Value {
+    let mut json = serde_json::to_value(variables).unwrap_or(serde_json::Value::Null);
+    redact_secrets(&mut json);
+    json
+}
+
+/// Replace values under secret-looking keys at any depth
+fn redact_secrets(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map.iter_mut() {
+                if is_secret_key(key) {
+                    *value = serde_json::Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_FRAGMENTS
+        .iter()
+        .any(|fragment| key.contains(fragment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::{EmptyMutation, EmptySubscription, Object, Request, Schema};
+    use serde_json::json;
+    use tracing_test::traced_test;
+
+    struct SampleQuery;
+
+    #[Object]
+    impl SampleQuery {
+        async fn greeting(&self, name: String) -> String {
+            format!("Hello, {}", name)
+        }
+    }
+
+    fn sample_schema(
+        config: OperationLogConfig,
+    ) -> Schema<SampleQuery, EmptyMutation, EmptySubscription> {
+        Schema::build(SampleQuery, EmptyMutation, EmptySubscription)
+            .extension(OperationLogging::new(config))
+            .finish()
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_logs_operation_name_and_duration() {
+        let schema = sample_schema(OperationLogConfig::default());
+
+        let response = schema
+            .execute(Request::new(
+                r#"query SampleGreeting { greeting(name: "Ada") }"#,
+            ))
+            .await;
+        assert!(response.errors.is_empty());
+
+        assert!(logs_contain("GraphQL operation finished"));
+        assert!(logs_contain("operation=SampleGreeting"));
+        assert!(logs_contain("duration_ms="));
+        assert!(logs_contain("complexity=Some(1)"));
+        // Variables are opt-in
+        assert!(!logs_contain("variables="));
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_logs_redacted_variables_when_enabled() {
+        let schema = sample_schema(OperationLogConfig {
+            log_variables: true,
+        });
+
+        let request = Request::new("query Greet($name: String!) { greeting(name: $name) }")
+            .variables(Variables::from_json(json!({
+                "name": "Ada",
+                "password": "hunter2",
+            })));
+        schema.execute(request).await;
+
+        assert!(logs_contain("operation=Greet"));
+        assert!(logs_contain("Ada"));
+        assert!(!logs_contain("hunter2"));
+    }
+
+    #[test]
+    fn test_redacts_secret_keys_at_any_depth() {
+        let variables = Variables::from_json(json!({
+            "input": {
+                "email": "ada@example.com",
+                "currentPassword": "old",
+                "newPassword": "new",
+                "integrations": [{ "apiKey": "abc", "service": "lastfm" }],
+            },
+            "refreshToken": "xyz",
+            "limit": 10,
+        }));
+
+        assert_eq!(
+            redact_variables(&variables),
+            json!({
+                "input": {
+                    "email": "ada@example.com",
+                    "currentPassword": REDACTED,
+                    "newPassword": REDACTED,
+                    "integrations": [{ "apiKey": REDACTED, "service": "lastfm" }],
+                },
+                "refreshToken": REDACTED,
+                "limit": 10,
+            })
+        );
+    }
+}
@@ -2,6 +2,19 @@
 //!
 //! This module provides constants and helper functions for consistent
 //! pagination across all query resolvers.
+//!
+//! Relay-style connections use a [`Cursor`] wrapping a stable key (an offset,
+//! or a keyset tuple such as `(played_at, id)`), encoded as opaque base64 so
+//! clients can't depend on its shape. [`ConnectionExt::from_slice`] builds a
+//! connection from one fetched page, and [`offset_connection`] covers the
+//! common `first`/`after` flow over an offset-ordered list.
+
+use std::future::Future;
+
+use async_graphql::connection::{query, Connection, CursorType, Edge};
+use async_graphql::OutputType;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{de::DeserializeOwned, Serialize};
 
 /// Maximum items per page for top-level list queries
 pub const MAX_LIMIT: i32 = 100;
@@ -27,6 +40,140 @@ pub fn clamp_offset(offset: i32) -> i64 {
     offset.max(0) as i64
 }
 
+/// Connection page size when `first` is omitted
+pub const DEFAULT_PAGE_SIZE: i32 = 50;
+
+/// Longest cursor accepted; anything longer can't have come from us
+const MAX_CURSOR_LEN: usize = 512;
+
+/// Opaque connection cursor holding a stable key
+///
+/// Encoded as URL-safe base64 of the key's JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor<K>(pub K);
+
+/// Why a client-supplied cursor was rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum CursorError {
+    #[error("Invalid cursor: too long")]
+    TooLong,
+    #[error("Invalid cursor: not base64")]
+    Encoding,
+    #[error("Invalid cursor: unrecognized contents")]
+    Key,
+}
+
+impl<K: Serialize + DeserializeOwned> Cursor<K> {
+    /// Encode the key as an opaque cursor string
+    pub fn encode(&self) -> String {
+        // Keys are plain data, so serializing them can't fail
+        let json = serde_json::to_vec(&self.0).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    /// Decode a cursor string produced by [`Cursor::encode`]
+    ///
+    /// # Errors
+    /// Returns a [`CursorError`] for anything else, never panicking
+    pub fn decode(s: &str) -> Result<Self, CursorError> {
+        if s.len() > MAX_CURSOR_LEN {
+            return Err(CursorError::TooLong);
+        }
+        let json = URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| CursorError::Encoding)?;
+        serde_json::from_slice(&json)
+            .map(Cursor)
+            .map_err(|_| CursorError::Key)
+    }
+}
+
+impl<K: Serialize + DeserializeOwned> CursorType for Cursor<K> {
+    type Error = CursorError;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        Self::decode(s)
+    }
+
+    fn encode_cursor(&self) -> String {
+        self.encode()
+    }
+}
+
+/// Relay connection whose edges are addressed by [`Cursor`]s
+pub type CursorConnection<K, T> = Connection<Cursor<K>, T>;
+
+/// Building a connection from one fetched page
+pub trait ConnectionExt<K, T> {
+    /// Connection over `items` in order; `cursor_fn(index, item)` gives each
+    /// edge's key and `has_more` whether another page follows
+    fn from_slice(items: Vec<T>, has_more: bool, cursor_fn: impl FnMut(usize, &T) -> K) -> Self;
+}
+
+impl<K, T> ConnectionExt<K, T> for CursorConnection<K, T>
+where
+    K: Serialize + DeserializeOwned + Send + Sync,
+    T: OutputType,
+{
+    fn from_slice(
+        items: Vec<T>,
+        has_more: bool,
+        mut cursor_fn: impl FnMut(usize, &T) -> K,
+    ) -> Self {
+        let mut connection = Connection::new(false, has_more);
+        connection
+            .edges
+            .extend(items.into_iter().enumerate().map(|(i, item)| {
+                let cursor = Cursor(cursor_fn(i, &item));
+                Edge::new(cursor, item)
+            }));
+        connection
+    }
+}
+
+/// Page through an offset-ordered list with `first`/`after` arguments
+///
+/// `fetch(limit, offset)` loads up to `limit` items starting at `offset`; one
+/// more item than the page size is requested to tell whether a next page
+/// exists. Cursors hold each item's offset.
+///
+/// # Errors
+/// Returns an error for a malformed `after` cursor, a negative `first`, or a
+/// failed fetch
+pub async fn offset_connection<T, F, Fut>(
+    after: Option<String>,
+    first: Option<i32>,
+    fetch: F,
+) -> async_graphql::Result<CursorConnection<usize, T>>
+where
+    T: OutputType,
+    F: FnOnce(i64, i64) -> Fut,
+    Fut: Future<Output = async_graphql::Result<Vec<T>>>,
+{
+    query(
+        after,
+        None,
+        first,
+        None,
+        |after: Option<Cursor<usize>>, _before: Option<Cursor<usize>>, first, _last| async move {
+            let offset = after.map_or(0, |Cursor(offset)| offset.saturating_add(1));
+            let limit = clamp_limit(
+                first.map_or(DEFAULT_PAGE_SIZE, |f| f.min(MAX_LIMIT as usize) as i32),
+                MAX_LIMIT,
+            );
+
+            let mut items = fetch(limit + 1, offset as i64).await?;
+            let has_more = items.len() as i64 > limit;
+            items.truncate(limit as usize);
+
+            let mut connection = Connection::from_slice(items, has_more, |i, _| offset + i);
+            connection.has_previous_page = offset > 0;
+            Ok::<_, async_graphql::Error>(connection)
+        },
+    )
+    .await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +203,87 @@ mod tests {
     fn test_clamp_offset_negative() {
         assert_eq!(clamp_offset(-5), 0);
     }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let offset = Cursor(42usize);
+        assert_eq!(Cursor::<usize>::decode(&offset.encode()), Ok(offset));
+
+        // Keyset cursors over compound keys round-trip too
+        let keyset = Cursor(("2025-01-01T00:00:00Z".to_string(), uuid::Uuid::nil()));
+        assert_eq!(Cursor::decode(&keyset.encode()), Ok(keyset.clone()));
+
+        // Cursors are opaque, URL-safe strings
+        let encoded = keyset.encode();
+        assert!(!encoded.contains("2025"));
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_cursor_rejects_malformed_input() {
+        assert_eq!(
+            Cursor::<usize>::decode("not base64!"),
+            Err(CursorError::Encoding)
+        );
+        // Valid base64 that isn't a key of the expected type
+        assert_eq!(
+            Cursor::<usize>::decode(&URL_SAFE_NO_PAD.encode("hello")),
+            Err(CursorError::Key)
+        );
+        assert_eq!(
+            Cursor::<usize>::decode(&Cursor(-1i64).encode()),
+            Err(CursorError::Key)
+        );
+        assert_eq!(
+            Cursor::<usize>::decode(&"A".repeat(MAX_CURSOR_LEN + 1)),
+            Err(CursorError::TooLong)
+        );
+        assert_eq!(Cursor::<usize>::decode(""), Err(CursorError::Key));
+    }
+
+    #[test]
+    fn test_connection_from_slice() {
+        let connection: CursorConnection<usize, i32> =
+            Connection::from_slice(vec![10, 20, 30], true, |i, _| 5 + i);
+
+        assert!(connection.has_next_page);
+        assert!(!connection.has_previous_page);
+        let cursors: Vec<usize> = connection.edges.iter().map(|e| e.cursor.0).collect();
+        assert_eq!(cursors, vec![5, 6, 7]);
+        let nodes: Vec<i32> = connection.edges.iter().map(|e| e.node).collect();
+        assert_eq!(nodes, vec![10, 20, 30]);
+    }
+
+    #[tokio::test]
+    async fn test_offset_connection_pages() {
+        let items: Vec<i32> = (0..5).collect();
+        let fetch = |limit: i64, offset: i64| {
+            let page = items
+                .iter()
+                .copied()
+                .skip(offset as usize)
+                .take(limit as usize)
+                .collect();
+            async move { Ok(page) }
+        };
+
+        let first = offset_connection(None, Some(2), fetch).await.unwrap();
+        assert!(first.has_next_page && !first.has_previous_page);
+        let after = first.edges.last().unwrap().cursor.encode();
+
+        let second = offset_connection(Some(after), Some(10), fetch)
+            .await
+            .unwrap();
+        assert!(!second.has_next_page && second.has_previous_page);
+        let nodes: Vec<i32> = second.edges.iter().map(|e| e.node).collect();
+        assert_eq!(nodes, vec![2, 3, 4]);
+
+        // Garbage cursors are a clean error, not a panic
+        let Err(err) = offset_connection(Some("%%%".to_string()), None, fetch).await else {
+            panic!("malformed cursor was accepted");
+        };
+        assert!(err.message.contains("Invalid cursor"));
+    }
 }
@@ -4,7 +4,11 @@
 //! - System statistics (user count, track count, etc.)
 //! - User listing with pagination and search
 //! - User detail with session information
+//! - Session audit across users with filtering
 //! - Runtime configuration overview
+//! - Manual library scan status
+//! - Embedding regeneration progress
+//! - Audio analysis progress
 //!
 //! All queries require admin role authentication.
 
@@ -13,13 +17,17 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::graphql::types::{
-    AdminSession, AdminUserDetail, AdminUserList, AdminUserListItem, ConfigSource,
+    AdminSession, AdminSessionFilter, AdminSessionList, AdminUserDetail, AdminUserList,
+    AdminUserListItem, AnalysisProgress, ConfigSource, EmbeddingRegeneration, LibraryScan,
     RuntimeConfigOverview, RuntimeConfigStatus, ServiceType, SystemStats,
 };
 use crate::models::user::{Claims, UserRole};
 use crate::repositories::AdminRepository;
 use crate::services::config::ConfigService;
 
+/// Number of recent runs averaged for the analysis ETA
+const ANALYSIS_ETA_WINDOW: i64 = 50;
+
 /// Admin-only queries
 #[derive(Default)]
 pub struct AdminQuery;
@@ -109,6 +117,53 @@ impl AdminQuery {
         })
     }
 
+    /// List sessions across all users for auditing
+    ///
+    /// # Arguments
+    /// * `limit` - Maximum number of sessions to return (default: 20, max: 100)
+    /// * `offset` - Number of sessions to skip (default: 0)
+    /// * `filter` - Optional filters by user, IP address, device type and status
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    async fn admin_sessions(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 20)] limit: i32,
+        #[graphql(default = 0)] offset: i32,
+        filter: Option<AdminSessionFilter>,
+    ) -> Result<AdminSessionList> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let limit = limit.clamp(1, 100) as i64;
+        let offset = offset.max(0) as i64;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let filter = filter.unwrap_or_default().into();
+        let (sessions, total_count) =
+            repo.list_sessions(&filter, limit, offset)
+                .await
+                .map_err(|e| {
+                    tracing::error!(error = %e, "Failed to list sessions");
+                    async_graphql::Error::new("Failed to retrieve sessions")
+                })?;
+
+        let sessions: Vec<AdminSession> = sessions.into_iter().map(|s| s.into()).collect();
+        let has_next_page = offset + (sessions.len() as i64) < total_count;
+
+        Ok(AdminSessionList {
+            sessions,
+            total_count,
+            has_next_page,
+        })
+    }
+
     /// Get detailed user information including sessions
     ///
     /// # Arguments
@@ -148,6 +203,99 @@ impl AdminQuery {
         })
     }
 
+    /// Get the status of a library scan started with `triggerLibraryScan`
+    ///
+    /// # Arguments
+    /// * `scan_id` - The scan ID returned by `triggerLibraryScan`
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    async fn admin_library_scan(
+        &self,
+        ctx: &Context<'_>,
+        scan_id: Uuid,
+    ) -> Result<Option<LibraryScan>> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let scan = repo.find_library_scan(scan_id).await.map_err(|e| {
+            tracing::error!(error = %e, scan_id = %scan_id, "Failed to get library scan");
+            async_graphql::Error::new("Failed to retrieve library scan")
+        })?;
+
+        Ok(scan.map(Into::into))
+    }
+
+    /// Get the progress of an embedding regeneration started with
+    /// `regenerateAllEmbeddings`
+    ///
+    /// # Arguments
+    /// * `regeneration_id` - The ID returned by `regenerateAllEmbeddings`
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    async fn admin_embedding_regeneration(
+        &self,
+        ctx: &Context<'_>,
+        regeneration_id: Uuid,
+    ) -> Result<Option<EmbeddingRegeneration>> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let regeneration = repo
+            .find_embedding_regeneration(regeneration_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, regeneration_id = %regeneration_id, "Failed to get embedding regeneration");
+                async_graphql::Error::new("Failed to retrieve embedding regeneration")
+            })?;
+
+        Ok(regeneration.map(Into::into))
+    }
+
+    /// Get progress of audio feature extraction and embedding generation
+    ///
+    /// The ETA averages the worker's last 50 successful runs of each kind.
+    /// Before any track has been analyzed, counts are zero and the ETA is
+    /// null.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated as admin
+    async fn analysis_progress(&self, ctx: &Context<'_>) -> Result<AnalysisProgress> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
+
+        require_admin(claims)?;
+
+        let pool = ctx.data::<PgPool>()?;
+        let repo = AdminRepository::new(pool.clone());
+
+        let (counts, feature_ms, embedding_ms) = tokio::try_join!(
+            repo.get_analysis_counts(),
+            repo.recent_analysis_durations("features", ANALYSIS_ETA_WINDOW),
+            repo.recent_analysis_durations("embedding", ANALYSIS_ETA_WINDOW),
+        )
+        .map_err(|e| {
+            tracing::error!(error = %e, "Failed to get analysis progress");
+            async_graphql::Error::new("Failed to retrieve analysis progress")
+        })?;
+
+        Ok(AnalysisProgress::new(counts, &feature_ms, &embedding_ms))
+    }
+
     /// Get runtime configuration overview
     ///
     /// Returns the current configuration status for all services,
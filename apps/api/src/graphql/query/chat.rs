@@ -10,6 +10,7 @@ use uuid::Uuid;
 
 use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_LIMIT};
 use crate::graphql::types::chat::{ChatConversation, ChatConversationWithMessages, ChatMessage};
+use crate::models::chat::ConversationFilter;
 use crate::models::user::Claims;
 use crate::repositories::ChatRepository;
 
@@ -25,11 +26,13 @@ impl ChatQuery {
     /// List the authenticated user's chat conversations
     ///
     /// Returns conversations in reverse chronological order (most recent first).
+    /// Archived conversations are excluded unless `include_archived` is set.
     /// Requires authentication.
     ///
     /// # Arguments
     /// * `limit` - Maximum number of conversations to return (default: 20, max: 100)
     /// * `offset` - Number of conversations to skip (default: 0)
+    /// * `include_archived` - Also return archived conversations (default: false)
     ///
     /// # Returns
     /// List of chat conversations
@@ -38,15 +41,21 @@ impl ChatQuery {
         ctx: &Context<'_>,
         #[graphql(default = 20)] limit: i32,
         #[graphql(default = 0)] offset: i32,
+        #[graphql(default = false)] include_archived: bool,
     ) -> Result<Vec<ChatConversation>> {
         let claims = ctx
             .data_opt::<Claims>()
             .ok_or_else(|| async_graphql::Error::new("Authentication required"))?;
 
         let repo = ctx.data::<ChatRepository>()?;
+        let filter = ConversationFilter {
+            include_archived,
+            ..Default::default()
+        };
         let conversations = repo
-            .find_conversations_by_user(
+            .find_conversations_filtered(
                 claims.sub,
+                &filter,
                 clamp_limit(limit, MAX_LIMIT),
                 clamp_offset(offset),
             )
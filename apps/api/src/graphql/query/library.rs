@@ -3,13 +3,18 @@
 //! This module provides queries for browsing the music library:
 //! - Artists: List and search artists
 //! - Albums: List and search albums
-//! - Tracks: List and search tracks
+//! - Tracks: List and search tracks, filter by audio features
+//! - Completeness: How much metadata the library has populated
 
 use async_graphql::{Context, Object, Result, ID};
 use uuid::Uuid;
 
-use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_LIMIT, MAX_SEARCH_LIMIT};
-use crate::graphql::types::{Album, Artist, Track};
+use crate::graphql::pagination::{
+    clamp_limit, clamp_offset, offset_connection, CursorConnection, MAX_LIMIT, MAX_SEARCH_LIMIT,
+};
+use crate::graphql::types::{
+    Album, Artist, AudioFeatureFilter, DuplicateGroup, LibraryCompleteness, Track,
+};
 use crate::repositories::{AlbumRepository, ArtistRepository, TrackRepository};
 
 /// Library-related queries for browsing artists, albums, and tracks
@@ -208,6 +213,27 @@ impl LibraryQuery {
         Ok(tracks.into_iter().map(Track::from).collect())
     }
 
+    /// Find tracks whose audio features fall within the given ranges
+    ///
+    /// e.g. `filter: { energy: { min: 0.8 }, danceability: { min: 0.7 } }`.
+    /// Returns a cursor-paginated connection ordered by title.
+    async fn tracks_by_audio_features(
+        &self,
+        ctx: &Context<'_>,
+        filter: AudioFeatureFilter,
+        #[graphql(desc = "Number of tracks to return (default: 50, max: 100)")] first: Option<i32>,
+        #[graphql(desc = "Cursor of the last track from the previous page")] after: Option<String>,
+    ) -> Result<CursorConnection<usize, Track>> {
+        let repo = ctx.data::<TrackRepository>()?;
+        let bounds = filter.to_bounds()?;
+
+        offset_connection(after, first, |limit, offset| async move {
+            let tracks = repo.find_by_audio_features(&bounds, limit, offset).await?;
+            Ok(tracks.into_iter().map(Track::from).collect())
+        })
+        .await
+    }
+
     /// Get top played tracks globally
     async fn top_tracks(
         &self,
@@ -218,4 +244,41 @@ impl LibraryQuery {
         let tracks = repo.find_top_tracks(clamp_limit(limit, MAX_LIMIT)).await?;
         Ok(tracks.into_iter().map(Track::from).collect())
     }
+
+    /// Groups of likely-duplicate tracks flagged by the library scan
+    ///
+    /// Nothing is removed automatically; the groups are for the user to review.
+    async fn duplicate_groups(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 50)] limit: i32,
+        #[graphql(default = 0)] offset: i32,
+    ) -> Result<Vec<DuplicateGroup>> {
+        let repo = ctx.data::<TrackRepository>()?;
+        let groups = repo
+            .find_duplicate_groups(clamp_limit(limit, MAX_LIMIT), clamp_offset(offset))
+            .await?;
+        Ok(groups.into_iter().map(DuplicateGroup::from).collect())
+    }
+
+    // ==================== Completeness Queries ====================
+
+    /// Average metadata completeness of artists, albums and tracks
+    async fn library_completeness(&self, ctx: &Context<'_>) -> Result<LibraryCompleteness> {
+        let artists = ctx
+            .data::<ArtistRepository>()?
+            .completeness_counts()
+            .await?;
+        let albums = ctx.data::<AlbumRepository>()?.completeness_counts().await?;
+        let tracks = ctx.data::<TrackRepository>()?.completeness_counts().await?;
+
+        Ok(LibraryCompleteness {
+            artists: artists.average_score(),
+            albums: albums.average_score(),
+            tracks: tracks.average_score(),
+            artist_count: artists.total,
+            album_count: albums.total,
+            track_count: tracks.total,
+        })
+    }
 }
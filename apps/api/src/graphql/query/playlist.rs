@@ -4,14 +4,20 @@
 //! - playlist: Get a specific playlist by ID
 //! - myPlaylists: Get playlists owned by the authenticated user
 //! - publicPlaylists: Browse public playlists
+//! - exportPlaylist: Export a playlist as M3U or JSON
+//! - previewSmartPlaylist: List the tracks smart playlist rules would match
 
-use async_graphql::{Context, Object, Result, ID};
+use async_graphql::{Context, ErrorExtensions, Object, Result, ID};
 use uuid::Uuid;
 
+use crate::error::ApiError;
+use crate::graphql::loaders::{BatchLoader, TrackLoader};
+use crate::graphql::mutation::{smart_rule_errors, SmartPlaylistRulesInput, SmartRuleError};
 use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_LIMIT};
-use crate::graphql::types::Playlist;
+use crate::graphql::types::{Playlist, PlaylistFileFormat, SmartPlaylistPreview, Track};
 use crate::models::user::Claims;
 use crate::repositories::PlaylistRepository;
+use crate::services::playlist::PlaylistService;
 
 /// Playlist-related queries
 #[derive(Default)]
@@ -91,4 +97,109 @@ impl PlaylistQuery {
             .await?;
         Ok(playlists.into_iter().map(Playlist::from).collect())
     }
+
+    /// Export a playlist as M3U or JSON
+    ///
+    /// Requires authentication and access to the playlist. Returns the file
+    /// contents as a string.
+    async fn export_playlist(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        format: PlaylistFileFormat,
+    ) -> Result<String> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+
+        let playlist_id =
+            Uuid::parse_str(&id).map_err(|_| async_graphql::Error::new("Invalid playlist ID"))?;
+
+        let service = ctx.data::<PlaylistService>()?;
+        service
+            .export(playlist_id, claims.sub, format.into())
+            .await
+            .map_err(|e| match e {
+                // Don't reveal whether an inaccessible playlist exists
+                ApiError::NotFound { .. } | ApiError::Forbidden(_) => {
+                    async_graphql::Error::new("Playlist not found")
+                }
+                other => {
+                    tracing::error!(error = %other, "Playlist export failed");
+                    async_graphql::Error::new("Failed to export playlist")
+                }
+            })
+    }
+
+    /// Preview the tracks a set of smart playlist rules would match
+    ///
+    /// Requires authentication. Evaluates the rules against the user's
+    /// library exactly as a saved smart playlist would, but saves nothing.
+    /// Returns up to `limit` tracks plus the total number matched. Invalid
+    /// rules fail with a `VALIDATION_ERROR` whose `fieldErrors` extension
+    /// lists every problem as `{ field, message }`.
+    async fn preview_smart_playlist(
+        &self,
+        ctx: &Context<'_>,
+        rules: SmartPlaylistRulesInput,
+        #[graphql(default = 50)] limit: i32,
+    ) -> Result<SmartPlaylistPreview> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+
+        let mut errors = smart_rule_errors(&rules);
+        if rules.rules.is_empty() {
+            errors.insert(
+                0,
+                SmartRuleError {
+                    field: "rules".to_string(),
+                    message: "Smart playlist must have at least one rule".to_string(),
+                },
+            );
+        }
+        if !errors.is_empty() {
+            return Err(invalid_rules_error(&errors));
+        }
+
+        let service = ctx.data::<PlaylistService>()?;
+        let track_ids = service
+            .evaluate_smart_rules(&rules.into(), claims.sub)
+            .await
+            .map_err(|e| e.extend())?;
+        let total_count = i32::try_from(track_ids.len()).unwrap_or(i32::MAX);
+
+        let shown: Vec<Uuid> = track_ids
+            .into_iter()
+            .take(clamp_limit(limit, MAX_LIMIT) as usize)
+            .collect();
+        let track_loader = ctx.data::<BatchLoader<TrackLoader>>()?;
+        let mut loaded = track_loader.load_many(shown.iter().copied()).await?;
+        let tracks = shown
+            .iter()
+            .filter_map(|id| loaded.remove(id))
+            .map(Track::from)
+            .collect();
+
+        Ok(SmartPlaylistPreview {
+            tracks,
+            total_count,
+        })
+    }
+}
+
+/// Validation error listing every problem with a smart playlist's rules
+fn invalid_rules_error(errors: &[SmartRuleError]) -> async_graphql::Error {
+    let field_errors = serde_json::to_value(errors)
+        .ok()
+        .and_then(|value| async_graphql::Value::from_json(value).ok())
+        .unwrap_or_default();
+    let message = match errors {
+        [only] => format!("Invalid smart playlist rules: {}", only.message),
+        _ => format!("Invalid smart playlist rules ({} problems)", errors.len()),
+    };
+
+    ApiError::ValidationError(message)
+        .extend()
+        .extend_with(|_, e| e.set("fieldErrors", field_errors))
 }
@@ -11,11 +11,14 @@ use async_graphql::{Context, Object, Result, ID};
 use tracing::{debug, instrument, warn};
 use uuid::Uuid;
 
+use crate::error::ApiError;
 use crate::graphql::pagination::{clamp_limit, MAX_SEARCH_LIMIT};
 use crate::graphql::types::{
     ArtistTag, FullTextAlbumHit, FullTextArtistHit, FullTextSearchResult, FullTextTrackHit,
     MoodTag, ScoredTrack, SemanticSearchResult, SimilarArtist, SimilarTrack, SimilarityMethod,
 };
+use crate::models::user::Claims;
+use crate::repositories::UserRepository;
 use crate::services::lastfm::LastfmService;
 use crate::services::meilisearch::filter::{
     self, FilterValidationError, ALBUM_ATTRIBUTES, ARTIST_ATTRIBUTES, TRACK_ATTRIBUTES,
@@ -24,6 +27,25 @@ use crate::services::meilisearch::MeilisearchService;
 use crate::services::search::SearchService;
 use crate::services::similarity::SimilarityService;
 
+/// The similarity service, scoring with the requesting user's stored weights
+///
+/// Anonymous requests, users without stored weights, and failures loading
+/// them all use the server's weights.
+async fn user_similarity_service(ctx: &Context<'_>) -> Result<SimilarityService> {
+    let service = ctx.data::<SimilarityService>()?;
+    let weights = match (ctx.data_opt::<Claims>(), ctx.data_opt::<UserRepository>()) {
+        (Some(claims), Some(user_repo)) => user_repo
+            .get_similarity_weights(claims.sub)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(error = %e, user_id = %claims.sub, "Failed to load similarity weights");
+                None
+            }),
+        _ => None,
+    };
+    Ok(service.for_user_weights(weights.as_ref()))
+}
+
 /// Validate a filter string against allowed attributes, converting to GraphQL error if invalid
 fn validate_filter<'a>(
     filter: Option<&'a str>,
@@ -66,6 +88,9 @@ impl SearchQuery {
         )]
         limit: i32,
     ) -> Result<SemanticSearchResult> {
+        let search_service = ctx.data::<SearchService>()?;
+        search_service.ensure_semantic_available()?;
+
         let trimmed = query.trim();
         if trimmed.is_empty() {
             return Ok(SemanticSearchResult {
@@ -83,8 +108,6 @@ impl SearchQuery {
                 async_graphql::Error::new("Semantic search is not available: Ollama not configured")
             })?;
 
-        let search_service = ctx.data::<SearchService>()?;
-
         // Check if we have any embeddings to search
         if !search_service.has_embeddings().await? {
             return Ok(SemanticSearchResult {
@@ -120,7 +143,8 @@ impl SearchQuery {
 
     /// Find tracks similar to a given track.
     /// Uses combined similarity (semantic, acoustic, and categorical) to find
-    /// the most similar tracks in your library.
+    /// the most similar tracks in your library, blended with your stored
+    /// similarity weights if you have set any.
     #[instrument(skip(self, ctx))]
     async fn similar_tracks(
         &self,
@@ -136,10 +160,8 @@ impl SearchQuery {
             .map_err(|_| async_graphql::Error::new("Invalid track ID"))?;
         let limit = clamp_limit(limit, MAX_SEARCH_LIMIT) as i32;
 
-        let similarity_service = ctx.data::<SimilarityService>()?;
-        let similar = similarity_service
-            .find_similar_combined(uuid, limit)
-            .await?;
+        let similarity_service = user_similarity_service(ctx).await?;
+        let similar = similarity_service.find_precomputed(uuid, limit).await?;
 
         Ok(similar.into_iter().map(ScoredTrack::from).collect())
     }
@@ -147,7 +169,8 @@ impl SearchQuery {
     /// Find tracks similar to a given track using a specific similarity method.
     ///
     /// Available methods:
-    /// - `COMBINED`: Weighted blend (50% semantic, 30% acoustic, 20% categorical)
+    /// - `COMBINED`: Weighted blend (50% semantic, 30% acoustic, 20% categorical,
+    ///   unless you have stored your own weights)
     /// - `SEMANTIC`: AI embeddings similarity (requires tracks to have embeddings)
     /// - `ACOUSTIC`: Audio features (BPM, energy, loudness, valence, danceability)
     /// - `CATEGORICAL`: Genre and mood tag matching
@@ -171,8 +194,9 @@ impl SearchQuery {
 
         let similar = match method {
             SimilarityMethod::Combined => {
-                similarity_service
-                    .find_similar_combined(uuid, limit)
+                user_similarity_service(ctx)
+                    .await?
+                    .find_precomputed(uuid, limit)
                     .await?
             }
             SimilarityMethod::Semantic => {
@@ -2,12 +2,16 @@
 //!
 //! This module provides queries for user data:
 //! - me: Get the currently authenticated user
+//! - recentlyPlayed: The authenticated user's listening history
+//! - devicePreferences: Audio preferences in effect on one of the user's devices
 
 use async_graphql::{Context, Object, Result};
 use sqlx::PgPool;
 
-use crate::graphql::types::User;
+use crate::graphql::pagination::{offset_connection, CursorConnection};
+use crate::graphql::types::{DevicePreferencesType, PlayedTrack, User};
 use crate::models::user::Claims;
+use crate::repositories::{TrackRepository, UserRepository};
 
 /// User-related queries
 #[derive(Default)]
@@ -75,4 +79,66 @@ impl UserQuery {
 
         Ok(User::from(user))
     }
+
+    /// Get the authenticated user's recently played tracks, newest first
+    ///
+    /// By default, consecutive plays of the same track collapse into one
+    /// entry; pass `collapseRepeats: false` to list every play.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    async fn recently_played(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(desc = "Number of plays to return (default: 50, max: 100)")] first: Option<i32>,
+        #[graphql(desc = "Cursor of the last play from the previous page")] after: Option<String>,
+        #[graphql(
+            default = true,
+            desc = "Collapse consecutive plays of the same track into one"
+        )]
+        collapse_repeats: bool,
+    ) -> Result<CursorConnection<usize, PlayedTrack>> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+        let user_id = claims.sub;
+        let repo = ctx.data::<TrackRepository>()?;
+
+        offset_connection(after, first, |limit, offset| async move {
+            let plays = repo
+                .find_listening_history(user_id, collapse_repeats, limit, offset)
+                .await?;
+            Ok(plays.into_iter().map(PlayedTrack::from).collect())
+        })
+        .await
+    }
+
+    /// Get the audio preferences in effect on one of the user's devices
+    ///
+    /// Device overrides take precedence; everything else falls back to the
+    /// global preferences.
+    ///
+    /// # Errors
+    /// - Returns error if not authenticated
+    async fn device_preferences(
+        &self,
+        ctx: &Context<'_>,
+        device_id: String,
+    ) -> Result<DevicePreferencesType> {
+        let claims = ctx
+            .data_opt::<Claims>()
+            .ok_or_else(|| async_graphql::Error::new("authentication required"))?;
+        let user_repo = ctx.data::<UserRepository>()?;
+
+        let prefs = user_repo
+            .get_device_prefs(claims.sub, &device_id)
+            .await
+            .map_err(|e| {
+                tracing::error!(error = %e, user_id = %claims.sub, "Failed to fetch device preferences");
+                async_graphql::Error::new("Failed to fetch device preferences")
+            })?
+            .ok_or_else(|| async_graphql::Error::new("user not found"))?;
+
+        Ok(DevicePreferencesType::new(device_id, prefs))
+    }
 }
@@ -2,10 +2,10 @@
 //!
 //! This module provides the schema construction for the async-graphql API.
 
-use async_graphql::dataloader::DataLoader;
 use async_graphql::{EmptySubscription, Schema};
 use sqlx::PgPool;
 
+use crate::middleware::RequestId;
 use crate::repositories::{
     AlbumRepository, ArtistRepository, ChatRepository, PlaylistRepository,
     SystemSettingsRepository, TrackRepository, UserRepository,
@@ -13,6 +13,7 @@ use crate::repositories::{
 use crate::services::auth::AuthService;
 use crate::services::config::ConfigService;
 use crate::services::encryption::EncryptionService;
+use crate::services::idempotency::IdempotencyStore;
 use crate::services::lastfm::LastfmService;
 use crate::services::listenbrainz::ListenBrainzService;
 use crate::services::meilisearch::MeilisearchService;
@@ -21,11 +22,9 @@ use crate::services::search::SearchService;
 use crate::services::similarity::SimilarityService;
 
 use super::guards::GraphQLRateLimiter;
-use super::loaders::{
-    AlbumLoader, AlbumsByArtistLoader, ArtistLoader, TrackLoader, TracksByAlbumLoader,
-    TracksByArtistLoader,
-};
+use super::loaders::{create_loaders, LoaderConfig, PerRequestLoaders};
 use super::mutation::Mutation;
+use super::operation_log::{OperationLogConfig, OperationLogging};
 use super::query::Query;
 
 /// The Resonance GraphQL schema type
@@ -38,6 +37,7 @@ pub struct SchemaBuilder {
     encryption_service: Option<EncryptionService>,
     config_service: Option<ConfigService>,
     rate_limiter: Option<GraphQLRateLimiter>,
+    idempotency_store: Option<IdempotencyStore>,
     artist_repository: Option<ArtistRepository>,
     album_repository: Option<AlbumRepository>,
     track_repository: Option<TrackRepository>,
@@ -54,6 +54,8 @@ pub struct SchemaBuilder {
     lastfm_service: Option<LastfmService>,
     listenbrainz_service: Option<ListenBrainzService>,
     ollama_client: Option<resonance_ollama_client::OllamaClient>,
+    loader_config: LoaderConfig,
+    operation_log_config: OperationLogConfig,
 }
 
 impl SchemaBuilder {
@@ -65,6 +67,7 @@ impl SchemaBuilder {
             encryption_service: None,
             config_service: None,
             rate_limiter: None,
+            idempotency_store: None,
             artist_repository: None,
             album_repository: None,
             track_repository: None,
@@ -79,6 +82,8 @@ impl SchemaBuilder {
             lastfm_service: None,
             listenbrainz_service: None,
             ollama_client: None,
+            loader_config: LoaderConfig::default(),
+            operation_log_config: OperationLogConfig::default(),
         }
     }
 
@@ -122,6 +127,12 @@ impl SchemaBuilder {
         self
     }
 
+    /// Set the idempotency key store (defaults to an in-memory store)
+    pub fn idempotency_store(mut self, store: IdempotencyStore) -> Self {
+        self.idempotency_store = Some(store);
+        self
+    }
+
     /// Set the artist repository
     #[allow(dead_code)]
     pub fn artist_repository(mut self, repo: ArtistRepository) -> Self {
@@ -220,6 +231,23 @@ impl SchemaBuilder {
         self
     }
 
+    /// Set DataLoader batching and caching options
+    ///
+    /// The schema's loaders are shared across requests and never cache; with
+    /// `enable_cache` each request gets its own caching loaders instead.
+    pub fn loader_config(mut self, config: LoaderConfig) -> Self {
+        self.loader_config = config;
+        self
+    }
+
+    /// Set per-operation logging options
+    ///
+    /// Every operation is logged; this controls whether its variables are too.
+    pub fn operation_log_config(mut self, config: OperationLogConfig) -> Self {
+        self.operation_log_config = config;
+        self
+    }
+
     /// Build the schema with all configured services
     ///
     /// # Panics
@@ -256,21 +284,24 @@ impl SchemaBuilder {
             .playlist_service
             .unwrap_or_else(|| PlaylistService::new(pool.clone()));
 
-        // Create DataLoaders for batched fetching
-        // Note: DataLoaders already batch requests within a single query execution.
-        // Per-request caching is handled by async-graphql's request context.
-        // Single-entity loaders (return one entity by ID)
-        let artist_loader = DataLoader::new(ArtistLoader::new(pool.clone()), tokio::spawn);
-        let album_loader = DataLoader::new(AlbumLoader::new(pool.clone()), tokio::spawn);
-        let track_loader = DataLoader::new(TrackLoader::new(pool.clone()), tokio::spawn);
-
-        // Collection loaders (return Vec of related entities by parent ID)
-        let albums_by_artist_loader =
-            DataLoader::new(AlbumsByArtistLoader::new(pool.clone()), tokio::spawn);
-        let tracks_by_album_loader =
-            DataLoader::new(TracksByAlbumLoader::new(pool.clone()), tokio::spawn);
-        let tracks_by_artist_loader =
-            DataLoader::new(TracksByArtistLoader::new(pool.clone()), tokio::spawn);
+        // Fall back to in-memory idempotency keys (single instance only)
+        let idempotency_store = self
+            .idempotency_store
+            .unwrap_or_else(IdempotencyStore::in_memory);
+
+        // Create DataLoaders for batched fetching. These are shared by every
+        // request, so they never cache; per-request loaders can override them.
+        let loaders = create_loaders(
+            pool.clone(),
+            &LoaderConfig {
+                enable_cache: false,
+                ..self.loader_config
+            },
+        );
+        let per_request_loaders = self
+            .loader_config
+            .enable_cache
+            .then(|| PerRequestLoaders::new(pool.clone(), self.loader_config));
 
         let mut builder = Schema::build(Query::default(), Mutation::default(), EmptySubscription)
             // Query complexity and depth limits to prevent DoS attacks
@@ -285,13 +316,21 @@ impl SchemaBuilder {
             .data(user_repo)
             .data(chat_repo)
             .data(system_settings_repo)
-            .data(artist_loader)
-            .data(album_loader)
-            .data(track_loader)
-            .data(albums_by_artist_loader)
-            .data(tracks_by_album_loader)
-            .data(tracks_by_artist_loader)
-            .data(playlist_service);
+            .data(loaders.artist)
+            .data(loaders.album)
+            .data(loaders.track)
+            .data(loaders.feature_status)
+            .data(loaders.albums_by_artist)
+            .data(loaders.tracks_by_album)
+            .data(loaders.tracks_by_artist)
+            .data(playlist_service)
+            .data(idempotency_store)
+            .extension(OperationLogging::new(self.operation_log_config));
+
+        // Give each request its own caching loaders if enabled
+        if let Some(extension) = per_request_loaders {
+            builder = builder.extension(extension);
+        }
 
         // Add rate limiter if configured
         if let Some(rate_limiter) = self.rate_limiter {
@@ -368,6 +407,19 @@ pub fn build_schema_with_rate_limiting(
         .build()
 }
 
+/// Add the request id to the extensions of every error in a GraphQL response
+///
+/// Clients report the `requestId` extension so a failed operation can be
+/// found in the API logs.
+pub fn attach_request_id(response: &mut async_graphql::Response, request_id: &RequestId) {
+    for error in &mut response.errors {
+        error
+            .extensions
+            .get_or_insert_with(Default::default)
+            .set("requestId", request_id.as_str());
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -398,4 +450,20 @@ mod tests {
         assert!(builder.listenbrainz_service.is_none());
         assert!(builder.ollama_client.is_none());
     }
+
+    #[test]
+    fn test_attach_request_id_to_errors() {
+        let mut response = async_graphql::Response::from_errors(vec![
+            async_graphql::ServerError::new("first", None),
+            async_graphql::ServerError::new("second", None),
+        ]);
+        let request_id = RequestId::generate();
+
+        attach_request_id(&mut response, &request_id);
+
+        let json = serde_json::to_value(&response).unwrap();
+        for error in json["errors"].as_array().unwrap() {
+            assert_eq!(error["extensions"]["requestId"], request_id.as_str());
+        }
+    }
 }
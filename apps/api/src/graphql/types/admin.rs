@@ -1,14 +1,18 @@
 //! Admin GraphQL types for dashboard and user management
 //!
 //! This module defines the GraphQL types for admin-only operations including
-//! system statistics, user management, and session information.
+//! system statistics, user management, session information, and audio
+//! analysis progress.
 
-use async_graphql::SimpleObject;
+use async_graphql::{Enum, InputObject, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use super::user::UserRole;
-use crate::repositories::{AdminSessionRow, AdminUserRow, SystemStats as DbSystemStats};
+use crate::repositories::{
+    AdminSessionRow, AdminUserRow, AnalysisCountsRow, EmbeddingRegenerationRow, LibraryScanRow,
+    SessionFilter, SessionStatus, SystemStats as DbSystemStats,
+};
 
 /// System-wide statistics for the admin dashboard
 #[derive(Debug, Clone, SimpleObject)]
@@ -102,11 +106,15 @@ impl From<AdminUserRow> for AdminUserListItem {
     }
 }
 
-/// Session information for admin user detail view
+/// Session information for admin user detail and session audit views
 #[derive(Debug, Clone, SimpleObject)]
 pub struct AdminSession {
     /// Session identifier
     pub id: Uuid,
+    /// User the session belongs to
+    pub user_id: Uuid,
+    /// Email of the session's user
+    pub user_email: String,
     /// Device type (desktop, mobile, tablet, web, tv)
     pub device_type: Option<String>,
     /// Human-readable device name
@@ -119,6 +127,8 @@ pub struct AdminSession {
     pub is_active: bool,
     /// Last activity timestamp
     pub last_active_at: DateTime<Utc>,
+    /// When the session expires
+    pub expires_at: DateTime<Utc>,
     /// Session creation timestamp
     pub created_at: DateTime<Utc>,
 }
@@ -127,17 +137,67 @@ impl From<AdminSessionRow> for AdminSession {
     fn from(row: AdminSessionRow) -> Self {
         Self {
             id: row.id,
+            user_id: row.user_id,
+            user_email: row.user_email,
             device_type: row.device_type,
             device_name: row.device_name,
             ip_address: row.ip_address,
             user_agent: row.user_agent,
             is_active: row.is_active,
             last_active_at: row.last_active_at,
+            expires_at: row.expires_at,
             created_at: row.created_at,
         }
     }
 }
 
+/// Session state filter for the admin session list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum AdminSessionStatus {
+    /// Not revoked and not yet expired
+    Active,
+    /// Revoked or past its expiry
+    Expired,
+}
+
+/// Filters for the admin session list; omitted fields match every session
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct AdminSessionFilter {
+    /// Only sessions of this user
+    pub user_id: Option<Uuid>,
+    /// Only sessions from this exact client IP address
+    pub ip_address: Option<String>,
+    /// Only sessions of this device type (desktop, mobile, tablet, web, tv)
+    pub device_type: Option<String>,
+    /// Only active or only expired sessions
+    pub status: Option<AdminSessionStatus>,
+}
+
+impl From<AdminSessionFilter> for SessionFilter {
+    fn from(filter: AdminSessionFilter) -> Self {
+        Self {
+            user_id: filter.user_id,
+            ip_address: filter.ip_address,
+            device_type: filter.device_type,
+            status: filter.status.map(|status| match status {
+                AdminSessionStatus::Active => SessionStatus::Active,
+                AdminSessionStatus::Expired => SessionStatus::Expired,
+            }),
+        }
+    }
+}
+
+/// Paginated list of sessions across all users
+#[derive(Debug, Clone, SimpleObject)]
+pub struct AdminSessionList {
+    /// Sessions on this page, most recently active first
+    pub sessions: Vec<AdminSession>,
+    /// Total number of matching sessions (for pagination)
+    pub total_count: i64,
+    /// Whether there are more sessions after this page
+    pub has_next_page: bool,
+}
+
 /// Detailed user info with sessions for admin detail view
 #[derive(Debug, Clone, SimpleObject)]
 pub struct AdminUserDetail {
@@ -147,6 +207,196 @@ pub struct AdminUserDetail {
     pub sessions: Vec<AdminSession>,
 }
 
+/// Status of a manually requested library scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum LibraryScanStatus {
+    /// Waiting for the worker to pick it up
+    Pending,
+    /// The worker is scanning the library
+    Running,
+    /// The scan finished successfully
+    Completed,
+    /// The scan failed; see `error`
+    Failed,
+}
+
+impl LibraryScanStatus {
+    fn from_db(status: &str) -> Self {
+        match status {
+            "pending" => Self::Pending,
+            "running" => Self::Running,
+            "completed" => Self::Completed,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// A manually requested library scan
+#[derive(Debug, Clone, SimpleObject)]
+pub struct LibraryScan {
+    /// Scan request ID, used to poll for status
+    pub id: Uuid,
+    /// Admin who requested the scan (None if their account was deleted)
+    pub requested_by: Option<Uuid>,
+    /// Whether unchanged files are re-read as well
+    pub full_rescan: bool,
+    /// Current status
+    pub status: LibraryScanStatus,
+    /// Failure reason, if the scan failed
+    pub error: Option<String>,
+    /// When the scan was requested
+    pub requested_at: DateTime<Utc>,
+    /// When the worker started the scan
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the scan finished
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<LibraryScanRow> for LibraryScan {
+    fn from(row: LibraryScanRow) -> Self {
+        Self {
+            id: row.id,
+            requested_by: row.requested_by,
+            full_rescan: row.full_rescan,
+            status: LibraryScanStatus::from_db(&row.status),
+            error: row.error,
+            requested_at: row.created_at,
+            started_at: row.started_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// Status of a requested embedding regeneration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum EmbeddingRegenerationStatus {
+    /// Waiting for the worker to pick it up
+    Pending,
+    /// The worker is rebuilding embeddings
+    Running,
+    /// Every track was processed
+    Completed,
+    /// The regeneration failed; see `error`
+    Failed,
+}
+
+impl EmbeddingRegenerationStatus {
+    fn from_db(status: &str) -> Self {
+        match status {
+            "pending" => Self::Pending,
+            "running" => Self::Running,
+            "completed" => Self::Completed,
+            _ => Self::Failed,
+        }
+    }
+}
+
+/// A requested regeneration of every track's embeddings
+#[derive(Debug, Clone, SimpleObject)]
+pub struct EmbeddingRegeneration {
+    /// Request ID, used to poll for progress
+    pub id: Uuid,
+    /// Admin who requested it (None if their account was deleted)
+    pub requested_by: Option<Uuid>,
+    /// Current status
+    pub status: EmbeddingRegenerationStatus,
+    /// Failure reason, if the regeneration failed
+    pub error: Option<String>,
+    /// Tracks re-embedded so far (updated when the worker finishes or pauses)
+    pub embedded: i32,
+    /// Tracks whose embedding generation failed
+    pub skipped: i32,
+    /// Tracks still waiting for embeddings from the current model
+    pub remaining_tracks: i64,
+    /// When the regeneration was requested
+    pub requested_at: DateTime<Utc>,
+    /// When the worker started it
+    pub started_at: Option<DateTime<Utc>>,
+    /// When it finished
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<EmbeddingRegenerationRow> for EmbeddingRegeneration {
+    fn from(row: EmbeddingRegenerationRow) -> Self {
+        Self {
+            id: row.id,
+            requested_by: row.requested_by,
+            status: EmbeddingRegenerationStatus::from_db(&row.status),
+            error: row.error,
+            embedded: row.embedded,
+            skipped: row.skipped,
+            remaining_tracks: row.remaining_tracks,
+            requested_at: row.created_at,
+            started_at: row.started_at,
+            completed_at: row.completed_at,
+        }
+    }
+}
+
+/// Progress of audio feature extraction and embedding generation
+#[derive(Debug, Clone, SimpleObject)]
+pub struct AnalysisProgress {
+    /// Total number of tracks in the library
+    pub total_tracks: i64,
+    /// Tracks with audio features extracted
+    pub features_computed: i64,
+    /// Tracks with title and description embeddings
+    pub embeddings_computed: i64,
+    /// Tracks whose latest feature extraction or embedding generation failed
+    pub failed: i64,
+    /// Estimated seconds until the remaining tracks are analyzed
+    ///
+    /// Extrapolated from the average time of recent successful runs. Null
+    /// while there is work left but no run of that kind has finished yet.
+    pub estimated_seconds_remaining: Option<i64>,
+}
+
+impl AnalysisProgress {
+    /// Build progress from track counts and recent run durations (in ms)
+    pub fn new(
+        counts: AnalysisCountsRow,
+        recent_feature_ms: &[i32],
+        recent_embedding_ms: &[i32],
+    ) -> Self {
+        let features_remaining =
+            counts.total_tracks - counts.features_computed - counts.features_failed;
+        let embeddings_remaining =
+            counts.total_tracks - counts.embeddings_computed - counts.embeddings_failed;
+
+        let estimated_ms = estimate_remaining_ms(features_remaining, recent_feature_ms)
+            .zip(estimate_remaining_ms(
+                embeddings_remaining,
+                recent_embedding_ms,
+            ))
+            .map(|(features, embeddings)| features + embeddings);
+
+        Self {
+            total_tracks: counts.total_tracks,
+            features_computed: counts.features_computed,
+            embeddings_computed: counts.embeddings_computed,
+            failed: counts.failed_tracks,
+            estimated_seconds_remaining: estimated_ms.map(|ms| (ms / 1000.0).ceil() as i64),
+        }
+    }
+}
+
+/// Estimate milliseconds to process `remaining` tracks at the average of
+/// `recent_ms`
+///
+/// Nothing remaining takes no time; otherwise there is no estimate until at
+/// least one run has been timed.
+fn estimate_remaining_ms(remaining: i64, recent_ms: &[i32]) -> Option<f64> {
+    if remaining <= 0 {
+        return Some(0.0);
+    }
+    if recent_ms.is_empty() {
+        return None;
+    }
+
+    let average = recent_ms.iter().map(|&ms| f64::from(ms)).sum::<f64>() / recent_ms.len() as f64;
+    Some(average * remaining as f64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +419,55 @@ mod tests {
         assert_eq!(stats.total_duration_formatted, "100 hours");
         assert_eq!(stats.total_file_size_formatted, "50.0 GB");
     }
+
+    fn analysis_counts(total: i64, features: i64, embeddings: i64) -> AnalysisCountsRow {
+        AnalysisCountsRow {
+            total_tracks: total,
+            features_computed: features,
+            embeddings_computed: embeddings,
+            features_failed: 0,
+            embeddings_failed: 0,
+            failed_tracks: 0,
+        }
+    }
+
+    #[test]
+    fn test_analysis_eta_from_recent_timings() {
+        let counts = AnalysisCountsRow {
+            features_failed: 2,
+            failed_tracks: 2,
+            ..analysis_counts(100, 60, 40)
+        };
+        // 38 tracks left to extract at 1.5s, 60 left to embed at 0.25s
+        let progress = AnalysisProgress::new(counts, &[1000, 2000, 1500], &[200, 300]);
+
+        assert_eq!(progress.total_tracks, 100);
+        assert_eq!(progress.features_computed, 60);
+        assert_eq!(progress.embeddings_computed, 40);
+        assert_eq!(progress.failed, 2);
+        // 38 * 1.5s + 60 * 0.25s = 72s
+        assert_eq!(progress.estimated_seconds_remaining, Some(72));
+    }
+
+    #[test]
+    fn test_analysis_eta_rounds_up_partial_seconds() {
+        let progress = AnalysisProgress::new(analysis_counts(3, 3, 2), &[], &[400]);
+        assert_eq!(progress.estimated_seconds_remaining, Some(1));
+    }
+
+    #[test]
+    fn test_analysis_eta_before_any_processing() {
+        let empty = AnalysisProgress::new(analysis_counts(0, 0, 0), &[], &[]);
+        assert_eq!(empty.total_tracks, 0);
+        assert_eq!(empty.estimated_seconds_remaining, Some(0));
+
+        // Tracks waiting, but nothing timed yet
+        let unstarted = AnalysisProgress::new(analysis_counts(50, 0, 0), &[], &[]);
+        assert_eq!(unstarted.failed, 0);
+        assert_eq!(unstarted.estimated_seconds_remaining, None);
+
+        // Features done; embeddings not yet timed
+        let partial = AnalysisProgress::new(analysis_counts(50, 50, 0), &[900], &[]);
+        assert_eq!(partial.estimated_seconds_remaining, None);
+    }
 }
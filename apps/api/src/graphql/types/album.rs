@@ -2,12 +2,11 @@
 //!
 //! This module defines the GraphQL type for albums with relationship resolvers.
 
-use async_graphql::dataloader::DataLoader;
 use async_graphql::{Context, Object, Result, SimpleObject};
 use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use uuid::Uuid;
 
-use crate::graphql::loaders::{ArtistLoader, TracksByAlbumLoader};
+use crate::graphql::loaders::{ArtistLoader, BatchLoader, TracksByAlbumLoader};
 use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_NESTED_LIMIT};
 use crate::models::album::CoverArtColors as DbCoverArtColors;
 use crate::models::Album as DbAlbum;
@@ -29,6 +28,8 @@ pub struct CoverArtColors {
     pub vibrant: Option<String>,
     /// Most muted color
     pub muted: Option<String>,
+    /// Readable text color over the primary color
+    pub text: Option<String>,
 }
 
 impl From<DbCoverArtColors> for CoverArtColors {
@@ -39,6 +40,7 @@ impl From<DbCoverArtColors> for CoverArtColors {
             accent: colors.accent,
             vibrant: colors.vibrant,
             muted: colors.muted,
+            text: colors.text,
         }
     }
 }
@@ -138,7 +140,7 @@ impl Album {
         self.inner
             .cover_art_path
             .as_ref()
-            .map(|_| format!("/api/albums/{}/cover", self.inner.id))
+            .map(|_| format!("/art/{}", self.inner.id))
     }
 
     /// Extracted color palette for visualizer
@@ -146,6 +148,12 @@ impl Album {
         self.inner.cover_art_colors.clone().into()
     }
 
+    /// How much optional metadata (cover art, genres, release date, MBID)
+    /// is populated, from 0.0 to 1.0
+    async fn completeness_score(&self) -> f32 {
+        self.inner.completeness_score()
+    }
+
     /// Creation timestamp
     async fn created_at(&self) -> DateTime<Utc> {
         self.inner.created_at
@@ -160,7 +168,7 @@ impl Album {
 
     /// Artist who created this album (uses DataLoader for batched fetching)
     async fn artist(&self, ctx: &Context<'_>) -> Result<Option<Artist>> {
-        let loader = ctx.data::<DataLoader<ArtistLoader>>()?;
+        let loader = ctx.data::<BatchLoader<ArtistLoader>>()?;
         let artist = loader.load_one(self.inner.artist_id).await?;
         Ok(artist.map(Artist::from))
     }
@@ -175,7 +183,7 @@ impl Album {
         #[graphql(default = 50)] limit: i32,
         #[graphql(default = 0)] offset: i32,
     ) -> Result<Vec<Track>> {
-        let loader = ctx.data::<DataLoader<TracksByAlbumLoader>>()?;
+        let loader = ctx.data::<BatchLoader<TracksByAlbumLoader>>()?;
         let tracks = loader.load_one(self.inner.id).await?;
 
         // Apply pagination with limits
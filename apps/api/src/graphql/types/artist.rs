@@ -2,15 +2,15 @@
 //!
 //! This module defines the GraphQL type for artists with relationship resolvers.
 
-use async_graphql::dataloader::DataLoader;
-use async_graphql::{Context, Object, Result};
+use async_graphql::{Context, Object, Result, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::graphql::loaders::{AlbumsByArtistLoader, TracksByArtistLoader};
+use crate::graphql::loaders::{AlbumsByArtistLoader, BatchLoader, TracksByArtistLoader};
 use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_NESTED_LIMIT};
 use crate::models::Artist as DbArtist;
 use crate::repositories::AlbumRepository;
+use crate::services::lastfm::{LastfmService, LibrarySimilarArtist};
 
 use super::album::Album;
 use super::track::Track;
@@ -33,6 +33,24 @@ impl From<DbArtist> for Artist {
     }
 }
 
+/// A library artist similar to another artist, per Last.fm
+#[derive(SimpleObject)]
+pub struct SimilarLibraryArtist {
+    /// The similar artist from the library
+    pub artist: Artist,
+    /// Last.fm similarity score (0.0 - 1.0)
+    pub match_score: f64,
+}
+
+impl From<LibrarySimilarArtist> for SimilarLibraryArtist {
+    fn from(similar: LibrarySimilarArtist) -> Self {
+        Self {
+            artist: similar.artist.into(),
+            match_score: similar.match_score,
+        }
+    }
+}
+
 #[Object]
 impl Artist {
     /// Unique artist identifier
@@ -70,6 +88,12 @@ impl Artist {
         &self.inner.genres
     }
 
+    /// How much optional metadata (image, biography, genres, MBID) is
+    /// populated, from 0.0 to 1.0
+    async fn completeness_score(&self) -> f32 {
+        self.inner.completeness_score()
+    }
+
     /// Creation timestamp
     async fn created_at(&self) -> DateTime<Utc> {
         self.inner.created_at
@@ -92,7 +116,7 @@ impl Artist {
         #[graphql(default = 50)] limit: i32,
         #[graphql(default = 0)] offset: i32,
     ) -> Result<Vec<Album>> {
-        let loader = ctx.data::<DataLoader<AlbumsByArtistLoader>>()?;
+        let loader = ctx.data::<BatchLoader<AlbumsByArtistLoader>>()?;
         let albums = loader.load_one(self.inner.id).await?;
 
         // Apply pagination with limits
@@ -124,7 +148,7 @@ impl Artist {
         ctx: &Context<'_>,
         #[graphql(default = 10)] limit: i32,
     ) -> Result<Vec<Track>> {
-        let loader = ctx.data::<DataLoader<TracksByArtistLoader>>()?;
+        let loader = ctx.data::<BatchLoader<TracksByArtistLoader>>()?;
         let tracks = loader.load_one(self.inner.id).await?;
 
         // Apply pagination limit
@@ -137,4 +161,28 @@ impl Artist {
             .map(Track::from)
             .collect())
     }
+
+    /// Artists in the library that Last.fm considers similar to this one
+    ///
+    /// Ordered by Last.fm match score, highest first. Requires LASTFM_API_KEY
+    /// to be configured.
+    async fn similar_in_library(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 10)] limit: i32,
+    ) -> Result<Vec<SimilarLibraryArtist>> {
+        let lastfm_service = ctx.data::<LastfmService>().map_err(|_| {
+            async_graphql::Error::new("Similar artists not available: Last.fm not configured")
+        })?;
+
+        let limit = clamp_limit(limit, MAX_NESTED_LIMIT) as usize;
+        let similar = lastfm_service
+            .similar_artists_in_library(self.inner.id, limit)
+            .await?;
+
+        Ok(similar
+            .into_iter()
+            .map(SimilarLibraryArtist::from)
+            .collect())
+    }
 }
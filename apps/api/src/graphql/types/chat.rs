@@ -88,6 +88,11 @@ impl ChatConversation {
     async fn updated_at(&self) -> DateTime<Utc> {
         self.inner.updated_at
     }
+
+    /// When the conversation was archived (null if active)
+    async fn archived_at(&self) -> Option<DateTime<Utc>> {
+        self.inner.archived_at
+    }
 }
 
 // =============================================================================
@@ -1,8 +1,8 @@
 //! Library-related GraphQL types and enums
 //!
-//! This module defines shared enums for the music library.
+//! This module defines shared enums and summaries for the music library.
 
-use async_graphql::Enum;
+use async_graphql::{Enum, SimpleObject};
 
 use crate::models::album::AlbumType as DbAlbumType;
 use crate::models::playlist::PlaylistType as DbPlaylistType;
@@ -103,3 +103,23 @@ impl From<DbPlaylistType> for PlaylistType {
         }
     }
 }
+
+/// Average metadata completeness across the library
+///
+/// Each average is the mean `completenessScore` of that kind of entity, or
+/// null when the library has none.
+#[derive(Debug, Clone, Copy, SimpleObject)]
+pub struct LibraryCompleteness {
+    /// Mean artist completeness (0.0 - 1.0)
+    pub artists: Option<f32>,
+    /// Mean album completeness (0.0 - 1.0)
+    pub albums: Option<f32>,
+    /// Mean track completeness (0.0 - 1.0)
+    pub tracks: Option<f32>,
+    /// Number of artists scored
+    pub artist_count: i64,
+    /// Number of albums scored
+    pub album_count: i64,
+    /// Number of tracks scored
+    pub track_count: i64,
+}
@@ -17,24 +17,35 @@ mod system_settings;
 mod track;
 mod user;
 
-pub use admin::{AdminSession, AdminUserDetail, AdminUserList, AdminUserListItem, SystemStats};
+pub use admin::{
+    AdminSession, AdminSessionFilter, AdminSessionList, AdminSessionStatus, AdminUserDetail,
+    AdminUserList, AdminUserListItem, AnalysisProgress, EmbeddingRegeneration,
+    EmbeddingRegenerationStatus, LibraryScan, LibraryScanStatus, SystemStats,
+};
 pub use album::{Album, CoverArtColors};
 pub use artist::Artist;
 pub use chat::{ChatConversation, ChatConversationWithMessages, ChatMessage, ChatRole};
-pub use library::{AlbumType, AudioFormat, PlaylistType};
+pub use library::{AlbumType, AudioFormat, LibraryCompleteness, PlaylistType};
 pub use playlist::{
-    Playlist, PlaylistTrackEntry, SmartPlaylistMatchMode, SmartPlaylistRule, SmartPlaylistRules,
-    SortOrder,
+    CollaboratorPermission, Playlist, PlaylistCollaborator, PlaylistFileFormat,
+    PlaylistImportResult, PlaylistTrackEntry, SmartPlaylistMatchMode, SmartPlaylistPreview,
+    SmartPlaylistRule, SmartPlaylistRules, SortOrder, UnmatchedPlaylistEntry,
 };
 pub use search::{
     ArtistTag, FullTextAlbumHit, FullTextArtistHit, FullTextSearchResult, FullTextTrackHit,
-    MoodTag, ScoredTrack, SemanticSearchResult, SimilarArtist, SimilarTrack, SimilarityMethod,
-    SimilarityType,
+    MoodTag, ScoreBreakdown, ScoredTrack, SemanticSearchResult, SimilarArtist, SimilarTrack,
+    SimilarityMethod, SimilarityType,
 };
 pub use system_settings::{
     ConfigSource, ConnectionTestResult, CreateAdminInput, RuntimeConfigOverview,
     RuntimeConfigStatus, ServiceType, SetupStatus, SystemSettingInfo, UpdateSystemSettingInput,
     UserLibraryPath,
 };
-pub use track::{AudioFeatures, Track};
-pub use user::{AuthPayload, RefreshPayload, User, UserPreferencesType, UserRole};
+pub use track::{
+    AudioFeatureFilter, AudioFeatures, DuplicateGroup, FeatureRange, FeatureStatus, PlayedTrack,
+    SkipContext, Track,
+};
+pub use user::{
+    AuthPayload, DevicePreferencesType, RefreshPayload, SimilarityWeightsType, User,
+    UserPreferencesType, UserRole,
+};
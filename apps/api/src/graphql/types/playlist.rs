@@ -2,15 +2,17 @@
 //!
 //! This module defines the GraphQL type for playlists with relationship resolvers.
 
-use async_graphql::dataloader::DataLoader;
-use async_graphql::{Context, Object, Result, SimpleObject};
+use async_graphql::{Context, Object, Result, SimpleObject, ID};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::graphql::loaders::TrackLoader;
+use crate::graphql::loaders::{BatchLoader, TrackLoader};
 use crate::graphql::pagination::{clamp_limit, clamp_offset, MAX_PLAYLIST_TRACKS};
 use crate::models::playlist::{
+    CollaboratorPermission as DbCollaboratorPermission, ExportFormat,
+    PlaylistCollaborator as DbPlaylistCollaborator, PlaylistImport,
     SmartPlaylistRule as DbSmartPlaylistRule, SmartPlaylistRules as DbSmartPlaylistRules,
+    UnmatchedEntry,
 };
 use crate::models::Playlist as DbPlaylist;
 use crate::models::PlaylistTrack as DbPlaylistTrack;
@@ -215,7 +217,7 @@ impl Playlist {
         let offset = clamp_offset(offset);
 
         let playlist_repo = ctx.data::<PlaylistRepository>()?;
-        let track_loader = ctx.data::<DataLoader<TrackLoader>>()?;
+        let track_loader = ctx.data::<BatchLoader<TrackLoader>>()?;
 
         let playlist_tracks = playlist_repo
             .get_tracks(self.inner.id, limit, offset)
@@ -245,6 +247,13 @@ impl Playlist {
 
         Ok(entries)
     }
+
+    /// Users this playlist is shared with, and what each may do
+    async fn collaborators(&self, ctx: &Context<'_>) -> Result<Vec<PlaylistCollaborator>> {
+        let playlist_repo = ctx.data::<PlaylistRepository>()?;
+        let collaborators = playlist_repo.list_collaborators(self.inner.id).await?;
+        Ok(collaborators.into_iter().map(Into::into).collect())
+    }
 }
 
 /// A track entry in a playlist with metadata
@@ -287,3 +296,135 @@ pub struct PlaylistStats {
     /// Formatted duration
     pub formatted_duration: String,
 }
+
+/// Tracks a set of smart playlist rules would match, without saving them
+#[derive(SimpleObject)]
+pub struct SmartPlaylistPreview {
+    /// Matching tracks, in the order the playlist would hold them
+    pub tracks: Vec<Track>,
+    /// Number of tracks the rules match, which may exceed `tracks`
+    pub total_count: i32,
+}
+
+// =============================================================================
+// Collaborator GraphQL Types
+// =============================================================================
+
+/// What a playlist collaborator is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum CollaboratorPermission {
+    /// Can read the playlist
+    Viewer,
+    /// Can also add, remove and reorder tracks
+    Editor,
+    /// Can also rename or delete the playlist and manage collaborators
+    Admin,
+}
+
+impl From<DbCollaboratorPermission> for CollaboratorPermission {
+    fn from(permission: DbCollaboratorPermission) -> Self {
+        match permission {
+            DbCollaboratorPermission::Viewer => Self::Viewer,
+            DbCollaboratorPermission::Editor => Self::Editor,
+            DbCollaboratorPermission::Admin => Self::Admin,
+        }
+    }
+}
+
+impl From<CollaboratorPermission> for DbCollaboratorPermission {
+    fn from(permission: CollaboratorPermission) -> Self {
+        match permission {
+            CollaboratorPermission::Viewer => Self::Viewer,
+            CollaboratorPermission::Editor => Self::Editor,
+            CollaboratorPermission::Admin => Self::Admin,
+        }
+    }
+}
+
+/// A user with access to another user's playlist
+#[derive(Debug, Clone, SimpleObject)]
+pub struct PlaylistCollaborator {
+    /// Playlist being collaborated on
+    pub playlist_id: ID,
+    /// The collaborating user
+    pub user_id: ID,
+    /// What the collaborator is allowed to do
+    pub permission: CollaboratorPermission,
+    /// When the collaborator was added
+    pub added_at: DateTime<Utc>,
+}
+
+impl From<DbPlaylistCollaborator> for PlaylistCollaborator {
+    fn from(collaborator: DbPlaylistCollaborator) -> Self {
+        Self {
+            playlist_id: ID(collaborator.playlist_id.to_string()),
+            user_id: ID(collaborator.user_id.to_string()),
+            permission: collaborator.permission.into(),
+            added_at: collaborator.added_at,
+        }
+    }
+}
+
+// =============================================================================
+// Import/Export GraphQL Types
+// =============================================================================
+
+/// File format for playlist import/export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, async_graphql::Enum)]
+pub enum PlaylistFileFormat {
+    /// Extended M3U with file paths
+    M3u,
+    /// Resonance JSON export with full metadata
+    Json,
+}
+
+impl From<PlaylistFileFormat> for ExportFormat {
+    fn from(format: PlaylistFileFormat) -> Self {
+        match format {
+            PlaylistFileFormat::M3u => Self::M3u,
+            PlaylistFileFormat::Json => Self::Json,
+        }
+    }
+}
+
+/// An imported playlist entry that matched no library track
+#[derive(Debug, Clone, SimpleObject)]
+pub struct UnmatchedPlaylistEntry {
+    /// Position of the entry in the imported file (0-based)
+    pub position: i32,
+    /// File path given for the entry
+    pub file_path: Option<String>,
+    /// Artist name given for the entry
+    pub artist: Option<String>,
+    /// Track title given for the entry
+    pub title: Option<String>,
+}
+
+impl From<UnmatchedEntry> for UnmatchedPlaylistEntry {
+    fn from(entry: UnmatchedEntry) -> Self {
+        Self {
+            position: i32::try_from(entry.position).unwrap_or(i32::MAX),
+            file_path: entry.file_path,
+            artist: entry.artist,
+            title: entry.title,
+        }
+    }
+}
+
+/// Result of importing a playlist
+#[derive(SimpleObject)]
+pub struct PlaylistImportResult {
+    /// The created playlist with all matched tracks
+    pub playlist: Playlist,
+    /// Entries that could not be matched to a library track
+    pub unmatched: Vec<UnmatchedPlaylistEntry>,
+}
+
+impl From<PlaylistImport> for PlaylistImportResult {
+    fn from(import: PlaylistImport) -> Self {
+        Self {
+            playlist: Playlist::from(import.playlist),
+            unmatched: import.unmatched.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -12,7 +12,8 @@ use crate::services::meilisearch::{
 };
 use crate::services::search::{MoodTag as ServiceMoodTag, ScoredTrack as ServiceScoredTrack};
 use crate::services::similarity::{
-    SimilarTrack as ServiceSimilarTrack, SimilarityType as ServiceSimilarityType,
+    ScoreBreakdown as ServiceScoreBreakdown, SimilarTrack as ServiceSimilarTrack,
+    SimilarityType as ServiceSimilarityType,
 };
 
 use super::{Album, Artist, Track};
@@ -31,6 +32,48 @@ pub struct ScoredTrack {
     pub album_title: Option<String>,
     /// Relevance/similarity score (0.0 - 1.0)
     pub score: f64,
+    /// Per-dimension contributions (only for combined similarity results)
+    pub score_breakdown: Option<ScoreBreakdown>,
+}
+
+/// Per-dimension scores and weights behind a combined similarity score
+///
+/// The combined score is the sum of each dimension score times its weight.
+#[derive(Debug, Clone, PartialEq, SimpleObject)]
+pub struct ScoreBreakdown {
+    /// Semantic (embedding) similarity score
+    pub semantic: f64,
+    /// Acoustic (audio feature) similarity score
+    pub acoustic: f64,
+    /// Categorical (genre/mood/tags) similarity score
+    pub categorical: f64,
+    /// Weight applied to the semantic score
+    pub weight_semantic: f64,
+    /// Weight applied to the acoustic score
+    pub weight_acoustic: f64,
+    /// Weight applied to the categorical score
+    pub weight_categorical: f64,
+    /// Dimensions that contributed to the score; a dimension whose lookup
+    /// failed is left out and its weight is zero
+    pub contributing_dimensions: Vec<SimilarityType>,
+}
+
+impl From<ServiceScoreBreakdown> for ScoreBreakdown {
+    fn from(b: ServiceScoreBreakdown) -> Self {
+        Self {
+            contributing_dimensions: b
+                .contributing_dimensions()
+                .into_iter()
+                .map(SimilarityType::from)
+                .collect(),
+            semantic: b.semantic,
+            acoustic: b.acoustic,
+            categorical: b.categorical,
+            weight_semantic: b.weight_semantic,
+            weight_acoustic: b.weight_acoustic,
+            weight_categorical: b.weight_categorical,
+        }
+    }
 }
 
 #[ComplexObject]
@@ -58,6 +101,7 @@ impl From<ServiceScoredTrack> for ScoredTrack {
             artist_name: st.artist_name,
             album_title: st.album_title,
             score,
+            score_breakdown: None,
         }
     }
 }
@@ -76,6 +120,7 @@ impl From<ServiceSimilarTrack> for ScoredTrack {
             artist_name: st.artist_name,
             album_title: st.album_title,
             score,
+            score_breakdown: st.score_breakdown.map(ScoreBreakdown::from),
         }
     }
 }
@@ -210,6 +255,8 @@ pub struct SimilarTrack {
     pub score: f64,
     /// The type of similarity used for this match
     pub similarity_type: SimilarityType,
+    /// Per-dimension contributions (only for combined similarity results)
+    pub score_breakdown: Option<ScoreBreakdown>,
 }
 
 #[ComplexObject]
@@ -238,6 +285,7 @@ impl From<ServiceSimilarTrack> for SimilarTrack {
             album_title: st.album_title,
             score,
             similarity_type: st.similarity_type.into(),
+            score_breakdown: st.score_breakdown.map(ScoreBreakdown::from),
         }
     }
 }
@@ -275,6 +323,7 @@ mod tests {
             album_title: Some("Album".to_string()),
             score: 0.72,
             similarity_type: SimilarityType::Combined,
+            score_breakdown: None,
         };
 
         let scored: ScoredTrack = similar_track.into();
@@ -324,6 +373,7 @@ mod tests {
             album_title: Some("Test Album".to_string()),
             score: 0.85,
             similarity_type: ServiceSimilarityType::Acoustic,
+            score_breakdown: None,
         };
 
         let graphql_track: SimilarTrack = service_track.into();
@@ -352,6 +402,7 @@ mod tests {
             album_title: None,
             score: 0.0,
             similarity_type: ServiceSimilarityType::Semantic,
+            score_breakdown: None,
         };
 
         let graphql_track: SimilarTrack = service_track.into();
@@ -369,6 +420,7 @@ mod tests {
             album_title: None,
             score: f64::NAN,
             similarity_type: ServiceSimilarityType::Semantic,
+            score_breakdown: None,
         };
 
         let graphql_track: SimilarTrack = service_track.into();
@@ -384,6 +436,7 @@ mod tests {
             album_title: None,
             score: f64::INFINITY,
             similarity_type: ServiceSimilarityType::Semantic,
+            score_breakdown: None,
         };
 
         let graphql_track: SimilarTrack = service_track.into();
@@ -400,6 +453,7 @@ mod tests {
             album_title: None,
             score: -0.5,
             similarity_type: ServiceSimilarityType::Semantic,
+            score_breakdown: None,
         };
         let graphql_track: SimilarTrack = service_track.into();
         assert!((graphql_track.score - 0.0).abs() < f64::EPSILON);
@@ -412,6 +466,7 @@ mod tests {
             album_title: None,
             score: 1.5,
             similarity_type: ServiceSimilarityType::Semantic,
+            score_breakdown: None,
         };
         let graphql_track: SimilarTrack = service_track.into();
         assert!((graphql_track.score - 1.0).abs() < f64::EPSILON);
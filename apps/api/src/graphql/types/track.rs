@@ -2,14 +2,18 @@
 //!
 //! This module defines the GraphQL type for tracks with relationship resolvers.
 
-use async_graphql::dataloader::DataLoader;
-use async_graphql::{Context, Object, Result, SimpleObject};
+use async_graphql::{Context, Enum, InputObject, Object, Result, SimpleObject};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::graphql::loaders::{AlbumLoader, ArtistLoader};
-use crate::models::track::AudioFeatures as DbAudioFeatures;
+use crate::graphql::loaders::{
+    AlbumLoader, ArtistLoader, BatchLoader, FeatureStatusLoader, TrackFeatureState,
+};
+use crate::models::track::{AudioFeatures as DbAudioFeatures, SkipContext as DbSkipContext};
 use crate::models::Track as DbTrack;
+use crate::repositories::{
+    AudioFeatureBound, AudioFeatureField, DuplicateTracks, RecentlyPlayedRow,
+};
 
 use super::album::Album;
 use super::artist::Artist;
@@ -26,6 +30,10 @@ pub struct AudioFeatures {
     pub mode: Option<String>,
     /// Loudness in dB
     pub loudness: Option<f64>,
+    /// Integrated loudness (EBU R128) in LUFS, used for volume normalization
+    pub lufs_integrated: Option<f64>,
+    /// Loudness range (EBU R128) in LU
+    pub lufs_range: Option<f64>,
     /// Energy level (0.0 - 1.0)
     pub energy: Option<f64>,
     /// Danceability (0.0 - 1.0)
@@ -38,6 +46,10 @@ pub struct AudioFeatures {
     pub instrumentalness: Option<f64>,
     /// Speechiness (0.0 - 1.0)
     pub speechiness: Option<f64>,
+    /// Peak-to-RMS amplitude ratio; higher values mean a less compressed master
+    pub crest_factor: Option<f64>,
+    /// Spread between loud and quiet passages in dB
+    pub short_term_dynamic_range: Option<f64>,
 }
 
 impl From<DbAudioFeatures> for AudioFeatures {
@@ -47,16 +59,144 @@ impl From<DbAudioFeatures> for AudioFeatures {
             key: features.key,
             mode: features.mode,
             loudness: features.loudness,
+            lufs_integrated: features.lufs_integrated,
+            lufs_range: features.lufs_range,
             energy: features.energy,
             danceability: features.danceability,
             valence: features.valence,
             acousticness: features.acousticness,
             instrumentalness: features.instrumentalness,
             speechiness: features.speechiness,
+            crest_factor: features.crest_factor,
+            short_term_dynamic_range: features.short_term_dynamic_range,
         }
     }
 }
 
+/// How far audio analysis and embedding generation have got for a track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum FeatureStatus {
+    /// Neither audio features nor embeddings have been generated yet
+    Pending,
+    /// Audio features extracted, embeddings not yet generated
+    FeaturesOnly,
+    /// Embeddings generated, audio features not yet extracted
+    EmbeddedOnly,
+    /// Both audio features and embeddings are available
+    Complete,
+    /// The most recent extraction attempt failed
+    Failed,
+}
+
+impl From<&TrackFeatureState> for FeatureStatus {
+    fn from(state: &TrackFeatureState) -> Self {
+        if state.failed {
+            return Self::Failed;
+        }
+        match (state.has_features, state.has_embedding) {
+            (false, false) => Self::Pending,
+            (true, false) => Self::FeaturesOnly,
+            (false, true) => Self::EmbeddedOnly,
+            (true, true) => Self::Complete,
+        }
+    }
+}
+
+/// Where a skipped track was playing from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Enum)]
+pub enum SkipContext {
+    /// A recommendation, e.g. similar tracks or a mood suggestion
+    Recommendation,
+    /// The generated discover playlist
+    DiscoverPlaylist,
+    /// Tracks queued automatically when the queue ran out
+    Autoplay,
+    /// Anywhere else
+    Other,
+}
+
+impl From<SkipContext> for DbSkipContext {
+    fn from(context: SkipContext) -> Self {
+        match context {
+            SkipContext::Recommendation => Self::Recommendation,
+            SkipContext::DiscoverPlaylist => Self::DiscoverPlaylist,
+            SkipContext::Autoplay => Self::Autoplay,
+            SkipContext::Other => Self::Other,
+        }
+    }
+}
+
+/// Inclusive range for a numeric audio feature; either end may be omitted
+#[derive(Debug, Clone, Copy, Default, InputObject)]
+pub struct FeatureRange {
+    /// Lower bound (inclusive)
+    pub min: Option<f64>,
+    /// Upper bound (inclusive)
+    pub max: Option<f64>,
+}
+
+/// Filter tracks by ranges of their audio features
+///
+/// Unset ranges are ignored; a track must satisfy every range that is set.
+#[derive(Debug, Clone, Default, InputObject)]
+pub struct AudioFeatureFilter {
+    /// Energy level (0.0 - 1.0)
+    pub energy: Option<FeatureRange>,
+    /// Danceability (0.0 - 1.0)
+    pub danceability: Option<FeatureRange>,
+    /// Valence/happiness (0.0 - 1.0)
+    pub valence: Option<FeatureRange>,
+    /// Tempo in beats per minute
+    pub tempo: Option<FeatureRange>,
+    /// Acousticness (0.0 - 1.0)
+    pub acousticness: Option<FeatureRange>,
+    /// Instrumentalness (0.0 - 1.0)
+    pub instrumentalness: Option<FeatureRange>,
+    /// Speechiness (0.0 - 1.0)
+    pub speechiness: Option<FeatureRange>,
+    /// Loudness in dB
+    pub loudness: Option<FeatureRange>,
+}
+
+impl AudioFeatureFilter {
+    /// Convert to repository bounds, rejecting ranges where min > max
+    pub fn to_bounds(&self) -> Result<Vec<AudioFeatureBound>> {
+        let ranges = [
+            (AudioFeatureField::Energy, self.energy),
+            (AudioFeatureField::Danceability, self.danceability),
+            (AudioFeatureField::Valence, self.valence),
+            (AudioFeatureField::Tempo, self.tempo),
+            (AudioFeatureField::Acousticness, self.acousticness),
+            (AudioFeatureField::Instrumentalness, self.instrumentalness),
+            (AudioFeatureField::Speechiness, self.speechiness),
+            (AudioFeatureField::Loudness, self.loudness),
+        ];
+
+        let mut bounds = Vec::new();
+        for (field, range) in ranges {
+            let Some(range) = range else { continue };
+            if let (Some(min), Some(max)) = (range.min, range.max) {
+                if min > max {
+                    return Err(async_graphql::Error::new(format!(
+                        "{} range is empty: min ({}) is greater than max ({})",
+                        field.json_key(),
+                        min,
+                        max
+                    )));
+                }
+            }
+            if range.min.is_some() || range.max.is_some() {
+                bounds.push(AudioFeatureBound {
+                    field,
+                    min: range.min,
+                    max: range.max,
+                });
+            }
+        }
+        Ok(bounds)
+    }
+}
+
 /// Track information exposed via GraphQL
 pub struct Track {
     inner: DbTrack,
@@ -75,6 +215,42 @@ impl From<DbTrack> for Track {
     }
 }
 
+/// A track from the user's listening history
+#[derive(SimpleObject)]
+pub struct PlayedTrack {
+    /// The track that was played
+    pub track: Track,
+    /// When playback started
+    pub played_at: DateTime<Utc>,
+}
+
+impl From<RecentlyPlayedRow> for PlayedTrack {
+    fn from(row: RecentlyPlayedRow) -> Self {
+        Self {
+            track: row.track.into(),
+            played_at: row.played_at,
+        }
+    }
+}
+
+/// Tracks the library scan flagged as likely copies of the same recording
+#[derive(SimpleObject)]
+pub struct DuplicateGroup {
+    /// The copy kept as canonical (lossless or highest bit rate)
+    pub canonical: Track,
+    /// Other copies, which point at the canonical track
+    pub duplicates: Vec<Track>,
+}
+
+impl From<DuplicateTracks> for DuplicateGroup {
+    fn from(group: DuplicateTracks) -> Self {
+        Self {
+            canonical: group.canonical.into(),
+            duplicates: group.duplicates.into_iter().map(Track::from).collect(),
+        }
+    }
+}
+
 #[Object]
 impl Track {
     /// Unique track identifier
@@ -177,6 +353,42 @@ impl Track {
         self.inner.audio_features.clone().into()
     }
 
+    /// Where audible content starts, in milliseconds (null until analyzed)
+    async fn fade_in_ms(&self) -> Option<i32> {
+        self.inner.audio_features.fade_in_ms
+    }
+
+    /// Where audible content ends and a crossfade can begin, in
+    /// milliseconds (null until analyzed)
+    async fn fade_out_start_ms(&self) -> Option<i32> {
+        self.inner.audio_features.fade_out_start_ms
+    }
+
+    /// Whether audio features and embeddings have been generated
+    async fn feature_status(&self, ctx: &Context<'_>) -> Result<FeatureStatus> {
+        let loader = ctx.data::<BatchLoader<FeatureStatusLoader>>()?;
+        let state = loader.load_one(self.inner.id).await?;
+        Ok(state
+            .as_ref()
+            .map_or(FeatureStatus::Pending, FeatureStatus::from))
+    }
+
+    /// How much optional metadata (audio features, embedding, genres, album,
+    /// MBID) is populated, from 0.0 to 1.0
+    async fn completeness_score(&self, ctx: &Context<'_>) -> Result<f32> {
+        let loader = ctx.data::<BatchLoader<FeatureStatusLoader>>()?;
+        let state = loader.load_one(self.inner.id).await?;
+        let has_embedding = state.is_some_and(|s| s.has_embedding);
+        Ok(self.inner.completeness_score(has_embedding))
+    }
+
+    /// Error from the most recent extraction attempt, when it failed
+    async fn failure_reason(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        let loader = ctx.data::<BatchLoader<FeatureStatusLoader>>()?;
+        let state = loader.load_one(self.inner.id).await?;
+        Ok(state.and_then(|s| s.failure_reason))
+    }
+
     /// AI-detected mood tags
     async fn ai_mood(&self) -> &[String] {
         &self.inner.ai_mood
@@ -227,7 +439,7 @@ impl Track {
     /// Album this track belongs to
     async fn album(&self, ctx: &Context<'_>) -> Result<Option<Album>> {
         if let Some(album_id) = self.inner.album_id {
-            let loader = ctx.data::<DataLoader<AlbumLoader>>()?;
+            let loader = ctx.data::<BatchLoader<AlbumLoader>>()?;
             let album = loader.load_one(album_id).await?;
             Ok(album.map(Album::from))
         } else {
@@ -237,7 +449,7 @@ impl Track {
 
     /// Artist who created this track
     async fn artist(&self, ctx: &Context<'_>) -> Result<Option<Artist>> {
-        let loader = ctx.data::<DataLoader<ArtistLoader>>()?;
+        let loader = ctx.data::<BatchLoader<ArtistLoader>>()?;
         let artist = loader.load_one(self.inner.artist_id).await?;
         Ok(artist.map(Artist::from))
     }
@@ -7,7 +7,8 @@ use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::models::user::{
-    AuthTokens, User as DbUser, UserPreferences as DbUserPreferences, UserRole as DbUserRole,
+    AuthTokens, EffectiveDevicePrefs, SimilarityWeights, User as DbUser,
+    UserPreferences as DbUserPreferences, UserRole as DbUserRole,
 };
 
 /// User role enum for GraphQL
@@ -52,6 +53,29 @@ pub struct UserPreferencesType {
     pub discord_rpc: bool,
     /// Enable ListenBrainz scrobbling
     pub listenbrainz_scrobble: bool,
+    /// Preferred blend of similarity dimensions; null uses the server's weights
+    pub similarity_weights: Option<SimilarityWeightsType>,
+}
+
+/// Relative weights of the similarity dimensions behind recommendations
+#[derive(Debug, Clone, SimpleObject)]
+pub struct SimilarityWeightsType {
+    /// Weight for semantic (embedding) similarity
+    pub semantic: f64,
+    /// Weight for acoustic (audio feature) similarity
+    pub acoustic: f64,
+    /// Weight for categorical (genre/mood/tag) similarity
+    pub categorical: f64,
+}
+
+impl From<SimilarityWeights> for SimilarityWeightsType {
+    fn from(weights: SimilarityWeights) -> Self {
+        Self {
+            semantic: weights.semantic,
+            acoustic: weights.acoustic,
+            categorical: weights.categorical,
+        }
+    }
 }
 
 impl From<DbUserPreferences> for UserPreferencesType {
@@ -66,6 +90,40 @@ impl From<DbUserPreferences> for UserPreferencesType {
             private_session: prefs.private_session,
             discord_rpc: prefs.discord_rpc,
             listenbrainz_scrobble: prefs.listenbrainz_scrobble,
+            similarity_weights: prefs.similarity_weights.map(SimilarityWeightsType::from),
+        }
+    }
+}
+
+/// Audio preferences in effect for a device
+///
+/// Fields without a device override come from the global preferences.
+#[derive(Debug, Clone, SimpleObject)]
+pub struct DevicePreferencesType {
+    /// Device the preferences apply to
+    pub device_id: String,
+    /// Global audio quality: "low", "medium", "high", "lossless"
+    pub quality: String,
+    /// Preferred transcode format; null lets the server choose from `quality`
+    pub transcode_format: Option<String>,
+    /// Maximum bitrate in kbps on cellular connections; null means no cap
+    pub cellular_max_bitrate_kbps: Option<u32>,
+    /// Normalize volume across tracks
+    pub normalize_volume: bool,
+    /// Whether the device has any override stored
+    pub has_override: bool,
+}
+
+impl DevicePreferencesType {
+    /// Build from the effective preferences for a device
+    pub fn new(device_id: String, prefs: EffectiveDevicePrefs) -> Self {
+        Self {
+            device_id,
+            quality: prefs.quality,
+            transcode_format: prefs.transcode_format,
+            cellular_max_bitrate_kbps: prefs.cellular_max_bitrate_kbps,
+            normalize_volume: prefs.normalize_volume,
+            has_override: prefs.has_override,
         }
     }
 }
@@ -7,10 +7,13 @@ pub mod config;
 pub mod error;
 pub mod graphql;
 pub mod middleware;
+pub mod migrations;
 pub mod models;
 pub mod repositories;
 pub mod routes;
 pub mod services;
+pub mod shutdown;
+pub mod utils;
 
 // Re-export commonly used types
 pub use error::{ApiError, ApiResult, ErrorResponse};
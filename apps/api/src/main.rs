@@ -1,116 +1,61 @@
+use async_graphql::ServerError;
 use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
 use axum::{
     extract::{ConnectInfo, Extension},
-    http::{header, header::HeaderMap, Method},
+    http::{header, header::HeaderMap},
     routing::{get, post},
     Router,
 };
 use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tower_http::trace::TraceLayer;
 
 mod config;
 mod error;
 mod graphql;
 mod middleware;
+mod migrations;
 mod models;
 mod repositories;
 mod routes;
 mod services;
+mod shutdown;
+mod utils;
 mod websocket;
 
 pub use error::{ApiError, ApiResult, ErrorResponse};
 
-use graphql::{GraphQLRateLimiter, ResonanceSchema, SchemaBuilder};
+use graphql::{
+    attach_request_id, GraphQLRateLimiter, LoaderConfig, OperationLogConfig, ResonanceSchema,
+    SchemaBuilder, IDEMPOTENCY_KEY_HEADER,
+};
 use middleware::{
-    extract_client_ip, security_headers_with_config, AuthRateLimitState, SecurityHeadersConfig,
+    build_cors_layer, extract_client_ip, request_id, security_headers_with_config,
+    track_http_metrics, AuthRateLimitState, CorsConfig, RateLimiter, RequestId,
+    SecurityHeadersConfig,
 };
 use models::user::RequestMetadata;
-use repositories::{SessionRepository, SystemSettingsRepository, TrackRepository, UserRepository};
+use repositories::{
+    AlbumRepository, SessionRepository, SystemSettingsRepository, TrackRepository, UserRepository,
+};
 use routes::{
-    auth_router, auth_router_with_rate_limiting, health_router, streaming_router, AuthState,
-    HealthState, StreamingState,
+    account_router, art_router, auth_router, auth_router_with_rate_limiting, health_router,
+    metrics_router, streaming_router, AccountState, ArtState, AuthState, HealthState, MetricsState,
+    StreamingState,
 };
 use services::auth::{AuthConfig, AuthService};
 use services::lastfm::LastfmService;
 use services::search::SearchService;
 use services::similarity::SimilarityService;
-use services::{ConfigService, EncryptionService};
-use websocket::{ws_handler, ConnectionManager, SyncPubSub};
-
-/// Build the CORS layer based on configuration.
-///
-/// In production mode:
-/// - If `CORS_ORIGINS` is set, only those origins are allowed
-/// - If `CORS_ORIGINS` is not set, CORS requests are rejected (no origins allowed)
-///
-/// In development mode:
-/// - If `CORS_ORIGINS` is set, those origins are used
-/// - If `CORS_ORIGINS` is not set, permissive CORS is used for convenience
-fn build_cors_layer(config: &config::Config) -> CorsLayer {
-    let is_production = config.is_production();
-
-    match &config.cors_allowed_origins {
-        Some(origins) if !origins.is_empty() => {
-            // Parse configured origins
-            let allowed_origins: Vec<_> = origins
-                .iter()
-                .filter_map(|origin| {
-                    origin.parse().ok().or_else(|| {
-                        tracing::warn!("Invalid CORS origin '{}', skipping", origin);
-                        None
-                    })
-                })
-                .collect();
-
-            if allowed_origins.is_empty() {
-                tracing::error!("No valid CORS origins configured, CORS requests will be rejected");
-                CorsLayer::new()
-            } else {
-                tracing::info!(
-                    "CORS configured with {} allowed origin(s): {:?}",
-                    allowed_origins.len(),
-                    origins
-                );
-                CorsLayer::new()
-                    .allow_origin(allowed_origins)
-                    .allow_methods([
-                        Method::GET,
-                        Method::POST,
-                        Method::PUT,
-                        Method::PATCH,
-                        Method::DELETE,
-                        Method::OPTIONS,
-                    ])
-                    .allow_headers([
-                        header::AUTHORIZATION,
-                        header::CONTENT_TYPE,
-                        header::ACCEPT,
-                        header::ORIGIN,
-                    ])
-                    .allow_credentials(true)
-                    .max_age(std::time::Duration::from_secs(3600))
-            }
-        }
-        _ if is_production => {
-            // Production without configured origins: strict CORS (no origins allowed)
-            tracing::warn!(
-                "CORS_ORIGINS not configured in production mode. \
-                 CORS requests will be rejected. Set CORS_ORIGINS to allow cross-origin requests."
-            );
-            CorsLayer::new()
-        }
-        _ => {
-            // Development without configured origins: permissive for convenience
-            tracing::warn!(
-                "Using permissive CORS in development mode. \
-                 Set CORS_ORIGINS for production-like behavior."
-            );
-            CorsLayer::permissive()
-        }
-    }
-}
+use services::{
+    ConfigService, EncryptionService, IdempotencyKey, IdempotencyStore, Metrics, TranscodeCache,
+    TranscoderService,
+};
+use shutdown::{serve_with_graceful_shutdown, shutdown_signal, ShutdownHandle};
+use websocket::{
+    connected_devices_handler, ws_handler, ChatServices, ConnectionManager, ResumeStore,
+    SyncPubSub, WsLimits,
+};
 
 /// Extract bearer token from Authorization header (case-insensitive)
 fn extract_bearer_token(headers: &HeaderMap) -> Option<&str> {
@@ -157,11 +102,15 @@ async fn graphql_handler(
     Extension(auth_service): Extension<AuthService>,
     Extension(session_repo): Extension<SessionRepository>,
     connect_info: Option<ConnectInfo<SocketAddr>>,
+    request_id: RequestId,
     headers: HeaderMap,
     req: GraphQLRequest,
 ) -> GraphQLResponse {
     let mut request = req.into_inner();
 
+    // Make the request id available to resolvers
+    request = request.data(request_id.clone());
+
     // Extract request metadata for audit trails
     let ip_address = Some(extract_client_ip(&headers, connect_info.as_ref()));
     let user_agent = extract_user_agent(&headers);
@@ -170,6 +119,23 @@ async fn graphql_handler(
     // Inject RequestMetadata into the GraphQL context
     request = request.data(request_metadata);
 
+    // Make the client's idempotency key available to non-idempotent mutations
+    if let Some(value) = headers.get(IDEMPOTENCY_KEY_HEADER) {
+        match value
+            .to_str()
+            .map_err(|_| "Idempotency-Key must be visible ASCII characters".to_string())
+            .and_then(IdempotencyKey::parse)
+        {
+            Ok(key) => request = request.data(key),
+            Err(message) => {
+                let mut response =
+                    async_graphql::Response::from_errors(vec![ServerError::new(message, None)]);
+                attach_request_id(&mut response, &request_id);
+                return response.into();
+            }
+        }
+    }
+
     // Try to extract and verify the Bearer token
     if let Some(token) = extract_bearer_token(&headers) {
         match auth_service.verify_access_token(token) {
@@ -200,7 +166,9 @@ async fn graphql_handler(
         }
     }
 
-    schema.execute(request).await.into()
+    let mut response = schema.execute(request).await;
+    attach_request_id(&mut response, &request_id);
+    response.into()
 }
 
 /// GraphQL Playground handler for development
@@ -212,25 +180,32 @@ async fn graphql_playground() -> impl axum::response::IntoResponse {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "resonance_api=debug,tower_http=debug".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
     // Load environment variables
     dotenvy::dotenv().ok();
 
+    // Initialize tracing
+    resonance_shared_config::init_tracing(
+        "resonance_api=debug,tower_http=debug",
+        resonance_shared_config::LogFormat::from_env(),
+    );
+
     // Load configuration
     let config = config::Config::from_env()?;
 
     tracing::info!("Starting Resonance API server on port {}", config.port);
 
+    // Streaming is unusable without a readable library root; fail fast in production
+    tracing::info!(roots = ?config.common.music_roots(), "Music library roots");
+    tracing::info!(features = ?config.features(), "Feature flags");
+    if let Err(e) = config.common.validate_music_roots() {
+        if config.is_production() {
+            return Err(e.into());
+        }
+        tracing::warn!(error = %e, "No readable music library root; streaming will fail");
+    }
+
     // Initialize database pool
-    let database_url = &config.common.database.url;
+    let database_url = config.common.database.url.expose();
     tracing::info!("Connecting to database...");
 
     let pool = PgPoolOptions::new()
@@ -243,10 +218,10 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Database connection established");
 
-    // Run migrations
+    // Run migrations; the pgvector ones only apply when the server offers it
     tracing::info!("Running database migrations...");
-    sqlx::migrate!("./migrations").run(&pool).await?;
-    tracing::info!("Migrations completed successfully");
+    let pgvector = migrations::run(&pool).await?;
+    tracing::info!(pgvector, "Migrations completed successfully");
 
     // Create UserRepository for centralized user database operations
     let user_repo = UserRepository::new(pool.clone());
@@ -266,21 +241,105 @@ async fn main() -> anyhow::Result<()> {
 
     // Create ConfigService for DB-driven configuration with cache
     // Uses HKDF to derive encryption key from JWT secret for storing API keys securely
-    let encryption_service = EncryptionService::new(&config.jwt_secret);
+    let encryption_service = EncryptionService::new(config.jwt_secret.expose());
     let config_service =
         ConfigService::new(system_settings_repo.clone(), encryption_service.clone());
     tracing::info!("ConfigService initialized (DB -> Env -> Defaults priority)");
 
-    // Create StreamingState for audio streaming
-    let streaming_state = StreamingState::new(track_repo, config.common.music_library_path.clone());
-    tracing::info!("StreamingState initialized");
+    // Metrics registry shared by the HTTP middleware, /metrics and the services below
+    let metrics = Metrics::new();
+
+    // Create StreamingState for audio streaming, with an optional transcode cache
+    let mut transcoder = TranscoderService::with_max_concurrent(config.transcode_max_concurrent)
+        .with_max_queued(config.transcode_max_queued)
+        .with_queue_timeout(std::time::Duration::from_secs(
+            config.transcode_queue_timeout_secs,
+        ))
+        .with_chunk_bytes(config.stream_chunk_bytes)
+        .with_metrics(metrics.clone());
+    tracing::info!(
+        max_concurrent = transcoder.max_concurrent(),
+        max_queued = transcoder.max_queued(),
+        "Transcoding concurrency configured"
+    );
+    if let Some(cache_path) = &config.transcode_cache_path {
+        match TranscodeCache::new(cache_path, config.transcode_cache_max_bytes()) {
+            Ok(cache) => transcoder = transcoder.with_cache(cache),
+            Err(e) => tracing::warn!(
+                error = %e,
+                path = %cache_path.display(),
+                "Transcode cache unavailable - transcodes will not be cached"
+            ),
+        }
+    }
+    if let Some(cache) = transcoder.cache() {
+        tracing::info!(
+            dir = %cache.dir().display(),
+            max_bytes = cache.max_bytes(),
+            entries = cache.len(),
+            "Transcode cache enabled"
+        );
+    }
+    transcoder.probe_formats().await;
+    tracing::info!(
+        formats = ?transcoder
+            .supported_formats()
+            .iter()
+            .map(|f| f.format)
+            .collect::<Vec<_>>(),
+        "Probed transcoding formats"
+    );
+    let streaming_state = StreamingState::new(track_repo, config.common.music_roots().to_vec())
+        .with_transcoder(transcoder.clone())
+        .with_raw_formats(config.raw_stream_formats.clone())
+        .with_chunk_bytes(config.stream_chunk_bytes)
+        .with_default_format(config.stream_default_format);
+    if let Some(format) = config.stream_default_format {
+        if !transcoder
+            .supported_formats()
+            .iter()
+            .any(|capability| capability.format == format)
+        {
+            tracing::warn!(
+                ?format,
+                "STREAM_DEFAULT_FORMAT is not supported by the installed FFmpeg - fallback transcodes will fail"
+            );
+        }
+    }
+    tracing::info!(
+        raw_formats = ?config.raw_stream_formats,
+        chunk_bytes = config.stream_chunk_bytes,
+        default_format = ?config.stream_default_format,
+        "StreamingState initialized"
+    );
+
+    // Create ArtState for resized album art; covers are read from the music
+    // library or from art the worker extracted from audio files
+    let extracted_art_path = &config.common.extracted_art_path;
+    if let Err(e) = std::fs::create_dir_all(extracted_art_path) {
+        tracing::warn!(error = %e, path = %extracted_art_path.display(), "Failed to create extracted art directory");
+    }
+    let mut art_roots = config.common.music_roots().to_vec();
+    art_roots.push(extracted_art_path.clone());
+    let art_state = ArtState::new(
+        AlbumRepository::new(pool.clone()),
+        art_roots,
+        config.art_cache_path.clone(),
+    );
+    tracing::info!(cache_dir = %config.art_cache_path.display(), "ArtState initialized");
 
     // Create AuthService
-    let auth_config = AuthConfig::with_expiry_strings(
-        config.jwt_secret.clone(),
-        &config.jwt_access_expiry,
-        &config.jwt_refresh_expiry,
+    let mut auth_config = AuthConfig::with_expiry(
+        config.jwt_secret.expose().to_string(),
+        config.jwt_access_expiry,
+        config.jwt_refresh_expiry,
     );
+    if let Some(issuer) = &config.jwt_issuer {
+        auth_config = auth_config.with_jwt_issuer(issuer);
+    }
+    if let Some(audience) = &config.jwt_audience {
+        auth_config = auth_config.with_jwt_audience(audience);
+    }
     let auth_service = AuthService::new(pool.clone(), auth_config);
 
     tracing::info!("AuthService initialized");
@@ -320,12 +379,36 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Build the CORS layer from configuration
-    let cors_layer = build_cors_layer(&config);
+    let cors_layer = build_cors_layer(&CorsConfig::from(&config));
 
     // Initialize AI/Search services (optional - gracefully degrade if not configured)
     // These services are always created since they only require the database pool
-    let search_service = SearchService::new(pool.clone());
-    tracing::info!("SearchService initialized");
+    // Without pgvector there are no embedding columns; disable semantic
+    // search up front so callers fall back to mood/keyword search instead
+    let semantic_available = pgvector;
+    if !semantic_available {
+        tracing::error!(
+            "The pgvector extension is not available in this database; semantic search \
+             is disabled. Install pgvector (https://github.com/pgvector/pgvector) on the \
+             database server and restart the API; its migrations apply on startup"
+        );
+    }
+    let loader_config = LoaderConfig::from_env();
+    tracing::info!(
+        max_batch_size = loader_config.max_batch_size,
+        cache = loader_config.enable_cache,
+        "GraphQL DataLoader settings"
+    );
+    let operation_log_config = OperationLogConfig::from_env();
+
+    let search_service = SearchService::new(pool.clone())
+        .with_embedding_dimension(config.ollama().embedding_dimension)
+        .with_semantic_available(semantic_available)
+        .with_semantic_search_enabled(config.features().semantic_search());
+    tracing::info!(
+        semantic_available = search_service.semantic_available(),
+        "SearchService initialized"
+    );
 
     let similarity_service = SimilarityService::new(pool.clone());
     tracing::info!("SimilarityService initialized");
@@ -365,10 +448,14 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // Build GraphQL schema and auth router - with or without rate limiting based on Redis availability
-    let (schema, auth_routes, sync_pubsub) = match redis_client {
+    let (schema, auth_routes, sync_pubsub, resume_store, export_limiter) = match redis_client {
         Some(client) => {
             // Create rate limit state for REST endpoints
-            let rate_limit_state = AuthRateLimitState::new(client.clone());
+            let rate_limit_state = AuthRateLimitState::with_config(
+                client.clone(),
+                config.auth_rate_limits.clone(),
+                metrics.clone(),
+            );
             tracing::info!(
                 "REST auth rate limiting enabled: login={} req/{} sec, register={} req/{} sec",
                 rate_limit_state.login_config.max_requests,
@@ -378,16 +465,19 @@ async fn main() -> anyhow::Result<()> {
             );
 
             // Create GraphQL rate limiter
-            let graphql_rate_limiter = GraphQLRateLimiter::new(client.clone());
+            let graphql_rate_limiter = GraphQLRateLimiter::new(client.clone(), metrics.clone());
             tracing::info!("GraphQL auth rate limiting enabled");
 
             // Build schema with rate limiting and AI services
             let mut builder = SchemaBuilder::new()
                 .pool(pool.clone())
+                .loader_config(loader_config)
+                .operation_log_config(operation_log_config)
                 .auth_service(auth_service.clone())
                 .encryption_service(encryption_service.clone())
                 .config_service(config_service.clone())
                 .rate_limiter(graphql_rate_limiter)
+                .idempotency_store(IdempotencyStore::new(client.clone()))
                 .search_service(search_service.clone())
                 .similarity_service(similarity_service.clone());
 
@@ -405,10 +495,18 @@ async fn main() -> anyhow::Result<()> {
             let auth_routes = auth_router_with_rate_limiting(auth_state, rate_limit_state);
 
             // Create Redis-backed pub/sub for real-time sync
-            let sync_pubsub = SyncPubSub::new_with_redis(client);
+            let sync_pubsub = SyncPubSub::new_with_redis(client.clone());
             tracing::info!("WebSocket sync using Redis pub/sub (multi-instance capable)");
-
-            (schema, auth_routes, sync_pubsub)
+            let resume_store = ResumeStore::new_with_redis(client.clone());
+            let export_limiter = RateLimiter::new(client, metrics.clone());
+
+            (
+                schema,
+                auth_routes,
+                sync_pubsub,
+                resume_store,
+                Some(export_limiter),
+            )
         }
         None => {
             tracing::warn!(
@@ -418,6 +516,8 @@ async fn main() -> anyhow::Result<()> {
             // Build schema without rate limiting but with AI services
             let mut builder = SchemaBuilder::new()
                 .pool(pool.clone())
+                .loader_config(loader_config)
+                .operation_log_config(operation_log_config)
                 .auth_service(auth_service.clone())
                 .encryption_service(encryption_service.clone())
                 .config_service(config_service.clone())
@@ -440,8 +540,9 @@ async fn main() -> anyhow::Result<()> {
             // Create in-memory pub/sub for real-time sync (single instance only)
             let sync_pubsub = SyncPubSub::new_in_memory();
             tracing::warn!("WebSocket sync using in-memory pub/sub (single instance only)");
+            let resume_store = ResumeStore::new_in_memory();
 
-            (schema, auth_routes, sync_pubsub)
+            (schema, auth_routes, sync_pubsub, resume_store, None)
         }
     };
 
@@ -449,6 +550,14 @@ async fn main() -> anyhow::Result<()> {
     let connection_manager = ConnectionManager::new();
     tracing::info!("WebSocket ConnectionManager initialized");
 
+    tracing::info!(
+        internal_only = config.metrics_internal_only,
+        "Metrics available at /metrics"
+    );
+
+    // Lets in-flight requests drain and WebSocket connections close on shutdown
+    let shutdown = ShutdownHandle::new(metrics.clone());
+
     // Build the router
     let app = Router::new()
         .route("/", get(root))
@@ -457,12 +566,30 @@ async fn main() -> anyhow::Result<()> {
         .route("/graphql/playground", get(graphql_playground))
         // WebSocket sync endpoint
         .route("/ws/sync", get(ws_handler))
+        // Presence for the "your devices" UI
+        .route("/devices/connected", get(connected_devices_handler))
         // Nested health routes: /health, /health/live, /health/ready
         .nest("/health", health_router(health_state))
         // Auth REST routes: /auth/register, /auth/login, /auth/refresh, /auth/logout
         .nest("/auth", auth_routes)
         // Streaming routes: /stream/:track_id
         .nest("/stream", streaming_router(streaming_state))
+        // Album art routes: /art/:album_id
+        .nest("/art", art_router(art_state))
+        // Account routes: /account/export
+        .nest(
+            "/account",
+            account_router(AccountState::new(pool.clone(), export_limiter)),
+        )
+        // Prometheus metrics: /metrics
+        .merge(metrics_router(MetricsState {
+            internal_only: config.metrics_internal_only,
+        }))
+        // Count requests by route; inside the routing layer so matched paths are known
+        .layer(axum::middleware::from_fn_with_state(
+            metrics.clone(),
+            track_http_metrics,
+        ))
         // Add services as extensions for middleware extractors
         .layer(Extension(schema))
         .layer(Extension(pool.clone()))
@@ -472,11 +599,19 @@ async fn main() -> anyhow::Result<()> {
         .layer(Extension(config_service))
         .layer(Extension(connection_manager))
         .layer(Extension(sync_pubsub))
+        .layer(Extension(resume_store))
+        .layer(Extension(WsLimits::from(&config)))
+        .layer(Extension(metrics.clone()))
+        .layer(Extension(shutdown.clone()))
         // Add AI/Search services for WebSocket chat handler
-        .layer(Extension(config.ollama().clone()))
-        .layer(Extension(search_service))
-        .layer(Extension(similarity_service))
-        .layer(Extension(ollama_client))
+        .layer(Extension(ChatServices {
+            ollama_config: config.ollama().clone(),
+            search_service,
+            similarity_service,
+            ollama_client,
+            features: config.features(),
+            metrics,
+        }))
         // Security headers with HSTS enabled in production
         .layer(axum::middleware::from_fn_with_state(
             if config.is_production() {
@@ -487,7 +622,11 @@ async fn main() -> anyhow::Result<()> {
             security_headers_with_config,
         ))
         .layer(TraceLayer::new_for_http())
-        .layer(cors_layer);
+        // Outside the trace layer so its spans carry the request id
+        .layer(axum::middleware::from_fn(request_id))
+        .layer(cors_layer)
+        // Outermost so every response body is counted until fully sent
+        .layer(shutdown.in_flight_layer());
 
     // Run the server with ConnectInfo to capture client addresses
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
@@ -500,13 +639,26 @@ async fn main() -> anyhow::Result<()> {
         addr.port()
     );
 
-    // Use into_make_service_with_connect_info to enable ConnectInfo extractor
-    axum::serve(
+    // Serves with ConnectInfo until Ctrl+C/SIGTERM, then drains connections
+    serve_with_graceful_shutdown(
         listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
+        app,
+        shutdown,
+        shutdown_signal(),
+        std::time::Duration::from_secs(config.shutdown_drain_timeout_secs),
     )
     .await?;
 
+    // Kill any transcodes whose streams outlived the drain window
+    if !transcoder.shutdown(std::time::Duration::from_secs(5)).await {
+        tracing::warn!(
+            running = transcoder.running_processes(),
+            "Transcode processes still running at exit"
+        );
+    }
+
+    tracing::info!("API server shutdown complete");
+
     Ok(())
 }
 
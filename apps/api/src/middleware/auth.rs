@@ -39,6 +39,7 @@ use axum::{
 use uuid::Uuid;
 
 use crate::error::{ApiError, ErrorResponse};
+use crate::middleware::request_id::current_request_id;
 use crate::models::user::{Claims, User, UserRole};
 use crate::repositories::{SessionRepository, UserRepository};
 use crate::services::AuthService;
@@ -184,6 +185,7 @@ impl IntoResponse for AuthRejection {
             code: error.error_code(),
             message: error.to_string(),
             details: None,
+            request_id: current_request_id(),
         });
 
         (status, body).into_response()
@@ -0,0 +1,303 @@
+//! CORS configuration for Resonance API
+//!
+//! In production mode:
+//! - If `CORS_ORIGINS` is set, only those origins are allowed
+//! - If `CORS_ORIGINS` is not set, CORS requests are rejected (no origins allowed)
+//!
+//! In development mode:
+//! - If `CORS_ORIGINS` is set, those origins are used
+//! - If `CORS_ORIGINS` is not set, permissive CORS is used for convenience
+//!
+//! Preflight responses are cached by browsers for `CORS_MAX_AGE_SECS`, and the
+//! headers in `CORS_EXPOSE_HEADERS` are readable from cross-origin responses.
+
+use std::time::Duration;
+
+use axum::http::{header, HeaderName, Method};
+use tower_http::cors::CorsLayer;
+
+use super::request_id::X_REQUEST_ID;
+use crate::config::Config;
+
+/// Default preflight cache duration (1 hour)
+pub const DEFAULT_CORS_MAX_AGE_SECS: u64 = 3600;
+
+/// Response headers exposed to cross-origin clients by default
+///
+/// Request ids for error reports, plus the range and caching headers clients
+/// need to seek and revalidate audio streams.
+pub const DEFAULT_CORS_EXPOSE_HEADERS: &[&str] = &[
+    "x-request-id",
+    "content-range",
+    "accept-ranges",
+    "etag",
+    "last-modified",
+    "x-fade-in-ms",
+    "x-fade-out-start-ms",
+];
+
+/// Settings for the CORS layer
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// Allowed origins; `None` or empty falls back to the environment default
+    pub allowed_origins: Option<Vec<String>>,
+    /// How long browsers may cache preflight responses
+    pub max_age: Duration,
+    /// Response headers readable from cross-origin responses
+    pub expose_headers: Vec<String>,
+    /// Whether to reject cross-origin requests when no origins are configured
+    pub is_production: bool,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: None,
+            max_age: Duration::from_secs(DEFAULT_CORS_MAX_AGE_SECS),
+            expose_headers: DEFAULT_CORS_EXPOSE_HEADERS
+                .iter()
+                .map(|h| h.to_string())
+                .collect(),
+            is_production: false,
+        }
+    }
+}
+
+impl From<&Config> for CorsConfig {
+    fn from(config: &Config) -> Self {
+        Self {
+            allowed_origins: config.cors_allowed_origins.clone(),
+            max_age: Duration::from_secs(config.cors_max_age_secs),
+            expose_headers: config.cors_expose_headers.clone(),
+            is_production: config.is_production(),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Parse the exposed header names, skipping invalid ones
+    fn expose_header_names(&self) -> Vec<HeaderName> {
+        self.expose_headers
+            .iter()
+            .filter_map(|name| {
+                name.parse().ok().or_else(|| {
+                    tracing::warn!("Invalid CORS expose header '{}', skipping", name);
+                    None
+                })
+            })
+            .collect()
+    }
+}
+
+/// Build the CORS layer based on configuration
+pub fn build_cors_layer(config: &CorsConfig) -> CorsLayer {
+    match &config.allowed_origins {
+        Some(origins) if !origins.is_empty() => {
+            // Parse configured origins
+            let allowed_origins: Vec<_> = origins
+                .iter()
+                .filter_map(|origin| {
+                    origin.parse().ok().or_else(|| {
+                        tracing::warn!("Invalid CORS origin '{}', skipping", origin);
+                        None
+                    })
+                })
+                .collect();
+
+            if allowed_origins.is_empty() {
+                tracing::error!("No valid CORS origins configured, CORS requests will be rejected");
+                CorsLayer::new()
+            } else {
+                tracing::info!(
+                    "CORS configured with {} allowed origin(s): {:?}",
+                    allowed_origins.len(),
+                    origins
+                );
+                CorsLayer::new()
+                    .allow_origin(allowed_origins)
+                    .allow_methods([
+                        Method::GET,
+                        Method::POST,
+                        Method::PUT,
+                        Method::PATCH,
+                        Method::DELETE,
+                        Method::OPTIONS,
+                    ])
+                    .allow_headers([
+                        header::AUTHORIZATION,
+                        header::CONTENT_TYPE,
+                        header::ACCEPT,
+                        header::ORIGIN,
+                        header::RANGE,
+                        X_REQUEST_ID.clone(),
+                    ])
+                    .expose_headers(config.expose_header_names())
+                    .allow_credentials(true)
+                    .max_age(config.max_age)
+            }
+        }
+        _ if config.is_production => {
+            // Production without configured origins: strict CORS (no origins allowed)
+            tracing::warn!(
+                "CORS_ORIGINS not configured in production mode. \
+                 CORS requests will be rejected. Set CORS_ORIGINS to allow cross-origin requests."
+            );
+            CorsLayer::new()
+        }
+        _ => {
+            // Development without configured origins: permissive for convenience
+            tracing::warn!(
+                "Using permissive CORS in development mode. \
+                 Set CORS_ORIGINS for production-like behavior."
+            );
+            CorsLayer::permissive().max_age(config.max_age)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{Request, StatusCode},
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    const ORIGIN: &str = "https://app.example.com";
+
+    fn create_test_app(config: &CorsConfig) -> Router {
+        Router::new()
+            .route(
+                "/stream",
+                get(|| async {
+                    (
+                        [
+                            (header::CONTENT_RANGE, "bytes 0-9/100"),
+                            (header::ACCEPT_RANGES, "bytes"),
+                        ],
+                        "0123456789",
+                    )
+                }),
+            )
+            .layer(build_cors_layer(config))
+    }
+
+    fn with_origins(origins: &[&str]) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: Some(origins.iter().map(|o| o.to_string()).collect()),
+            ..CorsConfig::default()
+        }
+    }
+
+    fn preflight() -> Request<Body> {
+        Request::builder()
+            .method(Method::OPTIONS)
+            .uri("/stream")
+            .header(header::ORIGIN, ORIGIN)
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn cross_origin_get() -> Request<Body> {
+        Request::builder()
+            .uri("/stream")
+            .header(header::ORIGIN, ORIGIN)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    fn header_str(response: &axum::response::Response, name: header::HeaderName) -> &str {
+        response
+            .headers()
+            .get(name)
+            .map(|v| v.to_str().unwrap())
+            .unwrap_or("")
+    }
+
+    #[tokio::test]
+    async fn test_preflight_uses_configured_max_age() {
+        let config = CorsConfig {
+            max_age: Duration::from_secs(600),
+            ..with_origins(&[ORIGIN])
+        };
+
+        let response = create_test_app(&config).oneshot(preflight()).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(header_str(&response, header::ACCESS_CONTROL_MAX_AGE), "600");
+        assert_eq!(
+            header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            ORIGIN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_exposes_request_id_and_range_headers() {
+        let response = create_test_app(&with_origins(&[ORIGIN]))
+            .oneshot(cross_origin_get())
+            .await
+            .unwrap();
+
+        let exposed = header_str(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS);
+        for name in ["x-request-id", "content-range", "accept-ranges", "etag"] {
+            assert!(exposed.contains(name), "{} not in {}", name, exposed);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_configured_expose_headers_replace_defaults() {
+        let config = CorsConfig {
+            expose_headers: vec!["X-Custom".to_string(), "bad header".to_string()],
+            ..with_origins(&[ORIGIN])
+        };
+
+        let response = create_test_app(&config)
+            .oneshot(cross_origin_get())
+            .await
+            .unwrap();
+
+        assert_eq!(
+            header_str(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+            "x-custom"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_production_without_origins_rejects_cross_origin() {
+        let config = CorsConfig {
+            is_production: true,
+            ..CorsConfig::default()
+        };
+
+        let response = create_test_app(&config).oneshot(preflight()).await.unwrap();
+
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+        assert!(response
+            .headers()
+            .get(header::ACCESS_CONTROL_MAX_AGE)
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_development_without_origins_is_permissive() {
+        let config = CorsConfig {
+            max_age: Duration::from_secs(120),
+            ..CorsConfig::default()
+        };
+
+        let response = create_test_app(&config).oneshot(preflight()).await.unwrap();
+
+        assert_eq!(
+            header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+            "*"
+        );
+        assert_eq!(header_str(&response, header::ACCESS_CONTROL_MAX_AGE), "120");
+    }
+}
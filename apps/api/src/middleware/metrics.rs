@@ -0,0 +1,106 @@
+//! HTTP metrics middleware for Resonance API
+//!
+//! Counts every response by matched route template and status code. Routes
+//! are labelled with their template (e.g. `/stream/:track_id`) rather than the
+//! raw path to keep label cardinality bounded; unmatched requests share the
+//! `unmatched` label. Scrapes of `/metrics` itself are not counted.
+
+use axum::{
+    body::Body,
+    extract::{MatchedPath, State},
+    http::Request,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::services::metrics::Metrics;
+
+/// Path of the metrics endpoint, excluded from request counting
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Route label for requests that matched no route
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// HTTP metrics middleware
+///
+/// Use with `axum::middleware::from_fn_with_state` so the registry is shared
+/// with the `/metrics` route.
+pub async fn track_http_metrics(
+    State(metrics): State<Metrics>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string());
+
+    if request.uri().path() == METRICS_PATH {
+        return next.run(request).await;
+    }
+
+    let response = next.run(request).await;
+    metrics.record_http_request(
+        route.as_deref().unwrap_or(UNMATCHED_ROUTE),
+        response.status().as_u16(),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn create_test_app(metrics: Metrics) -> Router {
+        Router::new()
+            .route("/tracks/:id", get(|| async { "track" }))
+            .route(METRICS_PATH, get(|| async { "metrics" }))
+            .nest(
+                "/stream",
+                Router::new().route("/:track_id", get(|| async { "audio" })),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                metrics,
+                track_http_metrics,
+            ))
+    }
+
+    async fn get_path(app: Router, path: &str) -> StatusCode {
+        app.oneshot(Request::builder().uri(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_requests_counted_by_route_template() {
+        let metrics = Metrics::new();
+        let app = create_test_app(metrics.clone());
+
+        get_path(app.clone(), "/tracks/1").await;
+        get_path(app.clone(), "/tracks/2").await;
+        get_path(app, "/stream/abc").await;
+
+        assert_eq!(metrics.http_requests("/tracks/:id", 200), 2);
+        assert_eq!(metrics.http_requests("/stream/:track_id", 200), 1);
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_requests_share_label() {
+        let metrics = Metrics::new();
+        let status = get_path(create_test_app(metrics.clone()), "/nope").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(metrics.http_requests(UNMATCHED_ROUTE, 404), 1);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_scrapes_not_counted() {
+        let metrics = Metrics::new();
+        get_path(create_test_app(metrics.clone()), METRICS_PATH).await;
+
+        assert_eq!(metrics.http_requests(METRICS_PATH, 200), 0);
+    }
+}
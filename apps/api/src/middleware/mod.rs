@@ -9,17 +9,33 @@
 //! - `login_rate_limit`: Limits login attempts (5 per minute per IP)
 //! - `register_rate_limit`: Limits registration attempts (3 per hour per IP)
 //!
+//! Metrics middleware:
+//! - `track_http_metrics`: Counts requests by route and status
+//!
+//! Request ID middleware:
+//! - `request_id`: Propagates or generates an `X-Request-Id` correlation id
+//!
+//! CORS:
+//! - `build_cors_layer`: Origin allowlist, preflight max-age and exposed headers
+//!
 //! Security headers middleware:
 //! - `security_headers`: Adds security headers (X-Frame-Options, CSP, etc.)
 
 pub mod auth;
+pub mod cors;
+pub mod metrics;
 pub mod rate_limit;
+pub mod request_id;
 pub mod security_headers;
 
 pub use auth::AuthUser;
+pub use cors::{build_cors_layer, CorsConfig};
+pub use metrics::track_http_metrics;
 pub use rate_limit::{
-    extract_client_ip, login_rate_limit, register_rate_limit, AuthRateLimitState,
+    extract_client_ip, login_rate_limit, register_rate_limit, AuthRateLimitConfig,
+    AuthRateLimitState,
 };
+pub use request_id::{request_id, RequestId};
 #[allow(unused_imports)]
 pub use security_headers::security_headers;
 pub use security_headers::{security_headers_with_config, SecurityHeadersConfig};
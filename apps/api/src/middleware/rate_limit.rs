@@ -4,13 +4,18 @@
 //! authentication endpoints. Uses a sliding window algorithm for accurate
 //! rate limiting across distributed instances.
 //!
-//! When Redis is unavailable, falls back to an in-memory rate limiter that
-//! provides local rate limiting per instance. This ensures rate limiting
-//! remains active even during Redis outages, though limits are per-instance
-//! rather than global.
+//! When Redis is unavailable, behavior depends on [`RateLimitConfig::fail_open`]:
+//! - Fail-open (the default): falls back to an in-memory rate limiter that
+//!   provides local rate limiting per instance, so requests keep flowing during
+//!   a Redis outage while limits become per-instance rather than global.
+//! - Fail-closed: denies the request until Redis is reachable again.
+//!
+//! Either way the failure is logged and counted in
+//! [`RateLimiter::redis_failure_count`].
 
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
@@ -25,7 +30,10 @@ use axum::{
 use tokio::sync::RwLock;
 use tracing::{debug, warn};
 
+use resonance_shared_config::{parse_env, ConfigError, ConfigResult};
+
 use crate::error::ApiError;
+use crate::services::metrics::Metrics;
 
 // =============================================================================
 // Trusted Proxy Configuration
@@ -121,7 +129,7 @@ impl TrustedProxies {
 }
 
 /// Check if an IP is private (RFC 1918) or localhost
-fn is_private_or_localhost(ip: &IpAddr) -> bool {
+pub(crate) fn is_private_or_localhost(ip: &IpAddr) -> bool {
     match ip {
         IpAddr::V4(ipv4) => {
             ipv4.is_loopback()           // 127.0.0.0/8
@@ -147,18 +155,31 @@ pub struct RateLimitConfig {
     pub window_secs: u64,
     /// Key prefix for Redis (e.g., "login", "register")
     pub key_prefix: String,
+    /// Whether to keep serving requests when Redis fails at call time
+    ///
+    /// When true, the in-memory fallback limiter is used; when false, the
+    /// request is denied with a short retry-after.
+    pub fail_open: bool,
 }
 
 impl RateLimitConfig {
-    /// Create a new rate limit configuration
+    /// Create a new rate limit configuration (fail-open by default)
     pub fn new(key_prefix: impl Into<String>, max_requests: u32, window_secs: u64) -> Self {
         Self {
             max_requests,
             window_secs,
             key_prefix: key_prefix.into(),
+            fail_open: true,
         }
     }
 
+    /// Set whether requests are allowed when Redis is unavailable
+    #[allow(dead_code)] // Available for fail-closed rate limits
+    pub fn with_fail_open(mut self, fail_open: bool) -> Self {
+        self.fail_open = fail_open;
+        self
+    }
+
     /// Rate limit for login: 5 attempts per 60 seconds per IP
     pub fn login() -> Self {
         Self::new("auth:login", 5, 60)
@@ -186,6 +207,77 @@ impl RateLimitConfig {
     pub fn change_password() -> Self {
         Self::new("auth:change_password", 5, 900) // 15 minutes
     }
+
+    /// Rate limit for personal data exports: 3 per hour per user
+    ///
+    /// An export reads the user's entire history, so it is far more expensive
+    /// than a normal query. Keyed by user ID rather than IP, so moving between
+    /// networks doesn't reset it and users behind a shared NAT don't share it.
+    pub fn data_export() -> Self {
+        Self::new("account:export", 3, 3600)
+    }
+}
+
+/// Login and registration rate limits, overridable from the environment
+///
+/// | Variable | Default |
+/// |----------|---------|
+/// | `AUTH_LOGIN_RATE_LIMIT_MAX` | 5 |
+/// | `AUTH_LOGIN_RATE_LIMIT_WINDOW_SECS` | 60 |
+/// | `AUTH_REGISTER_RATE_LIMIT_MAX` | 3 |
+/// | `AUTH_REGISTER_RATE_LIMIT_WINDOW_SECS` | 3600 |
+#[derive(Debug, Clone)]
+pub struct AuthRateLimitConfig {
+    pub login: RateLimitConfig,
+    pub register: RateLimitConfig,
+}
+
+impl Default for AuthRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            login: RateLimitConfig::login(),
+            register: RateLimitConfig::register(),
+        }
+    }
+}
+
+impl AuthRateLimitConfig {
+    /// Load the auth rate limits, falling back to the defaults for unset variables
+    ///
+    /// Rejects zero (and, since the values are unsigned, negative) limits and windows.
+    pub fn from_env() -> ConfigResult<Self> {
+        Ok(Self {
+            login: Self::load(RateLimitConfig::login(), "AUTH_LOGIN_RATE_LIMIT")?,
+            register: Self::load(RateLimitConfig::register(), "AUTH_REGISTER_RATE_LIMIT")?,
+        })
+    }
+
+    /// Apply `<prefix>_MAX` and `<prefix>_WINDOW_SECS` overrides to `defaults`
+    fn load(defaults: RateLimitConfig, prefix: &str) -> ConfigResult<RateLimitConfig> {
+        let max_var = format!("{}_MAX", prefix);
+        let window_var = format!("{}_WINDOW_SECS", prefix);
+        let max_requests: u32 = parse_env(&max_var, defaults.max_requests)?;
+        let window_secs: u64 = parse_env(&window_var, defaults.window_secs)?;
+
+        if max_requests == 0 {
+            return Err(ConfigError::InvalidValue(
+                max_var,
+                "must be greater than 0".to_string(),
+            ));
+        }
+        if window_secs == 0 {
+            return Err(ConfigError::InvalidValue(
+                window_var,
+                "must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(RateLimitConfig {
+            max_requests,
+            window_secs,
+            ..defaults
+        })
+    }
 }
 
 /// Entry for tracking request timestamps in the in-memory rate limiter
@@ -270,7 +362,7 @@ impl InMemoryRateLimiter {
 
     /// Check if a request should be rate limited
     ///
-    /// Returns Ok(remaining) if allowed, Err(retry_after) if rate limited
+    /// Returns Ok(remaining) if allowed, Err(retry_after) if rate limited.
     pub async fn check(&self, key: &str, config: &RateLimitConfig) -> Result<u32, u64> {
         let full_key = format!("{}:{}", config.key_prefix, key);
         let window = Duration::from_secs(config.window_secs);
@@ -342,54 +434,98 @@ impl InMemoryRateLimiter {
     }
 }
 
+/// Retry-after returned by fail-closed limits while Redis is unavailable
+const FAIL_CLOSED_RETRY_AFTER_SECS: u64 = 5;
+
 /// State for rate limiting middleware
 #[derive(Clone)]
 pub struct RateLimiter {
     redis: Arc<redis::Client>,
     fallback: Arc<InMemoryRateLimiter>,
+    /// Number of rate limit checks that hit a Redis error
+    redis_failures: Arc<AtomicU64>,
+    /// Registry counting rejections by key prefix
+    metrics: Metrics,
 }
 
 impl RateLimiter {
-    /// Create a new rate limiter with a Redis client
-    pub fn new(redis: redis::Client) -> Self {
-        Self {
-            redis: Arc::new(redis),
-            fallback: Arc::new(InMemoryRateLimiter::new()),
-        }
+    /// Create a new rate limiter with a Redis client, counting rejections in `metrics`
+    pub fn new(redis: redis::Client, metrics: Metrics) -> Self {
+        Self::with_fallback(redis, InMemoryRateLimiter::new(), metrics)
     }
 
-    /// Create a new rate limiter with a custom fallback (for testing)
-    #[cfg(test)]
-    #[allow(dead_code)] // Available for tests that need custom fallback behavior
-    pub fn with_fallback(redis: redis::Client, fallback: InMemoryRateLimiter) -> Self {
+    /// Create a new rate limiter with a custom in-memory fallback
+    pub fn with_fallback(
+        redis: redis::Client,
+        fallback: InMemoryRateLimiter,
+        metrics: Metrics,
+    ) -> Self {
         Self {
             redis: Arc::new(redis),
             fallback: Arc::new(fallback),
+            redis_failures: Arc::new(AtomicU64::new(0)),
+            metrics,
+        }
+    }
+
+    /// Number of rate limit checks that failed to reach Redis
+    #[allow(dead_code)] // Exposed for monitoring
+    pub fn redis_failure_count(&self) -> u64 {
+        self.redis_failures.load(Ordering::Relaxed)
+    }
+
+    /// Handle a Redis error during a rate limit check
+    ///
+    /// Fail-open limits degrade to the in-memory fallback; fail-closed limits
+    /// deny the request.
+    async fn degrade(
+        &self,
+        key: &str,
+        config: &RateLimitConfig,
+        error: &redis::RedisError,
+    ) -> Result<u32, u64> {
+        self.redis_failures.fetch_add(1, Ordering::Relaxed);
+
+        if config.fail_open {
+            warn!(
+                error = %error,
+                key_prefix = %config.key_prefix,
+                "Redis unavailable for rate limiting, using in-memory fallback"
+            );
+            self.fallback.check(key, config).await
+        } else {
+            warn!(
+                error = %error,
+                key_prefix = %config.key_prefix,
+                "Redis unavailable for fail-closed rate limit, denying request"
+            );
+            Err(FAIL_CLOSED_RETRY_AFTER_SECS)
         }
     }
 
     /// Check if a request should be rate limited
     ///
-    /// Uses Redis for distributed rate limiting when available.
-    /// Falls back to in-memory rate limiting when Redis is unavailable,
-    /// providing per-instance protection against brute-force attacks.
+    /// Uses Redis for distributed rate limiting when available. When Redis
+    /// is unavailable, fail-open limits fall back to in-memory rate limiting
+    /// (per-instance protection against brute-force attacks) and fail-closed
+    /// limits deny the request.
     ///
-    /// Returns Ok(remaining) if allowed, Err(retry_after) if rate limited
+    /// Returns Ok(remaining) if allowed, Err(retry_after) if rate limited.
+    /// Rejections are counted in the metrics registry by key prefix.
     pub async fn check(&self, key: &str, config: &RateLimitConfig) -> Result<u32, u64> {
+        let result = self.check_limit(key, config).await;
+        if result.is_err() {
+            self.metrics.record_rate_limit_rejection(&config.key_prefix);
+        }
+        result
+    }
+
+    async fn check_limit(&self, key: &str, config: &RateLimitConfig) -> Result<u32, u64> {
         let full_key = format!("ratelimit:{}:{}", config.key_prefix, key);
 
         let mut conn = match self.redis.get_multiplexed_async_connection().await {
             Ok(conn) => conn,
-            Err(e) => {
-                // If Redis is unavailable, use in-memory fallback rate limiter
-                // This provides per-instance rate limiting instead of global,
-                // which is still better than allowing unlimited requests
-                warn!(
-                    error = %e,
-                    "Redis unavailable for rate limiting, using in-memory fallback"
-                );
-                return self.fallback.check(key, config).await;
-            }
+            Err(e) => return self.degrade(key, config, &e).await,
         };
 
         // Get current time from Redis server to prevent clock skew
@@ -472,15 +608,7 @@ impl RateLimiter {
             .await
         {
             Ok(r) => r,
-            Err(e) => {
-                // Redis script execution failed, use in-memory fallback
-                warn!(
-                    error = %e,
-                    key = %full_key,
-                    "Rate limit check failed, using in-memory fallback"
-                );
-                return self.fallback.check(key, config).await;
-            }
+            Err(e) => return self.degrade(key, config, &e).await,
         };
 
         if result >= 0 {
@@ -689,25 +817,21 @@ pub struct AuthRateLimitState {
 
 impl AuthRateLimitState {
     /// Create new auth rate limit state with default configurations
-    pub fn new(redis_client: redis::Client) -> Self {
-        Self {
-            limiter: RateLimiter::new(redis_client),
-            login_config: RateLimitConfig::login(),
-            register_config: RateLimitConfig::register(),
-        }
+    #[allow(dead_code)] // The server loads limits from the environment via `with_config`
+    pub fn new(redis_client: redis::Client, metrics: Metrics) -> Self {
+        Self::with_config(redis_client, AuthRateLimitConfig::default(), metrics)
     }
 
     /// Create with custom configurations
-    #[allow(dead_code)] // Available for custom rate limit configuration
     pub fn with_config(
         redis_client: redis::Client,
-        login_config: RateLimitConfig,
-        register_config: RateLimitConfig,
+        config: AuthRateLimitConfig,
+        metrics: Metrics,
     ) -> Self {
         Self {
-            limiter: RateLimiter::new(redis_client),
-            login_config,
-            register_config,
+            limiter: RateLimiter::new(redis_client, metrics),
+            login_config: config.login,
+            register_config: config.register,
         }
     }
 }
@@ -1003,6 +1127,52 @@ mod tests {
         assert_eq!(config.key_prefix, "auth:change_password");
     }
 
+    #[test]
+    fn test_rate_limit_config_data_export() {
+        let config = RateLimitConfig::data_export();
+        assert_eq!(config.max_requests, 3);
+        assert_eq!(config.window_secs, 3600);
+        assert_eq!(config.key_prefix, "account:export");
+    }
+
+    // Each test uses its own variable prefix so they can run in parallel
+    #[test]
+    fn test_auth_rate_limit_config_env_overrides() {
+        std::env::set_var("TEST_RL_OVERRIDE_MAX", "10");
+        std::env::set_var("TEST_RL_OVERRIDE_WINDOW_SECS", "120");
+
+        let config =
+            AuthRateLimitConfig::load(RateLimitConfig::login(), "TEST_RL_OVERRIDE").unwrap();
+        let unset =
+            AuthRateLimitConfig::load(RateLimitConfig::register(), "TEST_RL_UNSET").unwrap();
+
+        assert_eq!(config.max_requests, 10);
+        assert_eq!(config.window_secs, 120);
+        assert_eq!(config.key_prefix, "auth:login");
+        assert_eq!(unset.max_requests, 3);
+        assert_eq!(unset.window_secs, 3600);
+    }
+
+    #[test]
+    fn test_auth_rate_limit_config_rejects_non_positive_values() {
+        std::env::set_var("TEST_RL_ZERO_MAX_MAX", "0");
+        std::env::set_var("TEST_RL_ZERO_WINDOW_WINDOW_SECS", "0");
+        std::env::set_var("TEST_RL_NEGATIVE_MAX", "-5");
+
+        for prefix in [
+            "TEST_RL_ZERO_MAX",
+            "TEST_RL_ZERO_WINDOW",
+            "TEST_RL_NEGATIVE",
+        ] {
+            let result = AuthRateLimitConfig::load(RateLimitConfig::login(), prefix);
+            assert!(
+                matches!(result, Err(ConfigError::InvalidValue(ref name, _)) if name.starts_with(prefix)),
+                "{} should be rejected",
+                prefix
+            );
+        }
+    }
+
     // In-memory rate limiter tests
 
     #[tokio::test]
@@ -1114,6 +1284,59 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_rate_limit_config_fail_open_default() {
+        assert!(RateLimitConfig::login().fail_open);
+        assert!(RateLimitConfig::register().fail_open);
+        assert!(!RateLimitConfig::login().with_fail_open(false).fail_open);
+    }
+
+    /// A limiter whose Redis client can never connect
+    fn unreachable_redis_limiter() -> RateLimiter {
+        unreachable_redis_limiter_with(Metrics::new())
+    }
+
+    fn unreachable_redis_limiter_with(metrics: Metrics) -> RateLimiter {
+        RateLimiter::new(redis::Client::open("redis://localhost:0").unwrap(), metrics)
+    }
+
+    #[tokio::test]
+    async fn test_redis_error_fail_open_allows_request() {
+        let limiter = unreachable_redis_limiter();
+        let config = RateLimitConfig::new("test:open", 5, 60);
+
+        let result = limiter.check("client1", &config).await;
+
+        assert_eq!(result, Ok(4));
+        assert_eq!(limiter.redis_failure_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_redis_error_fail_closed_denies_request() {
+        let limiter = unreachable_redis_limiter();
+        let config = RateLimitConfig::new("test:closed", 5, 60).with_fail_open(false);
+
+        let result = limiter.check("client1", &config).await;
+
+        assert_eq!(result, Err(FAIL_CLOSED_RETRY_AFTER_SECS));
+        assert_eq!(limiter.redis_failure_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejections_are_counted_in_injected_metrics() {
+        let metrics = Metrics::new();
+        let limiter = unreachable_redis_limiter_with(metrics.clone());
+        let config = RateLimitConfig::new("test:counted", 1, 60);
+
+        assert!(limiter.check("client1", &config).await.is_ok());
+        assert!(limiter.check("client1", &config).await.is_err());
+        assert!(limiter.check("client1", &config).await.is_err());
+
+        assert!(metrics
+            .render()
+            .contains("resonance_rate_limit_rejections_total{limit=\"test:counted\"} 2"));
+    }
+
     #[test]
     fn test_rate_limit_entry_is_expired() {
         let window = Duration::from_secs(60);
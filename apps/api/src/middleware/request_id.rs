@@ -0,0 +1,245 @@
+//! Request ID middleware for Resonance API
+//!
+//! Assigns every request a correlation id so a failed request can be traced
+//! across the API logs:
+//! - Reuses a well-formed incoming `X-Request-Id` header, or generates a UUID
+//! - Stores the id in request extensions (extract it with [`RequestId`])
+//! - Records the id on a tracing span wrapping the request
+//! - Echoes the id in the `X-Request-Id` response header
+//! - Makes the id available to error responses via [`current_request_id`]
+
+use axum::{
+    async_trait,
+    body::Body,
+    extract::FromRequestParts,
+    http::{header::HeaderName, request::Parts, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use std::convert::Infallible;
+use tracing::Instrument;
+use uuid::Uuid;
+
+/// Header carrying the request correlation id
+pub static X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+
+/// Maximum length of a client-provided request id
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: RequestId;
+}
+
+/// Correlation id of the current request
+///
+/// Inserted into request extensions by [`request_id`]. When used as an
+/// extractor outside the middleware, a fresh id is generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Generate a new random request id
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    /// Use a client-provided id if it is safe to log and echo back
+    ///
+    /// Accepts 1-128 visible ASCII characters; anything else is rejected so
+    /// clients cannot inject arbitrary content into logs.
+    pub fn from_header(value: &HeaderValue) -> Option<Self> {
+        let value = value.to_str().ok()?.trim();
+        let valid = !value.is_empty()
+            && value.len() <= MAX_REQUEST_ID_LEN
+            && value.bytes().all(|b| b.is_ascii_graphic());
+        valid.then(|| Self(value.to_string()))
+    }
+
+    /// Get the id as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for RequestId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestId
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parts
+            .extensions
+            .get::<RequestId>()
+            .cloned()
+            .unwrap_or_else(RequestId::generate))
+    }
+}
+
+/// Request id of the request currently being handled, if any
+///
+/// Available anywhere within the [`request_id`] middleware's scope, including
+/// `IntoResponse` implementations that have no access to the request.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID
+        .try_with(|id| id.as_str().to_string())
+        .ok()
+}
+
+/// Request id middleware
+///
+/// Reads or generates the request id, then runs the rest of the stack inside
+/// a `request` tracing span and the task-local scope used by
+/// [`current_request_id`]. The id is echoed in the `X-Request-Id` header.
+pub async fn request_id(mut request: Request<Body>, next: Next) -> Response {
+    let id = request
+        .headers()
+        .get(&X_REQUEST_ID)
+        .and_then(RequestId::from_header)
+        .unwrap_or_else(RequestId::generate);
+
+    request.extensions_mut().insert(id.clone());
+
+    let span = tracing::info_span!("request", request_id = %id);
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(request).instrument(span))
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(id.as_str()) {
+        response.headers_mut().insert(X_REQUEST_ID.clone(), value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ApiError;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn echo_handler(id: RequestId) -> String {
+        id.to_string()
+    }
+
+    async fn failing_handler() -> Result<(), ApiError> {
+        Err(ApiError::not_found("track", "missing"))
+    }
+
+    fn create_test_app() -> Router {
+        Router::new()
+            .route("/", get(echo_handler))
+            .route("/fail", get(failing_handler))
+            .layer(axum::middleware::from_fn(request_id))
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_provided_request_id_is_echoed() {
+        let response = create_test_app()
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("x-request-id", "client-req-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            response.headers().get("x-request-id").unwrap(),
+            "client-req-42"
+        );
+        assert_eq!(body_string(response).await, "client-req-42");
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated_when_absent() {
+        let response = create_test_app()
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let header = response
+            .headers()
+            .get("x-request-id")
+            .expect("response should have a request id")
+            .to_str()
+            .unwrap()
+            .to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+        assert_eq!(body_string(response).await, header);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_request_id_is_replaced() {
+        let response = create_test_app()
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header("x-request-id", "has spaces in it")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        let header = response.headers().get("x-request-id").unwrap();
+        assert!(Uuid::parse_str(header.to_str().unwrap()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_error_response_includes_request_id() {
+        let response = create_test_app()
+            .oneshot(
+                Request::builder()
+                    .uri("/fail")
+                    .header("x-request-id", "failing-req-7")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(
+            response.headers().get("x-request-id").unwrap(),
+            "failing-req-7"
+        );
+        let body: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+        assert_eq!(body["request_id"], "failing-req-7");
+    }
+
+    #[test]
+    fn test_from_header_validation() {
+        let valid = HeaderValue::from_static("abc-123_DEF");
+        assert_eq!(
+            RequestId::from_header(&valid).unwrap().as_str(),
+            "abc-123_DEF"
+        );
+
+        assert!(RequestId::from_header(&HeaderValue::from_static("")).is_none());
+        let too_long = HeaderValue::from_str(&"a".repeat(MAX_REQUEST_ID_LEN + 1)).unwrap();
+        assert!(RequestId::from_header(&too_long).is_none());
+    }
+
+    #[test]
+    fn test_current_request_id_outside_scope() {
+        assert!(current_request_id().is_none());
+    }
+}
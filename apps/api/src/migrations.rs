@@ -0,0 +1,52 @@
+//! Database migrations
+//!
+//! pgvector is optional. The migrations in `migrations/` that need it have
+//! vector-free variants under the same version in
+//! `migrations/without-pgvector/`, which run in their place when the database
+//! server doesn't offer the extension. The vector schema is completed by the
+//! idempotent migrations in `migrations/pgvector/`, which [`run`] applies
+//! whenever the extension is available. Without it the API starts with
+//! semantic search disabled and the worker skips its embedding jobs.
+//!
+//! The core and pgvector sets share the `_sqlx_migrations` table, so each
+//! migrator ignores the other's versions.
+
+use resonance_shared_config::MigrationSet;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::PgPool;
+
+/// Apply the core migrations, then the pgvector migrations if possible
+///
+/// Returns whether pgvector is installed afterwards. See
+/// [`resonance_shared_config::run_migrations`].
+pub async fn run(pool: &PgPool) -> Result<bool, MigrateError> {
+    resonance_shared_config::run_migrations(pool, &set()).await
+}
+
+/// All migrations, as applied by [`run`]
+pub fn set() -> MigrationSet {
+    MigrationSet {
+        core: core(),
+        without_pgvector: without_pgvector(),
+        pgvector: pgvector(),
+    }
+}
+
+/// Migrations every database gets
+pub fn core() -> Migrator {
+    let mut migrator = sqlx::migrate!("./migrations");
+    migrator.set_ignore_missing(true);
+    migrator
+}
+
+/// Vector-free variants of the core migrations that need pgvector
+pub fn without_pgvector() -> Migrator {
+    sqlx::migrate!("./migrations/without-pgvector")
+}
+
+/// Migrations that need the pgvector extension
+pub fn pgvector() -> Migrator {
+    let mut migrator = sqlx::migrate!("./migrations/pgvector");
+    migrator.set_ignore_missing(true);
+    migrator
+}
@@ -8,6 +8,19 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::completeness::{has_text, weighted_score};
+
+pub use resonance_media::CoverArtColors;
+
+/// Completeness weight of cover art
+pub const ALBUM_COVER_ART_WEIGHT: f32 = 0.35;
+/// Completeness weight of at least one genre
+pub const ALBUM_GENRES_WEIGHT: f32 = 0.25;
+/// Completeness weight of a release date (and so a year)
+pub const ALBUM_RELEASE_DATE_WEIGHT: f32 = 0.25;
+/// Completeness weight of a MusicBrainz identifier
+pub const ALBUM_MBID_WEIGHT: f32 = 0.15;
+
 /// Album type enum matching PostgreSQL album_type
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "album_type", rename_all = "lowercase")]
@@ -23,21 +36,6 @@ pub enum AlbumType {
     Other,
 }
 
-/// Cover art color palette extracted for visualizer
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct CoverArtColors {
-    /// Primary dominant color
-    pub primary: Option<String>,
-    /// Secondary color
-    pub secondary: Option<String>,
-    /// Accent color
-    pub accent: Option<String>,
-    /// Most vibrant color
-    pub vibrant: Option<String>,
-    /// Most muted color
-    pub muted: Option<String>,
-}
-
 /// Album record from the albums table
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct Album {
@@ -93,6 +91,23 @@ pub struct Album {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Album {
+    /// How much optional metadata is populated, from 0.0 to 1.0
+    ///
+    /// Weighted by the `ALBUM_*_WEIGHT` constants.
+    pub fn completeness_score(&self) -> f32 {
+        weighted_score(&[
+            (
+                ALBUM_COVER_ART_WEIGHT,
+                has_text(self.cover_art_path.as_deref()),
+            ),
+            (ALBUM_GENRES_WEIGHT, !self.genres.is_empty()),
+            (ALBUM_RELEASE_DATE_WEIGHT, self.release_date.is_some()),
+            (ALBUM_MBID_WEIGHT, self.mbid.is_some()),
+        ])
+    }
+}
+
 /// Album creation input
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
@@ -123,13 +138,12 @@ mod tests {
         assert!(colors.secondary.is_none());
     }
 
-    #[test]
-    fn test_album_serialization() {
-        let album = Album {
+    fn create_test_album() -> Album {
+        Album {
             id: Uuid::new_v4(),
             title: "Test Album".to_string(),
             artist_id: Uuid::new_v4(),
-            mbid: None,
+            mbid: Some(Uuid::new_v4()),
             lidarr_id: None,
             release_date: Some(NaiveDate::from_ymd_opt(2024, 1, 15).unwrap()),
             album_type: AlbumType::Album,
@@ -143,15 +157,50 @@ mod tests {
                 accent: Some("#0f3460".to_string()),
                 vibrant: Some("#e94560".to_string()),
                 muted: Some("#533483".to_string()),
+                text: Some("#ffffff".to_string()),
             },
             external_urls: serde_json::json!({}),
             metadata: serde_json::json!({}),
             created_at: Utc::now(),
             updated_at: Utc::now(),
-        };
+        }
+    }
+
+    #[test]
+    fn test_album_serialization() {
+        let album = create_test_album();
 
         let json = serde_json::to_string(&album).expect("serialization should succeed");
         assert!(json.contains("Test Album"));
         assert!(json.contains("#e94560"));
     }
+
+    #[test]
+    fn test_completeness_fully_populated() {
+        let album = create_test_album();
+        assert!((album.completeness_score() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_completeness_sparsely_populated() {
+        let mut album = create_test_album();
+        album.cover_art_path = None;
+        album.genres.clear();
+        album.mbid = None;
+
+        // Only the release date remains
+        assert!((album.completeness_score() - ALBUM_RELEASE_DATE_WEIGHT).abs() < 1e-6);
+
+        album.release_date = None;
+        assert_eq!(album.completeness_score(), 0.0);
+    }
+
+    #[test]
+    fn test_completeness_weights_sum_to_one() {
+        let total = ALBUM_COVER_ART_WEIGHT
+            + ALBUM_GENRES_WEIGHT
+            + ALBUM_RELEASE_DATE_WEIGHT
+            + ALBUM_MBID_WEIGHT;
+        assert!((total - 1.0).abs() < 1e-6);
+    }
 }
@@ -8,6 +8,17 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::completeness::{has_text, weighted_score};
+
+/// Completeness weight of an artist image
+pub const ARTIST_IMAGE_WEIGHT: f32 = 0.3;
+/// Completeness weight of a (non-blank) biography
+pub const ARTIST_BIOGRAPHY_WEIGHT: f32 = 0.3;
+/// Completeness weight of at least one genre
+pub const ARTIST_GENRES_WEIGHT: f32 = 0.25;
+/// Completeness weight of a MusicBrainz identifier
+pub const ARTIST_MBID_WEIGHT: f32 = 0.15;
+
 /// Artist record from the artists table
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct Artist {
@@ -50,6 +61,20 @@ pub struct Artist {
     pub updated_at: DateTime<Utc>,
 }
 
+impl Artist {
+    /// How much optional metadata is populated, from 0.0 to 1.0
+    ///
+    /// Weighted by the `ARTIST_*_WEIGHT` constants.
+    pub fn completeness_score(&self) -> f32 {
+        weighted_score(&[
+            (ARTIST_IMAGE_WEIGHT, has_text(self.image_url.as_deref())),
+            (ARTIST_BIOGRAPHY_WEIGHT, has_text(self.biography.as_deref())),
+            (ARTIST_GENRES_WEIGHT, !self.genres.is_empty()),
+            (ARTIST_MBID_WEIGHT, self.mbid.is_some()),
+        ])
+    }
+}
+
 /// Artist creation input
 #[allow(dead_code)]
 #[derive(Debug, Clone, Deserialize)]
@@ -67,9 +92,8 @@ pub struct CreateArtist {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_artist_serialization() {
-        let artist = Artist {
+    fn create_test_artist() -> Artist {
+        Artist {
             id: Uuid::new_v4(),
             name: "Test Artist".to_string(),
             sort_name: Some("Artist, Test".to_string()),
@@ -82,10 +106,44 @@ mod tests {
             metadata: serde_json::json!({}),
             created_at: Utc::now(),
             updated_at: Utc::now(),
-        };
+        }
+    }
+
+    #[test]
+    fn test_artist_serialization() {
+        let artist = create_test_artist();
 
         let json = serde_json::to_string(&artist).expect("serialization should succeed");
         assert!(json.contains("Test Artist"));
         assert!(json.contains("Rock"));
     }
+
+    #[test]
+    fn test_completeness_fully_populated() {
+        let artist = create_test_artist();
+        assert!((artist.completeness_score() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_completeness_sparsely_populated() {
+        let mut artist = create_test_artist();
+        artist.image_url = None;
+        artist.biography = Some("   ".to_string());
+        artist.mbid = None;
+
+        // Only genres remain
+        assert!((artist.completeness_score() - ARTIST_GENRES_WEIGHT).abs() < 1e-6);
+
+        artist.genres.clear();
+        assert_eq!(artist.completeness_score(), 0.0);
+    }
+
+    #[test]
+    fn test_completeness_weights_sum_to_one() {
+        let total = ARTIST_IMAGE_WEIGHT
+            + ARTIST_BIOGRAPHY_WEIGHT
+            + ARTIST_GENRES_WEIGHT
+            + ARTIST_MBID_WEIGHT;
+        assert!((total - 1.0).abs() < 1e-6);
+    }
 }
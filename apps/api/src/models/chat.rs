@@ -105,6 +105,9 @@ pub struct ChatConversation {
 
     /// Soft delete timestamp (None if not deleted)
     pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Archive timestamp (None if active)
+    pub archived_at: Option<DateTime<Utc>>,
 }
 
 /// Chat message record from the chat_messages table
@@ -129,14 +132,14 @@ pub struct ChatMessage {
     pub sequence_number: i32,
 
     /// Tool calls made by assistant (for function calling)
-    #[sqlx(json)]
+    #[sqlx(json(nullable))]
     pub tool_calls: Option<Vec<ToolCall>>,
 
     /// Tool call ID (for tool result messages)
     pub tool_call_id: Option<String>,
 
     /// User context at time of message
-    #[sqlx(json)]
+    #[sqlx(json(nullable))]
     pub context_snapshot: Option<ContextSnapshot>,
 
     /// Model used for generation
@@ -182,6 +185,31 @@ pub struct CreateConversation {
     pub title: Option<String>,
 }
 
+/// Filter for listing conversations
+///
+/// All criteria are optional and combined with AND; the default filter
+/// matches every active conversation.
+#[derive(Debug, Clone, Default)]
+pub struct ConversationFilter {
+    /// Case-insensitive substring the title must contain
+    pub title_contains: Option<String>,
+    /// Only conversations created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only conversations created before this time
+    pub created_before: Option<DateTime<Utc>>,
+    /// Include archived conversations (excluded by default)
+    pub include_archived: bool,
+}
+
+/// A page of conversations with the total number matching the filter
+#[derive(Debug, Clone, Serialize)]
+pub struct ConversationPage {
+    /// Conversations on this page, most recently updated first
+    pub conversations: Vec<ChatConversation>,
+    /// Total conversations matching the filter across all pages
+    pub total_count: i64,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,72 @@
+//! Metadata completeness scoring
+//!
+//! Artists, albums and tracks each score from 0 (no optional metadata) to 1
+//! (everything populated). Every scored field carries a fixed weight, and the
+//! weights for one kind of entity sum to 1, so the score is the total weight
+//! of the populated fields.
+//!
+//! Because the score is a weighted sum, the mean over a library is the sum of
+//! each weight times the share of entities with that field populated. That
+//! lets library-wide averages come from per-field counts instead of scoring
+//! every row.
+
+/// Score from `(weight, populated)` pairs whose weights sum to 1
+pub fn weighted_score(fields: &[(f32, bool)]) -> f32 {
+    let score: f32 = fields
+        .iter()
+        .filter(|(_, populated)| *populated)
+        .map(|(weight, _)| weight)
+        .sum();
+    score.clamp(0.0, 1.0)
+}
+
+/// Mean score over `total` entities from `(weight, populated_count)` pairs
+///
+/// Returns `None` when there are no entities to average.
+pub fn average_score(total: i64, fields: &[(f32, i64)]) -> Option<f32> {
+    if total <= 0 {
+        return None;
+    }
+    let score: f32 = fields
+        .iter()
+        .map(|(weight, count)| weight * (*count as f32 / total as f32))
+        .sum();
+    Some(score.clamp(0.0, 1.0))
+}
+
+/// Whether an optional text field holds something other than whitespace
+pub fn has_text(value: Option<&str>) -> bool {
+    value.is_some_and(|value| !value.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weighted_score_sums_populated_weights() {
+        assert_eq!(
+            weighted_score(&[(0.5, true), (0.3, false), (0.2, true)]),
+            0.7
+        );
+        assert_eq!(weighted_score(&[(0.5, false), (0.5, false)]), 0.0);
+        assert_eq!(weighted_score(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_average_score_matches_mean_of_scores() {
+        // Four entities: two fully populated, one with only the first field,
+        // one with nothing -> scores 1.0, 1.0, 0.6, 0.0
+        let average = average_score(4, &[(0.6, 3), (0.4, 2)]).unwrap();
+        assert!((average - 0.65).abs() < 1e-6);
+
+        assert_eq!(average_score(0, &[(1.0, 0)]), None);
+    }
+
+    #[test]
+    fn test_has_text() {
+        assert!(has_text(Some("bio")));
+        assert!(!has_text(Some("  ")));
+        assert!(!has_text(None));
+    }
+}
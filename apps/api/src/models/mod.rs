@@ -15,6 +15,7 @@
 pub mod album;
 pub mod artist;
 pub mod chat;
+pub mod completeness;
 pub mod device;
 pub mod playlist;
 pub mod queue;
@@ -26,16 +27,16 @@ pub mod user;
 pub use album::{Album, AlbumType, CoverArtColors, CreateAlbum};
 pub use artist::{Artist, CreateArtist};
 pub use chat::{
-    ChatConversation, ChatMessage, ChatRole, ContextSnapshot, CreateChatMessage,
-    CreateConversation, ToolCall, ToolCallFunction,
+    ChatConversation, ChatMessage, ChatRole, ContextSnapshot, ConversationFilter, ConversationPage,
+    CreateChatMessage, CreateConversation, ToolCall, ToolCallFunction,
 };
 pub use device::{
     DevicePresence, DeviceValidationError, UpdateDevicePlaybackState, UpsertDevicePresence,
     VALID_DEVICE_TYPES,
 };
 pub use playlist::{
-    CreatePlaylist, Playlist, PlaylistCollaborator, PlaylistTrack, PlaylistType,
-    SmartPlaylistRules, UpdatePlaylist,
+    CollaboratorPermission, CreatePlaylist, Playlist, PlaylistCollaborator, PlaylistTrack,
+    PlaylistType, SmartPlaylistRules, UpdatePlaylist,
 };
 pub use queue::{
     ContextType, QueueItem, QueuePlaybackState, QueueTrackId, QueueValidationError, SetQueue,
@@ -44,8 +45,11 @@ pub use queue::{
 pub use system_settings::{
     ServiceType, SetupStatus, SystemSetting, SystemSettingInput, UserLibraryPath,
 };
-pub use track::{AudioFeatures, AudioFormat, CreateTrack, SyncedLyricLine, Track};
+pub use track::{
+    AudioFeatures, AudioFormat, CreateTrack, ScrobbleEvent, SkipContext, SkipEvent,
+    SyncedLyricLine, Track,
+};
 pub use user::{
-    AuthTokens, Claims, DeviceInfo, DeviceType, PublicUser, RefreshClaims, RequestMetadata,
-    Session, User, UserPreferences, UserRole,
+    AuthTokens, Claims, DeviceInfo, DevicePrefs, DeviceType, EffectiveDevicePrefs, PublicUser,
+    RefreshClaims, RequestMetadata, Session, SimilarityWeights, User, UserPreferences, UserRole,
 };
@@ -46,7 +46,7 @@ pub struct SmartPlaylistRules {
 }
 
 /// Playlist record from the playlists table
-#[derive(Debug, Clone, FromRow, Serialize)]
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Playlist {
     /// Unique playlist identifier
     pub id: Uuid,
@@ -73,7 +73,7 @@ pub struct Playlist {
     pub playlist_type: PlaylistType,
 
     /// Smart playlist rules (for smart playlists)
-    #[sqlx(json)]
+    #[sqlx(json(nullable))]
     pub smart_rules: Option<SmartPlaylistRules>,
 
     /// Number of tracks in playlist
@@ -126,8 +126,36 @@ pub struct PlaylistTrack {
     pub added_at: DateTime<Utc>,
 }
 
+/// Collaborator access level matching PostgreSQL collaborator_permission
+///
+/// Levels are ordered: each one includes everything the previous allows.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type,
+)]
+#[sqlx(type_name = "collaborator_permission", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum CollaboratorPermission {
+    /// Can read the playlist
+    Viewer,
+    /// Can also add, remove and reorder tracks (the default for new and
+    /// migrated collaborators)
+    #[default]
+    Editor,
+    /// Can also rename or delete the playlist and manage collaborators
+    Admin,
+}
+
+impl std::fmt::Display for CollaboratorPermission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Viewer => write!(f, "viewer"),
+            Self::Editor => write!(f, "editor"),
+            Self::Admin => write!(f, "admin"),
+        }
+    }
+}
+
 /// Playlist collaborator from playlist_collaborators table
-#[allow(dead_code)]
 #[derive(Debug, Clone, FromRow, Serialize)]
 pub struct PlaylistCollaborator {
     /// Playlist being collaborated on
@@ -136,8 +164,8 @@ pub struct PlaylistCollaborator {
     /// User who is a collaborator
     pub user_id: Uuid,
 
-    /// Whether the collaborator can edit (add/remove tracks)
-    pub can_edit: bool,
+    /// What the collaborator is allowed to do
+    pub permission: CollaboratorPermission,
 
     /// When the collaborator was added
     pub added_at: DateTime<Utc>,
@@ -167,10 +195,105 @@ pub struct UpdatePlaylist {
     pub smart_rules: Option<SmartPlaylistRules>,
 }
 
+/// File format for playlist import/export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// Extended M3U with `#EXTINF` lines and file paths
+    M3u,
+    /// Resonance JSON document with full metadata and track ids
+    Json,
+}
+
+/// Current version of the JSON playlist export document
+pub const PLAYLIST_EXPORT_VERSION: u32 = 1;
+
+/// JSON playlist export document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlaylistExport {
+    /// Export document version
+    pub version: u32,
+
+    /// Playlist name
+    pub name: String,
+
+    /// Playlist description
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Whether the playlist is public
+    #[serde(default)]
+    pub is_public: bool,
+
+    /// Tracks in playlist order
+    pub tracks: Vec<ExportedTrack>,
+}
+
+/// A track entry in an exported playlist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, FromRow)]
+pub struct ExportedTrack {
+    /// Track ID on the exporting instance
+    pub track_id: Option<Uuid>,
+
+    /// Track title
+    pub title: String,
+
+    /// Primary artist name
+    pub artist: Option<String>,
+
+    /// Album title
+    #[serde(default)]
+    pub album: Option<String>,
+
+    /// Duration in milliseconds
+    #[serde(default)]
+    pub duration_ms: Option<i32>,
+
+    /// Path of the audio file on the exporting instance
+    #[serde(default)]
+    pub file_path: Option<String>,
+}
+
+/// An entry from an imported playlist that matched no library track
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnmatchedEntry {
+    /// Position of the entry in the imported playlist (0-based)
+    pub position: usize,
+
+    /// File path given for the entry
+    pub file_path: Option<String>,
+
+    /// Artist name given for the entry
+    pub artist: Option<String>,
+
+    /// Track title given for the entry
+    pub title: Option<String>,
+}
+
+/// Result of importing a playlist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistImport {
+    /// The newly created playlist containing the matched tracks
+    pub playlist: Playlist,
+
+    /// Entries that could not be matched to a library track
+    pub unmatched: Vec<UnmatchedEntry>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_collaborator_permission_ordering() {
+        assert_eq!(
+            CollaboratorPermission::default(),
+            CollaboratorPermission::Editor
+        );
+        assert!(CollaboratorPermission::Viewer < CollaboratorPermission::Editor);
+        assert!(CollaboratorPermission::Editor < CollaboratorPermission::Admin);
+    }
+
     #[test]
     fn test_playlist_type_default() {
         assert_eq!(PlaylistType::default(), PlaylistType::Manual);
@@ -8,6 +8,20 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use super::completeness::weighted_score;
+use crate::error::{ApiError, ApiResult};
+
+/// Completeness weight of extracted audio features
+pub const TRACK_FEATURES_WEIGHT: f32 = 0.3;
+/// Completeness weight of a semantic-search embedding
+pub const TRACK_EMBEDDING_WEIGHT: f32 = 0.3;
+/// Completeness weight of at least one genre
+pub const TRACK_GENRES_WEIGHT: f32 = 0.2;
+/// Completeness weight of belonging to an album (which carries art and year)
+pub const TRACK_ALBUM_WEIGHT: f32 = 0.1;
+/// Completeness weight of a MusicBrainz identifier
+pub const TRACK_MBID_WEIGHT: f32 = 0.1;
+
 /// Audio format enum matching PostgreSQL audio_format
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
 #[sqlx(type_name = "audio_format", rename_all = "lowercase")]
@@ -24,6 +38,22 @@ pub enum AudioFormat {
 }
 
 impl AudioFormat {
+    /// Parse a format name (e.g. "flac", "mp3"), case-insensitively
+    ///
+    /// Returns `None` for unknown names; `Other` cannot be parsed.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "flac" => Some(Self::Flac),
+            "mp3" => Some(Self::Mp3),
+            "aac" | "m4a" => Some(Self::Aac),
+            "opus" => Some(Self::Opus),
+            "ogg" => Some(Self::Ogg),
+            "wav" => Some(Self::Wav),
+            "alac" => Some(Self::Alac),
+            _ => None,
+        }
+    }
+
     /// Returns whether this format is lossless
     pub fn is_lossless(&self) -> bool {
         matches!(self, Self::Flac | Self::Wav | Self::Alac)
@@ -45,9 +75,13 @@ impl AudioFormat {
 }
 
 /// Audio features extracted from the track
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+///
+/// Stored as JSONB in `tracks.audio_features`. Keys the struct doesn't know
+/// about (e.g. `peak`, `mfcc`) are ignored when parsing.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AudioFeatures {
     /// Beats per minute
+    #[serde(alias = "tempo")]
     pub bpm: Option<f64>,
     /// Musical key (e.g., "C", "G#")
     pub key: Option<String>,
@@ -55,6 +89,10 @@ pub struct AudioFeatures {
     pub mode: Option<String>,
     /// Loudness in dB
     pub loudness: Option<f64>,
+    /// EBU R128 integrated loudness in LUFS (null for very short tracks)
+    pub lufs_integrated: Option<f64>,
+    /// EBU R128 loudness range in LU
+    pub lufs_range: Option<f64>,
     /// Energy level (0.0 - 1.0)
     pub energy: Option<f64>,
     /// Danceability (0.0 - 1.0)
@@ -67,6 +105,124 @@ pub struct AudioFeatures {
     pub instrumentalness: Option<f64>,
     /// Speechiness (0.0 - 1.0)
     pub speechiness: Option<f64>,
+    /// End of leading silence in milliseconds
+    pub fade_in_ms: Option<i32>,
+    /// Start of trailing silence ("musical end") in milliseconds
+    pub fade_out_start_ms: Option<i32>,
+    /// Peak-to-RMS amplitude ratio (higher is less compressed)
+    pub crest_factor: Option<f64>,
+    /// Spread of short-term loudness in dB
+    pub short_term_dynamic_range: Option<f64>,
+}
+
+impl AudioFeatures {
+    /// Parse features from the `audio_features` JSONB value
+    ///
+    /// A JSON null (tracks that were never analysed) yields all-`None`
+    /// features, as do missing keys. Values of the wrong type or outside
+    /// their valid range are rejected with `ApiError::ValidationError`.
+    pub fn from_json_value(value: &serde_json::Value) -> ApiResult<Self> {
+        if value.is_null() {
+            return Ok(Self::default());
+        }
+
+        let features: Self = serde_json::from_value(value.clone())
+            .map_err(|e| ApiError::ValidationError(format!("Malformed audio features: {}", e)))?;
+        features.validate()?;
+        Ok(features)
+    }
+
+    /// Serialize features for storage in the `audio_features` JSONB column
+    ///
+    /// Features are validated first so out-of-range values are never persisted.
+    #[allow(dead_code)] // Exposed for callers that write features
+    pub fn to_json_value(&self) -> ApiResult<serde_json::Value> {
+        self.validate()?;
+        serde_json::to_value(self)
+            .map_err(|e| ApiError::Internal(format!("Failed to serialize audio features: {}", e)))
+    }
+
+    /// Check that every present feature is within its valid range
+    ///
+    /// Ratio features must lie in [0, 1], bpm must be positive, loudness
+    /// values must be finite, dynamics and loudness range must be non-negative, and fade points must be
+    /// non-negative and ordered.
+    pub fn validate(&self) -> ApiResult<()> {
+        let unit_features = [
+            ("energy", self.energy),
+            ("danceability", self.danceability),
+            ("valence", self.valence),
+            ("acousticness", self.acousticness),
+            ("instrumentalness", self.instrumentalness),
+            ("speechiness", self.speechiness),
+        ];
+        for (name, value) in unit_features {
+            if let Some(v) = value {
+                if !(0.0..=1.0).contains(&v) {
+                    return Err(ApiError::ValidationError(format!(
+                        "Audio feature {} must be between 0 and 1 (got {})",
+                        name, v
+                    )));
+                }
+            }
+        }
+
+        if let Some(bpm) = self.bpm {
+            if !bpm.is_finite() || bpm <= 0.0 {
+                return Err(ApiError::ValidationError(format!(
+                    "Audio feature bpm must be positive (got {})",
+                    bpm
+                )));
+            }
+        }
+
+        for (name, value) in [
+            ("loudness", self.loudness),
+            ("lufs_integrated", self.lufs_integrated),
+        ] {
+            if let Some(v) = value.filter(|v| !v.is_finite()) {
+                return Err(ApiError::ValidationError(format!(
+                    "Audio feature {} must be finite (got {})",
+                    name, v
+                )));
+            }
+        }
+
+        for (name, value) in [
+            ("lufs_range", self.lufs_range),
+            ("crest_factor", self.crest_factor),
+            ("short_term_dynamic_range", self.short_term_dynamic_range),
+        ] {
+            if let Some(v) = value.filter(|v| !v.is_finite() || *v < 0.0) {
+                return Err(ApiError::ValidationError(format!(
+                    "Audio feature {} must be a non-negative number (got {})",
+                    name, v
+                )));
+            }
+        }
+
+        for (name, value) in [
+            ("fade_in_ms", self.fade_in_ms),
+            ("fade_out_start_ms", self.fade_out_start_ms),
+        ] {
+            if let Some(ms) = value.filter(|ms| *ms < 0) {
+                return Err(ApiError::ValidationError(format!(
+                    "Audio feature {} must not be negative (got {})",
+                    name, ms
+                )));
+            }
+        }
+        if let (Some(fade_in), Some(fade_out)) = (self.fade_in_ms, self.fade_out_start_ms) {
+            if fade_in > fade_out {
+                return Err(ApiError::ValidationError(format!(
+                    "Audio feature fade_in_ms ({}) is after fade_out_start_ms ({})",
+                    fade_in, fade_out
+                )));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Synced lyrics with timestamps
@@ -142,7 +298,7 @@ pub struct Track {
     pub lyrics: Option<String>,
 
     /// Time-synced lyrics
-    #[sqlx(json)]
+    #[sqlx(json(nullable))]
     pub synced_lyrics: Option<Vec<SyncedLyricLine>>,
 
     // Audio features
@@ -193,6 +349,24 @@ impl Track {
             && self.sample_rate.is_some_and(|sr| sr > 44100)
             && self.bit_depth.is_some_and(|bd| bd > 16)
     }
+
+    /// How much optional metadata is populated, from 0.0 to 1.0
+    ///
+    /// Weighted by the `TRACK_*_WEIGHT` constants. Embeddings live in
+    /// `track_embeddings` rather than on the track row, so the caller says
+    /// whether one exists.
+    pub fn completeness_score(&self, has_embedding: bool) -> f32 {
+        weighted_score(&[
+            (
+                TRACK_FEATURES_WEIGHT,
+                self.audio_features != AudioFeatures::default(),
+            ),
+            (TRACK_EMBEDDING_WEIGHT, has_embedding),
+            (TRACK_GENRES_WEIGHT, !self.genres.is_empty()),
+            (TRACK_ALBUM_WEIGHT, self.album_id.is_some()),
+            (TRACK_MBID_WEIGHT, self.mbid.is_some()),
+        ])
+    }
 }
 
 /// Track creation input
@@ -212,10 +386,84 @@ pub struct CreateTrack {
     pub explicit: Option<bool>,
 }
 
+/// Shortest track that can count as played for scrobbling (seconds)
+pub const MIN_SCROBBLE_TRACK_SECS: i32 = 30;
+
+/// Playback that always counts, however long the track (seconds)
+pub const MAX_SCROBBLE_THRESHOLD_SECS: i32 = 240;
+
+/// Seconds of playback after which a play of a `duration_secs` track counts
+///
+/// Half the track or 4 minutes, whichever is smaller. `None` for tracks
+/// shorter than [`MIN_SCROBBLE_TRACK_SECS`], which never count.
+pub fn scrobble_threshold_secs(duration_secs: i32) -> Option<i32> {
+    (duration_secs >= MIN_SCROBBLE_TRACK_SECS)
+        .then(|| (duration_secs / 2).min(MAX_SCROBBLE_THRESHOLD_SECS))
+}
+
+/// A play recorded by a client, e.g. while offline
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScrobbleEvent {
+    pub track_id: Uuid,
+    /// How much of the track was played, in milliseconds
+    pub played_ms: i64,
+    /// When playback started
+    pub played_at: DateTime<Utc>,
+}
+
+/// Skips within this much playback are quick skips (milliseconds)
+pub const QUICK_SKIP_THRESHOLD_MS: i64 = 30_000;
+
+/// Whether a skip after `played_ms` of playback is a quick skip
+///
+/// Quick skips mean the user rejected the track; later skips are treated as
+/// a natural move to the next track and are not held against it.
+pub fn is_quick_skip(played_ms: i64) -> bool {
+    played_ms < QUICK_SKIP_THRESHOLD_MS
+}
+
+/// Where a skipped track was playing from, matching PostgreSQL skip_context
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "skip_context", rename_all = "snake_case")]
+pub enum SkipContext {
+    Recommendation,
+    DiscoverPlaylist,
+    Autoplay,
+    Other,
+}
+
+/// A recorded skip of a track by a user
+#[derive(Debug, Clone, Copy, PartialEq, Eq, FromRow)]
+pub struct SkipEvent {
+    pub track_id: Uuid,
+    /// How much of the track was played before skipping, in milliseconds
+    pub played_ms: i32,
+    /// Skipped before [`QUICK_SKIP_THRESHOLD_MS`]
+    pub quick: bool,
+    pub context: SkipContext,
+    pub skipped_at: DateTime<Utc>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_scrobble_threshold() {
+        assert_eq!(scrobble_threshold_secs(29), None);
+        assert_eq!(scrobble_threshold_secs(30), Some(15));
+        assert_eq!(scrobble_threshold_secs(200), Some(100));
+        assert_eq!(scrobble_threshold_secs(600), Some(240));
+    }
+
+    #[test]
+    fn test_is_quick_skip() {
+        assert!(is_quick_skip(0));
+        assert!(is_quick_skip(QUICK_SKIP_THRESHOLD_MS - 1));
+        assert!(!is_quick_skip(QUICK_SKIP_THRESHOLD_MS));
+        assert!(!is_quick_skip(180_000));
+    }
+
     #[test]
     fn test_audio_format_is_lossless() {
         assert!(AudioFormat::Flac.is_lossless());
@@ -265,6 +513,142 @@ mod tests {
         assert!(!track.is_hires());
     }
 
+    #[test]
+    fn test_audio_features_from_valid_json() {
+        let value = serde_json::json!({
+            "bpm": 128.0,
+            "key": "A",
+            "mode": "minor",
+            "loudness": -7.5,
+            "lufs_integrated": -8.1,
+            "lufs_range": null,
+            "energy": 0.8,
+            "danceability": 0.65,
+            "valence": 0.3,
+            "acousticness": 0.0,
+            "instrumentalness": 1.0,
+            "speechiness": 0.05,
+            "peak": 0.98,
+            "crest_factor": 4.2,
+            "short_term_dynamic_range": 11.5
+        });
+
+        let features = AudioFeatures::from_json_value(&value).unwrap();
+
+        assert_eq!(features.bpm, Some(128.0));
+        assert_eq!(features.key.as_deref(), Some("A"));
+        assert_eq!(features.energy, Some(0.8));
+        assert_eq!(features.instrumentalness, Some(1.0));
+        assert_eq!(features.crest_factor, Some(4.2));
+        assert_eq!(features.short_term_dynamic_range, Some(11.5));
+        assert_eq!(features.lufs_integrated, Some(-8.1));
+        assert_eq!(features.lufs_range, None);
+        assert_eq!(
+            AudioFeatures::from_json_value(&features.to_json_value().unwrap()).unwrap(),
+            features
+        );
+    }
+
+    #[test]
+    fn test_audio_features_reject_out_of_range() {
+        let cases = [
+            serde_json::json!({ "energy": 1.5 }),
+            serde_json::json!({ "valence": -0.1 }),
+            serde_json::json!({ "bpm": 0.0 }),
+            serde_json::json!({ "bpm": -120.0 }),
+            serde_json::json!({ "crest_factor": -1.0 }),
+            serde_json::json!({ "lufs_range": -2.0 }),
+            serde_json::json!({ "fade_in_ms": -10 }),
+            serde_json::json!({ "fade_in_ms": 5000, "fade_out_start_ms": 1000 }),
+        ];
+        for value in cases {
+            let result = AudioFeatures::from_json_value(&value);
+            assert!(
+                matches!(result, Err(ApiError::ValidationError(_))),
+                "expected validation error for {}",
+                value
+            );
+        }
+
+        let features = AudioFeatures {
+            danceability: Some(2.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            features.to_json_value(),
+            Err(ApiError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_audio_features_reject_malformed_json() {
+        let wrong_type = serde_json::json!({ "energy": "high" });
+        let not_object = serde_json::json!([0.5, 0.2]);
+
+        assert!(matches!(
+            AudioFeatures::from_json_value(&wrong_type),
+            Err(ApiError::ValidationError(_))
+        ));
+        assert!(matches!(
+            AudioFeatures::from_json_value(&not_object),
+            Err(ApiError::ValidationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_audio_features_missing_fields_default() {
+        let partial =
+            AudioFeatures::from_json_value(&serde_json::json!({ "energy": 0.4 })).unwrap();
+        assert_eq!(partial.energy, Some(0.4));
+        assert!(partial.bpm.is_none());
+        assert!(partial.valence.is_none());
+
+        assert_eq!(
+            AudioFeatures::from_json_value(&serde_json::json!({})).unwrap(),
+            AudioFeatures::default()
+        );
+        assert_eq!(
+            AudioFeatures::from_json_value(&serde_json::Value::Null).unwrap(),
+            AudioFeatures::default()
+        );
+
+        // Legacy "tempo" key populates bpm rather than silently yielding None
+        let legacy = AudioFeatures::from_json_value(&serde_json::json!({ "tempo": 95.0 })).unwrap();
+        assert_eq!(legacy.bpm, Some(95.0));
+    }
+
+    #[test]
+    fn test_completeness_fully_populated() {
+        let mut track = create_test_track();
+        track.mbid = Some(Uuid::new_v4());
+        track.audio_features.bpm = Some(120.0);
+
+        assert!((track.completeness_score(true) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_completeness_sparsely_populated() {
+        let mut track = create_test_track();
+        track.album_id = None;
+
+        // Only genres: no features, embedding, album or MBID
+        assert!((track.completeness_score(false) - TRACK_GENRES_WEIGHT).abs() < 1e-6);
+
+        track.genres.clear();
+        assert_eq!(track.completeness_score(false), 0.0);
+        assert!((track.completeness_score(true) - TRACK_EMBEDDING_WEIGHT).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_completeness_weights_sum_to_one() {
+        let total = TRACK_FEATURES_WEIGHT
+            + TRACK_EMBEDDING_WEIGHT
+            + TRACK_GENRES_WEIGHT
+            + TRACK_ALBUM_WEIGHT
+            + TRACK_MBID_WEIGHT;
+        assert!((total - 1.0).abs() < 1e-6);
+    }
+
     fn create_test_track() -> Track {
         Track {
             id: Uuid::new_v4(),
@@ -5,6 +5,8 @@
 //! - Sessions and device tracking
 //! - JWT claims and token structures
 
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -58,6 +60,88 @@ pub struct UserPreferences {
     /// Enable ListenBrainz scrobbling
     #[serde(default)]
     pub listenbrainz_scrobble: bool,
+
+    /// Per-device audio overrides, keyed by device ID
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub device_overrides: HashMap<String, DevicePrefs>,
+
+    /// Preferred blend of similarity dimensions for recommendations; None
+    /// uses the server's weights
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity_weights: Option<SimilarityWeights>,
+}
+
+/// A user's blend of similarity dimensions
+///
+/// Weights are relative: they are scaled to sum to 1.0 when scoring, so
+/// `{1, 1, 2}` and `{0.25, 0.25, 0.5}` are the same blend.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityWeights {
+    /// Weight for semantic (embedding) similarity
+    pub semantic: f64,
+    /// Weight for acoustic (audio feature) similarity
+    pub acoustic: f64,
+    /// Weight for categorical (genre/mood/tag) similarity
+    pub categorical: f64,
+}
+
+/// Audio preferences for a single device
+///
+/// Unset fields fall back to the user's global preferences.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DevicePrefs {
+    /// Preferred transcode format (e.g. "opus", "mp3")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcode_format: Option<String>,
+
+    /// Maximum streaming bitrate in kbps on cellular connections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cellular_max_bitrate_kbps: Option<u32>,
+
+    /// Normalize volume across tracks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub normalize_volume: Option<bool>,
+}
+
+impl DevicePrefs {
+    /// Whether no field is overridden
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Audio preferences in effect for a device, after applying fallbacks
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EffectiveDevicePrefs {
+    /// Global audio quality: "low", "medium", "high", "lossless"
+    pub quality: String,
+    /// Preferred transcode format; None lets the server choose from `quality`
+    pub transcode_format: Option<String>,
+    /// Maximum bitrate on cellular; None means no cellular cap
+    pub cellular_max_bitrate_kbps: Option<u32>,
+    /// Normalize volume across tracks
+    pub normalize_volume: bool,
+    /// Whether the device has any override stored
+    pub has_override: bool,
+}
+
+impl UserPreferences {
+    /// Resolve the audio preferences for a device
+    ///
+    /// Fields the device overrides take precedence; everything else comes
+    /// from the global preferences.
+    pub fn device_prefs(&self, device_id: &str) -> EffectiveDevicePrefs {
+        let overrides = self.device_overrides.get(device_id);
+        let device = overrides.cloned().unwrap_or_default();
+
+        EffectiveDevicePrefs {
+            quality: self.quality.clone(),
+            transcode_format: device.transcode_format,
+            cellular_max_bitrate_kbps: device.cellular_max_bitrate_kbps,
+            normalize_volume: device.normalize_volume.unwrap_or(self.normalize_volume),
+            has_override: overrides.is_some(),
+        }
+    }
 }
 
 fn default_theme() -> String {
@@ -84,6 +168,8 @@ impl Default for UserPreferences {
             private_session: false,
             discord_rpc: true,
             listenbrainz_scrobble: false,
+            device_overrides: HashMap::new(),
+            similarity_weights: None,
         }
     }
 }
@@ -225,6 +311,52 @@ pub enum DeviceType {
     Tv,
 }
 
+impl DeviceType {
+    /// Classify a client from its `User-Agent` header
+    ///
+    /// Heuristic: TV platforms are checked first (many embed "Android" or
+    /// "Linux"), then tablets before phones (iPads and Android tablets omit
+    /// "Mobile"), then desktop app shells. Anything else, including a
+    /// plain desktop browser, is treated as a web client.
+    pub fn from_user_agent(ua: &str) -> DeviceType {
+        let ua = ua.to_ascii_lowercase();
+        let has = |needles: &[&str]| needles.iter().any(|n| ua.contains(n));
+
+        if has(&[
+            "smart-tv",
+            "smarttv",
+            "googletv",
+            "google tv",
+            "android tv",
+            "appletv",
+            "apple tv",
+            "tvos",
+            "roku",
+            "tizen",
+            "web0s",
+            "webos",
+            "hbbtv",
+            "crkey",
+            "bravia",
+            "aftb",
+            "aftm",
+            "aftt",
+        ]) {
+            DeviceType::Tv
+        } else if has(&["ipad", "tablet", "kindle", "silk/"])
+            || (ua.contains("android") && !ua.contains("mobile"))
+        {
+            DeviceType::Tablet
+        } else if has(&["iphone", "ipod", "mobile", "android", "windows phone"]) {
+            DeviceType::Mobile
+        } else if has(&["tauri", "electron"]) {
+            DeviceType::Desktop
+        } else {
+            DeviceType::Web
+        }
+    }
+}
+
 impl std::fmt::Display for DeviceType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -425,6 +557,69 @@ mod tests {
         assert!(!prefs.listenbrainz_scrobble);
     }
 
+    #[test]
+    fn test_device_prefs_fall_back_to_global() {
+        let prefs = UserPreferences {
+            normalize_volume: true,
+            quality: "lossless".to_string(),
+            ..Default::default()
+        };
+
+        let effective = prefs.device_prefs("phone");
+
+        assert!(!effective.has_override);
+        assert!(effective.normalize_volume);
+        assert_eq!(effective.quality, "lossless");
+        assert!(effective.transcode_format.is_none());
+        assert!(effective.cellular_max_bitrate_kbps.is_none());
+    }
+
+    #[test]
+    fn test_device_override_takes_precedence() {
+        let mut prefs = UserPreferences {
+            normalize_volume: true,
+            ..Default::default()
+        };
+        prefs.device_overrides.insert(
+            "phone".to_string(),
+            DevicePrefs {
+                transcode_format: Some("opus".to_string()),
+                cellular_max_bitrate_kbps: Some(96),
+                normalize_volume: Some(false),
+            },
+        );
+        prefs.device_overrides.insert(
+            "desktop".to_string(),
+            DevicePrefs {
+                transcode_format: Some("flac".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let phone = prefs.device_prefs("phone");
+        assert!(phone.has_override);
+        assert!(!phone.normalize_volume);
+        assert_eq!(phone.transcode_format.as_deref(), Some("opus"));
+        assert_eq!(phone.cellular_max_bitrate_kbps, Some(96));
+
+        // Fields a device leaves unset still come from the global preferences
+        let desktop = prefs.device_prefs("desktop");
+        assert!(desktop.has_override);
+        assert!(desktop.normalize_volume);
+        assert_eq!(desktop.transcode_format.as_deref(), Some("flac"));
+    }
+
+    #[test]
+    fn test_preferences_without_overrides_deserialize() {
+        let prefs: UserPreferences =
+            serde_json::from_value(serde_json::json!({ "theme": "light" })).unwrap();
+        assert!(prefs.device_overrides.is_empty());
+
+        // Empty overrides are not written back
+        let json = serde_json::to_value(&prefs).unwrap();
+        assert!(json.get("device_overrides").is_none());
+    }
+
     #[test]
     fn test_claims_is_expired() {
         let mut claims = Claims {
@@ -453,6 +648,52 @@ mod tests {
         assert_eq!(DeviceType::Tv.to_string(), "tv");
     }
 
+    #[test]
+    fn test_device_type_from_user_agent() {
+        let cases = [
+            (
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_4 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+                DeviceType::Mobile,
+            ),
+            (
+                "Mozilla/5.0 (iPad; CPU OS 17_4 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/17.4 Mobile/15E148 Safari/604.1",
+                DeviceType::Tablet,
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/124.0.0.0 Mobile Safari/537.36",
+                DeviceType::Mobile,
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 13; SM-X710) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+                DeviceType::Tablet,
+            ),
+            (
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Tauri/2.0 resonance-desktop/0.1.0",
+                DeviceType::Desktop,
+            ),
+            (
+                "Mozilla/5.0 (Linux; Android 12; BRAVIA 4K VH2 Build/STT1.211025.001.Z4) \
+                 AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+                DeviceType::Tv,
+            ),
+            (
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 \
+                 (KHTML, like Gecko) Chrome/124.0.0.0 Safari/537.36",
+                DeviceType::Web,
+            ),
+            ("", DeviceType::Web),
+        ];
+
+        for (ua, expected) in cases {
+            assert_eq!(DeviceType::from_user_agent(ua), expected, "{}", ua);
+        }
+    }
+
     #[test]
     fn test_auth_tokens_new() {
         let tokens = AuthTokens::new("access".to_string(), "refresh".to_string(), Utc::now());
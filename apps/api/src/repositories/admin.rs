@@ -4,7 +4,11 @@
 //! - System statistics aggregation
 //! - User listing with pagination and search
 //! - Role management
-//! - Session invalidation
+//! - Session listing and invalidation
+//! - Manual library scan requests
+//! - Manual audio feature recompute requests
+//! - Audio analysis progress
+//! - Full embedding regeneration requests
 
 use chrono::{DateTime, Utc};
 use sqlx::{FromRow, PgPool};
@@ -38,19 +42,98 @@ pub struct AdminUserRow {
     pub session_count: i64,
 }
 
-/// Session info for admin user detail view
+/// Session info for admin user detail and session audit views
 #[derive(Debug, FromRow)]
 pub struct AdminSessionRow {
     pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_email: String,
     pub device_type: Option<String>,
     pub device_name: Option<String>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub is_active: bool,
     pub last_active_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
 }
 
+/// Whether a session can still be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionStatus {
+    /// Not revoked and not yet expired
+    Active,
+    /// Revoked or past its expiry
+    Expired,
+}
+
+/// Filters for the admin session list; unset fields match every session
+#[derive(Debug, Clone, Default)]
+pub struct SessionFilter {
+    pub user_id: Option<Uuid>,
+    /// Exact client IP address
+    pub ip_address: Option<String>,
+    /// Device type as stored, e.g. "mobile" (case-insensitive)
+    pub device_type: Option<String>,
+    pub status: Option<SessionStatus>,
+}
+
+/// A manually requested library scan
+#[derive(Debug, FromRow)]
+pub struct LibraryScanRow {
+    pub id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub full_rescan: bool,
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// A requested regeneration of every track's embeddings
+#[derive(Debug, FromRow)]
+pub struct EmbeddingRegenerationRow {
+    pub id: Uuid,
+    pub requested_by: Option<Uuid>,
+    pub status: String,
+    pub error: Option<String>,
+    pub embedded: i32,
+    pub skipped: i32,
+    /// Tracks still without embeddings from the current model
+    pub remaining_tracks: i64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Columns selected into [`EmbeddingRegenerationRow`] (`r` = the request)
+const EMBEDDING_REGENERATION_COLUMNS: &str = r#"
+    r.id, r.requested_by, r.status, r.error, r.embedded, r.skipped,
+    (
+        SELECT COUNT(*) FROM tracks t
+        WHERE NOT EXISTS (
+            SELECT 1 FROM track_embeddings e
+            WHERE e.track_id = t.id AND e.embedding_version IS NOT NULL
+        )
+    ) AS remaining_tracks,
+    r.created_at, r.started_at, r.completed_at
+"#;
+
+/// Counts of analyzed tracks, for analysis progress
+#[derive(Debug, FromRow)]
+pub struct AnalysisCountsRow {
+    pub total_tracks: i64,
+    pub features_computed: i64,
+    pub embeddings_computed: i64,
+    /// Tracks without features whose latest extraction run failed
+    pub features_failed: i64,
+    /// Tracks without embeddings whose latest generation run failed
+    pub embeddings_failed: i64,
+    /// Tracks with either analysis failed
+    pub failed_tracks: i64,
+}
+
 /// Error type for admin operations that require atomicity
 #[derive(Debug, thiserror::Error)]
 pub enum AdminOperationError {
@@ -64,6 +147,22 @@ pub enum AdminOperationError {
     Database(#[from] sqlx::Error),
 }
 
+/// Columns selected into [`AdminSessionRow`] (`s` = sessions, `u` = users)
+const ADMIN_SESSION_COLUMNS: &str = r#"
+    s.id, s.user_id, u.email AS user_email,
+    s.device_type, s.device_name, host(s.ip_address) AS ip_address, s.user_agent,
+    s.is_active, s.last_active_at, s.expires_at, s.created_at
+"#;
+
+/// [`SessionFilter`] predicates; binds user id, IP, device type and
+/// active (true) / expired (false) as `$1`..`$4`
+const SESSION_FILTER_PREDICATES: &str = r#"
+    ($1::uuid IS NULL OR s.user_id = $1)
+    AND ($2::text IS NULL OR host(s.ip_address) = $2)
+    AND ($3::text IS NULL OR LOWER(s.device_type) = LOWER($3))
+    AND ($4::bool IS NULL OR (s.is_active AND s.expires_at > NOW()) = $4)
+"#;
+
 /// Repository for admin database operations
 #[derive(Clone)]
 pub struct AdminRepository {
@@ -237,26 +336,55 @@ impl AdminRepository {
         &self,
         user_id: Uuid,
     ) -> Result<Vec<AdminSessionRow>, sqlx::Error> {
-        sqlx::query_as::<_, AdminSessionRow>(
-            r#"
-            SELECT
-                id,
-                device_type,
-                device_name,
-                ip_address::text as ip_address,
-                user_agent,
-                is_active,
-                last_active_at,
-                created_at
-            FROM sessions
-            WHERE user_id = $1
-            ORDER BY last_active_at DESC
-            LIMIT 50
-            "#,
-        )
-        .bind(user_id)
-        .fetch_all(&self.pool)
-        .await
+        let sql = format!(
+            "SELECT {} FROM sessions s JOIN users u ON u.id = s.user_id \
+             WHERE s.user_id = $1 ORDER BY s.last_active_at DESC LIMIT 50",
+            ADMIN_SESSION_COLUMNS
+        );
+        sqlx::query_as::<_, AdminSessionRow>(&sql)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// List sessions across all users for auditing
+    ///
+    /// Returns the page of matching sessions, most recently active first,
+    /// together with the total number of matches.
+    pub async fn list_sessions(
+        &self,
+        filter: &SessionFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<AdminSessionRow>, i64), sqlx::Error> {
+        let status = filter.status.map(|status| status == SessionStatus::Active);
+
+        let sql = format!(
+            "SELECT {} FROM sessions s JOIN users u ON u.id = s.user_id \
+             WHERE {} ORDER BY s.last_active_at DESC, s.id LIMIT $5 OFFSET $6",
+            ADMIN_SESSION_COLUMNS, SESSION_FILTER_PREDICATES
+        );
+        let sessions = sqlx::query_as::<_, AdminSessionRow>(&sql)
+            .bind(filter.user_id)
+            .bind(filter.ip_address.as_deref())
+            .bind(filter.device_type.as_deref())
+            .bind(status)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool);
+
+        let count_sql = format!(
+            "SELECT COUNT(*) FROM sessions s WHERE {}",
+            SESSION_FILTER_PREDICATES
+        );
+        let total = sqlx::query_scalar::<_, i64>(&count_sql)
+            .bind(filter.user_id)
+            .bind(filter.ip_address.as_deref())
+            .bind(filter.device_type.as_deref())
+            .bind(status)
+            .fetch_one(&self.pool);
+
+        tokio::try_join!(sessions, total)
     }
 
     /// Update a user's role with atomic last-admin protection
@@ -393,6 +521,240 @@ impl AdminRepository {
 
         Ok(result.rows_affected())
     }
+
+    /// Request a library scan for the worker to pick up
+    ///
+    /// At most one scan may be pending or running. If one already is, it is
+    /// returned instead of queueing another, so concurrent triggers share a
+    /// single scan.
+    ///
+    /// # Returns
+    /// The in-flight scan request, and whether this call created it
+    pub async fn request_library_scan(
+        &self,
+        requested_by: Uuid,
+        full_rescan: bool,
+    ) -> Result<(LibraryScanRow, bool), sqlx::Error> {
+        // The in-flight request can finish between a conflicting insert and the
+        // lookup, so retry until one of the two statements sees a row
+        loop {
+            let created = sqlx::query_as::<_, LibraryScanRow>(
+                r#"
+                INSERT INTO library_scan_requests (requested_by, full_rescan)
+                VALUES ($1, $2)
+                ON CONFLICT ((TRUE)) WHERE status IN ('pending', 'running') DO NOTHING
+                RETURNING id, requested_by, full_rescan, status, error,
+                          created_at, started_at, completed_at
+                "#,
+            )
+            .bind(requested_by)
+            .bind(full_rescan)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if let Some(row) = created {
+                return Ok((row, true));
+            }
+
+            let in_flight = sqlx::query_as::<_, LibraryScanRow>(
+                r#"
+                SELECT id, requested_by, full_rescan, status, error,
+                       created_at, started_at, completed_at
+                FROM library_scan_requests
+                WHERE status IN ('pending', 'running')
+                "#,
+            )
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if let Some(row) = in_flight {
+                return Ok((row, false));
+            }
+        }
+    }
+
+    /// Find a library scan request by ID
+    pub async fn find_library_scan(
+        &self,
+        scan_id: Uuid,
+    ) -> Result<Option<LibraryScanRow>, sqlx::Error> {
+        sqlx::query_as::<_, LibraryScanRow>(
+            r#"
+            SELECT id, requested_by, full_rescan, status, error,
+                   created_at, started_at, completed_at
+            FROM library_scan_requests
+            WHERE id = $1
+            "#,
+        )
+        .bind(scan_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Count tracks by analysis state
+    pub async fn get_analysis_counts(&self) -> Result<AnalysisCountsRow, sqlx::Error> {
+        sqlx::query_as::<_, AnalysisCountsRow>(
+            r#"
+            WITH latest_runs AS (
+                SELECT DISTINCT ON (track_id, kind) track_id, kind, succeeded
+                FROM track_analysis_runs
+                ORDER BY track_id, kind, finished_at DESC
+            ),
+            track_state AS (
+                SELECT
+                    t.id,
+                    t.audio_features->>'loudness' IS NOT NULL AS has_features,
+                    e.track_id IS NOT NULL AS has_embedding,
+                    COALESCE(bool_or(r.kind = 'features' AND NOT r.succeeded), FALSE) AS features_run_failed,
+                    COALESCE(bool_or(r.kind = 'embedding' AND NOT r.succeeded), FALSE) AS embedding_run_failed
+                FROM tracks t
+                LEFT JOIN track_embeddings e ON e.track_id = t.id
+                    AND e.title_embedding IS NOT NULL
+                    AND e.description_embedding IS NOT NULL
+                LEFT JOIN latest_runs r ON r.track_id = t.id
+                GROUP BY t.id, e.track_id
+            )
+            SELECT
+                COUNT(*) AS total_tracks,
+                COUNT(*) FILTER (WHERE has_features) AS features_computed,
+                COUNT(*) FILTER (WHERE has_embedding) AS embeddings_computed,
+                COUNT(*) FILTER (WHERE NOT has_features AND features_run_failed) AS features_failed,
+                COUNT(*) FILTER (WHERE NOT has_embedding AND embedding_run_failed) AS embeddings_failed,
+                COUNT(*) FILTER (
+                    WHERE (NOT has_features AND features_run_failed)
+                       OR (NOT has_embedding AND embedding_run_failed)
+                ) AS failed_tracks
+            FROM track_state
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Durations in milliseconds of the most recent successful analysis runs
+    ///
+    /// `kind` is `"features"` or `"embedding"`. Newest first.
+    pub async fn recent_analysis_durations(
+        &self,
+        kind: &str,
+        limit: i64,
+    ) -> Result<Vec<i32>, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT duration_ms
+            FROM track_analysis_runs
+            WHERE kind = $1 AND succeeded
+            ORDER BY finished_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(kind)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Request that the worker regenerate every track's embeddings
+    ///
+    /// Marks all embeddings stale and queues the request in one transaction.
+    /// Existing vectors stay searchable until the worker replaces them.
+    ///
+    /// # Returns
+    /// The queued request, or `None` if a regeneration is already pending or
+    /// running (nothing is changed then)
+    pub async fn request_embedding_regeneration(
+        &self,
+        requested_by: Uuid,
+    ) -> Result<Option<EmbeddingRegenerationRow>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let created: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            INSERT INTO embedding_regeneration_requests (requested_by)
+            VALUES ($1)
+            ON CONFLICT ((TRUE)) WHERE status IN ('pending', 'running') DO NOTHING
+            RETURNING id
+            "#,
+        )
+        .bind(requested_by)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(id) = created else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE track_embeddings SET embedding_version = NULL")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        self.find_embedding_regeneration(id).await
+    }
+
+    /// Find an embedding regeneration request by ID
+    pub async fn find_embedding_regeneration(
+        &self,
+        request_id: Uuid,
+    ) -> Result<Option<EmbeddingRegenerationRow>, sqlx::Error> {
+        let sql = format!(
+            "SELECT {} FROM embedding_regeneration_requests r WHERE r.id = $1",
+            EMBEDDING_REGENERATION_COLUMNS
+        );
+        sqlx::query_as::<_, EmbeddingRegenerationRow>(&sql)
+            .bind(request_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Request an audio feature recompute of one track for the worker
+    ///
+    /// At most one recompute per track may be pending or running. If one
+    /// already is, its ID is returned instead of queueing another.
+    ///
+    /// # Returns
+    /// The in-flight request ID, and whether this call created it
+    pub async fn request_feature_extraction(
+        &self,
+        requested_by: Uuid,
+        track_id: Uuid,
+    ) -> Result<(Uuid, bool), sqlx::Error> {
+        // The in-flight request can finish between a conflicting insert and the
+        // lookup, so retry until one of the two statements sees a row
+        loop {
+            let created: Option<Uuid> = sqlx::query_scalar(
+                r#"
+                INSERT INTO feature_extraction_requests (track_id, requested_by)
+                VALUES ($1, $2)
+                ON CONFLICT (track_id) WHERE status IN ('pending', 'running') DO NOTHING
+                RETURNING id
+                "#,
+            )
+            .bind(track_id)
+            .bind(requested_by)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if let Some(id) = created {
+                return Ok((id, true));
+            }
+
+            let in_flight: Option<Uuid> = sqlx::query_scalar(
+                r#"
+                SELECT id FROM feature_extraction_requests
+                WHERE track_id = $1 AND status IN ('pending', 'running')
+                "#,
+            )
+            .bind(track_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+            if let Some(id) = in_flight {
+                return Ok((id, false));
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -7,8 +7,37 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::utils::{escape_ilike, ALBUM_COLUMNS};
+use crate::models::album::{
+    ALBUM_COVER_ART_WEIGHT, ALBUM_GENRES_WEIGHT, ALBUM_MBID_WEIGHT, ALBUM_RELEASE_DATE_WEIGHT,
+};
+use crate::models::completeness::average_score;
 use crate::models::Album;
 
+/// How many albums have each completeness field populated
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct AlbumCompletenessCounts {
+    pub total: i64,
+    pub with_cover_art: i64,
+    pub with_genres: i64,
+    pub with_release_date: i64,
+    pub with_mbid: i64,
+}
+
+impl AlbumCompletenessCounts {
+    /// Mean `Album::completeness_score`, or `None` with no albums
+    pub fn average_score(&self) -> Option<f32> {
+        average_score(
+            self.total,
+            &[
+                (ALBUM_COVER_ART_WEIGHT, self.with_cover_art),
+                (ALBUM_GENRES_WEIGHT, self.with_genres),
+                (ALBUM_RELEASE_DATE_WEIGHT, self.with_release_date),
+                (ALBUM_MBID_WEIGHT, self.with_mbid),
+            ],
+        )
+    }
+}
+
 /// Repository for album database operations
 #[derive(Clone)]
 pub struct AlbumRepository {
@@ -104,6 +133,25 @@ impl AlbumRepository {
             .await
     }
 
+    /// Count albums with each completeness field populated
+    ///
+    /// The conditions mirror `Album::completeness_score`.
+    pub async fn completeness_counts(&self) -> Result<AlbumCompletenessCounts, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE btrim(cover_art_path) <> '') AS with_cover_art,
+                COUNT(*) FILTER (WHERE cardinality(genres) > 0) AS with_genres,
+                COUNT(release_date) AS with_release_date,
+                COUNT(mbid) AS with_mbid
+            FROM albums
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
     /// Get album count for an artist
     pub async fn count_by_artist(&self, artist_id: Uuid) -> Result<i64, sqlx::Error> {
         sqlx::query_scalar("SELECT COUNT(*) FROM albums WHERE artist_id = $1")
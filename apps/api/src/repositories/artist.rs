@@ -7,8 +7,37 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::utils::{escape_ilike, ARTIST_COLUMNS};
+use crate::models::artist::{
+    ARTIST_BIOGRAPHY_WEIGHT, ARTIST_GENRES_WEIGHT, ARTIST_IMAGE_WEIGHT, ARTIST_MBID_WEIGHT,
+};
+use crate::models::completeness::average_score;
 use crate::models::Artist;
 
+/// How many artists have each completeness field populated
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct ArtistCompletenessCounts {
+    pub total: i64,
+    pub with_image: i64,
+    pub with_biography: i64,
+    pub with_genres: i64,
+    pub with_mbid: i64,
+}
+
+impl ArtistCompletenessCounts {
+    /// Mean `Artist::completeness_score`, or `None` with no artists
+    pub fn average_score(&self) -> Option<f32> {
+        average_score(
+            self.total,
+            &[
+                (ARTIST_IMAGE_WEIGHT, self.with_image),
+                (ARTIST_BIOGRAPHY_WEIGHT, self.with_biography),
+                (ARTIST_GENRES_WEIGHT, self.with_genres),
+                (ARTIST_MBID_WEIGHT, self.with_mbid),
+            ],
+        )
+    }
+}
+
 /// Repository for artist database operations
 #[derive(Clone)]
 pub struct ArtistRepository {
@@ -30,6 +59,20 @@ impl ArtistRepository {
             .await
     }
 
+    /// Find artists by ID, in no particular order
+    ///
+    /// IDs without an artist are skipped.
+    pub async fn find_by_ids(&self, artist_ids: &[Uuid]) -> Result<Vec<Artist>, sqlx::Error> {
+        if artist_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let sql = format!("SELECT {} FROM artists WHERE id = ANY($1)", ARTIST_COLUMNS);
+        sqlx::query_as::<_, Artist>(&sql)
+            .bind(artist_ids)
+            .fetch_all(&self.pool)
+            .await
+    }
+
     /// Find all artists with pagination
     pub async fn find_all(&self, limit: i64, offset: i64) -> Result<Vec<Artist>, sqlx::Error> {
         let sql = format!(
@@ -92,6 +135,25 @@ impl ArtistRepository {
             .await
     }
 
+    /// Count artists with each completeness field populated
+    ///
+    /// The conditions mirror `Artist::completeness_score`.
+    pub async fn completeness_counts(&self) -> Result<ArtistCompletenessCounts, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (WHERE btrim(image_url) <> '') AS with_image,
+                COUNT(*) FILTER (WHERE btrim(biography) <> '') AS with_biography,
+                COUNT(*) FILTER (WHERE cardinality(genres) > 0) AS with_genres,
+                COUNT(mbid) AS with_mbid
+            FROM artists
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
     /// Find artist by MusicBrainz ID
     #[allow(dead_code)]
     pub async fn find_by_mbid(&self, mbid: Uuid) -> Result<Option<Artist>, sqlx::Error> {
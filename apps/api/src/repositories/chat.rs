@@ -10,8 +10,10 @@ use sqlx::PgPool;
 use tracing::instrument;
 use uuid::Uuid;
 
+use super::utils::escape_ilike;
 use crate::models::chat::{
-    ChatConversation, ChatMessage, ChatRole, CreateChatMessage, CreateConversation, ToolCall,
+    ChatConversation, ChatMessage, ChatRole, ConversationFilter, CreateChatMessage,
+    CreateConversation, ToolCall,
 };
 
 /// Repository for chat database operations
@@ -58,7 +60,7 @@ impl ChatRepository {
             r#"
             INSERT INTO chat_conversations (user_id, title)
             VALUES ($1, $2)
-            RETURNING id, user_id, title, created_at, updated_at, deleted_at
+            RETURNING id, user_id, title, created_at, updated_at, deleted_at, archived_at
             "#,
         )
         .bind(input.user_id)
@@ -85,7 +87,7 @@ impl ChatRepository {
     ) -> Result<Option<ChatConversation>, sqlx::Error> {
         sqlx::query_as::<_, ChatConversation>(
             r#"
-            SELECT id, user_id, title, created_at, updated_at, deleted_at
+            SELECT id, user_id, title, created_at, updated_at, deleted_at, archived_at
             FROM chat_conversations
             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
@@ -96,7 +98,7 @@ impl ChatRepository {
         .await
     }
 
-    /// Find all conversations for a user, ordered by most recent
+    /// Find all active (non-archived) conversations for a user, ordered by most recent
     ///
     /// # Arguments
     /// * `user_id` - The user's UUID
@@ -112,24 +114,58 @@ impl ChatRepository {
         user_id: Uuid,
         limit: i64,
         offset: i64,
+    ) -> Result<Vec<ChatConversation>, sqlx::Error> {
+        self.find_conversations_filtered(user_id, &ConversationFilter::default(), limit, offset)
+            .await
+    }
+
+    /// Find conversations for a user matching a filter, ordered by most recent
+    ///
+    /// The title filter is a case-insensitive substring match with ILIKE
+    /// special characters escaped.
+    ///
+    /// # Arguments
+    /// * `user_id` - The user's UUID
+    /// * `filter` - Title and creation date criteria
+    /// * `limit` - Maximum number of conversations to return
+    /// * `offset` - Number of conversations to skip (for pagination)
+    ///
+    /// # Returns
+    /// * `Ok(Vec<ChatConversation>)` - List of matching conversations
+    /// * `Err(sqlx::Error)` - If a database error occurs
+    #[instrument(skip(self))]
+    pub async fn find_conversations_filtered(
+        &self,
+        user_id: Uuid,
+        filter: &ConversationFilter,
+        limit: i64,
+        offset: i64,
     ) -> Result<Vec<ChatConversation>, sqlx::Error> {
         sqlx::query_as::<_, ChatConversation>(
             r#"
-            SELECT id, user_id, title, created_at, updated_at, deleted_at
+            SELECT id, user_id, title, created_at, updated_at, deleted_at, archived_at
             FROM chat_conversations
             WHERE user_id = $1 AND deleted_at IS NULL
+              AND ($2::text IS NULL OR title ILIKE $2)
+              AND ($3::timestamptz IS NULL OR created_at >= $3)
+              AND ($4::timestamptz IS NULL OR created_at < $4)
+              AND ($5 OR archived_at IS NULL)
             ORDER BY updated_at DESC
-            LIMIT $2 OFFSET $3
+            LIMIT $6 OFFSET $7
             "#,
         )
         .bind(user_id)
+        .bind(title_pattern(filter))
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.include_archived)
         .bind(limit)
         .bind(offset)
         .fetch_all(&self.pool)
         .await
     }
 
-    /// Count total conversations for a user
+    /// Count total active (non-archived) conversations for a user
     ///
     /// # Arguments
     /// * `user_id` - The user's UUID
@@ -139,14 +175,41 @@ impl ChatRepository {
     /// * `Err(sqlx::Error)` - If a database error occurs
     #[instrument(skip(self))]
     pub async fn count_conversations_by_user(&self, user_id: Uuid) -> Result<i64, sqlx::Error> {
+        self.count_conversations_filtered(user_id, &ConversationFilter::default())
+            .await
+    }
+
+    /// Count conversations for a user matching a filter
+    ///
+    /// # Arguments
+    /// * `user_id` - The user's UUID
+    /// * `filter` - Title and creation date criteria
+    ///
+    /// # Returns
+    /// * `Ok(i64)` - Number of matching active conversations
+    /// * `Err(sqlx::Error)` - If a database error occurs
+    #[instrument(skip(self))]
+    pub async fn count_conversations_filtered(
+        &self,
+        user_id: Uuid,
+        filter: &ConversationFilter,
+    ) -> Result<i64, sqlx::Error> {
         sqlx::query_scalar(
             r#"
             SELECT COUNT(*)
             FROM chat_conversations
             WHERE user_id = $1 AND deleted_at IS NULL
+              AND ($2::text IS NULL OR title ILIKE $2)
+              AND ($3::timestamptz IS NULL OR created_at >= $3)
+              AND ($4::timestamptz IS NULL OR created_at < $4)
+              AND ($5 OR archived_at IS NULL)
             "#,
         )
         .bind(user_id)
+        .bind(title_pattern(filter))
+        .bind(filter.created_after)
+        .bind(filter.created_before)
+        .bind(filter.include_archived)
         .fetch_one(&self.pool)
         .await
         .map(|count: Option<i64>| count.unwrap_or(0))
@@ -175,7 +238,7 @@ impl ChatRepository {
             UPDATE chat_conversations
             SET title = $3, updated_at = NOW()
             WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
-            RETURNING id, user_id, title, created_at, updated_at, deleted_at
+            RETURNING id, user_id, title, created_at, updated_at, deleted_at, archived_at
             "#,
         )
         .bind(id)
@@ -212,6 +275,39 @@ impl ChatRepository {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Archive a conversation
+    ///
+    /// Archiving is idempotent: an already archived conversation keeps its
+    /// original `archived_at`.
+    ///
+    /// # Arguments
+    /// * `id` - The conversation UUID
+    /// * `user_id` - The user's UUID (for ownership check)
+    ///
+    /// # Returns
+    /// * `Ok(Some(ChatConversation))` - The archived conversation
+    /// * `Ok(None)` - If conversation not found
+    /// * `Err(sqlx::Error)` - If a database error occurs
+    #[instrument(skip(self))]
+    pub async fn archive_conversation(
+        &self,
+        id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<ChatConversation>, sqlx::Error> {
+        sqlx::query_as::<_, ChatConversation>(
+            r#"
+            UPDATE chat_conversations
+            SET archived_at = COALESCE(archived_at, NOW())
+            WHERE id = $1 AND user_id = $2 AND deleted_at IS NULL
+            RETURNING id, user_id, title, created_at, updated_at, deleted_at, archived_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
     /// Soft delete all conversations for a user
     ///
     /// # Arguments
@@ -423,6 +519,58 @@ impl ChatRepository {
         Ok(messages)
     }
 
+    /// Soft delete a single message
+    ///
+    /// # Arguments
+    /// * `message_id` - The message UUID
+    /// * `user_id` - The user's UUID (for ownership check)
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If the message was deleted
+    /// * `Ok(false)` - If message not found
+    /// * `Err(sqlx::Error)` - If a database error occurs
+    #[instrument(skip(self))]
+    pub async fn delete_message(
+        &self,
+        message_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        Ok(self.delete_messages(&[message_id], user_id).await? > 0)
+    }
+
+    /// Soft delete several messages at once
+    ///
+    /// Messages that don't exist, are already deleted, or belong to another
+    /// user are skipped.
+    ///
+    /// # Arguments
+    /// * `message_ids` - The message UUIDs
+    /// * `user_id` - The user's UUID (for ownership check)
+    ///
+    /// # Returns
+    /// * `Ok(i64)` - Number of messages deleted
+    /// * `Err(sqlx::Error)` - If a database error occurs
+    #[instrument(skip(self, message_ids), fields(count = message_ids.len()))]
+    pub async fn delete_messages(
+        &self,
+        message_ids: &[Uuid],
+        user_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_messages
+            SET deleted_at = NOW()
+            WHERE id = ANY($1) AND user_id = $2 AND deleted_at IS NULL
+            "#,
+        )
+        .bind(message_ids)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as i64)
+    }
+
     /// Get messages for a conversation, ordered by sequence
     ///
     /// # Arguments
@@ -446,7 +594,7 @@ impl ChatRepository {
                 id, conversation_id, user_id, role, content, sequence_number,
                 tool_calls, tool_call_id, context_snapshot, model_used, token_count, created_at
             FROM chat_messages
-            WHERE conversation_id = $1 AND user_id = $2
+            WHERE conversation_id = $1 AND user_id = $2 AND deleted_at IS NULL
             ORDER BY sequence_number ASC
             LIMIT $3
             "#,
@@ -485,6 +633,7 @@ impl ChatRepository {
                 tool_calls, tool_call_id, context_snapshot, model_used, token_count, created_at
             FROM chat_messages
             WHERE conversation_id = $1 AND user_id = $2 AND sequence_number >= $3
+              AND deleted_at IS NULL
             ORDER BY sequence_number ASC
             LIMIT $4
             "#,
@@ -499,6 +648,9 @@ impl ChatRepository {
 
     /// Get the most recent N messages for context building
     ///
+    /// Deleted messages and archived conversations are excluded so they never
+    /// reach the AI context.
+    ///
     /// # Arguments
     /// * `conversation_id` - The conversation UUID
     /// * `user_id` - The user's UUID (for ownership check)
@@ -521,8 +673,12 @@ impl ChatRepository {
                 SELECT
                     id, conversation_id, user_id, role, content, sequence_number,
                     tool_calls, tool_call_id, context_snapshot, model_used, token_count, created_at
-                FROM chat_messages
-                WHERE conversation_id = $1 AND user_id = $2
+                FROM chat_messages m
+                WHERE m.conversation_id = $1 AND m.user_id = $2 AND m.deleted_at IS NULL
+                  AND EXISTS (
+                      SELECT 1 FROM chat_conversations c
+                      WHERE c.id = m.conversation_id AND c.archived_at IS NULL
+                  )
                 ORDER BY sequence_number DESC
                 LIMIT $3
             ) AS recent
@@ -555,7 +711,7 @@ impl ChatRepository {
             r#"
             SELECT COUNT(*)
             FROM chat_messages
-            WHERE conversation_id = $1 AND user_id = $2
+            WHERE conversation_id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
         )
         .bind(conversation_id)
@@ -584,7 +740,7 @@ impl ChatRepository {
             r#"
             SELECT COALESCE(SUM(token_count), 0)
             FROM chat_messages
-            WHERE conversation_id = $1 AND user_id = $2
+            WHERE conversation_id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
         )
         .bind(conversation_id)
@@ -616,7 +772,7 @@ impl ChatRepository {
                 id, conversation_id, user_id, role, content, sequence_number,
                 tool_calls, tool_call_id, context_snapshot, model_used, token_count, created_at
             FROM chat_messages
-            WHERE tool_call_id = $1 AND user_id = $2
+            WHERE tool_call_id = $1 AND user_id = $2 AND deleted_at IS NULL
             "#,
         )
         .bind(tool_call_id)
@@ -626,6 +782,16 @@ impl ChatRepository {
     }
 }
 
+/// ILIKE pattern for a conversation title filter, if one is set
+fn title_pattern(filter: &ConversationFilter) -> Option<String> {
+    filter
+        .title_contains
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("%{}%", escape_ilike(t)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -744,4 +910,20 @@ mod tests {
         assert_eq!(json["track_count"], 100);
         assert_eq!(json["top_genres"][0], "jazz");
     }
+
+    #[test]
+    fn test_title_pattern_escapes_and_trims() {
+        let filter = ConversationFilter {
+            title_contains: Some("  100% jazz ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(title_pattern(&filter).as_deref(), Some(r"%100\% jazz%"));
+
+        let blank = ConversationFilter {
+            title_contains: Some("   ".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(title_pattern(&blank), None);
+        assert_eq!(title_pattern(&ConversationFilter::default()), None);
+    }
 }
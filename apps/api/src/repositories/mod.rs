@@ -23,7 +23,10 @@ pub mod track;
 pub mod user;
 pub mod utils;
 
-pub use admin::{AdminOperationError, AdminRepository, AdminSessionRow, AdminUserRow, SystemStats};
+pub use admin::{
+    AdminOperationError, AdminRepository, AdminSessionRow, AdminUserRow, AnalysisCountsRow,
+    EmbeddingRegenerationRow, LibraryScanRow, SessionFilter, SessionStatus, SystemStats,
+};
 pub use album::AlbumRepository;
 pub use artist::ArtistRepository;
 pub use chat::ChatRepository;
@@ -32,5 +35,8 @@ pub use playlist::PlaylistRepository;
 pub use queue::{QueueError, QueueRepository, QueueResult};
 pub use session::SessionRepository;
 pub use system_settings::SystemSettingsRepository;
-pub use track::{TrackRepository, TrackScrobbleInfo};
+pub use track::{
+    AudioFeatureBound, AudioFeatureField, DeletionReport, DuplicateTracks, FingerprintMatch,
+    RecentlyPlayedRow, TrackRepository, TrackScrobbleInfo,
+};
 pub use user::UserRepository;
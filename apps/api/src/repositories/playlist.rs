@@ -7,8 +7,8 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::utils::{escape_ilike, PLAYLIST_COLUMNS};
-use crate::models::playlist::{PlaylistType, SmartPlaylistRules};
-use crate::models::{Playlist, PlaylistTrack};
+use crate::models::playlist::{CollaboratorPermission, PlaylistType, SmartPlaylistRules};
+use crate::models::{Playlist, PlaylistCollaborator, PlaylistTrack};
 
 /// Repository for playlist database operations
 #[derive(Clone)]
@@ -133,13 +133,15 @@ impl PlaylistRepository {
         Ok(result.unwrap_or(false))
     }
 
-    /// Check if a user can edit a playlist (owner or collaborator with edit permission)
+    /// Check if a user can edit a playlist (owner, editor or admin collaborator)
+    #[allow(dead_code)]
     pub async fn can_edit(&self, playlist_id: Uuid, user_id: Uuid) -> Result<bool, sqlx::Error> {
         let result: Option<bool> = sqlx::query_scalar(
             r#"
             SELECT EXISTS(
                 SELECT 1 FROM playlists p
-                LEFT JOIN playlist_collaborators pc ON p.id = pc.playlist_id AND pc.can_edit = true
+                LEFT JOIN playlist_collaborators pc
+                    ON p.id = pc.playlist_id AND pc.permission IN ('editor', 'admin')
                 WHERE p.id = $1
                     AND (p.user_id = $2 OR pc.user_id = $2)
             )
@@ -153,6 +155,87 @@ impl PlaylistRepository {
         Ok(result.unwrap_or(false))
     }
 
+    /// Get a collaborator's permission level on a playlist
+    ///
+    /// Returns `None` if the user is not a collaborator (owners are not
+    /// collaborators on their own playlists).
+    pub async fn find_collaborator_permission(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<CollaboratorPermission>, sqlx::Error> {
+        sqlx::query_scalar(
+            "SELECT permission FROM playlist_collaborators WHERE playlist_id = $1 AND user_id = $2",
+        )
+        .bind(playlist_id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// List a playlist's collaborators, earliest first
+    pub async fn list_collaborators(
+        &self,
+        playlist_id: Uuid,
+    ) -> Result<Vec<PlaylistCollaborator>, sqlx::Error> {
+        sqlx::query_as::<_, PlaylistCollaborator>(
+            r#"
+            SELECT playlist_id, user_id, permission, added_at
+            FROM playlist_collaborators
+            WHERE playlist_id = $1
+            ORDER BY added_at ASC
+            "#,
+        )
+        .bind(playlist_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Add a collaborator, or change the permission of an existing one
+    ///
+    /// Keeps the legacy `can_edit` flag in sync for row level security.
+    pub async fn upsert_collaborator(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+        permission: CollaboratorPermission,
+    ) -> Result<PlaylistCollaborator, sqlx::Error> {
+        sqlx::query_as::<_, PlaylistCollaborator>(
+            r#"
+            INSERT INTO playlist_collaborators (playlist_id, user_id, permission, can_edit)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (playlist_id, user_id)
+            DO UPDATE SET permission = EXCLUDED.permission, can_edit = EXCLUDED.can_edit
+            RETURNING playlist_id, user_id, permission, added_at
+            "#,
+        )
+        .bind(playlist_id)
+        .bind(user_id)
+        .bind(permission)
+        .bind(permission >= CollaboratorPermission::Editor)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Remove a collaborator
+    ///
+    /// Returns true if the user was a collaborator.
+    pub async fn remove_collaborator(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "DELETE FROM playlist_collaborators WHERE playlist_id = $1 AND user_id = $2",
+        )
+        .bind(playlist_id)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Get count of playlists for a user
     #[allow(dead_code)]
     pub async fn count_by_user(&self, user_id: Uuid) -> Result<i64, sqlx::Error> {
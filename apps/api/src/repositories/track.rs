@@ -3,11 +3,112 @@
 //! This module provides all track-related database operations in a single location,
 //! following the repository pattern.
 
+use std::collections::{HashMap, HashSet};
+
+use chrono::{DateTime, Utc};
+use futures_util::TryStreamExt;
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use super::utils::{escape_ilike, TRACK_COLUMNS};
-use crate::models::Track;
+use crate::error::{ApiError, ApiResult};
+use crate::models::completeness::average_score;
+use crate::models::track::{
+    is_quick_skip, scrobble_threshold_secs, TRACK_ALBUM_WEIGHT, TRACK_EMBEDDING_WEIGHT,
+    TRACK_FEATURES_WEIGHT, TRACK_GENRES_WEIGHT, TRACK_MBID_WEIGHT,
+};
+use crate::models::{ScrobbleEvent, SkipContext, SkipEvent, Track};
+
+/// How many tracks have each completeness field populated
+#[derive(Debug, Clone, Copy, Default, sqlx::FromRow)]
+pub struct TrackCompletenessCounts {
+    pub total: i64,
+    pub with_features: i64,
+    pub with_embedding: i64,
+    pub with_genres: i64,
+    pub with_album: i64,
+    pub with_mbid: i64,
+}
+
+impl TrackCompletenessCounts {
+    /// Mean `Track::completeness_score`, or `None` with no tracks
+    pub fn average_score(&self) -> Option<f32> {
+        average_score(
+            self.total,
+            &[
+                (TRACK_FEATURES_WEIGHT, self.with_features),
+                (TRACK_EMBEDDING_WEIGHT, self.with_embedding),
+                (TRACK_GENRES_WEIGHT, self.with_genres),
+                (TRACK_ALBUM_WEIGHT, self.with_album),
+                (TRACK_MBID_WEIGHT, self.with_mbid),
+            ],
+        )
+    }
+}
+
+/// Numeric audio feature stored in `tracks.audio_features` that can be filtered on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioFeatureField {
+    Energy,
+    Danceability,
+    Valence,
+    Tempo,
+    Acousticness,
+    Instrumentalness,
+    Speechiness,
+    Loudness,
+}
+
+impl AudioFeatureField {
+    /// Key of the feature in the `audio_features` JSONB column
+    pub fn json_key(&self) -> &'static str {
+        match self {
+            Self::Energy => "energy",
+            Self::Danceability => "danceability",
+            Self::Valence => "valence",
+            Self::Tempo => "bpm",
+            Self::Acousticness => "acousticness",
+            Self::Instrumentalness => "instrumentalness",
+            Self::Speechiness => "speechiness",
+            Self::Loudness => "loudness",
+        }
+    }
+}
+
+/// Inclusive bounds on an audio feature; unset bounds are not applied
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AudioFeatureBound {
+    pub field: AudioFeatureField,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+/// Build the WHERE predicates for audio feature bounds
+///
+/// Returns the SQL (joined with `AND`, or `TRUE` if there are no bounds) and
+/// the values to bind, numbered from `$1`. The cast expressions match the
+/// expression indexes on `tracks.audio_features`. Tracks missing a bounded
+/// feature never match.
+fn audio_feature_predicates(bounds: &[AudioFeatureBound]) -> (String, Vec<f64>) {
+    let mut predicates = Vec::new();
+    let mut values = Vec::new();
+
+    for bound in bounds {
+        let column = format!("(audio_features->>'{}')::float8", bound.field.json_key());
+        for (op, value) in [(">=", bound.min), ("<=", bound.max)] {
+            if let Some(value) = value {
+                values.push(value);
+                predicates.push(format!("{} {} ${}", column, op, values.len()));
+            }
+        }
+    }
+
+    if predicates.is_empty() {
+        ("TRUE".to_string(), values)
+    } else {
+        (predicates.join(" AND "), values)
+    }
+}
 
 /// Repository for track database operations
 #[derive(Clone)]
@@ -94,6 +195,31 @@ impl TrackRepository {
             .await
     }
 
+    /// Find tracks whose audio features fall within all of the given bounds
+    ///
+    /// Ordered by title (then ID, for stable pagination).
+    pub async fn find_by_audio_features(
+        &self,
+        bounds: &[AudioFeatureBound],
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<Track>, sqlx::Error> {
+        let (predicates, values) = audio_feature_predicates(bounds);
+        let sql = format!(
+            "SELECT {} FROM tracks WHERE {} ORDER BY title ASC, id ASC LIMIT ${} OFFSET ${}",
+            TRACK_COLUMNS,
+            predicates,
+            values.len() + 1,
+            values.len() + 2
+        );
+
+        let mut query = sqlx::query_as::<_, Track>(&sql);
+        for value in values {
+            query = query.bind(value);
+        }
+        query.bind(limit).bind(offset).fetch_all(&self.pool).await
+    }
+
     /// Search tracks by title
     ///
     /// Escapes ILIKE special characters to prevent pattern injection.
@@ -162,6 +288,34 @@ impl TrackRepository {
             .await
     }
 
+    /// Count tracks with each completeness field populated
+    ///
+    /// The conditions mirror `Track::completeness_score`, with the embedding
+    /// taken from `track_embeddings`.
+    pub async fn completeness_counts(&self) -> Result<TrackCompletenessCounts, sqlx::Error> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                COUNT(*) AS total,
+                COUNT(*) FILTER (
+                    WHERE EXISTS (
+                        SELECT 1 FROM jsonb_each(t.audio_features) f
+                        WHERE f.value <> 'null'::jsonb
+                    )
+                ) AS with_features,
+                COUNT(*) FILTER (
+                    WHERE EXISTS (SELECT 1 FROM track_embeddings e WHERE e.track_id = t.id)
+                ) AS with_embedding,
+                COUNT(*) FILTER (WHERE cardinality(t.genres) > 0) AS with_genres,
+                COUNT(t.album_id) AS with_album,
+                COUNT(t.mbid) AS with_mbid
+            FROM tracks t
+            "#,
+        )
+        .fetch_one(&self.pool)
+        .await
+    }
+
     /// Increment play count for a track
     #[allow(dead_code)]
     pub async fn increment_play_count(&self, track_id: Uuid) -> Result<(), sqlx::Error> {
@@ -197,6 +351,140 @@ impl TrackRepository {
         Ok(())
     }
 
+    /// Record a batch of plays, e.g. from a client syncing offline listening
+    ///
+    /// Each event counts only if it meets the scrobble threshold for its
+    /// track (half the track or 4 minutes); counted events are added to the
+    /// user's listening history, which bumps the track's play count. Events
+    /// for unknown tracks or below the threshold are dropped. Events with
+    /// the same track and timestamp as one in the batch or already in the
+    /// history are ignored, so a retried submission doesn't count twice.
+    ///
+    /// Runs in a single transaction and returns how many events counted.
+    pub async fn record_scrobbles_batch(
+        &self,
+        user_id: Uuid,
+        mut events: Vec<ScrobbleEvent>,
+    ) -> ApiResult<u64> {
+        let mut seen = HashSet::new();
+        events.retain(|event| seen.insert((event.track_id, event.played_at)));
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Serialize a user's batches so a retry racing the original request
+        // can't slip past the duplicate check
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtextextended($1::text, 0))")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let track_ids: Vec<Uuid> = seen.iter().map(|(track_id, _)| *track_id).collect();
+        let durations: HashMap<Uuid, i32> = sqlx::query_as::<_, (Uuid, i32)>(
+            "SELECT id, duration_ms FROM tracks WHERE id = ANY($1)",
+        )
+        .bind(&track_ids)
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut counted = 0;
+        for event in &events {
+            let Some(threshold_secs) = durations
+                .get(&event.track_id)
+                .and_then(|&duration_ms| scrobble_threshold_secs(duration_ms / 1000))
+            else {
+                continue;
+            };
+            if event.played_ms < threshold_secs as i64 * 1000 {
+                continue;
+            }
+
+            counted += sqlx::query(
+                r#"
+                INSERT INTO listening_history (user_id, track_id, played_at, duration_played_ms, completed)
+                SELECT $1, $2, $3, $4, true
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM listening_history
+                    WHERE user_id = $1 AND track_id = $2 AND played_at = $3
+                )
+                "#,
+            )
+            .bind(user_id)
+            .bind(event.track_id)
+            .bind(event.played_at)
+            .bind(event.played_ms.min(i32::MAX as i64) as i32)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(counted)
+    }
+
+    /// Record that a user skipped a track after `played_ms` of playback
+    ///
+    /// Skips before [`QUICK_SKIP_THRESHOLD_MS`] are stored as quick skips and
+    /// also bump the track's skip count; later skips are a natural next and
+    /// are stored without penalty. Returns `None` if the track doesn't exist.
+    ///
+    /// [`QUICK_SKIP_THRESHOLD_MS`]: crate::models::track::QUICK_SKIP_THRESHOLD_MS
+    pub async fn record_skip(
+        &self,
+        user_id: Uuid,
+        track_id: Uuid,
+        played_ms: i32,
+        context: SkipContext,
+    ) -> ApiResult<Option<SkipEvent>> {
+        let skip = sqlx::query_as::<_, SkipEvent>(
+            r#"
+            WITH skip AS (
+                INSERT INTO track_skips (user_id, track_id, played_ms, quick, context)
+                SELECT $1, id, $3, $4, $5 FROM tracks WHERE id = $2
+                RETURNING track_id, played_ms, quick, context, skipped_at
+            ),
+            counted AS (
+                UPDATE tracks
+                SET skip_count = skip_count + 1,
+                    updated_at = NOW()
+                WHERE id IN (SELECT track_id FROM skip WHERE quick)
+            )
+            SELECT track_id, played_ms, quick, context, skipped_at FROM skip
+            "#,
+        )
+        .bind(user_id)
+        .bind(track_id)
+        .bind(played_ms)
+        .bind(is_quick_skip(played_ms as i64))
+        .bind(context)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(skip)
+    }
+
+    /// Find a user's most recent skips, newest first
+    #[allow(dead_code)] // Used by integration tests
+    pub async fn find_skips(&self, user_id: Uuid, limit: i64) -> ApiResult<Vec<SkipEvent>> {
+        let skips = sqlx::query_as::<_, SkipEvent>(
+            r#"
+            SELECT track_id, played_ms, quick, context, skipped_at
+            FROM track_skips
+            WHERE user_id = $1
+            ORDER BY skipped_at DESC, id DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(skips)
+    }
+
     /// Get track info for scrobbling to external services (ListenBrainz, Last.fm)
     ///
     /// Returns track title, artist name, album title, and MusicBrainz IDs
@@ -226,6 +514,413 @@ impl TrackRepository {
         .fetch_optional(&self.pool)
         .await
     }
+
+    /// Find a user's most recent plays from `listening_history`, newest first
+    ///
+    /// With `collapse_repeats`, consecutive plays of the same track collapse
+    /// into the most recent one, so looping a track shows up once.
+    pub async fn find_listening_history(
+        &self,
+        user_id: Uuid,
+        collapse_repeats: bool,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<RecentlyPlayedRow>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            WITH plays AS (
+                SELECT
+                    lh.id AS history_id,
+                    lh.track_id,
+                    lh.played_at,
+                    LAG(lh.track_id) OVER (ORDER BY lh.played_at DESC, lh.id DESC)
+                        AS newer_track_id
+                FROM listening_history lh
+                WHERE lh.user_id = $1
+            ),
+            page AS (
+                SELECT history_id, track_id, played_at
+                FROM plays
+                WHERE NOT $2 OR newer_track_id IS DISTINCT FROM track_id
+                ORDER BY played_at DESC, history_id DESC
+                LIMIT $3 OFFSET $4
+            )
+            SELECT {}, page.played_at
+            FROM page
+            JOIN tracks ON tracks.id = page.track_id
+            ORDER BY page.played_at DESC, page.history_id DESC
+            "#,
+            TRACK_COLUMNS
+        );
+        sqlx::query_as::<_, RecentlyPlayedRow>(&sql)
+            .bind(user_id)
+            .bind(collapse_repeats)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Find groups of tracks the library scan flagged as likely duplicates
+    ///
+    /// Pages over canonical tracks (ordered by title), returning each with
+    /// the available tracks whose `duplicate_of` points at it.
+    pub async fn find_duplicate_groups(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<DuplicateTracks>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT {} FROM tracks
+            WHERE id IN (
+                SELECT duplicate_of FROM tracks
+                WHERE duplicate_of IS NOT NULL AND is_available = true
+            )
+            ORDER BY title, id
+            LIMIT $1 OFFSET $2
+            "#,
+            TRACK_COLUMNS
+        );
+        let canonicals = sqlx::query_as::<_, Track>(&sql)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let canonical_ids: Vec<Uuid> = canonicals.iter().map(|t| t.id).collect();
+        let sql = format!(
+            r#"
+            SELECT {}, duplicate_of FROM tracks
+            WHERE duplicate_of = ANY($1) AND is_available = true
+            ORDER BY file_path
+            "#,
+            TRACK_COLUMNS
+        );
+        let rows = sqlx::query_as::<_, DuplicateRow>(&sql)
+            .bind(&canonical_ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut duplicates: HashMap<Uuid, Vec<Track>> = HashMap::new();
+        for row in rows {
+            duplicates
+                .entry(row.duplicate_of)
+                .or_default()
+                .push(row.track);
+        }
+
+        Ok(canonicals
+            .into_iter()
+            .map(|canonical| DuplicateTracks {
+                duplicates: duplicates.remove(&canonical.id).unwrap_or_default(),
+                canonical,
+            })
+            .collect())
+    }
+
+    /// Find tracks whose acoustic fingerprint matches `fingerprint`
+    ///
+    /// `fingerprint` is in the stored form written by feature extraction
+    /// (8 hex digits per 32-bit sub-fingerprint). Tracks match when the
+    /// fraction of differing bits over the shared sub-fingerprints is at
+    /// most `tolerance` (0.35 separates re-encodes of the same recording from
+    /// unrelated audio, which sits around 0.5). Matches are ordered best
+    /// first.
+    ///
+    /// Every stored fingerprint is compared, streaming them from the
+    /// database, so this suits one-off lookups rather than bulk matching.
+    #[allow(dead_code)]
+    pub async fn find_by_fingerprint(
+        &self,
+        fingerprint: &str,
+        tolerance: f32,
+    ) -> ApiResult<Vec<FingerprintMatch>> {
+        if !(0.0..=1.0).contains(&tolerance) {
+            return Err(ApiError::ValidationError(
+                "fingerprint tolerance must be between 0 and 1".to_string(),
+            ));
+        }
+        let query = decode_fingerprint(fingerprint)
+            .filter(|fp| fp.len() >= MIN_FINGERPRINT_OVERLAP)
+            .ok_or_else(|| ApiError::ValidationError("invalid fingerprint".to_string()))?;
+
+        let mut rows = sqlx::query_as::<_, (Uuid, String)>(
+            "SELECT id, fingerprint FROM tracks WHERE fingerprint IS NOT NULL",
+        )
+        .fetch(&self.pool);
+
+        let mut scores: HashMap<Uuid, f32> = HashMap::new();
+        while let Some((track_id, stored)) = rows.try_next().await? {
+            let error_rate = decode_fingerprint(&stored)
+                .and_then(|candidate| fingerprint_bit_error_rate(&query, &candidate));
+            if let Some(error_rate) = error_rate.filter(|&rate| rate <= tolerance) {
+                scores.insert(track_id, error_rate);
+            }
+        }
+        drop(rows);
+        if scores.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<Uuid> = scores.keys().copied().collect();
+        let sql = format!("SELECT {} FROM tracks WHERE id = ANY($1)", TRACK_COLUMNS);
+        let tracks = sqlx::query_as::<_, Track>(&sql)
+            .bind(&ids)
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut matches: Vec<FingerprintMatch> = tracks
+            .into_iter()
+            .filter_map(|track| {
+                let bit_error_rate = *scores.get(&track.id)?;
+                Some(FingerprintMatch {
+                    track,
+                    bit_error_rate,
+                })
+            })
+            .collect();
+        matches.sort_by(|a, b| a.bit_error_rate.total_cmp(&b.bit_error_rate));
+        Ok(matches)
+    }
+
+    /// Delete tracks along with every row that depends on them
+    ///
+    /// Runs in a single transaction. Playlist entries are removed before the
+    /// tracks so the playlist stats trigger can still read their durations,
+    /// and the remaining playlist and queue positions are compacted so they
+    /// stay contiguous. Each user's `queue_state.current_index` is shifted
+    /// back by the number of removed items that preceded it. A removal is
+    /// queued in `search_index_outbox` for each deleted track.
+    #[allow(dead_code)]
+    pub async fn delete_many(&self, track_ids: &[Uuid]) -> ApiResult<DeletionReport> {
+        let mut report = DeletionReport::default();
+        if track_ids.is_empty() {
+            return Ok(report);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // Playlist entries, then close the gaps they leave behind
+        let playlist_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            DELETE FROM playlist_tracks
+            WHERE track_id = ANY($1)
+            RETURNING playlist_id
+            "#,
+        )
+        .bind(track_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        report.playlist_entries = playlist_ids.len() as u64;
+
+        if !playlist_ids.is_empty() {
+            sqlx::query(
+                r#"
+                WITH ordered AS (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY playlist_id ORDER BY position
+                    ) - 1 AS new_position
+                    FROM playlist_tracks
+                    WHERE playlist_id = ANY($1)
+                )
+                UPDATE playlist_tracks pt
+                SET position = o.new_position
+                FROM ordered o
+                WHERE pt.id = o.id AND pt.position <> o.new_position
+                "#,
+            )
+            .bind(&playlist_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // Queue entries, keeping each user's current index on the same item
+        let removed_queue_items: Vec<(Uuid, i32)> = sqlx::query_as(
+            r#"
+            DELETE FROM queue_items
+            WHERE track_id = ANY($1)
+            RETURNING user_id, position
+            "#,
+        )
+        .bind(track_ids)
+        .fetch_all(&mut *tx)
+        .await?;
+        report.queue_entries = removed_queue_items.len() as u64;
+
+        if !removed_queue_items.is_empty() {
+            let (user_ids, positions): (Vec<Uuid>, Vec<i32>) =
+                removed_queue_items.into_iter().unzip();
+
+            sqlx::query(
+                r#"
+                UPDATE queue_state qs
+                SET current_index = GREATEST(0, qs.current_index - (
+                        SELECT COUNT(*)::int
+                        FROM UNNEST($1::uuid[], $2::int[]) AS r(user_id, position)
+                        WHERE r.user_id = qs.user_id AND r.position < qs.current_index
+                    )),
+                    updated_at = NOW()
+                WHERE qs.user_id = ANY($1)
+                "#,
+            )
+            .bind(&user_ids)
+            .bind(&positions)
+            .execute(&mut *tx)
+            .await?;
+
+            // Positions are unique per user and non-negative, so move the
+            // remaining items clear of their final slots before renumbering
+            sqlx::query(
+                r#"
+                UPDATE queue_items qi
+                SET position = qi.position + m.max_position + 1
+                FROM (
+                    SELECT user_id, MAX(position) AS max_position
+                    FROM queue_items
+                    WHERE user_id = ANY($1)
+                    GROUP BY user_id
+                ) m
+                WHERE qi.user_id = m.user_id
+                "#,
+            )
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query(
+                r#"
+                WITH ordered AS (
+                    SELECT id, ROW_NUMBER() OVER (
+                        PARTITION BY user_id ORDER BY position
+                    ) - 1 AS new_position
+                    FROM queue_items
+                    WHERE user_id = ANY($1)
+                )
+                UPDATE queue_items qi
+                SET position = o.new_position
+                FROM ordered o
+                WHERE qi.id = o.id
+                "#,
+            )
+            .bind(&user_ids)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        report.history_entries =
+            sqlx::query("DELETE FROM listening_history WHERE track_id = ANY($1)")
+                .bind(track_ids)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+        report.embeddings = sqlx::query("DELETE FROM track_embeddings WHERE track_id = ANY($1)")
+            .bind(track_ids)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // user_library is polymorphic, so it has no foreign key to cascade
+        report.library_entries =
+            sqlx::query("DELETE FROM user_library WHERE item_type = 'track' AND item_id = ANY($1)")
+                .bind(track_ids)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+
+        let deleted: Vec<Uuid> =
+            sqlx::query_scalar("DELETE FROM tracks WHERE id = ANY($1) RETURNING id")
+                .bind(track_ids)
+                .fetch_all(&mut *tx)
+                .await?;
+        report.tracks = deleted.len() as u64;
+
+        // Committed with the delete, so the search index can't miss it
+        sqlx::query(
+            r#"
+            INSERT INTO search_index_outbox (track_id, action)
+            SELECT id, 'delete' FROM UNNEST($1::uuid[]) AS id
+            "#,
+        )
+        .bind(&deleted)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(report)
+    }
+}
+
+/// Sub-fingerprints two fingerprints must share before they're compared
+const MIN_FINGERPRINT_OVERLAP: usize = 16;
+
+/// Decode a stored fingerprint into its 32-bit sub-fingerprints
+fn decode_fingerprint(encoded: &str) -> Option<Vec<u32>> {
+    if !encoded.len().is_multiple_of(8) || !encoded.is_ascii() {
+        return None;
+    }
+    (0..encoded.len())
+        .step_by(8)
+        .map(|i| u32::from_str_radix(&encoded[i..i + 8], 16).ok())
+        .collect()
+}
+
+/// Fraction of differing bits over the sub-fingerprints both share, or
+/// `None` if they share too few to compare
+fn fingerprint_bit_error_rate(a: &[u32], b: &[u32]) -> Option<f32> {
+    let overlap = a.len().min(b.len());
+    if overlap < MIN_FINGERPRINT_OVERLAP {
+        return None;
+    }
+    let differing: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+    Some(differing as f32 / (overlap * 32) as f32)
+}
+
+/// A track found by [`TrackRepository::find_by_fingerprint`]
+#[allow(dead_code)]
+#[derive(Debug)]
+pub struct FingerprintMatch {
+    pub track: Track,
+    /// Fraction of differing fingerprint bits; 0.0 is identical
+    pub bit_error_rate: f32,
+}
+
+/// Number of rows removed by [`TrackRepository::delete_many`], per table
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DeletionReport {
+    pub tracks: u64,
+    pub embeddings: u64,
+    pub playlist_entries: u64,
+    pub queue_entries: u64,
+    /// Listening history (scrobble) rows
+    pub history_entries: u64,
+    /// Liked-track entries in users' libraries
+    pub library_entries: u64,
+}
+
+/// A play from a user's listening history, with the played track
+#[derive(Debug, sqlx::FromRow)]
+pub struct RecentlyPlayedRow {
+    #[sqlx(flatten)]
+    pub track: Track,
+    pub played_at: DateTime<Utc>,
+}
+
+/// A canonical track and the tracks flagged as its duplicates
+#[derive(Debug)]
+pub struct DuplicateTracks {
+    pub canonical: Track,
+    pub duplicates: Vec<Track>,
+}
+
+/// A flagged duplicate with the canonical track it points at
+#[derive(Debug, sqlx::FromRow)]
+struct DuplicateRow {
+    #[sqlx(flatten)]
+    track: Track,
+    duplicate_of: Uuid,
 }
 
 /// Track info needed for scrobbling to external services
@@ -239,3 +934,60 @@ pub struct TrackScrobbleInfo {
     pub release_mbid: Option<String>,
     pub artist_mbid: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audio_feature_predicates_empty() {
+        let (sql, values) = audio_feature_predicates(&[]);
+        assert_eq!(sql, "TRUE");
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn test_audio_feature_predicates_ignore_unset_bounds() {
+        let (sql, values) = audio_feature_predicates(&[
+            AudioFeatureBound {
+                field: AudioFeatureField::Tempo,
+                min: Some(120.0),
+                max: Some(130.0),
+            },
+            AudioFeatureBound {
+                field: AudioFeatureField::Energy,
+                min: Some(0.8),
+                max: None,
+            },
+            AudioFeatureBound {
+                field: AudioFeatureField::Valence,
+                min: None,
+                max: None,
+            },
+        ]);
+
+        assert_eq!(
+            sql,
+            "(audio_features->>'bpm')::float8 >= $1 \
+             AND (audio_features->>'bpm')::float8 <= $2 \
+             AND (audio_features->>'energy')::float8 >= $3"
+        );
+        assert_eq!(values, vec![120.0, 130.0, 0.8]);
+    }
+
+    #[test]
+    fn test_fingerprint_bit_error_rate() {
+        let a = "00000000".repeat(MIN_FINGERPRINT_OVERLAP);
+        let b = "0000000f".repeat(MIN_FINGERPRINT_OVERLAP + 2);
+        let a = decode_fingerprint(&a).unwrap();
+        let b = decode_fingerprint(&b).unwrap();
+
+        // Four of every 32 bits differ over the shared prefix
+        assert_eq!(fingerprint_bit_error_rate(&a, &b), Some(0.125));
+        assert_eq!(fingerprint_bit_error_rate(&a, &a), Some(0.0));
+        assert_eq!(fingerprint_bit_error_rate(&a[..4], &b), None);
+
+        assert!(decode_fingerprint("0000000").is_none());
+        assert!(decode_fingerprint("xyz00000").is_none());
+    }
+}
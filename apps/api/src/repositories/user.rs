@@ -6,7 +6,9 @@
 use sqlx::PgPool;
 use uuid::Uuid;
 
-use crate::models::user::User;
+use crate::models::user::{
+    DevicePrefs, EffectiveDevicePrefs, SimilarityWeights, User, UserPreferences,
+};
 
 /// Repository for user database operations
 ///
@@ -162,6 +164,144 @@ impl UserRepository {
         Ok(())
     }
 
+    /// Get the audio preferences in effect for one of a user's devices
+    ///
+    /// Device overrides take precedence over the global preferences.
+    ///
+    /// # Returns
+    /// * `Ok(Some(EffectiveDevicePrefs))` - If the user exists
+    /// * `Ok(None)` - If no user with the given ID exists
+    pub async fn get_device_prefs(
+        &self,
+        user_id: Uuid,
+        device_id: &str,
+    ) -> Result<Option<EffectiveDevicePrefs>, sqlx::Error> {
+        let preferences: Option<sqlx::types::Json<UserPreferences>> =
+            sqlx::query_scalar("SELECT preferences FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(preferences.map(|prefs| prefs.0.device_prefs(device_id)))
+    }
+
+    /// Store the audio overrides for one of a user's devices
+    ///
+    /// Only the given device's entry is replaced, in a single statement, so
+    /// concurrent updates to other devices or global preferences are kept.
+    /// Empty overrides remove the device's entry.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If the user exists and was updated
+    /// * `Ok(false)` - If no user with the given ID exists
+    pub async fn set_device_prefs(
+        &self,
+        user_id: Uuid,
+        device_id: &str,
+        prefs: &DevicePrefs,
+    ) -> Result<bool, sqlx::Error> {
+        let result = if prefs.is_empty() {
+            sqlx::query(
+                r#"
+                UPDATE users
+                SET preferences = preferences || jsonb_build_object(
+                        'device_overrides',
+                        COALESCE(preferences->'device_overrides', '{}'::jsonb) - $2::text
+                    ),
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(user_id)
+            .bind(device_id)
+            .execute(&self.pool)
+            .await?
+        } else {
+            sqlx::query(
+                r#"
+                UPDATE users
+                SET preferences = preferences || jsonb_build_object(
+                        'device_overrides',
+                        COALESCE(preferences->'device_overrides', '{}'::jsonb)
+                            || jsonb_build_object($2::text, $3::jsonb)
+                    ),
+                    updated_at = NOW()
+                WHERE id = $1
+                "#,
+            )
+            .bind(user_id)
+            .bind(device_id)
+            .bind(sqlx::types::Json(prefs))
+            .execute(&self.pool)
+            .await?
+        };
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Store a user's preferred similarity weights
+    ///
+    /// Only the `similarity_weights` key is replaced, so concurrent updates to
+    /// other preferences are kept. `None` removes the stored weights.
+    ///
+    /// # Returns
+    /// * `Ok(true)` - If the user exists and was updated
+    /// * `Ok(false)` - If no user with the given ID exists
+    pub async fn set_similarity_weights(
+        &self,
+        user_id: Uuid,
+        weights: Option<&SimilarityWeights>,
+    ) -> Result<bool, sqlx::Error> {
+        let result = match weights {
+            Some(weights) => {
+                sqlx::query(
+                    r#"
+                    UPDATE users
+                    SET preferences = preferences
+                            || jsonb_build_object('similarity_weights', $2::jsonb),
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(user_id)
+                .bind(sqlx::types::Json(weights))
+                .execute(&self.pool)
+                .await?
+            }
+            None => {
+                sqlx::query(
+                    r#"
+                    UPDATE users
+                    SET preferences = preferences - 'similarity_weights',
+                        updated_at = NOW()
+                    WHERE id = $1
+                    "#,
+                )
+                .bind(user_id)
+                .execute(&self.pool)
+                .await?
+            }
+        };
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get a user's preferred similarity weights
+    ///
+    /// Returns `None` if the user has none stored or doesn't exist.
+    pub async fn get_similarity_weights(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<SimilarityWeights>, sqlx::Error> {
+        let weights: Option<Option<sqlx::types::Json<SimilarityWeights>>> =
+            sqlx::query_scalar("SELECT preferences->'similarity_weights' FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(weights.flatten().map(|weights| weights.0))
+    }
+
     /// Update user's ListenBrainz token
     ///
     /// # Arguments
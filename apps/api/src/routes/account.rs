@@ -0,0 +1,99 @@
+//! Account HTTP route handlers
+//!
+//! This module provides endpoints for a user's own account:
+//! - `GET /account/export` - Download everything stored about the user as JSON
+//!
+//! The export is streamed row by row from the database, so its size is not
+//! limited by server memory. Exports read the user's entire history, so they
+//! are rate limited per user.
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use sqlx::PgPool;
+
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::middleware::AuthUser;
+use crate::services::data_export::export_user_data;
+
+/// Filename suggested to browsers for the export download
+const EXPORT_FILENAME: &str = "resonance-export.json";
+
+/// Shared application state for account handlers
+#[derive(Clone)]
+pub struct AccountState {
+    /// Database connection pool
+    pub pool: PgPool,
+    /// Rate limiter for exports; `None` disables rate limiting (no Redis)
+    pub rate_limiter: Option<RateLimiter>,
+    /// Rate limit applied to exports, keyed by user ID
+    pub export_limit: RateLimitConfig,
+}
+
+impl AccountState {
+    /// Create a new AccountState instance with the default export rate limit
+    pub fn new(pool: PgPool, rate_limiter: Option<RateLimiter>) -> Self {
+        Self {
+            pool,
+            rate_limiter,
+            export_limit: RateLimitConfig::data_export(),
+        }
+    }
+}
+
+/// Create the account router
+///
+/// # Routes
+/// - `GET /export` - Stream the user's data export
+pub fn account_router(state: AccountState) -> Router {
+    Router::new()
+        .route("/export", get(export_data))
+        .with_state(state)
+}
+
+/// Stream everything stored about the current user
+///
+/// # Request
+/// - Method: GET
+/// - Path: /account/export
+/// - Headers:
+///   - Authorization: Bearer <token> (required)
+///
+/// # Response
+/// - 200 OK: JSON document with the user's profile, preferences, playlists,
+///   listening history and chat conversations, sent as an attachment
+/// - 401 Unauthorized: Missing or invalid token
+/// - 429 Too Many Requests: More than 3 exports in the last hour
+async fn export_data(State(state): State<AccountState>, auth: AuthUser) -> ApiResult<Response> {
+    let user_id = auth.claims.sub;
+
+    if let Some(limiter) = &state.rate_limiter {
+        if let Err(retry_after) = limiter
+            .check(&user_id.to_string(), &state.export_limit)
+            .await
+        {
+            tracing::warn!(user_id = %user_id, retry_after, "Data export rate limit exceeded");
+            return Err(ApiError::RateLimited { retry_after });
+        }
+    }
+
+    let export = export_user_data(&state.pool, user_id).await?;
+    tracing::info!(user_id = %user_id, "User data export started");
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{EXPORT_FILENAME}\""),
+        )
+        .header(header::CACHE_CONTROL, "private, no-store")
+        .body(Body::from_stream(export))
+        .expect("Failed to build response"))
+}
@@ -0,0 +1,273 @@
+//! Album art HTTP route handlers
+//!
+//! This module provides an endpoint for serving album cover art:
+//! - `GET /art/:album_id?size=256` - Square cover art thumbnail
+//!
+//! Features:
+//! - Resizing to an allowlist of square sizes (64, 128, 256, 512)
+//! - Disk cache of resized variants, keyed by the source file's size and mtime
+//! - Strong ETag and Last-Modified caching headers
+//! - Conditional request support (If-None-Match, If-Modified-Since)
+
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::Response,
+    routing::get,
+    Router,
+};
+use resonance_media::{resize_cover, ART_SIZES};
+use serde::Deserialize;
+use std::path::{Path as StdPath, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+use super::streaming::{format_http_date, is_cache_valid, validate_file_path};
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::AuthUser;
+use crate::repositories::AlbumRepository;
+
+/// Thumbnail size used when the `size` query parameter is omitted
+const DEFAULT_ART_SIZE: u32 = 256;
+
+/// Cache-Control for art responses
+///
+/// Cover art can change on rescan without the URL changing, so clients
+/// revalidate with the ETag once the max-age expires.
+const ART_CACHE_CONTROL: &str = "private, max-age=2592000";
+
+/// Query parameters for the album art route
+#[derive(Debug, Deserialize, Default)]
+pub struct ArtQuery {
+    /// Square size in pixels (64, 128, 256, 512)
+    pub size: Option<u32>,
+}
+
+/// Shared application state for album art handlers
+#[derive(Clone)]
+pub struct ArtState {
+    /// Album repository for database lookups
+    pub album_repo: Arc<AlbumRepository>,
+    /// Directories cover art may be read from: the music library roots and
+    /// the directory the worker writes extracted embedded art to
+    pub art_roots: Vec<PathBuf>,
+    /// Directory where resized variants are cached
+    pub cache_dir: PathBuf,
+}
+
+impl ArtState {
+    /// Create a new ArtState instance
+    pub fn new(album_repo: AlbumRepository, art_roots: Vec<PathBuf>, cache_dir: PathBuf) -> Self {
+        Self {
+            album_repo: Arc::new(album_repo),
+            art_roots,
+            cache_dir,
+        }
+    }
+}
+
+/// Create the album art router
+///
+/// # Routes
+/// - `GET /:album_id` - Get resized cover art for an album
+pub fn art_router(state: ArtState) -> Router {
+    Router::new()
+        .route("/:album_id", get(get_album_art))
+        .with_state(state)
+}
+
+/// Get resized cover art for an album
+///
+/// # Request
+/// - Method: GET
+/// - Path: /art/:album_id
+/// - Query Parameters:
+///   - size: Square size in pixels (64, 128, 256, 512) - optional, defaults to 256
+/// - Headers:
+///   - Authorization: Bearer <token> (required)
+///   - If-None-Match: <etag> (optional, for caching)
+///   - If-Modified-Since: <date> (optional, for caching)
+///
+/// # Response
+/// - 200 OK: JPEG image
+/// - 304 Not Modified: Cache is still valid
+/// - 400 Bad Request: Size not in the allowlist
+/// - 401 Unauthorized: Missing or invalid token
+/// - 404 Not Found: Album not found or has no cover art
+async fn get_album_art(
+    State(state): State<ArtState>,
+    _auth: AuthUser, // Validates authentication
+    Path(album_id): Path<Uuid>,
+    Query(query): Query<ArtQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    // 1. Validate the requested size before touching the database
+    let size = query.size.unwrap_or(DEFAULT_ART_SIZE);
+    if !ART_SIZES.contains(&size) {
+        return Err(ApiError::ValidationError(format!(
+            "Unsupported art size {}; allowed sizes are {:?}",
+            size, ART_SIZES
+        )));
+    }
+
+    // 2. Look up the album and its stored cover
+    let album = state
+        .album_repo
+        .find_by_id(album_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("album", album_id.to_string()))?;
+
+    let cover_path = album
+        .cover_art_path
+        .ok_or_else(|| ApiError::not_found("cover art", album_id.to_string()))?;
+
+    // 3. Validate and resolve the cover path
+    let source = validate_file_path(&cover_path, &state.art_roots)
+        .await
+        .map_err(|e| match e {
+            ApiError::AudioFileNotFound(_) => {
+                ApiError::not_found("cover art", album_id.to_string())
+            }
+            other => other,
+        })?;
+
+    let metadata = tokio::fs::metadata(&source).await.map_err(|e| {
+        tracing::warn!(error = %e, path = %source.display(), "Failed to read cover art metadata");
+        ApiError::not_found("cover art", album_id.to_string())
+    })?;
+
+    // 4. Generate caching headers from the source file and requested size
+    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+    let etag = generate_art_etag(size, metadata.len(), modified);
+    let last_modified = format_http_date(modified);
+
+    if is_cache_valid(&headers, &etag, modified) {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_MODIFIED)
+            .header(header::ETAG, &etag)
+            .header(header::LAST_MODIFIED, &last_modified)
+            .header(header::CACHE_CONTROL, ART_CACHE_CONTROL)
+            .body(Body::empty())
+            .expect("Failed to build response"));
+    }
+
+    // 5. Serve the cached variant, resizing on a miss
+    let cache_path =
+        state
+            .cache_dir
+            .join(cache_file_name(album_id, size, metadata.len(), modified));
+    let bytes = match tokio::fs::read(&cache_path).await {
+        Ok(bytes) => bytes,
+        Err(_) => resize_and_cache(&source, &cache_path, size).await?,
+    };
+
+    Ok(Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "image/jpeg")
+        .header(header::CONTENT_LENGTH, bytes.len())
+        .header(header::ETAG, &etag)
+        .header(header::LAST_MODIFIED, &last_modified)
+        .header(header::CACHE_CONTROL, ART_CACHE_CONTROL)
+        .body(Body::from(bytes))
+        .expect("Failed to build response"))
+}
+
+/// Resize the source image and write it to the cache
+///
+/// The variant is written to a temporary file and renamed into place so
+/// concurrent requests never read a partially written file. A failed cache
+/// write is logged and the resized image is still returned.
+async fn resize_and_cache(source: &StdPath, cache_path: &StdPath, size: u32) -> ApiResult<Vec<u8>> {
+    let original = tokio::fs::read(source).await.map_err(|e| {
+        tracing::error!(error = %e, path = %source.display(), "Failed to read cover art");
+        ApiError::Internal(format!("Failed to read cover art: {}", e))
+    })?;
+
+    let resized = tokio::task::spawn_blocking(move || resize_cover(&original, size))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Cover art resize task failed: {}", e)))?
+        .map_err(|e| {
+            tracing::warn!(error = %e, path = %source.display(), "Failed to resize cover art");
+            e
+        })?;
+
+    let temp_path = cache_path.with_extension(format!("{}.partial", Uuid::new_v4()));
+    let write_result = async {
+        if let Some(dir) = cache_path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        tokio::fs::write(&temp_path, &resized).await?;
+        tokio::fs::rename(&temp_path, cache_path).await
+    }
+    .await;
+
+    if let Err(e) = write_result {
+        tracing::warn!(error = %e, path = %cache_path.display(), "Failed to cache resized cover art");
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+
+    Ok(resized)
+}
+
+/// File name for a cached variant: `{album_id}_{size}_{source_len}_{mtime}.jpg`
+///
+/// Including the source size and mtime means a replaced cover is resized
+/// again rather than served stale from the cache.
+fn cache_file_name(album_id: Uuid, size: u32, source_len: u64, modified: SystemTime) -> String {
+    format!(
+        "{}_{}_{}_{}.jpg",
+        album_id,
+        size,
+        source_len,
+        mtime_secs(modified)
+    )
+}
+
+/// Generate a strong ETag for a resized variant
+///
+/// Resizing is deterministic, so the same source file and size always
+/// produce the same bytes. Format: `"{size}-{source_len}-{mtime_secs}"`
+fn generate_art_etag(size: u32, source_len: u64, modified: SystemTime) -> String {
+    format!("\"{}-{}-{}\"", size, source_len, mtime_secs(modified))
+}
+
+fn mtime_secs(modified: SystemTime) -> u64 {
+    modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_art_etag_is_strong_and_size_specific() {
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        let etag = generate_art_etag(256, 1234, modified);
+
+        assert_eq!(etag, "\"256-1234-1700000000\"");
+        assert!(!etag.starts_with("W/"));
+        assert_ne!(etag, generate_art_etag(128, 1234, modified));
+    }
+
+    #[test]
+    fn test_cache_file_name_changes_with_source() {
+        let album_id = Uuid::nil();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+
+        assert_eq!(
+            cache_file_name(album_id, 64, 10, modified),
+            "00000000-0000-0000-0000-000000000000_64_10_100.jpg"
+        );
+        assert_ne!(
+            cache_file_name(album_id, 64, 10, modified),
+            cache_file_name(album_id, 64, 10, modified + Duration::from_secs(1))
+        );
+    }
+}
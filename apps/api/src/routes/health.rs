@@ -81,7 +81,7 @@ async fn readiness_probe(State(state): State<HealthState>) -> impl IntoResponse
     let response = state
         .health_service
         .check_all(
-            &config.database().url,
+            config.database().url.expose(),
             &config.redis().connection_url(),
             &config.meilisearch_url,
             &config.meilisearch_key,
@@ -0,0 +1,157 @@
+//! Prometheus metrics HTTP route
+//!
+//! Provides the scrape endpoint for the metrics registry:
+//! - `GET /metrics` - Prometheus text exposition format
+//!
+//! When `METRICS_INTERNAL_ONLY` is enabled, scrapes are only accepted from
+//! loopback and private network peers. The check uses the socket address,
+//! never forwarding headers, so it cannot be bypassed by spoofing them.
+
+use axum::{
+    extract::{ConnectInfo, Extension, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use std::net::SocketAddr;
+
+use crate::error::ApiError;
+use crate::middleware::metrics::METRICS_PATH;
+use crate::middleware::rate_limit::is_private_or_localhost;
+use crate::services::metrics::Metrics;
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// State for the metrics route
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MetricsState {
+    /// Only serve scrapes from loopback or private network peers
+    pub internal_only: bool,
+}
+
+/// Create the metrics router
+///
+/// Expects the [`Metrics`] registry as a request extension.
+pub fn metrics_router(state: MetricsState) -> Router {
+    Router::new()
+        .route(METRICS_PATH, get(metrics_handler))
+        .with_state(state)
+}
+
+/// Render the metrics registry
+///
+/// # Response
+/// - 200 OK with the Prometheus text format
+/// - 403 Forbidden if restricted to internal peers and the peer is external
+async fn metrics_handler(
+    State(state): State<MetricsState>,
+    Extension(metrics): Extension<Metrics>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+) -> Response {
+    if state.internal_only {
+        let internal = connect_info
+            .map(|ConnectInfo(addr)| is_private_or_localhost(&addr.ip()))
+            .unwrap_or(false);
+        if !internal {
+            return ApiError::Forbidden("metrics are only available internally".to_string())
+                .into_response();
+        }
+    }
+
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)],
+        metrics.render(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::metrics::track_http_metrics;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn create_test_app(metrics: Metrics, internal_only: bool) -> Router {
+        Router::new()
+            .route("/", get(|| async { "home" }))
+            .merge(metrics_router(MetricsState { internal_only }))
+            .layer(axum::middleware::from_fn_with_state(
+                metrics.clone(),
+                track_http_metrics,
+            ))
+            .layer(Extension(metrics))
+    }
+
+    fn request(path: &str, peer: Option<&str>) -> Request<Body> {
+        let mut request = Request::builder().uri(path).body(Body::empty()).unwrap();
+        if let Some(peer) = peer {
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+        }
+        request
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_request_counter_increments_after_request() {
+        let metrics = Metrics::new();
+        let app = create_test_app(metrics.clone(), false);
+
+        app.clone().oneshot(request("/", None)).await.unwrap();
+        let response = app.oneshot(request("/metrics", None)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            PROMETHEUS_CONTENT_TYPE
+        );
+        let body = body_string(response).await;
+        assert!(body.contains("resonance_http_requests_total{route=\"/\",status=\"200\"} 1"));
+        assert!(!body.contains("route=\"/metrics\""));
+    }
+
+    #[tokio::test]
+    async fn test_internal_only_allows_private_peers() {
+        let app = create_test_app(Metrics::new(), true);
+
+        let loopback = app
+            .clone()
+            .oneshot(request("/metrics", Some("127.0.0.1:9000")))
+            .await
+            .unwrap();
+        let private = app
+            .oneshot(request("/metrics", Some("10.0.0.5:9000")))
+            .await
+            .unwrap();
+
+        assert_eq!(loopback.status(), StatusCode::OK);
+        assert_eq!(private.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_internal_only_rejects_external_peers() {
+        let app = create_test_app(Metrics::new(), true);
+
+        let external = app
+            .clone()
+            .oneshot(request("/metrics", Some("203.0.113.7:9000")))
+            .await
+            .unwrap();
+        let unknown = app.oneshot(request("/metrics", None)).await.unwrap();
+
+        assert_eq!(external.status(), StatusCode::FORBIDDEN);
+        assert_eq!(unknown.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -1,17 +1,26 @@
 //! HTTP route handlers for the Resonance API
 //!
 //! This module contains all REST endpoint handlers including:
+//! - Account endpoints (personal data export)
 //! - Authentication endpoints
 //! - Audio streaming endpoints
+//! - Album art endpoints
 //! - Lidarr webhook handlers
 //! - Health check and status endpoints
+//! - Prometheus metrics endpoint
 
+pub mod account;
+pub mod art;
 pub mod auth;
 pub mod health;
+pub mod metrics;
 pub mod streaming;
 
+pub use account::{account_router, AccountState};
+pub use art::{art_router, ArtState};
 pub use auth::{auth_router, auth_router_with_rate_limiting, AuthState};
 pub use health::{health_router, HealthState};
+pub use metrics::{metrics_router, MetricsState};
 pub use streaming::{streaming_router, StreamingState};
 
 // Future modules:
@@ -3,6 +3,11 @@
 //! This module provides endpoints for streaming audio files:
 //! - `GET /stream/:track_id` - Stream audio file with HTTP range request support
 //! - `HEAD /stream/:track_id` - Get file metadata without body
+//! - `GET /stream/formats` - List transcoding formats the server supports
+//! - `GET /stream/shared/:token` - Stream a track through a signed, expiring
+//!   share link, without a logged-in session
+//! - `GET /stream/:track_id/lyrics` - Fetch a track's lyrics for the player,
+//!   as JSON or LRC
 //!
 //! Features:
 //! - RFC 7233 compliant range request handling
@@ -10,40 +15,277 @@
 //! - Async streaming without loading entire file into memory
 //! - ETag and Last-Modified caching headers
 //! - Conditional request support (If-None-Match, If-Modified-Since)
+//! - Raw streaming (`?raw=true` or `format=original`) of the original file for
+//!   clients that decode it natively, limited to a configured format allowlist
+//! - `Accept` header negotiation of the transcode format when no `format` is
+//!   given, falling back to a configured default format
+//! - Loudness normalization of transcodes (`normalize=true`), using the
+//!   track's stored integrated loudness when it has been analyzed
 
 use axum::{
     body::Body,
     extract::{Path, Query, State},
-    http::{header, HeaderMap, StatusCode},
+    http::{header, response, HeaderMap, HeaderValue, StatusCode},
     response::Response,
     routing::get,
-    Router,
+    Extension, Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::path::{Path as StdPath, PathBuf};
 use std::sync::Arc;
-use std::time::SystemTime;
+use std::time::{Duration, SystemTime};
 use tokio::fs::File;
-use tokio::io::{AsyncReadExt, AsyncSeekExt, SeekFrom};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, SeekFrom};
 use tokio_util::io::ReaderStream;
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::AuthUser;
-use crate::models::AudioFormat;
+use crate::models::track::AudioFeatures;
+use crate::models::{AudioFormat, SyncedLyricLine, Track};
 use crate::repositories::TrackRepository;
-use crate::services::transcoder::TranscodeError;
-use crate::services::{TranscodeFormat, TranscodeOptions, TranscoderService};
+use crate::services::auth::AuthService;
+use crate::services::transcoder::{CachedOutput, FormatCapability, TranscodeError};
+use crate::services::{Normalization, TranscodeFormat, TranscodeOptions, TranscoderService};
+
+/// Original formats servable untranscoded by default
+pub const DEFAULT_RAW_STREAM_FORMATS: &[AudioFormat] = &[
+    AudioFormat::Flac,
+    AudioFormat::Mp3,
+    AudioFormat::Aac,
+    AudioFormat::Opus,
+    AudioFormat::Ogg,
+];
+
+/// Default size of each chunk read from an audio file or transcoder (64 KiB)
+///
+/// Larger chunks mean fewer syscalls and body frames on fast links, at the
+/// cost of more latency before the first byte reaches the client.
+pub const DEFAULT_STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Smallest configurable stream chunk size (4 KiB)
+pub const MIN_STREAM_CHUNK_BYTES: usize = 4 * 1024;
+
+/// Largest configurable stream chunk size (4 MiB)
+pub const MAX_STREAM_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// `format` value requesting the original file (same as `raw=true`)
+const ORIGINAL_FORMAT: &str = "original";
 
 /// Query parameters for transcoding options
 #[derive(Debug, Deserialize, Default)]
 pub struct TranscodeQuery {
-    /// Target audio format (mp3, aac, opus, flac)
+    /// Target audio format (mp3, aac, opus, flac, or original)
     /// If not specified, streams the original file without transcoding
     pub format: Option<String>,
-    /// Target bitrate in kbps (64, 96, 128, 192, 256, 320)
+    /// Target bitrate in kbps, within the format's accepted range
+    /// (MP3 8-320, AAC 16-512, Opus 6-510; ignored for FLAC)
     /// If not specified, uses the format's default bitrate
     pub bitrate: Option<u32>,
+    /// FLAC compression level, 0-8 (only with `format=flac`)
+    /// Re-encodes losslessly; higher levels are smaller but slower to encode
+    pub compression: Option<u8>,
+    /// Serve the original file, bypassing the transcoder entirely
+    /// Only allowed for formats in the server's raw streaming allowlist
+    #[serde(default)]
+    pub raw: bool,
+    /// Start the transcode this many seconds into the track
+    /// Requires `format`; ignored when a Range header is present
+    pub t: Option<f64>,
+    /// Normalize the transcode's loudness
+    /// Requires `format`; uses the track's stored integrated loudness when
+    /// available and measures while transcoding otherwise
+    #[serde(default)]
+    pub normalize: bool,
+}
+
+impl TranscodeQuery {
+    /// Whether the client explicitly asked for the original file
+    ///
+    /// # Errors
+    /// Returns `ValidationError` if `raw=true` is combined with a transcode
+    /// format, a bitrate or a compression level
+    pub fn wants_raw(&self) -> ApiResult<bool> {
+        let original = self
+            .format
+            .as_deref()
+            .is_some_and(|f| f.eq_ignore_ascii_case(ORIGINAL_FORMAT));
+        let transcode = self.format.is_some() && !original;
+
+        if self.raw && transcode {
+            return Err(ApiError::ValidationError(
+                "`raw` cannot be combined with a transcode `format`".to_string(),
+            ));
+        }
+        let raw = self.raw || original;
+        if raw && self.bitrate.is_some() {
+            return Err(ApiError::ValidationError(
+                "`bitrate` cannot be used when streaming the original file".to_string(),
+            ));
+        }
+        if raw && self.compression.is_some() {
+            return Err(ApiError::ValidationError(
+                "`compression` cannot be used when streaming the original file".to_string(),
+            ));
+        }
+        Ok(raw)
+    }
+}
+
+/// Validate a `t` seek offset against the track duration
+///
+/// # Errors
+/// Returns `ValidationError` if `t` is negative, not finite, or not before
+/// the end of the track
+pub fn validate_seek_offset(t: f64, duration_ms: i32) -> ApiResult<Duration> {
+    let duration_secs = f64::from(duration_ms.max(0)) / 1000.0;
+    if !t.is_finite() || t < 0.0 || t >= duration_secs {
+        return Err(ApiError::ValidationError(format!(
+            "`t` must be between 0 and the track duration ({:.3}s)",
+            duration_secs
+        )));
+    }
+    Ok(Duration::from_secs_f64(t))
+}
+
+/// Check that a track's original format may be streamed raw
+///
+/// `transcodable` lists the formats the transcoder can produce; the error
+/// suggests one of them, preferring FLAC for lossless originals.
+///
+/// # Errors
+/// Returns `UnsupportedFormat` if `format` is not in `allowed`
+pub fn check_raw_stream(
+    format: AudioFormat,
+    allowed: &[AudioFormat],
+    transcodable: &[TranscodeFormat],
+) -> ApiResult<()> {
+    if allowed.contains(&format) {
+        return Ok(());
+    }
+
+    let preferred = if format.is_lossless() {
+        TranscodeFormat::Flac
+    } else {
+        TranscodeFormat::Opus
+    };
+    let suggestion = if transcodable.contains(&preferred) {
+        Some(preferred)
+    } else {
+        transcodable.first().copied()
+    };
+
+    let name = format!("{:?}", format).to_uppercase();
+    Err(ApiError::UnsupportedFormat(match suggestion {
+        Some(suggested) => format!(
+            "raw streaming of {} files is not allowed on this server; request a transcoded stream instead, e.g. format={}",
+            name,
+            suggested.extension()
+        ),
+        None => format!(
+            "raw streaming of {} files is not allowed on this server",
+            name
+        ),
+    }))
+}
+
+/// Format picked by `Accept` negotiation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegotiatedFormat {
+    /// Serve the original file
+    Original,
+    /// Transcode to this format
+    Transcode(TranscodeFormat),
+}
+
+/// MIME types a client may use to ask for a transcode format
+fn accepted_mime_types(format: TranscodeFormat) -> &'static [&'static str] {
+    match format {
+        TranscodeFormat::Mp3 => &["audio/mpeg", "audio/mp3"],
+        TranscodeFormat::Aac => &["audio/aac", "audio/mp4"],
+        TranscodeFormat::Opus => &["audio/opus", "audio/ogg"],
+        TranscodeFormat::Flac => &["audio/flac", "audio/x-flac"],
+    }
+}
+
+/// Parse an `Accept` header into lowercased media ranges and their quality
+///
+/// Malformed quality values count as 1, per common browser behaviour.
+fn parse_accept(accept: &str) -> Vec<(String, f32)> {
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';');
+            let media_range = parts.next()?.trim().to_ascii_lowercase();
+            if media_range.is_empty() {
+                return None;
+            }
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .map_or(1.0, |q| q.clamp(0.0, 1.0));
+            Some((media_range, quality))
+        })
+        .collect()
+}
+
+/// Quality the parsed `Accept` ranges give `mime`, from the most specific
+/// matching range (`type/subtype` over `type/*` over `*/*`), or 0
+fn accept_quality(ranges: &[(String, f32)], mime: &str) -> f32 {
+    let main_type = mime.split('/').next().unwrap_or(mime);
+    let specificity = |range: &str| {
+        if range == mime {
+            Some(2)
+        } else if range.strip_suffix("/*") == Some(main_type) {
+            Some(1)
+        } else if range == "*/*" {
+            Some(0)
+        } else {
+            None
+        }
+    };
+    ranges
+        .iter()
+        .filter_map(|(range, q)| specificity(range).map(|rank| (rank, *q)))
+        .max_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)))
+        .map_or(0.0, |(_, q)| q)
+}
+
+/// Pick the format with the highest `Accept` quality
+///
+/// Candidates are the original file (by its content type) and each
+/// transcode format in `available`. Ties go to the original, then to the
+/// earlier format in `available`. Returns `None` when nothing is
+/// acceptable, so the caller can fall back to its default.
+pub fn negotiate_format(
+    accept: &str,
+    original_content_type: &str,
+    available: &[TranscodeFormat],
+) -> Option<NegotiatedFormat> {
+    let ranges = parse_accept(accept);
+    let original = (
+        NegotiatedFormat::Original,
+        accept_quality(&ranges, original_content_type),
+    );
+    let transcodes = available.iter().map(|&format| {
+        let quality = accepted_mime_types(format)
+            .iter()
+            .map(|mime| accept_quality(&ranges, mime))
+            .fold(0.0, f32::max);
+        (NegotiatedFormat::Transcode(format), quality)
+    });
+
+    std::iter::once(original)
+        .chain(transcodes)
+        .filter(|(_, quality)| *quality > 0.0)
+        .fold(
+            None,
+            |best: Option<(NegotiatedFormat, f32)>, candidate| match best {
+                Some(best) if best.1 >= candidate.1 => Some(best),
+                _ => Some(candidate),
+            },
+        )
+        .map(|(format, _)| format)
 }
 
 /// Shared application state for streaming handlers
@@ -51,44 +293,173 @@ pub struct TranscodeQuery {
 pub struct StreamingState {
     /// Track repository for database lookups
     pub track_repo: Arc<TrackRepository>,
-    /// Base path to the music library
-    pub music_library_path: PathBuf,
+    /// Music library root directories
+    pub music_roots: Vec<PathBuf>,
     /// Transcoder service for on-the-fly format conversion
     pub transcoder: TranscoderService,
+    /// Original formats that may be streamed with `raw=true`
+    pub raw_formats: Vec<AudioFormat>,
+    /// Size of each chunk read from audio files
+    pub chunk_bytes: usize,
+    /// Transcode format used when the `Accept` header allows nothing we
+    /// can serve (`None` serves the original file)
+    pub default_format: Option<TranscodeFormat>,
 }
 
 impl StreamingState {
     /// Create a new StreamingState instance
-    pub fn new(track_repo: TrackRepository, music_library_path: PathBuf) -> Self {
+    pub fn new(track_repo: TrackRepository, music_roots: Vec<PathBuf>) -> Self {
         Self {
             track_repo: Arc::new(track_repo),
-            music_library_path,
+            music_roots,
             transcoder: TranscoderService::new(),
+            raw_formats: DEFAULT_RAW_STREAM_FORMATS.to_vec(),
+            chunk_bytes: DEFAULT_STREAM_CHUNK_BYTES,
+            default_format: None,
+        }
+    }
+
+    /// Use a custom transcoder service (e.g. with caching enabled)
+    pub fn with_transcoder(mut self, transcoder: TranscoderService) -> Self {
+        self.transcoder = transcoder;
+        self
+    }
+
+    /// Set the original formats that may be streamed untranscoded
+    pub fn with_raw_formats(mut self, formats: Vec<AudioFormat>) -> Self {
+        self.raw_formats = formats;
+        self
+    }
+
+    /// Set the size of each chunk read from audio files
+    ///
+    /// Live transcodes read FFmpeg's output in chunks configured on the
+    /// transcoder (see [`TranscoderService::with_chunk_bytes`]).
+    pub fn with_chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+
+    /// Set the transcode format used when `Accept` negotiation finds no
+    /// acceptable format
+    pub fn with_default_format(mut self, format: Option<TranscodeFormat>) -> Self {
+        self.default_format = format;
+        self
+    }
+}
+
+/// Response body for the supported formats endpoint
+#[derive(Debug, Serialize)]
+pub struct FormatsResponse {
+    /// Formats that can be requested via the `format` query parameter
+    pub formats: Vec<FormatCapability>,
+}
+
+/// Query parameters for the lyrics endpoint
+#[derive(Debug, Deserialize, Default)]
+pub struct LyricsQuery {
+    /// Response format: `json` (default) or `lrc`
+    pub format: Option<String>,
+}
+
+/// Output format of the lyrics endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsFormat {
+    /// [`LyricsResponse`] as JSON
+    Json,
+    /// LRC text, one `[mm:ss.xx]` timestamped line per lyric
+    Lrc,
+}
+
+impl LyricsFormat {
+    /// Parse the `format` query parameter, defaulting to JSON
+    ///
+    /// # Errors
+    /// Returns `ValidationError` for anything other than `json` or `lrc`
+    pub fn parse(format: Option<&str>) -> ApiResult<Self> {
+        match format {
+            None => Ok(Self::Json),
+            Some(f) if f.eq_ignore_ascii_case("json") => Ok(Self::Json),
+            Some(f) if f.eq_ignore_ascii_case("lrc") => Ok(Self::Lrc),
+            Some(f) => Err(ApiError::ValidationError(format!(
+                "unsupported lyrics format '{}', expected json or lrc",
+                f
+            ))),
         }
     }
 }
 
+/// Response body for the lyrics endpoint
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LyricsResponse {
+    /// Track the lyrics belong to
+    pub track_id: Uuid,
+    /// Whether `lines` holds time-synced lyrics
+    pub synced: bool,
+    /// Time-synced lines in playback order (empty when not synced)
+    pub lines: Vec<SyncedLyricLine>,
+    /// Plain lyrics text, when stored
+    pub text: Option<String>,
+}
+
 /// Create the streaming router
 ///
 /// # Routes
+/// - `GET /formats` - List supported transcoding formats
 /// - `GET /:track_id` - Stream audio file for a track
 /// - `HEAD /:track_id` - Get file metadata without streaming body
+/// - `GET /:track_id/lyrics` - Fetch a track's lyrics
+/// - `GET /shared/:token` - Stream the track named by a signed share token
 pub fn streaming_router(state: StreamingState) -> Router {
     Router::new()
-        .route("/{track_id}", get(stream_track).head(head_track))
+        .route("/formats", get(list_formats))
+        .route("/shared/:token", get(stream_shared_track))
+        .route("/:track_id", get(stream_track).head(head_track))
+        .route("/:track_id/lyrics", get(track_lyrics))
         .with_state(state)
 }
 
+/// List the transcoding formats supported by the installed backend
+///
+/// # Request
+/// - Method: GET
+/// - Path: /stream/formats
+/// - Headers:
+///   - Authorization: Bearer <token> (required)
+///
+/// # Response
+/// - 200 OK: `{ "formats": [...] }` with one entry per available format,
+///   including its MIME type and accepted bitrate range
+/// - 401 Unauthorized: Missing or invalid token
+async fn list_formats(
+    State(state): State<StreamingState>,
+    _auth: AuthUser, // Validates authentication
+) -> Json<FormatsResponse> {
+    let formats = state.transcoder.probe_formats().await.to_vec();
+    Json(FormatsResponse { formats })
+}
+
 /// Stream audio file for a track
 ///
 /// # Request
 /// - Method: GET
 /// - Path: /stream/:track_id
 /// - Query Parameters:
-///   - format: Target format (mp3, aac, opus, flac) - optional, for transcoding
-///   - bitrate: Target bitrate in kbps (64, 96, 128, 192, 256, 320) - optional
+///   - format: Target format (mp3, aac, opus, flac) - optional, for transcoding;
+///     `original` is the same as `raw=true`
+///   - bitrate: Target bitrate in kbps, within the format's range - optional
+///   - compression: FLAC compression level 0-8 - optional, only with
+///     `format=flac`; re-encodes losslessly at that level
+///   - raw: Serve the original file untranscoded - optional, only for formats
+///     in the server's allowlist
+///   - t: Start offset in seconds - optional, requires `format`; returns a
+///     fresh transcode starting at that time (not cached)
 /// - Headers:
 ///   - Authorization: Bearer <token> (required)
+///   - Accept: audio MIME types with q-values (optional) - without `format`,
+///     `raw` or a Range header, picks the best of the original file and the
+///     supported transcode formats; if none is acceptable, the server's
+///     default format (or the original file) is served
 ///   - Range: bytes=START-END (optional, for seeking - not supported with transcoding)
 ///   - If-None-Match: <etag> (optional, for caching)
 ///   - If-Modified-Since: <date> (optional, for caching)
@@ -97,6 +468,7 @@ pub fn streaming_router(state: StreamingState) -> Router {
 /// - 200 OK: Full audio file stream (or transcoded stream)
 /// - 206 Partial Content: Partial file for range requests (passthrough only)
 /// - 304 Not Modified: Cache is still valid
+/// - 400 Bad Request: Raw streaming requested for a format not in the allowlist
 /// - 401 Unauthorized: Missing or invalid token
 /// - 404 Not Found: Track or audio file not found
 /// - 416 Range Not Satisfiable: Invalid byte range
@@ -106,6 +478,56 @@ async fn stream_track(
     Path(track_id): Path<Uuid>,
     Query(transcode_query): Query<TranscodeQuery>,
     headers: HeaderMap,
+) -> ApiResult<Response> {
+    serve_track(&state, track_id, transcode_query, headers).await
+}
+
+/// Stream a track through a signed share link
+///
+/// # Request
+/// - Method: GET
+/// - Path: /stream/shared/:token
+/// - Query Parameters and Headers: as for `GET /stream/:track_id`, except
+///   that no Authorization header is needed
+///
+/// # Response
+/// - As for `GET /stream/:track_id`
+/// - 403 Forbidden: Token is expired, tampered with, or not a share token
+async fn stream_shared_track(
+    State(state): State<StreamingState>,
+    Extension(auth_service): Extension<AuthService>,
+    Path(token): Path<String>,
+    Query(transcode_query): Query<TranscodeQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let track_id = auth_service.verify_stream_token(&token)?;
+    serve_track(&state, track_id, transcode_query, headers).await
+}
+
+/// Serve a track's audio once the caller has been authorized
+async fn serve_track(
+    state: &StreamingState,
+    track_id: Uuid,
+    transcode_query: TranscodeQuery,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    // Without an explicit format the response depends on the Accept header
+    let negotiable = transcode_query.format.is_none() && !transcode_query.raw;
+    let mut response = serve_track_audio(state, track_id, transcode_query, headers).await?;
+    if negotiable {
+        response
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Accept"));
+    }
+    Ok(response)
+}
+
+/// Serve the original file or a transcode, as the query and headers ask
+async fn serve_track_audio(
+    state: &StreamingState,
+    track_id: Uuid,
+    transcode_query: TranscodeQuery,
+    headers: HeaderMap,
 ) -> ApiResult<Response> {
     // 1. Look up track in database
     let track = state
@@ -115,17 +537,43 @@ async fn stream_track(
         .ok_or_else(|| ApiError::not_found("track", track_id.to_string()))?;
 
     // 2. Validate and resolve file path
-    let file_path = validate_file_path(&track.file_path, &state.music_library_path).await?;
+    let file_path = validate_file_path(&track.file_path, &state.music_roots).await?;
 
     // 3. Validate transcoding parameters
-    if transcode_query.bitrate.is_some() && transcode_query.format.is_none() {
+    let raw = transcode_query.wants_raw()?;
+    if raw {
+        let transcodable: Vec<TranscodeFormat> = state
+            .transcoder
+            .supported_formats()
+            .iter()
+            .map(|capability| capability.format)
+            .collect();
+        check_raw_stream(track.file_format, &state.raw_formats, &transcodable)?;
+    } else if transcode_query.bitrate.is_some() && transcode_query.format.is_none() {
         return Err(ApiError::ValidationError(
             "`bitrate` requires `format` parameter".to_string(),
         ));
+    } else if transcode_query.compression.is_some() && transcode_query.format.is_none() {
+        return Err(ApiError::ValidationError(
+            "`compression` requires `format` parameter".to_string(),
+        ));
+    }
+    if transcode_query.t.is_some() && (raw || transcode_query.format.is_none()) {
+        return Err(ApiError::ValidationError(
+            "`t` requires a transcode `format`; use a Range request to seek the original file"
+                .to_string(),
+        ));
+    }
+    if transcode_query.normalize && (raw || transcode_query.format.is_none()) {
+        return Err(ApiError::ValidationError(
+            "`normalize` requires a transcode `format`".to_string(),
+        ));
     }
 
-    // 4. Check if transcoding is requested
-    if let Some(format_str) = &transcode_query.format {
+    // 4. Check if transcoding is requested, explicitly or through the Accept header
+    let target_format =
+        select_transcode_format(state, &transcode_query, raw, track.file_format, &headers)?;
+    if let Some(target_format) = target_format {
         // Reject Range requests for transcoding - we can't seek in a live-transcoded stream
         if headers.get(header::RANGE).is_some() {
             return Err(ApiError::InvalidRange(
@@ -133,40 +581,58 @@ async fn stream_track(
             ));
         }
 
-        // Parse the target format
-        let target_format = TranscodeFormat::parse(format_str).ok_or_else(|| {
-            ApiError::ValidationError(format!("Unsupported format: {}", format_str))
-        })?;
-
         // Build transcode options
-        let options = match transcode_query.bitrate {
+        let mut options = match transcode_query.bitrate {
             Some(bitrate) => TranscodeOptions::with_bitrate(target_format, bitrate)
                 .map_err(|e| ApiError::ValidationError(e.to_string()))?,
             None => TranscodeOptions::new(target_format),
         };
+        if let Some(level) = transcode_query.compression {
+            options = options
+                .with_compression_level(level)
+                .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+        }
+        // Seek by time: byte offsets don't map to positions in VBR output
+        if let Some(t) = transcode_query.t {
+            options = options.with_start_offset(validate_seek_offset(t, track.duration_ms)?);
+        }
+        if transcode_query.normalize {
+            options = options.with_normalization(Normalization::for_track(
+                track.audio_features.lufs_integrated,
+            ));
+        }
 
-        // Start transcoding
-        let transcode_stream = state
+        // Serve from the transcode cache when enabled, falling back to a
+        // plain transcode
+        let cached = state
             .transcoder
-            .transcode(&file_path, &options)
+            .transcode_cached(track.id, &file_path, &options)
             .await
-            .map_err(|e| {
-                match &e {
-                    TranscodeError::ResourceExhausted => {
-                        // Return 503 Service Unavailable when at capacity
-                        tracing::warn!(error = %e, "Transcoding at capacity");
-                        ApiError::ServiceBusy("Transcoding capacity reached, try again later".to_string())
-                    }
-                    TranscodeError::FfmpegNotFound => {
-                        tracing::error!(error = %e, "FFmpeg not available");
-                        ApiError::Configuration("FFmpeg not installed".to_string())
-                    }
-                    _ => {
-                        tracing::error!(error = %e, path = %file_path.display(), "Transcoding failed");
-                        ApiError::AudioProcessing(format!("Transcoding failed: {}", e))
-                    }
-                }
-            })?;
+            .map_err(|e| map_transcode_error(e, &file_path))?;
+
+        let transcode_stream = match cached {
+            Some(CachedOutput::File(cached)) => {
+                let file = File::open(&cached.path).await.map_err(|e| {
+                    tracing::error!(error = %e, path = %cached.path.display(), "Failed to open cached transcode");
+                    ApiError::AudioProcessing(format!("Failed to open cached transcode: {}", e))
+                })?;
+
+                return Ok(fade_headers(Response::builder(), &track.audio_features)
+                    .status(StatusCode::OK)
+                    .header(header::CONTENT_TYPE, target_format.content_type())
+                    .header(header::CONTENT_LENGTH, cached.size)
+                    .header(header::ACCEPT_RANGES, "none")
+                    .header(header::CACHE_CONTROL, "private, no-store")
+                    .body(file_body(file, state.chunk_bytes))
+                    .expect("Failed to build response"));
+            }
+            Some(CachedOutput::Stream(stream)) => stream,
+            None => state
+                .transcoder
+                .transcode(track.id, &file_path, &options)
+                .await
+                .map_err(|e| map_transcode_error(e, &file_path))?,
+        };
 
         let content_type = target_format.content_type();
         let body = Body::from_stream(transcode_stream);
@@ -174,7 +640,7 @@ async fn stream_track(
         // Transcoded streams don't support range requests or Content-Length
         // (we don't know the final size until transcoding completes)
         // Note: Transfer-Encoding: chunked is implicit when streaming without Content-Length
-        return Ok(Response::builder()
+        return Ok(fade_headers(Response::builder(), &track.audio_features)
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, content_type)
             .header(header::ACCEPT_RANGES, "none") // Inform clients seeking is not supported
@@ -235,11 +701,9 @@ async fn stream_track(
                 .map_err(|e| ApiError::AudioProcessing(format!("Failed to seek: {}", e)))?;
 
             // Take only the bytes we need
-            let limited_file = file.take(content_length);
-            let stream = ReaderStream::new(limited_file);
-            let body = Body::from_stream(stream);
+            let body = file_body(file.take(content_length), state.chunk_bytes);
 
-            Ok(Response::builder()
+            Ok(fade_headers(Response::builder(), &track.audio_features)
                 .status(StatusCode::PARTIAL_CONTENT)
                 .header(header::CONTENT_TYPE, content_type)
                 .header(header::CONTENT_LENGTH, content_length)
@@ -259,10 +723,9 @@ async fn stream_track(
         }
         None => {
             // Full content
-            let stream = ReaderStream::new(file);
-            let body = Body::from_stream(stream);
+            let body = file_body(file, state.chunk_bytes);
 
-            Ok(Response::builder()
+            Ok(fade_headers(Response::builder(), &track.audio_features)
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, content_type)
                 .header(header::CONTENT_LENGTH, file_size)
@@ -308,7 +771,7 @@ async fn head_track(
         .ok_or_else(|| ApiError::not_found("track", track_id.to_string()))?;
 
     // 2. Validate and resolve file path
-    let file_path = validate_file_path(&track.file_path, &state.music_library_path).await?;
+    let file_path = validate_file_path(&track.file_path, &state.music_roots).await?;
 
     // 3. Get file metadata without opening the file for streaming
     let metadata = tokio::fs::metadata(&file_path).await.map_err(|e| {
@@ -339,7 +802,7 @@ async fn head_track(
     }
 
     // 6. Return headers only (no body for HEAD)
-    Ok(Response::builder()
+    Ok(fade_headers(Response::builder(), &track.audio_features)
         .status(StatusCode::OK)
         .header(header::CONTENT_TYPE, content_type)
         .header(header::CONTENT_LENGTH, file_size)
@@ -354,6 +817,182 @@ async fn head_track(
         .expect("Failed to build response"))
 }
 
+/// Fetch a track's lyrics for display alongside playback
+///
+/// # Request
+/// - Method: GET
+/// - Path: /stream/:track_id/lyrics
+/// - Query Parameters:
+///   - format: `json` (default) or `lrc` - optional
+/// - Headers:
+///   - Authorization: Bearer <token> (required)
+///
+/// # Response
+/// - 200 OK: Synced lyrics as JSON (`synced: true`) or LRC text. When only
+///   plain lyrics are stored, JSON has `synced: false` and `text` set, and
+///   LRC falls back to the plain text
+/// - 400 Bad Request: Unsupported `format`
+/// - 401 Unauthorized: Missing or invalid token
+/// - 404 Not Found: Track not found, or it has no lyrics
+async fn track_lyrics(
+    State(state): State<StreamingState>,
+    _auth: AuthUser, // Validates authentication
+    Path(track_id): Path<Uuid>,
+    Query(query): Query<LyricsQuery>,
+) -> ApiResult<Response> {
+    let format = LyricsFormat::parse(query.format.as_deref())?;
+
+    let track = state
+        .track_repo
+        .find_by_id(track_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("track", track_id.to_string()))?;
+
+    lyrics_response(&track, format)
+}
+
+/// Build the lyrics response for a track
+///
+/// Synced lyrics are preferred; plain lyrics are the fallback. Empty or
+/// whitespace-only lyrics count as missing.
+///
+/// # Errors
+/// Returns `NotFound` if the track has no lyrics
+pub fn lyrics_response(track: &Track, format: LyricsFormat) -> ApiResult<Response> {
+    let mut lines = track.synced_lyrics.clone().unwrap_or_default();
+    lines.sort_by_key(|line| line.time_ms);
+    let text = track
+        .lyrics
+        .as_deref()
+        .filter(|text| !text.trim().is_empty());
+
+    if lines.is_empty() && text.is_none() {
+        return Err(ApiError::not_found("lyrics", track.id.to_string()));
+    }
+
+    let response = match format {
+        LyricsFormat::Json => Response::builder()
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(
+                serde_json::to_vec(&LyricsResponse {
+                    track_id: track.id,
+                    synced: !lines.is_empty(),
+                    lines,
+                    text: text.map(str::to_string),
+                })
+                .map_err(|e| ApiError::Internal(format!("Failed to encode lyrics: {}", e)))?,
+            )),
+        LyricsFormat::Lrc => {
+            let body = if lines.is_empty() {
+                text.unwrap_or_default().to_string()
+            } else {
+                format_lrc(&lines)
+            };
+            Response::builder()
+                .header(header::CONTENT_TYPE, "text/plain; charset=utf-8")
+                .body(Body::from(body))
+        }
+    };
+
+    Ok(response.expect("Failed to build response"))
+}
+
+/// Render synced lyrics as LRC, e.g. `[01:02.50]line`
+pub fn format_lrc(lines: &[SyncedLyricLine]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            let ms = line.time_ms.max(0);
+            format!(
+                "[{:02}:{:02}.{:02}]{}\n",
+                ms / 60_000,
+                ms / 1000 % 60,
+                ms % 1000 / 10,
+                line.text
+            )
+        })
+        .collect()
+}
+
+/// Seconds clients are asked to wait when transcoding is at capacity
+const TRANSCODE_BUSY_RETRY_AFTER_SECS: u64 = 5;
+
+/// The format to transcode to, or `None` to serve the original file
+///
+/// An explicit `format` always wins. Otherwise the `Accept` header is
+/// negotiated, except for raw and Range requests, which read the original's
+/// bytes.
+fn select_transcode_format(
+    state: &StreamingState,
+    query: &TranscodeQuery,
+    raw: bool,
+    original: AudioFormat,
+    headers: &HeaderMap,
+) -> ApiResult<Option<TranscodeFormat>> {
+    match query.format.as_ref().filter(|_| !raw) {
+        Some(format_str) => TranscodeFormat::parse(format_str).map(Some).ok_or_else(|| {
+            ApiError::ValidationError(format!("Unsupported format: {}", format_str))
+        }),
+        None if raw || headers.get(header::RANGE).is_some() => Ok(None),
+        None => Ok(negotiated_transcode(state, original, headers)),
+    }
+}
+
+/// The transcode format the `Accept` header asks for, if any
+///
+/// Without an `Accept` header the original is served, as before
+/// negotiation existed. When nothing is acceptable, falls back to the
+/// configured default format.
+fn negotiated_transcode(
+    state: &StreamingState,
+    original: AudioFormat,
+    headers: &HeaderMap,
+) -> Option<TranscodeFormat> {
+    let accept = headers.get(header::ACCEPT)?.to_str().ok()?;
+    let available: Vec<TranscodeFormat> = state
+        .transcoder
+        .supported_formats()
+        .iter()
+        .map(|capability| capability.format)
+        .collect();
+
+    match negotiate_format(accept, content_type_for_format(&original), &available) {
+        Some(NegotiatedFormat::Original) => None,
+        Some(NegotiatedFormat::Transcode(format)) => Some(format),
+        None => state.default_format,
+    }
+}
+
+/// Stream a reader as a response body, `chunk_bytes` at a time
+fn file_body<R>(reader: R, chunk_bytes: usize) -> Body
+where
+    R: AsyncRead + Send + 'static,
+{
+    Body::from_stream(ReaderStream::with_capacity(reader, chunk_bytes))
+}
+
+/// Map a transcoder error to the appropriate API error
+fn map_transcode_error(e: TranscodeError, file_path: &StdPath) -> ApiError {
+    match &e {
+        TranscodeError::ResourceExhausted => {
+            // Return 503 Service Unavailable when at capacity
+            tracing::warn!(error = %e, "Transcoding at capacity");
+            ApiError::ServiceBusy {
+                message: "Transcoding capacity reached, try again later".to_string(),
+                retry_after: TRANSCODE_BUSY_RETRY_AFTER_SECS,
+            }
+        }
+        TranscodeError::FfmpegNotFound => {
+            tracing::error!(error = %e, "FFmpeg not available");
+            ApiError::Configuration("FFmpeg not installed".to_string())
+        }
+        _ => {
+            tracing::error!(error = %e, path = %file_path.display(), "Transcoding failed");
+            ApiError::AudioProcessing(format!("Transcoding failed: {}", e))
+        }
+    }
+}
+
 /// Parse HTTP Range header according to RFC 7233
 ///
 /// Supports formats:
@@ -435,6 +1074,26 @@ fn parse_range_header(range_header: &str, file_size: u64) -> Result<(u64, u64),
     Ok((start, end))
 }
 
+/// Header carrying the end of a track's leading silence, in milliseconds
+const FADE_IN_HEADER: &str = "x-fade-in-ms";
+
+/// Header carrying where a track's audible content ends, in milliseconds
+const FADE_OUT_START_HEADER: &str = "x-fade-out-start-ms";
+
+/// Add crossfade timing headers for gapless playback
+///
+/// Headers are omitted until feature extraction has computed the fade points.
+fn fade_headers(builder: response::Builder, features: &AudioFeatures) -> response::Builder {
+    let builder = match features.fade_in_ms {
+        Some(ms) => builder.header(FADE_IN_HEADER, ms),
+        None => builder,
+    };
+    match features.fade_out_start_ms {
+        Some(ms) => builder.header(FADE_OUT_START_HEADER, ms),
+        None => builder,
+    }
+}
+
 /// Get the Content-Type MIME type for an audio format
 fn content_type_for_format(format: &AudioFormat) -> &'static str {
     match format {
@@ -464,7 +1123,7 @@ fn generate_etag(file_size: u64, modified: SystemTime) -> String {
 /// Format a SystemTime as an HTTP-date for Last-Modified header
 ///
 /// Format: RFC 7231 (e.g., "Sun, 06 Nov 1994 08:49:37 GMT")
-fn format_http_date(time: SystemTime) -> String {
+pub(crate) fn format_http_date(time: SystemTime) -> String {
     httpdate::fmt_http_date(time)
 }
 
@@ -473,7 +1132,7 @@ fn format_http_date(time: SystemTime) -> String {
 /// Per RFC 7232 Section 6:
 /// - If-None-Match takes precedence over If-Modified-Since
 /// - If If-None-Match is present (even if malformed), If-Modified-Since is ignored
-fn is_cache_valid(headers: &HeaderMap, etag: &str, modified: SystemTime) -> bool {
+pub(crate) fn is_cache_valid(headers: &HeaderMap, etag: &str, modified: SystemTime) -> bool {
     // Check If-None-Match (takes precedence over If-Modified-Since per RFC 7232)
     if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
         // RFC 7232: If If-None-Match is present, it takes precedence and
@@ -516,62 +1175,98 @@ fn is_cache_valid(headers: &HeaderMap, etag: &str, modified: SystemTime) -> bool
     false
 }
 
-/// Validate that a file path is within the music library directory
+/// Validate that a file path is within one of the music library roots
 ///
 /// This prevents path traversal attacks by:
-/// 1. Canonicalizing the file path to resolve any `..` components
-/// 2. Verifying the canonical path starts with the library path
+/// 1. Rejecting any path containing `..` components, absolute or relative
+/// 2. Canonicalizing the file path and library roots to resolve symlinks
+/// 3. Verifying the canonical path starts with a canonical library root
+///
+/// Because containment is checked on canonical paths, a symlink inside the
+/// library that points outside it is rejected just like an absolute path
+/// outside the library.
+///
+/// Relative paths are resolved against each root in order; the first root
+/// containing the file wins. Roots that are unavailable (e.g. an unmounted
+/// drive) are skipped.
 ///
 /// Uses spawn_blocking to avoid blocking the async runtime during filesystem operations.
-async fn validate_file_path(file_path: &str, music_library_path: &StdPath) -> ApiResult<PathBuf> {
+pub(crate) async fn validate_file_path(
+    file_path: &str,
+    music_roots: &[PathBuf],
+) -> ApiResult<PathBuf> {
     let file_path = file_path.to_string();
-    let library = music_library_path.to_path_buf();
+    let roots = music_roots.to_vec();
 
     tokio::task::spawn_blocking(move || {
         // Construct the full path - handle both absolute and relative paths
         let input_path = StdPath::new(&file_path);
 
-        // Reject any parent-dir components in relative paths early to avoid
-        // existence probing via different error messages (file-not-found vs forbidden)
-        if !input_path.is_absolute()
-            && input_path
-                .components()
-                .any(|c| matches!(c, std::path::Component::ParentDir))
+        // Reject any parent-dir components early to avoid existence probing
+        // via different error messages (file-not-found vs forbidden)
+        if input_path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
         {
             tracing::warn!(file_path = %file_path, "Path traversal attempt blocked (contains ..)");
             return Err(ApiError::Forbidden("Access denied".to_string()));
         }
 
-        let full_path = if input_path.is_absolute() {
-            input_path.to_path_buf()
+        // Canonicalize the library roots so symlinked roots compare correctly
+        let canonical_roots: Vec<PathBuf> = roots
+            .iter()
+            .filter_map(|root| match root.canonicalize() {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    tracing::warn!(error = %e, path = %root.display(), "Music library root unavailable");
+                    None
+                }
+            })
+            .collect();
+
+        if canonical_roots.is_empty() {
+            tracing::error!(roots = ?roots, "No music library root is available");
+            return Err(ApiError::AudioProcessing(
+                "Invalid music library path: no library root is available".to_string(),
+            ));
+        }
+
+        let candidates: Vec<PathBuf> = if input_path.is_absolute() {
+            vec![input_path.to_path_buf()]
         } else {
-            library.join(input_path)
+            roots.iter().map(|root| root.join(input_path)).collect()
         };
 
-        // Canonicalize to resolve any .., symlinks, etc.
-        let canonical = full_path.canonicalize().map_err(|_| {
+        // Canonicalize to resolve symlinks, preferring a candidate inside a root
+        let resolved: Vec<PathBuf> = candidates
+            .iter()
+            .filter_map(|candidate| candidate.canonicalize().ok())
+            .collect();
+        if resolved.is_empty() {
             tracing::warn!(file_path = %file_path, "Audio file not found or inaccessible");
-            ApiError::AudioFileNotFound(file_path.to_string())
-        })?;
-
-        // Canonicalize the library path as well
-        let canonical_library = library.canonicalize().map_err(|e| {
-            tracing::error!(error = %e, path = %library.display(), "Invalid music library path");
-            ApiError::AudioProcessing(format!("Invalid music library path: {}", e))
-        })?;
+            return Err(ApiError::AudioFileNotFound(file_path.to_string()));
+        }
 
-        // Verify the canonical path starts with the library path
-        if !canonical.starts_with(&canonical_library) {
+        let Some(canonical) = resolved
+            .iter()
+            .find(|path| canonical_roots.iter().any(|root| path.starts_with(root)))
+        else {
             tracing::warn!(
                 file_path = %file_path,
-                canonical = %canonical.display(),
-                library = %canonical_library.display(),
+                canonical = ?resolved,
+                roots = ?canonical_roots,
                 "Path traversal attempt blocked"
             );
             return Err(ApiError::Forbidden("Access denied".to_string()));
+        };
+
+        // Only regular files can be streamed
+        if !canonical.is_file() {
+            tracing::warn!(file_path = %file_path, "Audio path is not a regular file");
+            return Err(ApiError::AudioFileNotFound(file_path.to_string()));
         }
 
-        Ok(canonical)
+        Ok(canonical.clone())
     })
     .await
     .map_err(|e| ApiError::Internal(format!("Path validation task failed: {}", e)))?
@@ -581,6 +1276,27 @@ async fn validate_file_path(file_path: &str, music_library_path: &StdPath) -> Ap
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_fade_headers() {
+        let features = AudioFeatures {
+            fade_in_ms: Some(120),
+            fade_out_start_ms: Some(183_400),
+            ..Default::default()
+        };
+        let response = fade_headers(Response::builder(), &features)
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(response.headers()[FADE_IN_HEADER], "120");
+        assert_eq!(response.headers()[FADE_OUT_START_HEADER], "183400");
+
+        // Not yet computed: no headers rather than zeros
+        let response = fade_headers(Response::builder(), &AudioFeatures::default())
+            .body(Body::empty())
+            .unwrap();
+        assert!(response.headers().get(FADE_IN_HEADER).is_none());
+        assert!(response.headers().get(FADE_OUT_START_HEADER).is_none());
+    }
+
     #[test]
     fn test_content_type_mapping() {
         assert_eq!(content_type_for_format(&AudioFormat::Flac), "audio/flac");
@@ -596,6 +1312,404 @@ mod tests {
         );
     }
 
+    fn query(format: Option<&str>, bitrate: Option<u32>, raw: bool) -> TranscodeQuery {
+        TranscodeQuery {
+            format: format.map(str::to_string),
+            bitrate,
+            compression: None,
+            raw,
+            t: None,
+            normalize: false,
+        }
+    }
+
+    #[test]
+    fn test_validate_seek_offset() {
+        assert_eq!(validate_seek_offset(0.0, 180_000).unwrap(), Duration::ZERO);
+        assert_eq!(
+            validate_seek_offset(90.5, 180_000).unwrap(),
+            Duration::from_millis(90_500)
+        );
+        for t in [-1.0, 180.0, 500.0, f64::NAN, f64::INFINITY] {
+            assert!(
+                matches!(
+                    validate_seek_offset(t, 180_000),
+                    Err(ApiError::ValidationError(_))
+                ),
+                "t={} should be rejected",
+                t
+            );
+        }
+    }
+
+    #[test]
+    fn test_wants_raw() {
+        assert!(!query(None, None, false).wants_raw().unwrap());
+        assert!(!query(Some("mp3"), Some(192), false).wants_raw().unwrap());
+        assert!(query(None, None, true).wants_raw().unwrap());
+        assert!(query(Some("Original"), None, false).wants_raw().unwrap());
+        assert!(query(Some("original"), None, true).wants_raw().unwrap());
+
+        assert!(query(Some("mp3"), None, true).wants_raw().is_err());
+        assert!(query(None, Some(320), true).wants_raw().is_err());
+        assert!(query(Some("original"), Some(320), false)
+            .wants_raw()
+            .is_err());
+
+        let compressed = |format: Option<&str>, raw: bool| TranscodeQuery {
+            compression: Some(8),
+            ..query(format, None, raw)
+        };
+        assert!(!compressed(Some("flac"), false).wants_raw().unwrap());
+        assert!(compressed(None, true).wants_raw().is_err());
+    }
+
+    #[test]
+    fn test_check_raw_stream_allowlist() {
+        let all = [
+            TranscodeFormat::Mp3,
+            TranscodeFormat::Opus,
+            TranscodeFormat::Flac,
+        ];
+
+        assert!(check_raw_stream(AudioFormat::Flac, DEFAULT_RAW_STREAM_FORMATS, &all).is_ok());
+
+        let err = check_raw_stream(AudioFormat::Wav, DEFAULT_RAW_STREAM_FORMATS, &all).unwrap_err();
+        assert!(
+            matches!(&err, ApiError::UnsupportedFormat(msg) if msg.contains("WAV") && msg.contains("format=flac"))
+        );
+
+        // Lossy originals get a lossy suggestion
+        let err = check_raw_stream(AudioFormat::Mp3, &[], &all).unwrap_err();
+        assert!(matches!(&err, ApiError::UnsupportedFormat(msg) if msg.contains("format=opus")));
+
+        // Falls back to whatever the transcoder supports, or no suggestion at all
+        let err = check_raw_stream(AudioFormat::Alac, &[], &[TranscodeFormat::Mp3]).unwrap_err();
+        assert!(matches!(&err, ApiError::UnsupportedFormat(msg) if msg.contains("format=mp3")));
+        let err = check_raw_stream(AudioFormat::Alac, &[], &[]).unwrap_err();
+        assert!(matches!(&err, ApiError::UnsupportedFormat(msg) if !msg.contains("format=")));
+    }
+
+    #[tokio::test]
+    async fn test_router_matches_track_id_path() {
+        use tower::ServiceExt;
+
+        // The pool is never connected: the request is rejected for missing auth
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let router = streaming_router(StreamingState::new(
+            TrackRepository::new(pool),
+            vec![PathBuf::from("/music")],
+        ));
+
+        let response = router
+            .oneshot(
+                axum::http::Request::get(format!("/{}", Uuid::new_v4()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_lyrics_route_requires_auth() {
+        use tower::ServiceExt;
+
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let router = streaming_router(StreamingState::new(
+            TrackRepository::new(pool),
+            vec![PathBuf::from("/music")],
+        ));
+
+        let response = router
+            .oneshot(
+                axum::http::Request::get(format!("/{}/lyrics", Uuid::new_v4()))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn lyric(time_ms: i64, text: &str) -> SyncedLyricLine {
+        SyncedLyricLine {
+            time_ms,
+            text: text.to_string(),
+        }
+    }
+
+    fn track_with_lyrics(synced: Option<Vec<SyncedLyricLine>>, plain: Option<&str>) -> Track {
+        Track {
+            id: Uuid::new_v4(),
+            title: "Lyrics Track".to_string(),
+            album_id: None,
+            artist_id: Uuid::new_v4(),
+            mbid: None,
+            file_path: "/music/lyrics.flac".to_string(),
+            file_size: 1000,
+            file_format: AudioFormat::Flac,
+            file_hash: None,
+            duration_ms: 180000,
+            bit_rate: None,
+            sample_rate: None,
+            channels: None,
+            bit_depth: None,
+            track_number: None,
+            disc_number: None,
+            genres: vec![],
+            explicit: false,
+            lyrics: plain.map(str::to_string),
+            synced_lyrics: synced,
+            audio_features: AudioFeatures::default(),
+            ai_mood: vec![],
+            ai_tags: vec![],
+            ai_description: None,
+            play_count: 0,
+            skip_count: 0,
+            last_played_at: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn response_text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn test_lyrics_format_parse() {
+        assert_eq!(LyricsFormat::parse(None).unwrap(), LyricsFormat::Json);
+        assert_eq!(LyricsFormat::parse(Some("LRC")).unwrap(), LyricsFormat::Lrc);
+        assert!(matches!(
+            LyricsFormat::parse(Some("srt")),
+            Err(ApiError::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_lyrics_json_returns_synced_lines_in_order() {
+        let track = track_with_lyrics(
+            Some(vec![lyric(5_250, "second"), lyric(1_000, "first")]),
+            Some("first\nsecond"),
+        );
+
+        let response = lyrics_response(&track, LyricsFormat::Json).unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+
+        let body: LyricsResponse = serde_json::from_str(&response_text(response).await).unwrap();
+        assert_eq!(body.track_id, track.id);
+        assert!(body.synced);
+        let lines: Vec<_> = body
+            .lines
+            .iter()
+            .map(|l| (l.time_ms, l.text.as_str()))
+            .collect();
+        assert_eq!(lines, vec![(1_000, "first"), (5_250, "second")]);
+        assert_eq!(body.text.as_deref(), Some("first\nsecond"));
+    }
+
+    #[tokio::test]
+    async fn test_lyrics_lrc_output() {
+        let track = track_with_lyrics(Some(vec![lyric(62_500, "later"), lyric(0, "start")]), None);
+
+        let response = lyrics_response(&track, LyricsFormat::Lrc).unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(
+            response_text(response).await,
+            "[00:00.00]start\n[01:02.50]later\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_lyrics_fall_back_to_plain_text() {
+        let track = track_with_lyrics(Some(vec![]), Some("just words"));
+
+        let response = lyrics_response(&track, LyricsFormat::Json).unwrap();
+        let body: LyricsResponse = serde_json::from_str(&response_text(response).await).unwrap();
+        assert!(!body.synced);
+        assert!(body.lines.is_empty());
+        assert_eq!(body.text.as_deref(), Some("just words"));
+
+        let response = lyrics_response(&track, LyricsFormat::Lrc).unwrap();
+        assert_eq!(response_text(response).await, "just words");
+    }
+
+    #[test]
+    fn test_no_lyrics_is_not_found() {
+        for track in [
+            track_with_lyrics(None, None),
+            track_with_lyrics(Some(vec![]), Some("  ")),
+        ] {
+            let err = lyrics_response(&track, LyricsFormat::Json).unwrap_err();
+            assert!(matches!(err, ApiError::NotFound { .. }));
+            assert_eq!(err.status_code(), StatusCode::NOT_FOUND);
+        }
+    }
+
+    fn negotiation_state(default_format: Option<TranscodeFormat>) -> StreamingState {
+        let pool = sqlx::PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        StreamingState::new(TrackRepository::new(pool), vec![PathBuf::from("/music")])
+            .with_transcoder(
+                TranscoderService::new().with_available_encoders(["libmp3lame", "libopus"]),
+            )
+            .with_default_format(default_format)
+    }
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_negotiate_format_prefers_highest_quality() {
+        let available = [TranscodeFormat::Mp3, TranscodeFormat::Opus];
+
+        assert_eq!(
+            negotiate_format(
+                "audio/ogg;q=0.9, audio/mpeg;q=0.5",
+                "audio/flac",
+                &available
+            ),
+            Some(NegotiatedFormat::Transcode(TranscodeFormat::Opus))
+        );
+        assert_eq!(
+            negotiate_format("audio/ogg;q=0.4, audio/mpeg", "audio/flac", &available),
+            Some(NegotiatedFormat::Transcode(TranscodeFormat::Mp3))
+        );
+        // The original wins ties, so wildcards don't trigger transcodes
+        assert_eq!(
+            negotiate_format("*/*", "audio/flac", &available),
+            Some(NegotiatedFormat::Original)
+        );
+        assert_eq!(
+            negotiate_format("audio/*;q=0.8, audio/opus", "audio/flac", &available),
+            Some(NegotiatedFormat::Transcode(TranscodeFormat::Opus))
+        );
+        // A more specific range overrides a wildcard
+        assert_eq!(
+            negotiate_format("audio/*, audio/flac;q=0", "audio/flac", &available),
+            Some(NegotiatedFormat::Transcode(TranscodeFormat::Mp3))
+        );
+        // Formats the backend can't produce are never picked
+        assert_eq!(
+            negotiate_format("audio/aac", "audio/flac", &available),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unsatisfiable_accept_falls_back_to_default() {
+        let query = TranscodeQuery::default();
+        let headers = accept("application/json, audio/x-ms-wma");
+
+        let with_default = negotiation_state(Some(TranscodeFormat::Mp3));
+        assert_eq!(
+            select_transcode_format(&with_default, &query, false, AudioFormat::Flac, &headers)
+                .unwrap(),
+            Some(TranscodeFormat::Mp3)
+        );
+
+        // Without a default the original is served
+        let without_default = negotiation_state(None);
+        assert_eq!(
+            select_transcode_format(&without_default, &query, false, AudioFormat::Flac, &headers)
+                .unwrap(),
+            None
+        );
+
+        // So is a request with no Accept header at all
+        assert_eq!(
+            select_transcode_format(
+                &with_default,
+                &query,
+                false,
+                AudioFormat::Flac,
+                &HeaderMap::new()
+            )
+            .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_explicit_format_overrides_accept() {
+        let state = negotiation_state(Some(TranscodeFormat::Opus));
+        let headers = accept("audio/ogg, audio/mpeg;q=0.5");
+
+        let explicit = TranscodeQuery {
+            format: Some("flac".to_string()),
+            ..TranscodeQuery::default()
+        };
+        assert_eq!(
+            select_transcode_format(&state, &explicit, false, AudioFormat::Mp3, &headers).unwrap(),
+            Some(TranscodeFormat::Flac)
+        );
+
+        let negotiated = TranscodeQuery::default();
+        assert_eq!(
+            select_transcode_format(&state, &negotiated, false, AudioFormat::Mp3, &headers)
+                .unwrap(),
+            Some(TranscodeFormat::Opus)
+        );
+
+        // Raw and Range requests always get the original
+        assert_eq!(
+            select_transcode_format(&state, &negotiated, true, AudioFormat::Mp3, &headers).unwrap(),
+            None
+        );
+        let mut ranged = headers.clone();
+        ranged.insert(header::RANGE, HeaderValue::from_static("bytes=0-"));
+        assert_eq!(
+            select_transcode_format(&state, &negotiated, false, AudioFormat::Mp3, &ranged).unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_body_uses_configured_chunk_size() {
+        use futures_util::StreamExt;
+        use std::io::Write;
+
+        // Five full chunks and a short one at 16 KiB
+        let chunk_bytes = 16 * 1024;
+        let file_size = 5 * chunk_bytes + 100;
+        let mut tmp = tempfile::NamedTempFile::new().unwrap();
+        tmp.write_all(&vec![0u8; file_size]).unwrap();
+
+        let count_frames = |chunk_bytes| {
+            let path = tmp.path().to_path_buf();
+            async move {
+                let file = File::open(path).await.unwrap();
+                let frames: Vec<_> = file_body(file, chunk_bytes)
+                    .into_data_stream()
+                    .map(|frame| frame.unwrap().len())
+                    .collect()
+                    .await;
+                frames
+            }
+        };
+
+        let frames = count_frames(chunk_bytes).await;
+        assert_eq!(frames.len(), 6);
+        assert!(frames[..5].iter().all(|&len| len == chunk_bytes));
+        assert_eq!(frames[5], 100);
+
+        // The default reads the whole file in two frames
+        assert_eq!(count_frames(DEFAULT_STREAM_CHUNK_BYTES).await.len(), 2);
+    }
+
     #[test]
     fn test_parse_range_header_full_range() {
         let (start, end) = parse_range_header("bytes=0-999", 5000).unwrap();
@@ -864,7 +1978,8 @@ mod tests {
         // Create a test file
         std::fs::write(&test_file, b"test content").unwrap();
 
-        let result = validate_file_path("test_audio_file.flac", &temp_dir).await;
+        let result =
+            validate_file_path("test_audio_file.flac", std::slice::from_ref(&temp_dir)).await;
         assert!(result.is_ok());
 
         // Cleanup
@@ -874,7 +1989,8 @@ mod tests {
     #[tokio::test]
     async fn test_validate_file_path_nonexistent_file() {
         let temp_dir = std::env::temp_dir();
-        let result = validate_file_path("nonexistent_file.flac", &temp_dir).await;
+        let result =
+            validate_file_path("nonexistent_file.flac", std::slice::from_ref(&temp_dir)).await;
 
         assert!(matches!(result, Err(ApiError::AudioFileNotFound(_))));
     }
@@ -891,7 +2007,11 @@ mod tests {
         std::fs::write(&outside_file, b"secret content").unwrap();
 
         // Try to access it via path traversal
-        let result = validate_file_path("../outside_library.txt", &library_subdir).await;
+        let result = validate_file_path(
+            "../outside_library.txt",
+            std::slice::from_ref(&library_subdir),
+        )
+        .await;
 
         // Should be blocked as Forbidden
         assert!(matches!(result, Err(ApiError::Forbidden(_))));
@@ -908,7 +2028,8 @@ mod tests {
         std::fs::write(&test_file, b"test content").unwrap();
 
         // Use absolute path
-        let result = validate_file_path(test_file.to_str().unwrap(), &temp_dir).await;
+        let result =
+            validate_file_path(test_file.to_str().unwrap(), std::slice::from_ref(&temp_dir)).await;
         assert!(result.is_ok());
 
         // Cleanup
@@ -926,7 +2047,11 @@ mod tests {
         std::fs::write(&outside_file, b"secret content").unwrap();
 
         // Try to access it via absolute path
-        let result = validate_file_path(outside_file.to_str().unwrap(), &library_subdir).await;
+        let result = validate_file_path(
+            outside_file.to_str().unwrap(),
+            std::slice::from_ref(&library_subdir),
+        )
+        .await;
 
         // Should be blocked as Forbidden
         assert!(matches!(result, Err(ApiError::Forbidden(_))));
@@ -946,14 +2071,125 @@ mod tests {
 
         // Try to access a non-existent file with traversal components
         // Should return Forbidden, not NotFound, to prevent existence probing
-        let result = validate_file_path("../nonexistent_file.txt", &library_subdir).await;
+        let result = validate_file_path(
+            "../nonexistent_file.txt",
+            std::slice::from_ref(&library_subdir),
+        )
+        .await;
         assert!(matches!(result, Err(ApiError::Forbidden(_))));
 
         // Also test nested traversal
-        let result2 = validate_file_path("subdir/../../../secret.txt", &library_subdir).await;
+        let result2 = validate_file_path(
+            "subdir/../../../secret.txt",
+            std::slice::from_ref(&library_subdir),
+        )
+        .await;
         assert!(matches!(result2, Err(ApiError::Forbidden(_))));
 
         // Cleanup
         std::fs::remove_dir(&library_subdir).ok();
     }
+
+    #[tokio::test]
+    async fn test_validate_file_path_searches_all_roots() {
+        let first = tempfile::TempDir::new().unwrap();
+        let second = tempfile::TempDir::new().unwrap();
+        std::fs::write(second.path().join("second_root.flac"), b"test content").unwrap();
+        let roots = vec![first.path().to_path_buf(), second.path().to_path_buf()];
+
+        let relative = validate_file_path("second_root.flac", &roots)
+            .await
+            .unwrap();
+        assert_eq!(
+            relative,
+            second
+                .path()
+                .join("second_root.flac")
+                .canonicalize()
+                .unwrap()
+        );
+
+        let absolute = second.path().join("second_root.flac");
+        assert!(validate_file_path(absolute.to_str().unwrap(), &roots)
+            .await
+            .is_ok());
+
+        // A missing root doesn't prevent serving from the others
+        let with_missing = vec![first.path().join("unmounted"), second.path().to_path_buf()];
+        assert!(validate_file_path("second_root.flac", &with_missing)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validate_file_path_absolute_with_parent_dir_blocked() {
+        let library = tempfile::TempDir::new().unwrap();
+        std::fs::write(library.path().join("inside.flac"), b"test content").unwrap();
+
+        // Even though this resolves back inside the library, `..` is rejected outright
+        let sneaky = library.path().join("sub").join("..").join("inside.flac");
+        let result =
+            validate_file_path(sneaky.to_str().unwrap(), &[library.path().to_path_buf()]).await;
+
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_validate_file_path_escaping_symlink_blocked() {
+        let library = tempfile::TempDir::new().unwrap();
+        let outside = tempfile::TempDir::new().unwrap();
+        let secret = outside.path().join("secret.txt");
+        std::fs::write(&secret, b"secret content").unwrap();
+
+        std::os::unix::fs::symlink(&secret, library.path().join("link.flac")).unwrap();
+        std::os::unix::fs::symlink(outside.path(), library.path().join("linked_dir")).unwrap();
+        let roots = [library.path().to_path_buf()];
+
+        let file_link = validate_file_path("link.flac", &roots).await;
+        let dir_link = validate_file_path("linked_dir/secret.txt", &roots).await;
+
+        assert!(matches!(file_link, Err(ApiError::Forbidden(_))));
+        assert!(matches!(dir_link, Err(ApiError::Forbidden(_))));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_validate_file_path_internal_symlink_allowed() {
+        let library = tempfile::TempDir::new().unwrap();
+        let target = library.path().join("original.flac");
+        std::fs::write(&target, b"test content").unwrap();
+        std::os::unix::fs::symlink(&target, library.path().join("alias.flac")).unwrap();
+
+        let result = validate_file_path("alias.flac", &[library.path().to_path_buf()])
+            .await
+            .unwrap();
+
+        assert_eq!(result, target.canonicalize().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_validate_file_path_directory_rejected() {
+        let library = tempfile::TempDir::new().unwrap();
+        std::fs::create_dir(library.path().join("Album")).unwrap();
+
+        let result = validate_file_path("Album", &[library.path().to_path_buf()]).await;
+
+        assert!(matches!(result, Err(ApiError::AudioFileNotFound(_))));
+    }
+
+    #[test]
+    fn test_transcode_capacity_maps_to_503_with_retry_after() {
+        use axum::response::IntoResponse;
+
+        let response =
+            map_transcode_error(TranscodeError::ResourceExhausted, StdPath::new("/a.flac"))
+                .into_response();
+
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &TRANSCODE_BUSY_RETRY_AFTER_SECS.to_string()
+        );
+    }
 }
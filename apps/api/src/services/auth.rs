@@ -5,6 +5,7 @@
 //! - Login with JWT access/refresh token generation
 //! - Token refresh and verification
 //! - Session management and logout
+//! - Signed, expiring stream links for sharing a track without a session
 
 use argon2::{
     password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
@@ -12,13 +13,15 @@ use argon2::{
 };
 use chrono::{Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use resonance_shared_config::{parse_duration, ConfigError, ConfigResult};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
 use crate::models::user::{
-    AuthTokens, Claims, DeviceInfo, RefreshClaims, User, UserPreferences, UserRole,
+    AuthTokens, Claims, DeviceInfo, DeviceType, RefreshClaims, User, UserPreferences, UserRole,
 };
 use crate::repositories::{SessionRepository, UserRepository};
 
@@ -48,10 +51,10 @@ pub struct AuthConfig {
     pub access_token_ttl_secs: i64,
     /// Refresh token TTL in seconds (default: 7 days)
     pub refresh_token_ttl_secs: i64,
-    /// JWT issuer
-    pub issuer: String,
-    /// JWT audience
-    pub audience: String,
+    /// Expected JWT issuer (`iss`); validation is skipped when unset
+    pub jwt_issuer: Option<String>,
+    /// Expected JWT audience (`aud`); validation is skipped when unset
+    pub jwt_audience: Option<String>,
 }
 
 impl AuthConfig {
@@ -66,28 +69,89 @@ impl AuthConfig {
             jwt_secret,
             access_token_ttl_secs: 15 * 60,        // 15 minutes
             refresh_token_ttl_secs: 7 * 24 * 3600, // 7 days
-            issuer: "resonance".to_string(),
-            audience: "resonance".to_string(),
+            jwt_issuer: None,
+            jwt_audience: None,
+        }
+    }
+
+    /// Create AuthConfig with the given token lifetimes
+    ///
+    /// Lifetimes longer than MAX_TOKEN_TTL_SECS are capped to it.
+    ///
+    /// # Panics
+    /// Panics if jwt_secret is shorter than MIN_JWT_SECRET_LENGTH (32 bytes)
+    pub fn with_expiry(
+        jwt_secret: String,
+        access_expiry: std::time::Duration,
+        refresh_expiry: std::time::Duration,
+    ) -> Self {
+        Self::validate_jwt_secret(&jwt_secret);
+        Self {
+            jwt_secret,
+            access_token_ttl_secs: capped_ttl_secs(access_expiry),
+            refresh_token_ttl_secs: capped_ttl_secs(refresh_expiry),
+            jwt_issuer: None,
+            jwt_audience: None,
         }
     }
 
     /// Create AuthConfig from expiry strings (e.g., "15m", "7d")
     ///
+    /// Returns `ConfigError::InvalidValue` naming `JWT_ACCESS_EXPIRY` or
+    /// `JWT_REFRESH_EXPIRY` if either string is not a valid duration.
+    ///
     /// # Panics
     /// Panics if jwt_secret is shorter than MIN_JWT_SECRET_LENGTH (32 bytes)
+    #[allow(dead_code)] // Available for callers holding unparsed expiry strings
     pub fn with_expiry_strings(
         jwt_secret: String,
         access_expiry: &str,
         refresh_expiry: &str,
-    ) -> Self {
-        Self::validate_jwt_secret(&jwt_secret);
-        Self {
+    ) -> ConfigResult<Self> {
+        let parse = |name: &str, value: &str| {
+            parse_duration(value).map_err(|e| ConfigError::InvalidValue(name.to_string(), e))
+        };
+        Ok(Self::with_expiry(
             jwt_secret,
-            access_token_ttl_secs: parse_duration_string(access_expiry).unwrap_or(15 * 60),
-            refresh_token_ttl_secs: parse_duration_string(refresh_expiry).unwrap_or(7 * 24 * 3600),
-            issuer: "resonance".to_string(),
-            audience: "resonance".to_string(),
+            parse("JWT_ACCESS_EXPIRY", access_expiry)?,
+            parse("JWT_REFRESH_EXPIRY", refresh_expiry)?,
+        ))
+    }
+
+    /// Set the issuer minted into tokens and required when verifying them
+    pub fn with_jwt_issuer(mut self, issuer: impl Into<String>) -> Self {
+        self.jwt_issuer = Some(issuer.into());
+        self
+    }
+
+    /// Set the audience minted into tokens and required when verifying them
+    pub fn with_jwt_audience(mut self, audience: impl Into<String>) -> Self {
+        self.jwt_audience = Some(audience.into());
+        self
+    }
+
+    /// Build JWT validation rules for this configuration
+    ///
+    /// A configured issuer or audience must be present in the token and
+    /// match; an unset one is not checked, so tokens minted before it was
+    /// configured keep working.
+    fn token_validation(&self) -> Validation {
+        let mut validation = Validation::default();
+        match &self.jwt_issuer {
+            Some(issuer) => {
+                validation.set_issuer(&[issuer]);
+                validation.required_spec_claims.insert("iss".to_string());
+            }
+            None => validation.iss = None,
         }
+        match &self.jwt_audience {
+            Some(audience) => {
+                validation.set_audience(&[audience]);
+                validation.required_spec_claims.insert("aud".to_string());
+            }
+            None => validation.validate_aud = false,
+        }
+        validation
     }
 
     /// Validate JWT secret meets minimum security requirements
@@ -107,34 +171,30 @@ impl AuthConfig {
 /// Prevents configuration errors from creating excessively long-lived tokens
 const MAX_TOKEN_TTL_SECS: i64 = 90 * 24 * 3600;
 
-/// Parse duration strings like "15m", "7d", "24h" to seconds
-/// Caps the result to MAX_TOKEN_TTL_SECS to prevent excessively long values
-fn parse_duration_string(s: &str) -> Option<i64> {
-    let s = s.trim();
-    if s.is_empty() {
-        return None;
-    }
-
-    let (num_str, unit) = s.split_at(s.len() - 1);
-    let num: i64 = num_str.parse().ok()?;
-
-    // Reject non-positive values
-    if num <= 0 {
-        return None;
-    }
+/// Convert a token lifetime to seconds, capped to MAX_TOKEN_TTL_SECS
+fn capped_ttl_secs(ttl: std::time::Duration) -> i64 {
+    i64::try_from(ttl.as_secs())
+        .unwrap_or(MAX_TOKEN_TTL_SECS)
+        .min(MAX_TOKEN_TTL_SECS)
+}
 
-    // Use checked_mul to prevent integer overflow
-    let secs = match unit {
-        "s" => Some(num),
-        "m" => num.checked_mul(60),
-        "h" => num.checked_mul(3600),
-        "d" => num.checked_mul(24)?.checked_mul(3600),
-        "w" => num.checked_mul(7)?.checked_mul(24)?.checked_mul(3600),
-        _ => None,
-    };
+/// Token type identifier for shared stream links
+const STREAM_SHARE_TOKEN_TYPE: &str = "stream_share";
 
-    // Cap to maximum allowed TTL
-    secs.map(|s| s.min(MAX_TOKEN_TTL_SECS))
+/// Claims carried by a shared stream link token
+///
+/// Signed with the JWT secret like session tokens; the `typ` claim keeps
+/// either kind from being accepted in place of the other.
+#[derive(Debug, Serialize, Deserialize)]
+struct StreamShareClaims {
+    /// Shared track ID
+    sub: Uuid,
+    /// Issued at timestamp
+    iat: i64,
+    /// Expiration timestamp
+    exp: i64,
+    /// Token type identifier
+    typ: String,
 }
 
 /// Authentication service providing registration, login, and token management
@@ -869,19 +929,17 @@ impl AuthService {
     ///
     /// # Errors
     /// - `ApiError::InvalidToken` if token is invalid, expired, or malformed
+    /// - `ApiError::TokenIssuerMismatch` if the token's issuer or audience is
+    ///   missing or doesn't match the configured one
     pub fn verify_access_token(&self, token: &str) -> ApiResult<Claims> {
-        let mut validation = Validation::default();
-        validation.set_issuer(&[&self.config.issuer]);
-        validation.set_audience(&[&self.config.audience]);
-
         let token_data = decode::<Claims>(
             token,
             &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-            &validation,
+            &self.config.token_validation(),
         )
         .map_err(|e| {
             tracing::debug!(error = %e, "Access token verification failed");
-            ApiError::InvalidToken(e.to_string())
+            token_error(e)
         })?;
 
         Ok(token_data.claims)
@@ -889,18 +947,14 @@ impl AuthService {
 
     /// Verify a refresh token and return its claims
     fn verify_refresh_token(&self, token: &str) -> ApiResult<RefreshClaims> {
-        let mut validation = Validation::default();
-        validation.set_issuer(&[&self.config.issuer]);
-        validation.set_audience(&[&self.config.audience]);
-
         let token_data = decode::<RefreshClaims>(
             token,
             &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
-            &validation,
+            &self.config.token_validation(),
         )
         .map_err(|e| {
             tracing::debug!(error = %e, "Refresh token verification failed");
-            ApiError::InvalidToken(e.to_string())
+            token_error(e)
         })?;
 
         // Verify it's a refresh token
@@ -911,6 +965,58 @@ impl AuthService {
         Ok(token_data.claims)
     }
 
+    /// Sign a token granting access to stream one track until `ttl` elapses
+    ///
+    /// The token is the `:token` segment of `GET /stream/shared/:token`. It
+    /// is HMAC-signed (HS256) with the JWT secret and needs no session, so
+    /// anyone holding it can stream the track until it expires.
+    pub fn sign_stream_url(&self, track_id: Uuid, ttl: Duration) -> String {
+        let now = Utc::now().timestamp();
+        let claims = StreamShareClaims {
+            sub: track_id,
+            iat: now,
+            exp: now + ttl.num_seconds(),
+            typ: STREAM_SHARE_TOKEN_TYPE.to_string(),
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.config.jwt_secret.as_bytes()),
+        )
+        .expect("HS256 encoding of stream share claims cannot fail")
+    }
+
+    /// Verify a shared stream token and return the track it grants access to
+    ///
+    /// # Errors
+    /// - `ApiError::Forbidden` if the token is expired, tampered with, or
+    ///   not a stream share token
+    pub fn verify_stream_token(&self, token: &str) -> ApiResult<Uuid> {
+        // Share links are checked to the second; no clock-skew leeway
+        let mut validation = Validation::default();
+        validation.leeway = 0;
+        validation.validate_aud = false;
+
+        let token_data = decode::<StreamShareClaims>(
+            token,
+            &DecodingKey::from_secret(self.config.jwt_secret.as_bytes()),
+            &validation,
+        )
+        .map_err(|e| {
+            tracing::debug!(error = %e, "Stream share token verification failed");
+            ApiError::Forbidden("invalid or expired share link".to_string())
+        })?;
+
+        if token_data.claims.typ != STREAM_SHARE_TOKEN_TYPE {
+            return Err(ApiError::Forbidden(
+                "invalid or expired share link".to_string(),
+            ));
+        }
+
+        Ok(token_data.claims.sub)
+    }
+
     /// Create a new session for a user
     async fn create_session(
         &self,
@@ -932,16 +1038,13 @@ impl AuthService {
         let access_token_hash = hash_token(&access_token);
         let refresh_token_hash = hash_token(&refresh_token);
 
-        // Extract device info
+        // Extract device info; an explicit device type wins over the user agent
         let (device_name, device_type, device_id) = device_info
-            .map(|d| {
-                (
-                    d.device_name,
-                    d.device_type.map(|t| t.to_string()),
-                    d.device_id,
-                )
-            })
+            .map(|d| (d.device_name, d.device_type, d.device_id))
             .unwrap_or((None, None, None));
+        let device_type = device_type
+            .or_else(|| user_agent.map(DeviceType::from_user_agent))
+            .map(|t| t.to_string());
 
         // Create session record using repository
         self.session_repo
@@ -969,12 +1072,21 @@ impl AuthService {
     /// Generate a pair of access and refresh tokens
     fn generate_token_pair(&self, user: &User, session_id: Uuid) -> ApiResult<(String, String)> {
         // Create access token claims
-        let access_claims = Claims::new(user, session_id, self.config.access_token_ttl_secs);
+        let mut access_claims = Claims::new(user, session_id, self.config.access_token_ttl_secs);
 
         // Create refresh token claims
-        let refresh_claims =
+        let mut refresh_claims =
             RefreshClaims::new(user.id, session_id, self.config.refresh_token_ttl_secs);
 
+        if let Some(issuer) = &self.config.jwt_issuer {
+            access_claims.iss = issuer.clone();
+            refresh_claims.iss = issuer.clone();
+        }
+        if let Some(audience) = &self.config.jwt_audience {
+            access_claims.aud = audience.clone();
+            refresh_claims.aud = audience.clone();
+        }
+
         // Encode access token
         let access_token = encode(
             &Header::default(),
@@ -1178,6 +1290,24 @@ pub fn is_valid_email(email: &str) -> bool {
     domain.split('.').all(|part| !part.is_empty())
 }
 
+/// Map a JWT decoding error to an API error
+///
+/// Issuer and audience failures get a dedicated error so replayed tokens
+/// from another instance are distinguishable from expired or forged ones.
+fn token_error(error: jsonwebtoken::errors::Error) -> ApiError {
+    use jsonwebtoken::errors::ErrorKind;
+
+    match error.kind() {
+        ErrorKind::InvalidIssuer | ErrorKind::InvalidAudience => {
+            ApiError::TokenIssuerMismatch(error.to_string())
+        }
+        ErrorKind::MissingRequiredClaim(claim) if claim == "iss" || claim == "aud" => {
+            ApiError::TokenIssuerMismatch(error.to_string())
+        }
+        _ => ApiError::InvalidToken(error.to_string()),
+    }
+}
+
 /// Normalize email address for consistent storage and lookup
 ///
 /// Normalization includes:
@@ -1198,15 +1328,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_parse_duration_string() {
-        assert_eq!(parse_duration_string("15m"), Some(900));
-        assert_eq!(parse_duration_string("7d"), Some(604800));
-        assert_eq!(parse_duration_string("24h"), Some(86400));
-        assert_eq!(parse_duration_string("30s"), Some(30));
-        assert_eq!(parse_duration_string("1w"), Some(604800));
-        assert_eq!(parse_duration_string(""), None);
-        assert_eq!(parse_duration_string("invalid"), None);
-        assert_eq!(parse_duration_string("15x"), None);
+    fn test_capped_ttl_secs() {
+        use std::time::Duration as StdDuration;
+
+        assert_eq!(capped_ttl_secs(StdDuration::from_secs(900)), 900);
+        assert_eq!(
+            capped_ttl_secs(StdDuration::from_secs(365 * 24 * 3600)),
+            MAX_TOKEN_TTL_SECS
+        );
+        assert_eq!(capped_ttl_secs(StdDuration::MAX), MAX_TOKEN_TTL_SECS);
     }
 
     #[test]
@@ -1275,23 +1405,257 @@ mod tests {
         let config = AuthConfig::new(TEST_JWT_SECRET.to_string());
         assert_eq!(config.access_token_ttl_secs, 15 * 60);
         assert_eq!(config.refresh_token_ttl_secs, 7 * 24 * 3600);
-        assert_eq!(config.issuer, "resonance");
-        assert_eq!(config.audience, "resonance");
+        assert_eq!(config.jwt_issuer, None);
+        assert_eq!(config.jwt_audience, None);
+    }
+
+    /// Auth service that never touches its (lazy) database pool
+    fn token_service(config: AuthConfig) -> AuthService {
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .connect_lazy("postgres://localhost/unused")
+            .unwrap();
+        AuthService::new(pool, config)
+    }
+
+    fn mint(claims: serde_json::Value) -> String {
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(TEST_JWT_SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    /// Access token claims with optional issuer and audience
+    fn access_claims(iss: Option<&str>, aud: Option<&str>) -> serde_json::Value {
+        let now = Utc::now().timestamp();
+        let mut claims = serde_json::json!({
+            "sub": Uuid::new_v4(),
+            "email": "user@example.com",
+            "role": "User",
+            "sid": Uuid::new_v4(),
+            "iat": now,
+            "exp": now + 600,
+        });
+        if let Some(iss) = iss {
+            claims["iss"] = iss.into();
+        }
+        if let Some(aud) = aud {
+            claims["aud"] = aud.into();
+        }
+        claims
+    }
+
+    fn scoped_config() -> AuthConfig {
+        AuthConfig::new(TEST_JWT_SECRET.to_string())
+            .with_jwt_issuer("https://music-a.example.com")
+            .with_jwt_audience("resonance-a")
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_with_matching_issuer_and_audience() {
+        let service = token_service(scoped_config());
+        let token = mint(access_claims(
+            Some("https://music-a.example.com"),
+            Some("resonance-a"),
+        ));
+
+        let claims = service.verify_access_token(&token).unwrap();
+        assert_eq!(claims.iss, "https://music-a.example.com");
+        assert_eq!(claims.aud, "resonance-a");
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_with_mismatched_issuer_or_audience() {
+        let service = token_service(scoped_config());
+
+        for (iss, aud) in [
+            ("https://music-b.example.com", "resonance-a"),
+            ("https://music-a.example.com", "resonance-b"),
+        ] {
+            let token = mint(access_claims(Some(iss), Some(aud)));
+            assert!(matches!(
+                service.verify_access_token(&token),
+                Err(ApiError::TokenIssuerMismatch(_))
+            ));
+        }
+
+        // Refresh tokens are held to the same rules
+        let mut refresh = access_claims(Some("https://music-b.example.com"), Some("resonance-a"));
+        refresh["jti"] = Uuid::new_v4().to_string().into();
+        refresh["typ"] = "refresh".into();
+        assert!(matches!(
+            service.verify_refresh_token(&mint(refresh)),
+            Err(ApiError::TokenIssuerMismatch(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_verify_token_with_absent_issuer_and_audience() {
+        let token = mint(access_claims(None, None));
+
+        // Required once configured
+        assert!(matches!(
+            token_service(scoped_config()).verify_access_token(&token),
+            Err(ApiError::TokenIssuerMismatch(_))
+        ));
+
+        // Permissive when unset, whether or not the token carries the claims
+        let permissive = token_service(AuthConfig::new(TEST_JWT_SECRET.to_string()));
+        assert!(permissive.verify_access_token(&token).is_ok());
+        let foreign = mint(access_claims(Some("anyone"), Some("anything")));
+        assert!(permissive.verify_access_token(&foreign).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_minted_tokens_carry_configured_issuer_and_audience() {
+        let service = token_service(scoped_config());
+        let now = Utc::now();
+        let user = User {
+            id: Uuid::new_v4(),
+            email: "user@example.com".to_string(),
+            password_hash: String::new(),
+            display_name: "User".to_string(),
+            avatar_url: None,
+            role: UserRole::User,
+            preferences: UserPreferences::default(),
+            listenbrainz_token: None,
+            discord_user_id: None,
+            email_verified: true,
+            last_seen_at: None,
+            created_at: now,
+            updated_at: now,
+            password_updated_at: now,
+        };
+
+        let (access, refresh) = service.generate_token_pair(&user, Uuid::new_v4()).unwrap();
+
+        let claims = service.verify_access_token(&access).unwrap();
+        assert_eq!(claims.iss, "https://music-a.example.com");
+        assert_eq!(claims.aud, "resonance-a");
+        assert!(service.verify_refresh_token(&refresh).is_ok());
+
+        // Another instance sharing the secret rejects them
+        let other = token_service(
+            AuthConfig::new(TEST_JWT_SECRET.to_string())
+                .with_jwt_issuer("https://music-b.example.com")
+                .with_jwt_audience("resonance-b"),
+        );
+        assert!(matches!(
+            other.verify_access_token(&access),
+            Err(ApiError::TokenIssuerMismatch(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_share_token_round_trip() {
+        let service = token_service(AuthConfig::new(TEST_JWT_SECRET.to_string()));
+        let track_id = Uuid::new_v4();
+
+        let token = service.sign_stream_url(track_id, Duration::hours(1));
+        assert_eq!(service.verify_stream_token(&token).unwrap(), track_id);
+    }
+
+    #[tokio::test]
+    async fn test_stream_share_token_expired() {
+        let service = token_service(AuthConfig::new(TEST_JWT_SECRET.to_string()));
+
+        let token = service.sign_stream_url(Uuid::new_v4(), Duration::seconds(-1));
+        assert!(matches!(
+            service.verify_stream_token(&token),
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_share_token_tampered() {
+        let service = token_service(AuthConfig::new(TEST_JWT_SECRET.to_string()));
+        let token = service.sign_stream_url(Uuid::new_v4(), Duration::hours(1));
+
+        // Swap the payload for one naming another track, keeping the signature
+        let parts: Vec<&str> = token.split('.').collect();
+        let forged = mint(serde_json::json!({
+            "sub": Uuid::new_v4(),
+            "iat": Utc::now().timestamp(),
+            "exp": Utc::now().timestamp() + 3600,
+            "typ": "stream_share",
+        }));
+        let forged_payload = forged.split('.').nth(1).unwrap();
+        let tampered = format!("{}.{}.{}", parts[0], forged_payload, parts[2]);
+        assert!(matches!(
+            service.verify_stream_token(&tampered),
+            Err(ApiError::Forbidden(_))
+        ));
+
+        // A token signed with another secret is rejected too
+        let other = token_service(AuthConfig::new(
+            "another-jwt-secret-that-is-at-least-32-characters".to_string(),
+        ));
+        let foreign = other.sign_stream_url(Uuid::new_v4(), Duration::hours(1));
+        assert!(matches!(
+            service.verify_stream_token(&foreign),
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stream_share_token_rejects_session_tokens() {
+        let service = token_service(AuthConfig::new(TEST_JWT_SECRET.to_string()));
+
+        let mut refresh = access_claims(None, None);
+        refresh["jti"] = Uuid::new_v4().to_string().into();
+        refresh["typ"] = "refresh".into();
+        assert!(service.verify_stream_token(&mint(refresh)).is_err());
+        assert!(service
+            .verify_stream_token(&mint(access_claims(None, None)))
+            .is_err());
+
+        // Nor is a share token accepted as an access token
+        let share = service.sign_stream_url(Uuid::new_v4(), Duration::hours(1));
+        assert!(service.verify_access_token(&share).is_err());
     }
 
     #[test]
     fn test_auth_config_with_expiry_strings() {
-        let config = AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "30m", "14d");
+        let config =
+            AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "30m", "14d").unwrap();
         assert_eq!(config.access_token_ttl_secs, 30 * 60);
         assert_eq!(config.refresh_token_ttl_secs, 14 * 24 * 3600);
+
+        for (expiry, secs) in [
+            ("30s", 30),
+            ("15m", 15 * 60),
+            ("12h", 12 * 3600),
+            ("7d", 7 * 24 * 3600),
+            ("1w", 7 * 24 * 3600),
+            ("600", 600),
+        ] {
+            let config =
+                AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), expiry, "7d").unwrap();
+            assert_eq!(config.access_token_ttl_secs, secs, "expiry {}", expiry);
+        }
     }
 
     #[test]
-    fn test_auth_config_invalid_expiry_uses_default() {
+    fn test_auth_config_expiry_capped() {
         let config =
-            AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "invalid", "also_invalid");
-        assert_eq!(config.access_token_ttl_secs, 15 * 60);
-        assert_eq!(config.refresh_token_ttl_secs, 7 * 24 * 3600);
+            AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "15m", "52w").unwrap();
+        assert_eq!(config.refresh_token_ttl_secs, MAX_TOKEN_TTL_SECS);
+    }
+
+    #[test]
+    fn test_auth_config_invalid_expiry_is_error() {
+        let err = AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "invalid", "7d")
+            .unwrap_err();
+        assert!(
+            matches!(err, ConfigError::InvalidValue(ref name, _) if name == "JWT_ACCESS_EXPIRY")
+        );
+
+        let err =
+            AuthConfig::with_expiry_strings(TEST_JWT_SECRET.to_string(), "15m", "15x").unwrap_err();
+        assert!(
+            matches!(err, ConfigError::InvalidValue(ref name, _) if name == "JWT_REFRESH_EXPIRY")
+        );
     }
 
     #[test]
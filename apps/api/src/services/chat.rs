@@ -6,6 +6,8 @@
 // Allow dead_code - WebSocket integration (Phase 4) will consume this service
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+
 use futures_util::StreamExt;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -16,12 +18,16 @@ use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
 use crate::models::chat::{
-    ChatConversation, ChatMessage, ChatRole, ContextSnapshot, CreateChatMessage,
-    CreateConversation, ToolCall, ToolCallFunction,
+    ChatConversation, ChatMessage, ChatRole, ContextSnapshot, ConversationFilter, ConversationPage,
+    CreateChatMessage, CreateConversation, ToolCall, ToolCallFunction,
 };
-use crate::repositories::ChatRepository;
-use crate::services::search::SearchService;
-use crate::services::similarity::SimilarityService;
+use crate::models::SimilarityWeights;
+use crate::repositories::{ChatRepository, UserRepository};
+use crate::services::circuit_breaker::{CircuitBreaker, CircuitBreakerConfig};
+use crate::services::metrics::Metrics;
+use crate::services::search::{SearchFilters, SearchService};
+use crate::services::similarity::{ScoreBreakdown, SimilarTrack, SimilarityService, TrackSignals};
+use crate::utils::{truncate_on_char_boundary, truncate_with_ellipsis};
 use resonance_ollama_client::OllamaClient;
 use resonance_shared_config::OllamaConfig;
 
@@ -40,6 +46,17 @@ pub enum ChatError {
     #[error("ollama response error: {0}")]
     OllamaResponse(String),
 
+    /// Ollama is still loading the model; the request can be retried shortly
+    #[error("ollama model is loading")]
+    ModelLoading,
+
+    /// Ollama is down and the circuit breaker is failing fast
+    #[error("ollama unavailable")]
+    Unavailable {
+        /// Seconds until the circuit breaker lets a probe through
+        retry_after_secs: Option<u64>,
+    },
+
     #[error("json serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 
@@ -66,8 +83,16 @@ impl From<ChatError> for crate::error::ApiError {
                 resource_type: "conversation",
                 id: id.to_string(),
             },
+            // Ollama can't be reached at all, as opposed to answering badly
+            ChatError::OllamaRequest(e) if e.is_connect() => {
+                crate::error::ApiError::AiUnavailable { retry_after: None }
+            }
             ChatError::OllamaRequest(e) => crate::error::ApiError::AiService(e.to_string()),
             ChatError::OllamaResponse(msg) => crate::error::ApiError::AiService(msg),
+            ChatError::ModelLoading => crate::error::ApiError::AiModelLoading,
+            ChatError::Unavailable { retry_after_secs } => crate::error::ApiError::AiUnavailable {
+                retry_after: retry_after_secs,
+            },
             ChatError::Serialization(e) => crate::error::ApiError::Serialization(e),
             ChatError::ToolExecution { tool_name, message } => crate::error::ApiError::AiService(
                 format!("Tool '{}' failed: {}", tool_name, message),
@@ -172,6 +197,8 @@ pub struct UserContext {
     pub top_genres: Vec<String>,
     pub current_track_id: Option<Uuid>,
     pub current_track_title: Option<String>,
+    /// The user's preferred similarity blend for recommendations
+    pub similarity_weights: Option<SimilarityWeights>,
 }
 
 impl From<&UserContext> for ContextSnapshot {
@@ -267,6 +294,10 @@ pub enum StreamErrorCode {
     OllamaRequest,
     /// Ollama response error
     OllamaResponse,
+    /// Ollama is still loading the model
+    ModelLoading,
+    /// Ollama is down
+    Unavailable,
     /// JSON serialization error
     Serialization,
     /// Tool execution failed
@@ -290,6 +321,14 @@ impl StreamEvent {
             ),
             ChatError::OllamaRequest(e) => (e.to_string(), StreamErrorCode::OllamaRequest),
             ChatError::OllamaResponse(msg) => (msg.clone(), StreamErrorCode::OllamaResponse),
+            ChatError::ModelLoading => (
+                "AI model is warming up, try again shortly".to_string(),
+                StreamErrorCode::ModelLoading,
+            ),
+            ChatError::Unavailable { .. } => (
+                AI_UNAVAILABLE_MESSAGE.to_string(),
+                StreamErrorCode::Unavailable,
+            ),
             ChatError::Serialization(e) => (e.to_string(), StreamErrorCode::Serialization),
             ChatError::ToolExecution { tool_name, message } => (
                 format!("Tool '{}' failed: {}", tool_name, message),
@@ -320,6 +359,137 @@ const TOTAL_TIMEOUT_MULTIPLIER: u64 = 2;
 /// Channel capacity for streaming events
 const STREAM_CHANNEL_CAPACITY: usize = 100;
 
+/// Maximum messages deleted in a single bulk request
+const MAX_BULK_DELETE_MESSAGES: usize = 500;
+
+/// Words of the first message used as a new conversation's title
+const TITLE_WORDS: usize = 5;
+
+/// Maximum conversation title length in bytes, before the `...`
+const MAX_TITLE_BYTES: usize = 100;
+
+/// Error message streamed while the Ollama circuit breaker is open
+const AI_UNAVAILABLE_MESSAGE: &str = "AI temporarily unavailable";
+
+/// Default work budget for one chat request's tool calls
+///
+/// Enough for three semantic searches plus a few cheap actions.
+const DEFAULT_TOOL_BUDGET: u32 = 16;
+
+/// Reply when the tool budget runs out before the model produced an answer
+const TOOL_BUDGET_EXHAUSTED_MESSAGE: &str = "I searched as much as I can for one request. \
+Here is what I found so far - try a more specific question to narrow it down.";
+
+/// Title for a new conversation: the first few words of its first message
+///
+/// Long words are cut so a pasted blob doesn't become the title.
+fn conversation_title(message: &str) -> String {
+    let words = message
+        .split_whitespace()
+        .take(TITLE_WORDS)
+        .collect::<Vec<_>>()
+        .join(" ");
+    truncate_with_ellipsis(&words, MAX_TITLE_BYTES)
+}
+
+// ==================== Configuration ====================
+
+/// Chat service settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChatConfig {
+    /// Work budget for the tool calls of one request (see [`ToolBudget`])
+    pub tool_budget: u32,
+}
+
+impl Default for ChatConfig {
+    fn default() -> Self {
+        Self {
+            tool_budget: DEFAULT_TOOL_BUDGET,
+        }
+    }
+}
+
+impl ChatConfig {
+    /// Load configuration from environment variables
+    ///
+    /// Environment variables:
+    /// - `CHAT_TOOL_BUDGET` (default: 16)
+    pub fn from_env() -> Self {
+        let tool_budget = std::env::var("CHAT_TOOL_BUDGET")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TOOL_BUDGET);
+
+        Self { tool_budget }
+    }
+}
+
+/// Estimated work done by one tool execution
+///
+/// Costs are relative: a semantic search (an embedding request plus a vector
+/// query) is the unit everything else is weighed against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCost {
+    /// Client-side actions that don't touch the database (play, queue)
+    Action = 1,
+    /// A single indexed database query (mood search, playlist creation)
+    Query = 2,
+    /// Similarity lookups over the library
+    Recommendation = 3,
+    /// Embedding generation plus a vector search
+    SemanticSearch = 5,
+}
+
+impl ToolCost {
+    /// Estimate the cost of calling `function_name` with `arguments`
+    pub fn of(function_name: &str, arguments: &str) -> Self {
+        match function_name {
+            "search_library" => {
+                let search_type = serde_json::from_str::<serde_json::Value>(arguments)
+                    .ok()
+                    .and_then(|args| args.get("search_type")?.as_str().map(str::to_lowercase));
+                match search_type.as_deref().map(str::trim) {
+                    Some("mood") => Self::Query,
+                    _ => Self::SemanticSearch,
+                }
+            }
+            "get_recommendations" => Self::Recommendation,
+            "create_playlist" => Self::Query,
+            _ => Self::Action,
+        }
+    }
+}
+
+/// Remaining work budget for one chat request's tool loop
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToolBudget {
+    remaining: u32,
+}
+
+impl ToolBudget {
+    pub fn new(budget: u32) -> Self {
+        Self { remaining: budget }
+    }
+
+    /// Debit `cost` if the budget covers it
+    ///
+    /// Returns `false`, leaving the budget untouched, when it doesn't.
+    pub fn try_spend(&mut self, cost: ToolCost) -> bool {
+        match self.remaining.checked_sub(cost as u32) {
+            Some(remaining) => {
+                self.remaining = remaining;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Budget left
+    pub fn remaining(&self) -> u32 {
+        self.remaining
+    }
+}
+
 // ==================== Chat Service ====================
 
 /// Service for AI chat functionality
@@ -337,6 +507,12 @@ pub struct ChatService {
     similarity_service: SimilarityService,
     /// Ollama client for generating embeddings
     ollama_client: Option<OllamaClient>,
+    /// Fails chat requests fast while Ollama is down (shared across clones)
+    circuit_breaker: CircuitBreaker,
+    /// Chat settings such as the tool budget
+    chat_config: ChatConfig,
+    /// Registry for Ollama request latency
+    metrics: Metrics,
 }
 
 impl ChatService {
@@ -373,9 +549,63 @@ impl ChatService {
             search_service,
             similarity_service,
             ollama_client,
+            circuit_breaker: CircuitBreaker::default(),
+            chat_config: ChatConfig::default(),
+            metrics: Metrics::new(),
         })
     }
 
+    /// Use a custom circuit breaker configuration for Ollama calls
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = CircuitBreaker::new(config);
+        self
+    }
+
+    /// Use custom chat settings
+    pub fn with_chat_config(mut self, config: ChatConfig) -> Self {
+        self.chat_config = config;
+        self
+    }
+
+    /// Record Ollama request latency into `metrics`, e.g. the server's shared registry
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Admit an Ollama call through the circuit breaker
+    ///
+    /// Returns `ChatError::Unavailable` immediately while the circuit is open,
+    /// with the remaining cool-down as the retry hint.
+    fn acquire_ollama(&self) -> ChatResult<()> {
+        if self.circuit_breaker.try_acquire() {
+            Ok(())
+        } else {
+            warn!("Ollama circuit breaker open, failing fast");
+            Err(ChatError::Unavailable {
+                retry_after_secs: self
+                    .circuit_breaker
+                    .retry_after()
+                    .map(|remaining| remaining.as_secs_f64().ceil() as u64),
+            })
+        }
+    }
+
+    /// Report the outcome of an Ollama call to the circuit breaker
+    ///
+    /// Only failures talking to Ollama count; database or input errors
+    /// say nothing about Ollama's health, and a model that is still loading
+    /// is a healthy Ollama warming up.
+    fn record_ollama_outcome<T>(&self, result: &ChatResult<T>) {
+        match result {
+            Ok(_) => self.circuit_breaker.record_success(),
+            Err(
+                ChatError::OllamaRequest(_) | ChatError::OllamaResponse(_) | ChatError::Timeout,
+            ) => self.circuit_breaker.record_failure(),
+            Err(_) => {}
+        }
+    }
+
     /// Create a new conversation
     #[instrument(skip(self))]
     pub async fn create_conversation(
@@ -400,6 +630,24 @@ impl ChatService {
             .ok_or(ChatError::ConversationNotFound(conversation_id))
     }
 
+    /// Get a conversation that can receive new messages
+    ///
+    /// Archived conversations are rejected so their history never reaches
+    /// the AI context.
+    async fn get_active_conversation(
+        &self,
+        conversation_id: Uuid,
+        user_id: Uuid,
+    ) -> ChatResult<ChatConversation> {
+        let conversation = self.get_conversation(conversation_id, user_id).await?;
+        if conversation.archived_at.is_some() {
+            return Err(ChatError::InvalidInput(
+                "Archived conversations cannot be continued".to_string(),
+            ));
+        }
+        Ok(conversation)
+    }
+
     /// List conversations for a user
     #[instrument(skip(self))]
     pub async fn list_conversations(
@@ -414,6 +662,42 @@ impl ChatService {
             .await?)
     }
 
+    /// List a page of conversations matching a filter, with the total count
+    ///
+    /// The total counts every conversation matching the filter, so clients
+    /// can paginate and group (e.g. "this week") without fetching everything.
+    ///
+    /// # Errors
+    /// Returns `ChatError::InvalidInput` if `created_after` is not before `created_before`
+    #[instrument(skip(self))]
+    pub async fn list_conversations_filtered(
+        &self,
+        user_id: Uuid,
+        filter: &ConversationFilter,
+        limit: i64,
+        offset: i64,
+    ) -> ChatResult<ConversationPage> {
+        if let (Some(after), Some(before)) = (filter.created_after, filter.created_before) {
+            if after >= before {
+                return Err(ChatError::InvalidInput(
+                    "created_after must be before created_before".to_string(),
+                ));
+            }
+        }
+
+        let (conversations, total_count) = tokio::try_join!(
+            self.repository
+                .find_conversations_filtered(user_id, filter, limit, offset),
+            self.repository
+                .count_conversations_filtered(user_id, filter),
+        )?;
+
+        Ok(ConversationPage {
+            conversations,
+            total_count,
+        })
+    }
+
     /// Delete a conversation (soft delete)
     #[instrument(skip(self))]
     pub async fn delete_conversation(
@@ -427,6 +711,48 @@ impl ChatService {
             .await?)
     }
 
+    /// Archive a conversation
+    ///
+    /// Archived conversations are hidden from the default conversation list
+    /// and can no longer be continued; list them with
+    /// `ConversationFilter::include_archived`.
+    #[instrument(skip(self))]
+    pub async fn archive_conversation(
+        &self,
+        conversation_id: Uuid,
+        user_id: Uuid,
+    ) -> ChatResult<ChatConversation> {
+        self.repository
+            .archive_conversation(conversation_id, user_id)
+            .await?
+            .ok_or(ChatError::ConversationNotFound(conversation_id))
+    }
+
+    /// Delete a single message (soft delete)
+    #[instrument(skip(self))]
+    pub async fn delete_message(&self, message_id: Uuid, user_id: Uuid) -> ChatResult<bool> {
+        Ok(self.repository.delete_message(message_id, user_id).await?)
+    }
+
+    /// Delete several messages at once (soft delete)
+    ///
+    /// Returns the number of messages deleted.
+    #[instrument(skip(self, message_ids), fields(count = message_ids.len()))]
+    pub async fn delete_messages(&self, message_ids: &[Uuid], user_id: Uuid) -> ChatResult<i64> {
+        if message_ids.len() > MAX_BULK_DELETE_MESSAGES {
+            return Err(ChatError::InvalidInput(format!(
+                "Too many messages: {} (max {})",
+                message_ids.len(),
+                MAX_BULK_DELETE_MESSAGES
+            )));
+        }
+
+        Ok(self
+            .repository
+            .delete_messages(message_ids, user_id)
+            .await?)
+    }
+
     /// Get messages for a conversation
     #[instrument(skip(self))]
     pub async fn get_messages(
@@ -476,15 +802,10 @@ impl ChatService {
 
         // Create or get conversation
         let conversation = match conversation_id {
-            Some(id) => self.get_conversation(id, user_id).await?,
+            Some(id) => self.get_active_conversation(id, user_id).await?,
             None => {
-                // Generate title from first few words of message
-                let title = message
-                    .split_whitespace()
-                    .take(5)
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                self.create_conversation(user_id, Some(title)).await?
+                self.create_conversation(user_id, Some(conversation_title(&message)))
+                    .await?
             }
         };
 
@@ -592,15 +913,10 @@ impl ChatService {
 
         // Create or get conversation
         let conversation = match conversation_id {
-            Some(id) => self.get_conversation(id, user_id).await?,
+            Some(id) => self.get_active_conversation(id, user_id).await?,
             None => {
-                // Generate title from first few words of message
-                let title = message
-                    .split_whitespace()
-                    .take(5)
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                self.create_conversation(user_id, Some(title)).await?
+                self.create_conversation(user_id, Some(conversation_title(&message)))
+                    .await?
             }
         };
 
@@ -659,35 +975,37 @@ impl ChatService {
                 .saturating_mul(TOTAL_TIMEOUT_MULTIPLIER),
         );
 
+        // Get Ollama client for streaming
+        let Some(ref ollama) = self.ollama_client else {
+            let error =
+                ChatError::OllamaResponse("Ollama client not configured for streaming".to_string());
+            let _ = tx.send(StreamEvent::from_error(&error)).await;
+            return;
+        };
+
+        if let Err(e) = self.acquire_ollama() {
+            let _ = tx.send(StreamEvent::from_error(&e)).await;
+            return;
+        }
+
         let result = tokio::time::timeout(
             total_timeout,
-            self.stream_with_tool_calls_inner(conversation_id, user_id, &context, &tx),
+            self.stream_with_tool_calls_inner(ollama, conversation_id, user_id, &context, &tx),
         )
-        .await;
+        .await
+        .unwrap_or(Err(ChatError::Timeout));
+        self.record_ollama_outcome(&result);
 
-        match result {
-            Ok(Ok(())) => {
-                // Successfully completed, Complete event already sent
-            }
-            Ok(Err(e)) => {
-                // Error during streaming
-                let _ = tx.send(StreamEvent::from_error(&e)).await;
-            }
-            Err(_) => {
-                // Timeout
-                let _ = tx
-                    .send(StreamEvent::Error {
-                        message: "Operation timed out".to_string(),
-                        code: StreamErrorCode::Timeout,
-                    })
-                    .await;
-            }
+        if let Err(e) = result {
+            let _ = tx.send(StreamEvent::from_error(&e)).await;
         }
+        // On success the Complete event has already been sent
     }
 
     /// Inner implementation of streaming with tool calls
     async fn stream_with_tool_calls_inner(
         &self,
+        ollama: &OllamaClient,
         conversation_id: Uuid,
         user_id: Uuid,
         context: &UserContext,
@@ -718,18 +1036,14 @@ impl ChatService {
             });
         }
 
-        // Get Ollama client for streaming
-        let Some(ref ollama) = self.ollama_client else {
-            return Err(ChatError::OllamaResponse(
-                "Ollama client not configured for streaming".to_string(),
-            ));
-        };
-
         // Start streaming
         let mut stream = ollama
             .chat_stream(messages, None)
             .await
-            .map_err(|e| ChatError::OllamaResponse(format!("Failed to start stream: {}", e)))?;
+            .map_err(|e| match e {
+                resonance_ollama_client::OllamaError::ModelLoading(_) => ChatError::ModelLoading,
+                e => ChatError::OllamaResponse(format!("Failed to start stream: {}", e)),
+            })?;
 
         let mut full_response = String::new();
 
@@ -819,6 +1133,9 @@ impl ChatService {
     }
 
     /// Chat with Ollama, handling tool calling loop with total operation timeout
+    ///
+    /// Calls go through the circuit breaker: while Ollama is failing, requests
+    /// fail fast instead of waiting for the timeout.
     #[instrument(skip(self, history, context))]
     async fn chat_with_ollama(
         &self,
@@ -831,9 +1148,14 @@ impl ChatService {
                 .saturating_mul(TOTAL_TIMEOUT_MULTIPLIER),
         );
 
-        tokio::time::timeout(total_timeout, self.chat_with_ollama_inner(history, context))
-            .await
-            .map_err(|_| ChatError::Timeout)?
+        self.acquire_ollama()?;
+
+        let result =
+            tokio::time::timeout(total_timeout, self.chat_with_ollama_inner(history, context))
+                .await
+                .unwrap_or(Err(ChatError::Timeout));
+        self.record_ollama_outcome(&result);
+        result
     }
 
     /// Inner implementation of chat_with_ollama without timeout wrapper
@@ -876,6 +1198,7 @@ impl ChatService {
         let mut all_tool_calls = Vec::new();
         let mut all_actions = Vec::new();
         let mut iteration = 0;
+        let mut budget = ToolBudget::new(self.chat_config.tool_budget);
 
         // Tool calling loop
         loop {
@@ -898,13 +1221,16 @@ impl ChatService {
 
             debug!("Sending request to Ollama");
 
+            let started = std::time::Instant::now();
             let response = self
                 .http_client
                 .post(self.config.chat_url())
                 .json(&request)
                 .timeout(std::time::Duration::from_secs(self.config.timeout_secs))
                 .send()
-                .await?;
+                .await;
+            self.metrics.observe_ollama_request(started.elapsed());
+            let response = response?;
 
             if !response.status().is_success() {
                 let status = response.status();
@@ -913,17 +1239,20 @@ impl ChatService {
                     Err(e) => format!("Failed to read error body: {}", e),
                 };
                 // Log truncated body to avoid flooding logs
-                // Use char boundary to avoid panic on multi-byte UTF-8 characters
                 const MAX_LOG_BODY: usize = 4096;
-                let truncated_body = if body.len() > MAX_LOG_BODY {
-                    let mut cut = MAX_LOG_BODY;
-                    while cut > 0 && !body.is_char_boundary(cut) {
-                        cut -= 1;
-                    }
-                    format!("{}...[truncated {} bytes]", &body[..cut], body.len() - cut)
+                let kept = truncate_on_char_boundary(&body, MAX_LOG_BODY);
+                let truncated_body = if kept.len() < body.len() {
+                    format!("{}...[truncated {} bytes]", kept, body.len() - kept.len())
                 } else {
                     body
                 };
+                if resonance_ollama_client::is_model_loading_response(
+                    status.as_u16(),
+                    &truncated_body,
+                ) {
+                    warn!(status = %status, "Ollama is still loading the model");
+                    return Err(ChatError::ModelLoading);
+                }
                 error!(status = %status, body = %truncated_body, "Ollama request failed");
                 // Return sanitized error to caller
                 return Err(ChatError::OllamaResponse(format!(
@@ -958,7 +1287,24 @@ impl ChatService {
 
                     // Execute each tool and collect results
                     for tool_call in tool_calls {
-                        let (result, action) = self.execute_tool(tool_call).await;
+                        let cost =
+                            ToolCost::of(&tool_call.function.name, &tool_call.function.arguments);
+                        if !budget.try_spend(cost) {
+                            warn!(
+                                tool = %tool_call.function.name,
+                                ?cost,
+                                remaining = budget.remaining(),
+                                iteration,
+                                "Tool budget exhausted, returning partial answer"
+                            );
+                            return Ok((
+                                TOOL_BUDGET_EXHAUSTED_MESSAGE.to_string(),
+                                all_tool_calls,
+                                all_actions,
+                            ));
+                        }
+
+                        let (result, action) = self.execute_tool(tool_call, context).await;
 
                         // Add tool result message
                         messages.push(OllamaMessage {
@@ -1059,7 +1405,7 @@ You can help users with their music library by:
                 tool_type: "function".to_string(),
                 function: OllamaToolFunction {
                     name: "search_library".to_string(),
-                    description: "Search the user's music library. Use search_type 'track' for finding specific songs/artists/albums by name, or 'mood' for finding tracks matching a mood or vibe."
+                    description: "Search the user's music library. Use search_type 'track' for finding specific songs/artists/albums by name, 'mood' for finding tracks matching a mood or vibe, or 'hybrid' to combine a description with mood, genre, or year filters (e.g. upbeat tracks about the ocean)."
                         .to_string(),
                     parameters: serde_json::json!({
                         "type": "object",
@@ -1070,8 +1416,26 @@ You can help users with their music library by:
                             },
                             "search_type": {
                                 "type": "string",
-                                "enum": ["track", "mood"],
-                                "description": "Search mode: 'track' (default) for semantic search using AI embeddings, 'mood' for finding tracks by mood tags like 'happy' or 'energetic'"
+                                "enum": ["track", "mood", "hybrid"],
+                                "description": "Search mode: 'track' (default) for semantic search using AI embeddings, 'mood' for finding tracks by mood tags like 'happy' or 'energetic', 'hybrid' for semantic search restricted by the moods/genres/year filters"
+                            },
+                            "moods": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Hybrid search only: tracks must have at least one of these mood tags"
+                            },
+                            "genres": {
+                                "type": "array",
+                                "items": { "type": "string" },
+                                "description": "Hybrid search only: tracks must have at least one of these genres"
+                            },
+                            "year_from": {
+                                "type": "integer",
+                                "description": "Hybrid search only: earliest release year (inclusive)"
+                            },
+                            "year_to": {
+                                "type": "integer",
+                                "description": "Hybrid search only: latest release year (inclusive)"
                             },
                             "limit": {
                                 "type": "integer",
@@ -1147,7 +1511,7 @@ You can help users with their music library by:
                 tool_type: "function".to_string(),
                 function: OllamaToolFunction {
                     name: "get_recommendations".to_string(),
-                    description: "Get track recommendations similar to a given track. For mood-based search, use search_library with search_type 'mood' instead."
+                    description: "Get track recommendations similar to a given track. Each result has a reason describing what it shares with that track; use it when explaining picks. For mood-based search, use search_library with search_type 'mood' instead."
                         .to_string(),
                     parameters: serde_json::json!({
                         "type": "object",
@@ -1169,8 +1533,12 @@ You can help users with their music library by:
     }
 
     /// Execute a tool call and return the result
-    #[instrument(skip(self))]
-    async fn execute_tool(&self, tool_call: &OllamaToolCall) -> (ToolResult, Option<ChatAction>) {
+    #[instrument(skip(self, context))]
+    async fn execute_tool(
+        &self,
+        tool_call: &OllamaToolCall,
+        context: &UserContext,
+    ) -> (ToolResult, Option<ChatAction>) {
         let function_name = &tool_call.function.name;
         let arguments = &tool_call.function.arguments;
 
@@ -1209,7 +1577,9 @@ You can help users with their music library by:
                 (c, a, err)
             }
             "get_recommendations" => {
-                let (c, a) = self.tool_get_recommendations(arguments).await;
+                let (c, a) = self
+                    .tool_get_recommendations(arguments, context.similarity_weights.as_ref())
+                    .await;
                 let err = has_json_error(&c);
                 (c, a, err)
             }
@@ -1266,9 +1636,12 @@ You can help users with their music library by:
 
     /// Search library tool implementation using semantic search or mood-based search
     ///
-    /// Supports two search modes:
+    /// Supports three search modes:
     /// - `mood`: Searches tracks by mood tags (e.g., "happy", "energetic", "melancholic")
     /// - `track`/default: Uses semantic search with AI embeddings to find matching tracks
+    /// - `hybrid`: Semantic search restricted to tracks matching the `moods`,
+    ///   `genres`, and `year_from`/`year_to` filters; without filters it is
+    ///   the same as `track`
     #[instrument(skip(self))]
     async fn tool_search_library(&self, arguments: &str) -> (String, Option<ChatAction>) {
         #[derive(Deserialize)]
@@ -1276,6 +1649,12 @@ You can help users with their music library by:
             query: String,
             search_type: Option<String>,
             limit: Option<i32>,
+            #[serde(default)]
+            moods: Vec<String>,
+            #[serde(default)]
+            genres: Vec<String>,
+            year_from: Option<i32>,
+            year_to: Option<i32>,
         }
 
         let args: Args = match serde_json::from_str(arguments) {
@@ -1323,11 +1702,11 @@ You can help users with their music library by:
             .to_lowercase();
 
         // Validate search_type
-        if search_type != "track" && search_type != "mood" {
+        if !matches!(search_type.as_str(), "track" | "mood" | "hybrid") {
             return (
                 serde_json::json!({
                     "error": "Invalid search_type",
-                    "allowed": ["track", "mood"]
+                    "allowed": ["track", "mood", "hybrid"]
                 })
                 .to_string(),
                 None,
@@ -1377,6 +1756,8 @@ You can help users with their music library by:
                     )
                 }
             }
+        } else if !self.search_service.semantic_available() {
+            self.keyword_search_fallback(query, limit).await
         } else {
             // Use semantic search with embeddings
             let Some(ref ollama) = self.ollama_client else {
@@ -1406,18 +1787,41 @@ You can help users with their music library by:
                 }
             };
 
-            // Search by embedding
-            match self
-                .search_service
-                .search_by_embedding(&embedding, limit)
-                .await
-            {
+            // Search by embedding, with SQL filters for hybrid search
+            let (search, label) = if search_type == "hybrid" {
+                let filters = SearchFilters {
+                    genres: args.genres,
+                    year_from: args.year_from,
+                    year_to: args.year_to,
+                };
+                let moods: Vec<String> = args
+                    .moods
+                    .iter()
+                    .map(|m| m.trim().to_string())
+                    .filter(|m| !m.is_empty())
+                    .collect();
+                (
+                    self.search_service
+                        .search_by_embedding_with_filters(&embedding, filters, &moods, limit)
+                        .await,
+                    "hybrid",
+                )
+            } else {
+                (
+                    self.search_service
+                        .search_by_embedding_fast(&embedding, limit)
+                        .await,
+                    "semantic",
+                )
+            };
+
+            match search {
                 Ok(tracks) => {
                     let results = Self::format_search_results(&tracks);
                     let mut result = serde_json::json!({
                         "results": results,
                         "query": query,
-                        "search_type": "semantic",
+                        "search_type": label,
                         "count": tracks.len()
                     });
                     if tracks.is_empty() {
@@ -1441,6 +1845,48 @@ You can help users with their music library by:
         }
     }
 
+    /// Title/artist search used when semantic search is unavailable
+    ///
+    /// The `notice` tells the model the results are keyword matches so it
+    /// doesn't present them as semantic ones.
+    async fn keyword_search_fallback(
+        &self,
+        query: &str,
+        limit: i32,
+    ) -> (String, Option<ChatAction>) {
+        let notice = "Semantic search unavailable; showing title/artist keyword matches. \
+                      Use search_type 'mood' to search by mood.";
+
+        match self.search_service.search_by_keyword(query, limit).await {
+            Ok(tracks) => {
+                let results = Self::format_search_results(&tracks);
+                let mut result = serde_json::json!({
+                    "results": results,
+                    "query": query,
+                    "search_type": "keyword",
+                    "count": tracks.len(),
+                    "notice": notice
+                });
+                if tracks.is_empty() {
+                    result["message"] = serde_json::json!("No tracks found matching your query");
+                }
+                (result.to_string(), None)
+            }
+            Err(e) => {
+                warn!(error = %e, "Keyword search failed");
+                (
+                    serde_json::json!({
+                        "error": format!("Search failed: {}", e),
+                        "query": query,
+                        "notice": notice
+                    })
+                    .to_string(),
+                    None,
+                )
+            }
+        }
+    }
+
     /// Play track tool implementation
     fn tool_play_track(&self, arguments: &str) -> (String, Option<ChatAction>) {
         #[derive(Deserialize)]
@@ -1594,8 +2040,13 @@ You can help users with their music library by:
     }
 
     /// Format similar tracks as JSON values for consistent response structure
+    ///
+    /// Each track gets a `reason` derived from what it shares with the seed
+    /// track, so the model can explain recommendations without inventing one.
     fn format_similar_tracks(
-        tracks: &[crate::services::similarity::SimilarTrack],
+        tracks: &[SimilarTrack],
+        seed: Option<&TrackSignals>,
+        signals: &HashMap<Uuid, TrackSignals>,
     ) -> Vec<serde_json::Value> {
         tracks
             .iter()
@@ -1606,18 +2057,49 @@ You can help users with their music library by:
                     "artist_name": t.artist_name.as_deref().unwrap_or(""),
                     "album_title": t.album_title.as_deref().unwrap_or(""),
                     "score": t.score,
-                    "similarity_type": format!("{:?}", t.similarity_type).to_lowercase()
+                    "similarity_type": format!("{:?}", t.similarity_type).to_lowercase(),
+                    "reason": recommendation_reason(
+                        seed,
+                        signals.get(&t.track_id),
+                        t.score_breakdown.as_ref(),
+                    )
                 })
             })
             .collect()
     }
 
+    /// Load explanation signals for a seed track and its recommendations
+    ///
+    /// Failures only cost the explanations detail, so they are logged and an
+    /// empty map is returned.
+    async fn recommendation_signals(
+        &self,
+        seed_id: Uuid,
+        tracks: &[SimilarTrack],
+    ) -> HashMap<Uuid, TrackSignals> {
+        let ids: Vec<Uuid> = std::iter::once(seed_id)
+            .chain(tracks.iter().map(|t| t.track_id))
+            .collect();
+        self.similarity_service
+            .track_signals(&ids)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(error = %e, track_id = %seed_id, "Failed to load recommendation signals");
+                HashMap::new()
+            })
+    }
+
     /// Get recommendations tool implementation
     ///
-    /// Finds tracks similar to a given track using combined similarity (semantic, acoustic, categorical).
+    /// Finds tracks similar to a given track using combined similarity (semantic, acoustic, categorical),
+    /// blended with the user's stored weights when they have any.
     /// For mood-based searches, use `search_library` with `search_type: "mood"` instead.
     #[instrument(skip(self))]
-    async fn tool_get_recommendations(&self, arguments: &str) -> (String, Option<ChatAction>) {
+    async fn tool_get_recommendations(
+        &self,
+        arguments: &str,
+        weights: Option<&SimilarityWeights>,
+    ) -> (String, Option<ChatAction>) {
         #[derive(Deserialize)]
         struct Args {
             similar_to_track_id: String,
@@ -1657,11 +2139,19 @@ You can help users with their music library by:
         // Find similar tracks using combined similarity
         match self
             .similarity_service
+            .for_user_weights(weights)
             .find_similar_combined(track_uuid, limit)
             .await
         {
             Ok(similar_tracks) => {
-                let results = Self::format_similar_tracks(&similar_tracks);
+                let signals = self
+                    .recommendation_signals(track_uuid, &similar_tracks)
+                    .await;
+                let results = Self::format_similar_tracks(
+                    &similar_tracks,
+                    signals.get(&track_uuid),
+                    &signals,
+                );
                 let mut result = serde_json::json!({
                     "recommendations": results,
                     "similar_to": track_uuid.to_string(),
@@ -1689,6 +2179,93 @@ You can help users with their music library by:
     }
 }
 
+// ==================== Recommendation Reasons ====================
+
+/// BPM difference still described as a similar tempo
+const SIMILAR_TEMPO_BPM: f64 = 8.0;
+
+/// Energy difference (0.0 - 1.0 scale) still described as similar energy
+const SIMILAR_ENERGY: f64 = 0.15;
+
+/// Most shared genres or moods named in a reason
+const MAX_REASON_TAGS: usize = 3;
+
+/// Short, factual explanation of why `candidate` was recommended for `seed`
+///
+/// Built from shared genres and moods and close tempo and energy. When none
+/// of those overlap (or they aren't known), falls back to the strongest
+/// dimension of the similarity score.
+pub fn recommendation_reason(
+    seed: Option<&TrackSignals>,
+    candidate: Option<&TrackSignals>,
+    breakdown: Option<&ScoreBreakdown>,
+) -> String {
+    let mut parts = Vec::new();
+
+    if let (Some(seed), Some(candidate)) = (seed, candidate) {
+        let genres = shared_tags(&seed.genres, &candidate.genres);
+        if !genres.is_empty() {
+            parts.push(format!("same genre ({})", genres.join(", ")));
+        }
+        let moods = shared_tags(&seed.moods, &candidate.moods);
+        if !moods.is_empty() {
+            parts.push(format!("shared mood ({})", moods.join(", ")));
+        }
+        if let (Some(a), Some(b)) = (seed.bpm, candidate.bpm) {
+            if (a - b).abs() <= SIMILAR_TEMPO_BPM {
+                parts.push(format!("similar tempo (~{:.0} BPM)", b));
+            }
+        }
+        if let (Some(a), Some(b)) = (seed.energy, candidate.energy) {
+            if (a - b).abs() <= SIMILAR_ENERGY {
+                parts.push("similar energy".to_string());
+            }
+        }
+    }
+
+    match parts.len() {
+        0 => fallback_reason(breakdown).to_string(),
+        1 => parts.remove(0),
+        n => {
+            let last = parts.remove(n - 1);
+            format!("{} and {}", parts.join(", "), last)
+        }
+    }
+}
+
+/// Tags present in both lists (case-insensitive), in the candidate's spelling
+fn shared_tags(seed: &[String], candidate: &[String]) -> Vec<String> {
+    let mut shared: Vec<String> = Vec::new();
+    for tag in candidate {
+        let is_shared = seed.iter().any(|s| s.eq_ignore_ascii_case(tag));
+        if is_shared && !shared.iter().any(|s| s.eq_ignore_ascii_case(tag)) {
+            shared.push(tag.clone());
+        }
+        if shared.len() == MAX_REASON_TAGS {
+            break;
+        }
+    }
+    shared
+}
+
+/// Describe the dimension that contributed most to a combined score
+fn fallback_reason(breakdown: Option<&ScoreBreakdown>) -> &'static str {
+    let Some(b) = breakdown else {
+        return "similar overall style";
+    };
+    let semantic = b.semantic * b.weight_semantic;
+    let acoustic = b.acoustic * b.weight_acoustic;
+    let categorical = b.categorical * b.weight_categorical;
+
+    if acoustic >= semantic && acoustic >= categorical && acoustic > 0.0 {
+        "similar sound"
+    } else if categorical >= semantic && categorical > 0.0 {
+        "overlapping tags"
+    } else {
+        "similar overall style"
+    }
+}
+
 // ==================== User Context Builder ====================
 
 /// Builder for creating user context from database
@@ -1710,8 +2287,8 @@ impl UserContextBuilder {
             WITH user_tracks AS (
                 SELECT DISTINCT t.id, t.artist_id, t.album_id
                 FROM tracks t
-                JOIN queue_history qh ON t.id = qh.track_id
-                WHERE qh.user_id = $1
+                JOIN listening_history lh ON t.id = lh.track_id
+                WHERE lh.user_id = $1
             )
             SELECT
                 (SELECT COUNT(*) FROM user_tracks) as track_count,
@@ -1730,7 +2307,7 @@ impl UserContextBuilder {
             r#"
             SELECT DISTINCT unnest(t.genres)
             FROM tracks t
-            INNER JOIN queue_history qh ON qh.track_id = t.id AND qh.user_id = $1
+            INNER JOIN listening_history lh ON lh.track_id = t.id AND lh.user_id = $1
             WHERE t.genres IS NOT NULL
             LIMIT 5
             "#,
@@ -1758,6 +2335,14 @@ impl UserContextBuilder {
         .fetch_optional(&self.pool)
         .await?;
 
+        let similarity_weights = UserRepository::new(self.pool.clone())
+            .get_similarity_weights(user_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(user_id = %user_id, error = %e, "Failed to fetch similarity weights");
+                None
+            });
+
         Ok(UserContext {
             user_id,
             track_count: stats.track_count.unwrap_or(0),
@@ -1767,6 +2352,7 @@ impl UserContextBuilder {
             top_genres,
             current_track_id: current_track.as_ref().and_then(|ct| ct.current_track_id),
             current_track_title: current_track.and_then(|ct| ct.title),
+            similarity_weights,
         })
     }
 }
@@ -1803,6 +2389,99 @@ mod tests {
         .expect("Failed to create test ChatService")
     }
 
+    #[tokio::test]
+    async fn test_search_falls_back_to_keywords_without_pgvector() {
+        // Nothing listens on port 1, so the keyword query fails fast
+        let pool = sqlx::postgres::PgPoolOptions::new()
+            .acquire_timeout(std::time::Duration::from_millis(200))
+            .connect_lazy("postgres://localhost:1/unused")
+            .unwrap();
+        let service = ChatService::new(
+            pool.clone(),
+            OllamaConfig::default(),
+            SearchService::new(pool.clone()).with_semantic_available(false),
+            SimilarityService::new(pool),
+            None,
+        )
+        .unwrap();
+
+        for search_type in ["track", "hybrid"] {
+            let args = serde_json::json!({ "query": "radiohead", "search_type": search_type });
+            let (content, action) = service.tool_search_library(&args.to_string()).await;
+            let result: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+            assert!(action.is_none());
+            assert!(
+                result["notice"]
+                    .as_str()
+                    .is_some_and(|n| n.starts_with("Semantic search unavailable")),
+                "expected keyword fallback for {}: {}",
+                search_type,
+                content
+            );
+            assert!(!content.contains("Ollama not configured"));
+        }
+    }
+
+    fn signals(genres: &[&str], moods: &[&str], bpm: f64, energy: f64) -> TrackSignals {
+        TrackSignals {
+            genres: genres.iter().map(|g| g.to_string()).collect(),
+            moods: moods.iter().map(|m| m.to_string()).collect(),
+            bpm: Some(bpm),
+            energy: Some(energy),
+        }
+    }
+
+    #[test]
+    fn test_conversation_title_uses_first_words() {
+        assert_eq!(
+            conversation_title("  play something   like Radiohead please, thanks"),
+            "play something like Radiohead please,"
+        );
+
+        let title = conversation_title(&"🎵".repeat(100));
+        assert!(title.ends_with("..."));
+        assert!(title.len() <= MAX_TITLE_BYTES + 3);
+    }
+
+    #[test]
+    fn test_recommendation_reason_reflects_shared_signals() {
+        let seed = signals(&["Rock", "indie", "shoegaze"], &["dreamy"], 118.0, 0.62);
+        let candidate = signals(&["indie", "rock", "pop"], &["upbeat"], 122.0, 0.95);
+        assert_eq!(
+            recommendation_reason(Some(&seed), Some(&candidate), None),
+            "same genre (indie, rock) and similar tempo (~122 BPM)"
+        );
+
+        let close = signals(&["jazz"], &["Dreamy"], 90.0, 0.55);
+        assert_eq!(
+            recommendation_reason(Some(&seed), Some(&close), None),
+            "shared mood (Dreamy) and similar energy"
+        );
+    }
+
+    #[test]
+    fn test_recommendation_reason_falls_back_to_score_breakdown() {
+        let seed = signals(&["rock"], &[], 120.0, 0.9);
+        let unrelated = signals(&["ambient"], &[], 70.0, 0.1);
+        let breakdown = ScoreBreakdown {
+            semantic: 0.2,
+            acoustic: 0.9,
+            categorical: 0.0,
+            weight_semantic: 0.5,
+            weight_acoustic: 0.3,
+            weight_categorical: 0.2,
+        };
+        assert_eq!(
+            recommendation_reason(Some(&seed), Some(&unrelated), Some(&breakdown)),
+            "similar sound"
+        );
+        assert_eq!(
+            recommendation_reason(None, None, None),
+            "similar overall style"
+        );
+    }
+
     #[tokio::test]
     async fn test_system_prompt_generation() {
         let context = UserContext {
@@ -1818,6 +2497,7 @@ mod tests {
             ],
             current_track_id: Some(Uuid::new_v4()),
             current_track_title: Some("Bohemian Rhapsody".to_string()),
+            similarity_weights: None,
         };
 
         let service = test_service().await;
@@ -1868,6 +2548,7 @@ mod tests {
             top_genres: vec!["pop".to_string()],
             current_track_id: None,
             current_track_title: None,
+            similarity_weights: None,
         };
 
         let snapshot: ContextSnapshot = (&context).into();
@@ -2237,6 +2918,7 @@ mod tests {
             top_genres: vec![],
             current_track_id: None,
             current_track_title: None,
+            similarity_weights: None,
         };
 
         let result = service
@@ -2265,6 +2947,7 @@ mod tests {
             top_genres: vec![],
             current_track_id: None,
             current_track_title: None,
+            similarity_weights: None,
         };
 
         // Create a message longer than MAX_MESSAGE_LENGTH (10_000)
@@ -2404,4 +3087,258 @@ mod tests {
         }
         assert_eq!(expected, 20);
     }
+
+    fn breaker_test_context() -> UserContext {
+        UserContext {
+            user_id: Uuid::new_v4(),
+            track_count: 0,
+            artist_count: 0,
+            album_count: 0,
+            playlist_count: 0,
+            top_genres: vec![],
+            current_track_id: None,
+            current_track_title: None,
+            similarity_weights: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_model_loading_maps_to_typed_error() {
+        use crate::services::circuit_breaker::CircuitState;
+        use resonance_test_utils::MockOllamaServer;
+
+        let server = MockOllamaServer::start().await;
+        server.mock_model_loading().await;
+
+        let pool = sqlx::PgPool::connect_lazy("postgres://test").unwrap();
+        let service = ChatService::new(
+            pool.clone(),
+            OllamaConfig::with_url(server.url()),
+            SearchService::new(pool.clone()),
+            SimilarityService::new(pool),
+            None,
+        )
+        .unwrap()
+        .with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            base_cooldown: std::time::Duration::from_secs(60),
+            max_cooldown: std::time::Duration::from_secs(60),
+        });
+        let context = breaker_test_context();
+
+        for _ in 0..2 {
+            let result = service.chat_with_ollama(&[], &context).await;
+            assert!(matches!(result, Err(ChatError::ModelLoading)));
+        }
+        // Warming up is not a hard failure, so the breaker stays closed
+        assert_eq!(service.circuit_breaker.state(), CircuitState::Closed);
+
+        let api_error = crate::error::ApiError::from(ChatError::ModelLoading);
+        assert_eq!(api_error.error_code(), "AI_MODEL_LOADING");
+        assert_eq!(
+            api_error.status_code(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert!(matches!(
+            StreamEvent::from_error(&ChatError::ModelLoading),
+            StreamEvent::Error {
+                code: StreamErrorCode::ModelLoading,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_fails_fast_and_recovers() {
+        use crate::services::circuit_breaker::CircuitState;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        let pool = sqlx::PgPool::connect_lazy("postgres://test").unwrap();
+        let service = ChatService::new(
+            pool.clone(),
+            OllamaConfig::with_url(server.uri()),
+            SearchService::new(pool.clone()),
+            SimilarityService::new(pool),
+            None,
+        )
+        .unwrap()
+        .with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 2,
+            base_cooldown: std::time::Duration::from_millis(50),
+            max_cooldown: std::time::Duration::from_secs(1),
+        });
+        let context = breaker_test_context();
+
+        // Drive the breaker open with consecutive failures
+        for _ in 0..2 {
+            let result = service.chat_with_ollama(&[], &context).await;
+            assert!(matches!(result, Err(ChatError::OllamaResponse(ref m)) if m.contains("503")));
+        }
+        assert_eq!(service.circuit_breaker.state(), CircuitState::Open);
+
+        // While open, requests fail fast without reaching Ollama
+        let result = service.chat_with_ollama(&[], &context).await;
+        assert!(matches!(
+            result,
+            Err(ChatError::Unavailable {
+                retry_after_secs: Some(1)
+            })
+        ));
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        // After the cool-down a probe succeeds and closes the circuit
+        server.reset().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "message": {"role": "assistant", "content": "Back online"},
+                "done": true
+            })))
+            .mount(&server)
+            .await;
+        tokio::time::sleep(std::time::Duration::from_millis(60)).await;
+
+        let (content, _, _) = service.chat_with_ollama(&[], &context).await.unwrap();
+        assert_eq!(content, "Back online");
+        assert_eq!(service.circuit_breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_ollama_down_maps_to_ai_unavailable_graphql_error() {
+        use crate::error::ApiError;
+        use async_graphql::{ErrorExtensions, Value};
+
+        // Nothing listens on port 1, so every request is refused
+        let pool = sqlx::PgPool::connect_lazy("postgres://test").unwrap();
+        let service = ChatService::new(
+            pool.clone(),
+            OllamaConfig::with_url("http://127.0.0.1:1"),
+            SearchService::new(pool.clone()),
+            SimilarityService::new(pool),
+            None,
+        )
+        .unwrap()
+        .with_circuit_breaker(CircuitBreakerConfig {
+            failure_threshold: 1,
+            base_cooldown: std::time::Duration::from_secs(60),
+            max_cooldown: std::time::Duration::from_secs(60),
+        });
+        let context = breaker_test_context();
+
+        let refused = service.chat_with_ollama(&[], &context).await.unwrap_err();
+        let error = ApiError::from(refused).extend();
+        let extensions = error.extensions.as_ref().unwrap();
+        assert_eq!(extensions.get("code"), Some(&Value::from("AI_UNAVAILABLE")));
+        assert_eq!(extensions.get("retryAfterSeconds"), None);
+
+        // The breaker is now open and reports its cool-down as the retry hint
+        let fast_failed = service.chat_with_ollama(&[], &context).await.unwrap_err();
+        let error = ApiError::from(fast_failed).extend();
+        let extensions = error.extensions.as_ref().unwrap();
+        assert_eq!(extensions.get("code"), Some(&Value::from("AI_UNAVAILABLE")));
+        assert_eq!(
+            extensions.get("retryAfterSeconds"),
+            Some(&Value::from(60u64))
+        );
+
+        // A bad response is reported as such rather than as an outage
+        let bad_response = ApiError::from(ChatError::OllamaResponse("garbled".to_string()));
+        assert_eq!(
+            bad_response.extend().extensions.unwrap().get("code"),
+            Some(&Value::from("AI_SERVICE_ERROR"))
+        );
+    }
+
+    #[test]
+    fn test_tool_cost_estimates() {
+        assert_eq!(
+            ToolCost::of("search_library", r#"{"query": "calm piano"}"#),
+            ToolCost::SemanticSearch
+        );
+        assert_eq!(
+            ToolCost::of(
+                "search_library",
+                r#"{"query": "happy", "search_type": "Mood"}"#
+            ),
+            ToolCost::Query
+        );
+        assert_eq!(
+            ToolCost::of("play_track", r#"{"track_id": "x"}"#),
+            ToolCost::Action
+        );
+        assert!(
+            ToolCost::of("search_library", "{}") as u32 > ToolCost::of("play_track", "{}") as u32
+        );
+    }
+
+    #[test]
+    fn test_tool_budget_refuses_overdraft() {
+        let mut budget = ToolBudget::new(6);
+        assert!(budget.try_spend(ToolCost::SemanticSearch));
+        assert!(!budget.try_spend(ToolCost::Query));
+        assert_eq!(budget.remaining(), 1);
+        assert!(budget.try_spend(ToolCost::Action));
+        assert_eq!(budget.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_tool_budget_stops_repeated_semantic_search() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // The model asks for yet another semantic search on every turn
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": {
+                            "name": "search_library",
+                            "arguments": "{\"query\": \"something slightly different\"}"
+                        }
+                    }]
+                },
+                "done": true
+            })))
+            .mount(&server)
+            .await;
+
+        let pool = sqlx::PgPool::connect_lazy("postgres://test").unwrap();
+        let service = ChatService::new(
+            pool.clone(),
+            OllamaConfig::with_url(server.uri()),
+            SearchService::new(pool.clone()),
+            SimilarityService::new(pool),
+            None,
+        )
+        .unwrap()
+        .with_chat_config(ChatConfig {
+            tool_budget: 2 * ToolCost::SemanticSearch as u32,
+        });
+
+        let (content, tool_calls, _) = service
+            .chat_with_ollama(&[], &breaker_test_context())
+            .await
+            .unwrap();
+
+        // Two searches fit the budget; the third request is refused
+        assert_eq!(content, TOOL_BUDGET_EXHAUSTED_MESSAGE);
+        assert_eq!(tool_calls.len(), 2);
+        let model_calls = server.received_requests().await.unwrap().len();
+        assert_eq!(model_calls, 3);
+        assert!(model_calls < MAX_TOOL_ITERATIONS);
+    }
 }
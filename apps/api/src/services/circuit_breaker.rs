@@ -0,0 +1,295 @@
+//! Circuit breaker for calls to external services
+//!
+//! Protects callers from piling up against a service that is down:
+//! - **Closed**: requests flow normally; consecutive failures are counted
+//! - **Open**: after `failure_threshold` consecutive failures, requests fail
+//!   fast for a cool-down period
+//! - **Half-open**: once the cool-down elapses, a single probe request is let
+//!   through; success closes the circuit, failure re-opens it with the
+//!   cool-down doubled (capped at `max_cooldown`)
+//!
+//! State lives behind an `Arc<Mutex<_>>`, so clones of a breaker share it.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Circuit breaker tuning
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// Cool-down after the circuit first opens
+    pub base_cooldown: Duration,
+    /// Upper bound for the cool-down as failed probes double it
+    pub max_cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            base_cooldown: Duration::from_secs(30),
+            max_cooldown: Duration::from_secs(300),
+        }
+    }
+}
+
+/// Observable circuit state
+#[allow(dead_code)] // Exposed for monitoring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally
+    Closed,
+    /// Requests fail fast until the cool-down elapses
+    Open,
+    /// The cool-down elapsed; the next request probes the service
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    consecutive_failures: u32,
+    /// When the circuit opened (or the last probe was let through)
+    opened_at: Option<Instant>,
+    cooldown: Duration,
+}
+
+/// Shared circuit breaker
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(CircuitBreakerConfig::default())
+    }
+}
+
+impl CircuitBreaker {
+    /// Create a closed circuit breaker
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        let cooldown = config.base_cooldown;
+        Self {
+            config,
+            state: Arc::new(Mutex::new(BreakerState {
+                consecutive_failures: 0,
+                opened_at: None,
+                cooldown,
+            })),
+        }
+    }
+
+    /// Current circuit state
+    #[allow(dead_code)] // Exposed for monitoring
+    pub fn state(&self) -> CircuitState {
+        self.state_at(Instant::now())
+    }
+
+    /// Check whether a request may proceed
+    ///
+    /// Returns false while the circuit is open. In the half-open state the
+    /// first caller is admitted as the probe and the cool-down restarts, so
+    /// concurrent callers keep failing fast until the probe reports back.
+    pub fn try_acquire(&self) -> bool {
+        self.try_acquire_at(Instant::now())
+    }
+
+    /// Time left before the open circuit lets a probe through
+    ///
+    /// `None` unless the circuit is open, so callers can pass it on as a
+    /// retry hint.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.retry_after_at(Instant::now())
+    }
+
+    /// Record a successful call, closing the circuit
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+        state.cooldown = self.config.base_cooldown;
+    }
+
+    /// Record a failed call, opening the circuit once the threshold is reached
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    #[allow(dead_code)]
+    fn state_at(&self, now: Instant) -> CircuitState {
+        let state = self.lock();
+        match state.opened_at {
+            None => CircuitState::Closed,
+            Some(opened_at) if now.duration_since(opened_at) >= state.cooldown => {
+                CircuitState::HalfOpen
+            }
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    fn retry_after_at(&self, now: Instant) -> Option<Duration> {
+        let state = self.lock();
+        let opened_at = state.opened_at?;
+        state
+            .cooldown
+            .checked_sub(now.duration_since(opened_at))
+            .filter(|remaining| !remaining.is_zero())
+    }
+
+    fn try_acquire_at(&self, now: Instant) -> bool {
+        let mut state = self.lock();
+        match state.opened_at {
+            None => true,
+            Some(opened_at) if now.duration_since(opened_at) >= state.cooldown => {
+                state.opened_at = Some(now);
+                true
+            }
+            Some(_) => false,
+        }
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        if state.opened_at.is_some() {
+            // A failed probe: back off further before the next one
+            state.cooldown = (state.cooldown * 2).min(self.config.max_cooldown);
+            state.opened_at = Some(now);
+            tracing::warn!(
+                cooldown_secs = state.cooldown.as_secs(),
+                "Circuit breaker probe failed, staying open"
+            );
+        } else if state.consecutive_failures >= self.config.failure_threshold {
+            state.opened_at = Some(now);
+            tracing::warn!(
+                failures = state.consecutive_failures,
+                cooldown_secs = state.cooldown.as_secs(),
+                "Circuit breaker opened"
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        // The state is always left consistent, so a poisoned lock is still usable
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            base_cooldown: Duration::from_secs(10),
+            max_cooldown: Duration::from_secs(25),
+        })
+    }
+
+    #[test]
+    fn test_opens_after_consecutive_failures() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        assert_eq!(breaker.state_at(now), CircuitState::Closed);
+        assert!(breaker.try_acquire_at(now));
+
+        breaker.record_failure_at(now);
+        assert_eq!(breaker.state_at(now), CircuitState::Open);
+        assert!(!breaker.try_acquire_at(now + Duration::from_secs(9)));
+    }
+
+    #[test]
+    fn test_success_resets_failure_count() {
+        let breaker = breaker();
+        let now = Instant::now();
+
+        breaker.record_failure_at(now);
+        breaker.record_failure_at(now);
+        breaker.record_success();
+        breaker.record_failure_at(now);
+
+        assert_eq!(breaker.state_at(now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probe_recovers() {
+        let breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(breaker.state_at(later), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire_at(later));
+        // Only one probe at a time
+        assert!(!breaker.try_acquire_at(later));
+
+        breaker.record_success();
+        assert_eq!(breaker.state_at(later), CircuitState::Closed);
+        assert!(breaker.try_acquire_at(later));
+    }
+
+    #[test]
+    fn test_failed_probe_doubles_cooldown_up_to_max() {
+        let breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+
+        let probe = now + Duration::from_secs(10);
+        assert!(breaker.try_acquire_at(probe));
+        breaker.record_failure_at(probe);
+
+        // Cool-down doubled to 20s
+        assert!(!breaker.try_acquire_at(probe + Duration::from_secs(19)));
+        let second_probe = probe + Duration::from_secs(20);
+        assert!(breaker.try_acquire_at(second_probe));
+        breaker.record_failure_at(second_probe);
+
+        // Capped at 25s rather than 40s
+        assert_eq!(
+            breaker.state_at(second_probe + Duration::from_secs(25)),
+            CircuitState::HalfOpen
+        );
+    }
+
+    #[test]
+    fn test_retry_after_counts_down_while_open() {
+        let breaker = breaker();
+        let now = Instant::now();
+        assert_eq!(breaker.retry_after_at(now), None);
+
+        for _ in 0..3 {
+            breaker.record_failure_at(now);
+        }
+        assert_eq!(
+            breaker.retry_after_at(now + Duration::from_secs(4)),
+            Some(Duration::from_secs(6))
+        );
+        assert_eq!(breaker.retry_after_at(now + Duration::from_secs(10)), None);
+
+        breaker.record_success();
+        assert_eq!(breaker.retry_after_at(now), None);
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let breaker = breaker();
+        let clone = breaker.clone();
+        for _ in 0..3 {
+            clone.record_failure();
+        }
+
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(!breaker.try_acquire());
+    }
+}
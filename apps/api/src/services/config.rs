@@ -205,9 +205,11 @@ impl ConfigService {
         //   "url": "http://ollama:11434",
         //   "model": "mistral",
         //   "embedding_model": "nomic-embed-text",
+        //   "embedding_dimension": 768,
         //   "timeout_secs": 60,
         //   "max_tokens": 2048,
-        //   "temperature": 0.7
+        //   "temperature": 0.7,
+        //   "warm_up": false
         // }
 
         let defaults = OllamaConfig::default();
@@ -233,6 +235,13 @@ impl ConfigService {
             .map(String::from)
             .unwrap_or(defaults.embedding_model);
 
+        let embedding_dimension = cached
+            .config
+            .get("embedding_dimension")
+            .and_then(|v| v.as_u64())
+            .map(|v| v as usize)
+            .unwrap_or(defaults.embedding_dimension);
+
         let timeout_secs = cached
             .config
             .get("timeout_secs")
@@ -253,13 +262,21 @@ impl ConfigService {
             .map(|v| v as f32)
             .unwrap_or(defaults.temperature);
 
+        let warm_up = cached
+            .config
+            .get("warm_up")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.warm_up);
+
         Ok(OllamaConfig {
             url,
             model,
             embedding_model,
+            embedding_dimension,
             timeout_secs,
             max_tokens,
             temperature,
+            warm_up,
         })
     }
 
@@ -318,11 +335,26 @@ impl ConfigService {
             .and_then(|v| v.as_u64())
             .unwrap_or(30);
 
+        let defaults = LidarrConfig::new(url.clone(), api_key.clone());
+        let retry_attempts = cached
+            .config
+            .get("retry_attempts")
+            .and_then(|v| v.as_u64())
+            .map_or(defaults.retry_attempts, |v| v as u32);
+
+        let retry_base_delay_ms = cached
+            .config
+            .get("retry_base_delay_ms")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(defaults.retry_base_delay_ms);
+
         Some(LidarrConfig {
             url,
             api_key,
             sync_interval_secs,
             timeout_secs,
+            retry_attempts,
+            retry_base_delay_ms,
         })
     }
 
@@ -0,0 +1,232 @@
+//! Personal data export
+//!
+//! Streams a single JSON document with everything Resonance stores about a
+//! user: profile, preferences, playlists (with their tracks), listening
+//! history and AI chat conversations. Postgres renders each row as JSON and
+//! each row is sent to the client as it is fetched, so a long listening
+//! history is never held in memory, neither as decoded rows nor as text.
+//!
+//! The document is served by `GET /account/export` (see
+//! [`crate::routes::account`]); the `exportMyData` mutation returns the link
+//! to it under the `/api` prefix, like the stream links.
+//!
+//! Credentials are left out: the password hash and the ListenBrainz token are
+//! never exported, and only rows owned by the user are included.
+
+use bytes::Bytes;
+use futures_util::TryStreamExt;
+use sqlx::PgPool;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::error::{ApiError, ApiResult};
+
+/// Link to the export route as clients request it
+///
+/// The reverse proxy (and the web dev server) strip `/api` before forwarding,
+/// so this reaches `GET /account/export` on the server.
+pub const EXPORT_PATH: &str = "/api/account/export";
+
+/// Chunks buffered between the database and a slow client
+///
+/// Fetching pauses while the buffer is full, so memory use stays bounded no
+/// matter how large the export is.
+const EXPORT_BUFFER_CHUNKS: usize = 32;
+
+/// A piece of the JSON document, or the error that cut it short
+pub type ExportChunk = Result<Bytes, sqlx::Error>;
+
+/// Profile fields, without credentials
+const PROFILE_QUERY: &str = r#"
+    SELECT jsonb_build_object(
+        'id', id,
+        'email', email,
+        'displayName', display_name,
+        'avatarUrl', avatar_url,
+        'role', role,
+        'discordUserId', discord_user_id,
+        'emailVerified', email_verified,
+        'lastSeenAt', last_seen_at,
+        'createdAt', created_at,
+        'updatedAt', updated_at
+    )::text
+    FROM users
+    WHERE id = $1
+"#;
+
+/// Stored preferences, including per-device overrides
+const PREFERENCES_QUERY: &str = "SELECT preferences::text FROM users WHERE id = $1";
+
+/// Playlists the user owns, each with its tracks in order
+const PLAYLISTS_QUERY: &str = r#"
+    SELECT jsonb_build_object(
+        'id', p.id,
+        'name', p.name,
+        'description', p.description,
+        'isPublic', p.is_public,
+        'isCollaborative', p.is_collaborative,
+        'playlistType', p.playlist_type,
+        'smartRules', p.smart_rules,
+        'createdAt', p.created_at,
+        'updatedAt', p.updated_at,
+        'tracks', COALESCE((
+            SELECT jsonb_agg(jsonb_build_object(
+                'trackId', t.id,
+                'title', t.title,
+                'artist', a.name,
+                'position', pt.position,
+                'addedAt', pt.added_at
+            ) ORDER BY pt.position)
+            FROM playlist_tracks pt
+            JOIN tracks t ON t.id = pt.track_id
+            JOIN artists a ON a.id = t.artist_id
+            WHERE pt.playlist_id = p.id
+        ), '[]'::jsonb)
+    )::text
+    FROM playlists p
+    WHERE p.user_id = $1
+    ORDER BY p.created_at
+"#;
+
+/// Every play the user recorded, oldest first
+const LISTENING_HISTORY_QUERY: &str = r#"
+    SELECT jsonb_build_object(
+        'trackId', h.track_id,
+        'title', t.title,
+        'artist', a.name,
+        'playedAt', h.played_at,
+        'durationPlayedMs', h.duration_played_ms,
+        'completed', h.completed,
+        'contextType', h.context_type,
+        'contextId', h.context_id,
+        'deviceId', h.device_id
+    )::text
+    FROM listening_history h
+    JOIN tracks t ON t.id = h.track_id
+    JOIN artists a ON a.id = t.artist_id
+    WHERE h.user_id = $1
+    ORDER BY h.played_at
+"#;
+
+/// Chat conversations, including archived ones, with their messages in order
+const CHAT_CONVERSATIONS_QUERY: &str = r#"
+    SELECT jsonb_build_object(
+        'id', c.id,
+        'title', c.title,
+        'createdAt', c.created_at,
+        'updatedAt', c.updated_at,
+        'deletedAt', c.deleted_at,
+        'messages', COALESCE((
+            SELECT jsonb_agg(jsonb_build_object(
+                'role', m.role,
+                'content', m.content,
+                'createdAt', m.created_at
+            ) ORDER BY m.sequence_number)
+            FROM chat_messages m
+            WHERE m.conversation_id = c.id
+        ), '[]'::jsonb)
+    )::text
+    FROM chat_conversations c
+    WHERE c.user_id = $1
+    ORDER BY c.created_at
+"#;
+
+/// Export all of a user's data as a stream of JSON chunks
+///
+/// The document has the keys `exportedAt`, `profile`, `preferences`,
+/// `playlists`, `listeningHistory` and `chatConversations`.
+///
+/// The profile is read before the stream is returned, so a missing user is a
+/// [`ApiError::NotFound`] rather than an empty response. A database error
+/// later on ends the stream with that error, which aborts the response
+/// instead of sending a truncated document as if it were complete.
+pub async fn export_user_data(
+    pool: &PgPool,
+    user_id: Uuid,
+) -> ApiResult<ReceiverStream<ExportChunk>> {
+    let profile = sqlx::query_scalar::<_, String>(PROFILE_QUERY)
+        .bind(user_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| ApiError::NotFound {
+            resource_type: "user",
+            id: user_id.to_string(),
+        })?;
+    let preferences = sqlx::query_scalar::<_, String>(PREFERENCES_QUERY)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await?;
+
+    let (tx, rx) = mpsc::channel(EXPORT_BUFFER_CHUNKS);
+    let pool = pool.clone();
+    tokio::spawn(async move {
+        let head = format!(
+            "{{\"exportedAt\":\"{}\",\"profile\":{profile},\"preferences\":{preferences}",
+            chrono::Utc::now().to_rfc3339()
+        );
+        if let Err(e) = write_document(&pool, user_id, head, &tx).await {
+            tracing::error!(error = %e, user_id = %user_id, "Data export failed");
+            // The client may be gone already; then there is nobody to tell
+            let _ = tx.send(Err(e)).await;
+        }
+    });
+
+    Ok(ReceiverStream::new(rx))
+}
+
+/// Send the rest of the document after `head`
+///
+/// Returns early without an error when the client disconnects.
+async fn write_document(
+    pool: &PgPool,
+    user_id: Uuid,
+    head: String,
+    tx: &mpsc::Sender<ExportChunk>,
+) -> Result<(), sqlx::Error> {
+    if tx.send(Ok(head.into())).await.is_err() {
+        return Ok(());
+    }
+
+    for (key, query) in [
+        ("playlists", PLAYLISTS_QUERY),
+        ("listeningHistory", LISTENING_HISTORY_QUERY),
+        ("chatConversations", CHAT_CONVERSATIONS_QUERY),
+    ] {
+        if !write_json_array(pool, key, query, user_id, tx).await? {
+            return Ok(());
+        }
+    }
+    let _ = tx.send(Ok(Bytes::from_static(b"}"))).await;
+
+    Ok(())
+}
+
+/// Send the JSON rows of `query` as the array `key`, one chunk per row
+///
+/// Returns false if the client disconnected.
+async fn write_json_array(
+    pool: &PgPool,
+    key: &str,
+    query: &str,
+    user_id: Uuid,
+    tx: &mpsc::Sender<ExportChunk>,
+) -> Result<bool, sqlx::Error> {
+    let mut rows = sqlx::query_scalar::<_, String>(query)
+        .bind(user_id)
+        .fetch(pool);
+
+    if tx.send(Ok(format!(",\"{key}\":[").into())).await.is_err() {
+        return Ok(false);
+    }
+    let mut first = true;
+    while let Some(row) = rows.try_next().await? {
+        let chunk = if first { row } else { format!(",{row}") };
+        if tx.send(Ok(chunk.into())).await.is_err() {
+            return Ok(false);
+        }
+        first = false;
+    }
+
+    Ok(tx.send(Ok(Bytes::from_static(b"]"))).await.is_ok())
+}
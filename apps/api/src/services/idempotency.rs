@@ -0,0 +1,343 @@
+//! Idempotency keys for non-idempotent mutations
+//!
+//! Clients on flaky networks retry requests whose responses they never saw.
+//! A client can send an `Idempotency-Key` header with a mutation; the first
+//! request with a key reserves it and records its result, and repeats of the
+//! same mutation with the same key get the recorded result back instead of
+//! performing the action again.
+//!
+//! Keys are scoped per user and per operation, so users cannot observe each
+//! other's results. Records live in Redis with a TTL when Redis is available,
+//! and in an in-memory map (per instance) otherwise. A request whose action
+//! fails releases its key so the client can retry it.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use redis::AsyncCommands;
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// How long results are remembered (24 hours)
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long a key stays reserved while its request is still running
+const PENDING_TTL: Duration = Duration::from_secs(60);
+
+/// Maximum length of a client-supplied key
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 255;
+
+/// Marker stored while the first request with a key is still running
+const PENDING_MARKER: &str = "__pending__";
+
+/// Idempotency key supplied by the client for the current request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    /// Validate a client-supplied key
+    ///
+    /// Keys must be 1-255 visible ASCII characters.
+    pub fn parse(key: &str) -> Result<Self, String> {
+        let key = key.trim();
+        if key.is_empty() {
+            return Err("Idempotency-Key cannot be empty".to_string());
+        }
+        if key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+            return Err(format!(
+                "Idempotency-Key cannot exceed {} characters",
+                MAX_IDEMPOTENCY_KEY_LENGTH
+            ));
+        }
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err("Idempotency-Key must be visible ASCII characters".to_string());
+        }
+        Ok(Self(key.to_string()))
+    }
+
+    /// The key as sent by the client
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// State of an idempotency key when a request starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reservation {
+    /// First request with this key; perform the action and record its result
+    Reserved,
+    /// An earlier request with this key completed with this (JSON) result
+    Completed(String),
+    /// An earlier request with this key is still running
+    InProgress,
+}
+
+/// In-memory record, used when Redis is unavailable
+#[derive(Debug, Clone)]
+struct MemoryRecord {
+    value: String,
+    expires_at: Instant,
+}
+
+/// Store of idempotency keys and the results recorded for them
+#[derive(Clone)]
+pub struct IdempotencyStore {
+    redis: Option<Arc<redis::Client>>,
+    fallback: Arc<RwLock<HashMap<String, MemoryRecord>>>,
+    ttl: Duration,
+}
+
+impl IdempotencyStore {
+    /// Create a store backed by Redis, falling back to memory on Redis errors
+    pub fn new(redis: redis::Client) -> Self {
+        Self {
+            redis: Some(Arc::new(redis)),
+            ..Self::in_memory()
+        }
+    }
+
+    /// Create a store that keeps keys in memory (single instance only)
+    pub fn in_memory() -> Self {
+        Self {
+            redis: None,
+            fallback: Arc::new(RwLock::new(HashMap::new())),
+            ttl: DEFAULT_IDEMPOTENCY_TTL,
+        }
+    }
+
+    /// Remember results for `ttl` instead of the default 24 hours
+    #[allow(dead_code)] // Configuration hook, the default suits current clients
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Storage key for a user's key for one operation
+    fn storage_key(user_id: Uuid, operation: &str, key: &IdempotencyKey) -> String {
+        format!("idempotency:{}:{}:{}", user_id, operation, key.as_str())
+    }
+
+    /// Reserve a key, or report the result of an earlier request that used it
+    pub async fn reserve(
+        &self,
+        user_id: Uuid,
+        operation: &str,
+        key: &IdempotencyKey,
+    ) -> Reservation {
+        let storage_key = Self::storage_key(user_id, operation, key);
+
+        if let Some(redis) = &self.redis {
+            match Self::reserve_redis(redis, &storage_key).await {
+                Ok(reservation) => return reservation,
+                Err(e) => {
+                    warn!(error = %e, "Redis unavailable for idempotency keys, using in-memory fallback");
+                }
+            }
+        }
+
+        self.reserve_memory(storage_key).await
+    }
+
+    /// Record the result of the request that reserved a key
+    pub async fn complete(
+        &self,
+        user_id: Uuid,
+        operation: &str,
+        key: &IdempotencyKey,
+        result: &str,
+    ) {
+        let storage_key = Self::storage_key(user_id, operation, key);
+
+        if let Some(redis) = &self.redis {
+            match Self::set_redis(redis, &storage_key, result, self.ttl).await {
+                Ok(()) => return,
+                Err(e) => {
+                    warn!(error = %e, "Redis unavailable for idempotency keys, using in-memory fallback");
+                }
+            }
+        }
+
+        self.set_memory(storage_key, result.to_string(), self.ttl)
+            .await;
+    }
+
+    /// Release a reserved key after its request failed, so it can be retried
+    pub async fn release(&self, user_id: Uuid, operation: &str, key: &IdempotencyKey) {
+        let storage_key = Self::storage_key(user_id, operation, key);
+
+        if let Some(redis) = &self.redis {
+            let released: Result<(), redis::RedisError> = async {
+                let mut conn = redis.get_multiplexed_async_connection().await?;
+                conn.del(&storage_key).await
+            }
+            .await;
+            if let Err(e) = released {
+                debug!(error = %e, "Redis DEL failed for idempotency key");
+            }
+        }
+
+        self.fallback.write().await.remove(&storage_key);
+    }
+
+    async fn reserve_redis(
+        redis: &redis::Client,
+        storage_key: &str,
+    ) -> Result<Reservation, redis::RedisError> {
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+
+        let reserved: Option<String> = redis::cmd("SET")
+            .arg(storage_key)
+            .arg(PENDING_MARKER)
+            .arg("NX")
+            .arg("EX")
+            .arg(PENDING_TTL.as_secs())
+            .query_async(&mut conn)
+            .await?;
+        if reserved.is_some() {
+            return Ok(Reservation::Reserved);
+        }
+
+        let existing: Option<String> = conn.get(storage_key).await?;
+        Ok(match existing {
+            Some(value) if value != PENDING_MARKER => Reservation::Completed(value),
+            Some(_) => Reservation::InProgress,
+            // Expired between SET and GET; treat as still settling
+            None => Reservation::InProgress,
+        })
+    }
+
+    async fn set_redis(
+        redis: &redis::Client,
+        storage_key: &str,
+        value: &str,
+        ttl: Duration,
+    ) -> Result<(), redis::RedisError> {
+        let mut conn = redis.get_multiplexed_async_connection().await?;
+        conn.set_ex(storage_key, value, ttl.as_secs().max(1)).await
+    }
+
+    async fn reserve_memory(&self, storage_key: String) -> Reservation {
+        let mut records = self.fallback.write().await;
+        let now = Instant::now();
+        records.retain(|_, record| record.expires_at > now);
+
+        match records.get(&storage_key) {
+            Some(record) if record.value == PENDING_MARKER => Reservation::InProgress,
+            Some(record) => Reservation::Completed(record.value.clone()),
+            None => {
+                records.insert(
+                    storage_key,
+                    MemoryRecord {
+                        value: PENDING_MARKER.to_string(),
+                        expires_at: now + PENDING_TTL,
+                    },
+                );
+                Reservation::Reserved
+            }
+        }
+    }
+
+    async fn set_memory(&self, storage_key: String, value: String, ttl: Duration) {
+        self.fallback.write().await.insert(
+            storage_key,
+            MemoryRecord {
+                value,
+                expires_at: Instant::now() + ttl,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(value: &str) -> IdempotencyKey {
+        IdempotencyKey::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(key(" abc-123 ").as_str(), "abc-123");
+        assert!(IdempotencyKey::parse("").is_err());
+        assert!(IdempotencyKey::parse("has space").is_err());
+        assert!(IdempotencyKey::parse(&"k".repeat(256)).is_err());
+        assert!(IdempotencyKey::parse(&"k".repeat(255)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_reserve_complete_replay() {
+        let store = IdempotencyStore::in_memory();
+        let user = Uuid::new_v4();
+        let k = key("retry-1");
+
+        assert_eq!(store.reserve(user, "op", &k).await, Reservation::Reserved);
+        assert_eq!(store.reserve(user, "op", &k).await, Reservation::InProgress);
+
+        store.complete(user, "op", &k, r#"{"id":1}"#).await;
+        assert_eq!(
+            store.reserve(user, "op", &k).await,
+            Reservation::Completed(r#"{"id":1}"#.to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_keys_scoped_by_user_and_operation() {
+        let store = IdempotencyStore::in_memory();
+        let (alice, bob) = (Uuid::new_v4(), Uuid::new_v4());
+        let k = key("shared");
+
+        store.reserve(alice, "createPlaylist", &k).await;
+        store.complete(alice, "createPlaylist", &k, "alice").await;
+
+        assert_eq!(
+            store.reserve(bob, "createPlaylist", &k).await,
+            Reservation::Reserved
+        );
+        assert_eq!(
+            store.reserve(alice, "importPlaylist", &k).await,
+            Reservation::Reserved
+        );
+    }
+
+    #[tokio::test]
+    async fn test_release_allows_retry() {
+        let store = IdempotencyStore::in_memory();
+        let user = Uuid::new_v4();
+        let k = key("failed");
+
+        store.reserve(user, "op", &k).await;
+        store.release(user, "op", &k).await;
+
+        assert_eq!(store.reserve(user, "op", &k).await, Reservation::Reserved);
+    }
+
+    #[tokio::test]
+    async fn test_completed_results_expire() {
+        let store = IdempotencyStore::in_memory().with_ttl(Duration::from_millis(20));
+        let user = Uuid::new_v4();
+        let k = key("short-lived");
+
+        store.reserve(user, "op", &k).await;
+        store.complete(user, "op", &k, "done").await;
+        tokio::time::sleep(Duration::from_millis(40)).await;
+
+        assert_eq!(store.reserve(user, "op", &k).await, Reservation::Reserved);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_redis_falls_back_to_memory() {
+        let store = IdempotencyStore::new(redis::Client::open("redis://localhost:0").unwrap());
+        let user = Uuid::new_v4();
+        let k = key("fallback");
+
+        assert_eq!(store.reserve(user, "op", &k).await, Reservation::Reserved);
+        store.complete(user, "op", &k, "done").await;
+        assert_eq!(
+            store.reserve(user, "op", &k).await,
+            Reservation::Completed("done".to_string())
+        );
+    }
+}
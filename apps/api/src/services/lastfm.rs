@@ -4,14 +4,42 @@
 //! Integrates with the local library to indicate which similar artists
 //! are already in the user's collection.
 //!
-//! Used by GraphQL SearchQuery for `similarArtists` and `artistTags` queries.
+//! Used by GraphQL SearchQuery for `similarArtists` and `artistTags` queries,
+//! and by `Artist.similarInLibrary`.
+//!
+//! Similar-artist lookups are cached in memory, since Last.fm's answer for an
+//! artist rarely changes and the API is rate limited.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use dashmap::DashMap;
+use resonance_media::best_match;
 use sqlx::PgPool;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::models::Artist;
+use crate::repositories::ArtistRepository;
+
+pub use resonance_lastfm_client::{
+    ApiKeyStatus, ArtistTag, LastfmClient, LastfmError, SimilarArtist,
+};
+
+/// How long Last.fm similar-artist responses are cached
+const SIMILAR_ARTISTS_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Maximum number of cached similar-artist responses
+const SIMILAR_ARTISTS_CACHE_CAPACITY: usize = 1000;
 
-pub use resonance_lastfm_client::{ApiKeyStatus, ArtistTag, LastfmClient, LastfmError};
+/// Similar artists requested from Last.fm when matching against the library
+///
+/// Only owned artists are kept, so ask for more than the caller wants.
+const LIBRARY_MATCH_FETCH_LIMIT: u32 = 100;
+
+/// Minimum name similarity for a Last.fm artist to count as a library artist
+const LIBRARY_MATCH_THRESHOLD: f32 = 0.92;
 
 /// Similar artist with library status
 #[derive(Debug, Clone, serde::Serialize)]
@@ -30,11 +58,40 @@ pub struct SimilarArtistWithStatus {
     pub track_count: Option<i64>,
 }
 
+/// Library artist similar to another, per Last.fm
+#[derive(Debug, Clone)]
+pub struct LibrarySimilarArtist {
+    /// The matched local artist
+    pub artist: Artist,
+    /// Last.fm similarity score (0.0 - 1.0)
+    pub match_score: f64,
+}
+
+/// Cached Last.fm similar-artist response
+struct CachedSimilarArtists {
+    fetched_at: Instant,
+    artists: Vec<SimilarArtist>,
+}
+
+/// Local artist name, matched against Last.fm names
+struct LocalArtistName {
+    id: Uuid,
+    name: String,
+}
+
+impl AsRef<str> for LocalArtistName {
+    fn as_ref(&self) -> &str {
+        &self.name
+    }
+}
+
 /// Last.fm service with library integration
 #[derive(Clone)]
 pub struct LastfmService {
     client: LastfmClient,
     db: PgPool,
+    /// Similar artists by (lowercased artist name, limit)
+    similar_cache: Arc<DashMap<(String, u32), CachedSimilarArtists>>,
 }
 
 /// Map Last.fm errors to API errors with explicit handling of all variants
@@ -62,7 +119,11 @@ impl LastfmService {
     /// Returns an error if the Last.fm API key is missing or invalid
     #[allow(dead_code)] // Public API for direct instantiation/testing
     pub fn new(client: LastfmClient, db: PgPool) -> Self {
-        Self { client, db }
+        Self {
+            client,
+            db,
+            similar_cache: Arc::new(DashMap::new()),
+        }
     }
 
     /// Create from environment variable
@@ -76,7 +137,7 @@ impl LastfmService {
             other => map_lastfm_error(other),
         })?;
 
-        Ok(Self { client, db })
+        Ok(Self::new(client, db))
     }
 
     /// Get similar artists with library status
@@ -90,11 +151,7 @@ impl LastfmService {
         limit: Option<u32>,
     ) -> ApiResult<Vec<SimilarArtistWithStatus>> {
         // Get similar artists from Last.fm
-        let similar = self
-            .client
-            .get_similar_artists(artist_name, limit)
-            .await
-            .map_err(map_lastfm_error)?;
+        let similar = self.fetch_similar_artists(artist_name, limit).await?;
 
         // Check which artists are in our library
         let artist_names: Vec<&str> = similar.iter().map(|a| a.name.as_str()).collect();
@@ -123,6 +180,99 @@ impl LastfmService {
         Ok(results)
     }
 
+    /// Get the library artists Last.fm considers similar to a library artist
+    ///
+    /// Last.fm's similar artists are matched against local artist names with
+    /// fuzzy matching, so spelling, accent and "feat." differences still
+    /// match. Results are ordered by Last.fm match score, highest first.
+    ///
+    /// # Errors
+    /// Returns `NotFound` if the artist does not exist locally
+    #[instrument(skip(self))]
+    pub async fn similar_artists_in_library(
+        &self,
+        artist_id: Uuid,
+        limit: usize,
+    ) -> ApiResult<Vec<LibrarySimilarArtist>> {
+        let artist_repo = ArtistRepository::new(self.db.clone());
+        let artist = artist_repo
+            .find_by_id(artist_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("artist", artist_id.to_string()))?;
+
+        let similar = self
+            .fetch_similar_artists(&artist.name, Some(LIBRARY_MATCH_FETCH_LIMIT))
+            .await?;
+        if similar.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let local: Vec<LocalArtistName> =
+            sqlx::query_as::<_, (Uuid, String)>("SELECT id, name FROM artists WHERE id <> $1")
+                .bind(artist_id)
+                .fetch_all(&self.db)
+                .await?
+                .into_iter()
+                .map(|(id, name)| LocalArtistName { id, name })
+                .collect();
+
+        let matches = match_library_artists(&similar, &local, limit);
+        if matches.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<Uuid> = matches.iter().map(|(id, _)| *id).collect();
+        let mut artists = artist_repo.find_by_ids(&ids).await?;
+
+        Ok(matches
+            .into_iter()
+            .filter_map(|(id, match_score)| {
+                let index = artists.iter().position(|a| a.id == id)?;
+                Some(LibrarySimilarArtist {
+                    artist: artists.swap_remove(index),
+                    match_score,
+                })
+            })
+            .collect())
+    }
+
+    /// Fetch similar artists from Last.fm, using the cache when fresh
+    async fn fetch_similar_artists(
+        &self,
+        artist_name: &str,
+        limit: Option<u32>,
+    ) -> ApiResult<Vec<SimilarArtist>> {
+        let key = (artist_name.to_lowercase(), limit.unwrap_or(0));
+        if let Some(cached) = self.similar_cache.get(&key) {
+            if cached.fetched_at.elapsed() < SIMILAR_ARTISTS_CACHE_TTL {
+                return Ok(cached.artists.clone());
+            }
+        }
+
+        let artists = self
+            .client
+            .get_similar_artists(artist_name, limit)
+            .await
+            .map_err(map_lastfm_error)?;
+
+        if self.similar_cache.len() >= SIMILAR_ARTISTS_CACHE_CAPACITY {
+            self.similar_cache
+                .retain(|_, cached| cached.fetched_at.elapsed() < SIMILAR_ARTISTS_CACHE_TTL);
+            if self.similar_cache.len() >= SIMILAR_ARTISTS_CACHE_CAPACITY {
+                self.similar_cache.clear();
+            }
+        }
+        self.similar_cache.insert(
+            key,
+            CachedSimilarArtists {
+                fetched_at: Instant::now(),
+                artists: artists.clone(),
+            },
+        );
+
+        Ok(artists)
+    }
+
     /// Get artist tags (genres/descriptors)
     #[instrument(skip(self))]
     pub async fn get_artist_tags(&self, artist_name: &str) -> ApiResult<Vec<ArtistTag>> {
@@ -173,6 +323,31 @@ impl LastfmService {
     }
 }
 
+/// Match Last.fm similar artists to local artists
+///
+/// Returns up to `limit` `(local artist id, match score)` pairs in Last.fm
+/// order, each local artist at most once.
+fn match_library_artists(
+    similar: &[SimilarArtist],
+    local: &[LocalArtistName],
+    limit: usize,
+) -> Vec<(Uuid, f64)> {
+    let mut matches: Vec<(Uuid, f64)> = Vec::new();
+
+    for candidate in similar {
+        if matches.len() >= limit {
+            break;
+        }
+        if let Some((artist, _)) = best_match(&candidate.name, local, LIBRARY_MATCH_THRESHOLD) {
+            if !matches.iter().any(|(id, _)| *id == artist.id) {
+                matches.push((artist.id, candidate.match_score));
+            }
+        }
+    }
+
+    matches
+}
+
 /// Artist info from local library
 #[derive(Debug, sqlx::FromRow)]
 struct LibraryArtist {
@@ -217,4 +392,38 @@ mod tests {
         assert!(artist.local_artist_id.is_none());
         assert!(artist.track_count.is_none());
     }
+
+    fn similar(name: &str, match_score: f64) -> SimilarArtist {
+        SimilarArtist {
+            name: name.to_string(),
+            mbid: None,
+            match_score,
+            url: None,
+        }
+    }
+
+    #[test]
+    fn test_match_library_artists() {
+        let local = vec![
+            LocalArtistName {
+                id: Uuid::new_v4(),
+                name: "Beyoncé".to_string(),
+            },
+            LocalArtistName {
+                id: Uuid::new_v4(),
+                name: "Sigur Rós".to_string(),
+            },
+        ];
+        let similar = vec![
+            similar("Sigur Ros", 0.9),
+            similar("Massive Attack", 0.8),
+            similar("Beyonce", 0.7),
+            similar("Sigur Rós", 0.6),
+        ];
+
+        let matches = match_library_artists(&similar, &local, 10);
+
+        assert_eq!(matches, vec![(local[1].id, 0.9), (local[0].id, 0.7)]);
+        assert_eq!(match_library_artists(&similar, &local, 1).len(), 1);
+    }
 }
@@ -42,6 +42,7 @@ use tracing::{info, instrument, warn};
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::models::track::scrobble_threshold_secs;
 use crate::models::user::UserPreferences;
 use crate::repositories::UserRepository;
 use crate::services::encryption::EncryptionService;
@@ -49,12 +50,6 @@ use crate::services::encryption::EncryptionService;
 /// ListenBrainz API base URL
 const LISTENBRAINZ_API_URL: &str = "https://api.listenbrainz.org";
 
-/// Minimum track duration in seconds for scrobbling
-const MIN_TRACK_DURATION_SECS: i32 = 30;
-
-/// Maximum play duration threshold in seconds (4 minutes)
-const MAX_SCROBBLE_THRESHOLD_SECS: i32 = 240;
-
 /// HTTP request timeout for ListenBrainz API calls.
 /// ListenBrainz recommends keeping requests under 30s; we use 10s for snappy UX.
 #[allow(dead_code)] // Used by ListenBrainzServiceBuilder
@@ -441,17 +436,15 @@ impl ListenBrainzService {
         played_at: DateTime<Utc>,
         duration_played_secs: i32,
     ) -> ApiResult<bool> {
-        // Check minimum track duration
-        if track.duration_secs < MIN_TRACK_DURATION_SECS {
+        // Check minimum track duration; the threshold is 50% of the track or
+        // 4 minutes, whichever is smaller
+        let Some(threshold_secs) = scrobble_threshold_secs(track.duration_secs) else {
             info!(
                 duration = track.duration_secs,
                 "Track too short for scrobbling"
             );
             return Ok(false);
-        }
-
-        // Calculate scrobble threshold: 50% of track or 4 minutes, whichever is smaller
-        let threshold_secs = std::cmp::min(track.duration_secs / 2, MAX_SCROBBLE_THRESHOLD_SECS);
+        };
 
         if duration_played_secs < threshold_secs {
             info!(
@@ -659,19 +652,15 @@ mod tests {
     #[test]
     fn test_scrobble_threshold_calculation() {
         // For a 3-minute track (180s), threshold should be 90s (50%)
-        let short_track_duration = 180;
-        let threshold = std::cmp::min(short_track_duration / 2, MAX_SCROBBLE_THRESHOLD_SECS);
-        assert_eq!(threshold, 90);
+        assert_eq!(scrobble_threshold_secs(180), Some(90));
 
         // For a 10-minute track (600s), threshold should be 240s (4 minutes cap)
-        let long_track_duration = 600;
-        let threshold = std::cmp::min(long_track_duration / 2, MAX_SCROBBLE_THRESHOLD_SECS);
-        assert_eq!(threshold, 240);
+        assert_eq!(scrobble_threshold_secs(600), Some(240));
     }
 
     #[test]
     fn test_min_track_duration() {
-        assert_eq!(MIN_TRACK_DURATION_SECS, 30);
+        assert_eq!(crate::models::track::MIN_SCROBBLE_TRACK_SECS, 30);
     }
 
     #[test]
@@ -0,0 +1,528 @@
+//! Lightweight Prometheus metrics registry
+//!
+//! Tracks a small, fixed set of operational metrics and renders them in the
+//! Prometheus text exposition format:
+//! - `resonance_http_requests_total{route,status}`: HTTP requests served
+//! - `resonance_transcodes_total{format}`: FFmpeg transcodes started
+//! - `resonance_transcode_duration_seconds{format,cache,result}`: wall-clock
+//!   time of finished transcodes, including cache hits and failures
+//! - `resonance_ollama_request_duration_seconds`: Ollama chat request latency
+//! - `resonance_rate_limit_rejections_total{limit}`: requests denied by a rate limit
+//! - `resonance_websocket_connections_active`: currently open WebSocket connections
+//! - `resonance_transcodes_active`: transcodes holding a concurrency slot
+//! - `resonance_transcodes_queued`: transcodes waiting for a concurrency slot
+//!
+//! The HTTP middleware and `/metrics` route receive the registry as an Axum
+//! extension; services that record metrics (rate limiters, the transcoder and
+//! the chat service) are handed a clone of the same registry when built.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Upper bounds (in seconds) of the Ollama request duration histogram buckets
+const OLLAMA_DURATION_BUCKETS: [f64; 10] = [0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0];
+
+/// Upper bounds (in seconds) of the transcode duration histogram buckets,
+/// from cache hits up to streams played to the end
+const TRANSCODE_DURATION_BUCKETS: [f64; 11] = [
+    0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 30.0, 60.0, 300.0, 900.0,
+];
+
+/// Cumulative histogram with fixed buckets
+#[derive(Debug)]
+struct Histogram {
+    bounds: &'static [f64],
+    /// Observations per bucket (non-cumulative); the last slot is `+Inf`
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(bounds: &'static [f64]) -> Self {
+        Self {
+            bounds,
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        let bucket = self
+            .bounds
+            .iter()
+            .position(|bound| value <= *bound)
+            .unwrap_or(self.bounds.len());
+        self.counts[bucket] += 1;
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug)]
+struct MetricsInner {
+    http_requests: Mutex<BTreeMap<(String, u16), u64>>,
+    transcodes: Mutex<BTreeMap<String, u64>>,
+    /// Keyed by (format, cache hit, failed)
+    transcode_durations: Mutex<BTreeMap<(String, bool, bool), Histogram>>,
+    ollama_durations: Mutex<Histogram>,
+    rate_limit_rejections: Mutex<BTreeMap<String, u64>>,
+    websocket_connections: AtomicI64,
+    transcodes_active: AtomicI64,
+    transcodes_queued: AtomicI64,
+}
+
+/// Shared metrics registry
+///
+/// Cheap to clone; clones record into the same registry.
+#[derive(Debug, Clone)]
+pub struct Metrics {
+    inner: Arc<MetricsInner>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Metrics {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(MetricsInner {
+                http_requests: Mutex::new(BTreeMap::new()),
+                transcodes: Mutex::new(BTreeMap::new()),
+                transcode_durations: Mutex::new(BTreeMap::new()),
+                ollama_durations: Mutex::new(Histogram::new(&OLLAMA_DURATION_BUCKETS)),
+                rate_limit_rejections: Mutex::new(BTreeMap::new()),
+                websocket_connections: AtomicI64::new(0),
+                transcodes_active: AtomicI64::new(0),
+                transcodes_queued: AtomicI64::new(0),
+            }),
+        }
+    }
+
+    /// Count an HTTP request by matched route and response status
+    pub fn record_http_request(&self, route: &str, status: u16) {
+        increment(&self.inner.http_requests, (route.to_string(), status));
+    }
+
+    /// Number of HTTP requests recorded for a route and status
+    #[allow(dead_code)] // Exposed for tests and monitoring
+    pub fn http_requests(&self, route: &str, status: u16) -> u64 {
+        let counters = lock(&self.inner.http_requests);
+        counters
+            .get(&(route.to_string(), status))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Count a transcode started for an output format
+    pub fn record_transcode(&self, format: &str) {
+        increment(&self.inner.transcodes, format.to_string());
+    }
+
+    /// Record the wall-clock duration of a finished transcode
+    pub fn observe_transcode(
+        &self,
+        format: &str,
+        cache_hit: bool,
+        failed: bool,
+        duration: Duration,
+    ) {
+        lock(&self.inner.transcode_durations)
+            .entry((format.to_string(), cache_hit, failed))
+            .or_insert_with(|| Histogram::new(&TRANSCODE_DURATION_BUCKETS))
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record the duration of a request to Ollama
+    pub fn observe_ollama_request(&self, duration: Duration) {
+        lock(&self.inner.ollama_durations).observe(duration.as_secs_f64());
+    }
+
+    /// Count a request rejected by the named rate limit
+    pub fn record_rate_limit_rejection(&self, limit: &str) {
+        increment(&self.inner.rate_limit_rejections, limit.to_string());
+    }
+
+    /// Track an open WebSocket connection until the guard is dropped
+    pub fn websocket_connected(&self) -> WebSocketConnectionGuard {
+        self.inner
+            .websocket_connections
+            .fetch_add(1, Ordering::Relaxed);
+        WebSocketConnectionGuard {
+            metrics: self.clone(),
+        }
+    }
+
+    /// Number of currently open WebSocket connections
+    pub fn websocket_connections(&self) -> i64 {
+        self.inner.websocket_connections.load(Ordering::Relaxed)
+    }
+
+    /// Track a transcode holding a concurrency slot until the guard is dropped
+    pub fn transcode_started(&self) -> GaugeGuard {
+        GaugeGuard::new(self, |inner| &inner.transcodes_active)
+    }
+
+    /// Track a transcode waiting for a concurrency slot until the guard is dropped
+    pub fn transcode_queued(&self) -> GaugeGuard {
+        GaugeGuard::new(self, |inner| &inner.transcodes_queued)
+    }
+
+    /// Number of transcodes currently holding a concurrency slot
+    pub fn transcodes_active(&self) -> i64 {
+        self.inner.transcodes_active.load(Ordering::Relaxed)
+    }
+
+    /// Number of transcodes currently waiting for a concurrency slot
+    pub fn transcodes_queued(&self) -> i64 {
+        self.inner.transcodes_queued.load(Ordering::Relaxed)
+    }
+
+    /// Render all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        write_header(
+            &mut out,
+            "resonance_http_requests_total",
+            "HTTP requests by matched route and response status",
+            "counter",
+        );
+        for ((route, status), value) in lock(&self.inner.http_requests).iter() {
+            let _ = writeln!(
+                out,
+                "resonance_http_requests_total{{route=\"{}\",status=\"{}\"}} {}",
+                escape_label(route),
+                status,
+                value
+            );
+        }
+
+        write_header(
+            &mut out,
+            "resonance_transcodes_total",
+            "Transcodes started by output format",
+            "counter",
+        );
+        for (format, value) in lock(&self.inner.transcodes).iter() {
+            let _ = writeln!(
+                out,
+                "resonance_transcodes_total{{format=\"{}\"}} {}",
+                escape_label(format),
+                value
+            );
+        }
+
+        write_header(
+            &mut out,
+            "resonance_transcode_duration_seconds",
+            "Wall-clock duration of finished transcodes by output format, cache use and result",
+            "histogram",
+        );
+        for ((format, cache_hit, failed), histogram) in lock(&self.inner.transcode_durations).iter()
+        {
+            let labels = format!(
+                "format=\"{}\",cache=\"{}\",result=\"{}\"",
+                escape_label(format),
+                if *cache_hit { "hit" } else { "miss" },
+                if *failed { "error" } else { "ok" }
+            );
+            write_histogram(
+                &mut out,
+                "resonance_transcode_duration_seconds",
+                &labels,
+                histogram,
+            );
+        }
+
+        write_header(
+            &mut out,
+            "resonance_ollama_request_duration_seconds",
+            "Duration of Ollama chat requests",
+            "histogram",
+        );
+        write_histogram(
+            &mut out,
+            "resonance_ollama_request_duration_seconds",
+            "",
+            &lock(&self.inner.ollama_durations),
+        );
+
+        write_header(
+            &mut out,
+            "resonance_rate_limit_rejections_total",
+            "Requests rejected by a rate limit",
+            "counter",
+        );
+        for (limit, value) in lock(&self.inner.rate_limit_rejections).iter() {
+            let _ = writeln!(
+                out,
+                "resonance_rate_limit_rejections_total{{limit=\"{}\"}} {}",
+                escape_label(limit),
+                value
+            );
+        }
+
+        write_header(
+            &mut out,
+            "resonance_websocket_connections_active",
+            "Currently open WebSocket connections",
+            "gauge",
+        );
+        let _ = writeln!(
+            out,
+            "resonance_websocket_connections_active {}",
+            self.websocket_connections()
+        );
+
+        write_header(
+            &mut out,
+            "resonance_transcodes_active",
+            "Transcodes holding a concurrency slot",
+            "gauge",
+        );
+        let _ = writeln!(
+            out,
+            "resonance_transcodes_active {}",
+            self.transcodes_active()
+        );
+
+        write_header(
+            &mut out,
+            "resonance_transcodes_queued",
+            "Transcodes waiting for a concurrency slot",
+            "gauge",
+        );
+        let _ = writeln!(
+            out,
+            "resonance_transcodes_queued {}",
+            self.transcodes_queued()
+        );
+
+        out
+    }
+}
+
+/// Decrements the active WebSocket gauge when the connection ends
+#[derive(Debug)]
+pub struct WebSocketConnectionGuard {
+    metrics: Metrics,
+}
+
+impl Drop for WebSocketConnectionGuard {
+    fn drop(&mut self) {
+        self.metrics
+            .inner
+            .websocket_connections
+            .fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Increments a gauge while alive and decrements it when dropped
+#[derive(Debug)]
+pub struct GaugeGuard {
+    metrics: Metrics,
+    gauge: fn(&MetricsInner) -> &AtomicI64,
+}
+
+impl GaugeGuard {
+    fn new(metrics: &Metrics, gauge: fn(&MetricsInner) -> &AtomicI64) -> Self {
+        gauge(&metrics.inner).fetch_add(1, Ordering::Relaxed);
+        Self {
+            metrics: metrics.clone(),
+            gauge,
+        }
+    }
+}
+
+impl Drop for GaugeGuard {
+    fn drop(&mut self) {
+        (self.gauge)(&self.metrics.inner).fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Lock a metrics mutex, recovering from poisoning
+///
+/// A panic while holding the lock cannot leave a counter map inconsistent,
+/// so metrics keep working rather than propagating the panic.
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn increment<K: Ord>(counters: &Mutex<BTreeMap<K, u64>>, key: K) {
+    *lock(counters).entry(key).or_insert(0) += 1;
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Write a histogram's bucket, sum and count series
+///
+/// `labels` are already-rendered `name="value"` pairs, or empty.
+fn write_histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    let bucket_labels = |le: &str| {
+        if labels.is_empty() {
+            format!("le=\"{}\"", le)
+        } else {
+            format!("{},le=\"{}\"", labels, le)
+        }
+    };
+    let series_labels = if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", labels)
+    };
+
+    let mut cumulative = 0;
+    for (bound, count) in histogram.bounds.iter().zip(&histogram.counts) {
+        cumulative += count;
+        let _ = writeln!(
+            out,
+            "{}_bucket{{{}}} {}",
+            name,
+            bucket_labels(&bound.to_string()),
+            cumulative
+        );
+    }
+    let _ = writeln!(
+        out,
+        "{}_bucket{{{}}} {}",
+        name,
+        bucket_labels("+Inf"),
+        histogram.count
+    );
+    let _ = writeln!(out, "{}_sum{} {}", name, series_labels, histogram.sum);
+    let _ = writeln!(out, "{}_count{} {}", name, series_labels, histogram.count);
+}
+
+/// Escape a label value per the Prometheus text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_requests_counted_by_route_and_status() {
+        let metrics = Metrics::new();
+        metrics.record_http_request("/graphql", 200);
+        metrics.record_http_request("/graphql", 200);
+        metrics.record_http_request("/graphql", 400);
+
+        assert_eq!(metrics.http_requests("/graphql", 200), 2);
+        assert_eq!(metrics.http_requests("/graphql", 400), 1);
+        assert_eq!(metrics.http_requests("/health", 200), 0);
+
+        let rendered = metrics.render();
+        assert!(
+            rendered.contains("resonance_http_requests_total{route=\"/graphql\",status=\"200\"} 2")
+        );
+        assert!(rendered.contains("# TYPE resonance_http_requests_total counter"));
+    }
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let metrics = Metrics::new();
+        metrics.observe_ollama_request(Duration::from_millis(200));
+        metrics.observe_ollama_request(Duration::from_secs(3));
+        metrics.observe_ollama_request(Duration::from_secs(500));
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("resonance_ollama_request_duration_seconds_bucket{le=\"0.1\"} 0"));
+        assert!(
+            rendered.contains("resonance_ollama_request_duration_seconds_bucket{le=\"0.25\"} 1")
+        );
+        assert!(rendered.contains("resonance_ollama_request_duration_seconds_bucket{le=\"5\"} 2"));
+        assert!(rendered.contains("resonance_ollama_request_duration_seconds_bucket{le=\"120\"} 2"));
+        assert!(
+            rendered.contains("resonance_ollama_request_duration_seconds_bucket{le=\"+Inf\"} 3")
+        );
+        assert!(rendered.contains("resonance_ollama_request_duration_seconds_count 3"));
+    }
+
+    #[test]
+    fn test_transcode_durations_labelled_by_format_cache_and_result() {
+        let metrics = Metrics::new();
+        metrics.observe_transcode("mp3", false, false, Duration::from_secs(2));
+        metrics.observe_transcode("mp3", true, false, Duration::from_millis(5));
+        metrics.observe_transcode("opus", false, true, Duration::from_millis(30));
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("# TYPE resonance_transcode_duration_seconds histogram"));
+        assert!(rendered.contains(
+            "resonance_transcode_duration_seconds_bucket{format=\"mp3\",cache=\"miss\",result=\"ok\",le=\"1\"} 0"
+        ));
+        assert!(rendered.contains(
+            "resonance_transcode_duration_seconds_bucket{format=\"mp3\",cache=\"miss\",result=\"ok\",le=\"5\"} 1"
+        ));
+        assert!(rendered.contains(
+            "resonance_transcode_duration_seconds_bucket{format=\"mp3\",cache=\"hit\",result=\"ok\",le=\"0.01\"} 1"
+        ));
+        assert!(rendered.contains(
+            "resonance_transcode_duration_seconds_count{format=\"opus\",cache=\"miss\",result=\"error\"} 1"
+        ));
+    }
+
+    #[test]
+    fn test_websocket_gauge_follows_guards() {
+        let metrics = Metrics::new();
+        let first = metrics.websocket_connected();
+        let second = metrics.websocket_connected();
+        assert_eq!(metrics.websocket_connections(), 2);
+
+        drop(first);
+        assert_eq!(metrics.websocket_connections(), 1);
+        drop(second);
+        assert!(metrics
+            .render()
+            .contains("resonance_websocket_connections_active 0"));
+    }
+
+    #[test]
+    fn test_transcode_gauges_follow_guards() {
+        let metrics = Metrics::new();
+        let active = metrics.transcode_started();
+        let queued = metrics.transcode_queued();
+        assert_eq!(metrics.transcodes_active(), 1);
+        assert_eq!(metrics.transcodes_queued(), 1);
+
+        drop(queued);
+        let rendered = metrics.render();
+        assert!(rendered.contains("resonance_transcodes_active 1"));
+        assert!(rendered.contains("resonance_transcodes_queued 0"));
+        drop(active);
+        assert_eq!(metrics.transcodes_active(), 0);
+    }
+
+    #[test]
+    fn test_labelled_counters_rendered() {
+        let metrics = Metrics::new();
+        metrics.record_transcode("mp3");
+        metrics.record_rate_limit_rejection("login");
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("resonance_transcodes_total{format=\"mp3\"} 1"));
+        assert!(rendered.contains("resonance_rate_limit_rejections_total{limit=\"login\"} 1"));
+    }
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label(r#"a"b\c"#), r#"a\"b\\c"#);
+        assert_eq!(escape_label("line\nbreak"), "line\\nbreak");
+    }
+}
@@ -11,18 +11,26 @@
 //! - Encryption for sensitive data
 //! - Configuration loading with DB -> Env -> Defaults priority
 //! - Meilisearch full-text search
+//! - Personal data export
+//! - Prometheus metrics registry
+//! - Idempotency keys for non-idempotent mutations
 
 pub mod auth;
 pub mod chat;
+pub mod circuit_breaker;
 pub mod config;
+pub mod data_export;
 pub mod encryption;
 pub mod health;
+pub mod idempotency;
 pub mod lastfm;
 pub mod listenbrainz;
 pub mod meilisearch;
+pub mod metrics;
 pub mod playlist;
 pub mod search;
 pub mod similarity;
+pub mod transcode_cache;
 pub mod transcoder;
 
 pub use auth::AuthService;
@@ -31,14 +39,18 @@ pub use config::{ConfigError, ConfigResult, ConfigService, LastFmConfig, MusicLi
 #[allow(unused_imports)] // Re-exported for external crate use
 pub use encryption::{EncryptionError, EncryptionService};
 pub use health::HealthService;
+pub use idempotency::{IdempotencyKey, IdempotencyStore};
+pub use metrics::Metrics;
 #[allow(unused_imports)] // Will be used once integrated into mutations
-pub use playlist::PlaylistService;
-pub use transcoder::{TranscodeFormat, TranscodeOptions, TranscoderService};
+pub use playlist::{PlaylistOperation, PlaylistService};
+#[allow(unused_imports)] // Re-exported for external crate use
+pub use transcode_cache::{CachedTranscode, TranscodeCache, TranscodeCacheKey};
+pub use transcoder::{Normalization, TranscodeFormat, TranscodeOptions, TranscoderService};
 
 // AI/Search services - re-exported for schema builder and external use
 // These are used via the schema builder pattern, not direct crate imports
 #[allow(unused_imports)]
-pub use chat::{ChatAction, ChatError, ChatService, UserContext};
+pub use chat::{ChatAction, ChatConfig, ChatError, ChatService, UserContext};
 #[allow(unused_imports)]
 pub use lastfm::LastfmService;
 #[allow(unused_imports)]
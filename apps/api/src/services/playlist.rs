@@ -4,6 +4,8 @@
 //! - Rule evaluation against the track library
 //! - Similarity-based track discovery using the SimilarityService
 //! - Dynamic SQL query building for filter rules
+//! - Import/export in M3U and JSON formats
+//! - Collaborator permission checks
 
 use std::collections::HashSet;
 
@@ -12,7 +14,11 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
-use crate::models::playlist::{Playlist, SmartPlaylistRule, SmartPlaylistRules};
+use crate::models::playlist::{
+    CollaboratorPermission, ExportFormat, ExportedTrack, Playlist, PlaylistCollaborator,
+    PlaylistExport, PlaylistImport, PlaylistType, SmartPlaylistRule, SmartPlaylistRules,
+    UnmatchedEntry, PLAYLIST_EXPORT_VERSION,
+};
 use crate::repositories::PlaylistRepository;
 use crate::services::similarity::SimilarityService;
 
@@ -32,6 +38,54 @@ const MAX_SIMILAR_TRACKS: i32 = 100;
 /// Default minimum similarity score threshold
 const DEFAULT_MIN_SCORE: f64 = 0.5;
 
+/// Minimum trigram similarity for both artist and title in fuzzy import matching
+const MIN_FUZZY_MATCH_SIMILARITY: f32 = 0.5;
+
+/// Playlist name used when an imported M3U file has no `#PLAYLIST` directive
+const DEFAULT_IMPORT_NAME: &str = "Imported Playlist";
+
+/// A playlist entry read from an import file, before matching against the library
+#[derive(Debug, Clone, Default, PartialEq)]
+struct ImportEntry {
+    track_id: Option<Uuid>,
+    file_path: Option<String>,
+    artist: Option<String>,
+    title: Option<String>,
+}
+
+/// A playlist read from an import file
+#[derive(Debug, Clone, PartialEq)]
+struct ParsedPlaylist {
+    name: String,
+    description: Option<String>,
+    is_public: bool,
+    entries: Vec<ImportEntry>,
+}
+
+/// Classes of playlist operations, for collaborator permission checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistOperation {
+    /// Read the playlist, its tracks and its collaborators
+    Read,
+    /// Add, remove or reorder tracks
+    EditTracks,
+    /// Add, remove or change collaborators
+    ManageCollaborators,
+    /// Rename, update or delete the playlist
+    Manage,
+}
+
+impl PlaylistOperation {
+    /// Lowest collaborator permission that allows this operation
+    pub fn required_permission(self) -> CollaboratorPermission {
+        match self {
+            Self::Read => CollaboratorPermission::Viewer,
+            Self::EditTracks => CollaboratorPermission::Editor,
+            Self::ManageCollaborators | Self::Manage => CollaboratorPermission::Admin,
+        }
+    }
+}
+
 /// Service for playlist operations including smart rule evaluation
 ///
 /// NOTE: This service creates its own instances of PlaylistRepository and SimilarityService
@@ -592,12 +646,407 @@ impl PlaylistService {
 
         Ok(updated)
     }
+
+    /// Check that a user may perform `operation` on a playlist
+    ///
+    /// Owners may do anything and anyone may read a public playlist. Other
+    /// users need a collaborator permission of at least
+    /// [`PlaylistOperation::required_permission`].
+    ///
+    /// # Returns
+    /// The playlist, so callers don't have to fetch it again
+    ///
+    /// # Errors
+    /// - `ApiError::NotFound` if the playlist doesn't exist
+    /// - `ApiError::Forbidden` if the user's permission is too low
+    #[instrument(skip(self))]
+    pub async fn authorize(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+        operation: PlaylistOperation,
+    ) -> ApiResult<Playlist> {
+        let playlist = self
+            .playlist_repo
+            .find_by_id(playlist_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("playlist", playlist_id.to_string()))?;
+
+        if playlist.user_id == user_id
+            || (operation == PlaylistOperation::Read && playlist.is_public)
+        {
+            return Ok(playlist);
+        }
+
+        let required = operation.required_permission();
+        match self
+            .playlist_repo
+            .find_collaborator_permission(playlist_id, user_id)
+            .await?
+        {
+            Some(permission) if permission >= required => Ok(playlist),
+            Some(permission) => Err(ApiError::Forbidden(format!(
+                "This requires {} access to the playlist; you have {} access",
+                required, permission
+            ))),
+            None => Err(ApiError::Forbidden(
+                "You don't have access to this playlist".to_string(),
+            )),
+        }
+    }
+
+    /// Add a collaborator or change an existing collaborator's permission
+    ///
+    /// Requires admin access. The owner can't be added as a collaborator.
+    #[instrument(skip(self))]
+    pub async fn set_collaborator(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+        collaborator_id: Uuid,
+        permission: CollaboratorPermission,
+    ) -> ApiResult<PlaylistCollaborator> {
+        let playlist = self
+            .authorize(playlist_id, user_id, PlaylistOperation::ManageCollaborators)
+            .await?;
+
+        if playlist.user_id == collaborator_id {
+            return Err(ApiError::ValidationError(
+                "The playlist owner can't be added as a collaborator".to_string(),
+            ));
+        }
+
+        Ok(self
+            .playlist_repo
+            .upsert_collaborator(playlist_id, collaborator_id, permission)
+            .await?)
+    }
+
+    /// Remove a collaborator
+    ///
+    /// Requires admin access, except that collaborators may always remove
+    /// themselves. Returns false if the user wasn't a collaborator.
+    #[instrument(skip(self))]
+    pub async fn remove_collaborator(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+        collaborator_id: Uuid,
+    ) -> ApiResult<bool> {
+        if collaborator_id != user_id {
+            self.authorize(playlist_id, user_id, PlaylistOperation::ManageCollaborators)
+                .await?;
+        }
+
+        Ok(self
+            .playlist_repo
+            .remove_collaborator(playlist_id, collaborator_id)
+            .await?)
+    }
+
+    /// Export a playlist as M3U or JSON
+    ///
+    /// The user must be able to access the playlist (owner, collaborator, or
+    /// public playlist).
+    #[instrument(skip(self))]
+    pub async fn export(
+        &self,
+        playlist_id: Uuid,
+        user_id: Uuid,
+        format: ExportFormat,
+    ) -> ApiResult<String> {
+        let playlist = self
+            .playlist_repo
+            .find_by_id(playlist_id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("playlist", playlist_id.to_string()))?;
+
+        if !self.playlist_repo.can_access(playlist_id, user_id).await? {
+            return Err(ApiError::Forbidden(
+                "Cannot export a playlist you do not have access to".to_string(),
+            ));
+        }
+
+        let tracks: Vec<ExportedTrack> = sqlx::query_as(
+            r#"
+            SELECT
+                t.id AS track_id,
+                t.title,
+                ar.name AS artist,
+                al.title AS album,
+                t.duration_ms,
+                t.file_path
+            FROM playlist_tracks pt
+            JOIN tracks t ON t.id = pt.track_id
+            LEFT JOIN artists ar ON ar.id = t.artist_id
+            LEFT JOIN albums al ON al.id = t.album_id
+            WHERE pt.playlist_id = $1
+            ORDER BY pt.position ASC
+            "#,
+        )
+        .bind(playlist_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let export = PlaylistExport {
+            version: PLAYLIST_EXPORT_VERSION,
+            name: playlist.name,
+            description: playlist.description,
+            is_public: playlist.is_public,
+            tracks,
+        };
+
+        match format {
+            ExportFormat::M3u => Ok(render_m3u(&export)),
+            ExportFormat::Json => serde_json::to_string_pretty(&export).map_err(|e| {
+                ApiError::Internal(format!("failed to serialize playlist export: {}", e))
+            }),
+        }
+    }
+
+    /// Import a playlist from M3U or JSON as a new manual playlist
+    ///
+    /// Each entry is matched to a library track by track id (JSON exports from
+    /// this instance), then by exact file path, then by a fuzzy artist + title
+    /// match. Entries that match no track are returned in
+    /// [`PlaylistImport::unmatched`]; duplicate matches are kept only once.
+    #[instrument(skip(self, data))]
+    pub async fn import(
+        &self,
+        user_id: Uuid,
+        format: ExportFormat,
+        data: &str,
+    ) -> ApiResult<PlaylistImport> {
+        let parsed = match format {
+            ExportFormat::M3u => parse_m3u(data),
+            ExportFormat::Json => parse_json_export(data)?,
+        };
+
+        let mut track_ids: Vec<Uuid> = Vec::new();
+        let mut seen: HashSet<Uuid> = HashSet::new();
+        let mut unmatched = Vec::new();
+
+        for (position, entry) in parsed.entries.into_iter().enumerate() {
+            match self.match_import_entry(&entry).await? {
+                Some(track_id) => {
+                    if seen.insert(track_id) {
+                        track_ids.push(track_id);
+                    }
+                }
+                None => unmatched.push(UnmatchedEntry {
+                    position,
+                    file_path: entry.file_path,
+                    artist: entry.artist,
+                    title: entry.title,
+                }),
+            }
+        }
+
+        let playlist = self
+            .playlist_repo
+            .create(
+                user_id,
+                &parsed.name,
+                parsed.description.as_deref(),
+                parsed.is_public,
+                PlaylistType::Manual,
+                None,
+            )
+            .await?;
+
+        self.playlist_repo
+            .set_tracks(playlist.id, &track_ids, Some(user_id))
+            .await?;
+
+        let playlist = self
+            .playlist_repo
+            .find_by_id(playlist.id)
+            .await?
+            .ok_or_else(|| ApiError::not_found("playlist", playlist.id.to_string()))?;
+
+        tracing::info!(
+            playlist_id = %playlist.id,
+            matched = track_ids.len(),
+            unmatched = unmatched.len(),
+            "Imported playlist"
+        );
+
+        Ok(PlaylistImport {
+            playlist,
+            unmatched,
+        })
+    }
+
+    /// Find the library track for an imported entry
+    async fn match_import_entry(&self, entry: &ImportEntry) -> ApiResult<Option<Uuid>> {
+        if let Some(track_id) = entry.track_id {
+            let exists: bool =
+                sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tracks WHERE id = $1)")
+                    .bind(track_id)
+                    .fetch_one(&self.pool)
+                    .await?;
+            if exists {
+                return Ok(Some(track_id));
+            }
+        }
+
+        if let Some(path) = &entry.file_path {
+            let by_path: Option<Uuid> =
+                sqlx::query_scalar("SELECT id FROM tracks WHERE file_path = $1")
+                    .bind(path)
+                    .fetch_optional(&self.pool)
+                    .await?;
+            if by_path.is_some() {
+                return Ok(by_path);
+            }
+        }
+
+        let (Some(artist), Some(title)) = (&entry.artist, &entry.title) else {
+            return Ok(None);
+        };
+
+        let by_name: Option<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT t.id
+            FROM tracks t
+            JOIN artists a ON a.id = t.artist_id
+            WHERE similarity(lower(t.title), lower($1)) >= $3
+              AND similarity(lower(a.name), lower($2)) >= $3
+            ORDER BY similarity(lower(t.title), lower($1))
+                   + similarity(lower(a.name), lower($2)) DESC,
+                     t.id
+            LIMIT 1
+            "#,
+        )
+        .bind(title)
+        .bind(artist)
+        .bind(MIN_FUZZY_MATCH_SIMILARITY)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(by_name)
+    }
+}
+
+/// Render a playlist as extended M3U
+fn render_m3u(export: &PlaylistExport) -> String {
+    let mut out = String::from("#EXTM3U\n");
+    out.push_str(&format!("#PLAYLIST:{}\n", single_line(&export.name)));
+
+    for track in &export.tracks {
+        let seconds = track.duration_ms.map_or(-1, |ms| ms / 1000);
+        let label = match &track.artist {
+            Some(artist) => format!("{} - {}", artist, track.title),
+            None => track.title.clone(),
+        };
+        out.push_str(&format!("#EXTINF:{},{}\n", seconds, single_line(&label)));
+        if let Some(path) = &track.file_path {
+            out.push_str(path);
+            out.push('\n');
+        }
+    }
+
+    out
+}
+
+/// Replace line breaks so a value fits on one M3U line
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+/// Parse an M3U or extended M3U playlist
+///
+/// `#EXTINF:<seconds>,<artist> - <title>` lines supply the artist and title
+/// for the path that follows. Unknown directives and comments are ignored.
+fn parse_m3u(data: &str) -> ParsedPlaylist {
+    let mut name = None;
+    let mut entries = Vec::new();
+    let mut pending: Option<ImportEntry> = None;
+
+    for line in data.lines() {
+        let line = line.trim().trim_start_matches('\u{feff}');
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(value) = line.strip_prefix("#PLAYLIST:") {
+            let value = value.trim();
+            if !value.is_empty() {
+                name = Some(value.to_string());
+            }
+        } else if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // Duration and attributes come before the first comma
+            let label = info.split_once(',').map_or("", |(_, label)| label.trim());
+            let (artist, title) = match label.split_once(" - ") {
+                Some((artist, title)) => (non_empty(artist), non_empty(title)),
+                None => (None, non_empty(label)),
+            };
+            pending = Some(ImportEntry {
+                artist,
+                title,
+                ..Default::default()
+            });
+        } else if !line.starts_with('#') {
+            let mut entry = pending.take().unwrap_or_default();
+            entry.file_path = Some(line.to_string());
+            entries.push(entry);
+        }
+    }
+
+    ParsedPlaylist {
+        name: name.unwrap_or_else(|| DEFAULT_IMPORT_NAME.to_string()),
+        description: None,
+        is_public: false,
+        entries,
+    }
+}
+
+/// Parse a JSON playlist export document
+fn parse_json_export(data: &str) -> ApiResult<ParsedPlaylist> {
+    let export: PlaylistExport = serde_json::from_str(data)
+        .map_err(|e| ApiError::ValidationError(format!("invalid playlist JSON: {}", e)))?;
+
+    if export.version > PLAYLIST_EXPORT_VERSION {
+        return Err(ApiError::ValidationError(format!(
+            "unsupported playlist export version {}",
+            export.version
+        )));
+    }
+
+    let name = export.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::ValidationError(
+            "playlist name cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(ParsedPlaylist {
+        name: name.to_string(),
+        description: export.description,
+        is_public: export.is_public,
+        entries: export
+            .tracks
+            .into_iter()
+            .map(|track| ImportEntry {
+                track_id: track.track_id,
+                file_path: track.file_path,
+                artist: track.artist,
+                title: non_empty(&track.title),
+            })
+            .collect(),
+    })
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
 }
 
 #[cfg(test)]
 mod tests {
-    // Note: super::* unused currently since we can't create PlaylistService without a DB pool.
-    // Tests for SQL generation and value extraction would require integration tests.
+    // Note: we can't create PlaylistService without a DB pool, so only the pure
+    // helpers are tested here. Library matching is covered by integration tests.
+    use super::*;
 
     #[test]
     fn test_get_sql_field_valid_fields() {
@@ -626,4 +1075,97 @@ mod tests {
         ];
         assert_eq!(valid_fields.len(), 19);
     }
+
+    const SAMPLE_M3U: &str = "#EXTM3U
+#PLAYLIST:Road Trip
+#EXTINF:354,Queen - Bohemian Rhapsody
+/music/Queen/A Night at the Opera/01 Bohemian Rhapsody.flac
+
+#EXTINF:-1,Unknown Song
+relative/unknown.mp3
+# a comment
+/music/no-extinf.ogg
+";
+
+    #[test]
+    fn test_parse_m3u_entries() {
+        let parsed = parse_m3u(SAMPLE_M3U);
+
+        assert_eq!(parsed.name, "Road Trip");
+        assert_eq!(parsed.entries.len(), 3);
+        assert_eq!(
+            parsed.entries[0],
+            ImportEntry {
+                track_id: None,
+                file_path: Some(
+                    "/music/Queen/A Night at the Opera/01 Bohemian Rhapsody.flac".to_string()
+                ),
+                artist: Some("Queen".to_string()),
+                title: Some("Bohemian Rhapsody".to_string()),
+            }
+        );
+        assert_eq!(parsed.entries[1].artist, None);
+        assert_eq!(parsed.entries[1].title.as_deref(), Some("Unknown Song"));
+        assert_eq!(
+            parsed.entries[2],
+            ImportEntry {
+                file_path: Some("/music/no-extinf.ogg".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_m3u_without_playlist_name() {
+        let parsed = parse_m3u("\u{feff}#EXTM3U\r\n/music/a.flac\r\n");
+        assert_eq!(parsed.name, DEFAULT_IMPORT_NAME);
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(
+            parsed.entries[0].file_path.as_deref(),
+            Some("/music/a.flac")
+        );
+    }
+
+    #[test]
+    fn test_render_m3u_round_trips_through_parser() {
+        let export = PlaylistExport {
+            version: PLAYLIST_EXPORT_VERSION,
+            name: "Mix\nTape".to_string(),
+            description: None,
+            is_public: false,
+            tracks: vec![ExportedTrack {
+                track_id: Some(Uuid::new_v4()),
+                title: "Song".to_string(),
+                artist: Some("Band".to_string()),
+                album: None,
+                duration_ms: Some(185_900),
+                file_path: Some("/music/Band/Song.flac".to_string()),
+            }],
+        };
+
+        let m3u = render_m3u(&export);
+        assert!(m3u.starts_with("#EXTM3U\n#PLAYLIST:Mix Tape\n"));
+        assert!(m3u.contains("#EXTINF:185,Band - Song\n/music/Band/Song.flac\n"));
+
+        let parsed = parse_m3u(&m3u);
+        assert_eq!(parsed.name, "Mix Tape");
+        assert_eq!(parsed.entries[0].artist.as_deref(), Some("Band"));
+        assert_eq!(parsed.entries[0].title.as_deref(), Some("Song"));
+    }
+
+    #[test]
+    fn test_parse_json_export_rejects_invalid_documents() {
+        assert!(matches!(
+            parse_json_export("not json"),
+            Err(ApiError::ValidationError(_))
+        ));
+        assert!(matches!(
+            parse_json_export(r#"{"version": 99, "name": "x", "tracks": []}"#),
+            Err(ApiError::ValidationError(_))
+        ));
+        assert!(matches!(
+            parse_json_export(r#"{"version": 1, "name": "  ", "tracks": []}"#),
+            Err(ApiError::ValidationError(_))
+        ));
+    }
 }
@@ -3,26 +3,41 @@
 //! Provides AI-powered semantic search capabilities:
 //! - Natural language query search using embeddings
 //! - Mood-based track discovery
+//! - Hybrid search: semantic ranking constrained by mood, genre, and year
 //! - Combined with existing similarity features
 //!
-//! Uses pgvector for efficient vector similarity search.
+//! Uses pgvector for efficient vector similarity search. Large libraries can
+//! use [`SearchService::search_by_embedding_fast`], which shortlists tracks by
+//! their reduced-dimension embedding before exact re-ranking.
+//!
+//! When the database lacks the pgvector extension (see [`crate::migrations`]),
+//! the service is built with semantic search disabled: embedding searches fail
+//! fast with `ApiError::SemanticSearchUnavailable` and callers fall back to
+//! [`SearchService::search_by_mood`] or [`SearchService::search_by_keyword`].
+//! Switching the `FEATURE_SEMANTIC_SEARCH` flag off does the same, failing
+//! with `ApiError::FeatureDisabled` instead.
 
 // Service is used via GraphQL schema builder, not direct crate imports
 #![allow(dead_code)]
 
+use resonance_ollama_client::{reduce_embedding, EMBEDDING_DIMENSION};
+use resonance_shared_config::DEFAULT_EMBEDDING_DIMENSION;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::repositories::utils::escape_ilike;
 
 /// Maximum number of search results
 const MAX_SEARCH_RESULTS: i32 = 100;
 
-/// Expected embedding dimension for nomic-embed-text model
-/// Must match the dimension used by the Ollama client
-const EXPECTED_EMBEDDING_DIMENSION: usize = 768;
+/// Candidates shortlisted per requested result by fast search
+const FAST_SEARCH_CANDIDATE_FACTOR: i32 = 10;
+
+/// Minimum candidates shortlisted by fast search
+const FAST_SEARCH_MIN_CANDIDATES: i32 = 100;
 
 /// Validate and clamp the limit parameter
 fn validate_limit(limit: i32) -> i32 {
@@ -33,6 +48,12 @@ fn validate_limit(limit: i32) -> i32 {
 #[derive(Clone)]
 pub struct SearchService {
     db: PgPool,
+    /// Dimension of query embeddings, matching `OllamaConfig::embedding_dimension`
+    embedding_dimension: usize,
+    /// Whether pgvector is installed and embedding searches can run
+    semantic_available: bool,
+    /// Whether semantic search is switched on for this deployment
+    semantic_enabled: bool,
 }
 
 /// A track with its search relevance score
@@ -47,6 +68,29 @@ pub struct ScoredTrack {
     pub score: f64,
 }
 
+/// SQL filters applied alongside semantic ranking
+///
+/// Genres match case-insensitively against any of a track's genres; the year
+/// range is inclusive and checked against the album's release date, so a
+/// track without one is excluded once either bound is set.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchFilters {
+    /// Track must have at least one of these genres
+    #[serde(default)]
+    pub genres: Vec<String>,
+    /// Earliest release year
+    pub year_from: Option<i32>,
+    /// Latest release year
+    pub year_to: Option<i32>,
+}
+
+impl SearchFilters {
+    /// Whether no filter is set
+    pub fn is_empty(&self) -> bool {
+        self.genres.is_empty() && self.year_from.is_none() && self.year_to.is_none()
+    }
+}
+
 /// Semantic search result containing tracks and metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SemanticSearchResult {
@@ -59,7 +103,67 @@ pub struct SemanticSearchResult {
 impl SearchService {
     /// Create a new search service
     pub fn new(db: PgPool) -> Self {
-        Self { db }
+        Self {
+            db,
+            embedding_dimension: DEFAULT_EMBEDDING_DIMENSION,
+            semantic_available: true,
+            semantic_enabled: true,
+        }
+    }
+
+    /// Enable or disable embedding-based search
+    ///
+    /// Pass the result of [`crate::migrations::run`] so a database without
+    /// pgvector reports a clear error instead of failing every query.
+    pub fn with_semantic_available(mut self, available: bool) -> Self {
+        self.semantic_available = available;
+        self
+    }
+
+    /// Switch embedding-based search on or off
+    ///
+    /// Pass the `FEATURE_SEMANTIC_SEARCH` flag; when off, embedding searches
+    /// fail with `ApiError::FeatureDisabled`.
+    pub fn with_semantic_search_enabled(mut self, enabled: bool) -> Self {
+        self.semantic_enabled = enabled;
+        self
+    }
+
+    /// Whether embedding-based (semantic and hybrid) search is available
+    pub fn semantic_available(&self) -> bool {
+        self.semantic_enabled && self.semantic_available
+    }
+
+    /// Fail fast when embedding search is switched off or unavailable
+    pub fn ensure_semantic_available(&self) -> ApiResult<()> {
+        if !self.semantic_enabled {
+            Err(ApiError::FeatureDisabled("semantic search"))
+        } else if !self.semantic_available {
+            Err(ApiError::SemanticSearchUnavailable)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set the expected query embedding dimension
+    ///
+    /// Use the configured Ollama embedding dimension so a swapped embedding
+    /// model is caught here with a clear error instead of in pgvector.
+    pub fn with_embedding_dimension(mut self, dimension: usize) -> Self {
+        self.embedding_dimension = dimension;
+        self
+    }
+
+    /// Reject query embeddings that don't match the configured dimension
+    fn validate_dimension(&self, query_embedding: &[f32]) -> ApiResult<()> {
+        if query_embedding.len() != self.embedding_dimension {
+            return Err(ApiError::ValidationError(format!(
+                "Invalid embedding dimension: expected {}, got {}",
+                self.embedding_dimension,
+                query_embedding.len()
+            )));
+        }
+        Ok(())
     }
 
     /// Perform semantic search using a pre-computed query embedding
@@ -68,10 +172,11 @@ impl SearchService {
     /// This method finds tracks whose description embeddings are most similar.
     ///
     /// # Arguments
-    /// * `query_embedding` - 768-dimensional embedding vector from Ollama
+    /// * `query_embedding` - Embedding vector from Ollama, of the configured dimension
     /// * `limit` - Maximum number of results to return
     ///
     /// # Errors
+    /// - `ApiError::SemanticSearchUnavailable` - If pgvector is not installed
     /// - `ApiError::ValidationError` - If embedding dimension is incorrect
     /// - `ApiError::Database` - If the query fails
     #[instrument(skip(self, query_embedding))]
@@ -80,14 +185,9 @@ impl SearchService {
         query_embedding: &[f32],
         limit: i32,
     ) -> ApiResult<Vec<ScoredTrack>> {
+        self.ensure_semantic_available()?;
         // Validate embedding dimension
-        if query_embedding.len() != EXPECTED_EMBEDDING_DIMENSION {
-            return Err(ApiError::ValidationError(format!(
-                "Invalid embedding dimension: expected {}, got {}",
-                EXPECTED_EMBEDDING_DIMENSION,
-                query_embedding.len()
-            )));
-        }
+        self.validate_dimension(query_embedding)?;
 
         let limit = validate_limit(limit);
 
@@ -120,18 +220,160 @@ impl SearchService {
         .fetch_all(&self.db)
         .await?;
 
-        Ok(tracks
-            .into_iter()
-            .map(|r| ScoredTrack {
-                track_id: r.track_id,
-                title: r.title,
-                artist_id: r.artist_id,
-                artist_name: r.artist_name,
-                album_id: r.album_id,
-                album_title: r.album_title,
-                score: r.score.unwrap_or(0.0),
-            })
-            .collect())
+        Ok(tracks.into_iter().map(ScoredTrack::from).collect())
+    }
+
+    /// Approximate semantic search using reduced-dimension embeddings
+    ///
+    /// Shortlists candidates by cosine distance on the 128-dim reduced
+    /// description embeddings, then re-ranks them with the full 768-dim
+    /// embedding, so scores match [`Self::search_by_embedding`]. Results can
+    /// differ from the exact search only when a relevant track misses the
+    /// shortlist. Falls back to the exact search when too few tracks have
+    /// reduced embeddings to fill `limit` (e.g. before the backfill has run).
+    ///
+    /// # Errors
+    /// - `ApiError::SemanticSearchUnavailable` - If pgvector is not installed
+    /// - `ApiError::ValidationError` - If embedding dimension is incorrect
+    /// - `ApiError::Database` - If the query fails
+    #[instrument(skip(self, query_embedding))]
+    pub async fn search_by_embedding_fast(
+        &self,
+        query_embedding: &[f32],
+        limit: i32,
+    ) -> ApiResult<Vec<ScoredTrack>> {
+        self.ensure_semantic_available()?;
+        self.validate_dimension(query_embedding)?;
+
+        // Reduced embeddings are only defined for the default dimension
+        if self.embedding_dimension != EMBEDDING_DIMENSION {
+            return self.search_by_embedding(query_embedding, limit).await;
+        }
+        let reduced = reduce_embedding(query_embedding)
+            .map_err(|e| ApiError::ValidationError(e.to_string()))?;
+
+        let limit = validate_limit(limit);
+        let candidates = (limit * FAST_SEARCH_CANDIDATE_FACTOR).max(FAST_SEARCH_MIN_CANDIDATES);
+
+        let tracks: Vec<ScoredTrackRow> = sqlx::query_as(
+            r#"
+            WITH candidates AS (
+                SELECT track_id, description_embedding
+                FROM track_embeddings
+                WHERE description_embedding_reduced IS NOT NULL
+                  AND description_embedding IS NOT NULL
+                ORDER BY description_embedding_reduced <=> $1::vector
+                LIMIT $3
+            )
+            SELECT
+                t.id as track_id,
+                t.title,
+                t.artist_id,
+                a.name as artist_name,
+                t.album_id,
+                al.title as album_title,
+                1.0 - (c.description_embedding <=> $2::vector) as score
+            FROM candidates c
+            JOIN tracks t ON t.id = c.track_id
+            LEFT JOIN artists a ON t.artist_id = a.id
+            LEFT JOIN albums al ON t.album_id = al.id
+            ORDER BY c.description_embedding <=> $2::vector
+            LIMIT $4
+            "#,
+        )
+        .bind(format_embedding(&reduced))
+        .bind(format_embedding(query_embedding))
+        .bind(candidates)
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        if (tracks.len() as i32) < limit {
+            tracing::debug!(
+                found = tracks.len(),
+                limit,
+                "Too few reduced embeddings for fast search, using exact search"
+            );
+            return self.search_by_embedding(query_embedding, limit).await;
+        }
+
+        Ok(tracks.into_iter().map(ScoredTrack::from).collect())
+    }
+
+    /// Semantic search constrained by mood, genre, and release year
+    ///
+    /// Ranks tracks by cosine distance like [`Self::search_by_embedding`],
+    /// but only among tracks that have at least one of `moods` (when given)
+    /// and pass `filters`, all in one query. With no moods and empty filters
+    /// this is exactly [`Self::search_by_embedding`].
+    ///
+    /// # Errors
+    /// - `ApiError::SemanticSearchUnavailable` - If pgvector is not installed
+    /// - `ApiError::ValidationError` - If embedding dimension is incorrect or
+    ///   `year_from` is after `year_to`
+    /// - `ApiError::Database` - If the query fails
+    #[instrument(skip(self, query_embedding))]
+    pub async fn search_by_embedding_with_filters(
+        &self,
+        query_embedding: &[f32],
+        filters: SearchFilters,
+        moods: &[String],
+        limit: i32,
+    ) -> ApiResult<Vec<ScoredTrack>> {
+        if filters.is_empty() && moods.is_empty() {
+            return self.search_by_embedding(query_embedding, limit).await;
+        }
+
+        self.ensure_semantic_available()?;
+        self.validate_dimension(query_embedding)?;
+        if let (Some(from), Some(to)) = (filters.year_from, filters.year_to) {
+            if from > to {
+                return Err(ApiError::ValidationError(format!(
+                    "year_from ({}) is after year_to ({})",
+                    from, to
+                )));
+            }
+        }
+
+        let limit = validate_limit(limit);
+        let genres_lower: Vec<String> = filters.genres.iter().map(|g| g.to_lowercase()).collect();
+        let moods_lower: Vec<String> = moods.iter().map(|m| m.to_lowercase()).collect();
+
+        let tracks: Vec<ScoredTrackRow> = sqlx::query_as(
+            r#"
+            SELECT
+                t.id as track_id,
+                t.title,
+                t.artist_id,
+                a.name as artist_name,
+                t.album_id,
+                al.title as album_title,
+                1.0 - (te.description_embedding <=> $1::vector) as score
+            FROM track_embeddings te
+            JOIN tracks t ON t.id = te.track_id
+            LEFT JOIN artists a ON t.artist_id = a.id
+            LEFT JOIN albums al ON t.album_id = al.id
+            WHERE te.description_embedding IS NOT NULL
+              AND (cardinality($3::text[]) = 0
+                   OR EXISTS (SELECT 1 FROM unnest(t.ai_mood) m WHERE LOWER(m) = ANY($3)))
+              AND (cardinality($4::text[]) = 0
+                   OR EXISTS (SELECT 1 FROM unnest(t.genres) g WHERE LOWER(g) = ANY($4)))
+              AND ($5::int IS NULL OR EXTRACT(YEAR FROM al.release_date) >= $5)
+              AND ($6::int IS NULL OR EXTRACT(YEAR FROM al.release_date) <= $6)
+            ORDER BY te.description_embedding <=> $1::vector
+            LIMIT $2
+            "#,
+        )
+        .bind(format_embedding(query_embedding))
+        .bind(limit)
+        .bind(&moods_lower)
+        .bind(&genres_lower)
+        .bind(filters.year_from)
+        .bind(filters.year_to)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(tracks.into_iter().map(ScoredTrack::from).collect())
     }
 
     /// Search tracks by mood tags
@@ -197,18 +439,7 @@ impl SearchService {
         .fetch_all(&self.db)
         .await?;
 
-        Ok(tracks
-            .into_iter()
-            .map(|r| ScoredTrack {
-                track_id: r.track_id,
-                title: r.title,
-                artist_id: r.artist_id,
-                artist_name: r.artist_name,
-                album_id: r.album_id,
-                album_title: r.album_title,
-                score: r.score.unwrap_or(0.0),
-            })
-            .collect())
+        Ok(tracks.into_iter().map(ScoredTrack::from).collect())
     }
 
     /// Get available mood tags in the library
@@ -242,9 +473,57 @@ impl SearchService {
             .collect())
     }
 
+    /// Search tracks by title or artist name
+    ///
+    /// Used when semantic search is unavailable. Prefix matches rank above
+    /// substring matches, then by play count.
+    #[instrument(skip(self))]
+    pub async fn search_by_keyword(&self, query: &str, limit: i32) -> ApiResult<Vec<ScoredTrack>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(ApiError::ValidationError(
+                "Search query must not be empty".into(),
+            ));
+        }
+
+        let limit = validate_limit(limit);
+        let escaped = escape_ilike(query);
+
+        let tracks: Vec<ScoredTrackRow> = sqlx::query_as(
+            r#"
+            SELECT
+                t.id as track_id,
+                t.title,
+                t.artist_id,
+                a.name as artist_name,
+                t.album_id,
+                al.title as album_title,
+                (CASE WHEN t.title ILIKE $2 OR a.name ILIKE $2 THEN 1.0 ELSE 0.5 END)::float8 as score
+            FROM tracks t
+            LEFT JOIN artists a ON t.artist_id = a.id
+            LEFT JOIN albums al ON t.album_id = al.id
+            WHERE t.title ILIKE $1 OR a.name ILIKE $1
+            ORDER BY score DESC, t.play_count DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(format!("%{}%", escaped))
+        .bind(format!("{}%", escaped))
+        .bind(limit)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(tracks.into_iter().map(ScoredTrack::from).collect())
+    }
+
     /// Check if any tracks have embeddings for semantic search
+    ///
+    /// Always false when semantic search is unavailable.
     #[instrument(skip(self))]
     pub async fn has_embeddings(&self) -> ApiResult<bool> {
+        if !self.semantic_available() {
+            return Ok(false);
+        }
         let count: (i64,) = sqlx::query_as(
             "SELECT COUNT(*) FROM track_embeddings WHERE description_embedding IS NOT NULL",
         )
@@ -287,6 +566,20 @@ struct ScoredTrackRow {
     score: Option<f64>,
 }
 
+impl From<ScoredTrackRow> for ScoredTrack {
+    fn from(r: ScoredTrackRow) -> Self {
+        Self {
+            track_id: r.track_id,
+            title: r.title,
+            artist_id: r.artist_id,
+            artist_name: r.artist_name,
+            album_id: r.album_id,
+            album_title: r.album_title,
+            score: r.score.unwrap_or(0.0),
+        }
+    }
+}
+
 /// Row struct for mood tag queries
 #[derive(Debug, sqlx::FromRow)]
 struct MoodTagRow {
@@ -298,6 +591,87 @@ struct MoodTagRow {
 mod tests {
     use super::*;
 
+    fn service(dimension: usize) -> SearchService {
+        let pool = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        SearchService::new(pool).with_embedding_dimension(dimension)
+    }
+
+    #[tokio::test]
+    async fn test_validate_dimension_uses_configured_dimension() {
+        let service = service(1024);
+        assert!(service.validate_dimension(&[0.0; 1024]).is_ok());
+
+        let err = service.validate_dimension(&[0.0; 768]).unwrap_err();
+        assert!(
+            matches!(&err, ApiError::ValidationError(msg) if msg.contains("expected 1024, got 768")),
+            "unexpected error: {:?}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_default_dimension() {
+        let service =
+            SearchService::new(PgPool::connect_lazy("postgres://localhost/unused").unwrap());
+        assert!(service
+            .validate_dimension(&[0.0; EMBEDDING_DIMENSION])
+            .is_ok());
+        assert!(service.validate_dimension(&[0.0; 1024]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_disabled_fails_fast() {
+        let service = service(EMBEDDING_DIMENSION).with_semantic_available(false);
+        assert!(!service.semantic_available());
+
+        // The pool is never connected, so these must return before querying
+        let embedding = [0.0; EMBEDDING_DIMENSION];
+        assert!(matches!(
+            service.search_by_embedding(&embedding, 10).await,
+            Err(ApiError::SemanticSearchUnavailable)
+        ));
+        assert!(matches!(
+            service.search_by_embedding_fast(&embedding, 10).await,
+            Err(ApiError::SemanticSearchUnavailable)
+        ));
+        let filters = SearchFilters {
+            genres: vec!["rock".to_string()],
+            ..Default::default()
+        };
+        assert!(matches!(
+            service
+                .search_by_embedding_with_filters(&embedding, filters, &[], 10)
+                .await,
+            Err(ApiError::SemanticSearchUnavailable)
+        ));
+        assert!(!service.has_embeddings().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_semantic_search_switched_off_fails_fast() {
+        let service = service(EMBEDDING_DIMENSION).with_semantic_search_enabled(false);
+        assert!(!service.semantic_available());
+
+        let embedding = [0.0; EMBEDDING_DIMENSION];
+        assert!(matches!(
+            service.search_by_embedding(&embedding, 10).await,
+            Err(ApiError::FeatureDisabled(_))
+        ));
+        assert!(matches!(
+            service.search_by_embedding_fast(&embedding, 10).await,
+            Err(ApiError::FeatureDisabled(_))
+        ));
+        assert!(!service.has_embeddings().await.unwrap());
+
+        // The flag wins over a missing pgvector, so the reason reported is stable
+        assert!(matches!(
+            service
+                .with_semantic_available(false)
+                .ensure_semantic_available(),
+            Err(ApiError::FeatureDisabled(_))
+        ));
+    }
+
     #[test]
     fn test_format_embedding() {
         let embedding = vec![0.1, 0.2, 0.3];
@@ -337,6 +711,21 @@ mod tests {
         assert_eq!(result, "[0.000000,0.000000,0.500000]");
     }
 
+    #[test]
+    fn test_search_filters_is_empty() {
+        assert!(SearchFilters::default().is_empty());
+        assert!(!SearchFilters {
+            genres: vec!["rock".to_string()],
+            ..Default::default()
+        }
+        .is_empty());
+        assert!(!SearchFilters {
+            year_to: Some(1999),
+            ..Default::default()
+        }
+        .is_empty());
+    }
+
     #[test]
     fn test_validate_limit() {
         assert_eq!(validate_limit(10), 10);
@@ -13,18 +13,27 @@
 //! `SimilarityService` to reduce database load and improve response times.
 //! Cache keys follow the format: `similarity:{track_id}:{method}:{limit}`
 //! with a configurable TTL (default: 10 minutes).
+//!
+//! ## Precomputed neighbors
+//!
+//! The worker's similarity precompute job stores each track's top combined
+//! neighbors in `track_similarities`. [`SimilarityService::find_precomputed`]
+//! serves them with a single indexed read and computes live on a miss.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 
 use redis::AsyncCommands;
+use resonance_shared_config::{ttl_with_jitter, DEFAULT_TTL_JITTER_PCT};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use tracing::{debug, info, info_span, instrument, warn, Instrument};
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::models::{AudioFeatures, SimilarityWeights};
 
 /// Query timeout in seconds for similarity queries
 const QUERY_TIMEOUT_SECONDS: u64 = 5;
@@ -37,6 +46,15 @@ const DEFAULT_WEIGHT_SEMANTIC: f64 = 0.5;
 const DEFAULT_WEIGHT_ACOUSTIC: f64 = 0.3;
 const DEFAULT_WEIGHT_CATEGORICAL: f64 = 0.2;
 
+/// Number of each user's top tracks used as seeds for blended recommendations
+const BLEND_SEED_TRACKS: i64 = 10;
+
+/// Listening window (days) from which blend seeds are drawn
+const BLEND_SEED_WINDOW_DAYS: i32 = 90;
+
+/// Tracks either user played within this many days are not recommended
+const BLEND_RECENT_EXCLUSION_DAYS: i32 = 7;
+
 /// Epsilon tolerance for weight validation (floating point comparison)
 #[allow(dead_code)]
 const WEIGHT_EPSILON: f64 = 0.001;
@@ -160,6 +178,25 @@ impl SimilarityConfig {
         }
     }
 
+    /// Scale a user's relative weights to sum to 1.0
+    ///
+    /// Returns `None` if the weights are negative, not finite, or all zero.
+    pub fn from_weights(weights: &SimilarityWeights) -> Option<Self> {
+        let parts = [weights.semantic, weights.acoustic, weights.categorical];
+        if parts.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return None;
+        }
+        let total: f64 = parts.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            weight_semantic: weights.semantic / total,
+            weight_acoustic: weights.acoustic / total,
+            weight_categorical: weights.categorical / total,
+        })
+    }
+
     /// Validate that weights sum to 1.0 (within epsilon tolerance)
     pub fn validate(&self) -> Result<(), SimilarityConfigError> {
         let total = self.weight_semantic + self.weight_acoustic + self.weight_categorical;
@@ -230,6 +267,66 @@ pub struct SimilarTrack {
     pub album_title: Option<String>,
     pub score: f64,
     pub similarity_type: SimilarityType,
+    /// Per-dimension scores behind a combined score (`None` for single-dimension methods)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<ScoreBreakdown>,
+}
+
+/// Per-dimension scores and weights that make up a combined similarity score
+///
+/// A dimension score is 0.0 when the track was not among that dimension's
+/// matches. When a dimension's lookup failed, its weight is 0.0 as well and
+/// the other weights are scaled up. The combined score is
+/// `semantic * weight_semantic + acoustic * weight_acoustic + categorical * weight_categorical`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    /// Semantic (embedding) similarity score
+    pub semantic: f64,
+    /// Acoustic (audio feature) similarity score
+    pub acoustic: f64,
+    /// Categorical (genre/mood/tags) similarity score
+    pub categorical: f64,
+    /// Weight applied to the semantic score
+    pub weight_semantic: f64,
+    /// Weight applied to the acoustic score
+    pub weight_acoustic: f64,
+    /// Weight applied to the categorical score
+    pub weight_categorical: f64,
+}
+
+impl ScoreBreakdown {
+    /// Create an empty breakdown using the configured weights
+    fn with_weights(config: &SimilarityConfig) -> Self {
+        Self {
+            weight_semantic: config.weight_semantic,
+            weight_acoustic: config.weight_acoustic,
+            weight_categorical: config.weight_categorical,
+            ..Default::default()
+        }
+    }
+
+    /// Weighted sum of the dimension scores
+    pub fn combined_score(&self) -> f64 {
+        self.semantic * self.weight_semantic
+            + self.acoustic * self.weight_acoustic
+            + self.categorical * self.weight_categorical
+    }
+
+    /// Dimensions that contributed to the combined score
+    ///
+    /// A dimension whose lookup failed has its weight set to zero, so it is
+    /// left out here.
+    pub fn contributing_dimensions(&self) -> Vec<SimilarityType> {
+        [
+            (SimilarityType::Semantic, self.weight_semantic),
+            (SimilarityType::Acoustic, self.weight_acoustic),
+            (SimilarityType::Categorical, self.weight_categorical),
+        ]
+        .into_iter()
+        .filter(|(_, weight)| *weight > 0.0)
+        .map(|(dimension, _)| dimension)
+        .collect()
+    }
 }
 
 /// Type of similarity used for the match
@@ -246,21 +343,13 @@ pub enum SimilarityType {
     Combined,
 }
 
-/// Audio features extracted from JSON.
-/// Fields are populated via serde deserialization and accessed for None checks.
-#[derive(Debug, Clone, Default, Deserialize)]
-#[allow(dead_code)] // Fields accessed via serde deserialization, not direct field access
-struct AudioFeatures {
-    bpm: Option<f64>,
-    key: Option<String>,
-    mode: Option<String>,
-    loudness: Option<f64>,
-    energy: Option<f64>,
-    danceability: Option<f64>,
-    valence: Option<f64>,
-    acousticness: Option<f64>,
-    instrumentalness: Option<f64>,
-    speechiness: Option<f64>,
+/// Tags and audio features used to explain why two tracks match
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackSignals {
+    pub genres: Vec<String>,
+    pub moods: Vec<String>,
+    pub bpm: Option<f64>,
+    pub energy: Option<f64>,
 }
 
 impl SimilarityService {
@@ -283,6 +372,50 @@ impl SimilarityService {
         &self.config
     }
 
+    /// This service scoring with a user's preferred weights
+    ///
+    /// Falls back to the configured weights when the user has none stored, or
+    /// the stored ones are unusable.
+    pub fn for_user_weights(&self, weights: Option<&SimilarityWeights>) -> Self {
+        match weights.and_then(SimilarityConfig::from_weights) {
+            Some(config) => Self {
+                db: self.db.clone(),
+                config: Arc::new(config),
+            },
+            None => self.clone(),
+        }
+    }
+
+    /// Load the signals used to explain recommendations for the given tracks
+    ///
+    /// Tracks with unreadable audio features get signals without them; tracks
+    /// that don't exist are absent from the map.
+    pub async fn track_signals(
+        &self,
+        track_ids: &[Uuid],
+    ) -> ApiResult<HashMap<Uuid, TrackSignals>> {
+        let rows: Vec<(Uuid, Vec<String>, Vec<String>, serde_json::Value)> = sqlx::query_as(
+            "SELECT id, genres, ai_mood, audio_features FROM tracks WHERE id = ANY($1)",
+        )
+        .bind(track_ids)
+        .fetch_all(&self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, genres, moods, features)| {
+                let features = AudioFeatures::from_json_value(&features).unwrap_or_default();
+                let signals = TrackSignals {
+                    genres,
+                    moods,
+                    bpm: features.bpm,
+                    energy: features.energy,
+                };
+                (id, signals)
+            })
+            .collect())
+    }
+
     /// Find similar tracks using embedding similarity (pgvector)
     ///
     /// Uses cosine distance on description embeddings for semantic similarity.
@@ -367,6 +500,7 @@ impl SimilarityService {
                 // Clamp score to [0.0, 1.0] - cosine distance can produce values outside this range
                 score: r.score.unwrap_or(0.0).clamp(0.0, 1.0),
                 similarity_type: SimilarityType::Semantic,
+                score_breakdown: None,
             })
             .collect())
     }
@@ -432,17 +566,14 @@ impl SimilarityService {
         let source_features =
             source_features.ok_or_else(|| ApiError::not_found("track", track_id.to_string()))?;
 
-        let source: AudioFeatures = match serde_json::from_value(source_features.0.clone()) {
-            Ok(features) => features,
-            Err(e) => {
-                warn!(
-                    track_id = %track_id,
-                    error = %e,
-                    "Failed to parse audio features JSON, using defaults"
-                );
-                AudioFeatures::default()
-            }
-        };
+        let source = AudioFeatures::from_json_value(&source_features.0).map_err(|e| {
+            warn!(
+                track_id = %track_id,
+                error = %e,
+                "Invalid audio features for source track"
+            );
+            e
+        })?;
 
         // Check if source has useful features
         if source.energy.is_none() && source.loudness.is_none() {
@@ -518,6 +649,7 @@ impl SimilarityService {
                 album_title: r.album_title,
                 score: r.score.unwrap_or(0.0).clamp(0.0, 1.0),
                 similarity_type: SimilarityType::Acoustic,
+                score_breakdown: None,
             })
             .collect())
     }
@@ -563,13 +695,18 @@ impl SimilarityService {
                     t.title,
                     a.name as artist_name,
                     al.title as album_title,
-                    -- Calculate normalized Euclidean distance
-                    SQRT(
-                        COALESCE(POWER((t.audio_features->>'energy')::float - src.energy, 2), 0) +
-                        COALESCE(POWER(((t.audio_features->>'loudness')::float + 60) / 60 - (src.loudness + 60) / 60, 2), 0) +
-                        COALESCE(POWER((t.audio_features->>'valence')::float - src.valence, 2), 0) +
-                        COALESCE(POWER((t.audio_features->>'danceability')::float - src.danceability, 2), 0) +
-                        COALESCE(POWER(((t.audio_features->>'bpm')::float - src.bpm) / 200, 2), 0)
+                    -- Normalized Euclidean distance over the dimensions both tracks
+                    -- have, scaled to the full five so tracks missing disabled
+                    -- analyzers aren't favored
+                    (
+                        SELECT SQRT(AVG(d) * 5)
+                        FROM (VALUES
+                            (POWER((t.audio_features->>'energy')::float - src.energy, 2)),
+                            (POWER(((t.audio_features->>'loudness')::float + 60) / 60 - (src.loudness + 60) / 60, 2)),
+                            (POWER((t.audio_features->>'valence')::float - src.valence, 2)),
+                            (POWER((t.audio_features->>'danceability')::float - src.danceability, 2)),
+                            (POWER(((t.audio_features->>'bpm')::float - src.bpm) / 200, 2))
+                        ) AS dims(d)
                     ) as distance
                 FROM tracks t
                 CROSS JOIN source_track src
@@ -608,6 +745,7 @@ impl SimilarityService {
                 album_title: r.album_title,
                 score: r.score.unwrap_or(0.0),
                 similarity_type: SimilarityType::Acoustic,
+                score_breakdown: None,
             })
             .collect())
     }
@@ -698,6 +836,7 @@ impl SimilarityService {
                 album_title: r.album_title,
                 score: r.score.unwrap_or(0.0),
                 similarity_type: SimilarityType::Categorical,
+                score_breakdown: None,
             })
             .collect())
     }
@@ -708,9 +847,12 @@ impl SimilarityService {
     /// A track appearing in only one dimension receives a proportionally lower score.
     ///
     /// Queries are executed in parallel using tokio::join! for improved latency (~50% reduction).
+    /// A dimension whose query fails (e.g. the track has no embedding) is
+    /// skipped and the other weights are scaled up to cover it; the score
+    /// breakdown's weights show which dimensions contributed.
     ///
     /// # Errors
-    /// - Returns an empty result if all similarity methods fail
+    /// - Returns the semantic lookup's error if all similarity methods fail
     #[instrument(skip(self), fields(similarity_type = "combined"))]
     pub async fn find_similar_combined(
         &self,
@@ -740,85 +882,82 @@ impl SimilarityService {
         .instrument(info_span!("parallel_similarity_queries"))
         .await;
 
-        // Process results, logging errors instead of silently discarding
-        let semantic = match semantic_result {
-            Ok(tracks) => Some(tracks),
-            Err(e) => {
-                warn!(
-                    track_id = %track_id,
-                    error = %e,
-                    "Semantic similarity lookup failed, continuing with other methods"
-                );
-                None
-            }
-        };
-
-        let acoustic = match acoustic_result {
-            Ok(tracks) => Some(tracks),
-            Err(e) => {
-                warn!(
-                    track_id = %track_id,
-                    error = %e,
-                    "Acoustic similarity lookup failed, continuing with other methods"
-                );
-                None
-            }
-        };
-
-        let categorical = match categorical_result {
-            Ok(tracks) => Some(tracks),
-            Err(e) => {
-                warn!(
-                    track_id = %track_id,
-                    error = %e,
-                    "Categorical similarity lookup failed, continuing with other methods"
-                );
-                None
-            }
-        };
+        combine_dimensions(
+            &self.config,
+            track_id,
+            semantic_result,
+            acoustic_result,
+            categorical_result,
+            limit as usize,
+        )
+    }
 
-        // Merge and weight results
-        let mut combined: HashMap<Uuid, (SimilarTrack, f64)> = HashMap::new();
+    /// Find similar tracks from the worker's precomputed neighbors
+    ///
+    /// Reads the per-dimension scores stored in `track_similarities` and
+    /// re-weights them with the current configuration. When the track has
+    /// fewer stored neighbors than `limit` (not yet computed, or invalidated
+    /// by a feature/embedding change), falls back to [`Self::find_similar_combined`].
+    ///
+    /// # Errors
+    /// - `ApiError::Database` - If the database query fails
+    #[instrument(skip(self), fields(similarity_type = "combined"))]
+    pub async fn find_precomputed(
+        &self,
+        track_id: Uuid,
+        limit: i32,
+    ) -> ApiResult<Vec<SimilarTrack>> {
+        let limit = validate_limit(limit);
 
-        // Helper to merge tracks into combined map
-        let merge_tracks = |map: &mut HashMap<Uuid, (SimilarTrack, f64)>,
-                            tracks: Vec<SimilarTrack>,
-                            weight: f64| {
-            for track in tracks {
-                let entry = map.entry(track.track_id).or_insert_with(|| {
-                    (
-                        SimilarTrack {
-                            track_id: track.track_id,
-                            title: track.title.clone(),
-                            artist_name: track.artist_name.clone(),
-                            album_title: track.album_title.clone(),
-                            score: 0.0,
-                            similarity_type: SimilarityType::Combined,
-                        },
-                        0.0,
-                    )
-                });
-                entry.1 += track.score * weight;
-            }
-        };
+        let rows: Vec<PrecomputedSimilarityRow> = sqlx::query_as(
+            r#"
+            SELECT
+                t.id as track_id,
+                t.title,
+                a.name as artist_name,
+                al.title as album_title,
+                ts.semantic_score,
+                ts.acoustic_score,
+                ts.categorical_score
+            FROM track_similarities ts
+            JOIN tracks t ON t.id = ts.similar_track_id
+            LEFT JOIN artists a ON t.artist_id = a.id
+            LEFT JOIN albums al ON t.album_id = al.id
+            WHERE ts.track_id = $1
+            "#,
+        )
+        .bind(track_id)
+        .fetch_all(&self.db)
+        .await
+        .map_err(|e| handle_query_error(e, "find_precomputed"))?;
 
-        // Apply weights from configuration
-        if let Some(tracks) = semantic {
-            merge_tracks(&mut combined, tracks, self.config.weight_semantic);
-        }
-        if let Some(tracks) = acoustic {
-            merge_tracks(&mut combined, tracks, self.config.weight_acoustic);
-        }
-        if let Some(tracks) = categorical {
-            merge_tracks(&mut combined, tracks, self.config.weight_categorical);
+        if rows.len() < limit as usize {
+            debug!(
+                track_id = %track_id,
+                stored = rows.len(),
+                "Precomputed similarities missing, computing live"
+            );
+            return self.find_similar_combined(track_id, limit).await;
         }
 
-        // Sort by combined score and take top N
-        let mut results: Vec<SimilarTrack> = combined
-            .into_values()
-            .map(|(mut track, score)| {
-                track.score = score;
-                track
+        let mut results: Vec<SimilarTrack> = rows
+            .into_iter()
+            .map(|r| {
+                let breakdown = ScoreBreakdown {
+                    semantic: r.semantic_score,
+                    acoustic: r.acoustic_score,
+                    categorical: r.categorical_score,
+                    ..ScoreBreakdown::with_weights(&self.config)
+                };
+                SimilarTrack {
+                    track_id: r.track_id,
+                    title: r.title,
+                    artist_name: r.artist_name,
+                    album_title: r.album_title,
+                    score: breakdown.combined_score(),
+                    similarity_type: SimilarityType::Combined,
+                    score_breakdown: Some(breakdown),
+                }
             })
             .collect();
 
@@ -831,6 +970,289 @@ impl SimilarityService {
 
         Ok(results)
     }
+
+    /// Find tracks similar to any of a set of seed tracks
+    ///
+    /// Each candidate's score is its best combined similarity to any seed.
+    /// Seeds themselves are never returned. Seeds whose lookup fails are
+    /// logged and skipped.
+    ///
+    /// # Errors
+    /// - Returns an empty result if every seed lookup fails
+    #[allow(dead_code)] // Used by blend_recommendations, not yet exposed over GraphQL
+    #[instrument(skip(self, seeds), fields(seeds = seeds.len()))]
+    pub async fn find_similar_for_seeds(
+        &self,
+        seeds: &[Uuid],
+        limit: i32,
+    ) -> ApiResult<Vec<SimilarTrack>> {
+        let limit = validate_limit(limit);
+        let seed_set: HashSet<Uuid> = seeds.iter().copied().collect();
+        let mut best: HashMap<Uuid, SimilarTrack> = HashMap::new();
+
+        for &seed_id in seeds {
+            let similar = match self.find_precomputed(seed_id, limit).await {
+                Ok(similar) => similar,
+                Err(e) => {
+                    warn!(
+                        seed_track_id = %seed_id,
+                        error = %e,
+                        "Failed to find similar tracks for seed, continuing with other seeds"
+                    );
+                    continue;
+                }
+            };
+
+            for track in similar {
+                if seed_set.contains(&track.track_id) {
+                    continue;
+                }
+                match best.get(&track.track_id) {
+                    Some(existing) if existing.score >= track.score => {}
+                    _ => {
+                        best.insert(track.track_id, track);
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<SimilarTrack> = best.into_values().collect();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit as usize);
+
+        Ok(results)
+    }
+
+    /// Recommend tracks that suit two listeners at once
+    ///
+    /// Seeds from each user's most played tracks of the last 90 days, finds
+    /// candidates for each with [`Self::find_similar_for_seeds`], and ranks
+    /// them by the harmonic mean of the two users' affinities, so tracks both
+    /// would like come before tracks only one would. Tracks either user played
+    /// in the last 7 days are excluded. If only one user has recent history,
+    /// the results are that user's recommendations (every score is 0.0 and
+    /// tracks are ordered by that user's affinity); if neither does, the
+    /// result is empty.
+    ///
+    /// # Errors
+    /// - `ApiError::Database` - If loading listening history fails
+    #[allow(dead_code)] // Not yet exposed over GraphQL
+    #[instrument(skip(self))]
+    pub async fn blend_recommendations(
+        &self,
+        user_a: Uuid,
+        user_b: Uuid,
+        limit: i32,
+    ) -> ApiResult<Vec<BlendedTrack>> {
+        let limit = validate_limit(limit);
+        let fetch_limit = validate_limit(limit * 3);
+
+        let seeds_a = self.blend_seeds(user_a).await?;
+        let seeds_b = self.blend_seeds(user_b).await?;
+        if seeds_a.is_empty() && seeds_b.is_empty() {
+            debug!("Neither user has recent listening history, nothing to blend");
+            return Ok(Vec::new());
+        }
+
+        let excluded: HashSet<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT DISTINCT track_id
+            FROM listening_history
+            WHERE user_id = ANY($1)
+              AND played_at > NOW() - make_interval(days => $2)
+            "#,
+        )
+        .bind([user_a, user_b])
+        .bind(BLEND_RECENT_EXCLUSION_DAYS)
+        .fetch_all(&self.db)
+        .await?
+        .into_iter()
+        .collect();
+
+        let (candidates_a, candidates_b) = tokio::join!(
+            self.find_similar_for_seeds(&seeds_a, fetch_limit),
+            self.find_similar_for_seeds(&seeds_b, fetch_limit),
+        );
+
+        Ok(blend_candidates(
+            candidates_a?,
+            candidates_b?,
+            &excluded,
+            limit as usize,
+        ))
+    }
+
+    /// A user's most played tracks within the blend seed window
+    async fn blend_seeds(&self, user_id: Uuid) -> ApiResult<Vec<Uuid>> {
+        Ok(sqlx::query_scalar(
+            r#"
+            SELECT track_id
+            FROM listening_history
+            WHERE user_id = $1
+              AND played_at > NOW() - make_interval(days => $2)
+            GROUP BY track_id
+            ORDER BY COUNT(*) DESC, MAX(played_at) DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(BLEND_SEED_WINDOW_DAYS)
+        .bind(BLEND_SEED_TRACKS)
+        .fetch_all(&self.db)
+        .await?)
+    }
+}
+
+/// Merge per-dimension lookups into combined results
+///
+/// A failed dimension is logged and left out, and the remaining weights are
+/// scaled up to make up for it. Only fails if every dimension failed, with
+/// the semantic lookup's error.
+fn combine_dimensions(
+    config: &SimilarityConfig,
+    track_id: Uuid,
+    semantic: ApiResult<Vec<SimilarTrack>>,
+    acoustic: ApiResult<Vec<SimilarTrack>>,
+    categorical: ApiResult<Vec<SimilarTrack>>,
+    limit: usize,
+) -> ApiResult<Vec<SimilarTrack>> {
+    let mut first_error = None;
+    let mut dimension = |name: &str, result: ApiResult<Vec<SimilarTrack>>| match result {
+        Ok(tracks) => Some(tracks),
+        Err(e) => {
+            warn!(
+                track_id = %track_id,
+                dimension = name,
+                error = %e,
+                "Similarity lookup failed, continuing with other dimensions"
+            );
+            first_error.get_or_insert(e);
+            None
+        }
+    };
+
+    let semantic = dimension("semantic", semantic);
+    let acoustic = dimension("acoustic", acoustic);
+    let categorical = dimension("categorical", categorical);
+
+    if semantic.is_none() && acoustic.is_none() && categorical.is_none() {
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+    }
+
+    Ok(merge_combined(
+        config,
+        semantic,
+        acoustic,
+        categorical,
+        limit,
+    ))
+}
+
+/// Weights to use when only some dimensions are available
+///
+/// Unavailable dimensions get zero weight and the rest are scaled so the
+/// weights keep their total. If every available dimension has zero weight
+/// there is nothing to scale, and they stay at zero.
+fn available_weights(
+    config: &SimilarityConfig,
+    semantic: bool,
+    acoustic: bool,
+    categorical: bool,
+) -> SimilarityConfig {
+    let pick = |available: bool, weight: f64| if available { weight } else { 0.0 };
+    let total = config.weight_semantic + config.weight_acoustic + config.weight_categorical;
+    let weights = SimilarityConfig {
+        weight_semantic: pick(semantic, config.weight_semantic),
+        weight_acoustic: pick(acoustic, config.weight_acoustic),
+        weight_categorical: pick(categorical, config.weight_categorical),
+    };
+    let available = weights.weight_semantic + weights.weight_acoustic + weights.weight_categorical;
+    if available <= 0.0 {
+        return weights;
+    }
+
+    let scale = total / available;
+    SimilarityConfig {
+        weight_semantic: weights.weight_semantic * scale,
+        weight_acoustic: weights.weight_acoustic * scale,
+        weight_categorical: weights.weight_categorical * scale,
+    }
+}
+
+/// Merge per-dimension results into weighted combined results
+///
+/// Each track's combined score is the weighted sum of its dimension scores,
+/// recorded in its `score_breakdown`. Returns the top `limit` tracks by
+/// combined score.
+fn merge_combined(
+    config: &SimilarityConfig,
+    semantic: Option<Vec<SimilarTrack>>,
+    acoustic: Option<Vec<SimilarTrack>>,
+    categorical: Option<Vec<SimilarTrack>>,
+    limit: usize,
+) -> Vec<SimilarTrack> {
+    let weights = available_weights(
+        config,
+        semantic.is_some(),
+        acoustic.is_some(),
+        categorical.is_some(),
+    );
+    let (semantic, acoustic, categorical) = (
+        semantic.unwrap_or_default(),
+        acoustic.unwrap_or_default(),
+        categorical.unwrap_or_default(),
+    );
+    let mut combined: HashMap<Uuid, SimilarTrack> = HashMap::new();
+
+    let mut merge = |tracks: Vec<SimilarTrack>, set: fn(&mut ScoreBreakdown, f64)| {
+        for track in tracks {
+            let entry = combined
+                .entry(track.track_id)
+                .or_insert_with(|| SimilarTrack {
+                    track_id: track.track_id,
+                    title: track.title.clone(),
+                    artist_name: track.artist_name.clone(),
+                    album_title: track.album_title.clone(),
+                    score: 0.0,
+                    similarity_type: SimilarityType::Combined,
+                    score_breakdown: Some(ScoreBreakdown::with_weights(&weights)),
+                });
+            if let Some(breakdown) = entry.score_breakdown.as_mut() {
+                set(breakdown, track.score);
+            }
+        }
+    };
+
+    merge(semantic, |b, score| b.semantic = score);
+    merge(acoustic, |b, score| b.acoustic = score);
+    merge(categorical, |b, score| b.categorical = score);
+
+    // Sort by combined score and take top N
+    let mut results: Vec<SimilarTrack> = combined
+        .into_values()
+        .map(|mut track| {
+            track.score = track
+                .score_breakdown
+                .as_ref()
+                .map_or(0.0, ScoreBreakdown::combined_score);
+            track
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    results.truncate(limit);
+
+    results
 }
 
 /// Row struct for sqlx queries
@@ -843,6 +1265,102 @@ struct SimilarTrackRow {
     score: Option<f64>,
 }
 
+/// A track recommended for two listeners at once
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlendedTrack {
+    pub track_id: Uuid,
+    pub title: String,
+    pub artist_name: Option<String>,
+    pub album_title: Option<String>,
+    /// Harmonic mean of the two affinities (0.0 if either is 0.0)
+    pub score: f64,
+    /// Similarity to the first user's seed tracks
+    pub affinity_a: f64,
+    /// Similarity to the second user's seed tracks
+    pub affinity_b: f64,
+}
+
+/// Harmonic mean of two affinities, 0.0 when either is 0.0
+fn harmonic_mean(a: f64, b: f64) -> f64 {
+    if a <= 0.0 || b <= 0.0 {
+        0.0
+    } else {
+        2.0 * a * b / (a + b)
+    }
+}
+
+/// Combine two users' candidate affinities into blended recommendations
+///
+/// Tracks are ranked by the harmonic mean of both affinities, so a track
+/// only one user would like ranks below every track both would like. Among
+/// those one-sided tracks, the stronger affinity ranks first. Tracks in
+/// `excluded` are dropped.
+fn blend_candidates(
+    candidates_a: Vec<SimilarTrack>,
+    candidates_b: Vec<SimilarTrack>,
+    excluded: &HashSet<Uuid>,
+    limit: usize,
+) -> Vec<BlendedTrack> {
+    let mut blended: HashMap<Uuid, BlendedTrack> = HashMap::new();
+
+    let mut merge = |tracks: Vec<SimilarTrack>, set: fn(&mut BlendedTrack, f64)| {
+        for track in tracks {
+            if excluded.contains(&track.track_id) {
+                continue;
+            }
+            let score = track.score;
+            let entry = blended
+                .entry(track.track_id)
+                .or_insert_with(|| BlendedTrack {
+                    track_id: track.track_id,
+                    title: track.title,
+                    artist_name: track.artist_name,
+                    album_title: track.album_title,
+                    score: 0.0,
+                    affinity_a: 0.0,
+                    affinity_b: 0.0,
+                });
+            set(entry, score);
+        }
+    };
+    merge(candidates_a, |t, score| t.affinity_a = score);
+    merge(candidates_b, |t, score| t.affinity_b = score);
+
+    let mut results: Vec<BlendedTrack> = blended
+        .into_values()
+        .map(|mut track| {
+            track.score = harmonic_mean(track.affinity_a, track.affinity_b);
+            track
+        })
+        .collect();
+
+    results.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| {
+                b.affinity_a
+                    .max(b.affinity_b)
+                    .partial_cmp(&a.affinity_a.max(a.affinity_b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    });
+    results.truncate(limit);
+    results
+}
+
+/// Row struct for precomputed neighbors
+#[derive(Debug, sqlx::FromRow)]
+struct PrecomputedSimilarityRow {
+    track_id: Uuid,
+    title: String,
+    artist_name: Option<String>,
+    album_title: Option<String>,
+    semantic_score: f64,
+    acoustic_score: f64,
+    categorical_score: f64,
+}
+
 // =============================================================================
 // Redis Caching Layer
 // =============================================================================
@@ -857,6 +1375,8 @@ const DEFAULT_CACHE_TTL_SECONDS: u64 = 600;
 pub struct SimilarityCacheConfig {
     /// Time-to-live for cached similarity results in seconds
     pub ttl_seconds: u64,
+    /// Random spread applied to the TTL, in percent either way
+    pub jitter_pct: f32,
     /// Whether caching is enabled
     pub enabled: bool,
 }
@@ -865,6 +1385,7 @@ impl Default for SimilarityCacheConfig {
     fn default() -> Self {
         Self {
             ttl_seconds: DEFAULT_CACHE_TTL_SECONDS,
+            jitter_pct: DEFAULT_TTL_JITTER_PCT,
             enabled: true,
         }
     }
@@ -876,6 +1397,7 @@ impl SimilarityCacheConfig {
     pub fn with_ttl(ttl_seconds: u64) -> Self {
         Self {
             ttl_seconds,
+            jitter_pct: DEFAULT_TTL_JITTER_PCT,
             enabled: true,
         }
     }
@@ -884,6 +1406,7 @@ impl SimilarityCacheConfig {
     pub fn disabled() -> Self {
         Self {
             ttl_seconds: 0,
+            jitter_pct: DEFAULT_TTL_JITTER_PCT,
             enabled: false,
         }
     }
@@ -892,6 +1415,7 @@ impl SimilarityCacheConfig {
     ///
     /// Environment variables:
     /// - `SIMILARITY_CACHE_TTL_SECONDS` (default: 600)
+    /// - `CACHE_TTL_JITTER_PCT` (default: 10)
     /// - `SIMILARITY_CACHE_ENABLED` (default: true)
     pub fn from_env() -> Self {
         let ttl_seconds = env::var("SIMILARITY_CACHE_TTL_SECONDS")
@@ -899,12 +1423,18 @@ impl SimilarityCacheConfig {
             .and_then(|v| v.parse().ok())
             .unwrap_or(DEFAULT_CACHE_TTL_SECONDS);
 
+        let jitter_pct = env::var("CACHE_TTL_JITTER_PCT")
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_TTL_JITTER_PCT);
+
         let enabled = env::var("SIMILARITY_CACHE_ENABLED")
             .map(|v| !v.eq_ignore_ascii_case("false") && v != "0")
             .unwrap_or(true);
 
         Self {
             ttl_seconds,
+            jitter_pct,
             enabled,
         }
     }
@@ -1025,16 +1555,23 @@ impl CachedSimilarityService {
             }
         };
 
+        // Jitter the TTL so results cached together don't all expire together
+        let ttl_seconds = ttl_with_jitter(
+            Duration::from_secs(self.config.ttl_seconds),
+            self.config.jitter_pct,
+        )
+        .as_secs()
+        .max(1);
+
         // Use SETEX for atomic set-with-expiry
-        let result: Result<(), redis::RedisError> =
-            conn.set_ex(key, &json, self.config.ttl_seconds).await;
+        let result: Result<(), redis::RedisError> = conn.set_ex(key, &json, ttl_seconds).await;
 
         match result {
             Ok(()) => {
                 debug!(
                     key = %key,
                     count = tracks.len(),
-                    ttl_seconds = self.config.ttl_seconds,
+                    ttl_seconds,
                     "Cached similarity results"
                 );
             }
@@ -1367,6 +1904,31 @@ mod tests {
         assert!((config.weight_categorical - 0.2).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn test_similarity_config_from_user_weights() {
+        let config = SimilarityConfig::from_weights(&SimilarityWeights {
+            semantic: 1.0,
+            acoustic: 1.0,
+            categorical: 2.0,
+        })
+        .unwrap();
+        assert!((config.weight_semantic - 0.25).abs() < f64::EPSILON);
+        assert!((config.weight_acoustic - 0.25).abs() < f64::EPSILON);
+        assert!((config.weight_categorical - 0.5).abs() < f64::EPSILON);
+        assert!(config.validate().is_ok());
+
+        for (semantic, acoustic, categorical) in
+            [(0.0, 0.0, 0.0), (-0.1, 0.5, 0.6), (f64::NAN, 0.5, 0.5)]
+        {
+            let weights = SimilarityWeights {
+                semantic,
+                acoustic,
+                categorical,
+            };
+            assert!(SimilarityConfig::from_weights(&weights).is_none());
+        }
+    }
+
     #[test]
     fn test_similarity_config_new_valid() {
         let config = SimilarityConfig::new(0.4, 0.4, 0.2).unwrap();
@@ -1617,6 +2179,7 @@ mod tests {
             album_title: Some("Test Album".to_string()),
             score: 0.95,
             similarity_type: SimilarityType::Semantic,
+            score_breakdown: None,
         };
 
         let tracks = vec![track.clone()];
@@ -1645,6 +2208,7 @@ mod tests {
             album_title: None,
             score: 0.75,
             similarity_type: SimilarityType::Acoustic,
+            score_breakdown: None,
         };
 
         let tracks = vec![track];
@@ -1674,4 +2238,215 @@ mod tests {
             assert_eq!(deserialized, sim_type);
         }
     }
+
+    fn similar(track_id: Uuid, score: f64, similarity_type: SimilarityType) -> SimilarTrack {
+        SimilarTrack {
+            track_id,
+            title: format!("Track {}", track_id),
+            artist_name: None,
+            album_title: None,
+            score,
+            similarity_type,
+            score_breakdown: None,
+        }
+    }
+
+    #[test]
+    fn test_harmonic_mean() {
+        assert_eq!(harmonic_mean(0.5, 0.5), 0.5);
+        assert_eq!(harmonic_mean(0.9, 0.0), 0.0);
+        assert!((harmonic_mean(0.2, 0.6) - 0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_blend_prefers_mutual_fit_and_skips_excluded() {
+        let (mutual, one_sided, heard) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let candidates_a = vec![
+            similar(one_sided, 0.95, SimilarityType::Combined),
+            similar(mutual, 0.4, SimilarityType::Combined),
+            similar(heard, 0.9, SimilarityType::Combined),
+        ];
+        let candidates_b = vec![
+            similar(mutual, 0.5, SimilarityType::Combined),
+            similar(heard, 0.9, SimilarityType::Combined),
+        ];
+
+        let blended = blend_candidates(candidates_a, candidates_b, &HashSet::from([heard]), 10);
+
+        let ids: Vec<Uuid> = blended.iter().map(|t| t.track_id).collect();
+        assert_eq!(ids, vec![mutual, one_sided]);
+        assert!((blended[0].score - harmonic_mean(0.4, 0.5)).abs() < 1e-9);
+        assert_eq!(blended[1].score, 0.0);
+        assert_eq!(blended[1].affinity_a, 0.95);
+    }
+
+    #[test]
+    fn test_combined_breakdown_sums_to_score() {
+        let config = SimilarityConfig::new(0.5, 0.3, 0.2).unwrap();
+        let both = Uuid::new_v4();
+        let acoustic_only = Uuid::new_v4();
+
+        let results = merge_combined(
+            &config,
+            Some(vec![similar(both, 0.9, SimilarityType::Semantic)]),
+            Some(vec![
+                similar(both, 0.6, SimilarityType::Acoustic),
+                similar(acoustic_only, 0.8, SimilarityType::Acoustic),
+            ]),
+            Some(vec![similar(both, 0.5, SimilarityType::Categorical)]),
+            10,
+        );
+
+        assert_eq!(results.len(), 2);
+        for track in &results {
+            assert_eq!(track.similarity_type, SimilarityType::Combined);
+            let b = track
+                .score_breakdown
+                .expect("combined results have a breakdown");
+            let weighted = b.semantic * b.weight_semantic
+                + b.acoustic * b.weight_acoustic
+                + b.categorical * b.weight_categorical;
+            assert!((weighted - track.score).abs() < 1e-9);
+            assert!((b.weight_semantic - 0.5).abs() < f64::EPSILON);
+            assert!((b.weight_acoustic - 0.3).abs() < f64::EPSILON);
+            assert!((b.weight_categorical - 0.2).abs() < f64::EPSILON);
+        }
+
+        // 0.9 * 0.5 + 0.6 * 0.3 + 0.5 * 0.2
+        assert_eq!(results[0].track_id, both);
+        assert!((results[0].score - 0.73).abs() < 1e-9);
+
+        // Missing dimensions contribute zero
+        let b = results[1].score_breakdown.unwrap();
+        assert_eq!(results[1].track_id, acoustic_only);
+        assert!((b.semantic).abs() < f64::EPSILON);
+        assert!((b.categorical).abs() < f64::EPSILON);
+        assert!((results[1].score - 0.24).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_combined_survives_embedding_failure() {
+        let config = SimilarityConfig::new(0.5, 0.3, 0.2).unwrap();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let results = combine_dimensions(
+            &config,
+            Uuid::new_v4(),
+            Err(ApiError::SemanticSearchUnavailable),
+            Ok(vec![
+                similar(a, 0.8, SimilarityType::Acoustic),
+                similar(b, 0.4, SimilarityType::Acoustic),
+            ]),
+            Ok(vec![similar(b, 0.9, SimilarityType::Categorical)]),
+            10,
+        )
+        .expect("acoustic and categorical results still come back");
+
+        assert_eq!(results.len(), 2);
+        let breakdown = results[0].score_breakdown.unwrap();
+        assert_eq!(
+            breakdown.contributing_dimensions(),
+            vec![SimilarityType::Acoustic, SimilarityType::Categorical]
+        );
+        // 0.3 and 0.2 scaled up to 0.6 and 0.4
+        assert!(breakdown.weight_semantic.abs() < f64::EPSILON);
+        assert!((breakdown.weight_acoustic - 0.6).abs() < 1e-9);
+        assert!((breakdown.weight_categorical - 0.4).abs() < 1e-9);
+
+        // b: 0.4 * 0.6 + 0.9 * 0.4, a: 0.8 * 0.6
+        assert_eq!(results[0].track_id, b);
+        assert!((results[0].score - 0.6).abs() < 1e-9);
+        assert_eq!(results[1].track_id, a);
+        assert!((results[1].score - 0.48).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_combined_fails_only_when_every_dimension_fails() {
+        let config = SimilarityConfig::default();
+
+        let result = combine_dimensions(
+            &config,
+            Uuid::new_v4(),
+            Err(ApiError::SemanticSearchUnavailable),
+            Err(ApiError::DatabaseUnavailable),
+            Err(ApiError::DatabaseUnavailable),
+            10,
+        );
+        assert!(matches!(result, Err(ApiError::SemanticSearchUnavailable)));
+
+        // An empty success is still a success
+        let result = combine_dimensions(
+            &config,
+            Uuid::new_v4(),
+            Err(ApiError::SemanticSearchUnavailable),
+            Err(ApiError::DatabaseUnavailable),
+            Ok(Vec::new()),
+            10,
+        );
+        assert!(result.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_combined_merge_respects_limit() {
+        let config = SimilarityConfig::default();
+        let semantic: Vec<SimilarTrack> = (0..5)
+            .map(|i| similar(Uuid::new_v4(), 0.1 * i as f64, SimilarityType::Semantic))
+            .collect();
+
+        let results = merge_combined(&config, Some(semantic), Some(Vec::new()), None, 3);
+
+        assert_eq!(results.len(), 3);
+        assert!(results.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+
+    #[test]
+    fn test_categorical_heavy_user_weights_favor_tag_overlap() {
+        let (close_embedding, shared_tags) = (Uuid::new_v4(), Uuid::new_v4());
+        let rank = |config: &SimilarityConfig| {
+            merge_combined(
+                config,
+                Some(vec![
+                    similar(close_embedding, 0.9, SimilarityType::Semantic),
+                    similar(shared_tags, 0.4, SimilarityType::Semantic),
+                ]),
+                Some(vec![
+                    similar(close_embedding, 0.5, SimilarityType::Acoustic),
+                    similar(shared_tags, 0.5, SimilarityType::Acoustic),
+                ]),
+                Some(vec![
+                    similar(close_embedding, 0.1, SimilarityType::Categorical),
+                    similar(shared_tags, 0.9, SimilarityType::Categorical),
+                ]),
+                10,
+            )
+            .into_iter()
+            .map(|t| t.track_id)
+            .collect::<Vec<_>>()
+        };
+
+        let user_config = SimilarityConfig::from_weights(&SimilarityWeights {
+            semantic: 1.0,
+            acoustic: 1.0,
+            categorical: 6.0,
+        })
+        .unwrap();
+
+        assert_eq!(
+            rank(&SimilarityConfig::default()),
+            vec![close_embedding, shared_tags]
+        );
+        assert_eq!(rank(&user_config), vec![shared_tags, close_embedding]);
+    }
+
+    #[test]
+    fn test_breakdown_omitted_from_cache_when_absent() {
+        let track = similar(Uuid::nil(), 0.5, SimilarityType::Semantic);
+
+        let json = serde_json::to_string(&track).unwrap();
+        assert!(!json.contains("score_breakdown"));
+
+        // Entries cached before the breakdown existed still deserialize
+        let deserialized: SimilarTrack = serde_json::from_str(&json).unwrap();
+        assert!(deserialized.score_breakdown.is_none());
+    }
 }
@@ -0,0 +1,863 @@
+//! Disk-backed cache for transcoded audio
+//!
+//! Stores completed transcoder output on disk keyed by track, format,
+//! bitrate and compression level so popular tracks are only transcoded once. The cache is bounded
+//! by total size and evicts the least recently used entries once the budget
+//! is exceeded.
+//!
+//! Keys also carry the source file's size and mtime, so a file replaced on
+//! disk (e.g. retagged or re-ripped, then rescanned under the same track ID)
+//! is transcoded again instead of served stale. Outputs of the old file are
+//! never requested again and age out through normal eviction.
+//!
+//! # Concurrency
+//!
+//! Concurrent requests for the same uncached key share a single fill: the
+//! first caller gets a [`CacheFill`] and writes the output to a partial file,
+//! while it and any other callers read that file as it grows through a
+//! [`FillFollower`], so nobody waits for the whole transcode. The partial
+//! file is moved into the cache once the fill is committed, or deleted if
+//! the fill is dropped, after which the next request starts a new one.
+
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
+use uuid::Uuid;
+
+use super::transcoder::{TranscodeError, TranscodeFormat};
+
+/// Extension used for in-progress outputs that have not been committed yet
+const PARTIAL_EXTENSION: &str = "partial";
+
+/// Cache key identifying a single transcoded output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TranscodeCacheKey {
+    pub track_id: Uuid,
+    /// Size of the source file in bytes
+    pub source_len: u64,
+    /// Modification time of the source file, in seconds since the epoch
+    pub source_mtime: u64,
+    pub format: TranscodeFormat,
+    pub bitrate: u32,
+    /// FLAC compression level; `None` for FFmpeg's default
+    pub compression_level: Option<u8>,
+}
+
+impl TranscodeCacheKey {
+    /// Create a new cache key
+    pub fn new(track_id: Uuid, format: TranscodeFormat, bitrate: u32) -> Self {
+        Self {
+            track_id,
+            source_len: 0,
+            source_mtime: 0,
+            format,
+            bitrate,
+            compression_level: None,
+        }
+    }
+
+    /// Key for output transcoded from a source file with this size and mtime
+    pub fn with_source(mut self, source_len: u64, modified: SystemTime) -> Self {
+        self.source_len = source_len;
+        self.source_mtime = modified
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        self
+    }
+
+    /// Key for output encoded at a specific compression level
+    pub fn with_compression_level(mut self, compression_level: Option<u8>) -> Self {
+        self.compression_level = compression_level;
+        self
+    }
+
+    /// File name used to store this entry:
+    /// `{track_id}_{source_len}_{source_mtime}_{bitrate}.{ext}`, with a
+    /// `_c{level}` suffix before the extension for a compression level
+    fn file_name(&self) -> String {
+        let level = self
+            .compression_level
+            .map(|level| format!("_c{}", level))
+            .unwrap_or_default();
+        format!(
+            "{}_{}_{}_{}{}.{}",
+            self.track_id,
+            self.source_len,
+            self.source_mtime,
+            self.bitrate,
+            level,
+            self.format.extension()
+        )
+    }
+
+    /// Parse a cache key back out of a file name produced by `file_name()`
+    fn from_file_name(name: &str) -> Option<Self> {
+        let (stem, ext) = name.rsplit_once('.')?;
+        let mut parts = stem.splitn(4, '_');
+        let (track_id, source_len, source_mtime, rest) =
+            (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+        let (bitrate, compression_level) = match rest.split_once("_c") {
+            Some((bitrate, level)) => (bitrate, Some(level.parse().ok()?)),
+            None => (rest, None),
+        };
+        Some(Self {
+            track_id: Uuid::parse_str(track_id).ok()?,
+            source_len: source_len.parse().ok()?,
+            source_mtime: source_mtime.parse().ok()?,
+            format: TranscodeFormat::parse(ext)?,
+            bitrate: bitrate.parse().ok()?,
+            compression_level,
+        })
+    }
+}
+
+/// Whether `name` looks like a cache entry (`{track_id}_...`) written under
+/// an older key format
+fn is_outdated_entry(name: &str) -> bool {
+    name.split_once('_')
+        .is_some_and(|(track_id, _)| Uuid::parse_str(track_id).is_ok())
+}
+
+/// A completed transcode stored in the cache
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedTranscode {
+    /// Path to the transcoded file on disk
+    pub path: PathBuf,
+    /// Size of the transcoded file in bytes
+    pub size: u64,
+}
+
+/// How far an in-flight fill has got
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillProgress {
+    /// This many bytes have been written to the partial file
+    Writing(u64),
+    /// The output is complete and stored in the cache
+    Done(u64),
+    /// The fill was abandoned and its partial file deleted
+    Failed,
+}
+
+/// Result of looking up a key with [`TranscodeCache::lookup`]
+#[derive(Debug)]
+pub enum CacheLookup {
+    /// The output is cached
+    Hit(CachedTranscode),
+    /// Another request is producing the output; read it as it is written
+    Filling(FillFollower),
+    /// Nobody is producing the output; the caller should, through this fill
+    Miss(CacheFill),
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    size: u64,
+    /// Logical access time used for LRU ordering
+    last_access: u64,
+}
+
+/// In-memory index of the files stored on disk
+#[derive(Debug, Default)]
+struct CacheIndex {
+    entries: HashMap<TranscodeCacheKey, CacheEntry>,
+    total_bytes: u64,
+    clock: u64,
+}
+
+impl CacheIndex {
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn touch(&mut self, key: &TranscodeCacheKey) -> Option<u64> {
+        let now = self.tick();
+        let entry = self.entries.get_mut(key)?;
+        entry.last_access = now;
+        Some(entry.size)
+    }
+
+    fn insert(&mut self, key: TranscodeCacheKey, size: u64) {
+        let last_access = self.tick();
+        if let Some(old) = self.entries.insert(key, CacheEntry { size, last_access }) {
+            self.total_bytes = self.total_bytes.saturating_sub(old.size);
+        }
+        self.total_bytes += size;
+    }
+
+    fn remove(&mut self, key: &TranscodeCacheKey) {
+        if let Some(old) = self.entries.remove(key) {
+            self.total_bytes = self.total_bytes.saturating_sub(old.size);
+        }
+    }
+
+    /// Remove least recently used entries until the index fits in `max_bytes`
+    ///
+    /// `keep` is never evicted so a freshly inserted entry can still be served
+    /// even if it alone exceeds the budget.
+    fn evict(
+        &mut self,
+        max_bytes: u64,
+        keep: Option<&TranscodeCacheKey>,
+    ) -> Vec<TranscodeCacheKey> {
+        let mut evicted = Vec::new();
+        while self.total_bytes > max_bytes {
+            let victim = self
+                .entries
+                .iter()
+                .filter(|(key, _)| Some(*key) != keep)
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(key, _)| *key);
+
+            match victim {
+                Some(key) => {
+                    self.remove(&key);
+                    evicted.push(key);
+                }
+                None => break,
+            }
+        }
+        evicted
+    }
+}
+
+struct CacheInner {
+    dir: PathBuf,
+    max_bytes: u64,
+    index: Mutex<CacheIndex>,
+    /// Fills currently running, for single-flight transcoding
+    in_flight: Mutex<HashMap<TranscodeCacheKey, InFlight>>,
+}
+
+/// A fill other requests can follow
+struct InFlight {
+    partial: PathBuf,
+    progress: watch::Receiver<FillProgress>,
+}
+
+/// Size-bounded LRU cache of transcoded audio files
+#[derive(Clone)]
+pub struct TranscodeCache {
+    inner: Arc<CacheInner>,
+}
+
+impl std::fmt::Debug for TranscodeCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscodeCache")
+            .field("dir", &self.inner.dir)
+            .field("max_bytes", &self.inner.max_bytes)
+            .field("total_bytes", &self.total_bytes())
+            .finish()
+    }
+}
+
+impl TranscodeCache {
+    /// Open (or create) a transcode cache in `dir` bounded to `max_bytes`
+    ///
+    /// Existing cache files are re-indexed using their modification time as
+    /// the initial LRU order. Leftover partial outputs from an interrupted
+    /// run are removed, as are entries named by an older key format, which
+    /// would never be looked up or evicted.
+    pub fn new(dir: impl Into<PathBuf>, max_bytes: u64) -> Result<Self, TranscodeError> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut existing = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            if path.extension().and_then(|e| e.to_str()) == Some(PARTIAL_EXTENSION) {
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            match TranscodeCacheKey::from_file_name(name) {
+                Some(key) => {
+                    let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    existing.push((modified, key, metadata.len()));
+                }
+                None if is_outdated_entry(name) => {
+                    let _ = std::fs::remove_file(&path);
+                }
+                None => {}
+            }
+        }
+
+        existing.sort_by_key(|(modified, _, _)| *modified);
+        let mut index = CacheIndex::default();
+        for (_, key, size) in existing {
+            index.insert(key, size);
+        }
+        for key in index.evict(max_bytes, None) {
+            let _ = std::fs::remove_file(dir.join(key.file_name()));
+        }
+
+        tracing::info!(
+            dir = %dir.display(),
+            entries = index.entries.len(),
+            total_bytes = index.total_bytes,
+            max_bytes,
+            "Transcode cache initialized"
+        );
+
+        Ok(Self {
+            inner: Arc::new(CacheInner {
+                dir,
+                max_bytes,
+                index: Mutex::new(index),
+                in_flight: Mutex::new(HashMap::new()),
+            }),
+        })
+    }
+
+    /// Directory the cache stores files in
+    pub fn dir(&self) -> &Path {
+        &self.inner.dir
+    }
+
+    /// Maximum total size of cached files in bytes
+    pub fn max_bytes(&self) -> u64 {
+        self.inner.max_bytes
+    }
+
+    /// Current total size of cached files in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.lock_index().total_bytes
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.lock_index().entries.len()
+    }
+
+    /// Whether the cache has no entries
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Look up a cached entry, marking it as recently used
+    ///
+    /// Entries whose backing file has been removed externally are dropped
+    /// from the index and reported as a miss.
+    pub async fn get(&self, key: &TranscodeCacheKey) -> Option<CachedTranscode> {
+        let size = self.lock_index().touch(key)?;
+        let path = self.path_for(key);
+
+        if tokio::fs::metadata(&path).await.is_err() {
+            tracing::warn!(path = %path.display(), "Cached transcode missing on disk");
+            self.lock_index().remove(key);
+            return None;
+        }
+
+        Some(CachedTranscode { path, size })
+    }
+
+    /// Look up `key`, joining or starting a fill on a miss
+    ///
+    /// Only one fill runs per key at a time. The caller that gets
+    /// [`CacheLookup::Miss`] must write the output through the returned
+    /// [`CacheFill`] and commit it; callers that arrive meanwhile get a
+    /// [`FillFollower`] for the same output.
+    pub async fn lookup(&self, key: TranscodeCacheKey) -> Result<CacheLookup, TranscodeError> {
+        if let Some(hit) = self.get(&key).await {
+            tracing::debug!(?key, "Transcode cache hit");
+            return Ok(CacheLookup::Hit(hit));
+        }
+        if let Some(follower) = self.follow(&key) {
+            tracing::debug!(?key, "Following in-flight transcode");
+            return Ok(CacheLookup::Filling(follower));
+        }
+
+        let partial = self.inner.dir.join(format!(
+            "{}.{}.{}",
+            key.file_name(),
+            Uuid::new_v4(),
+            PARTIAL_EXTENSION
+        ));
+        let file = tokio::fs::File::create(&partial).await?;
+        let (progress, receiver) = watch::channel(FillProgress::Writing(0));
+
+        // Another request may have started or finished a fill meanwhile
+        let raced = {
+            let mut in_flight = self.lock_in_flight();
+            if self.lock_index().entries.contains_key(&key) {
+                Some(None)
+            } else {
+                match in_flight.entry(key) {
+                    Entry::Occupied(entry) => Some(Some(self.follower(&key, entry.get()))),
+                    Entry::Vacant(entry) => {
+                        entry.insert(InFlight {
+                            partial: partial.clone(),
+                            progress: receiver,
+                        });
+                        None
+                    }
+                }
+            }
+        };
+        match raced {
+            Some(follower) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                match follower {
+                    Some(follower) => Ok(CacheLookup::Filling(follower)),
+                    None => Box::pin(self.lookup(key)).await,
+                }
+            }
+            None => {
+                tracing::debug!(?key, "Transcode cache miss");
+                Ok(CacheLookup::Miss(CacheFill {
+                    cache: self.clone(),
+                    key,
+                    partial,
+                    file,
+                    written: 0,
+                    progress,
+                    committed: false,
+                }))
+            }
+        }
+    }
+
+    /// Follow the fill running for `key`, if any
+    fn follow(&self, key: &TranscodeCacheKey) -> Option<FillFollower> {
+        let in_flight = self.lock_in_flight();
+        in_flight
+            .get(key)
+            .map(|running| self.follower(key, running))
+    }
+
+    fn follower(&self, key: &TranscodeCacheKey, running: &InFlight) -> FillFollower {
+        FillFollower {
+            partial: running.partial.clone(),
+            path: self.path_for(key),
+            progress: running.progress.clone(),
+        }
+    }
+
+    fn path_for(&self, key: &TranscodeCacheKey) -> PathBuf {
+        self.inner.dir.join(key.file_name())
+    }
+
+    fn lock_index(&self) -> std::sync::MutexGuard<'_, CacheIndex> {
+        self.inner
+            .index
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_in_flight(&self) -> std::sync::MutexGuard<'_, HashMap<TranscodeCacheKey, InFlight>> {
+        self.inner
+            .in_flight
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// The single producer of a cache entry
+///
+/// Output written here is visible to followers straight away. The partial
+/// file is moved into the cache by [`CacheFill::commit`]; dropping the fill
+/// instead deletes it and tells followers the output is incomplete.
+pub struct CacheFill {
+    cache: TranscodeCache,
+    key: TranscodeCacheKey,
+    partial: PathBuf,
+    file: tokio::fs::File,
+    written: u64,
+    progress: watch::Sender<FillProgress>,
+    committed: bool,
+}
+
+impl std::fmt::Debug for CacheFill {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheFill")
+            .field("key", &self.key)
+            .field("partial", &self.partial)
+            .field("written", &self.written)
+            .finish()
+    }
+}
+
+impl CacheFill {
+    /// Read the output as it is written
+    pub fn follow(&self) -> FillFollower {
+        FillFollower {
+            partial: self.partial.clone(),
+            path: self.cache.path_for(&self.key),
+            progress: self.progress.subscribe(),
+        }
+    }
+
+    /// Append a chunk of output
+    pub async fn write(&mut self, chunk: &[u8]) -> std::io::Result<()> {
+        self.file.write_all(chunk).await?;
+        // Hand the write to the file before followers look for it
+        self.file.flush().await?;
+        self.written += chunk.len() as u64;
+        self.progress
+            .send_replace(FillProgress::Writing(self.written));
+        Ok(())
+    }
+
+    /// Move the complete output into the cache, evicting old entries to fit
+    pub async fn commit(mut self) -> Result<CachedTranscode, TranscodeError> {
+        if self.written == 0 {
+            return Err(TranscodeError::ProcessError(
+                "Transcoder produced empty output".to_string(),
+            ));
+        }
+        self.file.flush().await?;
+
+        let path = self.cache.path_for(&self.key);
+        tokio::fs::rename(&self.partial, &path).await?;
+
+        let evicted = {
+            let mut in_flight = self.cache.lock_in_flight();
+            let mut index = self.cache.lock_index();
+            index.insert(self.key, self.written);
+            in_flight.remove(&self.key);
+            index.evict(self.cache.inner.max_bytes, Some(&self.key))
+        };
+        self.committed = true;
+        self.progress.send_replace(FillProgress::Done(self.written));
+
+        for evicted_key in evicted {
+            let path = self.cache.path_for(&evicted_key);
+            tracing::debug!(key = ?evicted_key, "Evicting cached transcode");
+            if let Err(e) = tokio::fs::remove_file(&path).await {
+                tracing::warn!(error = %e, path = %path.display(), "Failed to remove evicted transcode");
+            }
+        }
+
+        Ok(CachedTranscode {
+            path,
+            size: self.written,
+        })
+    }
+}
+
+impl Drop for CacheFill {
+    fn drop(&mut self) {
+        if self.committed {
+            return;
+        }
+        {
+            let mut in_flight = self.cache.lock_in_flight();
+            if in_flight
+                .get(&self.key)
+                .is_some_and(|running| running.partial == self.partial)
+            {
+                in_flight.remove(&self.key);
+            }
+        }
+        self.progress.send_replace(FillProgress::Failed);
+        let _ = std::fs::remove_file(&self.partial);
+    }
+}
+
+/// Reader of a cache entry that is still being written
+#[derive(Debug)]
+pub struct FillFollower {
+    partial: PathBuf,
+    path: PathBuf,
+    progress: watch::Receiver<FillProgress>,
+}
+
+impl FillFollower {
+    /// Open the output for reading from the start
+    ///
+    /// Once opened, the file stays readable when the fill is committed
+    /// (renamed) or abandoned (deleted).
+    pub async fn open(&mut self) -> std::io::Result<tokio::fs::File> {
+        loop {
+            match tokio::fs::File::open(&self.partial).await {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    match self.progress() {
+                        FillProgress::Done(_) => return tokio::fs::File::open(&self.path).await,
+                        FillProgress::Failed => return Err(e),
+                        // Committed but not yet announced
+                        FillProgress::Writing(_) => {}
+                    }
+                    if self.progress.changed().await.is_err() {
+                        return Err(e);
+                    }
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The fill's progress, marking it as seen
+    pub fn progress(&mut self) -> FillProgress {
+        *self.progress.borrow_and_update()
+    }
+
+    /// Wait until the fill's progress changes from what was last seen
+    pub async fn changed(&mut self) -> FillProgress {
+        match self.progress.changed().await {
+            Ok(()) => self.progress(),
+            Err(_) => FillProgress::Failed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+    use tokio::io::AsyncReadExt;
+
+    fn key(bitrate: u32) -> TranscodeCacheKey {
+        TranscodeCacheKey::new(
+            Uuid::parse_str("00000000-0000-0000-0000-000000000001").unwrap(),
+            TranscodeFormat::Mp3,
+            bitrate,
+        )
+    }
+
+    /// Store `len` bytes for `key` unless it is cached, returning whether a
+    /// fill ran
+    async fn store(cache: &TranscodeCache, key: TranscodeCacheKey, len: usize) -> bool {
+        match cache.lookup(key).await.unwrap() {
+            CacheLookup::Hit(_) => false,
+            CacheLookup::Filling(_) => panic!("no fill should be running"),
+            CacheLookup::Miss(mut fill) => {
+                fill.write(&vec![0u8; len]).await.unwrap();
+                fill.commit().await.unwrap();
+                true
+            }
+        }
+    }
+
+    /// Read a fill's output to the end, or `None` if it fails
+    async fn read_all(mut follower: FillFollower) -> Option<Vec<u8>> {
+        let mut file = follower.open().await.ok()?;
+        let mut output = Vec::new();
+        loop {
+            let progress = follower.progress();
+            file.read_to_end(&mut output).await.unwrap();
+            match progress {
+                FillProgress::Done(_) => return Some(output),
+                FillProgress::Failed => return None,
+                FillProgress::Writing(_) => {
+                    follower.changed().await;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cache_key_file_name_roundtrip() {
+        let key = TranscodeCacheKey::new(Uuid::new_v4(), TranscodeFormat::Opus, 128);
+        let name = key.file_name();
+        assert!(name.ends_with(".opus"));
+        assert_eq!(TranscodeCacheKey::from_file_name(&name), Some(key));
+
+        let key = TranscodeCacheKey::new(Uuid::new_v4(), TranscodeFormat::Flac, 0)
+            .with_source(
+                4096,
+                SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            )
+            .with_compression_level(Some(8));
+        let name = key.file_name();
+        assert!(name.ends_with("_4096_1700000000_0_c8.flac"));
+        assert_eq!(TranscodeCacheKey::from_file_name(&name), Some(key));
+    }
+
+    #[tokio::test]
+    async fn test_changed_source_is_cache_miss() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut fills = 0;
+
+        for source in [
+            key(128).with_source(1000, modified),
+            key(128).with_source(1000, modified),
+            // Replaced with a file of the same size
+            key(128).with_source(1000, modified + Duration::from_secs(60)),
+            // Replaced with a file of a different size
+            key(128).with_source(2000, modified + Duration::from_secs(60)),
+        ] {
+            if store(&cache, source, 10).await {
+                fills += 1;
+            }
+        }
+
+        assert_eq!(fills, 3);
+    }
+
+    #[test]
+    fn test_cache_key_rejects_unknown_file_names() {
+        assert_eq!(TranscodeCacheKey::from_file_name("notes.txt"), None);
+        assert_eq!(TranscodeCacheKey::from_file_name("abc_128.mp3"), None);
+        assert_eq!(
+            TranscodeCacheKey::from_file_name("00000000-0000-0000-0000-000000000001_128.mp3"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_second_request_is_cache_hit() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+
+        assert!(store(&cache, key(128), 100).await);
+
+        let CacheLookup::Hit(cached) = cache.lookup(key(128)).await.unwrap() else {
+            panic!("second request should be a cache hit");
+        };
+        assert_eq!(cached.size, 100);
+        assert!(cached.path.exists());
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.total_bytes(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_single_flight() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024 * 1024).unwrap();
+
+        let CacheLookup::Miss(mut fill) = cache.lookup(key(320)).await.unwrap() else {
+            panic!("first request should start a fill");
+        };
+        fill.write(b"first ").await.unwrap();
+
+        let readers: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    match cache.lookup(key(320)).await.unwrap() {
+                        CacheLookup::Filling(follower) => read_all(follower).await,
+                        other => panic!("expected to follow the fill, got {other:?}"),
+                    }
+                })
+            })
+            .collect();
+        let own = tokio::spawn(read_all(fill.follow()));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        fill.write(b"second").await.unwrap();
+        let cached = fill.commit().await.unwrap();
+        assert_eq!(cached.size, 12);
+
+        for reader in readers {
+            assert_eq!(reader.await.unwrap().unwrap(), b"first second");
+        }
+        assert_eq!(own.await.unwrap().unwrap(), b"first second");
+        assert!(cache.inner.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_follower_reads_output_before_fill_completes() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+
+        let CacheLookup::Miss(mut fill) = cache.lookup(key(128)).await.unwrap() else {
+            panic!("first request should start a fill");
+        };
+        let mut follower = fill.follow();
+        let mut file = follower.open().await.unwrap();
+        fill.write(b"partial").await.unwrap();
+
+        assert_eq!(follower.progress(), FillProgress::Writing(7));
+        let mut output = Vec::new();
+        file.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, b"partial");
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_fill_is_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+
+        let CacheLookup::Miss(mut fill) = cache.lookup(key(128)).await.unwrap() else {
+            panic!("first request should start a fill");
+        };
+        let follower = fill.follow();
+        fill.write(b"partial").await.unwrap();
+        drop(fill);
+
+        assert_eq!(read_all(follower).await, None);
+        assert!(cache.is_empty());
+        // No partial files left behind
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+        // The next request starts over
+        assert!(store(&cache, key(128), 10).await);
+    }
+
+    #[tokio::test]
+    async fn test_empty_output_is_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+
+        let CacheLookup::Miss(fill) = cache.lookup(key(128)).await.unwrap() else {
+            panic!("first request should start a fill");
+        };
+        assert!(fill.commit().await.is_err());
+        assert!(cache.is_empty());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_lru_eviction_respects_size_budget() {
+        let dir = TempDir::new().unwrap();
+        let cache = TranscodeCache::new(dir.path(), 250).unwrap();
+
+        store(&cache, key(64), 100).await;
+        store(&cache, key(96), 100).await;
+        let first = cache.path_for(&key(64));
+        let second = cache.path_for(&key(96));
+
+        // Touch the first entry so the second becomes least recently used
+        assert!(cache.get(&key(64)).await.is_some());
+
+        store(&cache, key(128), 100).await;
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.total_bytes(), 200);
+        assert!(first.exists());
+        assert!(!second.exists());
+        assert!(cache.get(&key(96)).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_reopen_reindexes_existing_files() {
+        let dir = TempDir::new().unwrap();
+        {
+            let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+            store(&cache, key(192), 42).await;
+        }
+        std::fs::write(dir.path().join("leftover.partial"), b"x").unwrap();
+        // Written before keys carried the source file's size and mtime
+        let outdated = dir
+            .path()
+            .join("00000000-0000-0000-0000-000000000001_192.mp3");
+        std::fs::write(&outdated, b"x").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), b"x").unwrap();
+
+        let cache = TranscodeCache::new(dir.path(), 1024).unwrap();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.total_bytes(), 42);
+        assert!(!dir.path().join("leftover.partial").exists());
+        assert!(!outdated.exists());
+        assert!(dir.path().join("notes.txt").exists());
+        assert!(cache.get(&key(192)).await.is_some());
+    }
+}
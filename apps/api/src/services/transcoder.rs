@@ -12,22 +12,67 @@
 //! # Resource Limits
 //!
 //! The service enforces a configurable limit on concurrent transcoding operations
-//! to prevent resource exhaustion. Requests that exceed this limit will receive
-//! a `ResourceExhausted` error.
+//! (default: one per CPU) to prevent resource exhaustion. Requests beyond the
+//! limit wait in a bounded queue for a free slot; when the queue is full, or a
+//! queued request waits longer than the queue timeout, it receives a
+//! `ResourceExhausted` error. Active and queued counts are reported to the
+//! metrics registry.
+//!
+//! # Instrumentation
+//!
+//! Every transcode ends with a `Transcode finished` event carrying the track,
+//! source and target format, bitrate, wall-clock duration, whether the cache
+//! was hit and whether it failed. The duration also feeds the
+//! `resonance_transcode_duration_seconds` histogram.
+//!
+//! # Normalization
+//!
+//! A transcode can be normalized to [`NORMALIZATION_TARGET_LUFS`]. When the
+//! worker has stored the track's integrated loudness, a single-pass `volume`
+//! gain is applied; otherwise FFmpeg's `loudnorm` filter measures as it goes.
+//!
+//! # Capabilities
+//!
+//! Which formats can actually be produced depends on the encoders compiled
+//! into the installed FFmpeg. `probe_formats()` asks FFmpeg once and caches
+//! the result; `supported_formats()` returns the cached list.
+//!
+//! # Caching
+//!
+//! When configured with a [`TranscodeCache`], transcodes are written to disk
+//! as they are streamed and reused for subsequent requests with the same
+//! track, format and bitrate, as long as the source file is unchanged (see
+//! `transcode_cached()`).
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use futures_core::Stream;
+use futures_util::StreamExt;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
 use std::path::Path;
 use std::pin::Pin;
 use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant, SystemTime};
 use thiserror::Error;
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 use tokio::process::{Child, Command};
-use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::sync::{mpsc, OnceCell, OwnedSemaphorePermit, Semaphore, TryAcquireError};
+use tokio::task::JoinHandle;
 use tokio_util::io::ReaderStream;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
 use url::Url;
+use uuid::Uuid;
+
+use super::metrics::{GaugeGuard, Metrics};
+use super::transcode_cache::{
+    CacheFill, CacheLookup, CachedTranscode, FillFollower, FillProgress, TranscodeCache,
+    TranscodeCacheKey,
+};
 
 /// Errors that can occur during transcoding
 #[derive(Error, Debug)]
@@ -42,8 +87,24 @@ pub enum TranscodeError {
     #[allow(dead_code)]
     UnsupportedFormat(String),
 
-    #[error("Invalid bitrate: {0}")]
-    InvalidBitrate(u32),
+    #[error(
+        "Invalid bitrate {bitrate} kbps for {}: allowed range is {}-{} kbps",
+        .format.extension(),
+        .range.start(),
+        .range.end()
+    )]
+    InvalidBitrate {
+        format: TranscodeFormat,
+        bitrate: u32,
+        range: RangeInclusive<u32>,
+    },
+
+    #[error(
+        "Invalid compression level {level} for {}: {}",
+        .format.extension(),
+        compression_level_hint(*.format)
+    )]
+    InvalidCompressionLevel { format: TranscodeFormat, level: u8 },
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
@@ -55,8 +116,23 @@ pub enum TranscodeError {
     InvalidPath,
 }
 
+/// Preset bitrates (kbps) offered for lossy formats
+pub const LOSSY_BITRATES: [u32; 6] = [64, 96, 128, 192, 256, 320];
+
+/// Compression levels the FLAC encoder accepts (0 = fastest, 8 = smallest)
+pub const FLAC_COMPRESSION_LEVELS: RangeInclusive<u8> = 0..=8;
+
+/// Describe the compression levels a format accepts, for error messages
+fn compression_level_hint(format: TranscodeFormat) -> String {
+    match format.compression_levels() {
+        Some(range) => format!("allowed range is {}-{}", range.start(), range.end()),
+        None => "the format has no compression levels".to_string(),
+    }
+}
+
 /// Output format for transcoding
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum TranscodeFormat {
     Mp3,
     Aac,
@@ -65,6 +141,9 @@ pub enum TranscodeFormat {
 }
 
 impl TranscodeFormat {
+    /// All formats the transcoder knows how to produce
+    pub const ALL: [Self; 4] = [Self::Mp3, Self::Aac, Self::Opus, Self::Flac];
+
     /// Parse format from string
     pub fn parse(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
@@ -76,16 +155,32 @@ impl TranscodeFormat {
         }
     }
 
-    /// Get FFmpeg format/codec parameters
-    fn ffmpeg_args(&self) -> Vec<&'static str> {
+    /// Get the FFmpeg encoder used to produce this format
+    pub fn encoder(&self) -> &'static str {
         match self {
-            Self::Mp3 => vec!["-f", "mp3", "-c:a", "libmp3lame"],
-            Self::Aac => vec!["-f", "adts", "-c:a", "aac"],
-            Self::Opus => vec!["-f", "opus", "-c:a", "libopus"],
-            Self::Flac => vec!["-f", "flac", "-c:a", "flac"],
+            Self::Mp3 => "libmp3lame",
+            Self::Aac => "aac",
+            Self::Opus => "libopus",
+            Self::Flac => "flac",
         }
     }
 
+    /// Get FFmpeg format/codec parameters
+    fn ffmpeg_args(&self) -> Vec<&'static str> {
+        let muxer = match self {
+            Self::Mp3 => "mp3",
+            Self::Aac => "adts",
+            Self::Opus => "opus",
+            Self::Flac => "flac",
+        };
+        vec!["-f", muxer, "-c:a", self.encoder()]
+    }
+
+    /// Whether the format is lossless (bitrate does not apply)
+    pub fn is_lossless(&self) -> bool {
+        matches!(self, Self::Flac)
+    }
+
     /// Get default bitrate for format (in kbps)
     pub fn default_bitrate(&self) -> u32 {
         match self {
@@ -96,18 +191,65 @@ impl TranscodeFormat {
         }
     }
 
+    /// Bitrates (kbps) the encoder accepts for this format
+    ///
+    /// Lossless formats have no bitrate and return `0..=0`.
+    pub fn valid_bitrate_range(&self) -> RangeInclusive<u32> {
+        match self {
+            Self::Mp3 => 8..=320,
+            Self::Aac => 16..=512,
+            Self::Opus => 6..=510,
+            Self::Flac => 0..=0,
+        }
+    }
+
     /// Validate bitrate for format
     pub fn validate_bitrate(&self, bitrate: u32) -> Result<u32, TranscodeError> {
+        if self.is_lossless() {
+            return Ok(0); // Ignore bitrate for lossless
+        }
+
+        let range = self.valid_bitrate_range();
+        if range.contains(&bitrate) {
+            Ok(bitrate)
+        } else {
+            Err(TranscodeError::InvalidBitrate {
+                format: *self,
+                bitrate,
+                range,
+            })
+        }
+    }
+
+    /// Compression levels the encoder accepts, if the format has any
+    ///
+    /// Only lossless FLAC does: every level decodes to the same audio and
+    /// only trades encoding time for size.
+    pub fn compression_levels(&self) -> Option<RangeInclusive<u8>> {
         match self {
-            Self::Flac => Ok(0), // Ignore bitrate for lossless
-            _ => {
-                // Valid bitrates: 64, 96, 128, 192, 256, 320
-                if matches!(bitrate, 64 | 96 | 128 | 192 | 256 | 320) {
-                    Ok(bitrate)
-                } else {
-                    Err(TranscodeError::InvalidBitrate(bitrate))
-                }
-            }
+            Self::Flac => Some(FLAC_COMPRESSION_LEVELS),
+            Self::Mp3 | Self::Aac | Self::Opus => None,
+        }
+    }
+
+    /// Validate a compression level for the format
+    pub fn validate_compression_level(&self, level: u8) -> Result<u8, TranscodeError> {
+        match self.compression_levels() {
+            Some(range) if range.contains(&level) => Ok(level),
+            _ => Err(TranscodeError::InvalidCompressionLevel {
+                format: *self,
+                level,
+            }),
+        }
+    }
+
+    /// Get the file extension used for this format's output
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Mp3 => "mp3",
+            Self::Aac => "aac",
+            Self::Opus => "opus",
+            Self::Flac => "flac",
         }
     }
 
@@ -122,11 +264,116 @@ impl TranscodeFormat {
     }
 }
 
+/// A format the transcoder can produce with the installed backend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FormatCapability {
+    /// Output format
+    pub format: TranscodeFormat,
+    /// MIME type of the transcoded stream
+    pub mime_type: &'static str,
+    /// Whether the format is lossless (bitrate is ignored)
+    pub lossless: bool,
+    /// Lowest accepted bitrate in kbps (0 for lossless formats)
+    pub min_bitrate: u32,
+    /// Highest accepted bitrate in kbps (0 for lossless formats)
+    pub max_bitrate: u32,
+    /// Bitrate used when none is requested (0 for lossless formats)
+    pub default_bitrate: u32,
+}
+
+impl FormatCapability {
+    fn new(format: TranscodeFormat) -> Self {
+        let range = format.valid_bitrate_range();
+        let (min_bitrate, max_bitrate) = (*range.start(), *range.end());
+        Self {
+            format,
+            mime_type: format.content_type(),
+            lossless: format.is_lossless(),
+            min_bitrate,
+            max_bitrate,
+            default_bitrate: format.default_bitrate(),
+        }
+    }
+}
+
+/// Build the capability list for the formats whose encoder is available
+pub fn capabilities_for_encoders(encoders: &HashSet<String>) -> Vec<FormatCapability> {
+    TranscodeFormat::ALL
+        .into_iter()
+        .filter(|format| encoders.contains(format.encoder()))
+        .map(FormatCapability::new)
+        .collect()
+}
+
+/// Parse the audio encoder names from `ffmpeg -encoders` output
+///
+/// Each entry after the `------` separator looks like
+/// ` A....D libmp3lame           libmp3lame MP3 (MPEG audio layer 3)`,
+/// where the leading `A` marks an audio encoder.
+fn parse_ffmpeg_encoders(output: &str) -> HashSet<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let flags = fields.next()?;
+            let name = fields.next()?;
+            flags.starts_with('A').then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Integrated loudness normalized transcodes are brought to (LUFS)
+pub const NORMALIZATION_TARGET_LUFS: f64 = -14.0;
+
+/// Largest boost applied from a stored loudness value, so very quiet tracks
+/// aren't pushed into clipping
+const MAX_NORMALIZATION_GAIN_DB: f64 = 12.0;
+
+/// How a transcode's volume is normalized
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Normalization {
+    /// Fixed gain in dB, computed from the stored integrated loudness
+    Gain(f64),
+    /// Measure loudness while transcoding with FFmpeg's `loudnorm` filter
+    Measure,
+}
+
+impl Normalization {
+    /// Normalization for a track, preferring its stored integrated loudness
+    ///
+    /// Falls back to measuring when the track hasn't been analyzed or was too
+    /// short to measure.
+    pub fn for_track(lufs_integrated: Option<f64>) -> Self {
+        match lufs_integrated.filter(|lufs| lufs.is_finite()) {
+            Some(lufs) => {
+                Self::Gain((NORMALIZATION_TARGET_LUFS - lufs).min(MAX_NORMALIZATION_GAIN_DB))
+            }
+            None => Self::Measure,
+        }
+    }
+
+    /// FFmpeg audio filter applying this normalization
+    fn filter(&self) -> String {
+        match self {
+            Self::Gain(gain_db) => format!("volume={:.2}dB", gain_db),
+            Self::Measure => format!("loudnorm=I={}:TP=-1.5:LRA=11", NORMALIZATION_TARGET_LUFS),
+        }
+    }
+}
+
 /// Transcoding options
 #[derive(Debug, Clone)]
 pub struct TranscodeOptions {
     pub format: TranscodeFormat,
     pub bitrate: u32,
+    /// Encoder compression level (FLAC only); `None` uses FFmpeg's default
+    pub compression_level: Option<u8>,
+    /// Position in the input to start transcoding from
+    pub start_offset: Option<Duration>,
+    /// Loudness normalization; `None` keeps the original volume
+    pub normalization: Option<Normalization>,
 }
 
 impl TranscodeOptions {
@@ -135,6 +382,9 @@ impl TranscodeOptions {
         Self {
             bitrate: format.default_bitrate(),
             format,
+            compression_level: None,
+            start_offset: None,
+            normalization: None,
         }
     }
 
@@ -144,59 +394,370 @@ impl TranscodeOptions {
         Ok(Self {
             format,
             bitrate: validated_bitrate,
+            compression_level: None,
+            start_offset: None,
+            normalization: None,
         })
     }
+
+    /// Encode at a compression level (validated against the format)
+    pub fn with_compression_level(mut self, level: u8) -> Result<Self, TranscodeError> {
+        self.compression_level = Some(self.format.validate_compression_level(level)?);
+        Ok(self)
+    }
+
+    /// Start transcoding `offset` into the input instead of at the beginning
+    pub fn with_start_offset(mut self, offset: Duration) -> Self {
+        self.start_offset = Some(offset);
+        self
+    }
+
+    /// Normalize the output's loudness
+    pub fn with_normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = Some(normalization);
+        self
+    }
 }
 
-/// Stream wrapper for FFmpeg output
+/// Transcoded chunks buffered ahead of a slow client
 ///
-/// Holds an `OwnedSemaphorePermit` that is released when the stream is dropped,
-/// ensuring the concurrent transcoding limit is properly enforced.
-pub struct TranscodeStream {
-    inner: ReaderStream<tokio::process::ChildStdout>,
-    child: Child,
-    /// Semaphore permit - released when stream is dropped
-    _permit: OwnedSemaphorePermit,
+/// Once the buffer is full the pump stops reading FFmpeg's stdout, the pipe
+/// fills up and FFmpeg blocks, so a slow client throttles the encoder instead
+/// of growing memory.
+const TRANSCODE_BUFFER_CHUNKS: usize = 8;
+
+/// Default size of each read from FFmpeg's stdout, matching the default
+/// stream chunk size for files
+const DEFAULT_OUTPUT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// How a transcode's output pump finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TranscodeOutcome {
+    /// FFmpeg wrote all of its output
+    Completed,
+    /// The client went away and the stream was dropped
+    ClientDisconnected,
+    /// The service was shut down
+    Cancelled,
 }
 
-impl TranscodeStream {
-    /// Create a new transcode stream from an FFmpeg child process
-    fn new(mut child: Child, permit: OwnedSemaphorePermit) -> Result<Self, TranscodeError> {
-        let stdout = child.stdout.take().ok_or_else(|| {
-            TranscodeError::ProcessError("Failed to capture FFmpeg stdout".into())
-        })?;
+/// A transcode being timed for the event and metric reported when it ends
+#[derive(Debug, Clone)]
+struct TranscodeRun {
+    track_id: Uuid,
+    source_format: String,
+    format: TranscodeFormat,
+    bitrate: u32,
+    started: Instant,
+    metrics: Metrics,
+}
 
-        Ok(Self {
-            inner: ReaderStream::new(stdout),
-            child,
-            _permit: permit,
-        })
+impl TranscodeRun {
+    fn start(
+        track_id: Uuid,
+        input_path: &Path,
+        options: &TranscodeOptions,
+        metrics: &Metrics,
+    ) -> Self {
+        Self {
+            track_id,
+            source_format: input_path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map_or_else(|| "unknown".to_string(), str::to_ascii_lowercase),
+            format: options.format,
+            bitrate: options.bitrate,
+            started: Instant::now(),
+            metrics: metrics.clone(),
+        }
     }
+
+    /// Emit the `Transcode finished` event and record its duration
+    fn finish(&self, cache_hit: bool, error: Option<&str>) {
+        let duration = self.started.elapsed();
+        tracing::info!(
+            track_id = %self.track_id,
+            source_format = %self.source_format,
+            format = ?self.format,
+            bitrate = self.bitrate,
+            duration_ms = duration.as_millis() as u64,
+            cache_hit,
+            error = error.is_some(),
+            error_message = error,
+            "Transcode finished"
+        );
+        self.metrics.observe_transcode(
+            self.format.extension(),
+            cache_hit,
+            error.is_some(),
+            duration,
+        );
+    }
+}
+
+/// Stream of transcoded audio
+///
+/// Chunks are fed by a background task that owns the FFmpeg process and the
+/// concurrency permit. Dropping the stream (e.g. when the client disconnects)
+/// makes that task kill and reap FFmpeg and release the permit.
+pub struct TranscodeStream {
+    rx: mpsc::Receiver<Result<Bytes, std::io::Error>>,
 }
 
 impl Stream for TranscodeStream {
     type Item = Result<Bytes, std::io::Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        Pin::new(&mut self.inner).poll_next(cx)
+        self.rx.poll_recv(cx)
     }
 }
 
-impl Drop for TranscodeStream {
-    fn drop(&mut self) {
-        // Kill FFmpeg process when stream is dropped
-        if let Err(e) = self.child.start_kill() {
-            tracing::warn!(error = %e, "Failed to kill FFmpeg process on drop");
+/// Copy FFmpeg's stdout into `tx` until it ends, the receiver is dropped or
+/// `cancel` fires, then make sure the process is gone and report `run`
+async fn pump_output(
+    mut child: Child,
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+    cancel: CancellationToken,
+    chunk_bytes: usize,
+    run: TranscodeRun,
+    _permit: TranscodePermit,
+) -> TranscodeOutcome {
+    let Some(stdout) = child.stdout.take() else {
+        let _ = tx
+            .send(Err(std::io::Error::other(
+                "Failed to capture FFmpeg stdout",
+            )))
+            .await;
+        let _ = child.kill().await;
+        run.finish(false, Some("Failed to capture FFmpeg stdout"));
+        return TranscodeOutcome::Completed;
+    };
+    let mut output = ReaderStream::with_capacity(stdout, chunk_bytes);
+
+    let outcome = loop {
+        let chunk = tokio::select! {
+            _ = tx.closed() => break TranscodeOutcome::ClientDisconnected,
+            _ = cancel.cancelled() => break TranscodeOutcome::Cancelled,
+            chunk = output.next() => chunk,
+        };
+        let Some(chunk) = chunk else {
+            break TranscodeOutcome::Completed;
+        };
+        // Waits while the buffer is full
+        tokio::select! {
+            sent = tx.send(chunk) => {
+                if sent.is_err() {
+                    break TranscodeOutcome::ClientDisconnected;
+                }
+            }
+            _ = cancel.cancelled() => break TranscodeOutcome::Cancelled,
+        }
+    };
+
+    if outcome != TranscodeOutcome::Completed {
+        if let Err(e) = child.start_kill() {
+            tracing::warn!(error = %e, "Failed to kill FFmpeg process");
+        }
+    }
+    // Reap the process so it doesn't linger as a zombie
+    let error = match child.wait().await {
+        // A killed process exiting unsuccessfully is expected
+        Ok(status) if outcome == TranscodeOutcome::Completed && !status.success() => {
+            Some(format!("FFmpeg exited with {}", status))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to wait for FFmpeg process");
+            Some(e.to_string())
+        }
+    };
+
+    tracing::debug!(?outcome, "Transcode output ended");
+    run.finish(false, error.as_deref());
+    outcome
+}
+
+/// Copy FFmpeg's stdout into a cache fill until it ends or `cancel` fires,
+/// then commit the fill if FFmpeg succeeded and report `run`
+///
+/// Unlike [`pump_output`] this keeps going when clients disconnect, so the
+/// cache is filled for the next request.
+async fn fill_output(
+    mut child: Child,
+    mut fill: CacheFill,
+    cancel: CancellationToken,
+    chunk_bytes: usize,
+    run: TranscodeRun,
+    _permit: TranscodePermit,
+) -> TranscodeOutcome {
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill().await;
+        run.finish(false, Some("Failed to capture FFmpeg stdout"));
+        return TranscodeOutcome::Completed;
+    };
+    let mut output = ReaderStream::with_capacity(stdout, chunk_bytes);
+
+    let (outcome, mut error) = loop {
+        let chunk = tokio::select! {
+            _ = cancel.cancelled() => break (TranscodeOutcome::Cancelled, None),
+            chunk = output.next() => chunk,
+        };
+        match chunk {
+            None => break (TranscodeOutcome::Completed, None),
+            Some(Err(e)) => break (TranscodeOutcome::Completed, Some(e.to_string())),
+            Some(Ok(chunk)) => {
+                if let Err(e) = fill.write(&chunk).await {
+                    tracing::warn!(error = %e, "Failed to write cached transcode");
+                    break (TranscodeOutcome::Completed, Some(e.to_string()));
+                }
+            }
+        }
+    };
+
+    if outcome != TranscodeOutcome::Completed || error.is_some() {
+        if let Err(e) = child.start_kill() {
+            tracing::warn!(error = %e, "Failed to kill FFmpeg process");
+        }
+    }
+    // Reap the process so it doesn't linger as a zombie
+    match child.wait().await {
+        Ok(status) if error.is_none() && outcome == TranscodeOutcome::Completed => {
+            if !status.success() {
+                error = Some(format!("FFmpeg exited with {}", status));
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to wait for FFmpeg process");
+            error.get_or_insert(e.to_string());
+        }
+    }
+
+    // Dropping an uncommitted fill deletes it and fails its followers
+    if outcome == TranscodeOutcome::Completed && error.is_none() {
+        if let Err(e) = fill.commit().await {
+            error = Some(e.to_string());
+        }
+    }
+
+    tracing::debug!(?outcome, "Cached transcode output ended");
+    run.finish(false, error.as_deref());
+    outcome
+}
+
+/// Copy a cache fill's output into `tx` as it is written, until the fill
+/// ends, the receiver is dropped or `cancel` fires
+async fn follow_output(
+    mut follower: FillFollower,
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+    cancel: CancellationToken,
+    chunk_bytes: usize,
+) {
+    let mut file = match follower.open().await {
+        Ok(file) => file,
+        Err(e) => {
+            let _ = tx.send(Err(e)).await;
+            return;
+        }
+    };
+    let mut buf = BytesMut::new();
+
+    loop {
+        let progress = follower.progress();
+
+        // Everything written so far
+        loop {
+            buf.reserve(chunk_bytes);
+            match file.read_buf(&mut buf).await {
+                Ok(0) => break,
+                Ok(_) => {
+                    if tx.send(Ok(buf.split().freeze())).await.is_err() {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            }
+        }
+
+        match progress {
+            FillProgress::Done(_) => return,
+            FillProgress::Failed => {
+                let _ = tx
+                    .send(Err(std::io::Error::other("Transcode failed")))
+                    .await;
+                return;
+            }
+            FillProgress::Writing(_) => {
+                tokio::select! {
+                    _ = follower.changed() => {}
+                    _ = tx.closed() => return,
+                    _ = cancel.cancelled() => return,
+                }
+            }
         }
-        // Reap the child process to prevent zombie processes from accumulating
-        // try_wait is non-blocking and will reap if the process has exited
-        let _ = self.child.try_wait();
     }
 }
 
-/// Default maximum concurrent transcoding operations
+/// Output of [`TranscoderService::transcode_cached`]
+pub enum CachedOutput {
+    /// The complete transcode, stored on disk
+    File(CachedTranscode),
+    /// A transcode being written to the cache, streamed as it is produced
+    Stream(TranscodeStream),
+}
+
+/// Concurrency limit used when the CPU count cannot be determined
 pub const DEFAULT_MAX_CONCURRENT_TRANSCODES: usize = 4;
 
+/// Default number of transcodes that may wait for a free slot
+pub const DEFAULT_MAX_QUEUED_TRANSCODES: usize = 16;
+
+/// Default time a queued transcode waits for a free slot
+pub const DEFAULT_TRANSCODE_QUEUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default concurrency limit: one transcode per available CPU
+pub fn default_max_concurrent_transcodes() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_TRANSCODES)
+}
+
+/// A transcode's concurrency slot, released when dropped
+struct TranscodePermit {
+    _permit: OwnedSemaphorePermit,
+    _active: GaugeGuard,
+}
+
+/// A transcode's place in the wait queue, released when dropped
+struct QueueSlot {
+    queued: Arc<AtomicUsize>,
+    _gauge: GaugeGuard,
+}
+
+impl QueueSlot {
+    /// Join the queue unless it already holds `max_queued` transcodes
+    fn try_join(queued: &Arc<AtomicUsize>, max_queued: usize, metrics: &Metrics) -> Option<Self> {
+        queued
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < max_queued).then_some(n + 1)
+            })
+            .ok()?;
+        Some(Self {
+            queued: queued.clone(),
+            _gauge: metrics.transcode_queued(),
+        })
+    }
+}
+
+impl Drop for QueueSlot {
+    fn drop(&mut self) {
+        self.queued.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
 /// Transcoder service for converting audio formats
 ///
 /// Enforces a configurable limit on concurrent transcoding operations
@@ -207,6 +768,25 @@ pub struct TranscoderService {
     semaphore: Arc<Semaphore>,
     /// Maximum concurrent transcodes (for logging/metrics)
     max_concurrent: usize,
+    /// Transcodes currently waiting for a permit
+    queued: Arc<AtomicUsize>,
+    /// Maximum transcodes waiting for a permit
+    max_queued: usize,
+    /// How long a queued transcode waits before giving up
+    queue_timeout: Duration,
+    /// Size of each read from FFmpeg's stdout
+    chunk_bytes: usize,
+    /// Optional disk cache for completed transcodes
+    cache: Option<TranscodeCache>,
+    /// Formats the installed FFmpeg can produce, probed once
+    capabilities: Arc<OnceCell<Vec<FormatCapability>>>,
+    /// Output pumps, cache fills and their followers, so shutdown can wait
+    /// for them
+    processes: TaskTracker,
+    /// Cancels all running transcodes on shutdown
+    cancel: CancellationToken,
+    /// Registry for transcode counts, durations and slot gauges
+    metrics: Metrics,
 }
 
 impl std::fmt::Debug for TranscoderService {
@@ -214,6 +794,12 @@ impl std::fmt::Debug for TranscoderService {
         f.debug_struct("TranscoderService")
             .field("max_concurrent", &self.max_concurrent)
             .field("available_permits", &self.semaphore.available_permits())
+            .field("queued", &self.queued_transcodes())
+            .field("max_queued", &self.max_queued)
+            .field("chunk_bytes", &self.chunk_bytes)
+            .field("cache", &self.cache)
+            .field("capabilities", &self.capabilities.get())
+            .field("running_processes", &self.processes.len())
             .finish()
     }
 }
@@ -221,7 +807,7 @@ impl std::fmt::Debug for TranscoderService {
 impl TranscoderService {
     /// Create a new transcoder service with the default concurrency limit
     pub fn new() -> Self {
-        Self::with_max_concurrent(DEFAULT_MAX_CONCURRENT_TRANSCODES)
+        Self::with_max_concurrent(default_max_concurrent_transcodes())
     }
 
     /// Create a new transcoder service with a custom concurrency limit
@@ -229,9 +815,109 @@ impl TranscoderService {
         Self {
             semaphore: Arc::new(Semaphore::new(max_concurrent)),
             max_concurrent,
+            queued: Arc::new(AtomicUsize::new(0)),
+            max_queued: DEFAULT_MAX_QUEUED_TRANSCODES,
+            queue_timeout: DEFAULT_TRANSCODE_QUEUE_TIMEOUT,
+            chunk_bytes: DEFAULT_OUTPUT_CHUNK_BYTES,
+            cache: None,
+            capabilities: Arc::new(OnceCell::new()),
+            processes: TaskTracker::new(),
+            cancel: CancellationToken::new(),
+            metrics: Metrics::new(),
         }
     }
 
+    /// Set how many transcodes may wait for a free slot (0 disables queueing)
+    pub fn with_max_queued(mut self, max_queued: usize) -> Self {
+        self.max_queued = max_queued;
+        self
+    }
+
+    /// Set how long a queued transcode waits for a free slot
+    pub fn with_queue_timeout(mut self, queue_timeout: Duration) -> Self {
+        self.queue_timeout = queue_timeout;
+        self
+    }
+
+    /// Set the size of each read from FFmpeg's stdout
+    pub fn with_chunk_bytes(mut self, chunk_bytes: usize) -> Self {
+        self.chunk_bytes = chunk_bytes;
+        self
+    }
+
+    /// Use a fixed set of available encoders instead of probing FFmpeg
+    ///
+    /// Useful in tests and when the backend is known ahead of time.
+    #[allow(dead_code)]
+    pub fn with_available_encoders<I, S>(mut self, encoders: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let encoders: HashSet<String> = encoders.into_iter().map(Into::into).collect();
+        self.capabilities = Arc::new(OnceCell::new_with(Some(capabilities_for_encoders(
+            &encoders,
+        ))));
+        self
+    }
+
+    /// Probe FFmpeg for available encoders and cache the supported formats
+    ///
+    /// Only the first call runs FFmpeg; later calls return the cached list.
+    /// If FFmpeg is missing or fails, no formats are reported.
+    pub async fn probe_formats(&self) -> &[FormatCapability] {
+        self.capabilities
+            .get_or_init(|| async {
+                let output = Command::new("ffmpeg")
+                    .args(["-hide_banner", "-encoders"])
+                    .stdin(Stdio::null())
+                    .stderr(Stdio::null())
+                    .output()
+                    .await;
+
+                match output {
+                    Ok(output) if output.status.success() => {
+                        let encoders =
+                            parse_ffmpeg_encoders(&String::from_utf8_lossy(&output.stdout));
+                        capabilities_for_encoders(&encoders)
+                    }
+                    Ok(output) => {
+                        tracing::warn!(status = %output.status, "FFmpeg encoder probe failed");
+                        Vec::new()
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e, "FFmpeg not available - transcoding disabled");
+                        Vec::new()
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Get the formats the installed backend supports
+    ///
+    /// Returns an empty list until `probe_formats()` has run.
+    pub fn supported_formats(&self) -> Vec<FormatCapability> {
+        self.capabilities.get().cloned().unwrap_or_default()
+    }
+
+    /// Record transcode metrics into `metrics`, e.g. the server's shared registry
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Enable caching of completed transcodes on disk
+    pub fn with_cache(mut self, cache: TranscodeCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Get the transcode cache, if caching is enabled
+    pub fn cache(&self) -> Option<&TranscodeCache> {
+        self.cache.as_ref()
+    }
+
     /// Check if FFmpeg is available
     ///
     /// Useful for startup validation to fail fast if FFmpeg is not installed.
@@ -253,6 +939,97 @@ impl TranscoderService {
             .saturating_sub(self.semaphore.available_permits())
     }
 
+    /// Get the number of transcodes waiting for a free slot
+    pub fn queued_transcodes(&self) -> usize {
+        self.queued.load(Ordering::Acquire)
+    }
+
+    /// Get the concurrency limit
+    pub fn max_concurrent(&self) -> usize {
+        self.max_concurrent
+    }
+
+    /// Get the number of transcodes that may wait for a free slot
+    pub fn max_queued(&self) -> usize {
+        self.max_queued
+    }
+
+    /// Acquire a concurrency slot, waiting in the bounded queue if needed
+    ///
+    /// Fails with `ResourceExhausted` if the service is shut down, the queue
+    /// is full, or no slot frees up within the queue timeout.
+    async fn acquire_permit(&self) -> Result<TranscodePermit, TranscodeError> {
+        let permit = match self.semaphore.clone().try_acquire_owned() {
+            Ok(permit) => permit,
+            Err(TryAcquireError::Closed) => return Err(TranscodeError::ResourceExhausted),
+            Err(TryAcquireError::NoPermits) => {
+                let Some(_slot) = QueueSlot::try_join(&self.queued, self.max_queued, &self.metrics)
+                else {
+                    tracing::warn!(
+                        active = self.active_transcodes(),
+                        max = self.max_concurrent,
+                        queued = self.queued_transcodes(),
+                        max_queued = self.max_queued,
+                        "Transcoding limit reached and queue full"
+                    );
+                    return Err(TranscodeError::ResourceExhausted);
+                };
+
+                tracing::debug!(
+                    queued = self.queued_transcodes(),
+                    "Transcoding limit reached, queueing"
+                );
+                match tokio::time::timeout(
+                    self.queue_timeout,
+                    self.semaphore.clone().acquire_owned(),
+                )
+                .await
+                {
+                    Ok(Ok(permit)) => permit,
+                    Ok(Err(_)) => return Err(TranscodeError::ResourceExhausted),
+                    Err(_) => {
+                        tracing::warn!(
+                            timeout_ms = self.queue_timeout.as_millis() as u64,
+                            "Timed out waiting for a transcoding slot"
+                        );
+                        return Err(TranscodeError::ResourceExhausted);
+                    }
+                }
+            }
+        };
+
+        Ok(TranscodePermit {
+            _permit: permit,
+            _active: self.metrics.transcode_started(),
+        })
+    }
+
+    /// Get the number of FFmpeg processes still running for streams
+    ///
+    /// Can briefly exceed `active_transcodes()` while a killed process is
+    /// being reaped.
+    pub fn running_processes(&self) -> usize {
+        self.processes.len()
+    }
+
+    /// Stop all streaming transcodes and wait for their processes to exit
+    ///
+    /// New transcodes can't be started afterwards. Returns `false` if some
+    /// processes were still running when `timeout` elapsed.
+    pub async fn shutdown(&self, timeout: Duration) -> bool {
+        self.semaphore.close();
+        self.cancel.cancel();
+        self.processes.close();
+
+        let running = self.running_processes();
+        if running > 0 {
+            tracing::info!(running, "Stopping running transcodes");
+        }
+        tokio::time::timeout(timeout, self.processes.wait())
+            .await
+            .is_ok()
+    }
+
     /// Transcode an audio file to a different format
     ///
     /// Returns a stream of bytes that can be sent directly to the client.
@@ -260,24 +1037,39 @@ impl TranscoderService {
     ///
     /// # Resource Limits
     ///
-    /// This method acquires a semaphore permit before spawning FFmpeg.
-    /// If all permits are in use, returns `TranscodeError::ResourceExhausted`.
-    /// The permit is held by the returned `TranscodeStream` and released
-    /// when the stream is dropped.
+    /// This method acquires a semaphore permit before spawning FFmpeg,
+    /// queueing for one if all are in use. If the queue is full or the wait
+    /// times out, returns `TranscodeError::ResourceExhausted`.
+    /// The permit is held until the FFmpeg process has exited, which happens
+    /// promptly once the returned `TranscodeStream` is dropped.
+    ///
+    /// A `Transcode finished` event is emitted when FFmpeg exits, or when the
+    /// transcode fails to start.
     pub async fn transcode(
         &self,
+        track_id: Uuid,
         input_path: &Path,
         options: &TranscodeOptions,
     ) -> Result<TranscodeStream, TranscodeError> {
-        // Acquire semaphore permit (non-blocking - fail fast if at limit)
-        let permit = self.semaphore.clone().try_acquire_owned().map_err(|_| {
-            tracing::warn!(
-                active = self.active_transcodes(),
-                max = self.max_concurrent,
-                "Transcoding limit reached"
-            );
-            TranscodeError::ResourceExhausted
-        })?;
+        let run = TranscodeRun::start(track_id, input_path, options, &self.metrics);
+        let (child, permit) = match self.spawn_streaming(input_path, options).await {
+            Ok(started) => started,
+            Err(e) => {
+                run.finish(false, Some(&e.to_string()));
+                return Err(e);
+            }
+        };
+
+        Ok(self.stream_output(child, permit, run).0)
+    }
+
+    /// Wait for a concurrency slot and spawn FFmpeg writing to stdout
+    async fn spawn_streaming(
+        &self,
+        input_path: &Path,
+        options: &TranscodeOptions,
+    ) -> Result<(Child, TranscodePermit), TranscodeError> {
+        let permit = self.acquire_permit().await?;
 
         tracing::debug!(
             format = ?options.format,
@@ -287,40 +1079,218 @@ impl TranscoderService {
             "Starting transcode"
         );
 
-        let mut cmd = Command::new("ffmpeg");
+        // Output to stdout (pipe)
+        let child = self.spawn_ffmpeg(input_path, options, "pipe:1")?;
 
-        // Input file - use file: protocol URL to prevent argument injection and properly
-        // encode special characters. Url::from_file_path handles all URL encoding
-        // (spaces, #, %, ?, non-ASCII, etc.) per RFC 8089.
-        let file_url = Url::from_file_path(input_path).map_err(|()| {
-            tracing::error!(path = %input_path.display(), "Failed to convert path to file URL");
-            TranscodeError::InvalidPath
-        })?;
+        Ok((child, permit))
+    }
+
+    /// Start pumping a process's stdout into a [`TranscodeStream`]
+    ///
+    /// Returns the stream and a handle to the pump task.
+    fn stream_output(
+        &self,
+        child: Child,
+        permit: TranscodePermit,
+        run: TranscodeRun,
+    ) -> (TranscodeStream, JoinHandle<TranscodeOutcome>) {
+        let (tx, rx) = mpsc::channel(TRANSCODE_BUFFER_CHUNKS);
+        let pump = self.processes.spawn(pump_output(
+            child,
+            tx,
+            self.cancel.child_token(),
+            self.chunk_bytes,
+            run,
+            permit,
+        ));
+        (TranscodeStream { rx }, pump)
+    }
+
+    /// Transcode an audio file, serving the result from the disk cache
+    ///
+    /// On a cache miss FFmpeg runs in a background task that writes its
+    /// output into the cache, and the output is streamed to the caller as it
+    /// is written. Concurrent requests for the same `(track, format, bitrate)`
+    /// stream the same output rather than starting another FFmpeg run. The
+    /// background task keeps running if its callers go away, so the next
+    /// request is a hit, and is stopped by `shutdown()`. A source file whose
+    /// size or mtime has changed since it was cached is transcoded again.
+    ///
+    /// Returns `Ok(None)` when caching is not enabled or `options` has a
+    /// start offset or normalization; callers should fall back to
+    /// `transcode()`. Otherwise a `Transcode finished` event records whether
+    /// FFmpeg had to run.
+    pub async fn transcode_cached(
+        &self,
+        track_id: Uuid,
+        input_path: &Path,
+        options: &TranscodeOptions,
+    ) -> Result<Option<CachedOutput>, TranscodeError> {
+        let Some(cache) = &self.cache else {
+            return Ok(None);
+        };
+        // Cache entries hold whole tracks at their original volume; a
+        // normalization gain changes whenever the track is re-analyzed
+        if options.start_offset.is_some() || options.normalization.is_some() {
+            return Ok(None);
+        }
 
-        cmd.arg("-i")
-            .arg(file_url.as_str())
+        let run = TranscodeRun::start(track_id, input_path, options, &self.metrics);
+        // Keyed on the file as it is now, so a replaced file isn't served stale
+        let lookup = match tokio::fs::metadata(input_path).await {
+            Ok(source) => {
+                let key = TranscodeCacheKey::new(track_id, options.format, options.bitrate)
+                    .with_source(
+                        source.len(),
+                        source.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+                    )
+                    .with_compression_level(options.compression_level);
+                cache.lookup(key).await
+            }
+            Err(e) => Err(e.into()),
+        };
+
+        let fill = match lookup {
+            Ok(CacheLookup::Hit(cached)) => {
+                run.finish(true, None);
+                return Ok(Some(CachedOutput::File(cached)));
+            }
+            Ok(CacheLookup::Filling(follower)) => {
+                run.finish(true, None);
+                return Ok(Some(CachedOutput::Stream(self.follow(follower))));
+            }
+            Ok(CacheLookup::Miss(fill)) => fill,
+            Err(e) => {
+                run.finish(false, Some(&e.to_string()));
+                return Err(e);
+            }
+        };
+
+        let (child, permit) = match self.spawn_streaming(input_path, options).await {
+            Ok(started) => started,
+            Err(e) => {
+                run.finish(false, Some(&e.to_string()));
+                return Err(e);
+            }
+        };
+        Ok(Some(CachedOutput::Stream(
+            self.fill_cache(child, fill, permit, run),
+        )))
+    }
+
+    /// Start writing a process's stdout into a cache fill
+    ///
+    /// Returns a stream of the output as it is written.
+    fn fill_cache(
+        &self,
+        child: Child,
+        fill: CacheFill,
+        permit: TranscodePermit,
+        run: TranscodeRun,
+    ) -> TranscodeStream {
+        let follower = fill.follow();
+        self.processes.spawn(fill_output(
+            child,
+            fill,
+            self.cancel.child_token(),
+            self.chunk_bytes,
+            run,
+            permit,
+        ));
+        self.follow(follower)
+    }
+
+    /// Stream a cache fill's output as it is written
+    fn follow(&self, follower: FillFollower) -> TranscodeStream {
+        let (tx, rx) = mpsc::channel(TRANSCODE_BUFFER_CHUNKS);
+        self.processes.spawn(follow_output(
+            follower,
+            tx,
+            self.cancel.child_token(),
+            self.chunk_bytes,
+        ));
+        TranscodeStream { rx }
+    }
+
+    /// FFmpeg arguments to transcode `input_url` to `output`
+    fn ffmpeg_command_args(
+        input_url: &str,
+        options: &TranscodeOptions,
+        output: &str,
+    ) -> Vec<String> {
+        let mut args = Vec::new();
+
+        // Seek before `-i` so FFmpeg skips straight to the offset in the
+        // input instead of decoding and discarding everything before it
+        if let Some(offset) = options.start_offset {
+            args.push("-ss".to_string());
+            args.push(format!("{:.3}", offset.as_secs_f64()));
+        }
+
+        args.extend([
+            "-i".to_string(),
+            input_url.to_string(),
             // Suppress banner and stats
-            .arg("-hide_banner")
-            .arg("-loglevel")
-            .arg("error");
+            "-hide_banner".to_string(),
+            "-loglevel".to_string(),
+            "error".to_string(),
+        ]);
 
-        // Add format-specific codec args
-        for arg in options.format.ffmpeg_args() {
-            cmd.arg(arg);
+        if let Some(normalization) = options.normalization {
+            args.push("-af".to_string());
+            args.push(normalization.filter());
         }
 
+        // Add format-specific codec args
+        args.extend(options.format.ffmpeg_args().into_iter().map(String::from));
+
         // Add bitrate for lossy formats
         if options.bitrate > 0 {
-            cmd.arg("-b:a").arg(format!("{}k", options.bitrate));
+            args.push("-b:a".to_string());
+            args.push(format!("{}k", options.bitrate));
         }
 
-        // Output to stdout (pipe)
-        cmd.arg("pipe:1");
+        if let Some(level) = options.compression_level {
+            args.push("-compression_level".to_string());
+            args.push(level.to_string());
+        }
+
+        args.push("-y".to_string());
+        args.push(output.to_string());
+        args
+    }
+
+    /// Build and spawn an FFmpeg process writing to `output`
+    ///
+    /// The process is killed if its handle is dropped, so abandoned requests
+    /// don't leave FFmpeg running.
+    fn spawn_ffmpeg(
+        &self,
+        input_path: &Path,
+        options: &TranscodeOptions,
+        output: &str,
+    ) -> Result<Child, TranscodeError> {
+        let mut cmd = Command::new("ffmpeg");
+
+        // Input file - use file: protocol URL to prevent argument injection and properly
+        // encode special characters. Url::from_file_path handles all URL encoding
+        // (spaces, #, %, ?, non-ASCII, etc.) per RFC 8089.
+        let file_url = Url::from_file_path(input_path).map_err(|()| {
+            tracing::error!(path = %input_path.display(), "Failed to convert path to file URL");
+            TranscodeError::InvalidPath
+        })?;
+
+        cmd.args(Self::ffmpeg_command_args(
+            file_url.as_str(),
+            options,
+            output,
+        ));
 
         // Configure stdio - capture stderr for logging
         cmd.stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .stdin(Stdio::null());
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
 
         let mut child = cmd.spawn().map_err(|e| {
             if e.kind() == std::io::ErrorKind::NotFound {
@@ -329,6 +1299,7 @@ impl TranscoderService {
                 TranscodeError::ProcessError(e.to_string())
             }
         })?;
+        self.metrics.record_transcode(options.format.extension());
 
         // Spawn a task to read and log stderr
         if let Some(stderr) = child.stderr.take() {
@@ -346,7 +1317,7 @@ impl TranscoderService {
             });
         }
 
-        TranscodeStream::new(child, permit)
+        Ok(child)
     }
 }
 
@@ -359,6 +1330,7 @@ impl Default for TranscoderService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tracing_test::traced_test;
 
     #[test]
     fn test_format_parse() {
@@ -381,6 +1353,86 @@ mod tests {
         assert_eq!(TranscodeFormat::Flac.default_bitrate(), 0);
     }
 
+    const ENCODERS_OUTPUT: &str = "\
+Encoders:
+ V..... = Video
+ A..... = Audio
+ S..... = Subtitle
+ ------
+ V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC (codec h264)
+ A....D aac                  AAC (Advanced Audio Coding)
+ A....D flac                 FLAC (Free Lossless Audio Codec)
+ A....D libmp3lame           libmp3lame MP3 (MPEG audio layer 3) (codec mp3)
+ S..... srt                  SubRip subtitle
+";
+
+    #[test]
+    fn test_parse_ffmpeg_encoders() {
+        let encoders = parse_ffmpeg_encoders(ENCODERS_OUTPUT);
+        assert!(encoders.contains("aac"));
+        assert!(encoders.contains("flac"));
+        assert!(encoders.contains("libmp3lame"));
+        // Video/subtitle encoders and legend lines are ignored
+        assert!(!encoders.contains("libx264"));
+        assert!(!encoders.contains("srt"));
+        assert!(!encoders.contains("="));
+        assert_eq!(encoders.len(), 3);
+    }
+
+    #[test]
+    fn test_capabilities_exclude_unavailable_codecs() {
+        // FFmpeg built without libopus
+        let capabilities = capabilities_for_encoders(&parse_ffmpeg_encoders(ENCODERS_OUTPUT));
+
+        let formats: Vec<TranscodeFormat> = capabilities.iter().map(|c| c.format).collect();
+        assert_eq!(
+            formats,
+            vec![
+                TranscodeFormat::Mp3,
+                TranscodeFormat::Aac,
+                TranscodeFormat::Flac
+            ]
+        );
+    }
+
+    #[test]
+    fn test_capability_bitrate_ranges() {
+        let service = TranscoderService::new().with_available_encoders(["libopus", "flac"]);
+        let capabilities = service.supported_formats();
+
+        assert_eq!(capabilities.len(), 2);
+        let opus = &capabilities[0];
+        assert_eq!(opus.format, TranscodeFormat::Opus);
+        assert_eq!(opus.mime_type, "audio/opus");
+        assert!(!opus.lossless);
+        assert_eq!((opus.min_bitrate, opus.max_bitrate), (6, 510));
+        assert_eq!(opus.default_bitrate, 128);
+
+        let flac = &capabilities[1];
+        assert!(flac.lossless);
+        assert_eq!((flac.min_bitrate, flac.max_bitrate), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_preset_encoders_skip_probe() {
+        let service = TranscoderService::new().with_available_encoders(["aac"]);
+        let formats = service.probe_formats().await;
+        assert_eq!(formats.len(), 1);
+        assert_eq!(formats[0].format, TranscodeFormat::Aac);
+    }
+
+    #[test]
+    fn test_supported_formats_empty_before_probe() {
+        assert!(TranscoderService::new().supported_formats().is_empty());
+    }
+
+    #[test]
+    fn test_format_serializes_lowercase() {
+        let json = serde_json::to_value(FormatCapability::new(TranscodeFormat::Mp3)).unwrap();
+        assert_eq!(json["format"], "mp3");
+        assert_eq!(json["mime_type"], "audio/mpeg");
+    }
+
     #[test]
     fn test_validate_bitrate() {
         // Valid bitrates
@@ -389,7 +1441,7 @@ mod tests {
         assert!(TranscodeFormat::Aac.validate_bitrate(256).is_ok());
 
         // Invalid bitrates
-        assert!(TranscodeFormat::Mp3.validate_bitrate(100).is_err());
+        assert!(TranscodeFormat::Mp3.validate_bitrate(0).is_err());
         assert!(TranscodeFormat::Mp3.validate_bitrate(400).is_err());
 
         // FLAC ignores bitrate
@@ -412,12 +1464,433 @@ mod tests {
         assert_eq!(opts.bitrate, 320);
     }
 
+    /// Spawn a process that writes output until killed, standing in for FFmpeg
+    #[cfg(unix)]
+    fn spawn_endless_output() -> Child {
+        Command::new("yes")
+            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("failed to spawn `yes`")
+    }
+
+    fn test_run() -> TranscodeRun {
+        TranscodeRun::start(
+            Uuid::new_v4(),
+            Path::new("/music/a.flac"),
+            &TranscodeOptions::new(TranscodeFormat::Mp3),
+            &Metrics::new(),
+        )
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    #[traced_test]
+    async fn test_finished_transcode_reports_event() {
+        let service = TranscoderService::with_max_concurrent(1);
+        let track_id = Uuid::new_v4();
+        let run = TranscodeRun::start(
+            track_id,
+            Path::new("/music/a.FLAC"),
+            &TranscodeOptions::with_bitrate(TranscodeFormat::Opus, 96).unwrap(),
+            &Metrics::new(),
+        );
+        let child = Command::new("echo")
+            .arg("encoded")
+            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("failed to spawn `echo`");
+
+        let permit = service.acquire_permit().await.unwrap();
+        let (stream, pump) = service.stream_output(child, permit, run);
+        let output: Vec<_> = stream.collect().await;
+        assert!(output.iter().all(Result::is_ok));
+        assert_eq!(pump.await.unwrap(), TranscodeOutcome::Completed);
+
+        assert!(logs_contain("Transcode finished"));
+        assert!(logs_contain(&format!("track_id={}", track_id)));
+        assert!(logs_contain("source_format=flac"));
+        assert!(logs_contain("format=Opus"));
+        assert!(logs_contain("bitrate=96"));
+        assert!(logs_contain("duration_ms="));
+        assert!(logs_contain("cache_hit=false"));
+        assert!(logs_contain("error=false"));
+        assert!(!logs_contain("error_message"));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_dropped_stream_cancels_transcode() {
+        let service = TranscoderService::with_max_concurrent(1);
+        let permit = service.acquire_permit().await.unwrap();
+        let (mut stream, pump) = service.stream_output(spawn_endless_output(), permit, test_run());
+
+        assert!(stream.next().await.unwrap().is_ok());
+        assert_eq!(service.active_transcodes(), 1);
+
+        // Client disconnects
+        drop(stream);
+
+        let outcome = tokio::time::timeout(Duration::from_secs(5), pump)
+            .await
+            .expect("transcode should stop after the stream is dropped")
+            .unwrap();
+        assert_eq!(outcome, TranscodeOutcome::ClientDisconnected);
+        assert_eq!(service.active_transcodes(), 0);
+        assert_eq!(service.running_processes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_slot_gauges_record_into_injected_metrics() {
+        let metrics = Metrics::new();
+        let service = TranscoderService::with_max_concurrent(1).with_metrics(metrics.clone());
+
+        let permit = service.acquire_permit().await.unwrap();
+        assert_eq!(metrics.transcodes_active(), 1);
+
+        let queued = tokio::spawn({
+            let service = service.clone();
+            async move { service.acquire_permit().await.map(drop) }
+        });
+        while metrics.transcodes_queued() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        drop(permit);
+        queued.await.unwrap().unwrap();
+        assert_eq!(metrics.transcodes_active(), 0);
+        assert_eq!(metrics.transcodes_queued(), 0);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_slow_client_bounds_buffered_output() {
+        let service = TranscoderService::with_max_concurrent(1);
+        let permit = service.acquire_permit().await.unwrap();
+        let (stream, pump) = service.stream_output(spawn_endless_output(), permit, test_run());
+
+        // Nobody reads: the pump fills the buffer and then waits
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(stream.rx.len(), TRANSCODE_BUFFER_CHUNKS);
+        assert!(!pump.is_finished());
+
+        drop(stream);
+        pump.await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_shutdown_stops_running_transcodes() {
+        let service = TranscoderService::with_max_concurrent(2);
+        let permit = service.acquire_permit().await.unwrap();
+        let (_stream, pump) = service.stream_output(spawn_endless_output(), permit, test_run());
+
+        assert!(service.shutdown(Duration::from_secs(5)).await);
+
+        assert_eq!(pump.await.unwrap(), TranscodeOutcome::Cancelled);
+        assert_eq!(service.running_processes(), 0);
+        assert!(matches!(
+            service
+                .transcode(
+                    Uuid::new_v4(),
+                    Path::new("/music/a.flac"),
+                    &TranscodeOptions::new(TranscodeFormat::Mp3)
+                )
+                .await,
+            Err(TranscodeError::ResourceExhausted)
+        ));
+    }
+
+    /// Start a cache fill for `service`'s cache, which must miss
+    async fn start_fill(service: &TranscoderService) -> CacheFill {
+        let key = TranscodeCacheKey::new(Uuid::nil(), TranscodeFormat::Mp3, 320);
+        match service.cache().unwrap().lookup(key).await.unwrap() {
+            CacheLookup::Miss(fill) => fill,
+            _ => panic!("expected a cache miss"),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_cache_miss_streams_while_filling() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = TranscoderService::with_max_concurrent(1)
+            .with_cache(TranscodeCache::new(dir.path(), u64::MAX).unwrap());
+        let fill = start_fill(&service).await;
+        let permit = service.acquire_permit().await.unwrap();
+
+        let mut stream = service.fill_cache(spawn_endless_output(), fill, permit, test_run());
+
+        // Output arrives while FFmpeg is still running
+        let first = tokio::time::timeout(Duration::from_secs(5), stream.next())
+            .await
+            .expect("output should stream before the transcode finishes")
+            .unwrap();
+        assert!(!first.unwrap().is_empty());
+        assert_eq!(service.active_transcodes(), 1);
+
+        // The fill outlives the client, and shutdown stops it
+        drop(stream);
+        assert!(service.shutdown(Duration::from_secs(5)).await);
+        assert_eq!(service.running_processes(), 0);
+        assert!(service.cache().unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_completed_fill_is_cached() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let service = TranscoderService::with_max_concurrent(1)
+            .with_cache(TranscodeCache::new(dir.path(), u64::MAX).unwrap());
+        let fill = start_fill(&service).await;
+        let permit = service.acquire_permit().await.unwrap();
+        let child = Command::new("echo")
+            .arg("encoded")
+            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .expect("failed to spawn `echo`");
+
+        let stream = service.fill_cache(child, fill, permit, test_run());
+        let output: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(output.concat(), b"encoded\n");
+
+        service.processes.close();
+        service.processes.wait().await;
+        let key = TranscodeCacheKey::new(Uuid::nil(), TranscodeFormat::Mp3, 320);
+        let cached = service.cache().unwrap().get(&key).await.unwrap();
+        assert_eq!(cached.size, 8);
+    }
+
+    #[tokio::test]
+    async fn test_saturated_transcoder_queues_then_rejects() {
+        let service = TranscoderService::with_max_concurrent(1).with_max_queued(1);
+        let running = service.acquire_permit().await.unwrap();
+
+        // The second request waits in the queue for the running one
+        let queued = tokio::spawn({
+            let service = service.clone();
+            async move { service.acquire_permit().await.map(|_| ()) }
+        });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while service.queued_transcodes() == 0 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("second request should queue");
+        assert_eq!(service.active_transcodes(), 1);
+
+        // The queue is full, so the third is rejected immediately
+        assert!(matches!(
+            service.acquire_permit().await,
+            Err(TranscodeError::ResourceExhausted)
+        ));
+
+        // Finishing the running transcode lets the queued one start
+        drop(running);
+        tokio::time::timeout(Duration::from_secs(5), queued)
+            .await
+            .expect("queued request should get the freed slot")
+            .unwrap()
+            .unwrap();
+        assert_eq!(service.queued_transcodes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_queued_transcode_times_out() {
+        let service =
+            TranscoderService::with_max_concurrent(1).with_queue_timeout(Duration::from_millis(20));
+        let _running = service.acquire_permit().await.unwrap();
+
+        assert!(matches!(
+            service.acquire_permit().await,
+            Err(TranscodeError::ResourceExhausted)
+        ));
+        assert_eq!(service.queued_transcodes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_no_queue_rejects_when_saturated() {
+        let service = TranscoderService::with_max_concurrent(1).with_max_queued(0);
+        let _running = service.acquire_permit().await.unwrap();
+
+        assert!(matches!(
+            service.acquire_permit().await,
+            Err(TranscodeError::ResourceExhausted)
+        ));
+    }
+
+    #[test]
+    fn test_default_concurrency_follows_cpu_count() {
+        let service = TranscoderService::new();
+        assert!(service.max_concurrent() >= 1);
+        assert_eq!(
+            service.max_concurrent(),
+            default_max_concurrent_transcodes()
+        );
+        assert_eq!(service.max_queued(), DEFAULT_MAX_QUEUED_TRANSCODES);
+    }
+
     #[test]
     fn test_transcode_options_with_bitrate() {
         let opts = TranscodeOptions::with_bitrate(TranscodeFormat::Mp3, 128).unwrap();
         assert_eq!(opts.bitrate, 128);
 
-        let err = TranscodeOptions::with_bitrate(TranscodeFormat::Mp3, 100);
+        let err = TranscodeOptions::with_bitrate(TranscodeFormat::Mp3, 9999);
         assert!(err.is_err());
     }
+
+    #[test]
+    fn test_ffmpeg_args_seek_to_start_offset() {
+        let opts = TranscodeOptions::new(TranscodeFormat::Opus)
+            .with_start_offset(Duration::from_secs_f64(83.25));
+        let args = TranscoderService::ffmpeg_command_args("file:///music/a.flac", &opts, "pipe:1");
+
+        // Input seeking: `-ss` comes before `-i`
+        assert_eq!(args[..4], ["-ss", "83.250", "-i", "file:///music/a.flac"]);
+        assert_eq!(args.last().map(String::as_str), Some("pipe:1"));
+    }
+
+    #[test]
+    fn test_normalization_prefers_stored_loudness() {
+        // A track mastered at -9 LUFS is turned down by 5 dB
+        assert_eq!(
+            Normalization::for_track(Some(-9.0)),
+            Normalization::Gain(-5.0)
+        );
+        // Boosts of very quiet tracks are capped
+        assert_eq!(
+            Normalization::for_track(Some(-40.0)),
+            Normalization::Gain(MAX_NORMALIZATION_GAIN_DB)
+        );
+        // Unanalyzed (or too short to measure) tracks are measured by FFmpeg
+        assert_eq!(Normalization::for_track(None), Normalization::Measure);
+
+        let opts = TranscodeOptions::new(TranscodeFormat::Mp3)
+            .with_normalization(Normalization::for_track(Some(-9.0)));
+        let args = TranscoderService::ffmpeg_command_args("file:///music/a.flac", &opts, "pipe:1");
+        let filter = args.iter().position(|arg| arg == "-af");
+        assert_eq!(filter.map(|i| args[i + 1].as_str()), Some("volume=-5.00dB"));
+
+        let opts =
+            TranscodeOptions::new(TranscodeFormat::Mp3).with_normalization(Normalization::Measure);
+        let args = TranscoderService::ffmpeg_command_args("file:///music/a.flac", &opts, "pipe:1");
+        let filter = args.iter().position(|arg| arg == "-af");
+        assert!(filter.is_some_and(|i| args[i + 1].starts_with("loudnorm=I=-14")));
+
+        let args = TranscoderService::ffmpeg_command_args(
+            "file:///music/a.flac",
+            &TranscodeOptions::new(TranscodeFormat::Mp3),
+            "pipe:1",
+        );
+        assert!(!args.iter().any(|arg| arg == "-af"));
+    }
+
+    #[test]
+    fn test_ffmpeg_args_flac_compression_level() {
+        let opts = TranscodeOptions::new(TranscodeFormat::Flac)
+            .with_compression_level(8)
+            .unwrap();
+        let args = TranscoderService::ffmpeg_command_args("file:///music/a.flac", &opts, "pipe:1");
+
+        let level = args.iter().position(|arg| arg == "-compression_level");
+        assert_eq!(level.map(|i| args[i + 1].as_str()), Some("8"));
+        // Lossless: no bitrate
+        assert!(!args.iter().any(|arg| arg == "-b:a"));
+
+        let default = TranscodeOptions::new(TranscodeFormat::Flac);
+        let args =
+            TranscoderService::ffmpeg_command_args("file:///music/a.flac", &default, "pipe:1");
+        assert!(!args.iter().any(|arg| arg == "-compression_level"));
+    }
+
+    #[test]
+    fn test_compression_level_validation() {
+        for level in [0, 5, 8] {
+            assert!(TranscodeOptions::new(TranscodeFormat::Flac)
+                .with_compression_level(level)
+                .is_ok());
+        }
+
+        let err = TranscodeOptions::new(TranscodeFormat::Flac)
+            .with_compression_level(9)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            TranscodeError::InvalidCompressionLevel { level: 9, .. }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Invalid compression level 9 for flac: allowed range is 0-8"
+        );
+
+        // Lossy formats have no compression levels
+        assert!(TranscodeOptions::new(TranscodeFormat::Mp3)
+            .with_compression_level(5)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ffmpeg_args_without_start_offset() {
+        let opts = TranscodeOptions::with_bitrate(TranscodeFormat::Mp3, 192).unwrap();
+        let args = TranscoderService::ffmpeg_command_args("file:///music/a.flac", &opts, "pipe:1");
+
+        assert_eq!(args[0], "-i");
+        assert!(!args.iter().any(|a| a == "-ss"));
+        assert!(args.windows(2).any(|w| w == ["-b:a", "192k"]));
+    }
+
+    #[test]
+    fn test_valid_bitrate_ranges() {
+        assert_eq!(TranscodeFormat::Mp3.valid_bitrate_range(), 8..=320);
+        assert_eq!(TranscodeFormat::Aac.valid_bitrate_range(), 16..=512);
+        assert_eq!(TranscodeFormat::Opus.valid_bitrate_range(), 6..=510);
+        assert_eq!(TranscodeFormat::Flac.valid_bitrate_range(), 0..=0);
+
+        for format in TranscodeFormat::ALL {
+            let range = format.valid_bitrate_range();
+            assert!(range.contains(&format.default_bitrate()), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_bitrate_bounds_are_inclusive() {
+        for format in [
+            TranscodeFormat::Mp3,
+            TranscodeFormat::Aac,
+            TranscodeFormat::Opus,
+        ] {
+            let range = format.valid_bitrate_range();
+            assert_eq!(
+                format.validate_bitrate(*range.start()).unwrap(),
+                *range.start()
+            );
+            assert_eq!(format.validate_bitrate(*range.end()).unwrap(), *range.end());
+            assert!(format.validate_bitrate(range.start() - 1).is_err());
+            assert!(format.validate_bitrate(range.end() + 1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_out_of_range_bitrate_error_names_range() {
+        let err = TranscodeOptions::with_bitrate(TranscodeFormat::Opus, 9999).unwrap_err();
+
+        assert!(matches!(
+            err,
+            TranscodeError::InvalidBitrate {
+                format: TranscodeFormat::Opus,
+                bitrate: 9999,
+                ..
+            }
+        ));
+        assert_eq!(
+            err.to_string(),
+            "Invalid bitrate 9999 kbps for opus: allowed range is 6-510 kbps"
+        );
+    }
 }
@@ -0,0 +1,327 @@
+//! Graceful shutdown for the API server
+//!
+//! On Ctrl+C or SIGTERM the server stops accepting connections and gives
+//! in-flight requests (including audio streams still sending their body) a
+//! bounded drain window to finish. Open WebSocket connections watch the same
+//! [`ShutdownHandle`] and close themselves with a "going away" close frame.
+
+use std::future::{Future, IntoFuture};
+use std::io;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::Router;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+use tower_http::metrics::in_flight_requests::InFlightRequestsCounter;
+use tower_http::metrics::InFlightRequestsLayer;
+
+use crate::services::metrics::Metrics;
+
+/// How often to check whether WebSocket connections have closed while draining
+const WEBSOCKET_DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Shared shutdown state for the server and long-lived connections
+///
+/// Cheap to clone; clones observe the same shutdown.
+#[derive(Debug, Clone)]
+pub struct ShutdownHandle {
+    token: CancellationToken,
+    in_flight: InFlightRequestsCounter,
+    metrics: Metrics,
+}
+
+impl ShutdownHandle {
+    /// Create a handle; open WebSocket connections are read from `metrics`
+    pub fn new(metrics: Metrics) -> Self {
+        Self {
+            token: CancellationToken::new(),
+            in_flight: InFlightRequestsCounter::new(),
+            metrics,
+        }
+    }
+
+    /// Layer counting requests until their response body has been sent
+    pub fn in_flight_layer(&self) -> InFlightRequestsLayer {
+        InFlightRequestsLayer::new(self.in_flight.clone())
+    }
+
+    /// Start shutting down
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether shutdown has started
+    #[allow(dead_code)] // Exposed for handlers that poll instead of awaiting
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown has started
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Requests still being processed or sending their response body
+    pub fn in_flight_requests(&self) -> usize {
+        self.in_flight.get()
+    }
+
+    /// Currently open WebSocket connections
+    pub fn websocket_connections(&self) -> usize {
+        self.metrics.websocket_connections().max(0) as usize
+    }
+
+    /// Connections that still have to drain
+    fn open_connections(&self) -> usize {
+        self.in_flight_requests() + self.websocket_connections()
+    }
+
+    async fn websockets_closed(&self) {
+        while self.websocket_connections() > 0 {
+            tokio::time::sleep(WEBSOCKET_DRAIN_POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Serve `app` until `signal` resolves, then drain connections
+///
+/// After the signal no new connections are accepted. In-flight requests and
+/// WebSocket connections get up to `drain_timeout` to finish; anything still
+/// open after that is dropped when this returns.
+pub async fn serve_with_graceful_shutdown<F>(
+    listener: TcpListener,
+    app: Router,
+    handle: ShutdownHandle,
+    signal: F,
+    drain_timeout: Duration,
+) -> io::Result<()>
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let trigger = handle.clone();
+    tokio::spawn(async move {
+        signal.await;
+        trigger.trigger();
+    });
+
+    let stop_accepting = handle.clone();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { stop_accepting.cancelled().await })
+    .into_future();
+    tokio::pin!(server);
+
+    // Prefer the signal branch so the drain below still runs if the server
+    // future also finished in the meantime
+    tokio::select! {
+        biased;
+        _ = handle.cancelled() => {}
+        result = &mut server => return result,
+    }
+
+    let pending = handle.open_connections();
+    tracing::info!(
+        in_flight_requests = handle.in_flight_requests(),
+        websocket_connections = handle.websocket_connections(),
+        drain_timeout_secs = drain_timeout.as_secs(),
+        "Shutdown signal received, draining connections"
+    );
+
+    // Upgraded WebSocket connections outlive the server future, so wait for
+    // them separately once HTTP connections are done
+    let drain = async {
+        server.await?;
+        handle.websockets_closed().await;
+        Ok::<_, io::Error>(())
+    };
+
+    match tokio::time::timeout(drain_timeout, drain).await {
+        Ok(result) => {
+            result?;
+            tracing::info!(drained = pending, "All connections drained");
+        }
+        Err(_) => {
+            let remaining = handle.open_connections();
+            tracing::warn!(
+                drained = pending.saturating_sub(remaining),
+                remaining,
+                "Drain window elapsed, closing remaining connections"
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Wait for shutdown signal (Ctrl+C or SIGTERM)
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+    use tokio::sync::oneshot;
+
+    /// Start a test server with one slow route; returns its address and task
+    async fn start_server(
+        handle: ShutdownHandle,
+        drain_timeout: Duration,
+        request_time: Duration,
+    ) -> (
+        SocketAddr,
+        oneshot::Sender<()>,
+        tokio::task::JoinHandle<io::Result<()>>,
+    ) {
+        let app = Router::new()
+            .route(
+                "/slow",
+                get(move || async move {
+                    tokio::time::sleep(request_time).await;
+                    "done"
+                }),
+            )
+            .layer(handle.in_flight_layer());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (signal_tx, signal_rx) = oneshot::channel::<()>();
+        let server = tokio::spawn(serve_with_graceful_shutdown(
+            listener,
+            app,
+            handle,
+            async move {
+                let _ = signal_rx.await;
+            },
+            drain_timeout,
+        ));
+        (addr, signal_tx, server)
+    }
+
+    /// Send a raw HTTP/1.1 request and read the full response
+    async fn request(addr: SocketAddr) -> io::Result<String> {
+        let mut stream = TcpStream::connect(addr).await?;
+        stream
+            .write_all(b"GET /slow HTTP/1.1\r\nHost: test\r\nConnection: close\r\n\r\n")
+            .await?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await?;
+        Ok(response)
+    }
+
+    async fn wait_for_in_flight(handle: &ShutdownHandle) {
+        while handle.in_flight_requests() == 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_request_completes_after_shutdown_signal() {
+        let handle = ShutdownHandle::new(Metrics::new());
+        let (addr, signal, server) = start_server(
+            handle.clone(),
+            Duration::from_secs(5),
+            Duration::from_millis(300),
+        )
+        .await;
+
+        let in_flight = tokio::spawn(request(addr));
+        wait_for_in_flight(&handle).await;
+        signal.send(()).unwrap();
+
+        let response = in_flight.await.unwrap().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+        assert!(response.ends_with("done"));
+
+        server.await.unwrap().unwrap();
+        assert!(handle.is_shutting_down());
+        assert_eq!(handle.in_flight_requests(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_new_connections_refused_after_shutdown() {
+        let handle = ShutdownHandle::new(Metrics::new());
+        let (addr, signal, server) = start_server(
+            handle.clone(),
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        signal.send(()).unwrap();
+        server.await.unwrap().unwrap();
+
+        assert!(request(addr).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_drain_window_is_bounded() {
+        let handle = ShutdownHandle::new(Metrics::new());
+        let (addr, signal, server) = start_server(
+            handle.clone(),
+            Duration::from_millis(100),
+            Duration::from_secs(30),
+        )
+        .await;
+
+        let _stuck = tokio::spawn(request(addr));
+        wait_for_in_flight(&handle).await;
+        signal.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(5), server)
+            .await
+            .expect("server should stop once the drain window elapses")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_websockets_to_close() {
+        let metrics = Metrics::new();
+        let handle = ShutdownHandle::new(metrics.clone());
+        let (_addr, signal, server) = start_server(
+            handle.clone(),
+            Duration::from_secs(5),
+            Duration::from_millis(10),
+        )
+        .await;
+
+        let connection = metrics.websocket_connected();
+        let closer = handle.clone();
+        tokio::spawn(async move {
+            closer.cancelled().await;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(connection);
+        });
+
+        signal.send(()).unwrap();
+        server.await.unwrap().unwrap();
+        assert_eq!(handle.websocket_connections(), 0);
+    }
+}
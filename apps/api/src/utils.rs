@@ -0,0 +1,91 @@
+//! Shared helpers used across services
+//!
+//! Small, dependency-free functions that don't belong to any one service.
+
+/// Truncate a string to at most `max_bytes` bytes without splitting a character
+///
+/// Cuts at the last UTF-8 character boundary at or below `max_bytes`, so a
+/// multi-byte character straddling the limit is dropped rather than causing a
+/// panic. Returns the whole string when it is within the limit.
+///
+/// # Example
+/// ```
+/// use resonance_api::utils::truncate_on_char_boundary;
+///
+/// assert_eq!(truncate_on_char_boundary("héllo", 2), "h");
+/// assert_eq!(truncate_on_char_boundary("hello", 10), "hello");
+/// ```
+pub fn truncate_on_char_boundary(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+
+    let mut cut = max_bytes;
+    while !s.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    &s[..cut]
+}
+
+/// Truncate a string like [`truncate_on_char_boundary`], appending `...` if
+/// anything was cut
+///
+/// The `...` comes on top of `max_bytes`.
+pub fn truncate_with_ellipsis(s: &str, max_bytes: usize) -> String {
+    let truncated = truncate_on_char_boundary(s, max_bytes);
+    if truncated.len() < s.len() {
+        format!("{}...", truncated)
+    } else {
+        truncated.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate_returns_whole_string_within_limit() {
+        assert_eq!(truncate_on_char_boundary("", 0), "");
+        assert_eq!(truncate_on_char_boundary("abc", 3), "abc");
+        assert_eq!(truncate_on_char_boundary("🎵音楽", 100), "🎵音楽");
+        assert_eq!(truncate_with_ellipsis("abc", 3), "abc");
+    }
+
+    #[test]
+    fn test_truncate_emoji_at_every_boundary() {
+        // Each emoji is 4 bytes
+        let s = "🎵🎶🎸";
+        let expected = [
+            "", "", "", "", "🎵", "🎵", "🎵", "🎵", "🎵🎶", "🎵🎶", "🎵🎶", "🎵🎶",
+        ];
+        for (max_bytes, want) in expected.iter().enumerate() {
+            assert_eq!(
+                truncate_on_char_boundary(s, max_bytes),
+                *want,
+                "{}",
+                max_bytes
+            );
+        }
+        assert_eq!(truncate_on_char_boundary(s, 12), s);
+    }
+
+    #[test]
+    fn test_truncate_cjk_mid_character() {
+        // Each character is 3 bytes
+        let s = "音楽を聴く";
+        assert_eq!(truncate_on_char_boundary(s, 7), "音楽");
+        assert_eq!(truncate_on_char_boundary(s, 8), "音楽");
+        assert_eq!(truncate_on_char_boundary(s, 9), "音楽を");
+        assert_eq!(truncate_with_ellipsis(s, 5), "音...");
+    }
+
+    #[test]
+    fn test_truncate_mixed_ascii_and_multibyte() {
+        let s = "ab🎵c";
+        assert_eq!(truncate_on_char_boundary(s, 3), "ab");
+        assert_eq!(truncate_on_char_boundary(s, 6), "ab🎵");
+        assert_eq!(truncate_with_ellipsis(s, 2), "ab...");
+        assert_eq!(truncate_with_ellipsis(s, 0), "...");
+    }
+}
@@ -3,7 +3,7 @@
 //! This module handles the chat-specific WebSocket messages,
 //! integrating with the ChatService for AI responses.
 
-use resonance_shared_config::OllamaConfig;
+use resonance_shared_config::{FeatureFlags, OllamaConfig};
 use sqlx::PgPool;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
@@ -20,8 +20,10 @@ use super::messages::{
     ServerMessage,
 };
 use crate::services::chat::{
-    ChatAction as ServiceChatAction, ChatError, ChatService, StreamEvent, UserContextBuilder,
+    ChatAction as ServiceChatAction, ChatConfig, ChatError, ChatService, StreamErrorCode,
+    StreamEvent, UserContextBuilder,
 };
+use crate::services::metrics::Metrics;
 use crate::services::search::SearchService;
 use crate::services::similarity::SimilarityService;
 use resonance_ollama_client::OllamaClient;
@@ -35,6 +37,21 @@ const MIN_MESSAGE_INTERVAL_SECS: u64 = 2;
 /// Channel capacity for pending chat messages
 const CHAT_CHANNEL_CAPACITY: usize = 4;
 
+/// Services and settings each connection's chat handler is built from
+///
+/// Registered as a single router extension so the WebSocket handler stays
+/// within axum's extractor limit.
+#[derive(Clone)]
+pub struct ChatServices {
+    pub ollama_config: OllamaConfig,
+    pub search_service: SearchService,
+    pub similarity_service: SimilarityService,
+    pub ollama_client: Option<OllamaClient>,
+    pub features: FeatureFlags,
+    /// Registry for Ollama latency and chat rate-limit rejections
+    pub metrics: Metrics,
+}
+
 /// Handles chat messages for a WebSocket connection
 pub struct ChatHandler {
     user_id: Uuid,
@@ -48,8 +65,12 @@ pub struct ChatHandler {
     message_count: Arc<AtomicU32>,
     /// Window start time for rate limiting
     window_start: Arc<Mutex<Instant>>,
+    /// Deployment feature flags (chat quotas)
+    features: FeatureFlags,
     /// Cancellation token for graceful shutdown when WebSocket disconnects
     cancellation_token: CancellationToken,
+    /// Registry for chat rate-limit rejections
+    metrics: Metrics,
 }
 
 impl ChatHandler {
@@ -64,7 +85,9 @@ impl ChatHandler {
     /// * `similarity_service` - Service for finding similar tracks
     /// * `ollama_client` - Optional Ollama client for embeddings
     /// * `connection_manager` - WebSocket connection manager
+    /// * `features` - Feature flags; `chat_quotas` enables per-user message quotas
     /// * `cancellation_token` - Token for graceful cancellation when connection closes
+    /// * `metrics` - Registry for Ollama latency and rate-limit rejections
     ///
     /// # Errors
     /// Returns `ChatError` if the chat service fails to initialize
@@ -78,7 +101,9 @@ impl ChatHandler {
         similarity_service: SimilarityService,
         ollama_client: Option<OllamaClient>,
         connection_manager: ConnectionManager,
+        features: FeatureFlags,
         cancellation_token: CancellationToken,
+        metrics: Metrics,
     ) -> Result<Self, ChatError> {
         let now = Instant::now();
         // Initialize last_message_time in the past so first message isn't rate-limited
@@ -92,18 +117,28 @@ impl ChatHandler {
                 search_service,
                 similarity_service,
                 ollama_client,
-            )?,
+            )?
+            .with_chat_config(ChatConfig::from_env())
+            .with_metrics(metrics.clone()),
             context_builder: UserContextBuilder::new(pool),
             connection_manager,
             last_message_time: Arc::new(Mutex::new(past)),
             message_count: Arc::new(AtomicU32::new(0)),
             window_start: Arc::new(Mutex::new(now)),
+            features,
             cancellation_token,
+            metrics,
         })
     }
 
     /// Check rate limit and return error payload if exceeded
+    ///
+    /// Always passes when the `chat_quotas` feature is disabled.
     async fn check_rate_limit(&self) -> Option<ChatErrorPayload> {
+        if !self.features.chat_quotas() {
+            return None;
+        }
+
         let now = Instant::now();
 
         // Check minimum interval between messages
@@ -172,6 +207,7 @@ impl ChatHandler {
                 device_id = %self.device_id,
                 "Chat message rate limited"
             );
+            self.metrics.record_rate_limit_rejection("chat");
             self.send_to_self(ServerMessage::ChatError(error_payload));
             return;
         }
@@ -274,11 +310,19 @@ impl ChatHandler {
                                         "Chat streaming error"
                                     );
 
-                                    let error_payload = ChatErrorPayload::new(
-                                        Some(conv_id),
-                                        format!("{:?}", code),
-                                        message,
-                                    );
+                                    let error_payload = match code {
+                                        StreamErrorCode::ModelLoading => {
+                                            ChatErrorPayload::ai_warming_up(Some(conv_id))
+                                        }
+                                        StreamErrorCode::Unavailable => {
+                                            ChatErrorPayload::ai_unavailable(Some(conv_id))
+                                        }
+                                        _ => ChatErrorPayload::new(
+                                            Some(conv_id),
+                                            format!("{:?}", code),
+                                            message,
+                                        ),
+                                    };
                                     self.send_to_self(ServerMessage::ChatError(error_payload));
                                     break;
                                 }
@@ -440,9 +484,10 @@ fn convert_action(action: ServiceChatAction) -> Option<ChatAction> {
 fn convert_chat_error(conversation_id: Option<Uuid>, error: ChatError) -> ChatErrorPayload {
     match error {
         ChatError::ConversationNotFound(id) => ChatErrorPayload::conversation_not_found(id),
-        ChatError::OllamaRequest(_) | ChatError::OllamaResponse(_) => {
-            ChatErrorPayload::ai_unavailable(conversation_id)
-        }
+        ChatError::OllamaRequest(_)
+        | ChatError::OllamaResponse(_)
+        | ChatError::Unavailable { .. } => ChatErrorPayload::ai_unavailable(conversation_id),
+        ChatError::ModelLoading => ChatErrorPayload::ai_warming_up(conversation_id),
         ChatError::InvalidInput(msg) => ChatErrorPayload::invalid_message(conversation_id, msg),
         ChatError::Timeout => ChatErrorPayload::new(
             conversation_id,
@@ -499,15 +544,11 @@ fn convert_chat_error(conversation_id: Option<Uuid>, error: ChatError) -> ChatEr
 ///
 /// # Errors
 /// Returns `ChatError` if the chat handler fails to initialize
-#[allow(clippy::too_many_arguments)]
 pub fn spawn_chat_handler(
     user_id: Uuid,
     device_id: String,
     pool: PgPool,
-    config: OllamaConfig,
-    search_service: SearchService,
-    similarity_service: SimilarityService,
-    ollama_client: Option<OllamaClient>,
+    services: ChatServices,
     connection_manager: ConnectionManager,
 ) -> Result<
     (
@@ -524,12 +565,14 @@ pub fn spawn_chat_handler(
         user_id,
         device_id.clone(),
         pool,
-        config,
-        search_service,
-        similarity_service,
-        ollama_client,
+        services.ollama_config,
+        services.search_service,
+        services.similarity_service,
+        services.ollama_client,
         connection_manager,
+        services.features,
         cancellation_token.clone(),
+        services.metrics,
     )?;
 
     let task_token = cancellation_token.clone();
@@ -9,8 +9,29 @@ use std::sync::Arc;
 use tokio::sync::mpsc;
 use uuid::Uuid;
 
+use serde::Serialize;
+
 use super::messages::{DevicePresence, DeviceType, PlaybackState, ServerMessage};
 
+/// Idle time after which a device is reported offline
+///
+/// Three missed heartbeats at the web client's 30s interval.
+pub const PRESENCE_STALE_AFTER_MS: i64 = 90_000;
+
+/// A user's device as shown in the "your devices" UI
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectedDevice {
+    pub device_id: String,
+    pub device_name: String,
+    pub device_type: DeviceType,
+    /// Last heartbeat or other activity (Unix ms)
+    pub last_heartbeat: i64,
+    /// Socket open and heard from within the stale threshold
+    pub online: bool,
+    /// Whether this device is controlling playback
+    pub is_active_playback: bool,
+}
+
 /// Handle for sending messages to a specific WebSocket connection
 #[derive(Debug)]
 pub struct ConnectionHandle {
@@ -313,6 +334,44 @@ impl ConnectionManager {
             .unwrap_or(false)
     }
 
+    /// List a user's devices for presence UIs
+    ///
+    /// Devices whose socket has closed or that have been idle longer than
+    /// `stale_after_ms` are reported offline until the reaper removes them.
+    /// The active playback device comes first, then devices by name.
+    pub fn connected_devices(&self, user_id: Uuid, stale_after_ms: i64) -> Vec<ConnectedDevice> {
+        let Some(state) = self.users.get(&user_id) else {
+            return Vec::new();
+        };
+        let now = chrono::Utc::now().timestamp_millis();
+
+        let mut devices: Vec<ConnectedDevice> = state
+            .connections
+            .iter()
+            .map(|entry| {
+                let handle = entry.value();
+                let last_heartbeat = handle.last_seen();
+                ConnectedDevice {
+                    device_id: handle.device_info.device_id.clone(),
+                    device_name: handle.device_info.device_name.clone(),
+                    device_type: handle.device_info.device_type,
+                    last_heartbeat,
+                    online: handle.is_alive() && now - last_heartbeat <= stale_after_ms,
+                    is_active_playback: state.active_device_id.as_deref()
+                        == Some(handle.device_info.device_id.as_str()),
+                }
+            })
+            .collect();
+
+        devices.sort_by(|a, b| {
+            b.is_active_playback
+                .cmp(&a.is_active_playback)
+                .then_with(|| a.device_name.cmp(&b.device_name))
+                .then_with(|| a.device_id.cmp(&b.device_id))
+        });
+        devices
+    }
+
     /// Get all device presences for a user (alias for get_device_presences)
     pub fn get_device_list(&self, user_id: Uuid) -> Vec<DevicePresence> {
         self.get_device_presences(user_id)
@@ -641,6 +700,80 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_connected_devices_reports_presence() {
+        let manager = ConnectionManager::new();
+        let user_id = Uuid::new_v4();
+        let device = |id: &str, name: &str, device_type| DeviceInfo {
+            device_id: id.to_string(),
+            device_name: name.to_string(),
+            device_type,
+            user_agent: None,
+        };
+
+        let (tx_phone, _rx_phone) = mpsc::unbounded_channel();
+        let (tx_laptop, _rx_laptop) = mpsc::unbounded_channel();
+        let (tx_tablet, rx_tablet) = mpsc::unbounded_channel();
+        manager.add_connection(
+            user_id,
+            "phone".to_string(),
+            tx_phone,
+            device("phone", "Phone", DeviceType::Mobile),
+        );
+        manager.add_connection(
+            user_id,
+            "laptop".to_string(),
+            tx_laptop,
+            device("laptop", "Laptop", DeviceType::Web),
+        );
+        manager.add_connection(
+            user_id,
+            "tablet".to_string(),
+            tx_tablet,
+            device("tablet", "Tablet", DeviceType::Tablet),
+        );
+        manager.set_active_device(user_id, "phone");
+
+        // Laptop missed its heartbeats; tablet's socket has closed
+        let stale_at = chrono::Utc::now().timestamp_millis() - PRESENCE_STALE_AFTER_MS - 1_000;
+        manager
+            .users
+            .get(&user_id)
+            .unwrap()
+            .connections
+            .get("laptop")
+            .unwrap()
+            .last_activity
+            .store(stale_at, Ordering::Relaxed);
+        drop(rx_tablet);
+
+        let devices = manager.connected_devices(user_id, PRESENCE_STALE_AFTER_MS);
+        let summary: Vec<_> = devices
+            .iter()
+            .map(|d| (d.device_id.as_str(), d.online, d.is_active_playback))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("phone", true, true),
+                ("laptop", false, false),
+                ("tablet", false, false),
+            ]
+        );
+        assert_eq!(devices[0].device_type, DeviceType::Mobile);
+        assert_eq!(devices[1].last_heartbeat, stale_at);
+
+        // Once reaped, stale devices drop out of the list entirely
+        manager.cleanup_stale_connections(PRESENCE_STALE_AFTER_MS);
+        let devices = manager.connected_devices(user_id, PRESENCE_STALE_AFTER_MS);
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].device_id, "phone");
+
+        assert!(manager
+            .connected_devices(Uuid::new_v4(), PRESENCE_STALE_AFTER_MS)
+            .is_empty());
+    }
+
     #[test]
     fn test_connection_manager_cleanup_stale_connections() {
         let manager = ConnectionManager::new();
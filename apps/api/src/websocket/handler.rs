@@ -1,35 +1,38 @@
 //! WebSocket upgrade handler with JWT authentication
 //!
 //! This module handles the WebSocket upgrade request and authenticates
-//! clients using JWT tokens passed via query parameter.
+//! clients using JWT tokens passed via query parameter. Inbound messages are
+//! checked against per-connection [`WsLimits`]; clients breaking them are
+//! disconnected.
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
         ConnectInfo, Extension, Query,
     },
     http::HeaderMap,
     response::Response,
 };
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{SinkExt, Stream, StreamExt};
 use serde::Deserialize;
 use sqlx::PgPool;
+use std::future::Future;
 use std::net::SocketAddr;
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 use uuid::Uuid;
 
-use resonance_shared_config::OllamaConfig;
-
 use crate::middleware::extract_client_ip;
 use crate::services::auth::AuthService;
-use crate::services::search::SearchService;
-use crate::services::similarity::SimilarityService;
-use resonance_ollama_client::OllamaClient;
+use crate::services::metrics::Metrics;
+use crate::shutdown::ShutdownHandle;
 
-use super::chat_handler::spawn_chat_handler;
+use super::chat_handler::{spawn_chat_handler, ChatServices};
 use super::connection::{ConnectionManager, DeviceInfo};
+use super::limits::{Inbound, LimitViolation, MessageLimiter, WsLimits};
 use super::messages::{ClientMessage, ConnectedPayload, DeviceType, ErrorPayload, ServerMessage};
 use super::pubsub::SyncPubSub;
+use super::resume::{ResumeState, ResumeStore};
 use super::sync::SyncHandler;
 
 /// Query parameters for WebSocket connection
@@ -45,8 +48,14 @@ pub struct WsQueryParams {
     /// Device type hint
     #[serde(default)]
     device_type: Option<String>,
+    /// Resume token from a previous connection's `Connected` message
+    #[serde(default)]
+    resume_token: Option<String>,
 }
 
+/// How long to wait for the close frame to be sent after a limit violation
+const VIOLATION_CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
 fn default_device_name() -> String {
     "Unknown Device".to_string()
 }
@@ -79,14 +88,19 @@ pub async fn ws_handler(
     Extension(auth_service): Extension<AuthService>,
     Extension(connection_manager): Extension<ConnectionManager>,
     Extension(pubsub): Extension<SyncPubSub>,
+    Extension(resume_store): Extension<ResumeStore>,
     Extension(pool): Extension<PgPool>,
-    Extension(ollama_config): Extension<OllamaConfig>,
-    Extension(search_service): Extension<SearchService>,
-    Extension(similarity_service): Extension<SimilarityService>,
-    Extension(ollama_client): Extension<Option<OllamaClient>>,
+    Extension(chat_services): Extension<ChatServices>,
+    Extension(metrics): Extension<Metrics>,
+    Extension(shutdown): Extension<ShutdownHandle>,
+    Extension(limits): Extension<WsLimits>,
     connect_info: Option<ConnectInfo<SocketAddr>>,
     headers: HeaderMap,
 ) -> Response {
+    let ws = ws
+        .max_message_size(limits.protocol_max_message_bytes())
+        .max_frame_size(limits.protocol_max_message_bytes());
+
     // Verify JWT token
     let claims = match auth_service.verify_access_token(&params.token) {
         Ok(claims) => claims,
@@ -118,13 +132,6 @@ pub async fn ws_handler(
 
     let user_id = claims.sub;
     let session_id = claims.sid;
-    let device_id = params.device_id.clone();
-    let device_name = params.device_name.clone();
-    let device_type = params
-        .device_type
-        .as_deref()
-        .and_then(|s| s.parse().ok())
-        .unwrap_or(DeviceType::Unknown);
 
     // Extract client IP for logging
     let client_ip = extract_client_ip(&headers, connect_info.as_ref());
@@ -135,12 +142,54 @@ pub async fn ws_handler(
         .and_then(|v| v.to_str().ok())
         .map(String::from);
 
+    // A valid resume token reclaims the previous connection's device;
+    // anything else falls back to a fresh connection
+    let resumed = match params.resume_token.as_deref() {
+        Some(token) => {
+            let state = resume_store.take(token, user_id).await;
+            if state.is_none() {
+                tracing::debug!(
+                    user_id = %user_id,
+                    device_id = %params.device_id,
+                    "Invalid or expired resume token, starting a fresh connection"
+                );
+            }
+            state
+        }
+        None => None,
+    };
+
+    let device_info = match &resumed {
+        Some(state) => state.device_info(user_agent),
+        None => {
+            // An explicit device type from the client wins over the user agent
+            let device_type = params
+                .device_type
+                .as_deref()
+                .and_then(|s| s.parse().ok())
+                .or_else(|| {
+                    user_agent
+                        .as_deref()
+                        .map(|ua| crate::models::DeviceType::from_user_agent(ua).into())
+                })
+                .unwrap_or(DeviceType::Unknown);
+
+            DeviceInfo {
+                device_id: params.device_id.clone(),
+                device_name: params.device_name.clone(),
+                device_type,
+                user_agent,
+            }
+        }
+    };
+
     tracing::info!(
         user_id = %user_id,
-        device_id = %device_id,
-        device_name = %device_name,
-        device_type = %device_type,
+        device_id = %device_info.device_id,
+        device_name = %device_info.device_name,
+        device_type = %device_info.device_type,
         client_ip = %client_ip,
+        resumed = resumed.is_some(),
         "WebSocket connection authenticated"
     );
 
@@ -150,19 +199,16 @@ pub async fn ws_handler(
             socket,
             user_id,
             session_id,
-            DeviceInfo {
-                device_id,
-                device_name,
-                device_type,
-                user_agent,
-            },
+            device_info,
+            resumed,
             connection_manager,
             pubsub,
+            resume_store,
             pool,
-            ollama_config,
-            search_service,
-            similarity_service,
-            ollama_client,
+            chat_services,
+            metrics,
+            shutdown,
+            limits,
         )
     })
 }
@@ -174,14 +220,18 @@ async fn handle_socket(
     user_id: Uuid,
     session_id: Uuid,
     device_info: DeviceInfo,
+    resumed: Option<ResumeState>,
     connection_manager: ConnectionManager,
     pubsub: SyncPubSub,
+    resume_store: ResumeStore,
     pool: PgPool,
-    ollama_config: OllamaConfig,
-    search_service: SearchService,
-    similarity_service: SimilarityService,
-    ollama_client: Option<OllamaClient>,
+    chat_services: ChatServices,
+    metrics: Metrics,
+    shutdown: ShutdownHandle,
+    limits: WsLimits,
 ) {
+    // Counts this connection as active until the handler returns
+    let _connection_guard = metrics.websocket_connected();
     let device_id = device_info.device_id.clone();
     let device_name = device_info.device_name.clone();
 
@@ -199,10 +249,7 @@ async fn handle_socket(
         user_id,
         device_id.clone(),
         pool.clone(),
-        ollama_config,
-        search_service,
-        similarity_service,
-        ollama_client,
+        chat_services,
         connection_manager.clone(),
     ) {
         Ok(result) => result,
@@ -238,14 +285,28 @@ async fn handle_socket(
         pool.clone(),
     );
 
+    // A resumed device reclaims active status unless another device took over
+    if let Some(state) = &resumed {
+        if sync_handler.handle_device_resumed(state.was_active).await {
+            tracing::info!(
+                user_id = %user_id,
+                device_id = %device_id,
+                "Resumed device restored as active device"
+            );
+        }
+    }
+
     // Get current state for the connecting device
     let active_device_id = connection_manager.get_active_device(user_id);
 
-    // Send connected message
+    // Send connected message with a token for resuming after a drop
+    let resume_token = ResumeStore::issue_token();
     let connected_msg = ServerMessage::Connected(ConnectedPayload {
         device_id: device_id.clone(),
         session_id,
         active_device_id: active_device_id.clone(),
+        resume_token: resume_token.clone(),
+        resumed: resumed.is_some(),
     });
 
     if let Ok(json) = serde_json::to_string(&connected_msg) {
@@ -285,11 +346,29 @@ async fn handle_socket(
     // Subscribe to Redis pub/sub for this user
     let mut pubsub_receiver = pubsub.subscribe(user_id).await;
 
+    // Close frame for a client that broke a connection limit
+    let (close_tx, mut close_rx) = oneshot::channel::<CloseFrame<'static>>();
+
     // Spawn task to forward messages from channel to WebSocket
     let device_id_clone = device_id.clone();
     let mut send_task = tokio::spawn(async move {
         loop {
             tokio::select! {
+                // Client broke a limit: tell it why before disconnecting
+                Ok(frame) = &mut close_rx => {
+                    let _ = ws_sender.send(Message::Close(Some(frame))).await;
+                    break;
+                }
+                // Server is shutting down: tell the client to reconnect elsewhere
+                _ = shutdown.cancelled() => {
+                    let close = Message::Close(Some(CloseFrame {
+                        code: close_code::AWAY,
+                        reason: "server shutting down".into(),
+                    }));
+                    let _ = ws_sender.send(close).await;
+                    tracing::debug!(device_id = %device_id_clone, "WebSocket closed for shutdown");
+                    break;
+                }
                 // Messages from internal channel (from other handlers)
                 Some(msg) = rx.recv() => {
                     match serde_json::to_string(&msg) {
@@ -342,93 +421,91 @@ async fn handle_socket(
     let device_id_recv = device_id.clone();
     let chat_tx_recv = chat_tx.clone();
     let mut recv_task = tokio::spawn(async move {
-        while let Some(result) = ws_receiver.next().await {
-            match result {
-                Ok(Message::Text(text)) => {
-                    match serde_json::from_str::<ClientMessage>(&text) {
-                        Ok(msg) => {
-                            // Route ChatSend messages to the chat handler
-                            if let ClientMessage::ChatSend(payload) = msg {
-                                match chat_tx_recv.try_send(payload) {
-                                    Ok(_) => {}
-                                    Err(mpsc::error::TrySendError::Full(_)) => {
-                                        tracing::warn!(
-                                            device_id = %device_id_recv,
-                                            "Chat message queue full, message dropped"
-                                        );
-                                        // Send error back to client
-                                        let error_msg = super::messages::ChatErrorPayload::new(
-                                            None,
-                                            "QUEUE_FULL",
-                                            "Too many pending messages. Please wait.",
-                                        );
-                                        sync_handler.send_to_device(
-                                            &device_id_recv,
-                                            ServerMessage::ChatError(error_msg),
-                                        );
-                                    }
-                                    Err(mpsc::error::TrySendError::Closed(_)) => {
-                                        tracing::warn!(
-                                            device_id = %device_id_recv,
-                                            "Chat handler channel closed"
-                                        );
-                                        // Notify client that chat is unavailable
-                                        let error_msg = super::messages::ChatErrorPayload::new(
-                                            None,
-                                            "CHAT_UNAVAILABLE",
-                                            "Chat service is temporarily unavailable. Please try again.",
-                                        );
-                                        sync_handler.send_to_device(
-                                            &device_id_recv,
-                                            ServerMessage::ChatError(error_msg),
-                                        );
-                                    }
+        let sync_handler = &sync_handler;
+        let chat_tx_recv = &chat_tx_recv;
+        let device_id_recv = &device_id_recv;
+        let violation = receive_messages(
+            &mut ws_receiver,
+            MessageLimiter::new(limits),
+            device_id_recv,
+            move |parsed| async move {
+                match parsed {
+                    Ok(msg) => {
+                        // Route ChatSend messages to the chat handler
+                        if let ClientMessage::ChatSend(payload) = msg {
+                            match chat_tx_recv.try_send(payload) {
+                                Ok(_) => {}
+                                Err(mpsc::error::TrySendError::Full(_)) => {
+                                    tracing::warn!(
+                                        device_id = %device_id_recv,
+                                        "Chat message queue full, message dropped"
+                                    );
+                                    // Send error back to client
+                                    let error_msg = super::messages::ChatErrorPayload::new(
+                                        None,
+                                        "QUEUE_FULL",
+                                        "Too many pending messages. Please wait.",
+                                    );
+                                    sync_handler.send_to_device(
+                                        device_id_recv,
+                                        ServerMessage::ChatError(error_msg),
+                                    );
                                 }
-                            } else {
-                                // All other messages go to sync handler
-                                if let Err(e) = sync_handler.handle_message(msg).await {
+                                Err(mpsc::error::TrySendError::Closed(_)) => {
                                     tracing::warn!(
-                                        error = %e,
                                         device_id = %device_id_recv,
-                                        "Error handling client message"
+                                        "Chat handler channel closed"
+                                    );
+                                    // Notify client that chat is unavailable
+                                    let error_msg = super::messages::ChatErrorPayload::new(
+                                        None,
+                                        "CHAT_UNAVAILABLE",
+                                        "Chat service is temporarily unavailable. Please try again.",
+                                    );
+                                    sync_handler.send_to_device(
+                                        device_id_recv,
+                                        ServerMessage::ChatError(error_msg),
                                     );
                                 }
                             }
-                        }
-                        Err(e) => {
-                            tracing::debug!(
-                                error = %e,
-                                device_id = %device_id_recv,
-                                "Failed to parse client message"
-                            );
-                            // Send error back to client
-                            let error_msg = ErrorPayload::invalid_message(e.to_string());
-                            sync_handler
-                                .send_to_device(&device_id_recv, ServerMessage::Error(error_msg));
+                        } else {
+                            // All other messages go to sync handler
+                            if let Err(e) = sync_handler.handle_message(msg).await {
+                                tracing::warn!(
+                                    error = %e,
+                                    device_id = %device_id_recv,
+                                    "Error handling client message"
+                                );
+                            }
                         }
                     }
+                    Err(e) => {
+                        tracing::debug!(
+                            error = %e,
+                            device_id = %device_id_recv,
+                            "Failed to parse client message"
+                        );
+                        // Send error back to client
+                        let error_msg = ErrorPayload::invalid_message(e.to_string());
+                        sync_handler
+                            .send_to_device(device_id_recv, ServerMessage::Error(error_msg));
+                    }
                 }
-                Ok(Message::Binary(_)) => {
-                    // Binary messages not supported for sync protocol
-                    tracing::debug!(device_id = %device_id_recv, "Received unsupported binary message");
-                }
-                Ok(Message::Ping(data)) => {
-                    // Pings are handled automatically by axum-ws
-                    tracing::trace!(device_id = %device_id_recv, "Received ping: {:?}", data);
-                }
-                Ok(Message::Pong(_)) => {
-                    // Update last seen time
-                    tracing::trace!(device_id = %device_id_recv, "Received pong");
-                }
-                Ok(Message::Close(_)) => {
-                    tracing::debug!(device_id = %device_id_recv, "WebSocket close received");
-                    break;
-                }
-                Err(e) => {
-                    tracing::debug!(error = %e, device_id = %device_id_recv, "WebSocket error");
-                    break;
-                }
+            },
+        )
+        .await;
+
+        match violation {
+            Some(violation) => {
+                tracing::warn!(
+                    user_id = %user_id,
+                    device_id = %device_id_recv,
+                    violation = %violation,
+                    "Closing WebSocket connection for exceeding limits"
+                );
+                close_tx.send(violation.close_frame()).is_ok()
             }
+            None => false,
         }
     });
 
@@ -438,9 +515,18 @@ async fn handle_socket(
             tracing::debug!(device_id = %device_id, "Send task completed");
             recv_task.abort();
         }
-        _ = &mut recv_task => {
+        closing = &mut recv_task => {
             tracing::debug!(device_id = %device_id, "Receive task completed");
-            send_task.abort();
+            // Give the send task a moment to deliver a limit violation's close frame
+            if matches!(closing, Ok(true))
+                && tokio::time::timeout(VIOLATION_CLOSE_TIMEOUT, &mut send_task)
+                    .await
+                    .is_ok()
+            {
+                tracing::debug!(device_id = %device_id, "Sent limit violation close frame");
+            } else {
+                send_task.abort();
+            }
         }
     }
 
@@ -473,6 +559,14 @@ async fn handle_socket(
     // Check if this device was active BEFORE removing connection (to avoid race condition)
     let was_active = connection_manager.get_active_device(user_id) == Some(device_id.clone());
 
+    // Let the device reclaim its identity and active status if it reconnects
+    resume_store
+        .save(
+            &resume_token,
+            &ResumeState::new(user_id, &device_info, was_active),
+        )
+        .await;
+
     // Clean up: remove connection
     connection_manager.remove_connection(user_id, &device_id);
 
@@ -495,6 +589,52 @@ async fn handle_socket(
     );
 }
 
+/// Read client messages until the connection closes or breaks a limit
+///
+/// Text messages within the limits are parsed and passed to `dispatch`.
+/// Returns the violation if the client has to be disconnected for breaking
+/// a limit, `None` if the connection closed normally.
+async fn receive_messages<S, F, Fut>(
+    receiver: &mut S,
+    mut limiter: MessageLimiter,
+    device_id: &str,
+    mut dispatch: F,
+) -> Option<LimitViolation>
+where
+    S: Stream<Item = Result<Message, axum::Error>> + Unpin,
+    F: FnMut(Result<ClientMessage, serde_json::Error>) -> Fut,
+    Fut: Future<Output = ()>,
+{
+    while let Some(result) = receiver.next().await {
+        let message = match result {
+            Ok(message) => message,
+            Err(e) => {
+                tracing::debug!(error = %e, device_id = %device_id, "WebSocket error");
+                return None;
+            }
+        };
+
+        match limiter.screen(message) {
+            Inbound::Message(msg) => dispatch(Ok(msg)).await,
+            Inbound::Invalid(e) => dispatch(Err(e)).await,
+            Inbound::Unsupported => {
+                // Binary messages not supported for sync protocol
+                tracing::debug!(device_id = %device_id, "Received unsupported binary message");
+            }
+            Inbound::Ignore => {
+                // Pings are answered automatically by axum-ws
+                tracing::trace!(device_id = %device_id, "Received control frame");
+            }
+            Inbound::Close => {
+                tracing::debug!(device_id = %device_id, "WebSocket close received");
+                return None;
+            }
+            Inbound::Violation(violation) => return Some(violation),
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -549,4 +689,136 @@ mod tests {
         assert_eq!(params.device_name, "Unknown Device");
         assert_eq!(params.device_type, None);
     }
+
+    // ========== Connection limit tests ==========
+
+    use axum::routing::get;
+    use axum::Router;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::Message as ClientFrame;
+    use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+    type Client = WebSocketStream<MaybeTlsStream<tokio::net::TcpStream>>;
+
+    /// Serve a socket that runs the limited receive loop, replying "ok" to
+    /// every dispatched message and closing on violations like `handle_socket`
+    async fn start_limited_server(limits: WsLimits) -> SocketAddr {
+        let app = Router::new().route(
+            "/ws",
+            get(move |ws: WebSocketUpgrade| async move {
+                ws.max_message_size(limits.protocol_max_message_bytes())
+                    .on_upgrade(move |socket| async move {
+                        let (sender, mut receiver) = socket.split();
+                        let sender = tokio::sync::Mutex::new(sender);
+                        let violation = receive_messages(
+                            &mut receiver,
+                            MessageLimiter::new(limits),
+                            "test-device",
+                            |_| async {
+                                let _ = sender.lock().await.send(Message::Text("ok".into())).await;
+                            },
+                        )
+                        .await;
+                        if let Some(violation) = violation {
+                            let _ = sender
+                                .lock()
+                                .await
+                                .send(Message::Close(Some(violation.close_frame())))
+                                .await;
+                        }
+                    })
+            }),
+        );
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        addr
+    }
+
+    async fn connect(addr: SocketAddr) -> Client {
+        let (client, _) = connect_async(format!("ws://{}/ws", addr)).await.unwrap();
+        client
+    }
+
+    /// Read frames until the server closes, returning the close code
+    async fn close_code_of(client: &mut Client) -> Option<CloseCode> {
+        let read = async {
+            while let Some(Ok(frame)) = client.next().await {
+                if let ClientFrame::Close(frame) = frame {
+                    return frame.map(|f| f.code);
+                }
+            }
+            None
+        };
+        tokio::time::timeout(Duration::from_secs(5), read)
+            .await
+            .expect("server should close the connection")
+    }
+
+    fn heartbeat() -> ClientFrame {
+        ClientFrame::Text(r#"{"type":"Heartbeat"}"#.into())
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_closes_connection() {
+        let limits = WsLimits {
+            max_message_bytes: 256,
+            ..WsLimits::default()
+        };
+        let addr = start_limited_server(limits).await;
+        let mut client = connect(addr).await;
+
+        let oversized = format!(
+            r#"{{"type":"TransferPlayback","payload":{{"target_device_id":"{}"}}}}"#,
+            "x".repeat(512)
+        );
+        client.send(ClientFrame::Text(oversized)).await.unwrap();
+
+        assert_eq!(close_code_of(&mut client).await, Some(CloseCode::Size));
+    }
+
+    #[tokio::test]
+    async fn test_message_flood_closes_connection() {
+        let limits = WsLimits {
+            messages_per_sec: 5,
+            burst: 10,
+            ..WsLimits::default()
+        };
+        let addr = start_limited_server(limits).await;
+        let mut client = connect(addr).await;
+
+        for _ in 0..50 {
+            let request = ClientFrame::Text(r#"{"type":"RequestDeviceList"}"#.into());
+            if client.send(request).await.is_err() {
+                break;
+            }
+        }
+
+        assert_eq!(close_code_of(&mut client).await, Some(CloseCode::Policy));
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_are_not_rate_limited() {
+        let limits = WsLimits {
+            messages_per_sec: 1,
+            burst: 1,
+            ..WsLimits::default()
+        };
+        let addr = start_limited_server(limits).await;
+        let mut client = connect(addr).await;
+
+        for _ in 0..50 {
+            client.send(heartbeat()).await.unwrap();
+        }
+        for _ in 0..50 {
+            let reply = tokio::time::timeout(Duration::from_secs(5), client.next())
+                .await
+                .expect("server should answer every heartbeat")
+                .unwrap()
+                .unwrap();
+            assert_eq!(reply, ClientFrame::Text("ok".into()));
+        }
+    }
 }
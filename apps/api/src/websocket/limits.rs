@@ -0,0 +1,300 @@
+//! Per-connection limits on inbound WebSocket messages
+//!
+//! Each sync connection may send messages up to a maximum size, at a bounded
+//! rate (a token bucket refilled at `messages_per_sec` up to `burst`). A client
+//! breaking either limit is disconnected: oversized messages close the
+//! connection with 1009 (message too big), floods with 1008 (policy
+//! violation). Heartbeats and ping/pong frames never count against the rate,
+//! so an idle client keeping its connection alive cannot trip the limit.
+
+use std::fmt;
+use std::time::Instant;
+
+use axum::extract::ws::{close_code, CloseFrame, Message};
+
+use super::messages::ClientMessage;
+use crate::config::{
+    Config, DEFAULT_WS_MAX_MESSAGE_BYTES, DEFAULT_WS_MESSAGES_PER_SEC, DEFAULT_WS_MESSAGE_BURST,
+};
+
+/// Multiple of the message limit at which the protocol layer drops frames
+///
+/// Messages between the limit and this ceiling are read and rejected with a
+/// proper close frame; anything larger is refused before being buffered.
+const PROTOCOL_SIZE_FACTOR: usize = 4;
+
+/// Limits applied to every sync connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WsLimits {
+    /// Maximum size of a text or binary message in bytes
+    pub max_message_bytes: usize,
+    /// Sustained messages per second (0 disables rate limiting)
+    pub messages_per_sec: u32,
+    /// Messages allowed in a burst above the sustained rate
+    pub burst: u32,
+}
+
+impl Default for WsLimits {
+    fn default() -> Self {
+        Self {
+            max_message_bytes: DEFAULT_WS_MAX_MESSAGE_BYTES,
+            messages_per_sec: DEFAULT_WS_MESSAGES_PER_SEC,
+            burst: DEFAULT_WS_MESSAGE_BURST,
+        }
+    }
+}
+
+impl From<&Config> for WsLimits {
+    fn from(config: &Config) -> Self {
+        Self {
+            max_message_bytes: config.ws_max_message_bytes,
+            messages_per_sec: config.ws_messages_per_sec,
+            burst: config.ws_message_burst,
+        }
+    }
+}
+
+impl WsLimits {
+    /// Size above which the WebSocket protocol layer refuses a message outright
+    pub fn protocol_max_message_bytes(&self) -> usize {
+        self.max_message_bytes.saturating_mul(PROTOCOL_SIZE_FACTOR)
+    }
+}
+
+/// A client exceeded one of the connection limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitViolation {
+    /// Message larger than `max_message_bytes`
+    TooLarge { size: usize, max: usize },
+    /// Messages sent faster than the rate limit allows
+    RateLimited,
+}
+
+impl LimitViolation {
+    /// Close frame telling the client why it is being disconnected
+    pub fn close_frame(&self) -> CloseFrame<'static> {
+        match self {
+            Self::TooLarge { .. } => CloseFrame {
+                code: close_code::SIZE,
+                reason: "message too large".into(),
+            },
+            Self::RateLimited => CloseFrame {
+                code: close_code::POLICY,
+                reason: "rate limit exceeded".into(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooLarge { size, max } => {
+                write!(
+                    f,
+                    "message of {} bytes exceeds limit of {} bytes",
+                    size, max
+                )
+            }
+            Self::RateLimited => write!(f, "message rate limit exceeded"),
+        }
+    }
+}
+
+/// What the receive loop should do with an inbound frame
+#[derive(Debug)]
+pub enum Inbound {
+    /// A client message to dispatch
+    Message(ClientMessage),
+    /// A text message that is not a valid client message
+    Invalid(serde_json::Error),
+    /// A binary message (not part of the sync protocol)
+    Unsupported,
+    /// A control frame with nothing to do
+    Ignore,
+    /// The client closed the connection
+    Close,
+    /// The client broke a limit and must be disconnected
+    Violation(LimitViolation),
+}
+
+/// Tracks one connection's inbound messages against its limits
+#[derive(Debug)]
+pub struct MessageLimiter {
+    limits: WsLimits,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl MessageLimiter {
+    /// Create a limiter starting with a full burst allowance
+    pub fn new(limits: WsLimits) -> Self {
+        Self {
+            limits,
+            tokens: f64::from(limits.burst.max(1)),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Check an inbound frame against the limits and classify it
+    pub fn screen(&mut self, message: Message) -> Inbound {
+        match message {
+            Message::Text(text) => {
+                if let Err(violation) = self.check_size(text.len()) {
+                    return Inbound::Violation(violation);
+                }
+                let parsed = serde_json::from_str::<ClientMessage>(&text);
+                if !matches!(parsed, Ok(ClientMessage::Heartbeat)) {
+                    if let Err(violation) = self.check_rate() {
+                        return Inbound::Violation(violation);
+                    }
+                }
+                match parsed {
+                    Ok(message) => Inbound::Message(message),
+                    Err(e) => Inbound::Invalid(e),
+                }
+            }
+            Message::Binary(data) => {
+                match self.check_size(data.len()).and_then(|()| self.check_rate()) {
+                    Ok(()) => Inbound::Unsupported,
+                    Err(violation) => Inbound::Violation(violation),
+                }
+            }
+            Message::Ping(_) | Message::Pong(_) => Inbound::Ignore,
+            Message::Close(_) => Inbound::Close,
+        }
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), LimitViolation> {
+        if size > self.limits.max_message_bytes {
+            return Err(LimitViolation::TooLarge {
+                size,
+                max: self.limits.max_message_bytes,
+            });
+        }
+        Ok(())
+    }
+
+    /// Take one token from the bucket
+    fn check_rate(&mut self) -> Result<(), LimitViolation> {
+        if self.limits.messages_per_sec == 0 {
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.tokens = (self.tokens + elapsed * f64::from(self.limits.messages_per_sec))
+            .min(f64::from(self.limits.burst.max(1)));
+
+        if self.tokens < 1.0 {
+            return Err(LimitViolation::RateLimited);
+        }
+        self.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(json: &str) -> Message {
+        Message::Text(json.to_string())
+    }
+
+    fn limits(max_message_bytes: usize, messages_per_sec: u32, burst: u32) -> WsLimits {
+        WsLimits {
+            max_message_bytes,
+            messages_per_sec,
+            burst,
+        }
+    }
+
+    #[test]
+    fn test_oversized_message_is_a_violation() {
+        let mut limiter = MessageLimiter::new(limits(32, 10, 10));
+        let big = format!(
+            r#"{{"type":"Seek","payload":{{"position_ms":{}}}}}"#,
+            "1".repeat(40)
+        );
+
+        let inbound = limiter.screen(text(&big));
+
+        assert!(matches!(
+            inbound,
+            Inbound::Violation(LimitViolation::TooLarge { max: 32, .. })
+        ));
+        assert!(matches!(
+            limiter.screen(Message::Binary(vec![0; 33])),
+            Inbound::Violation(LimitViolation::TooLarge { size: 33, .. })
+        ));
+    }
+
+    #[test]
+    fn test_burst_then_rate_limited() {
+        let mut limiter = MessageLimiter::new(limits(1024, 1, 3));
+
+        for _ in 0..3 {
+            assert!(matches!(
+                limiter.screen(text(r#"{"type":"RequestDeviceList"}"#)),
+                Inbound::Message(ClientMessage::RequestDeviceList)
+            ));
+        }
+        assert!(matches!(
+            limiter.screen(text(r#"{"type":"RequestDeviceList"}"#)),
+            Inbound::Violation(LimitViolation::RateLimited)
+        ));
+        // Unparseable messages count too
+        let mut limiter = MessageLimiter::new(limits(1024, 1, 1));
+        assert!(matches!(
+            limiter.screen(text("garbage")),
+            Inbound::Invalid(_)
+        ));
+        assert!(matches!(
+            limiter.screen(text("garbage")),
+            Inbound::Violation(LimitViolation::RateLimited)
+        ));
+    }
+
+    #[test]
+    fn test_heartbeats_and_control_frames_are_exempt() {
+        let mut limiter = MessageLimiter::new(limits(1024, 1, 1));
+
+        for _ in 0..100 {
+            assert!(matches!(
+                limiter.screen(text(r#"{"type":"Heartbeat"}"#)),
+                Inbound::Message(ClientMessage::Heartbeat)
+            ));
+            assert!(matches!(
+                limiter.screen(Message::Ping(vec![1])),
+                Inbound::Ignore
+            ));
+        }
+        assert!(matches!(
+            limiter.screen(text(r#"{"type":"RequestDeviceList"}"#)),
+            Inbound::Message(_)
+        ));
+    }
+
+    #[test]
+    fn test_zero_rate_disables_rate_limit() {
+        let mut limiter = MessageLimiter::new(limits(1024, 0, 0));
+        for _ in 0..1000 {
+            assert!(matches!(
+                limiter.screen(text(r#"{"type":"RequestDeviceList"}"#)),
+                Inbound::Message(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_close_frames() {
+        let too_large = LimitViolation::TooLarge { size: 2, max: 1 }.close_frame();
+        assert_eq!(too_large.code, close_code::SIZE);
+        assert_eq!(
+            LimitViolation::RateLimited.close_frame().code,
+            close_code::POLICY
+        );
+    }
+}
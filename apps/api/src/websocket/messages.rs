@@ -110,6 +110,11 @@ pub struct ConnectedPayload {
     pub session_id: Uuid,
     /// Current active device (if any)
     pub active_device_id: Option<String>,
+    /// Single-use token for resuming this device's session after a reconnect
+    pub resume_token: String,
+    /// Whether this connection resumed a previous session
+    #[serde(default)]
+    pub resumed: bool,
 }
 
 /// Payload for Error message
@@ -303,6 +308,19 @@ impl std::fmt::Display for DeviceType {
     }
 }
 
+impl From<crate::models::DeviceType> for DeviceType {
+    fn from(device_type: crate::models::DeviceType) -> Self {
+        use crate::models::DeviceType as Detected;
+        match device_type {
+            Detected::Desktop => DeviceType::Desktop,
+            Detected::Mobile => DeviceType::Mobile,
+            Detected::Tablet => DeviceType::Tablet,
+            Detected::Web => DeviceType::Web,
+            Detected::Tv => DeviceType::Unknown,
+        }
+    }
+}
+
 impl std::str::FromStr for DeviceType {
     type Err = ();
 
@@ -451,6 +469,14 @@ impl ChatErrorPayload {
         )
     }
 
+    pub fn ai_warming_up(conversation_id: Option<Uuid>) -> Self {
+        Self::new(
+            conversation_id,
+            "AI_WARMING_UP",
+            "AI model is warming up. Please try again shortly.",
+        )
+    }
+
     pub fn rate_limited(conversation_id: Option<Uuid>, retry_after: u64) -> Self {
         Self::new(
             conversation_id,
@@ -553,6 +579,8 @@ mod tests {
             device_id: "device-1".into(),
             session_id: Uuid::nil(),
             active_device_id: None,
+            resume_token: "token-1".into(),
+            resumed: false,
         });
 
         let json = serde_json::to_string(&msg).unwrap();
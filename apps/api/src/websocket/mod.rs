@@ -25,6 +25,17 @@
 //! WebSocket connections are authenticated via JWT token passed as a query parameter:
 //! `wss://api.example.com/ws/sync?token=<jwt>&device_id=<id>&device_name=<name>`
 //!
+//! # Reconnection
+//!
+//! The `Connected` message carries a single-use `resume_token`. A client that
+//! reconnects shortly after a drop can add `&resume_token=<token>` to reclaim
+//! its device identity and active-device status (see [`resume`]).
+//!
+//! # Limits
+//!
+//! Inbound messages are capped in size and rate per connection (see
+//! [`limits`]); abusive clients are disconnected with a close code.
+//!
 //! # Message Protocol
 //!
 //! See [`messages`] module for the full message type definitions.
@@ -64,11 +75,17 @@
 pub mod chat_handler;
 pub mod connection;
 pub mod handler;
+pub mod limits;
 pub mod messages;
 pub mod presence;
 pub mod pubsub;
+pub mod resume;
 pub mod sync;
 
+pub use chat_handler::ChatServices;
 pub use connection::ConnectionManager;
 pub use handler::ws_handler;
+pub use limits::WsLimits;
+pub use presence::connected_devices_handler;
 pub use pubsub::SyncPubSub;
+pub use resume::ResumeStore;
@@ -4,11 +4,33 @@
 //! - Tracking which devices are online
 //! - Current playback state per device
 //! - Last activity timestamps for stale connection cleanup
+//! - The `GET /devices/connected` endpoint backing the presence UI
 
+use axum::{Extension, Json};
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 
+use crate::middleware::AuthUser;
+
+use super::connection::{ConnectedDevice, ConnectionManager, PRESENCE_STALE_AFTER_MS};
 use super::messages::{DevicePresence, DeviceType, PlaybackState, TrackSummary};
 
+/// Response body for `GET /devices/connected`
+#[derive(Debug, Serialize)]
+pub struct ConnectedDevicesResponse {
+    pub devices: Vec<ConnectedDevice>,
+}
+
+/// List the authenticated user's devices and whether each is online
+pub async fn connected_devices_handler(
+    auth: AuthUser,
+    Extension(manager): Extension<ConnectionManager>,
+) -> Json<ConnectedDevicesResponse> {
+    Json(ConnectedDevicesResponse {
+        devices: manager.connected_devices(auth.user.id, PRESENCE_STALE_AFTER_MS),
+    })
+}
+
 /// Presence information for a single device
 #[derive(Debug, Clone)]
 pub struct DevicePresenceInfo {
@@ -0,0 +1,286 @@
+//! Resume tokens for reconnecting devices
+//!
+//! Every `Connected` handshake carries a fresh, single-use resume token. When
+//! the socket closes, the device's identity and whether it was the active
+//! device are saved under that token for [`RESUME_TOKEN_TTL`]. A client that
+//! reconnects within the window passes `resume_token=<token>` and gets its
+//! prior device identity back, along with active-device status if no other
+//! device has taken over in the meantime.
+//!
+//! Snapshots live in Redis so a device can resume on any instance, with an
+//! in-memory fallback for single-instance mode. Unknown, expired, already
+//! used or foreign tokens are ignored and the client gets a fresh connection.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use redis::AsyncCommands;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::connection::DeviceInfo;
+use super::messages::DeviceType;
+
+/// How long a disconnected device may resume its session
+pub const RESUME_TOKEN_TTL: Duration = Duration::from_secs(120);
+
+/// Redis key prefix for resume snapshots
+const RESUME_KEY_PREFIX: &str = "ws:resume:";
+
+/// Device state saved when a connection closes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResumeState {
+    pub user_id: Uuid,
+    pub device_id: String,
+    pub device_name: String,
+    pub device_type: DeviceType,
+    /// Whether the device was controlling playback when it disconnected
+    pub was_active: bool,
+}
+
+impl ResumeState {
+    /// Snapshot a device's state at disconnect
+    pub fn new(user_id: Uuid, device_info: &DeviceInfo, was_active: bool) -> Self {
+        Self {
+            user_id,
+            device_id: device_info.device_id.clone(),
+            device_name: device_info.device_name.clone(),
+            device_type: device_info.device_type,
+            was_active,
+        }
+    }
+
+    /// Device info for the resumed connection
+    pub fn device_info(&self, user_agent: Option<String>) -> DeviceInfo {
+        DeviceInfo {
+            device_id: self.device_id.clone(),
+            device_name: self.device_name.clone(),
+            device_type: self.device_type,
+            user_agent,
+        }
+    }
+}
+
+/// Store for resume snapshots with Redis + in-memory fallback
+#[derive(Clone)]
+pub struct ResumeStore {
+    inner: Arc<ResumeStoreInner>,
+    ttl: Duration,
+}
+
+enum ResumeStoreInner {
+    /// Redis-backed store shared by all instances
+    Redis(redis::Client),
+    /// In-memory store for single-instance mode
+    InMemory(Mutex<HashMap<String, (ResumeState, Instant)>>),
+}
+
+impl ResumeStore {
+    /// Create a Redis-backed store
+    pub fn new_with_redis(client: redis::Client) -> Self {
+        Self {
+            inner: Arc::new(ResumeStoreInner::Redis(client)),
+            ttl: RESUME_TOKEN_TTL,
+        }
+    }
+
+    /// Create an in-memory store (single instance mode)
+    pub fn new_in_memory() -> Self {
+        Self {
+            inner: Arc::new(ResumeStoreInner::InMemory(Mutex::new(HashMap::new()))),
+            ttl: RESUME_TOKEN_TTL,
+        }
+    }
+
+    /// Set how long snapshots stay resumable
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Generate a new unguessable resume token
+    pub fn issue_token() -> String {
+        Uuid::new_v4().simple().to_string()
+    }
+
+    /// Save a device's state under `token` until the TTL elapses
+    ///
+    /// Failures are logged; the device just won't be able to resume.
+    pub async fn save(&self, token: &str, state: &ResumeState) {
+        match &*self.inner {
+            ResumeStoreInner::Redis(client) => {
+                let json = match serde_json::to_string(state) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to serialize resume state");
+                        return;
+                    }
+                };
+                let result: Result<(), redis::RedisError> = async {
+                    let mut conn = client.get_multiplexed_async_connection().await?;
+                    conn.set_ex(resume_key(token), json, self.ttl.as_secs().max(1))
+                        .await
+                }
+                .await;
+                if let Err(e) = result {
+                    tracing::warn!(error = %e, "Failed to store WebSocket resume state");
+                }
+            }
+            ResumeStoreInner::InMemory(states) => {
+                let now = Instant::now();
+                let mut states = states.lock().unwrap_or_else(|e| e.into_inner());
+                states.retain(|_, (_, expires_at)| *expires_at > now);
+                states.insert(token.to_string(), (state.clone(), now + self.ttl));
+            }
+        }
+    }
+
+    /// Redeem a token for `user_id`, consuming it
+    ///
+    /// Returns `None` for unknown, expired or already redeemed tokens, and
+    /// for tokens issued to another user.
+    pub async fn take(&self, token: &str, user_id: Uuid) -> Option<ResumeState> {
+        let state = match &*self.inner {
+            ResumeStoreInner::Redis(client) => {
+                let result: Result<Option<String>, redis::RedisError> = async {
+                    let mut conn = client.get_multiplexed_async_connection().await?;
+                    redis::cmd("GETDEL")
+                        .arg(resume_key(token))
+                        .query_async(&mut conn)
+                        .await
+                }
+                .await;
+                match result {
+                    Ok(json) => json.and_then(|json| serde_json::from_str(&json).ok()),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Failed to load WebSocket resume state");
+                        None
+                    }
+                }
+            }
+            ResumeStoreInner::InMemory(states) => {
+                let mut states = states.lock().unwrap_or_else(|e| e.into_inner());
+                states
+                    .remove(token)
+                    .filter(|(_, expires_at)| *expires_at > Instant::now())
+                    .map(|(state, _)| state)
+            }
+        }?;
+
+        (state.user_id == user_id).then_some(state)
+    }
+}
+
+fn resume_key(token: &str) -> String {
+    format!("{}{}", RESUME_KEY_PREFIX, token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::websocket::connection::ConnectionManager;
+    use crate::websocket::messages::SyncEvent;
+    use crate::websocket::pubsub::SyncPubSub;
+    use crate::websocket::sync::SyncHandler;
+    use tokio::sync::mpsc;
+
+    fn device(device_id: &str) -> DeviceInfo {
+        DeviceInfo::new(
+            device_id.to_string(),
+            Some("Living Room".to_string()),
+            Some("desktop".to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_and_resume_restores_active_device() {
+        let user_id = Uuid::new_v4();
+        let manager = ConnectionManager::new();
+        let pubsub = SyncPubSub::new_in_memory();
+        let store = ResumeStore::new_in_memory();
+        let handler =
+            SyncHandler::new(user_id, "speaker-1".into(), manager.clone(), pubsub.clone());
+
+        // Active device connects, then its socket drops
+        let (tx, _rx) = mpsc::unbounded_channel();
+        manager.add_connection(user_id, "speaker-1".into(), tx, device("speaker-1"));
+        manager.set_active_device(user_id, "speaker-1");
+        let token = ResumeStore::issue_token();
+
+        let was_active = manager.get_active_device(user_id).as_deref() == Some("speaker-1");
+        store
+            .save(
+                &token,
+                &ResumeState::new(user_id, &device("speaker-1"), was_active),
+            )
+            .await;
+        manager.remove_connection(user_id, "speaker-1");
+        handler.handle_device_disconnected(was_active).await;
+        assert_eq!(manager.get_active_device(user_id), None);
+
+        // Reconnect with the token
+        let mut events = pubsub.subscribe(user_id).await;
+        let state = store.take(&token, user_id).await.expect("token is valid");
+        let (tx, _rx) = mpsc::unbounded_channel();
+        manager.add_connection(
+            user_id,
+            state.device_id.clone(),
+            tx,
+            state.device_info(None),
+        );
+        assert!(handler.handle_device_resumed(state.was_active).await);
+
+        assert_eq!(state.device_id, "speaker-1");
+        assert_eq!(state.device_name, "Living Room");
+        assert_eq!(manager.get_active_device(user_id), Some("speaker-1".into()));
+        assert!(matches!(
+            events.try_recv(),
+            Ok(SyncEvent::ActiveDeviceChanged {
+                previous_device_id: None,
+                new_device_id: Some(ref id),
+            }) if id == "speaker-1"
+        ));
+
+        // Tokens are single use
+        assert!(store.take(&token, user_id).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resume_does_not_take_over_new_active_device() {
+        let user_id = Uuid::new_v4();
+        let manager = ConnectionManager::new();
+        let handler = SyncHandler::new(
+            user_id,
+            "speaker-1".into(),
+            manager.clone(),
+            SyncPubSub::new_in_memory(),
+        );
+
+        // Another device took over while speaker-1 was away
+        let (tx, _rx) = mpsc::unbounded_channel();
+        manager.add_connection(user_id, "phone".into(), tx, device("phone"));
+        manager.set_active_device(user_id, "phone");
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        manager.add_connection(user_id, "speaker-1".into(), tx, device("speaker-1"));
+        assert!(!handler.handle_device_resumed(true).await);
+        assert_eq!(manager.get_active_device(user_id), Some("phone".into()));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_resume_tokens_are_rejected() {
+        let user_id = Uuid::new_v4();
+        let state = ResumeState::new(user_id, &device("speaker-1"), true);
+
+        let store = ResumeStore::new_in_memory();
+        store.save("token", &state).await;
+        assert!(store.take("unknown", user_id).await.is_none());
+        // Another user's token is rejected (and burned)
+        assert!(store.take("token", Uuid::new_v4()).await.is_none());
+
+        let expired = ResumeStore::new_in_memory().with_ttl(Duration::ZERO);
+        expired.save("token", &state).await;
+        assert!(expired.take("token", user_id).await.is_none());
+    }
+}
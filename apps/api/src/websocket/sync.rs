@@ -345,6 +345,31 @@ impl SyncHandler {
         self.pubsub.publish(self.user_id, event).await;
     }
 
+    /// Restore active-device status for a device resuming its session
+    ///
+    /// The device becomes active again only if it was active when it
+    /// disconnected and no other device has taken over since. Returns whether
+    /// it was restored.
+    pub async fn handle_device_resumed(&self, was_active: bool) -> bool {
+        if !was_active
+            || self
+                .connection_manager
+                .get_active_device(self.user_id)
+                .is_some()
+        {
+            return false;
+        }
+
+        self.connection_manager
+            .set_active_device(self.user_id, &self.device_id);
+        let change_event = SyncEvent::ActiveDeviceChanged {
+            previous_device_id: None,
+            new_device_id: Some(self.device_id.clone()),
+        };
+        self.pubsub.publish(self.user_id, change_event).await;
+        true
+    }
+
     /// Handle a device disconnection event
     ///
     /// The `was_active` parameter indicates whether this device was the active device
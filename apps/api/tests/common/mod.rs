@@ -5,10 +5,13 @@
 
 #![allow(unused_imports)]
 
+#[macro_use]
+pub mod database;
 pub mod fixtures;
 pub mod helpers;
 pub mod similarity_fixtures;
 
+pub use database::*;
 pub use fixtures::*;
 pub use helpers::*;
 pub use similarity_fixtures::*;
@@ -5,6 +5,7 @@
 //! - Range request handling (full file, partial range, suffix range)
 //! - ETag/caching (If-None-Match, If-Modified-Since)
 //! - Transcoding options (format, bitrate validation)
+//! - Raw streaming of allowlisted original formats
 //! - Path traversal security (../, absolute paths outside library)
 //!
 //! # Note
@@ -42,6 +43,8 @@ use uuid::Uuid;
 
 use resonance_api::error::{ApiError, ApiResult};
 use resonance_api::models::{AudioFormat, Track};
+use resonance_api::routes::streaming::{check_raw_stream, DEFAULT_RAW_STREAM_FORMATS};
+use resonance_api::services::{TranscodeFormat, TranscodeOptions};
 
 // ========== Test Configuration ==========
 
@@ -127,11 +130,7 @@ struct TestAppState {
 
 // ========== Streaming Handler (simplified for testing) ==========
 
-#[derive(Debug, Deserialize, Default)]
-struct TranscodeQuery {
-    format: Option<String>,
-    bitrate: Option<u32>,
-}
+type TranscodeQuery = resonance_api::routes::streaming::TranscodeQuery;
 
 /// Check Authorization header and return error if invalid
 fn validate_auth(headers: &axum::http::HeaderMap) -> ApiResult<()> {
@@ -180,14 +179,23 @@ async fn stream_track(
     let file_path = validate_file_path(&track.file_path, &state.music_library_path).await?;
 
     // 4. Validate transcoding parameters
-    if transcode_query.bitrate.is_some() && transcode_query.format.is_none() {
+    let raw = transcode_query.wants_raw()?;
+    if raw {
+        let transcodable = [
+            TranscodeFormat::Mp3,
+            TranscodeFormat::Aac,
+            TranscodeFormat::Opus,
+            TranscodeFormat::Flac,
+        ];
+        check_raw_stream(track.file_format, DEFAULT_RAW_STREAM_FORMATS, &transcodable)?;
+    } else if transcode_query.bitrate.is_some() && transcode_query.format.is_none() {
         return Err(ApiError::ValidationError(
             "`bitrate` requires `format` parameter".to_string(),
         ));
     }
 
     // 5. Handle transcoding request
-    if let Some(format_str) = &transcode_query.format {
+    if let Some(format_str) = transcode_query.format.as_ref().filter(|_| !raw) {
         // Reject Range requests for transcoding
         if headers.get(header::RANGE).is_some() {
             return Err(ApiError::InvalidRange(
@@ -195,34 +203,17 @@ async fn stream_track(
             ));
         }
 
-        // Validate format
-        let valid_formats = ["mp3", "aac", "opus", "flac"];
-        if !valid_formats.contains(&format_str.as_str()) {
-            return Err(ApiError::ValidationError(format!(
-                "Unsupported format: {}",
-                format_str
-            )));
-        }
-
-        // Validate bitrate if provided
+        // Validate format and bitrate with the real transcoder rules
+        let format = TranscodeFormat::parse(format_str).ok_or_else(|| {
+            ApiError::ValidationError(format!("Unsupported format: {}", format_str))
+        })?;
         if let Some(bitrate) = transcode_query.bitrate {
-            let valid_bitrates = [64, 96, 128, 192, 256, 320];
-            if !valid_bitrates.contains(&bitrate) {
-                return Err(ApiError::ValidationError(format!(
-                    "Unsupported bitrate: {}",
-                    bitrate
-                )));
-            }
+            TranscodeOptions::with_bitrate(format, bitrate)
+                .map_err(|e| ApiError::ValidationError(e.to_string()))?;
         }
 
         // Return mock transcoded response
-        let content_type = match format_str.as_str() {
-            "mp3" => "audio/mpeg",
-            "aac" => "audio/aac",
-            "opus" => "audio/opus",
-            "flac" => "audio/flac",
-            _ => "application/octet-stream",
-        };
+        let content_type = format.content_type();
 
         return Ok(Response::builder()
             .status(StatusCode::OK)
@@ -1088,6 +1079,36 @@ async fn test_transcode_rejects_invalid_format() {
     assert!(body["message"].as_str().unwrap().contains("format"));
 }
 
+#[tokio::test]
+async fn test_transcode_rejects_out_of_range_bitrate() {
+    let (state, temp_dir, track_repo) = create_test_state().await;
+
+    let audio_content = b"test_audio";
+    create_test_audio_file(&temp_dir, "test.flac", audio_content);
+
+    let track = create_test_track(test_track_id(), "test.flac");
+    track_repo.add_track(track).await;
+
+    let app = create_test_app(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/{}?format=mp3&bitrate=9999", test_track_id()))
+                .header(header::AUTHORIZATION, "Bearer valid_token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = parse_body(response).await;
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    assert!(body["message"].as_str().unwrap().contains("8-320 kbps"));
+}
+
 #[tokio::test]
 async fn test_transcode_rejects_range_requests() {
     let (state, temp_dir, track_repo) = create_test_state().await;
@@ -1120,6 +1141,111 @@ async fn test_transcode_rejects_range_requests() {
     assert!(body["message"].as_str().unwrap().contains("not supported"));
 }
 
+// ========== Raw Streaming Tests ==========
+
+#[tokio::test]
+async fn test_raw_flac_served_with_range() {
+    let (state, temp_dir, track_repo) = create_test_state().await;
+
+    let audio_content = b"fLaC0123456789ABCDEF";
+    create_test_audio_file(&temp_dir, "test.flac", audio_content);
+
+    let track = create_test_track(test_track_id(), "test.flac");
+    track_repo.add_track(track).await;
+
+    let app = create_test_app(state);
+
+    for query in ["raw=true", "format=original"] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri(format!("/{}?{}", test_track_id(), query))
+                    .header(header::AUTHORIZATION, "Bearer valid_token")
+                    .header(header::RANGE, "bytes=4-9")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT, "{}", query);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "audio/flac"
+        );
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 4-9/20"
+        );
+        assert_eq!(
+            response.headers().get(header::ACCEPT_RANGES).unwrap(),
+            "bytes"
+        );
+
+        let body = get_body_bytes(response).await;
+        assert_eq!(body, b"012345");
+    }
+}
+
+#[tokio::test]
+async fn test_raw_rejected_for_disallowed_format() {
+    let (state, temp_dir, track_repo) = create_test_state().await;
+
+    create_test_audio_file(&temp_dir, "test.wav", b"RIFF_WAVE_DATA");
+
+    let mut track = create_test_track(test_track_id(), "test.wav");
+    track.file_format = AudioFormat::Wav;
+    track_repo.add_track(track).await;
+
+    let app = create_test_app(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/{}?raw=true", test_track_id()))
+                .header(header::AUTHORIZATION, "Bearer valid_token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = parse_body(response).await;
+    assert_eq!(body["code"], "UNSUPPORTED_FORMAT");
+    assert!(body["message"].as_str().unwrap().contains("format=flac"));
+}
+
+#[tokio::test]
+async fn test_raw_rejects_transcode_parameters() {
+    let (state, temp_dir, track_repo) = create_test_state().await;
+
+    create_test_audio_file(&temp_dir, "test.flac", b"test_audio");
+
+    let track = create_test_track(test_track_id(), "test.flac");
+    track_repo.add_track(track).await;
+
+    let app = create_test_app(state);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri(format!("/{}?raw=true&format=mp3", test_track_id()))
+                .header(header::AUTHORIZATION, "Bearer valid_token")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let body: Value = parse_body(response).await;
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+}
+
 // ========== Path Traversal Security Tests ==========
 
 #[tokio::test]
@@ -9,6 +9,13 @@
 //! - resonance://search?q=<query>
 //! - resonance://settings
 //! - resonance://library
+//!
+//! Any web page can open a `resonance://` URL, so only hosts on a
+//! [`DeepLinkAllowlist`] are forwarded to the frontend; anything else is
+//! rejected and logged. Besides the routes above, `playlist` and `artist`
+//! links navigate to those pages (e.g. resonance://artist/<artist_id>).
+
+use std::collections::HashSet;
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Runtime};
@@ -36,26 +43,81 @@ pub enum DeepLinkAction {
     Navigate { path: String },
 }
 
+/// Environment variable overriding the allowed deep link hosts (comma-separated)
+pub const ALLOWED_HOSTS_ENV: &str = "RESONANCE_DEEP_LINK_HOSTS";
+
+/// Deep link hosts allowed by default: the app's known routes
+pub const DEFAULT_ALLOWED_HOSTS: &[&str] = &[
+    "play", "playlist", "artist", "search", "settings", "library",
+];
+
+/// Deep link hosts (actions) that may be forwarded to the frontend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeepLinkAllowlist {
+    hosts: HashSet<String>,
+}
+
+impl Default for DeepLinkAllowlist {
+    fn default() -> Self {
+        Self::new(DEFAULT_ALLOWED_HOSTS.iter().copied())
+    }
+}
+
+impl DeepLinkAllowlist {
+    /// Allow exactly `hosts` (matched case-insensitively)
+    pub fn new<'a>(hosts: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            hosts: hosts
+                .into_iter()
+                .map(|host| host.trim().to_ascii_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect(),
+        }
+    }
+
+    /// Allowlist from `RESONANCE_DEEP_LINK_HOSTS`, or the default when unset
+    pub fn from_env() -> Self {
+        match std::env::var(ALLOWED_HOSTS_ENV) {
+            Ok(value) => Self::new(value.split(',')),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Whether links to `host` may be handled
+    pub fn allows(&self, host: &str) -> bool {
+        self.hosts.contains(&host.to_ascii_lowercase())
+    }
+}
+
 /// Parses and handles a deep link URL
-pub fn handle_deep_link<R: Runtime>(app: &AppHandle<R>, urls: Vec<String>) {
+///
+/// Links whose host isn't on `allowlist` are logged and dropped without
+/// reaching the frontend.
+pub fn handle_deep_link<R: Runtime>(
+    app: &AppHandle<R>,
+    allowlist: &DeepLinkAllowlist,
+    urls: Vec<String>,
+) {
     for url_str in urls {
         tracing::info!("Handling deep link: {}", url_str);
 
-        match parse_deep_link(&url_str) {
+        match parse_deep_link(&url_str, allowlist) {
             Ok(action) => {
                 if let Err(e) = app.emit("deep-link", &action) {
                     tracing::error!("Failed to emit deep link event: {}", e);
                 }
             }
             Err(e) => {
-                tracing::warn!("Invalid deep link '{}': {}", url_str, e);
+                tracing::warn!("Rejected deep link '{}': {}", url_str, e);
             }
         }
     }
 }
 
 /// Parses a deep link URL into a DeepLinkAction
-fn parse_deep_link(url_str: &str) -> Result<DeepLinkAction, String> {
+///
+/// Fails for malformed URLs, other schemes, and hosts not on `allowlist`.
+fn parse_deep_link(url_str: &str, allowlist: &DeepLinkAllowlist) -> Result<DeepLinkAction, String> {
     let url = Url::parse(url_str).map_err(|e| format!("Invalid URL: {}", e))?;
 
     // Ensure the scheme is resonance://
@@ -64,6 +126,9 @@ fn parse_deep_link(url_str: &str) -> Result<DeepLinkAction, String> {
     }
 
     let host = url.host_str().unwrap_or("");
+    if !allowlist.allows(host) {
+        return Err(format!("Deep link action '{}' is not allowed", host));
+    }
     let path_segments: Vec<&str> = url.path_segments().map_or(vec![], |s| s.collect());
 
     match host {
@@ -90,7 +155,7 @@ fn parse_deep_link(url_str: &str) -> Result<DeepLinkAction, String> {
             }
         }
         _ => {
-            // Treat unknown hosts as navigation paths
+            // Treat other allowed hosts as navigation paths
             let full_path = if path_segments.is_empty() {
                 format!("/{}", host)
             } else {
@@ -131,7 +196,8 @@ mod tests {
 
     #[test]
     fn test_parse_play_track() {
-        let action = parse_deep_link("resonance://play/track/123").unwrap();
+        let action =
+            parse_deep_link("resonance://play/track/123", &DeepLinkAllowlist::default()).unwrap();
         match action {
             DeepLinkAction::PlayTrack { track_id } => assert_eq!(track_id, "123"),
             _ => panic!("Expected PlayTrack action"),
@@ -140,7 +206,8 @@ mod tests {
 
     #[test]
     fn test_parse_play_album() {
-        let action = parse_deep_link("resonance://play/album/456").unwrap();
+        let action =
+            parse_deep_link("resonance://play/album/456", &DeepLinkAllowlist::default()).unwrap();
         match action {
             DeepLinkAction::PlayAlbum { album_id } => assert_eq!(album_id, "456"),
             _ => panic!("Expected PlayAlbum action"),
@@ -149,7 +216,11 @@ mod tests {
 
     #[test]
     fn test_parse_play_playlist() {
-        let action = parse_deep_link("resonance://play/playlist/789").unwrap();
+        let action = parse_deep_link(
+            "resonance://play/playlist/789",
+            &DeepLinkAllowlist::default(),
+        )
+        .unwrap();
         match action {
             DeepLinkAction::PlayPlaylist { playlist_id } => assert_eq!(playlist_id, "789"),
             _ => panic!("Expected PlayPlaylist action"),
@@ -158,7 +229,8 @@ mod tests {
 
     #[test]
     fn test_parse_play_artist() {
-        let action = parse_deep_link("resonance://play/artist/abc").unwrap();
+        let action =
+            parse_deep_link("resonance://play/artist/abc", &DeepLinkAllowlist::default()).unwrap();
         match action {
             DeepLinkAction::PlayArtist { artist_id } => assert_eq!(artist_id, "abc"),
             _ => panic!("Expected PlayArtist action"),
@@ -167,7 +239,11 @@ mod tests {
 
     #[test]
     fn test_parse_search() {
-        let action = parse_deep_link("resonance://search?q=test%20query").unwrap();
+        let action = parse_deep_link(
+            "resonance://search?q=test%20query",
+            &DeepLinkAllowlist::default(),
+        )
+        .unwrap();
         match action {
             DeepLinkAction::Search { query } => assert_eq!(query, "test query"),
             _ => panic!("Expected Search action"),
@@ -176,7 +252,8 @@ mod tests {
 
     #[test]
     fn test_parse_settings() {
-        let action = parse_deep_link("resonance://settings").unwrap();
+        let action =
+            parse_deep_link("resonance://settings", &DeepLinkAllowlist::default()).unwrap();
         match action {
             DeepLinkAction::OpenSettings => {}
             _ => panic!("Expected OpenSettings action"),
@@ -185,7 +262,7 @@ mod tests {
 
     #[test]
     fn test_parse_library() {
-        let action = parse_deep_link("resonance://library").unwrap();
+        let action = parse_deep_link("resonance://library", &DeepLinkAllowlist::default()).unwrap();
         match action {
             DeepLinkAction::OpenLibrary => {}
             _ => panic!("Expected OpenLibrary action"),
@@ -194,7 +271,8 @@ mod tests {
 
     #[test]
     fn test_parse_navigate() {
-        let action = parse_deep_link("resonance://queue").unwrap();
+        let action =
+            parse_deep_link("resonance://queue", &DeepLinkAllowlist::new(["queue"])).unwrap();
         match action {
             DeepLinkAction::Navigate { path } => assert_eq!(path, "/queue"),
             _ => panic!("Expected Navigate action"),
@@ -203,22 +281,64 @@ mod tests {
 
     #[test]
     fn test_invalid_scheme() {
-        let result = parse_deep_link("http://example.com");
+        let result = parse_deep_link("http://example.com", &DeepLinkAllowlist::default());
         assert!(result.is_err());
     }
 
     #[test]
     fn test_missing_search_query() {
-        let result = parse_deep_link("resonance://search");
+        let result = parse_deep_link("resonance://search", &DeepLinkAllowlist::default());
         assert!(result.is_err());
     }
 
     #[test]
     fn test_incomplete_play_action() {
-        let result = parse_deep_link("resonance://play/track");
+        let result = parse_deep_link("resonance://play/track", &DeepLinkAllowlist::default());
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_unknown_action_is_rejected() {
+        let allowlist = DeepLinkAllowlist::default();
+
+        for url in [
+            "resonance://queue",
+            "resonance://logout",
+            "resonance:///settings",
+            "resonance://admin/users",
+        ] {
+            let err = parse_deep_link(url, &allowlist).unwrap_err();
+            assert!(err.contains("not allowed"), "{}: {}", url, err);
+        }
+    }
+
+    #[test]
+    fn test_known_actions_pass_default_allowlist() {
+        let allowlist = DeepLinkAllowlist::default();
+
+        assert!(parse_deep_link("resonance://play/track/123", &allowlist).is_ok());
+        match parse_deep_link("resonance://playlist/789", &allowlist).unwrap() {
+            DeepLinkAction::Navigate { path } => assert_eq!(path, "/playlist/789"),
+            _ => panic!("Expected Navigate action"),
+        }
+        match parse_deep_link("resonance://artist/abc", &allowlist).unwrap() {
+            DeepLinkAction::Navigate { path } => assert_eq!(path, "/artist/abc"),
+            _ => panic!("Expected Navigate action"),
+        }
+        // Hosts are case-insensitive
+        assert!(parse_deep_link("resonance://PLAY/track/123", &allowlist).is_ok());
+    }
+
+    #[test]
+    fn test_custom_allowlist() {
+        let allowlist = DeepLinkAllowlist::new([" play ", "", "Search"]);
+
+        assert!(allowlist.allows("play"));
+        assert!(allowlist.allows("search"));
+        assert!(!allowlist.allows("settings"));
+        assert!(!allowlist.allows(""));
+    }
+
     #[test]
     fn test_get_deep_link_scheme() {
         assert_eq!(get_deep_link_scheme(), "resonance");
@@ -13,6 +13,8 @@ use parking_lot::Mutex;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager, Wry};
 
+use crate::playback_debounce::{schedule_flush, Debounced, PlaybackDebouncers, PlaybackIdentity};
+
 /// Discord Application ID for Resonance
 /// This is a placeholder - in production, register at https://discord.com/developers/applications
 pub const DISCORD_APP_ID: &str = "1234567890123456789";
@@ -26,8 +28,47 @@ pub fn init_discord_state() -> DiscordState {
 }
 
 /// Set the Discord rich presence with track information
+///
+/// Updates for the current track and play state (e.g. after a seek) are
+/// debounced, with the latest one sent when the interval expires; a new
+/// track or play/pause change is sent immediately.
 #[tauri::command]
 pub fn set_presence(app: AppHandle<Wry>, payload: PresencePayload) -> Result<(), String> {
+    let identity = PlaybackIdentity::new(
+        Some(&payload.track_title),
+        Some(&payload.artist_name),
+        payload.album_name.as_deref(),
+        payload.is_playing,
+    );
+    let debouncers = app.state::<PlaybackDebouncers>();
+    let payload = match debouncers.discord.submit(&identity, payload) {
+        Debounced::Push(payload) => payload,
+        Debounced::Deferred(flush) => {
+            schedule_flush(
+                &app,
+                |d| &d.discord,
+                flush,
+                |app, payload| {
+                    if push_presence(app, &payload).is_err() {
+                        app.state::<PlaybackDebouncers>().discord.reset();
+                    }
+                },
+            );
+            return Ok(());
+        }
+        Debounced::Coalesced => return Ok(()),
+    };
+
+    let result = push_presence(&app, &payload);
+    if result.is_err() {
+        // Let the next update retry instead of waiting out the interval
+        debouncers.discord.reset();
+    }
+    result
+}
+
+/// Connect to Discord if needed and send the presence
+fn push_presence(app: &AppHandle<Wry>, payload: &PresencePayload) -> Result<(), String> {
     let state = app.state::<DiscordState>();
     let mut guard = state.lock();
 
@@ -46,7 +87,7 @@ pub fn set_presence(app: AppHandle<Wry>, payload: PresencePayload) -> Result<(),
     }
 
     if let Some(ref mut conn) = *guard {
-        let presence = RichPresence::from_payload(&payload);
+        let presence = RichPresence::from_payload(payload);
         conn.set_activity(presence).map_err(|e| {
             tracing::error!("Failed to set Discord presence: {}", e);
             e.to_string()
@@ -59,6 +100,7 @@ pub fn set_presence(app: AppHandle<Wry>, payload: PresencePayload) -> Result<(),
 /// Clear the Discord rich presence
 #[tauri::command]
 pub fn clear_presence(app: AppHandle<Wry>) -> Result<(), String> {
+    app.state::<PlaybackDebouncers>().discord.reset();
     let state = app.state::<DiscordState>();
     let mut guard = state.lock();
 
@@ -76,6 +118,7 @@ pub fn clear_presence(app: AppHandle<Wry>) -> Result<(), String> {
 /// Disconnect from Discord RPC
 #[tauri::command]
 pub fn disconnect_discord(app: AppHandle<Wry>) -> Result<(), String> {
+    app.state::<PlaybackDebouncers>().discord.reset();
     let state = app.state::<DiscordState>();
     let mut guard = state.lock();
 
@@ -7,6 +7,7 @@
 //! - Minimize-to-tray functionality
 //! - Discord Rich Presence integration
 //! - Native notifications for track changes
+//! - Debounced playback updates for the tray, Discord and notifications
 //! - Autostart on system boot
 //! - Deep linking (resonance:// protocol)
 //! - Automatic updates
@@ -16,6 +17,7 @@ mod deep_link;
 mod discord;
 mod media_keys;
 mod notifications;
+mod playback_debounce;
 mod tray;
 mod updater;
 
@@ -40,6 +42,8 @@ pub fn run() {
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .manage(discord::init_discord_state())
+        .manage(notifications::NotificationSinks::default())
+        .manage(playback_debounce::PlaybackDebouncers::from_env())
         .invoke_handler(tauri::generate_handler![
             // Tray commands
             tray::update_playback_state,
@@ -70,6 +74,9 @@ pub fn run() {
                 tracing::error!("Failed to create system tray: {}", e);
             }
 
+            // Route track-change notifications to the OS toast and configured webhooks
+            notifications::register_default_sinks(app.handle());
+
             // Register global media key shortcuts
             if let Err(e) = media_keys::register_media_keys(app.handle()) {
                 tracing::error!("Failed to register media keys: {}", e);
@@ -77,9 +84,11 @@ pub fn run() {
 
             // Register deep link handler
             let handle = app.handle().clone();
+            let allowlist = deep_link::DeepLinkAllowlist::from_env();
             app.deep_link().on_open_url(move |event| {
                 deep_link::handle_deep_link(
                     &handle,
+                    &allowlist,
                     event.urls().iter().map(|u| u.to_string()).collect(),
                 );
             });
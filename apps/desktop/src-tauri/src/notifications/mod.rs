@@ -0,0 +1,226 @@
+//! Native Notifications
+//!
+//! Provides native desktop notifications for track changes and other events.
+//! Uses tauri-plugin-notification for cross-platform notification support.
+//!
+//! Track-change notifications fan out to every registered `NotificationSink`:
+//! the OS toast, plus any webhooks configured at startup.
+
+mod sink;
+mod webhook;
+
+pub use sink::{NotificationPayload, NotificationSink, NotificationSinks, OsToastSink};
+pub use webhook::{WebhookSink, WEBHOOK_URLS_ENV};
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::playback_debounce::{schedule_flush, Debounced, PlaybackDebouncers, PlaybackIdentity};
+
+/// Track information for notification display
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackNotification {
+    pub title: String,
+    pub artist: String,
+    pub album: Option<String>,
+    pub artwork_url: Option<String>,
+}
+
+impl From<&TrackNotification> for NotificationPayload {
+    fn from(track: &TrackNotification) -> Self {
+        let body = if let Some(album) = &track.album {
+            format!("{} \u{2022} {}", track.artist, album)
+        } else {
+            track.artist.clone()
+        };
+
+        Self {
+            title: track.title.clone(),
+            body: Some(body),
+            artwork_url: track.artwork_url.clone(),
+        }
+    }
+}
+
+/// Registers the OS toast sink and any webhooks from `RESONANCE_NOTIFICATION_WEBHOOKS`
+pub fn register_default_sinks(app: &AppHandle<Wry>) {
+    let sinks = app.state::<NotificationSinks>();
+    sinks.register(Arc::new(OsToastSink::new(app.clone())));
+    for webhook in WebhookSink::from_env() {
+        sinks.register(Arc::new(webhook));
+    }
+}
+
+/// Shows a notification when the track changes
+///
+/// Delivered to every registered sink. Fails only if no sink succeeded.
+/// Repeated notifications for the same track are debounced, with the latest
+/// one shown when the interval expires.
+#[tauri::command]
+pub fn show_track_notification(
+    app: AppHandle<Wry>,
+    track: TrackNotification,
+) -> Result<(), String> {
+    let identity = PlaybackIdentity::new(
+        Some(&track.title),
+        Some(&track.artist),
+        track.album.as_deref(),
+        true,
+    );
+    match app
+        .state::<PlaybackDebouncers>()
+        .notifications
+        .submit(&identity, track)
+    {
+        Debounced::Push(track) => notify_sinks(&app, &track),
+        Debounced::Deferred(flush) => {
+            schedule_flush(
+                &app,
+                |d| &d.notifications,
+                flush,
+                |app, track| {
+                    if let Err(e) = notify_sinks(app, &track) {
+                        tracing::warn!("{}", e);
+                    }
+                },
+            );
+            Ok(())
+        }
+        Debounced::Coalesced => {
+            tracing::debug!("Coalesced repeated track notification");
+            Ok(())
+        }
+    }
+}
+
+/// Deliver a track notification to every registered sink
+fn notify_sinks(app: &AppHandle<Wry>, track: &TrackNotification) -> Result<(), String> {
+    let sinks = app.state::<NotificationSinks>();
+    let failures = sinks.notify_all(&NotificationPayload::from(track));
+
+    if !failures.is_empty() && failures.len() == sinks.count() {
+        let errors: Vec<String> = failures
+            .into_iter()
+            .map(|(sink, error)| format!("{}: {}", sink, error))
+            .collect();
+        return Err(format!(
+            "Failed to show notification: {}",
+            errors.join("; ")
+        ));
+    }
+
+    tracing::debug!(
+        "Showed track notification: {} - {}",
+        track.title,
+        track.artist
+    );
+
+    Ok(())
+}
+
+/// Shows a generic notification
+#[tauri::command]
+pub fn show_notification(
+    app: AppHandle<Wry>,
+    title: String,
+    body: Option<String>,
+) -> Result<(), String> {
+    let notification = app.notification();
+
+    let mut builder = notification.builder().title(&title);
+
+    if let Some(body_text) = body {
+        builder = builder.body(&body_text);
+    }
+
+    builder
+        .show()
+        .map_err(|e| format!("Failed to show notification: {}", e))?;
+
+    tracing::debug!("Showed notification: {}", title);
+
+    Ok(())
+}
+
+/// Checks if notifications are permitted
+#[tauri::command]
+pub fn check_notification_permission(app: AppHandle<Wry>) -> Result<bool, String> {
+    let notification = app.notification();
+
+    notification
+        .permission_state()
+        .map(|state| state == tauri_plugin_notification::PermissionState::Granted)
+        .map_err(|e| format!("Failed to check permission: {}", e))
+}
+
+/// Requests notification permission from the user
+#[tauri::command]
+pub async fn request_notification_permission(app: AppHandle<Wry>) -> Result<bool, String> {
+    let notification = app.notification();
+
+    notification
+        .request_permission()
+        .map(|state| state == tauri_plugin_notification::PermissionState::Granted)
+        .map_err(|e| format!("Failed to request permission: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_notification_with_album() {
+        let track = TrackNotification {
+            title: "Test Song".to_string(),
+            artist: "Test Artist".to_string(),
+            album: Some("Test Album".to_string()),
+            artwork_url: None,
+        };
+        assert_eq!(track.title, "Test Song");
+        assert_eq!(track.artist, "Test Artist");
+        assert_eq!(track.album, Some("Test Album".to_string()));
+    }
+
+    #[test]
+    fn test_track_notification_without_album() {
+        let track = TrackNotification {
+            title: "Single Track".to_string(),
+            artist: "Solo Artist".to_string(),
+            album: None,
+            artwork_url: None,
+        };
+        assert_eq!(track.title, "Single Track");
+        assert!(track.album.is_none());
+    }
+
+    #[test]
+    fn test_track_notification_payload() {
+        let track = TrackNotification {
+            title: "Test Song".to_string(),
+            artist: "Test Artist".to_string(),
+            album: Some("Test Album".to_string()),
+            artwork_url: Some("https://example.com/art.jpg".to_string()),
+        };
+        let payload = NotificationPayload::from(&track);
+        assert_eq!(payload.title, "Test Song");
+        assert_eq!(
+            payload.body.as_deref(),
+            Some("Test Artist \u{2022} Test Album")
+        );
+        assert_eq!(
+            payload.artwork_url.as_deref(),
+            Some("https://example.com/art.jpg")
+        );
+
+        let single = TrackNotification {
+            album: None,
+            ..track
+        };
+        assert_eq!(
+            NotificationPayload::from(&single).body.as_deref(),
+            Some("Test Artist")
+        );
+    }
+}
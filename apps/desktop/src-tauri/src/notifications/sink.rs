@@ -0,0 +1,186 @@
+//! Notification Sinks
+//!
+//! Defines the `NotificationSink` trait and the registry that fans each
+//! notification out to every registered sink. The OS toast is one sink;
+//! others (e.g. a webhook for home automation) are registered at startup.
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use tauri::{AppHandle, Wry};
+use tauri_plugin_notification::NotificationExt;
+
+/// Notification content delivered to every sink
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotificationPayload {
+    pub title: String,
+    pub body: Option<String>,
+    pub artwork_url: Option<String>,
+}
+
+/// A destination for notifications
+pub trait NotificationSink: Send + Sync {
+    /// Short name used in logs
+    fn name(&self) -> &str;
+
+    /// Deliver one notification
+    fn notify(&self, payload: NotificationPayload) -> Result<(), String>;
+}
+
+/// Registered notification sinks
+#[derive(Default)]
+pub struct NotificationSinks {
+    sinks: RwLock<Vec<Arc<dyn NotificationSink>>>,
+}
+
+impl NotificationSinks {
+    /// Add a sink; it receives every notification sent afterwards
+    pub fn register(&self, sink: Arc<dyn NotificationSink>) {
+        tracing::debug!("Registered notification sink: {}", sink.name());
+        self.sinks.write().push(sink);
+    }
+
+    /// Number of registered sinks
+    pub fn count(&self) -> usize {
+        self.sinks.read().len()
+    }
+
+    /// Deliver a notification to every sink
+    ///
+    /// A sink that errors or panics is logged and skipped; the rest still
+    /// receive the notification. Returns the `(sink, error)` pairs for the
+    /// sinks that failed.
+    pub fn notify_all(&self, payload: &NotificationPayload) -> Vec<(String, String)> {
+        // Clone the list so a sink can't deadlock by registering another
+        let sinks: Vec<Arc<dyn NotificationSink>> = self.sinks.read().clone();
+
+        let mut failures = Vec::new();
+        for sink in sinks {
+            let result = catch_unwind(AssertUnwindSafe(|| sink.notify(payload.clone())))
+                .unwrap_or_else(|_| Err("sink panicked".to_string()));
+            if let Err(e) = result {
+                tracing::warn!("Notification sink {} failed: {}", sink.name(), e);
+                failures.push((sink.name().to_string(), e));
+            }
+        }
+        failures
+    }
+}
+
+/// Shows notifications as native OS toasts
+pub struct OsToastSink {
+    app: AppHandle<Wry>,
+}
+
+impl OsToastSink {
+    pub fn new(app: AppHandle<Wry>) -> Self {
+        Self { app }
+    }
+}
+
+impl NotificationSink for OsToastSink {
+    fn name(&self) -> &str {
+        "os-toast"
+    }
+
+    fn notify(&self, payload: NotificationPayload) -> Result<(), String> {
+        let notification = self.app.notification();
+
+        let mut builder = notification.builder().title(&payload.title);
+        if let Some(body) = &payload.body {
+            builder = builder.body(body);
+        }
+
+        builder
+            .show()
+            .map_err(|e| format!("Failed to show notification: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Sink that records what it was sent
+    #[derive(Default)]
+    struct RecordingSink {
+        received: Mutex<Vec<NotificationPayload>>,
+    }
+
+    impl NotificationSink for RecordingSink {
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn notify(&self, payload: NotificationPayload) -> Result<(), String> {
+            self.received.lock().push(payload);
+            Ok(())
+        }
+    }
+
+    /// Sink that always fails, by error or by panic
+    struct FailingSink {
+        panic: bool,
+    }
+
+    impl NotificationSink for FailingSink {
+        fn name(&self) -> &str {
+            "failing"
+        }
+
+        fn notify(&self, _payload: NotificationPayload) -> Result<(), String> {
+            if self.panic {
+                panic!("sink exploded");
+            }
+            Err("endpoint unreachable".to_string())
+        }
+    }
+
+    fn payload() -> NotificationPayload {
+        NotificationPayload {
+            title: "Test Song".to_string(),
+            body: Some("Test Artist".to_string()),
+            artwork_url: None,
+        }
+    }
+
+    #[test]
+    fn test_notify_all_fans_out_to_every_sink() {
+        let sinks = NotificationSinks::default();
+        let first = Arc::new(RecordingSink::default());
+        let second = Arc::new(RecordingSink::default());
+        sinks.register(first.clone());
+        sinks.register(second.clone());
+
+        let failures = sinks.notify_all(&payload());
+
+        assert!(failures.is_empty());
+        assert_eq!(*first.received.lock(), vec![payload()]);
+        assert_eq!(*second.received.lock(), vec![payload()]);
+    }
+
+    #[test]
+    fn test_failing_sink_does_not_block_others() {
+        let sinks = NotificationSinks::default();
+        let recorder = Arc::new(RecordingSink::default());
+        sinks.register(Arc::new(FailingSink { panic: false }));
+        sinks.register(Arc::new(FailingSink { panic: true }));
+        sinks.register(recorder.clone());
+
+        let failures = sinks.notify_all(&payload());
+
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].1, "endpoint unreachable");
+        assert_eq!(failures[1].1, "sink panicked");
+        assert_eq!(*recorder.received.lock(), vec![payload()]);
+    }
+
+    #[test]
+    fn test_notify_all_without_sinks() {
+        let sinks = NotificationSinks::default();
+        assert_eq!(sinks.count(), 0);
+        assert!(sinks.notify_all(&payload()).is_empty());
+    }
+}
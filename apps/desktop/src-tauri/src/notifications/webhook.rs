@@ -0,0 +1,224 @@
+//! Webhook Notification Sink
+//!
+//! Posts each notification as JSON to a local HTTP endpoint, e.g. a home
+//! automation server. Only plain `http://` URLs are supported; the endpoint
+//! is expected to be on the local network.
+
+use std::time::Duration;
+use tokio::sync::mpsc;
+use url::Url;
+
+use super::sink::{NotificationPayload, NotificationSink};
+
+/// Environment variable holding comma-separated webhook URLs
+pub const WEBHOOK_URLS_ENV: &str = "RESONANCE_NOTIFICATION_WEBHOOKS";
+
+/// Connect and overall request timeout for webhook deliveries
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Notifications waiting for delivery before new ones are refused
+const WEBHOOK_QUEUE_CAPACITY: usize = 16;
+
+/// Posts notifications to an HTTP webhook
+///
+/// Notifications are queued for a single delivery task on the async runtime,
+/// so a slow or unreachable endpoint never delays the other sinks; delivery
+/// errors are logged.
+pub struct WebhookSink {
+    url: Url,
+    queue: mpsc::Sender<NotificationPayload>,
+}
+
+impl WebhookSink {
+    /// Create a sink for `url`, which must be an `http://` URL with a host
+    ///
+    /// Spawns the sink's delivery task, which ends when the sink is dropped.
+    pub fn new(url: &str) -> Result<Self, String> {
+        let url = Url::parse(url).map_err(|e| format!("Invalid webhook URL: {}", e))?;
+        if url.scheme() != "http" {
+            return Err(format!(
+                "Unsupported webhook scheme '{}': only http is supported",
+                url.scheme()
+            ));
+        }
+        if url.host_str().is_none() {
+            return Err("Webhook URL has no host".to_string());
+        }
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(WEBHOOK_TIMEOUT)
+            .timeout(WEBHOOK_TIMEOUT)
+            .build()
+            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let (queue, pending) = mpsc::channel(WEBHOOK_QUEUE_CAPACITY);
+        tauri::async_runtime::spawn(deliver(client, url.clone(), pending));
+
+        Ok(Self { url, queue })
+    }
+
+    /// Parse the webhook sinks configured in the environment
+    ///
+    /// Invalid URLs are logged and skipped.
+    pub fn from_env() -> Vec<Self> {
+        std::env::var(WEBHOOK_URLS_ENV)
+            .map(|urls| parse_webhook_urls(&urls))
+            .unwrap_or_default()
+    }
+}
+
+/// Build sinks from a comma-separated list of URLs, skipping invalid ones
+fn parse_webhook_urls(urls: &str) -> Vec<WebhookSink> {
+    urls.split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .filter_map(|url| match WebhookSink::new(url) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                tracing::warn!("Ignoring notification webhook {}: {}", url, e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn notify(&self, payload: NotificationPayload) -> Result<(), String> {
+        self.queue.try_send(payload).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(_) => {
+                format!("Delivery queue for {} is full", self.url)
+            }
+            mpsc::error::TrySendError::Closed(_) => {
+                format!("Delivery task for {} has stopped", self.url)
+            }
+        })
+    }
+}
+
+/// Deliver queued notifications to `url` one at a time
+async fn deliver(
+    client: reqwest::Client,
+    url: Url,
+    mut pending: mpsc::Receiver<NotificationPayload>,
+) {
+    while let Some(payload) = pending.recv().await {
+        if let Err(e) = post_json(&client, &url, &payload).await {
+            tracing::warn!("Notification webhook {} failed: {}", url, e);
+        }
+    }
+}
+
+/// POST a notification as JSON and require a 2xx response
+async fn post_json(
+    client: &reqwest::Client,
+    url: &Url,
+    payload: &NotificationPayload,
+) -> Result<(), String> {
+    let response = client
+        .post(url.clone())
+        .json(payload)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(format!("Webhook returned HTTP {}", status.as_u16()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_webhook_requires_http_url() {
+        assert!(WebhookSink::new("http://192.168.1.10:8123/api/webhook/music").is_ok());
+        assert!(WebhookSink::new("https://example.com/hook").is_err());
+        assert!(WebhookSink::new("not a url").is_err());
+    }
+
+    #[test]
+    fn test_parse_webhook_urls_skips_invalid() {
+        let sinks = parse_webhook_urls("http://localhost:8123/a, ftp://nope, ,http://hub.local/b");
+        let urls: Vec<&str> = sinks.iter().map(|s| s.url.as_str()).collect();
+        assert_eq!(urls, vec!["http://localhost:8123/a", "http://hub.local/b"]);
+    }
+
+    #[test]
+    fn test_webhook_accepts_ipv6_literal() {
+        let sink = WebhookSink::new("http://[::1]:8123/api/webhook").unwrap();
+        assert_eq!(sink.url.host_str(), Some("[::1]"));
+    }
+
+    /// Accept one request on `listener`, answer with `status`, and return
+    /// the request as received
+    fn serve_once(listener: TcpListener, status: &'static str) -> std::thread::JoinHandle<String> {
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+
+            let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\n\r\n", status);
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            request
+        })
+    }
+
+    fn payload() -> NotificationPayload {
+        NotificationPayload {
+            title: "Test Song".to_string(),
+            body: Some("Test Artist".to_string()),
+            artwork_url: None,
+        }
+    }
+
+    #[test]
+    fn test_post_json_delivers_payload() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let server = serve_once(listener, "204 No Content");
+
+        let client = reqwest::Client::new();
+        tauri::async_runtime::block_on(post_json(&client, &url, &payload())).unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hook HTTP/1.1\r\n"));
+        assert!(request.contains("\"title\":\"Test Song\""));
+    }
+
+    #[test]
+    fn test_post_json_rejects_error_status() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}/hook", listener.local_addr().unwrap())).unwrap();
+        let server = serve_once(listener, "500 Internal Server Error");
+
+        let client = reqwest::Client::new();
+        let result = tauri::async_runtime::block_on(post_json(&client, &url, &payload()));
+
+        assert_eq!(result, Err("Webhook returned HTTP 500".to_string()));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,395 @@
+//! Playback State Debouncing
+//!
+//! The frontend reports playback state on every change, including seeks within
+//! a track. Pushing each of those to Discord, the tray and notifications is
+//! wasteful (and Discord rate-limits presence updates), so each path asks a
+//! `PlaybackStateDebouncer` first: a new track or a play/pause change goes
+//! through immediately, while repeats of the same state are coalesced to at
+//! most one refresh per interval, with the latest one delivered when the
+//! interval expires.
+
+use parking_lot::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, Wry};
+
+use crate::discord::PresencePayload;
+use crate::notifications::TrackNotification;
+use crate::tray::PlaybackState;
+
+/// Environment variable overriding the debounce interval, in milliseconds
+pub const DEBOUNCE_INTERVAL_ENV: &str = "RESONANCE_PRESENCE_DEBOUNCE_MS";
+
+/// Default minimum time between refreshes of an unchanged track
+pub const DEFAULT_DEBOUNCE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The parts of playback state that warrant an immediate refresh
+///
+/// Position is deliberately left out: a seek alone never bypasses the
+/// debounce interval.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlaybackIdentity {
+    /// Track being played, as `title\0artist\0album`; `None` when stopped
+    track: Option<String>,
+    is_playing: bool,
+}
+
+impl PlaybackIdentity {
+    /// Identity of a track and its play/pause state
+    pub fn new(
+        title: Option<&str>,
+        artist: Option<&str>,
+        album: Option<&str>,
+        is_playing: bool,
+    ) -> Self {
+        let track = title.map(|title| {
+            format!(
+                "{}\0{}\0{}",
+                title,
+                artist.unwrap_or_default(),
+                album.unwrap_or_default()
+            )
+        });
+        Self { track, is_playing }
+    }
+}
+
+/// What to do with a playback update handed to a debouncer
+#[derive(Debug)]
+pub enum Debounced<T> {
+    /// Push this payload now
+    Push(T),
+    /// The payload is held back; call [`schedule_flush`] with this flush so it
+    /// is pushed when the interval expires
+    Deferred(PendingFlush),
+    /// The payload replaced one that is already waiting for a flush
+    Coalesced,
+}
+
+/// A trailing-edge flush owed for a held-back payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingFlush {
+    ticket: u64,
+    /// How long until the interval expires
+    pub delay: Duration,
+}
+
+#[derive(Debug)]
+struct DebounceState<T> {
+    last: Option<(PlaybackIdentity, Instant)>,
+    pending: Option<(u64, PlaybackIdentity, T)>,
+    next_ticket: u64,
+}
+
+/// Decides when a playback update should be pushed to one output
+///
+/// Throttles on both edges: the first update of an interval is pushed right
+/// away, and the latest update held back during the interval is kept and
+/// pushed once it expires, so the final position after a burst of seeks is
+/// never lost.
+#[derive(Debug)]
+pub struct PlaybackStateDebouncer<T> {
+    interval: Duration,
+    state: Mutex<DebounceState<T>>,
+}
+
+impl<T> PlaybackStateDebouncer<T> {
+    /// Create a debouncer allowing one refresh per `interval` for unchanged state
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            state: Mutex::new(DebounceState {
+                last: None,
+                pending: None,
+                next_ticket: 0,
+            }),
+        }
+    }
+
+    /// Hand over an update with `identity`
+    ///
+    /// The first update, a change of track or play state, or one arriving
+    /// once `interval` has passed since the last push is returned for pushing
+    /// (and recorded). Anything else becomes the pending payload, replacing an
+    /// older one, to be delivered by the trailing flush.
+    pub fn submit(&self, identity: &PlaybackIdentity, payload: T) -> Debounced<T> {
+        self.submit_at(identity, payload, Instant::now())
+    }
+
+    fn submit_at(&self, identity: &PlaybackIdentity, payload: T, now: Instant) -> Debounced<T> {
+        let mut state = self.state.lock();
+        let elapsed = match &state.last {
+            Some((previous, pushed_at)) if previous == identity => {
+                Some(now.duration_since(*pushed_at))
+            }
+            _ => None,
+        };
+        match elapsed {
+            Some(elapsed) if elapsed < self.interval => {
+                if let Some((ticket, _, _)) = state.pending.take() {
+                    state.pending = Some((ticket, identity.clone(), payload));
+                    return Debounced::Coalesced;
+                }
+                let ticket = state.next_ticket;
+                state.next_ticket += 1;
+                state.pending = Some((ticket, identity.clone(), payload));
+                Debounced::Deferred(PendingFlush {
+                    ticket,
+                    delay: self.interval - elapsed,
+                })
+            }
+            _ => {
+                // Whatever was pending is older than this update
+                state.pending = None;
+                state.last = Some((identity.clone(), now));
+                Debounced::Push(payload)
+            }
+        }
+    }
+
+    /// Take the payload owed to `flush`, recording it as pushed
+    ///
+    /// Returns `None` if a newer push or a reset superseded it.
+    pub fn flush(&self, flush: PendingFlush) -> Option<T> {
+        self.flush_at(flush, Instant::now())
+    }
+
+    fn flush_at(&self, flush: PendingFlush, now: Instant) -> Option<T> {
+        let mut state = self.state.lock();
+        match state.pending.take() {
+            Some((ticket, identity, payload)) if ticket == flush.ticket => {
+                state.last = Some((identity, now));
+                Some(payload)
+            }
+            other => {
+                state.pending = other;
+                None
+            }
+        }
+    }
+
+    /// Forget the last push and any pending payload, so the next update goes
+    /// through immediately
+    pub fn reset(&self) {
+        let mut state = self.state.lock();
+        state.last = None;
+        state.pending = None;
+    }
+}
+
+/// Debouncers for each output that reflects playback state
+#[derive(Debug)]
+pub struct PlaybackDebouncers {
+    pub tray: PlaybackStateDebouncer<PlaybackState>,
+    pub discord: PlaybackStateDebouncer<PresencePayload>,
+    pub notifications: PlaybackStateDebouncer<TrackNotification>,
+}
+
+impl PlaybackDebouncers {
+    /// Create debouncers sharing one interval
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            tray: PlaybackStateDebouncer::new(interval),
+            discord: PlaybackStateDebouncer::new(interval),
+            notifications: PlaybackStateDebouncer::new(interval),
+        }
+    }
+
+    /// Create debouncers using the interval from `RESONANCE_PRESENCE_DEBOUNCE_MS`
+    ///
+    /// An unset or invalid value uses the default interval.
+    pub fn from_env() -> Self {
+        let interval = std::env::var(DEBOUNCE_INTERVAL_ENV)
+            .ok()
+            .and_then(|value| parse_interval(&value))
+            .unwrap_or(DEFAULT_DEBOUNCE_INTERVAL);
+        Self::new(interval)
+    }
+}
+
+/// Push a held-back payload once its interval expires
+///
+/// `select` picks the output's debouncer from the managed
+/// [`PlaybackDebouncers`]; `push` runs only if nothing superseded the payload
+/// in the meantime.
+pub fn schedule_flush<T, F>(
+    app: &AppHandle<Wry>,
+    select: fn(&PlaybackDebouncers) -> &PlaybackStateDebouncer<T>,
+    flush: PendingFlush,
+    push: F,
+) where
+    T: Send + 'static,
+    F: FnOnce(&AppHandle<Wry>, T) + Send + 'static,
+{
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(flush.delay).await;
+        let payload = select(&app.state::<PlaybackDebouncers>()).flush(flush);
+        if let Some(payload) = payload {
+            push(&app, payload);
+        }
+    });
+}
+
+/// Parse a debounce interval in milliseconds, logging invalid values
+fn parse_interval(value: &str) -> Option<Duration> {
+    match value.trim().parse::<u64>() {
+        Ok(ms) => Some(Duration::from_millis(ms)),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring invalid {} value '{}': {}",
+                DEBOUNCE_INTERVAL_ENV,
+                value,
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn playing(title: &str) -> PlaybackIdentity {
+        PlaybackIdentity::new(Some(title), Some("Artist"), Some("Album"), true)
+    }
+
+    fn debouncer() -> PlaybackStateDebouncer<u64> {
+        PlaybackStateDebouncer::new(Duration::from_secs(5))
+    }
+
+    fn pushed(decision: Debounced<u64>) -> Option<u64> {
+        match decision {
+            Debounced::Push(payload) => Some(payload),
+            _ => None,
+        }
+    }
+
+    fn deferred(decision: Debounced<u64>) -> PendingFlush {
+        match decision {
+            Debounced::Deferred(flush) => flush,
+            other => panic!("expected a deferred update, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_position_only_change_is_debounced() {
+        let debouncer = debouncer();
+        let start = Instant::now();
+
+        assert_eq!(
+            pushed(debouncer.submit_at(&playing("Song"), 0, start)),
+            Some(0)
+        );
+        // Seeks report the same track and play state
+        let flush =
+            deferred(debouncer.submit_at(&playing("Song"), 1, start + Duration::from_secs(1)));
+        assert_eq!(flush.delay, Duration::from_secs(4));
+        assert!(matches!(
+            debouncer.submit_at(&playing("Song"), 4, start + Duration::from_secs(4)),
+            Debounced::Coalesced
+        ));
+        // Once the interval has passed, an update goes straight through
+        // and supersedes the pending one
+        assert_eq!(
+            pushed(debouncer.submit_at(&playing("Song"), 5, start + Duration::from_secs(5))),
+            Some(5)
+        );
+        assert_eq!(
+            debouncer.flush_at(flush, start + Duration::from_secs(5)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_final_position_after_seek_burst_is_delivered() {
+        let debouncer = debouncer();
+        let start = Instant::now();
+
+        assert_eq!(
+            pushed(debouncer.submit_at(&playing("Song"), 0, start)),
+            Some(0)
+        );
+        let flush =
+            deferred(debouncer.submit_at(&playing("Song"), 10, start + Duration::from_millis(100)));
+        for (i, position) in [20, 30, 40].into_iter().enumerate() {
+            let at = start + Duration::from_millis(200 + 100 * i as u64);
+            assert!(matches!(
+                debouncer.submit_at(&playing("Song"), position, at),
+                Debounced::Coalesced
+            ));
+        }
+
+        // The trailing flush delivers the last seek, once
+        let expiry = start + Duration::from_millis(100) + flush.delay;
+        assert_eq!(debouncer.flush_at(flush, expiry), Some(40));
+        assert_eq!(debouncer.flush_at(flush, expiry), None);
+
+        // The flush counts as a push for the next interval
+        assert!(matches!(
+            debouncer.submit_at(&playing("Song"), 50, expiry + Duration::from_secs(1)),
+            Debounced::Deferred(_)
+        ));
+    }
+
+    #[test]
+    fn test_track_change_is_immediate() {
+        let debouncer = debouncer();
+        let start = Instant::now();
+
+        assert!(pushed(debouncer.submit_at(&playing("First"), 0, start)).is_some());
+        let flush =
+            deferred(debouncer.submit_at(&playing("First"), 1, start + Duration::from_millis(5)));
+        assert!(pushed(debouncer.submit_at(
+            &playing("Second"),
+            2,
+            start + Duration::from_millis(10)
+        ))
+        .is_some());
+        // The first track's pending update is stale now
+        assert_eq!(
+            debouncer.flush_at(flush, start + Duration::from_secs(5)),
+            None
+        );
+        assert!(pushed(debouncer.submit_at(
+            &playing("Second"),
+            3,
+            start + Duration::from_millis(20)
+        ))
+        .is_none());
+
+        // Pausing is a genuine change too
+        let paused = PlaybackIdentity::new(Some("Second"), Some("Artist"), Some("Album"), false);
+        assert!(
+            pushed(debouncer.submit_at(&paused, 4, start + Duration::from_millis(30))).is_some()
+        );
+    }
+
+    #[test]
+    fn test_reset_lets_next_update_through() {
+        let debouncer = debouncer();
+        let start = Instant::now();
+
+        assert!(pushed(debouncer.submit_at(&playing("Song"), 0, start)).is_some());
+        let flush =
+            deferred(debouncer.submit_at(&playing("Song"), 1, start + Duration::from_millis(5)));
+        debouncer.reset();
+        assert_eq!(
+            debouncer.flush_at(flush, start + Duration::from_secs(5)),
+            None
+        );
+        assert!(pushed(debouncer.submit_at(
+            &playing("Song"),
+            2,
+            start + Duration::from_millis(10)
+        ))
+        .is_some());
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("250"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_interval(" 0 "), Some(Duration::ZERO));
+        assert_eq!(parse_interval("soon"), None);
+    }
+}
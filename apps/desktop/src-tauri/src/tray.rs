@@ -8,6 +8,8 @@ use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
 use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
 use tauri::{AppHandle, Emitter, Manager, Runtime, Window, Wry};
 
+use crate::playback_debounce::{schedule_flush, Debounced, PlaybackDebouncers, PlaybackIdentity};
+
 /// Playback state for updating tray menu
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PlaybackState {
@@ -145,6 +147,9 @@ pub fn minimize_to_tray(window: &Window) -> Result<(), tauri::Error> {
 }
 
 /// Tauri command to update playback state from frontend
+///
+/// Repeats of the current track and play state are debounced, with the
+/// latest one applied when the interval expires.
 #[tauri::command]
 pub fn update_playback_state(
     app: AppHandle<Wry>,
@@ -152,13 +157,43 @@ pub fn update_playback_state(
     track_title: Option<String>,
     artist_name: Option<String>,
 ) -> Result<(), String> {
+    let identity = PlaybackIdentity::new(
+        track_title.as_deref(),
+        artist_name.as_deref(),
+        None,
+        is_playing,
+    );
     let state = PlaybackState {
         is_playing,
         track_title,
         artist_name,
     };
+    match app
+        .state::<PlaybackDebouncers>()
+        .tray
+        .submit(&identity, state)
+    {
+        Debounced::Push(state) => apply_playback_state(&app, &state),
+        Debounced::Deferred(flush) => {
+            schedule_flush(
+                &app,
+                |d| &d.tray,
+                flush,
+                |app, state| {
+                    if let Err(e) = apply_playback_state(app, &state) {
+                        tracing::warn!("Failed to update tray playback state: {}", e);
+                    }
+                },
+            );
+            Ok(())
+        }
+        Debounced::Coalesced => Ok(()),
+    }
+}
 
-    update_tray_menu(&app, &state).map_err(|e| e.to_string())?;
+/// Reflect playback state in the tray menu and tooltip
+fn apply_playback_state(app: &AppHandle<Wry>, state: &PlaybackState) -> Result<(), String> {
+    update_tray_menu(app, state).map_err(|e| e.to_string())?;
 
     // Update tooltip with current track
     if let Some(tray) = app.tray_by_id("main-tray") {
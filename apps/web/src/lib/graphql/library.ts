@@ -125,6 +125,7 @@ export const ALBUM_QUERY = gql`
         accent
         vibrant
         muted
+        text
       }
       createdAt
       updatedAt
@@ -71,6 +71,7 @@ export class WebSocketClient {
   private _deviceId: string;
   private _sessionId: string | null = null;
   private _activeDeviceId: string | null = null;
+  private resumeToken: string | null = null;
 
   // Reconnection
   private reconnectAttempt = 0;
@@ -164,6 +165,7 @@ export class WebSocketClient {
   disconnect(): void {
     this.shouldReconnect = false;
     this.messageQueue = []; // Clear queued messages to prevent stale data on reconnect
+    this.resumeToken = null; // Explicit disconnects start a fresh session next time
     this.cleanup();
     this.setState('disconnected');
   }
@@ -249,6 +251,9 @@ export class WebSocketClient {
     url.searchParams.set('device_id', this._deviceId);
     url.searchParams.set('device_name', deviceInfo.device_name);
     url.searchParams.set('device_type', deviceInfo.device_type);
+    if (this.resumeToken) {
+      url.searchParams.set('resume_token', this.resumeToken);
+    }
 
     try {
       this.ws = new WebSocket(url.toString());
@@ -296,6 +301,7 @@ export class WebSocketClient {
         case 'Connected':
           this._sessionId = message.payload.session_id;
           this._activeDeviceId = message.payload.active_device_id;
+          this.resumeToken = message.payload.resume_token ?? null;
           this.setState('connected');
           this.startHeartbeat();
           this.flushMessageQueue();
@@ -51,6 +51,10 @@ export interface ConnectedPayload {
   device_id: string;
   session_id: string;
   active_device_id: string | null;
+  /** Single-use token for resuming this session after a disconnect */
+  resume_token: string;
+  /** Whether this connection resumed a previous session */
+  resumed?: boolean;
 }
 
 export interface ErrorPayload {
@@ -445,7 +445,12 @@ export function installMockWebSocket(): () => void {
  * Create a mock Connected message payload
  */
 export function createConnectedMessage(
-  overrides: Partial<{ session_id: string; device_id: string; active_device_id: string | null }> = {}
+  overrides: Partial<{
+    session_id: string;
+    device_id: string;
+    active_device_id: string | null;
+    resume_token: string;
+  }> = {}
 ): ServerMessage {
   return {
     type: 'Connected',
@@ -453,6 +458,7 @@ export function createConnectedMessage(
       session_id: overrides.session_id ?? 'test-session-123',
       device_id: overrides.device_id ?? 'test-device-456',
       active_device_id: overrides.active_device_id ?? null,
+      resume_token: overrides.resume_token ?? 'test-resume-token',
     },
   };
 }
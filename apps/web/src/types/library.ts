@@ -91,6 +91,7 @@ export interface GqlCoverArtColors {
   accent?: string
   vibrant?: string
   muted?: string
+  text?: string
 }
 
 /**
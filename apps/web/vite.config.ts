@@ -95,7 +95,9 @@ export default defineConfig({
     proxy: {
       '/api': {
         target: 'http://localhost:4440',
-        changeOrigin: true
+        changeOrigin: true,
+        // Same as the nginx proxy: the backend serves /stream, /account, ...
+        rewrite: (path) => path.replace(/^\/api/, '')
       },
       '/graphql': {
         target: 'http://localhost:4440',
@@ -4,14 +4,22 @@
 //! Configuration is loaded from environment variables with sensible defaults for
 //! development environments.
 
+use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result};
 use resonance_shared_config::{
-    CommonConfig, DatabaseConfig, Environment, LidarrConfig, OllamaConfig, RedisConfig,
+    CommonConfig, DatabaseConfig, Environment, FeatureFlags, LidarrConfig, OllamaConfig,
+    RedisConfig,
 };
 
+use crate::jobs::feature_extraction::FeatureExtractionConfig;
+use crate::jobs::resample::DEFAULT_ANALYSIS_SAMPLE_RATE;
+
+/// Prefix of the per-format analyzer overrides, e.g. `ANALYSIS_FEATURES_MP3`
+const ANALYSIS_FEATURES_FORMAT_PREFIX: &str = "ANALYSIS_FEATURES_";
+
 /// Worker configuration
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -21,6 +29,9 @@ pub struct Config {
     /// Job polling interval in seconds
     pub poll_interval_secs: u64,
 
+    /// Longest the polling interval grows to while the worker is idle
+    pub max_poll_interval_secs: u64,
+
     /// Maximum concurrent jobs
     pub max_concurrent_jobs: usize,
 
@@ -35,6 +46,21 @@ pub struct Config {
 
     /// Meilisearch API key
     pub meilisearch_key: String,
+
+    /// Sample rate audio is resampled to before feature analysis, so
+    /// features from tracks at different native rates stay comparable
+    /// (`None` analyzes at the native rate)
+    pub analysis_sample_rate: Option<u32>,
+
+    /// Audio analyzers to run during feature extraction
+    pub analysis_features: FeatureExtractionConfig,
+
+    /// Analyzer overrides keyed by lowercase file extension
+    pub analysis_features_by_format: HashMap<String, FeatureExtractionConfig>,
+
+    /// Whether library scans follow symlinks to their target files and
+    /// directories (otherwise symlinks are skipped)
+    pub scan_follow_symlinks: bool,
 }
 
 impl Config {
@@ -51,6 +77,11 @@ impl Config {
                 .parse()
                 .context("Invalid WORKER_POLL_INTERVAL value")?,
 
+            max_poll_interval_secs: env::var("WORKER_MAX_POLL_INTERVAL")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .context("Invalid WORKER_MAX_POLL_INTERVAL value")?,
+
             max_concurrent_jobs: env::var("WORKER_MAX_CONCURRENT_JOBS")
                 .unwrap_or_else(|_| "4".to_string())
                 .parse()
@@ -71,24 +102,52 @@ impl Config {
 
             meilisearch_key: env::var("MEILISEARCH_KEY")
                 .unwrap_or_else(|_| "masterKey".to_string()),
+
+            analysis_sample_rate: parse_analysis_sample_rate(
+                env::var("ANALYSIS_SAMPLE_RATE").ok().as_deref(),
+            )?,
+
+            analysis_features: match env::var("ANALYSIS_FEATURES") {
+                Ok(value) => FeatureExtractionConfig::parse(&value)
+                    .map_err(|e| anyhow::anyhow!("Invalid ANALYSIS_FEATURES value: {}", e))?,
+                Err(_) => FeatureExtractionConfig::default(),
+            },
+
+            analysis_features_by_format: parse_analysis_features_by_format(env::vars())?,
+
+            scan_follow_symlinks: env::var("SCAN_FOLLOW_SYMLINKS")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
         })
     }
 
+    /// Analyzers to run for a track file, honoring per-format overrides
+    pub fn analysis_features_for(&self, path: &Path) -> FeatureExtractionConfig {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| {
+                self.analysis_features_by_format
+                    .get(&ext.to_ascii_lowercase())
+            })
+            .copied()
+            .unwrap_or(self.analysis_features)
+    }
+
     // Convenience accessors for common config fields
 
     /// Get database URL (for backward compatibility)
     pub fn database_url(&self) -> &str {
-        &self.common.database.url
+        self.common.database.url.expose()
     }
 
     /// Get Redis URL (for backward compatibility)
     pub fn redis_url(&self) -> &str {
-        &self.common.redis.url
+        self.common.redis.url.expose()
     }
 
-    /// Get music library path
-    pub fn music_library_path(&self) -> &PathBuf {
-        &self.common.music_library_path
+    /// Get music library root directories
+    pub fn music_roots(&self) -> &[PathBuf] {
+        self.common.music_roots()
     }
 
     /// Get database configuration
@@ -106,6 +165,11 @@ impl Config {
         &self.common.ollama
     }
 
+    /// Get feature flags
+    pub fn features(&self) -> FeatureFlags {
+        self.common.features
+    }
+
     /// Get Lidarr configuration (if configured)
     pub fn lidarr(&self) -> Option<&LidarrConfig> {
         self.common.lidarr.as_ref()
@@ -137,6 +201,33 @@ impl Config {
     }
 }
 
+/// Parse `ANALYSIS_SAMPLE_RATE`; unset uses the default, `0` disables resampling
+fn parse_analysis_sample_rate(value: Option<&str>) -> Result<Option<u32>> {
+    let rate: u32 = match value {
+        Some(v) => v.parse().context("Invalid ANALYSIS_SAMPLE_RATE value")?,
+        None => DEFAULT_ANALYSIS_SAMPLE_RATE,
+    };
+    Ok((rate > 0).then_some(rate))
+}
+
+/// Collect `ANALYSIS_FEATURES_<EXT>` overrides, keyed by lowercase extension
+fn parse_analysis_features_by_format(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<HashMap<String, FeatureExtractionConfig>> {
+    vars.into_iter()
+        .filter_map(|(key, value)| {
+            let format = key.strip_prefix(ANALYSIS_FEATURES_FORMAT_PREFIX)?;
+            Some((format.to_ascii_lowercase(), key.clone(), value))
+        })
+        .filter(|(format, _, _)| !format.is_empty())
+        .map(|(format, key, value)| {
+            FeatureExtractionConfig::parse(&value)
+                .map(|config| (format, config))
+                .map_err(|e| anyhow::anyhow!("Invalid {} value: {}", key, e))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -285,6 +376,38 @@ mod tests {
         assert_eq!(retry_delay, 120);
     }
 
+    #[test]
+    fn test_analysis_sample_rate() {
+        assert_eq!(
+            parse_analysis_sample_rate(None).unwrap(),
+            Some(DEFAULT_ANALYSIS_SAMPLE_RATE)
+        );
+        assert_eq!(
+            parse_analysis_sample_rate(Some("48000")).unwrap(),
+            Some(48_000)
+        );
+        assert_eq!(parse_analysis_sample_rate(Some("0")).unwrap(), None);
+        assert!(parse_analysis_sample_rate(Some("fast")).is_err());
+    }
+
+    #[test]
+    fn test_analysis_features_by_format() {
+        let vars = [
+            ("ANALYSIS_FEATURES_MP3", "bpm"),
+            ("ANALYSIS_FEATURES_FLAC", "all"),
+            ("ANALYSIS_SAMPLE_RATE", "44100"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let overrides = parse_analysis_features_by_format(vars).unwrap();
+
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(overrides["flac"], FeatureExtractionConfig::all());
+        assert!(overrides["mp3"].enable_bpm && !overrides["mp3"].enable_spectral);
+
+        let invalid = [("ANALYSIS_FEATURES_OGG".to_string(), "loud".to_string())];
+        assert!(parse_analysis_features_by_format(invalid).is_err());
+    }
+
     #[test]
     fn test_invalid_poll_interval_format() {
         let _lock = ENV_MUTEX.lock().unwrap();
@@ -0,0 +1,67 @@
+//! Per-track analysis timings
+//!
+//! Feature extraction and embedding generation record how long each track
+//! took, and whether it succeeded, in `track_analysis_runs`. The API averages
+//! recent runs to estimate how long analysis of the rest of the library will
+//! take.
+
+use std::time::Duration;
+
+use uuid::Uuid;
+
+/// Which analysis a run performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisKind {
+    /// Audio feature extraction
+    Features,
+    /// Title and description embedding generation
+    Embedding,
+}
+
+impl AnalysisKind {
+    /// Value stored in `track_analysis_runs.kind`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AnalysisKind::Features => "features",
+            AnalysisKind::Embedding => "embedding",
+        }
+    }
+}
+
+/// Record one analysis run of a track
+///
+/// Best effort: the timing is only used for progress estimates, so a failed
+/// insert is logged rather than failing the job. Runs for tracks that no
+/// longer exist are not recorded.
+pub async fn record_run(
+    db: &sqlx::PgPool,
+    track_id: Uuid,
+    kind: AnalysisKind,
+    elapsed: Duration,
+    succeeded: bool,
+) {
+    let duration_ms = i32::try_from(elapsed.as_millis()).unwrap_or(i32::MAX);
+
+    let result = sqlx::query(
+        r#"
+        INSERT INTO track_analysis_runs (track_id, kind, duration_ms, succeeded)
+        SELECT $1, $2, $3, $4
+        WHERE EXISTS (SELECT 1 FROM tracks WHERE id = $1)
+        "#,
+    )
+    .bind(track_id)
+    .bind(kind.as_str())
+    .bind(duration_ms)
+    .bind(succeeded)
+    .execute(db)
+    .await;
+
+    if let Err(e) = result {
+        tracing::warn!(
+            track_id = %track_id,
+            kind = kind.as_str(),
+            error = %e,
+            "Failed to record analysis run"
+        );
+    }
+}
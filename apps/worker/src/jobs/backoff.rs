@@ -0,0 +1,148 @@
+//! Adaptive polling interval for the job runner
+//!
+//! An idle worker has no reason to poll every few seconds. [`IdleBackoff`]
+//! doubles the wait after each tick that finds no work, up to a maximum, and
+//! drops back to the base interval as soon as a tick does some work or a job
+//! is enqueued. [`wait_for_tick`] sleeps for the current interval but wakes
+//! early on an enqueue or shutdown, so a long idle sleep never delays either.
+
+use std::time::Duration;
+
+use tokio::sync::{broadcast, Notify};
+
+/// Poll interval that grows while the worker is idle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdleBackoff {
+    base: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl IdleBackoff {
+    /// Start at `base`, growing to at most `max` (never below `base`)
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            base,
+            max: max.max(base),
+            current: base,
+        }
+    }
+
+    /// How long to wait before the next tick
+    pub fn interval(&self) -> Duration {
+        self.current
+    }
+
+    /// Record a tick's outcome: grow the interval if it found no work,
+    /// otherwise reset it
+    pub fn record(&mut self, found_work: bool) {
+        if found_work {
+            self.reset();
+        } else {
+            self.current = self.current.saturating_mul(2).min(self.max);
+        }
+    }
+
+    /// Go back to the base interval
+    pub fn reset(&mut self) {
+        self.current = self.base;
+    }
+}
+
+/// Why [`wait_for_tick`] returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wake {
+    /// The interval elapsed; time to poll for work
+    Tick,
+    /// A job was enqueued during the wait
+    Enqueued,
+    /// The worker is shutting down
+    Shutdown,
+}
+
+/// Wait for `interval`, returning early if a job is enqueued or shutdown starts
+pub async fn wait_for_tick(
+    interval: Duration,
+    enqueued: &Notify,
+    shutdown_rx: &mut broadcast::Receiver<()>,
+) -> Wake {
+    tokio::select! {
+        _ = shutdown_rx.recv() => Wake::Shutdown,
+        _ = enqueued.notified() => Wake::Enqueued,
+        _ = tokio::time::sleep(interval) => Wake::Tick,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::time::Instant;
+
+    const BASE: Duration = Duration::from_secs(5);
+    const MAX: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_max_below_base_is_raised() {
+        let mut backoff = IdleBackoff::new(BASE, Duration::from_secs(1));
+        backoff.record(false);
+        assert_eq!(backoff.interval(), BASE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_interval_grows_while_idle_and_resets_on_work() {
+        let enqueued = Notify::new();
+        let (_shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+        let mut backoff = IdleBackoff::new(BASE, MAX);
+
+        // Each idle tick waits twice as long as the last, capped at MAX
+        let mut waits = Vec::new();
+        for _ in 0..6 {
+            let started = Instant::now();
+            let wake = wait_for_tick(backoff.interval(), &enqueued, &mut shutdown_rx).await;
+            assert_eq!(wake, Wake::Tick);
+            waits.push(started.elapsed().as_secs());
+            backoff.record(false);
+        }
+        assert_eq!(waits, vec![5, 10, 20, 40, 60, 60]);
+
+        // A tick that finds work brings the next wait back to BASE
+        backoff.record(true);
+        let started = Instant::now();
+        wait_for_tick(backoff.interval(), &enqueued, &mut shutdown_rx).await;
+        assert_eq!(started.elapsed(), BASE);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_enqueue_cuts_idle_wait_short() {
+        let enqueued = Arc::new(Notify::new());
+        let (_shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+
+        let notifier = enqueued.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(3)).await;
+            notifier.notify_one();
+        });
+
+        let started = Instant::now();
+        let wake = wait_for_tick(MAX, &enqueued, &mut shutdown_rx).await;
+        assert_eq!(wake, Wake::Enqueued);
+        assert_eq!(started.elapsed(), Duration::from_secs(3));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_shutdown_interrupts_long_wait() {
+        let enqueued = Notify::new();
+        let (shutdown_tx, mut shutdown_rx) = broadcast::channel(1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let _ = shutdown_tx.send(());
+        });
+
+        let started = Instant::now();
+        let wake = wait_for_tick(MAX, &enqueued, &mut shutdown_rx).await;
+        assert_eq!(wake, Wake::Shutdown);
+        assert_eq!(started.elapsed(), Duration::from_secs(1));
+    }
+}
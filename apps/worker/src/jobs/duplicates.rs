@@ -0,0 +1,277 @@
+//! Duplicate track detection
+//!
+//! Libraries often hold the same recording more than once: a FLAC rip next to
+//! an MP3 copy, or the same album in two folders. After each library scan the
+//! worker groups available tracks that are likely the same recording and
+//! points every member but one at a canonical copy through
+//! `tracks.duplicate_of`. Nothing is deleted; the API lists the groups so the
+//! user can decide what to keep.
+//!
+//! Two tracks are grouped when their files have the same content hash, or when
+//! their normalized artist and title match and their durations are within
+//! [`DUPLICATE_DURATION_TOLERANCE_MS`] of each other.
+
+use std::collections::HashMap;
+
+use resonance_media::normalize_name;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::error::WorkerResult;
+
+/// Maximum duration difference for tracks to count as the same recording
+///
+/// Different encoders pad or trim a few hundred milliseconds; a radio edit or
+/// live take differs by much more.
+pub const DUPLICATE_DURATION_TOLERANCE_MS: i32 = 2_000;
+
+/// Formats that are preferred as the canonical copy of a duplicate group
+const LOSSLESS_FORMATS: &[&str] = &["flac", "alac", "wav"];
+
+/// Track fields needed to detect duplicates
+#[derive(Debug, Clone, FromRow)]
+pub struct DuplicateCandidate {
+    pub id: Uuid,
+    pub title: String,
+    pub artist_name: String,
+    pub duration_ms: i32,
+    pub file_hash: Option<String>,
+    pub file_format: String,
+    pub bit_rate: Option<i32>,
+}
+
+impl DuplicateCandidate {
+    /// Ranking for picking the canonical copy: lossless first, then bit rate
+    fn quality(&self) -> (bool, i32) {
+        (
+            LOSSLESS_FORMATS.contains(&self.file_format.as_str()),
+            self.bit_rate.unwrap_or(0),
+        )
+    }
+}
+
+/// A group of tracks that are likely the same recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateGroup {
+    /// The copy the others are marked as duplicates of
+    pub canonical: Uuid,
+    /// The remaining copies
+    pub duplicates: Vec<Uuid>,
+}
+
+/// Group likely-duplicate tracks
+///
+/// The canonical copy of each group is the highest-quality one, with ties
+/// going to the lowest ID so repeated runs pick the same track. Groups are
+/// returned ordered by canonical ID; tracks without duplicates are omitted.
+pub fn find_duplicate_groups(candidates: &[DuplicateCandidate]) -> Vec<DuplicateGroup> {
+    let mut sets = DisjointSets::new(candidates.len());
+
+    let mut by_hash: HashMap<&str, usize> = HashMap::new();
+    let mut by_name: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    for (i, candidate) in candidates.iter().enumerate() {
+        if let Some(hash) = candidate.file_hash.as_deref() {
+            if let Some(&first) = by_hash.get(hash) {
+                sets.union(first, i);
+            } else {
+                by_hash.insert(hash, i);
+            }
+        }
+
+        let title = normalize_name(&candidate.title);
+        if !title.is_empty() {
+            by_name
+                .entry((normalize_name(&candidate.artist_name), title))
+                .or_default()
+                .push(i);
+        }
+    }
+
+    // Within a name, chain tracks whose durations are within the tolerance
+    for mut members in by_name.into_values() {
+        members.sort_by_key(|&i| candidates[i].duration_ms);
+        for pair in members.windows(2) {
+            let gap = candidates[pair[1]].duration_ms - candidates[pair[0]].duration_ms;
+            if gap <= DUPLICATE_DURATION_TOLERANCE_MS {
+                sets.union(pair[0], pair[1]);
+            }
+        }
+    }
+
+    let mut members_by_root: HashMap<usize, Vec<usize>> = HashMap::new();
+    for i in 0..candidates.len() {
+        members_by_root.entry(sets.find(i)).or_default().push(i);
+    }
+
+    let mut groups: Vec<DuplicateGroup> = members_by_root
+        .into_values()
+        .filter(|members| members.len() > 1)
+        .map(|members| {
+            let canonical = members
+                .iter()
+                .map(|&i| &candidates[i])
+                .max_by(|a, b| a.quality().cmp(&b.quality()).then(b.id.cmp(&a.id)))
+                .map(|c| c.id)
+                .expect("group has members");
+            let mut duplicates: Vec<Uuid> = members
+                .iter()
+                .map(|&i| candidates[i].id)
+                .filter(|&id| id != canonical)
+                .collect();
+            duplicates.sort();
+            DuplicateGroup {
+                canonical,
+                duplicates,
+            }
+        })
+        .collect();
+
+    groups.sort_by_key(|g| g.canonical);
+    groups
+}
+
+/// Recompute duplicate groups over the available library
+///
+/// Replaces every `duplicate_of` reference in one transaction, so tracks that
+/// stopped matching (retagged, or their copy removed) are released. Returns
+/// the number of tracks marked as duplicates.
+pub async fn detect_duplicates(db: &sqlx::PgPool) -> WorkerResult<usize> {
+    let candidates = sqlx::query_as::<_, DuplicateCandidate>(
+        r#"
+        SELECT t.id, t.title, a.name AS artist_name, t.duration_ms, t.file_hash,
+               t.file_format::text AS file_format, t.bit_rate
+        FROM tracks t
+        JOIN artists a ON a.id = t.artist_id
+        WHERE t.is_available = true
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+
+    let groups = find_duplicate_groups(&candidates);
+    let (duplicate_ids, canonical_ids): (Vec<Uuid>, Vec<Uuid>) = groups
+        .iter()
+        .flat_map(|g| g.duplicates.iter().map(move |&id| (id, g.canonical)))
+        .unzip();
+
+    let mut tx = db.begin().await?;
+    sqlx::query("UPDATE tracks SET duplicate_of = NULL WHERE duplicate_of IS NOT NULL")
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        UPDATE tracks t SET duplicate_of = d.canonical_id
+        FROM UNNEST($1::uuid[], $2::uuid[]) AS d(track_id, canonical_id)
+        WHERE t.id = d.track_id
+        "#,
+    )
+    .bind(&duplicate_ids)
+    .bind(&canonical_ids)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(duplicate_ids.len())
+}
+
+/// Union-find over candidate indices
+struct DisjointSets {
+    parent: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+        }
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.parent[i] != i {
+            self.parent[i] = self.parent[self.parent[i]];
+            i = self.parent[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parent[b.max(a)] = a.min(b);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        title: &str,
+        artist: &str,
+        duration_ms: i32,
+        format: &str,
+        bit_rate: i32,
+    ) -> DuplicateCandidate {
+        DuplicateCandidate {
+            id: Uuid::new_v4(),
+            title: title.to_string(),
+            artist_name: artist.to_string(),
+            duration_ms,
+            file_hash: Some(Uuid::new_v4().to_string()),
+            file_format: format.to_string(),
+            bit_rate: Some(bit_rate),
+        }
+    }
+
+    #[test]
+    fn test_matching_metadata_and_near_equal_duration_are_grouped() {
+        let flac = candidate("Bohemian Rhapsody", "Queen", 354_320, "flac", 1_411);
+        let mp3 = candidate(
+            "Bohemian Rhapsody (Remastered)",
+            "QUEEN",
+            355_100,
+            "mp3",
+            320,
+        );
+        let other = candidate("Killer Queen", "Queen", 180_000, "flac", 1_411);
+
+        let groups = find_duplicate_groups(&[mp3.clone(), flac.clone(), other]);
+
+        assert_eq!(
+            groups,
+            vec![DuplicateGroup {
+                canonical: flac.id,
+                duplicates: vec![mp3.id],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_different_tracks_are_not_grouped() {
+        let tracks = [
+            // Same title, different artist
+            candidate("Hurt", "Nine Inch Nails", 373_000, "flac", 1_411),
+            candidate("Hurt", "Johnny Cash", 373_500, "flac", 1_411),
+            // Same song, much longer live take
+            candidate("Creep", "Radiohead", 238_000, "mp3", 320),
+            candidate("Creep", "Radiohead", 262_000, "mp3", 320),
+        ];
+
+        assert!(find_duplicate_groups(&tracks).is_empty());
+    }
+
+    #[test]
+    fn test_identical_content_hash_is_grouped_despite_tags() {
+        let mut a = candidate("Track 01", "Unknown Artist", 200_000, "mp3", 256);
+        let mut b = candidate("Intro", "Some Band", 200_000, "mp3", 256);
+        a.file_hash = Some("abc".to_string());
+        b.file_hash = Some("abc".to_string());
+
+        let groups = find_duplicate_groups(&[a.clone(), b.clone()]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].canonical, a.id.min(b.id));
+        assert_eq!(groups[0].duplicates, vec![a.id.max(b.id)]);
+    }
+}
@@ -3,17 +3,24 @@
 //! Generates vector embeddings for tracks using Ollama.
 //! Embeddings are used for semantic search and AI recommendations.
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 use tokio::time::timeout;
 
+use super::analysis_runs::{record_run, AnalysisKind};
 use crate::error::WorkerResult;
 use crate::AppState;
 
 /// Job-level timeout for embedding generation (2 minutes)
 const JOB_TIMEOUT_SECS: u64 = 120;
 
+/// Version of the title and description text templates
+///
+/// Bump when [`build_title_text`] or [`build_description_text`] change, so
+/// embeddings built from the old text are detected as stale and rebuilt.
+pub const EMBEDDING_TEMPLATE_VERSION: u32 = 1;
+
 /// Embedding generation job payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingGenerationJob {
@@ -26,16 +33,15 @@ pub struct EmbeddingGenerationJob {
 
 /// Track info for embedding generation
 #[derive(Debug, sqlx::FromRow)]
-struct TrackMetadata {
-    #[allow(dead_code)]
-    id: sqlx::types::Uuid,
-    title: String,
-    artist_name: Option<String>,
-    album_title: Option<String>,
-    genres: Vec<String>,
-    ai_mood: Vec<String>,
-    ai_tags: Vec<String>,
-    ai_description: Option<String>,
+pub(crate) struct TrackMetadata {
+    pub(crate) id: sqlx::types::Uuid,
+    pub(crate) title: String,
+    pub(crate) artist_name: Option<String>,
+    pub(crate) album_title: Option<String>,
+    pub(crate) genres: Vec<String>,
+    pub(crate) ai_mood: Vec<String>,
+    pub(crate) ai_tags: Vec<String>,
+    pub(crate) ai_description: Option<String>,
 }
 
 /// Execute the embedding generation job
@@ -48,13 +54,14 @@ pub async fn execute(state: &AppState, job: &EmbeddingGenerationJob) -> WorkerRe
     tracing::info!(track_id = %track_id, "Generating embedding for track");
 
     // Wrap in timeout to prevent runaway jobs
+    let started = Instant::now();
     let result = timeout(
         Duration::from_secs(JOB_TIMEOUT_SECS),
         execute_inner(state, track_id, job.force),
     )
     .await;
 
-    match result {
+    let outcome = match result {
         Ok(inner_result) => inner_result,
         Err(_) => {
             tracing::error!(track_id = %track_id, timeout_secs = JOB_TIMEOUT_SECS, "Embedding generation timed out");
@@ -62,15 +69,32 @@ pub async fn execute(state: &AppState, job: &EmbeddingGenerationJob) -> WorkerRe
                 seconds: JOB_TIMEOUT_SECS,
             })
         }
+    };
+
+    // Skips of tracks that already have embeddings say nothing about timing
+    if !matches!(outcome, Ok(false)) {
+        record_run(
+            &state.db,
+            track_id,
+            AnalysisKind::Embedding,
+            started.elapsed(),
+            outcome.is_ok(),
+        )
+        .await;
     }
+
+    outcome.map(|_| ())
 }
 
 /// Inner execution logic (called within timeout)
+///
+/// Returns whether embeddings were generated, or `false` if they already
+/// existed.
 async fn execute_inner(
     state: &AppState,
     track_id: sqlx::types::Uuid,
     force: bool,
-) -> WorkerResult<()> {
+) -> WorkerResult<bool> {
     // Ensure Ollama client is available
     let ollama = state.ollama.as_ref().ok_or_else(|| {
         crate::WorkerError::OllamaUnavailable(
@@ -78,20 +102,24 @@ async fn execute_inner(
         )
     })?;
 
+    let version = embedding_version(ollama);
+
     // Check if both embeddings already exist (unless force regeneration)
     // Using EXISTS for efficiency - avoids fetching data
-    // We check for both title AND description embeddings to ensure completeness
+    // We check for both title AND description embeddings to ensure completeness,
+    // built by the current model and template
     if !force {
         let exists: (bool,) = sqlx::query_as(
-            "SELECT EXISTS(SELECT 1 FROM track_embeddings WHERE track_id = $1 AND title_embedding IS NOT NULL AND description_embedding IS NOT NULL)",
+            "SELECT EXISTS(SELECT 1 FROM track_embeddings WHERE track_id = $1 AND title_embedding IS NOT NULL AND description_embedding IS NOT NULL AND embedding_version = $2)",
         )
         .bind(track_id)
+        .bind(&version)
         .fetch_one(&state.db)
         .await?;
 
         if exists.0 {
             tracing::debug!(track_id = %track_id, "Both embeddings already exist, skipping");
-            return Ok(());
+            return Ok(false);
         }
     }
 
@@ -119,11 +147,7 @@ async fn execute_inner(
     .ok_or_else(|| crate::WorkerError::NotFound(format!("Track not found: {}", track_id)))?;
 
     // Construct text for title embedding
-    let title_text = format!(
-        "{} by {}",
-        track.title,
-        track.artist_name.as_deref().unwrap_or("Unknown Artist")
-    );
+    let title_text = build_title_text(&track);
 
     // Construct rich description text for semantic embedding
     let description_text = build_description_text(&track);
@@ -141,42 +165,94 @@ async fn execute_inner(
         ollama.generate_embedding(&description_text)
     )?;
 
-    // Validate embedding dimensions
-    resonance_ollama_client::validate_embedding_dimension(&title_embedding)?;
-    resonance_ollama_client::validate_embedding_dimension(&description_embedding)?;
+    // Validate embedding dimensions against the configured model dimension
+    let dimension = ollama.embedding_dimension();
+    resonance_ollama_client::check_embedding_dimension(&title_embedding, dimension)?;
+    resonance_ollama_client::check_embedding_dimension(&description_embedding, dimension)?;
+
+    upsert_embeddings(
+        &state.db,
+        track_id,
+        &title_embedding,
+        &description_embedding,
+        &version,
+    )
+    .await?;
+
+    tracing::info!(
+        track_id = %track_id,
+        title = %track.title,
+        "Embedding generation completed"
+    );
+
+    Ok(true)
+}
+
+/// Version recorded with embeddings from `ollama`
+///
+/// Combines the embedding model, its dimension and the text template
+/// version; embeddings recorded with any other version are stale.
+pub(crate) fn embedding_version(ollama: &resonance_ollama_client::OllamaClient) -> String {
+    format!(
+        "{}:{}:t{}",
+        ollama.config().embedding_model,
+        ollama.embedding_dimension(),
+        EMBEDDING_TEMPLATE_VERSION
+    )
+}
 
+/// Store title and description embeddings for a track, replacing existing ones
+///
+/// The reduced description embedding used by fast search is stored with them,
+/// along with the `version` that produced them.
+pub(crate) async fn upsert_embeddings(
+    db: &sqlx::PgPool,
+    track_id: sqlx::types::Uuid,
+    title_embedding: &[f32],
+    description_embedding: &[f32],
+    version: &str,
+) -> WorkerResult<()> {
     // Convert embeddings to pgvector format (text representation)
-    let title_vec_str = format_embedding_for_pgvector(&title_embedding)?;
-    let description_vec_str = format_embedding_for_pgvector(&description_embedding)?;
+    let title_vec_str = format_embedding_for_pgvector(title_embedding)?;
+    let description_vec_str = format_embedding_for_pgvector(description_embedding)?;
+    let reduced_vec_str = format_embedding_for_pgvector(
+        &resonance_ollama_client::reduce_embedding(description_embedding)?,
+    )?;
 
-    // Upsert embeddings into track_embeddings table
     sqlx::query(
         r#"
-        INSERT INTO track_embeddings (track_id, title_embedding, description_embedding, created_at, updated_at)
-        VALUES ($1, $2::vector, $3::vector, NOW(), NOW())
+        INSERT INTO track_embeddings (track_id, title_embedding, description_embedding, description_embedding_reduced, embedding_version, created_at, updated_at)
+        VALUES ($1, $2::vector, $3::vector, $4::vector, $5, NOW(), NOW())
         ON CONFLICT (track_id) DO UPDATE SET
             title_embedding = EXCLUDED.title_embedding,
             description_embedding = EXCLUDED.description_embedding,
+            description_embedding_reduced = EXCLUDED.description_embedding_reduced,
+            embedding_version = EXCLUDED.embedding_version,
             updated_at = NOW()
         "#,
     )
     .bind(track_id)
     .bind(&title_vec_str)
     .bind(&description_vec_str)
-    .execute(&state.db)
+    .bind(&reduced_vec_str)
+    .bind(version)
+    .execute(db)
     .await?;
 
-    tracing::info!(
-        track_id = %track_id,
-        title = %track.title,
-        "Embedding generation completed"
-    );
-
     Ok(())
 }
 
+/// Build the short "title by artist" text used for the title embedding
+pub(crate) fn build_title_text(track: &TrackMetadata) -> String {
+    format!(
+        "{} by {}",
+        track.title,
+        track.artist_name.as_deref().unwrap_or("Unknown Artist")
+    )
+}
+
 /// Build rich description text from track metadata
-fn build_description_text(track: &TrackMetadata) -> String {
+pub(crate) fn build_description_text(track: &TrackMetadata) -> String {
     let mut parts = Vec::new();
 
     // Title and artist
@@ -216,7 +292,7 @@ fn build_description_text(track: &TrackMetadata) -> String {
 
 /// Format embedding vector as pgvector string representation
 /// Returns an error if any values are non-finite (NaN/inf)
-fn format_embedding_for_pgvector(embedding: &[f32]) -> WorkerResult<String> {
+pub(crate) fn format_embedding_for_pgvector(embedding: &[f32]) -> WorkerResult<String> {
     // Validate that all values are finite to prevent database errors
     if embedding.iter().any(|v| !v.is_finite()) {
         return Err(crate::WorkerError::InvalidPayload(
@@ -232,6 +308,20 @@ fn format_embedding_for_pgvector(embedding: &[f32]) -> WorkerResult<String> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_embedding_version_names_model_dimension_and_template() {
+        let config = resonance_shared_config::OllamaConfig::with_url("http://localhost:11434");
+        let ollama = resonance_ollama_client::OllamaClient::new(&config).unwrap();
+
+        assert_eq!(
+            embedding_version(&ollama),
+            format!(
+                "{}:{}:t{}",
+                config.embedding_model, config.embedding_dimension, EMBEDDING_TEMPLATE_VERSION
+            )
+        );
+    }
+
     #[test]
     fn test_build_description_text_full() {
         let track = TrackMetadata {
@@ -0,0 +1,154 @@
+//! Reduced embedding backfill job
+//!
+//! Fills `track_embeddings.description_embedding_reduced` for tracks whose
+//! description embedding was stored before reduced embeddings existed. New
+//! embeddings get their reduced vector when they are written (see
+//! [`super::embedding_generation::upsert_embeddings`]); this job only catches
+//! up older rows. Rows are processed in track id order, a batch at a time, and
+//! each batch commits on its own, so an interrupted run simply continues with
+//! the rows still missing a reduced vector next time.
+
+use resonance_ollama_client::reduce_embedding;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::embedding_generation::format_embedding_for_pgvector;
+use super::weekly_playlist::parse_pgvector_string;
+use crate::error::{WorkerError, WorkerResult};
+use crate::AppState;
+
+/// Reduced embedding backfill job payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingReductionJob {
+    /// Number of embeddings reduced per batch
+    pub batch_size: i64,
+}
+
+impl Default for EmbeddingReductionJob {
+    fn default() -> Self {
+        Self { batch_size: 500 }
+    }
+}
+
+/// Description embedding missing its reduced counterpart
+#[derive(Debug, sqlx::FromRow)]
+struct UnreducedEmbedding {
+    track_id: Uuid,
+    description_embedding: String,
+}
+
+/// Execute the reduced embedding backfill job
+pub async fn execute(state: &AppState, job: &EmbeddingReductionJob) -> WorkerResult<()> {
+    let (reduced, skipped) = backfill_reduced_embeddings(&state.db, job.batch_size).await?;
+
+    tracing::info!(reduced, skipped, "Reduced embedding backfill finished");
+
+    Ok(())
+}
+
+/// Reduce every description embedding lacking a reduced vector
+///
+/// Returns `(reduced, skipped)`; embeddings that cannot be parsed or have the
+/// wrong dimension are logged and skipped.
+async fn backfill_reduced_embeddings(db: &PgPool, batch_size: i64) -> WorkerResult<(usize, usize)> {
+    if batch_size < 1 {
+        return Err(WorkerError::InvalidPayload(format!(
+            "batch_size must be at least 1, got {}",
+            batch_size
+        )));
+    }
+
+    let mut after = Uuid::nil();
+    let (mut reduced, mut skipped) = (0, 0);
+
+    loop {
+        let batch: Vec<UnreducedEmbedding> = sqlx::query_as(
+            r#"
+            SELECT track_id, description_embedding::text AS description_embedding
+            FROM track_embeddings
+            WHERE description_embedding IS NOT NULL
+              AND description_embedding_reduced IS NULL
+              AND track_id > $1
+            ORDER BY track_id
+            LIMIT $2
+            "#,
+        )
+        .bind(after)
+        .bind(batch_size)
+        .fetch_all(db)
+        .await?;
+
+        let Some(last) = batch.last() else {
+            break;
+        };
+        after = last.track_id;
+
+        let mut track_ids = Vec::with_capacity(batch.len());
+        let mut vectors = Vec::with_capacity(batch.len());
+        for row in &batch {
+            match reduce_stored_embedding(&row.description_embedding) {
+                Ok(vector) => {
+                    track_ids.push(row.track_id);
+                    vectors.push(vector);
+                }
+                Err(e) => {
+                    tracing::warn!(track_id = %row.track_id, error = %e, "Skipping embedding reduction");
+                    skipped += 1;
+                }
+            }
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE track_embeddings te
+            SET description_embedding_reduced = r.reduced::vector
+            FROM UNNEST($1::uuid[], $2::text[]) AS r(track_id, reduced)
+            WHERE te.track_id = r.track_id
+            "#,
+        )
+        .bind(&track_ids)
+        .bind(&vectors)
+        .execute(db)
+        .await?;
+        reduced += track_ids.len();
+
+        tracing::debug!(reduced, skipped, "Reduced embedding batch stored");
+
+        if (batch.len() as i64) < batch_size {
+            break;
+        }
+    }
+
+    Ok((reduced, skipped))
+}
+
+/// Reduce a stored pgvector embedding, returning the reduced pgvector string
+fn reduce_stored_embedding(stored: &str) -> WorkerResult<String> {
+    let embedding = parse_pgvector_string(stored)?;
+    format_embedding_for_pgvector(&reduce_embedding(&embedding)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resonance_ollama_client::{EMBEDDING_DIMENSION, REDUCED_EMBEDDING_DIMENSION};
+
+    #[test]
+    fn test_reduce_stored_embedding() {
+        let values: Vec<String> = (0..EMBEDDING_DIMENSION)
+            .map(|i| format!("{:.6}", (i as f32 * 0.37).sin()))
+            .collect();
+        let stored = format!("[{}]", values.join(","));
+
+        let reduced = parse_pgvector_string(&reduce_stored_embedding(&stored).unwrap()).unwrap();
+
+        assert_eq!(reduced.len(), REDUCED_EMBEDDING_DIMENSION);
+    }
+
+    #[test]
+    fn test_reduce_stored_embedding_rejects_wrong_dimension() {
+        assert!(reduce_stored_embedding("[0.1,0.2,0.3]").is_err());
+        assert!(reduce_stored_embedding("not a vector").is_err());
+    }
+}
@@ -0,0 +1,163 @@
+//! Crossfade point detection
+//!
+//! Finds where a track's audible content starts and ends so clients can
+//! crossfade from the "musical end" instead of a fixed offset. The decoded
+//! mono signal is reduced to a short-window RMS envelope, and the active
+//! region is the span between the first and last window above a silence
+//! threshold.
+
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+/// Length of one envelope window in milliseconds
+pub const ENVELOPE_WINDOW_MS: u32 = 10;
+
+/// RMS level (dBFS) below which a window counts as silence
+pub const SILENCE_THRESHOLD_DB: f32 = -50.0;
+
+/// Fade points for a track, in milliseconds from its start
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FadePoints {
+    /// End of leading silence; audible content starts here
+    pub fade_in_ms: u32,
+    /// Start of trailing silence; audible content ends here
+    pub fade_out_start_ms: u32,
+}
+
+/// Accumulates a mono signal into an RMS envelope of fixed-size windows
+#[derive(Debug)]
+pub struct EnvelopeBuilder {
+    window_len: usize,
+    sum_squared: f64,
+    count: usize,
+    windows: Vec<f32>,
+}
+
+impl EnvelopeBuilder {
+    /// Create a builder producing [`ENVELOPE_WINDOW_MS`] windows
+    pub fn new(sample_rate: u32) -> Self {
+        let window_len = (sample_rate as usize * ENVELOPE_WINDOW_MS as usize / 1000).max(1);
+        Self {
+            window_len,
+            sum_squared: 0.0,
+            count: 0,
+            windows: Vec::new(),
+        }
+    }
+
+    /// Add one mono sample
+    pub fn push(&mut self, sample: f32) {
+        self.sum_squared += (sample * sample) as f64;
+        self.count += 1;
+        if self.count == self.window_len {
+            self.flush();
+        }
+    }
+
+    /// Finish the envelope, including a trailing partial window
+    pub fn finish(mut self) -> Vec<f32> {
+        if self.count > 0 {
+            self.flush();
+        }
+        self.windows
+    }
+
+    fn flush(&mut self) {
+        self.windows
+            .push((self.sum_squared / self.count as f64).sqrt() as f32);
+        self.sum_squared = 0.0;
+        self.count = 0;
+    }
+}
+
+/// Range of envelope windows between the first and last one above `threshold`
+///
+/// Returns `None` when every window is at or below the threshold.
+pub fn find_active_region(envelope: &[f32], threshold: f32) -> Option<Range<usize>> {
+    let start = envelope.iter().position(|&level| level > threshold)?;
+    let end = envelope.iter().rposition(|&level| level > threshold)?;
+    Some(start..end + 1)
+}
+
+/// Compute fade points from an envelope of [`ENVELOPE_WINDOW_MS`] windows
+///
+/// Returns `None` for a silent signal, leaving clients on their fixed
+/// crossfade.
+pub fn compute_fade_points(envelope: &[f32]) -> Option<FadePoints> {
+    let threshold = 10f32.powf(SILENCE_THRESHOLD_DB / 20.0);
+    let active = find_active_region(envelope, threshold)?;
+
+    Some(FadePoints {
+        fade_in_ms: active.start as u32 * ENVELOPE_WINDOW_MS,
+        fade_out_start_ms: active.end as u32 * ENVELOPE_WINDOW_MS,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 44100;
+
+    /// Envelope of `silence_before` ms of silence, `tone` ms of a 440 Hz
+    /// tone, then `silence_after` ms of silence
+    fn envelope(silence_before: u32, tone: u32, silence_after: u32) -> Vec<f32> {
+        let samples = |ms: u32| (SAMPLE_RATE as usize * ms as usize) / 1000;
+        let mut builder = EnvelopeBuilder::new(SAMPLE_RATE);
+        for _ in 0..samples(silence_before) {
+            builder.push(0.0);
+        }
+        for i in 0..samples(tone) {
+            let t = i as f32 / SAMPLE_RATE as f32;
+            builder.push(0.5 * (2.0 * std::f32::consts::PI * 440.0 * t).sin());
+        }
+        for _ in 0..samples(silence_after) {
+            builder.push(0.0);
+        }
+        builder.finish()
+    }
+
+    #[test]
+    fn test_envelope_window_count() {
+        // One second is 100 windows; a partial window is kept
+        assert_eq!(envelope(0, 1000, 0).len(), 100);
+        assert_eq!(envelope(0, 1005, 0).len(), 101);
+    }
+
+    #[test]
+    fn test_find_active_region() {
+        let levels = [0.0, 0.0, 0.5, 0.1, 0.0, 0.7, 0.0];
+        assert_eq!(find_active_region(&levels, 0.05), Some(2..6));
+        assert_eq!(find_active_region(&levels, 0.9), None);
+        assert_eq!(find_active_region(&[], 0.05), None);
+    }
+
+    #[test]
+    fn test_fade_points_with_trailing_silence() {
+        let points = compute_fade_points(&envelope(500, 3000, 2000)).unwrap();
+        assert_eq!(points.fade_in_ms, 500);
+        assert_eq!(points.fade_out_start_ms, 3500);
+    }
+
+    #[test]
+    fn test_fade_points_without_silence() {
+        let points = compute_fade_points(&envelope(0, 3000, 0)).unwrap();
+        assert_eq!(points.fade_in_ms, 0);
+        assert_eq!(points.fade_out_start_ms, 3000);
+    }
+
+    #[test]
+    fn test_fade_points_ignore_low_noise_tail() {
+        // A tail well under the threshold (-70 dBFS) counts as silence
+        let mut levels = envelope(0, 1000, 0);
+        levels.extend(std::iter::repeat_n(0.0003, 100));
+        let points = compute_fade_points(&levels).unwrap();
+        assert_eq!(points.fade_out_start_ms, 1000);
+    }
+
+    #[test]
+    fn test_fade_points_for_silence() {
+        assert_eq!(compute_fade_points(&envelope(2000, 0, 0)), None);
+    }
+}
@@ -1,10 +1,25 @@
 //! Audio feature extraction job
 //!
 //! Extracts audio features from tracks using Symphonia for analysis.
-//! Features include loudness, energy, BPM, key, danceability, and more.
+//! Features include loudness, energy, BPM, key, danceability, and more, plus
+//! the crossfade points where audible content starts and ends.
+//!
+//! Loudness, energy and fade points come from the decode pass and are always
+//! stored, including EBU R128 integrated loudness and loudness range (null
+//! for tracks too short to measure). The heavier analyzers (rhythm, chroma/key, spectral, MFCC) are
+//! toggled by a [`FeatureExtractionConfig`]; fields of disabled analyzers are
+//! stored as null. An acoustic fingerprint of the analysis window is always
+//! generated and stored in `tracks.fingerprint` when generation succeeds.
+//! When spectral analysis runs, tracks without genres are tagged with any
+//! high-confidence suggestions from [`genre_suggestion`].
+//!
+//! Admins can also ask the API to recompute one track's features. Those
+//! requests are rows in `feature_extraction_requests`, which the worker claims,
+//! runs, and marks completed or failed so the API can report status.
 
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 use serde::{Deserialize, Serialize};
 use symphonia::core::audio::SampleBuffer;
@@ -18,10 +33,17 @@ use uuid::Uuid;
 use crate::error::{WorkerError, WorkerResult};
 use crate::AppState;
 
+use super::analysis_runs::{record_run, AnalysisKind};
 // Import the analyzer modules
+use super::fade_points::{self, EnvelopeBuilder};
+use super::fingerprint;
+use super::genre_suggestion;
 use super::key_detection;
+use super::library_scan::{canonical_music_roots, is_within_roots};
+use super::loudness::{self, LoudnessMeter};
+use super::resample;
 use super::rhythm_analysis;
-use super::spectral;
+use super::spectral::{self, SpectralFeatures};
 
 /// Maximum file size for feature extraction (500 MB)
 const MAX_FILE_SIZE_BYTES: u64 = 500 * 1024 * 1024;
@@ -39,6 +61,86 @@ const ANALYSIS_DURATION_SECS: usize = 45;
 pub struct FeatureExtractionJob {
     /// Track ID (UUID as string) to process
     pub track_id: String,
+
+    /// Analyzers to run; `None` uses the worker's configuration for the
+    /// track's format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub features: Option<FeatureExtractionConfig>,
+}
+
+/// Which audio analyzers to run during feature extraction
+///
+/// Trades analysis depth for speed. Defaults to spectral and BPM analysis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeatureExtractionConfig {
+    /// Spectral analysis: valence, acousticness, instrumentalness, speechiness,
+    /// crest factor and short-term dynamic range
+    pub enable_spectral: bool,
+    /// Mel-frequency cepstral coefficients (timbre)
+    pub enable_mfcc: bool,
+    /// Chromagram key detection: key and mode
+    pub enable_chroma: bool,
+    /// Rhythm analysis: BPM and danceability
+    pub enable_bpm: bool,
+}
+
+impl Default for FeatureExtractionConfig {
+    fn default() -> Self {
+        Self {
+            enable_spectral: true,
+            enable_mfcc: false,
+            enable_chroma: false,
+            enable_bpm: true,
+        }
+    }
+}
+
+impl FeatureExtractionConfig {
+    /// Every analyzer enabled
+    pub fn all() -> Self {
+        Self {
+            enable_spectral: true,
+            enable_mfcc: true,
+            enable_chroma: true,
+            enable_bpm: true,
+        }
+    }
+
+    /// Every analyzer disabled (only decode-pass features are stored)
+    pub fn none() -> Self {
+        Self {
+            enable_spectral: false,
+            enable_mfcc: false,
+            enable_chroma: false,
+            enable_bpm: false,
+        }
+    }
+
+    /// Parse a comma-separated analyzer list, e.g. `spectral,bpm`
+    ///
+    /// Accepts `spectral`, `mfcc`, `chroma` (or `key`), `bpm` (or `rhythm`),
+    /// plus `all` and `none`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut config = Self::none();
+        for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            match name.to_ascii_lowercase().as_str() {
+                "all" => config = Self::all(),
+                "none" => config = Self::none(),
+                "spectral" => config.enable_spectral = true,
+                "mfcc" => config.enable_mfcc = true,
+                "chroma" | "key" => config.enable_chroma = true,
+                "bpm" | "rhythm" => config.enable_bpm = true,
+                other => return Err(format!("unknown analyzer '{}'", other)),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Whether any analyzer needs the buffered analysis window
+    fn needs_analysis_buffer(&self) -> bool {
+        self.enable_spectral || self.enable_mfcc || self.enable_chroma || self.enable_bpm
+    }
 }
 
 impl FeatureExtractionJob {
@@ -63,6 +165,13 @@ pub struct AudioFeatures {
     /// Overall loudness in LUFS (approximated from RMS)
     pub loudness: Option<f32>,
 
+    /// EBU R128 integrated loudness in LUFS; null when the track is shorter
+    /// than one 400 ms gating block or silent
+    pub lufs_integrated: Option<f32>,
+
+    /// EBU R128 loudness range in LU; null for tracks under 3 seconds
+    pub lufs_range: Option<f32>,
+
     /// Energy level (0.0 - 1.0) - derived from RMS
     pub energy: Option<f32>,
 
@@ -88,6 +197,55 @@ pub struct AudioFeatures {
     /// Dynamic range in dB
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dynamic_range: Option<f32>,
+
+    /// End of leading silence in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fade_in_ms: Option<u32>,
+
+    /// Start of trailing silence ("musical end") in milliseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fade_out_start_ms: Option<u32>,
+
+    /// Mean MFCCs over the analysis window - requires MFCC analysis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mfcc: Option<Vec<f32>>,
+
+    /// Peak-to-RMS ratio over the analysis window - requires spectral analysis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub crest_factor: Option<f32>,
+
+    /// Spread of short-term loudness in dB over the analysis window -
+    /// requires spectral analysis
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_term_dynamic_range: Option<f32>,
+
+    /// Acoustic fingerprint of the analysis window, stored in
+    /// `tracks.fingerprint` rather than with the other features
+    #[serde(skip)]
+    pub fingerprint: Option<String>,
+
+    /// Aggregated spectral features of the analysis window, used to suggest
+    /// genres for untagged tracks rather than stored
+    #[serde(skip)]
+    pub spectral: Option<SpectralFeatures>,
+}
+
+/// Features from the buffered analysis window, `None` where the analyzer
+/// was disabled or there was too little audio
+#[derive(Debug, Default)]
+struct WindowFeatures {
+    bpm: Option<f32>,
+    danceability: Option<f32>,
+    key: Option<String>,
+    mode: Option<String>,
+    valence: Option<f32>,
+    acousticness: Option<f32>,
+    instrumentalness: Option<f32>,
+    speechiness: Option<f32>,
+    mfcc: Option<Vec<f32>>,
+    crest_factor: Option<f32>,
+    short_term_dynamic_range: Option<f32>,
+    spectral: Option<SpectralFeatures>,
 }
 
 /// Track info for feature extraction
@@ -146,6 +304,63 @@ pub async fn execute(state: &AppState, job: &FeatureExtractionJob) -> WorkerResu
         .track_uuid()
         .map_err(|e| WorkerError::InvalidJobData(format!("Invalid track ID: {}", e)))?;
 
+    let started = Instant::now();
+    let outcome = extract_and_store(state, track_id, job).await;
+    let stored = matches!(outcome, Ok(true));
+    record_run(
+        &state.db,
+        track_id,
+        AnalysisKind::Features,
+        started.elapsed(),
+        stored,
+    )
+    .await;
+
+    outcome.map(|_| ())
+}
+
+/// Extract and save features for one track
+///
+/// Returns whether features were stored; oversized and undecodable files are
+/// skipped without an error.
+async fn extract_and_store(
+    state: &AppState,
+    track_id: Uuid,
+    job: &FeatureExtractionJob,
+) -> WorkerResult<bool> {
+    let canonical_track = resolve_track_file(state, track_id).await?;
+
+    // Check file size before processing
+    let metadata = std::fs::metadata(&canonical_track)?;
+    if metadata.len() > MAX_FILE_SIZE_BYTES {
+        tracing::warn!(
+            "Track {} exceeds max file size ({} bytes > {} bytes), skipping",
+            track_id,
+            metadata.len(),
+            MAX_FILE_SIZE_BYTES
+        );
+        return Ok(false); // Skip without error - very large files are not processed
+    }
+
+    let analyzers = job
+        .features
+        .unwrap_or_else(|| state.config.analysis_features_for(&canonical_track));
+
+    // Only update database if extraction succeeded (don't overwrite existing data with defaults)
+    let features = match extract_in_background(state, track_id, canonical_track, analyzers).await? {
+        Ok(f) => f,
+        Err(e) => {
+            tracing::warn!("Failed to extract features for track {}: {}", track_id, e);
+            return Ok(false);
+        }
+    };
+
+    store_features(state, track_id, &features).await?;
+    Ok(true)
+}
+
+/// Look up a track's file and verify it exists inside a library root
+async fn resolve_track_file(state: &AppState, track_id: Uuid) -> WorkerResult<PathBuf> {
     // Load track from database
     let track: TrackInfo = sqlx::query_as("SELECT id, file_path FROM tracks WHERE id = $1")
         .bind(track_id)
@@ -153,51 +368,38 @@ pub async fn execute(state: &AppState, job: &FeatureExtractionJob) -> WorkerResu
         .await?
         .ok_or_else(|| WorkerError::InvalidJobData(format!("Track not found: {}", track_id)))?;
 
-    // Get music library path for path traversal protection
-    let library_path = state.config.music_library_path();
-
-    // Security: Canonicalize paths and verify track is within library
-    let canonical_library = library_path.canonicalize().map_err(|e| {
-        WorkerError::Configuration(format!("Failed to canonicalize library path: {}", e))
-    })?;
+    // Security: Canonicalize paths and verify track is within a library root
+    let canonical_roots = canonical_music_roots(state.config.music_roots())?;
 
     let track_path = PathBuf::from(&track.file_path);
     let canonical_track = track_path.canonicalize().map_err(|_| {
         WorkerError::InvalidJobData(format!("Track file not found: {}", track.file_path))
     })?;
 
-    if !canonical_track.starts_with(&canonical_library) {
+    if !is_within_roots(&canonical_track, &canonical_roots) {
         return Err(WorkerError::InvalidJobData(format!(
             "Track path {:?} is outside the music library",
             track.file_path
         )));
     }
 
-    // Check file size before processing
-    let metadata = std::fs::metadata(&canonical_track)?;
-    if metadata.len() > MAX_FILE_SIZE_BYTES {
-        tracing::warn!(
-            "Track {} exceeds max file size ({} bytes > {} bytes), skipping",
-            track_id,
-            metadata.len(),
-            MAX_FILE_SIZE_BYTES
-        );
-        return Ok(()); // Skip without error - very large files are not processed
-    }
-
-    // Run CPU-intensive extraction in blocking thread pool
-    let path_for_extraction = canonical_track.clone();
-    let extraction_result =
-        tokio::task::spawn_blocking(move || extract_features(&path_for_extraction)).await;
+    Ok(canonical_track)
+}
 
-    // Only update database if extraction succeeded (don't overwrite existing data with defaults)
-    let features = match extraction_result {
-        Ok(Ok(f)) => Some(f),
-        Ok(Err(e)) => {
-            tracing::warn!("Failed to extract features for track {}: {}", track_id, e);
-            None
-        }
-        Err(e) => {
+/// Run CPU-intensive extraction in the blocking thread pool
+///
+/// The outer error is a panicked or cancelled task; the inner one is a file
+/// that could not be decoded.
+async fn extract_in_background(
+    state: &AppState,
+    track_id: Uuid,
+    path: PathBuf,
+    analyzers: FeatureExtractionConfig,
+) -> WorkerResult<WorkerResult<AudioFeatures>> {
+    let analysis_rate = state.config.analysis_sample_rate;
+    tokio::task::spawn_blocking(move || extract_features(&path, analysis_rate, &analyzers))
+        .await
+        .map_err(|e| {
             // Differentiate panics from cancellations for better diagnostics
             if e.is_panic() {
                 tracing::error!(
@@ -213,37 +415,149 @@ pub async fn execute(state: &AppState, job: &FeatureExtractionJob) -> WorkerResu
                 );
             }
             // Return error to enable job retries
-            return Err(WorkerError::AudioProcessing(format!(
+            WorkerError::AudioProcessing(format!(
                 "Feature extraction join error for {}: {}",
                 track_id, e
-            )));
-        }
-    };
-
-    if let Some(features) = features {
-        let features_json = serde_json::to_value(&features).map_err(|e| {
-            WorkerError::InvalidJobData(format!("Failed to serialize features: {}", e))
-        })?;
+            ))
+        })
+}
 
-        sqlx::query("UPDATE tracks SET audio_features = $1, updated_at = NOW() WHERE id = $2")
-            .bind(&features_json)
-            .bind(track_id)
-            .execute(&state.db)
-            .await?;
+/// Save extracted features on the track
+async fn store_features(
+    state: &AppState,
+    track_id: Uuid,
+    features: &AudioFeatures,
+) -> WorkerResult<()> {
+    let features_json = serde_json::to_value(features)
+        .map_err(|e| WorkerError::InvalidJobData(format!("Failed to serialize features: {}", e)))?;
+
+    // Keep the previous fingerprint when this run couldn't generate one
+    sqlx::query(
+        r#"
+        UPDATE tracks
+        SET audio_features = $1, fingerprint = COALESCE($2, fingerprint), updated_at = NOW()
+        WHERE id = $3
+        "#,
+    )
+    .bind(&features_json)
+    .bind(&features.fingerprint)
+    .bind(track_id)
+    .execute(&state.db)
+    .await?;
+
+    tracing::info!(
+        "Feature extraction completed for track {}: loudness={:?}dB, lufs={:?}, energy={:?}, fingerprinted={}",
+        track_id,
+        features.loudness,
+        features.lufs_integrated,
+        features.energy,
+        features.fingerprint.is_some()
+    );
 
-        tracing::info!(
-            "Feature extraction completed for track {}: loudness={:?}dB, energy={:?}",
-            track_id,
-            features.loudness,
-            features.energy
-        );
+    if let Some(spectral) = &features.spectral {
+        genre_suggestion::apply_to_untagged(&state.db, track_id, spectral).await?;
     }
 
     Ok(())
 }
 
+/// Recompute features for one track, failing if the file can't be analyzed
+///
+/// Unlike [`execute`], decode failures and oversized files are errors, so the
+/// admin who asked for the recompute sees why it didn't happen.
+async fn recompute(state: &AppState, track_id: Uuid) -> WorkerResult<()> {
+    let canonical_track = resolve_track_file(state, track_id).await?;
+
+    let size = std::fs::metadata(&canonical_track)?.len();
+    if size > MAX_FILE_SIZE_BYTES {
+        return Err(WorkerError::AudioProcessing(format!(
+            "Track file is too large to analyze ({} bytes > {} bytes)",
+            size, MAX_FILE_SIZE_BYTES
+        )));
+    }
+
+    let analyzers = state.config.analysis_features_for(&canonical_track);
+    let features = extract_in_background(state, track_id, canonical_track, analyzers).await??;
+    store_features(state, track_id, &features).await
+}
+
+/// Run the oldest pending admin-requested feature recompute, if there is one
+///
+/// Returns whether a request was processed. The request's outcome is recorded
+/// on its row; a failed extraction is not an error for the caller.
+pub async fn process_extraction_request(state: &AppState) -> WorkerResult<bool> {
+    let claimed: Option<(Uuid, Uuid)> = sqlx::query_as(
+        r#"
+        UPDATE feature_extraction_requests
+        SET status = 'running', started_at = NOW()
+        WHERE id = (
+            SELECT id FROM feature_extraction_requests
+            WHERE status = 'pending'
+            ORDER BY created_at
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, track_id
+        "#,
+    )
+    .fetch_optional(&state.db)
+    .await?;
+
+    let Some((request_id, track_id)) = claimed else {
+        return Ok(false);
+    };
+
+    tracing::info!(%request_id, %track_id, "Recomputing audio features");
+
+    let error = recompute(state, track_id).await.err().map(|e| {
+        e.log();
+        e.to_string()
+    });
+
+    sqlx::query(
+        r#"
+        UPDATE feature_extraction_requests
+        SET status = CASE WHEN $2::text IS NULL THEN 'completed' ELSE 'failed' END,
+            error = $2,
+            completed_at = NOW()
+        WHERE id = $1
+        "#,
+    )
+    .bind(request_id)
+    .bind(error)
+    .execute(&state.db)
+    .await?;
+
+    Ok(true)
+}
+
+/// Fail feature recompute requests left running by a previous worker process
+///
+/// Called once at startup, before any request is claimed.
+pub async fn fail_interrupted_extraction_requests(db: &sqlx::PgPool) -> WorkerResult<u64> {
+    let result = sqlx::query(
+        r#"
+        UPDATE feature_extraction_requests
+        SET status = 'failed', error = 'Interrupted by worker restart', completed_at = NOW()
+        WHERE status = 'running'
+        "#,
+    )
+    .execute(db)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
 /// Extract audio features from a file using Symphonia
-fn extract_features(path: &Path) -> WorkerResult<AudioFeatures> {
+///
+/// When `analysis_rate` is set, the analysis buffer is resampled to it before
+/// rhythm, key and spectral analysis. Only the analyzers enabled in
+/// `analyzers` run.
+fn extract_features(
+    path: &Path,
+    analysis_rate: Option<u32>,
+    analyzers: &FeatureExtractionConfig,
+) -> WorkerResult<AudioFeatures> {
     let path_str = path.display().to_string();
 
     // Open the audio file
@@ -303,10 +617,20 @@ fn extract_features(path: &Path) -> WorkerResult<AudioFeatures> {
     let max_frames = track.codec_params.max_frames_per_packet.unwrap_or(4096) as u64;
     let mut sample_buf = SampleBuffer::<f32>::new(max_frames, spec);
 
-    // Buffer for advanced analysis (mono samples from first 45 seconds)
+    // Buffer for fingerprinting and advanced analysis (mono samples from
+    // first 45 seconds)
     let analysis_buffer_size = ANALYSIS_DURATION_SECS * sample_rate as usize;
     let mut analysis_buffer: Vec<f32> = Vec::with_capacity(analysis_buffer_size);
 
+    // Whole-track RMS envelope for crossfade points
+    let mut envelope = EnvelopeBuilder::new(sample_rate);
+    let weights = match track.codec_params.channels {
+        Some(layout) if layout.count() == channels => loudness::channel_weights(layout),
+        _ => vec![1.0; channels],
+    };
+    let mut loudness_meter = LoudnessMeter::new(sample_rate, weights);
+    let mut truncated = false;
+
     // Decode packets and analyze samples
     loop {
         let packet = match format.next_packet() {
@@ -331,6 +655,7 @@ fn extract_features(path: &Path) -> WorkerResult<AudioFeatures> {
         // Check sample limit to prevent unbounded processing
         if stats.sample_count >= MAX_SAMPLES {
             tracing::debug!("Sample limit reached, stopping analysis");
+            truncated = true;
             break;
         }
 
@@ -365,19 +690,23 @@ fn extract_features(path: &Path) -> WorkerResult<AudioFeatures> {
                                 stats.peak = abs_sample;
                             }
 
+                            loudness_meter.push(sample);
                             mono_sum += sample;
                         }
                     }
 
                     // Add mono sample to analysis buffer (first N seconds only)
+                    let mono = mono_sum / channels as f32;
                     if analysis_buffer.len() < analysis_buffer_size {
-                        analysis_buffer.push(mono_sum / channels as f32);
+                        analysis_buffer.push(mono);
                     }
+                    envelope.push(mono);
 
                     i += channels;
 
                     // Check limit during sample processing
                     if stats.sample_count >= MAX_SAMPLES {
+                        truncated = true;
                         break;
                     }
                 }
@@ -402,65 +731,118 @@ fn extract_features(path: &Path) -> WorkerResult<AudioFeatures> {
         None
     };
 
-    // Run advanced audio analysis on the buffered samples
-    let (bpm, key, mode, danceability, valence, acousticness, instrumentalness, speechiness) =
-        if analysis_buffer.len() >= spectral::DEFAULT_FRAME_SIZE {
-            // Analyze rhythm for BPM and danceability
-            let rhythm_features = rhythm_analysis::analyze(&analysis_buffer, sample_rate);
-
-            // Analyze key and mode
-            let key_result = key_detection::analyze(&analysis_buffer, sample_rate);
-
-            // Analyze spectral features for valence, acousticness, instrumentalness, speechiness
-            let spectral_features =
-                spectral::analyze_spectral_features(&analysis_buffer, sample_rate);
-
-            // Compute derived features from spectral analysis
-            let valence = spectral::compute_valence(&spectral_features, sample_rate);
-            let acousticness = spectral::compute_acousticness(&spectral_features);
-            let instrumentalness = spectral::compute_instrumentalness(&spectral_features);
-            let speechiness = spectral::compute_speechiness(&spectral_features);
-
-            // Store key and mode separately (no redundancy)
-            // key = just the note (e.g., "C", "F#")
-            // mode = just the mode (e.g., "major", "minor")
-            (
-                Some(rhythm_features.bpm),
-                Some(key_result.key),
-                Some(key_result.mode),
-                Some(rhythm_features.danceability),
-                Some(valence),
-                Some(acousticness),
-                Some(instrumentalness),
-                Some(speechiness),
-            )
-        } else {
-            tracing::debug!(
-                "Not enough samples for advanced analysis: {} < {}",
-                analysis_buffer.len(),
-                spectral::DEFAULT_FRAME_SIZE
-            );
-            (None, None, None, None, None, None, None, None)
-        };
+    // Normalize the analysis buffer to the reference rate; fall back to the
+    // native rate rather than losing the features if resampling fails
+    let (analysis_buffer, sample_rate) = match analysis_rate {
+        Some(target) if target != sample_rate => {
+            match resample::resample_mono(&analysis_buffer, sample_rate, target) {
+                Ok(resampled) => (resampled, target),
+                Err(e) => {
+                    tracing::warn!("{}: analyzing at native {}Hz: {}", path_str, sample_rate, e);
+                    (analysis_buffer, sample_rate)
+                }
+            }
+        }
+        _ => (analysis_buffer, sample_rate),
+    };
+
+    let window = analyze_window(&analysis_buffer, sample_rate, analyzers);
+    let fingerprint =
+        fingerprint::compute(&analysis_buffer, sample_rate).map(|fp| fingerprint::encode(&fp));
+
+    // The end of a truncated decode isn't the end of the track
+    let fade_points = if truncated {
+        None
+    } else {
+        fade_points::compute_fade_points(&envelope.finish())
+    };
+
+    // Integrated loudness covers the whole decoded signal, like the
+    // normalization it feeds
+    let lufs = loudness_meter.finish();
 
     let features = AudioFeatures {
         loudness: Some(stats.approximate_lufs()),
+        lufs_integrated: lufs.integrated,
+        lufs_range: lufs.range,
         energy: Some(stats.energy()),
         peak: Some(stats.peak),
         dynamic_range,
-        bpm,
-        key,
-        mode,
-        danceability,
-        valence,
-        acousticness,
-        instrumentalness,
-        speechiness,
+        bpm: window.bpm,
+        key: window.key,
+        mode: window.mode,
+        danceability: window.danceability,
+        valence: window.valence,
+        acousticness: window.acousticness,
+        instrumentalness: window.instrumentalness,
+        speechiness: window.speechiness,
+        fade_in_ms: fade_points.map(|p| p.fade_in_ms),
+        fade_out_start_ms: fade_points.map(|p| p.fade_out_start_ms),
+        mfcc: window.mfcc,
+        crest_factor: window.crest_factor,
+        short_term_dynamic_range: window.short_term_dynamic_range,
+        fingerprint,
+        spectral: window.spectral,
     };
 
     Ok(features)
 }
 
+/// Run the enabled analyzers on the buffered analysis window
+fn analyze_window(
+    samples: &[f32],
+    sample_rate: u32,
+    analyzers: &FeatureExtractionConfig,
+) -> WindowFeatures {
+    let mut features = WindowFeatures::default();
+
+    if samples.len() < spectral::DEFAULT_FRAME_SIZE {
+        if analyzers.needs_analysis_buffer() {
+            tracing::debug!(
+                "Not enough samples for advanced analysis: {} < {}",
+                samples.len(),
+                spectral::DEFAULT_FRAME_SIZE
+            );
+        }
+        return features;
+    }
+
+    if analyzers.enable_bpm {
+        let rhythm = rhythm_analysis::analyze(samples, sample_rate);
+        features.bpm = Some(rhythm.bpm);
+        features.danceability = Some(rhythm.danceability);
+    }
+
+    // Store key and mode separately: key is just the note (e.g. "C", "F#"),
+    // mode is "major" or "minor"
+    if analyzers.enable_chroma {
+        let key = key_detection::analyze(samples, sample_rate);
+        features.key = Some(key.key);
+        features.mode = Some(key.mode);
+    }
+
+    if analyzers.enable_spectral {
+        let spectral_features = spectral::analyze_spectral_features(samples, sample_rate);
+        features.valence = Some(spectral::compute_valence(&spectral_features, sample_rate));
+        features.acousticness = Some(spectral::compute_acousticness(&spectral_features));
+        features.instrumentalness = Some(spectral::compute_instrumentalness(&spectral_features));
+        features.speechiness = Some(spectral::compute_speechiness(&spectral_features));
+        features.crest_factor = Some(spectral_features.crest_factor);
+        features.short_term_dynamic_range = Some(spectral_features.short_term_dynamic_range);
+        features.spectral = Some(spectral_features);
+    }
+
+    if analyzers.enable_mfcc {
+        features.mfcc = Some(spectral::compute_mfcc_means(
+            samples,
+            sample_rate,
+            spectral::DEFAULT_MFCC_COEFFICIENTS,
+        ));
+    }
+
+    features
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -496,12 +878,83 @@ mod tests {
     fn test_feature_extraction_job_parse() {
         let job = FeatureExtractionJob {
             track_id: "550e8400-e29b-41d4-a716-446655440000".to_string(),
+            features: None,
         };
         assert!(job.track_uuid().is_ok());
 
         let invalid_job = FeatureExtractionJob {
             track_id: "invalid".to_string(),
+            features: None,
         };
         assert!(invalid_job.track_uuid().is_err());
+
+        // Jobs queued before analyzer toggles existed still parse
+        let legacy: FeatureExtractionJob =
+            serde_json::from_str(r#"{"track_id":"550e8400-e29b-41d4-a716-446655440000"}"#).unwrap();
+        assert!(legacy.features.is_none());
+    }
+
+    #[test]
+    fn test_feature_extraction_config_parse() {
+        assert_eq!(
+            FeatureExtractionConfig::parse("spectral, bpm").unwrap(),
+            FeatureExtractionConfig::default()
+        );
+        assert_eq!(
+            FeatureExtractionConfig::parse("all").unwrap(),
+            FeatureExtractionConfig::all()
+        );
+        assert_eq!(
+            FeatureExtractionConfig::parse("none").unwrap(),
+            FeatureExtractionConfig::none()
+        );
+        assert_eq!(
+            FeatureExtractionConfig::parse("").unwrap(),
+            FeatureExtractionConfig::none()
+        );
+        assert!(FeatureExtractionConfig::parse("spectral,loudness").is_err());
+    }
+
+    #[test]
+    fn test_disabled_analyzers_are_skipped() {
+        let sample_rate = 44_100;
+        let samples: Vec<f32> = (0..sample_rate * 5)
+            .map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / sample_rate as f32).sin())
+            .collect();
+
+        let bpm_only = FeatureExtractionConfig {
+            enable_bpm: true,
+            ..FeatureExtractionConfig::none()
+        };
+        let window = analyze_window(&samples, sample_rate as u32, &bpm_only);
+        assert!(window.bpm.is_some());
+        assert!(window.danceability.is_some());
+        assert!(window.key.is_none() && window.mode.is_none());
+        assert!(window.valence.is_none() && window.acousticness.is_none());
+        assert!(window.instrumentalness.is_none() && window.speechiness.is_none());
+        assert!(window.mfcc.is_none());
+        assert!(window.crest_factor.is_none() && window.short_term_dynamic_range.is_none());
+
+        let window = analyze_window(
+            &samples,
+            sample_rate as u32,
+            &FeatureExtractionConfig::all(),
+        );
+        assert!(window.key.is_some() && window.valence.is_some());
+        assert!(window.crest_factor.is_some() && window.short_term_dynamic_range.is_some());
+        assert_eq!(
+            window.mfcc.map(|m| m.len()),
+            Some(spectral::DEFAULT_MFCC_COEFFICIENTS)
+        );
+
+        // Disabled fields are stored as null, not left out
+        let features = AudioFeatures {
+            bpm: Some(120.0),
+            ..AudioFeatures::default()
+        };
+        let json = serde_json::to_value(&features).unwrap();
+        assert!(json["valence"].is_null());
+        assert!(json["lufs_integrated"].is_null() && json["lufs_range"].is_null());
+        assert!(json.get("mfcc").is_none());
     }
 }
@@ -0,0 +1,234 @@
+//! Acoustic fingerprinting
+//!
+//! Produces a compact, Chromaprint-style fingerprint from the analysis window
+//! so files with identical audio can be matched regardless of tags, container
+//! or bitrate, and later looked up in MusicBrainz/AcoustID.
+//!
+//! The audio is resampled to [`FINGERPRINT_SAMPLE_RATE`] and split into
+//! overlapping frames. Each frame's spectrum between 300Hz and 2kHz is pooled
+//! into 33 log-spaced bands, and each pair of adjacent bands yields one bit:
+//! whether their energy difference grew since the previous frame. That gives
+//! one 32-bit sub-fingerprint per frame, which survives lossy encoding and
+//! gain changes. Two fingerprints match when the fraction of differing bits
+//! (the bit error rate) is below a tolerance.
+//!
+//! The stored form is the sub-fingerprints as 8 lowercase hex digits each,
+//! big-endian, concatenated.
+
+use super::resample;
+use super::spectral::SpectralAnalyzer;
+
+/// Sample rate audio is resampled to before fingerprinting (Hz)
+pub const FINGERPRINT_SAMPLE_RATE: u32 = 11_025;
+
+/// FFT frame length (~370ms)
+const FRAME_SIZE: usize = 4096;
+
+/// Hop between frames (~46ms)
+const HOP_SIZE: usize = 512;
+
+/// Lowest and highest analyzed frequencies (Hz)
+const MIN_FREQUENCY: f32 = 300.0;
+const MAX_FREQUENCY: f32 = 2000.0;
+
+/// Energy bands; adjacent pairs give the 32 bits of a sub-fingerprint
+const BANDS: usize = 33;
+
+/// Sub-fingerprints two fingerprints must share before they're compared
+pub const MIN_OVERLAP: usize = 16;
+
+/// Bit error rate below which two fingerprints are the same recording
+///
+/// Unrelated audio sits around 0.5.
+#[allow(dead_code)]
+pub const DEFAULT_MATCH_TOLERANCE: f32 = 0.35;
+
+/// RMS level below which the window is treated as silence
+const SILENCE_RMS: f32 = 1e-4;
+
+/// Compute a fingerprint from mono samples
+///
+/// Returns `None` when there's too little audio for [`MIN_OVERLAP`]
+/// sub-fingerprints, the window is silent, or resampling fails, so a
+/// fingerprint is only stored when it can be matched.
+pub fn compute(samples: &[f32], sample_rate: u32) -> Option<Vec<u32>> {
+    let samples = match resample::resample_mono(samples, sample_rate, FINGERPRINT_SAMPLE_RATE) {
+        Ok(resampled) => resampled,
+        Err(e) => {
+            tracing::debug!("Skipping fingerprint: {}", e);
+            return None;
+        }
+    };
+
+    let frames = (samples.len().saturating_sub(FRAME_SIZE) / HOP_SIZE) + 1;
+    if samples.len() < FRAME_SIZE || frames < MIN_OVERLAP + 1 {
+        return None;
+    }
+    let rms = (samples.iter().map(|&s| (s * s) as f64).sum::<f64>() / samples.len() as f64).sqrt();
+    if (rms as f32) < SILENCE_RMS {
+        return None;
+    }
+
+    // Band edges as spectrum bin positions, evenly spaced on a log scale
+    let bin_width = FINGERPRINT_SAMPLE_RATE as f32 / FRAME_SIZE as f32;
+    let ratio = MAX_FREQUENCY / MIN_FREQUENCY;
+    let edges: Vec<usize> = (0..=BANDS)
+        .map(|i| {
+            let hz = MIN_FREQUENCY * ratio.powf(i as f32 / BANDS as f32);
+            (hz / bin_width).round() as usize
+        })
+        .collect();
+
+    let mut analyzer = SpectralAnalyzer::with_params(FINGERPRINT_SAMPLE_RATE, FRAME_SIZE, HOP_SIZE);
+    let mut previous: Option<[f32; BANDS]> = None;
+    let mut fingerprint = Vec::with_capacity(frames - 1);
+
+    for frame_start in (0..frames).map(|i| i * HOP_SIZE) {
+        let spectrum = analyzer.compute_spectrum(&samples[frame_start..frame_start + FRAME_SIZE]);
+        let mut energies = [0.0f32; BANDS];
+        for (band, energy) in energies.iter_mut().enumerate() {
+            let bins = &spectrum[edges[band]..edges[band + 1].max(edges[band] + 1)];
+            *energy = bins.iter().map(|m| m * m).sum();
+        }
+
+        if let Some(previous) = previous {
+            let mut bits = 0u32;
+            for band in 0..BANDS - 1 {
+                let now = energies[band] - energies[band + 1];
+                let before = previous[band] - previous[band + 1];
+                if now - before > 0.0 {
+                    bits |= 1 << band;
+                }
+            }
+            fingerprint.push(bits);
+        }
+        previous = Some(energies);
+    }
+
+    Some(fingerprint)
+}
+
+/// Encode sub-fingerprints in the stored hex form
+pub fn encode(fingerprint: &[u32]) -> String {
+    fingerprint
+        .iter()
+        .map(|bits| format!("{:08x}", bits))
+        .collect()
+}
+
+/// Decode the stored hex form, or `None` if it is malformed
+#[allow(dead_code)]
+pub fn decode(encoded: &str) -> Option<Vec<u32>> {
+    if !encoded.len().is_multiple_of(8) || !encoded.is_ascii() {
+        return None;
+    }
+    (0..encoded.len())
+        .step_by(8)
+        .map(|i| u32::from_str_radix(&encoded[i..i + 8], 16).ok())
+        .collect()
+}
+
+/// Fraction of differing bits over the overlapping sub-fingerprints
+///
+/// Returns `None` when the fingerprints share fewer than [`MIN_OVERLAP`]
+/// sub-fingerprints.
+#[allow(dead_code)]
+pub fn bit_error_rate(a: &[u32], b: &[u32]) -> Option<f32> {
+    let overlap = a.len().min(b.len());
+    if overlap < MIN_OVERLAP {
+        return None;
+    }
+    let differing: u32 = a.iter().zip(b).map(|(x, y)| (x ^ y).count_ones()).sum();
+    Some(differing as f32 / (overlap * 32) as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A melody of decaying plucked notes over a bass line, with a little
+    /// deterministic noise
+    fn melody(notes: &[f32], notes_per_sec: usize, sample_rate: u32, seed: u32) -> Vec<f32> {
+        let note_len = sample_rate as usize / notes_per_sec;
+        let mut state = seed;
+        let mut samples = Vec::with_capacity(notes.len() * note_len);
+        for (n, &freq) in notes.iter().enumerate() {
+            let bass = notes[n / 4 * 4] / 4.0;
+            for i in 0..note_len {
+                let t = (n * note_len + i) as f32 / sample_rate as f32;
+                let decay = (-6.0 * i as f32 / note_len as f32).exp();
+                state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                let noise = (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5;
+                samples.push(
+                    0.4 * decay * (2.0 * std::f32::consts::PI * freq * t).sin()
+                        + 0.2 * (2.0 * std::f32::consts::PI * bass * t).sin()
+                        + 0.01 * noise,
+                );
+            }
+        }
+        samples
+    }
+
+    const TUNE: [f32; 8] = [440.0, 494.0, 523.0, 587.0, 659.0, 587.0, 523.0, 494.0];
+    const OTHER_TUNE: [f32; 8] = [392.0, 330.0, 784.0, 349.0, 698.0, 415.0, 880.0, 370.0];
+
+    fn repeat(tune: &[f32], times: usize) -> Vec<f32> {
+        tune.iter()
+            .copied()
+            .cycle()
+            .take(tune.len() * times)
+            .collect()
+    }
+
+    #[test]
+    fn test_identical_audio_matches() {
+        let samples = melody(&repeat(&TUNE, 4), 4, 44_100, 1);
+        let a = compute(&samples, 44_100).unwrap();
+        let b = compute(&samples, 44_100).unwrap();
+        assert_eq!(bit_error_rate(&a, &b), Some(0.0));
+
+        // Same recording, quieter, with different noise and a different
+        // sample rate still matches
+        let quieter: Vec<f32> = melody(&repeat(&TUNE, 4), 4, 48_000, 2)
+            .iter()
+            .map(|s| s * 0.5)
+            .collect();
+        let c = compute(&quieter, 48_000).unwrap();
+        let ber = bit_error_rate(&a, &c).unwrap();
+        assert!(ber < DEFAULT_MATCH_TOLERANCE, "bit error rate {}", ber);
+    }
+
+    #[test]
+    fn test_different_audio_does_not_match() {
+        let a = compute(&melody(&repeat(&TUNE, 4), 4, 44_100, 1), 44_100).unwrap();
+        let b = compute(&melody(&repeat(&OTHER_TUNE, 4), 3, 44_100, 1), 44_100).unwrap();
+        let ber = bit_error_rate(&a, &b).unwrap();
+        assert!(ber > DEFAULT_MATCH_TOLERANCE, "bit error rate {}", ber);
+    }
+
+    #[test]
+    fn test_no_fingerprint_for_silence_or_short_audio() {
+        assert!(compute(&vec![0.0; 44_100 * 5], 44_100).is_none());
+        assert!(compute(&melody(&TUNE[..2], 4, 44_100, 1), 44_100).is_none());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let fingerprint = vec![0, 1, 0xdead_beef, u32::MAX];
+        let encoded = encode(&fingerprint);
+        assert_eq!(encoded, "0000000000000001deadbeefffffffff");
+        assert_eq!(decode(&encoded), Some(fingerprint));
+
+        assert_eq!(decode("abc"), None);
+        assert_eq!(decode("0000000g"), None);
+        assert_eq!(decode(""), Some(Vec::new()));
+    }
+
+    #[test]
+    fn test_bit_error_rate_requires_overlap() {
+        let a = vec![0u32; MIN_OVERLAP];
+        let b = vec![u32::MAX; MIN_OVERLAP + 4];
+        assert_eq!(bit_error_rate(&a, &b), Some(1.0));
+        assert_eq!(bit_error_rate(&a[..MIN_OVERLAP - 1], &b), None);
+    }
+}
@@ -0,0 +1,356 @@
+//! Retry-aware Lidarr API client
+//!
+//! Wraps the worker's shared HTTP client with the Lidarr base URL, API key
+//! and retry policy from [`LidarrConfig`]. Idempotent requests (GET, HEAD)
+//! are retried with exponential backoff on 5xx responses and connection
+//! errors; mutating requests are sent exactly once, since a request that
+//! timed out may still have been applied by Lidarr.
+//!
+//! [`probe_all`] checks that instances are reachable before a sync, with a
+//! short per-instance timeout so a hung instance can't stall the job.
+
+use std::time::Duration;
+
+use reqwest::{Method, Response};
+use resonance_shared_config::LidarrConfig;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::error::{WorkerError, WorkerResult};
+
+/// Lidarr API client borrowing the worker's HTTP client
+pub struct LidarrClient<'a> {
+    http: &'a reqwest::Client,
+    config: &'a LidarrConfig,
+}
+
+impl<'a> LidarrClient<'a> {
+    /// Create a client for the given Lidarr instance
+    pub fn new(http: &'a reqwest::Client, config: &'a LidarrConfig) -> Self {
+        Self { http, config }
+    }
+
+    /// GET `path` (relative to `/api/v1/`) and decode the JSON response
+    pub async fn get_json<T: DeserializeOwned>(&self, path: &str) -> WorkerResult<T> {
+        let response = self.send(Method::GET, path, None).await?;
+        Ok(response.json().await?)
+    }
+
+    /// Send a request to `path` (relative to `/api/v1/`)
+    ///
+    /// Returns the response on a 2xx status and [`WorkerError::LidarrApi`]
+    /// otherwise. Only idempotent methods are retried.
+    pub async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+    ) -> WorkerResult<Response> {
+        let url = self.config.api_url(path);
+        let max_attempts = if is_idempotent(&method) {
+            self.config.retry_attempts.max(1)
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            let mut request = self
+                .http
+                .request(method.clone(), &url)
+                .header("X-Api-Key", &self.config.api_key);
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let error = match request.send().await {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let status = response.status();
+                    let text = response.text().await.unwrap_or_default();
+                    let error = WorkerError::lidarr_api(status.as_u16(), text);
+                    if !status.is_server_error() {
+                        return Err(error);
+                    }
+                    error
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => WorkerError::from(e),
+                Err(e) => return Err(e.into()),
+            };
+
+            if attempt >= max_attempts {
+                return Err(error);
+            }
+
+            let delay = self.config.retry_delay(attempt);
+            tracing::warn!(
+                method = %method,
+                url = %url,
+                attempt,
+                max_attempts,
+                delay_ms = delay.as_millis() as u64,
+                error = %error,
+                "Lidarr request failed, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Whether a request can be repeated without side effects
+fn is_idempotent(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD)
+}
+
+/// Errors from probing a Lidarr instance
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ProbeError {
+    /// No response within the probe timeout
+    #[error("Lidarr did not respond within {0:?}")]
+    Timeout(Duration),
+
+    /// Connection failed (refused, DNS, TLS, ...)
+    #[error("failed to connect to Lidarr: {0}")]
+    Unreachable(String),
+
+    /// Lidarr answered with a non-success status
+    #[error("Lidarr returned HTTP {0}")]
+    Status(u16),
+
+    /// The status response could not be parsed
+    #[error("invalid Lidarr status response: {0}")]
+    InvalidResponse(String),
+}
+
+/// `/api/v1/system/status` response, reduced to the fields we use
+#[derive(Debug, Deserialize)]
+struct SystemStatus {
+    version: String,
+}
+
+/// A reachable Lidarr instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LidarrHealth {
+    /// Lidarr version, e.g. `2.4.3.4248`
+    pub version: String,
+}
+
+/// Probe result for one Lidarr instance
+#[derive(Debug, Clone)]
+pub struct LidarrProbe {
+    /// Instance URL
+    pub url: String,
+    /// Health if reachable, otherwise why not
+    pub health: Result<LidarrHealth, ProbeError>,
+}
+
+impl LidarrProbe {
+    /// Whether the instance answered the probe
+    pub fn is_reachable(&self) -> bool {
+        self.health.is_ok()
+    }
+
+    /// Version of a reachable instance
+    #[allow(dead_code)] // Used by tests
+    pub fn version(&self) -> Option<&str> {
+        self.health.as_ref().ok().map(|h| h.version.as_str())
+    }
+}
+
+/// Check that an instance is reachable and report its version
+///
+/// Requests `/api/v1/system/status`, giving up after `timeout`. Uses its own
+/// HTTP client, since the worker's shared client has a longer timeout.
+pub async fn ping(config: &LidarrConfig, timeout: Duration) -> Result<LidarrHealth, ProbeError> {
+    let client = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| ProbeError::Unreachable(e.to_string()))?;
+
+    let response = client
+        .get(config.api_url("system/status"))
+        .header("X-Api-Key", &config.api_key)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_timeout() {
+                ProbeError::Timeout(timeout)
+            } else {
+                ProbeError::Unreachable(e.to_string())
+            }
+        })?;
+
+    if !response.status().is_success() {
+        return Err(ProbeError::Status(response.status().as_u16()));
+    }
+
+    let status: SystemStatus = response.json().await.map_err(|e| {
+        if e.is_timeout() {
+            ProbeError::Timeout(timeout)
+        } else {
+            ProbeError::InvalidResponse(e.to_string())
+        }
+    })?;
+
+    Ok(LidarrHealth {
+        version: status.version,
+    })
+}
+
+/// Ping every instance concurrently, each with its own `timeout`
+///
+/// A slow or dead instance only delays the report by `timeout`. Results are
+/// returned in the order of `instances`.
+pub async fn probe_all(instances: &[LidarrConfig], timeout: Duration) -> Vec<LidarrProbe> {
+    futures_util::future::join_all(instances.iter().map(|config| async move {
+        LidarrProbe {
+            url: config.url.clone(),
+            health: ping(config, timeout).await,
+        }
+    }))
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resonance_test_utils::{LidarrArtistFixture, MockLidarrServer};
+    use serde_json::json;
+
+    fn config_for(server: &MockLidarrServer) -> LidarrConfig {
+        LidarrConfig::new(server.url(), server.api_key()).with_retry_config(3, 1)
+    }
+
+    #[tokio::test]
+    async fn test_get_retries_transient_errors_until_success() {
+        let server = MockLidarrServer::start().await;
+        server
+            .mock_artists_success(vec![LidarrArtistFixture::monitored(1, "Queen")])
+            .await;
+        server.mock_transient_errors(2).await;
+
+        let http = reqwest::Client::new();
+        let config = config_for(&server);
+        let artists: Vec<serde_json::Value> = LidarrClient::new(&http, &config)
+            .get_json("artist")
+            .await
+            .unwrap();
+
+        assert_eq!(artists.len(), 1);
+        assert_eq!(server.request_count("GET", "/api/v1/artist").await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_gives_up_after_retry_attempts() {
+        let server = MockLidarrServer::start().await;
+        server.mock_transient_errors(10).await;
+
+        let http = reqwest::Client::new();
+        let config = config_for(&server);
+        let result: WorkerResult<Vec<serde_json::Value>> =
+            LidarrClient::new(&http, &config).get_json("artist").await;
+
+        assert!(matches!(
+            result,
+            Err(WorkerError::LidarrApi {
+                status_code: 503,
+                ..
+            })
+        ));
+        assert_eq!(server.request_count("GET", "/api/v1/artist").await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_get_does_not_retry_client_errors() {
+        let server = MockLidarrServer::start().await;
+        server.mock_rate_limit().await;
+
+        let http = reqwest::Client::new();
+        let config = config_for(&server);
+        let result: WorkerResult<Vec<serde_json::Value>> =
+            LidarrClient::new(&http, &config).get_json("artist").await;
+
+        assert!(result.is_err());
+        assert_eq!(server.request_count("GET", "/api/v1/artist").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_mutation_is_not_retried() {
+        let server = MockLidarrServer::start().await;
+        server.mock_command_error().await;
+
+        let http = reqwest::Client::new();
+        let config = config_for(&server);
+        let body = json!({ "name": "RefreshArtist" });
+        let result = LidarrClient::new(&http, &config)
+            .send(Method::POST, "command", Some(&body))
+            .await;
+
+        assert!(matches!(
+            result,
+            Err(WorkerError::LidarrApi {
+                status_code: 502,
+                ..
+            })
+        ));
+        assert_eq!(server.request_count("POST", "/api/v1/command").await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_ping_healthy_instance() {
+        let server = MockLidarrServer::start().await;
+        server.mock_system_status("2.4.3.4248").await;
+
+        let health = ping(&config_for(&server), Duration::from_secs(2))
+            .await
+            .unwrap();
+        assert_eq!(health.version, "2.4.3.4248");
+    }
+
+    #[tokio::test]
+    async fn test_ping_times_out() {
+        let server = MockLidarrServer::start().await;
+        server
+            .mock_system_status_delayed("2.4.3.4248", Duration::from_secs(5))
+            .await;
+
+        let timeout = Duration::from_millis(200);
+        let result = ping(&config_for(&server), timeout).await;
+        assert_eq!(result, Err(ProbeError::Timeout(timeout)));
+    }
+
+    #[tokio::test]
+    async fn test_ping_wrong_api_key() {
+        let server = MockLidarrServer::start().await;
+        server.mock_system_status("2.4.3.4248").await;
+
+        // Unmatched requests get wiremock's 404
+        let config = LidarrConfig::new(server.url(), "wrong-key");
+        let result = ping(&config, Duration::from_secs(2)).await;
+        assert_eq!(result, Err(ProbeError::Status(404)));
+    }
+
+    #[tokio::test]
+    async fn test_probe_all_mixed_instances() {
+        let healthy = MockLidarrServer::start().await;
+        healthy.mock_system_status("2.4.3.4248").await;
+        let slow = MockLidarrServer::start().await;
+        slow.mock_system_status_delayed("2.0.0.0", Duration::from_secs(5))
+            .await;
+        let dead = LidarrConfig::new("http://127.0.0.1:1", "key");
+
+        let instances = vec![config_for(&healthy), config_for(&slow), dead];
+        let started = std::time::Instant::now();
+        let report = probe_all(&instances, Duration::from_millis(300)).await;
+
+        // Probed concurrently: the slow instance costs one timeout, not more
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(report.len(), 3);
+        assert_eq!(report[0].url, healthy.url());
+        assert_eq!(report[0].version(), Some("2.4.3.4248"));
+        assert!(matches!(report[1].health, Err(ProbeError::Timeout(_))));
+        assert!(!report[2].is_reachable());
+    }
+}
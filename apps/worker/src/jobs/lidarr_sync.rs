@@ -2,18 +2,33 @@
 //!
 //! Syncs with Lidarr to monitor for new releases from followed artists
 //! and automatically add them to the library.
+//!
+//! Each run first builds a [`SyncPlan`] by diffing Lidarr's artists and albums
+//! against the local database, then applies it. With `dry_run` set the plan is
+//! only logged and returned, so changes can be previewed before they are made.
 
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Duration;
 
+use chrono::NaiveDate;
+use resonance_media::normalize_name;
 use serde::{Deserialize, Serialize};
 use sqlx::Row;
 use uuid::Uuid;
 
 use crate::error::{WorkerError, WorkerResult};
-use crate::jobs::{enqueue_job, library_scan::LibraryScanJob, Job};
+use crate::jobs::library_scan::{canonical_music_roots, is_within_roots, LibraryScanJob};
+use crate::jobs::lidarr_api::{probe_all, LidarrClient};
+use crate::jobs::{enqueue_job, Job};
 use crate::AppState;
 
+/// Metadata key set on artists and albums that Lidarr no longer knows about
+const MISSING_METADATA_KEY: &str = "lidarr_missing";
+
+/// How long each Lidarr instance gets to answer the pre-sync health probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 /// Lidarr sync job payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LidarrSyncJob {
@@ -22,6 +37,10 @@ pub struct LidarrSyncJob {
 
     /// Whether to sync artist metadata
     pub sync_metadata: bool,
+
+    /// Compute and log the sync plan without applying it
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 impl Default for LidarrSyncJob {
@@ -29,6 +48,7 @@ impl Default for LidarrSyncJob {
         Self {
             check_new_releases: true,
             sync_metadata: true,
+            dry_run: false,
         }
     }
 }
@@ -92,165 +112,489 @@ struct LidarrImage {
     url: String,
 }
 
+/// Changes a Lidarr sync would make to the local library
+#[derive(Debug, Default, Serialize)]
+pub struct SyncPlan {
+    /// Monitored Lidarr artists not yet in the library
+    pub artists_to_add: Vec<PlannedArtist>,
+    /// Library artists whose metadata differs from Lidarr
+    pub artists_to_update: Vec<PlannedArtist>,
+    /// Library artists linked to Lidarr that Lidarr no longer has
+    pub artists_missing: Vec<MissingEntry>,
+    /// Lidarr albums with files on disk that are not in the library yet
+    pub albums_to_add: Vec<PlannedAlbum>,
+    /// Library albums to update from Lidarr, including ones to link by title
+    pub albums_to_update: Vec<PlannedAlbum>,
+    /// Library albums linked to Lidarr that Lidarr no longer has
+    pub albums_missing: Vec<MissingEntry>,
+    /// Artist directories to scan for the new albums
+    pub scan_paths: Vec<String>,
+}
+
+impl SyncPlan {
+    /// Whether applying the plan would change nothing
+    pub fn is_empty(&self) -> bool {
+        self.artists_to_add.is_empty()
+            && self.artists_to_update.is_empty()
+            && self.artists_missing.is_empty()
+            && self.albums_to_add.is_empty()
+            && self.albums_to_update.is_empty()
+            && self.albums_missing.is_empty()
+    }
+}
+
+/// Artist as it would be written from Lidarr
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedArtist {
+    pub lidarr_id: i64,
+    pub name: String,
+    pub sort_name: Option<String>,
+    pub biography: Option<String>,
+    pub image_url: Option<String>,
+    pub genres: Vec<String>,
+    pub mbid: Option<Uuid>,
+}
+
+/// Album as it would be written from Lidarr
+#[derive(Debug, Clone, Serialize)]
+pub struct PlannedAlbum {
+    pub lidarr_id: i64,
+    pub lidarr_artist_id: i64,
+    pub title: String,
+    /// Existing library album, if any
+    pub local_id: Option<Uuid>,
+    pub release_date: Option<NaiveDate>,
+    pub album_type: &'static str,
+    pub genres: Vec<String>,
+    pub total_tracks: i32,
+    pub mbid: Option<Uuid>,
+}
+
+/// Library row that Lidarr no longer reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MissingEntry {
+    pub id: Uuid,
+    pub lidarr_id: i64,
+    pub name: String,
+}
+
+/// Library artist linked to Lidarr
+#[derive(Debug, Clone)]
+struct LocalArtist {
+    id: Uuid,
+    name: String,
+    sort_name: Option<String>,
+    biography: Option<String>,
+    image_url: Option<String>,
+    genres: Vec<String>,
+    mbid: Option<Uuid>,
+    missing: bool,
+}
+
+/// Library album, linked to Lidarr or belonging to a linked artist
+#[derive(Debug, Clone)]
+struct LocalAlbum {
+    id: Uuid,
+    title: String,
+    release_date: Option<NaiveDate>,
+    album_type: String,
+    genres: Vec<String>,
+    total_tracks: Option<i32>,
+    mbid: Option<Uuid>,
+    missing: bool,
+}
+
+/// Snapshot of the library state the plan is diffed against
+#[derive(Debug, Default)]
+struct LocalLibrary {
+    /// Artists by Lidarr ID
+    artists: HashMap<i64, LocalArtist>,
+    /// Albums by Lidarr ID
+    albums: HashMap<i64, LocalAlbum>,
+    /// Albums not linked to Lidarr yet, by artist Lidarr ID and normalized title
+    unlinked_albums: HashMap<(i64, String), LocalAlbum>,
+}
+
 /// Execute the Lidarr sync job
-pub async fn execute(state: &AppState, job: &LidarrSyncJob) -> WorkerResult<()> {
+///
+/// Returns the computed plan; it is only applied when `dry_run` is unset.
+pub async fn execute(state: &AppState, job: &LidarrSyncJob) -> WorkerResult<SyncPlan> {
     // Check if Lidarr is configured
     let lidarr_config = match state.config.lidarr() {
         Some(config) => config,
         None => {
             tracing::debug!("Lidarr not configured, skipping sync");
-            return Ok(());
+            return Ok(SyncPlan::default());
         }
     };
 
-    let lidarr_url = &lidarr_config.url;
-    let api_key = &lidarr_config.api_key;
+    // Skip unreachable instances instead of failing the job on a hung request
+    let report = probe_all(std::slice::from_ref(lidarr_config), PROBE_TIMEOUT).await;
+    for probe in &report {
+        match &probe.health {
+            Ok(health) => {
+                tracing::debug!(url = %probe.url, version = %health.version, "Lidarr reachable")
+            }
+            Err(e) => {
+                tracing::warn!(url = %probe.url, error = %e, "Skipping unreachable Lidarr instance")
+            }
+        }
+    }
+    if !report.iter().any(|probe| probe.is_reachable()) {
+        return Ok(SyncPlan::default());
+    }
 
-    tracing::info!("Starting Lidarr sync");
+    let client = LidarrClient::new(&state.http_client, lidarr_config);
 
-    // Fetch all artists once (used by both sync_artists and check_new_releases)
-    let artists = fetch_all_artists(state, lidarr_url, api_key).await?;
+    tracing::info!(dry_run = job.dry_run, "Starting Lidarr sync");
 
-    // Build artist path cache for efficient lookup
-    let artist_paths: HashMap<i64, String> = artists
-        .iter()
-        .filter_map(|a| a.path.as_ref().map(|p| (a.id, p.clone())))
-        .collect();
+    // Fetch all artists once (used for both artist and album planning)
+    let artists = fetch_all_artists(&client).await?;
+    let albums = if job.check_new_releases {
+        fetch_all_albums(&client).await?
+    } else {
+        Vec::new()
+    };
 
-    if job.sync_metadata {
-        sync_artists_from_data(state, &artists).await?;
+    let local = load_local_library(&state.db).await?;
+    let plan = build_plan(job, &artists, &albums, &local);
+
+    tracing::info!(
+        artists_to_add = plan.artists_to_add.len(),
+        artists_to_update = plan.artists_to_update.len(),
+        artists_missing = plan.artists_missing.len(),
+        albums_to_add = plan.albums_to_add.len(),
+        albums_to_update = plan.albums_to_update.len(),
+        albums_missing = plan.albums_missing.len(),
+        "Lidarr sync plan computed"
+    );
+
+    if job.dry_run {
+        match serde_json::to_string(&plan) {
+            Ok(json) => tracing::info!(plan = %json, "Lidarr sync dry run, no changes applied"),
+            Err(e) => tracing::warn!("Failed to serialize Lidarr sync plan: {}", e),
+        }
+        return Ok(plan);
     }
 
-    if job.check_new_releases {
-        check_new_releases(state, lidarr_url, api_key, &artist_paths).await?;
+    if plan.is_empty() {
+        tracing::info!("Library already matches Lidarr, nothing to sync");
+        return Ok(plan);
     }
 
+    apply_plan(state, &plan).await?;
+
     tracing::info!("Lidarr sync completed");
 
-    Ok(())
+    Ok(plan)
 }
 
-/// Fetch all artists from Lidarr API with proper error handling
-async fn fetch_all_artists(
-    state: &AppState,
-    lidarr_url: &str,
-    api_key: &str,
-) -> WorkerResult<Vec<LidarrArtist>> {
-    let response = state
-        .http_client
-        .get(format!("{}/api/v1/artist", lidarr_url))
-        .header("X-Api-Key", api_key)
-        .send()
-        .await?;
+/// Fetch all artists from Lidarr API
+async fn fetch_all_artists(client: &LidarrClient<'_>) -> WorkerResult<Vec<LidarrArtist>> {
+    client.get_json("artist").await
+}
+
+/// Fetch all albums from Lidarr API
+async fn fetch_all_albums(client: &LidarrClient<'_>) -> WorkerResult<Vec<LidarrAlbum>> {
+    client.get_json("album").await
+}
+
+/// Load the Lidarr-linked artists and albums from the database
+async fn load_local_library(db: &sqlx::PgPool) -> WorkerResult<LocalLibrary> {
+    let mut local = LocalLibrary::default();
+
+    let artist_rows = sqlx::query(
+        r#"
+        SELECT id, lidarr_id, name, sort_name, biography, image_url, genres, mbid,
+               metadata ? $1 AS missing
+        FROM artists
+        WHERE lidarr_id IS NOT NULL
+        "#,
+    )
+    .bind(MISSING_METADATA_KEY)
+    .fetch_all(db)
+    .await?;
 
-    // Check HTTP status before parsing JSON
-    if !response.status().is_success() {
-        let status = response.status().as_u16();
-        let body = response.text().await.unwrap_or_default();
-        return Err(WorkerError::lidarr_api(status, body));
+    for row in artist_rows {
+        local.artists.insert(
+            row.get::<i32, _>("lidarr_id") as i64,
+            LocalArtist {
+                id: row.get("id"),
+                name: row.get("name"),
+                sort_name: row.get("sort_name"),
+                biography: row.get("biography"),
+                image_url: row.get("image_url"),
+                genres: row.get("genres"),
+                mbid: row.get("mbid"),
+                missing: row.get("missing"),
+            },
+        );
     }
 
-    let artists: Vec<LidarrArtist> = response.json().await?;
-    Ok(artists)
-}
-
-/// Fetch all albums from Lidarr API with proper error handling
-async fn fetch_all_albums(
-    state: &AppState,
-    lidarr_url: &str,
-    api_key: &str,
-) -> WorkerResult<Vec<LidarrAlbum>> {
-    let response = state
-        .http_client
-        .get(format!("{}/api/v1/album", lidarr_url))
-        .header("X-Api-Key", api_key)
-        .send()
-        .await?;
+    let album_rows = sqlx::query(
+        r#"
+        SELECT al.id, al.lidarr_id, ar.lidarr_id AS artist_lidarr_id, al.title,
+               al.release_date, al.album_type::text AS album_type, al.genres,
+               al.total_tracks, al.mbid, al.metadata ? $1 AS missing
+        FROM albums al
+        JOIN artists ar ON ar.id = al.artist_id
+        WHERE al.lidarr_id IS NOT NULL OR ar.lidarr_id IS NOT NULL
+        "#,
+    )
+    .bind(MISSING_METADATA_KEY)
+    .fetch_all(db)
+    .await?;
+
+    for row in album_rows {
+        let album = LocalAlbum {
+            id: row.get("id"),
+            title: row.get("title"),
+            release_date: row.get("release_date"),
+            album_type: row.get("album_type"),
+            genres: row.get("genres"),
+            total_tracks: row.get("total_tracks"),
+            mbid: row.get("mbid"),
+            missing: row.get("missing"),
+        };
 
-    // Check HTTP status before parsing JSON
-    if !response.status().is_success() {
-        let status = response.status().as_u16();
-        let body = response.text().await.unwrap_or_default();
-        return Err(WorkerError::lidarr_api(status, body));
+        match (
+            row.get::<Option<i32>, _>("lidarr_id"),
+            row.get::<Option<i32>, _>("artist_lidarr_id"),
+        ) {
+            (Some(lidarr_id), _) => {
+                local.albums.insert(lidarr_id as i64, album);
+            }
+            (None, Some(artist_lidarr_id)) => {
+                local.unlinked_albums.insert(
+                    (artist_lidarr_id as i64, normalize_name(&album.title)),
+                    album,
+                );
+            }
+            (None, None) => {}
+        }
     }
 
-    let albums: Vec<LidarrAlbum> = response.json().await?;
-    Ok(albums)
+    Ok(local)
 }
 
-/// Sync artist metadata from pre-fetched Lidarr data
-async fn sync_artists_from_data(state: &AppState, artists: &[LidarrArtist]) -> WorkerResult<()> {
-    tracing::debug!("Syncing artists from Lidarr");
-
-    let monitored_artists: Vec<&LidarrArtist> = artists.iter().filter(|a| a.monitored).collect();
+/// Diff Lidarr's view of the library against the local one
+fn build_plan(
+    job: &LidarrSyncJob,
+    artists: &[LidarrArtist],
+    albums: &[LidarrAlbum],
+    local: &LocalLibrary,
+) -> SyncPlan {
+    let mut plan = SyncPlan::default();
 
-    tracing::info!(
-        "Found {} monitored artists in Lidarr",
-        monitored_artists.len()
-    );
+    if job.sync_metadata {
+        for artist in artists.iter().filter(|a| a.monitored) {
+            let planned = planned_artist(artist);
+            match local.artists.get(&artist.id) {
+                None => plan.artists_to_add.push(planned),
+                Some(existing) if artist_changed(existing, &planned) => {
+                    plan.artists_to_update.push(planned)
+                }
+                Some(_) => {}
+            }
+        }
 
-    let mut created = 0;
-    let mut updated = 0;
+        let lidarr_artist_ids: HashSet<i64> = artists.iter().map(|a| a.id).collect();
+        plan.artists_missing = missing_entries(
+            local
+                .artists
+                .iter()
+                .filter(|(lidarr_id, artist)| {
+                    !artist.missing && !lidarr_artist_ids.contains(lidarr_id)
+                })
+                .map(|(lidarr_id, artist)| (*lidarr_id, artist.id, artist.name.as_str())),
+        );
+    }
 
-    for artist in monitored_artists {
-        // Find poster image URL
-        let image_url = artist
-            .images
+    if job.check_new_releases {
+        // Artist paths for queueing scans of new albums
+        let artist_paths: HashMap<i64, &String> = artists
             .iter()
-            .find(|img| img.cover_type == "poster" || img.cover_type == "fanart")
-            .map(|img| img.url.clone());
+            .filter_map(|a| a.path.as_ref().map(|p| (a.id, p)))
+            .collect();
+        let mut scan_paths: HashSet<String> = HashSet::new();
+
+        for album in albums {
+            let mut planned = planned_album(album);
+
+            if let Some(existing) = local.albums.get(&album.id) {
+                if album_changed(existing, &planned) {
+                    planned.local_id = Some(existing.id);
+                    plan.albums_to_update.push(planned);
+                }
+            } else if let Some(existing) = local
+                .unlinked_albums
+                .get(&(album.artist_id, normalize_name(&album.title)))
+            {
+                // Same title by the same artist, ignoring case, accents and
+                // edition suffixes: link it rather than import a copy
+                planned.local_id = Some(existing.id);
+                plan.albums_to_update.push(planned);
+            } else if album.statistics.track_file_count > 0 {
+                if let Some(path) = artist_paths.get(&album.artist_id) {
+                    scan_paths.insert((*path).clone());
+                }
+                plan.albums_to_add.push(planned);
+            }
+        }
 
+        let lidarr_album_ids: HashSet<i64> = albums.iter().map(|a| a.id).collect();
+        plan.albums_missing = missing_entries(
+            local
+                .albums
+                .iter()
+                .filter(|(lidarr_id, album)| {
+                    !album.missing && !lidarr_album_ids.contains(lidarr_id)
+                })
+                .map(|(lidarr_id, album)| (*lidarr_id, album.id, album.title.as_str())),
+        );
+
+        plan.scan_paths = scan_paths.into_iter().collect();
+        plan.scan_paths.sort();
+    }
+
+    plan
+}
+
+/// Collect missing entries in a stable order
+fn missing_entries<'a>(entries: impl Iterator<Item = (i64, Uuid, &'a str)>) -> Vec<MissingEntry> {
+    let mut missing: Vec<MissingEntry> = entries
+        .map(|(lidarr_id, id, name)| MissingEntry {
+            id,
+            lidarr_id,
+            name: name.to_string(),
+        })
+        .collect();
+    missing.sort_by_key(|entry| entry.lidarr_id);
+    missing
+}
+
+fn planned_artist(artist: &LidarrArtist) -> PlannedArtist {
+    // Find poster image URL
+    let image_url = artist
+        .images
+        .iter()
+        .find(|img| img.cover_type == "poster" || img.cover_type == "fanart")
+        .map(|img| img.url.clone());
+
+    PlannedArtist {
+        lidarr_id: artist.id,
+        name: artist.artist_name.clone(),
+        sort_name: artist.sort_name.clone(),
+        biography: artist.overview.clone(),
+        image_url,
+        genres: artist.genres.clone(),
         // Parse MusicBrainz ID if available
-        let mbid = artist
+        mbid: artist
             .foreign_artist_id
             .as_ref()
-            .and_then(|id| Uuid::parse_str(id).ok());
-
-        // Upsert artist into database
-        let result = upsert_artist(
-            &state.db,
-            artist.id,
-            &artist.artist_name,
-            artist.sort_name.as_deref(),
-            artist.overview.as_deref(),
-            image_url.as_deref(),
-            &artist.genres,
-            mbid,
-        )
-        .await?;
+            .and_then(|id| Uuid::parse_str(id).ok()),
+    }
+}
 
-        if result {
-            created += 1;
-        } else {
-            updated += 1;
+fn planned_album(album: &LidarrAlbum) -> PlannedAlbum {
+    PlannedAlbum {
+        lidarr_id: album.id,
+        lidarr_artist_id: album.artist_id,
+        title: album.title.clone(),
+        local_id: None,
+        // Parse release date if available using safe string slicing
+        release_date: album.release_date.as_ref().and_then(|d| {
+            d.get(..10)
+                .and_then(|date_str| NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok())
+        }),
+        album_type: map_album_type(album.album_type.as_deref()),
+        genres: album.genres.clone(),
+        total_tracks: album.statistics.total_track_count,
+        // Parse MusicBrainz ID if available
+        mbid: album
+            .foreign_album_id
+            .as_ref()
+            .and_then(|id| Uuid::parse_str(id).ok()),
+    }
+}
+
+/// Map a Lidarr album type onto the `album_type` enum
+fn map_album_type(album_type: Option<&str>) -> &'static str {
+    match album_type {
+        Some("single") => "single",
+        Some("ep") => "ep",
+        Some("compilation") => "compilation",
+        Some("live") => "live",
+        _ => "album",
+    }
+}
+
+/// Whether a value from Lidarr would overwrite the stored one
+///
+/// Mirrors the `COALESCE` updates: absent Lidarr values keep the stored value.
+fn overwrites<T: PartialEq>(incoming: &Option<T>, stored: &Option<T>) -> bool {
+    incoming.is_some() && incoming != stored
+}
+
+/// Whether upserting the artist would change the stored row
+fn artist_changed(existing: &LocalArtist, planned: &PlannedArtist) -> bool {
+    existing.missing
+        || existing.name != planned.name
+        || existing.genres != planned.genres
+        || overwrites(&planned.sort_name, &existing.sort_name)
+        || overwrites(&planned.biography, &existing.biography)
+        || overwrites(&planned.image_url, &existing.image_url)
+        || overwrites(&planned.mbid, &existing.mbid)
+}
+
+/// Whether updating the album from Lidarr would change the stored row
+fn album_changed(existing: &LocalAlbum, planned: &PlannedAlbum) -> bool {
+    existing.missing
+        || existing.album_type != planned.album_type
+        || existing.total_tracks != Some(planned.total_tracks)
+        || (!planned.genres.is_empty() && existing.genres != planned.genres)
+        || overwrites(&planned.release_date, &existing.release_date)
+        || overwrites(&planned.mbid, &existing.mbid)
+}
+
+/// Apply a sync plan to the library
+async fn apply_plan(state: &AppState, plan: &SyncPlan) -> WorkerResult<()> {
+    for artist in plan.artists_to_add.iter().chain(&plan.artists_to_update) {
+        upsert_artist(&state.db, artist).await?;
+    }
+
+    for album in &plan.albums_to_update {
+        if let Some(album_id) = album.local_id {
+            update_album(&state.db, album_id, album).await?;
         }
     }
 
+    mark_missing(&state.db, "artists", &plan.artists_missing).await?;
+    mark_missing(&state.db, "albums", &plan.albums_missing).await?;
+
     tracing::info!(
-        "Artist sync complete: {} created, {} updated",
-        created,
-        updated
+        "Applied Lidarr sync: {} artists created, {} artists updated, {} albums updated",
+        plan.artists_to_add.len(),
+        plan.artists_to_update.len(),
+        plan.albums_to_update.len()
     );
 
+    if !plan.scan_paths.is_empty() {
+        queue_scans(state, &plan.scan_paths).await?;
+    }
+
     Ok(())
 }
 
 /// Upsert an artist into the database using ON CONFLICT for race-condition safety
 /// Returns true if created, false if updated
-#[allow(clippy::too_many_arguments)]
-async fn upsert_artist(
-    db: &sqlx::PgPool,
-    lidarr_id: i64,
-    name: &str,
-    sort_name: Option<&str>,
-    biography: Option<&str>,
-    image_url: Option<&str>,
-    genres: &[String],
-    mbid: Option<Uuid>,
-) -> WorkerResult<bool> {
+async fn upsert_artist(db: &sqlx::PgPool, artist: &PlannedArtist) -> WorkerResult<bool> {
     // Safely convert i64 to i32 to prevent silent truncation
-    let lidarr_id_i32 = i32::try_from(lidarr_id).map_err(|_| {
-        WorkerError::InvalidJobData(format!("Lidarr artist ID out of i32 range: {}", lidarr_id))
+    let lidarr_id_i32 = i32::try_from(artist.lidarr_id).map_err(|_| {
+        WorkerError::InvalidJobData(format!(
+            "Lidarr artist ID out of i32 range: {}",
+            artist.lidarr_id
+        ))
     })?;
 
     // Use ON CONFLICT to handle race conditions atomically
@@ -266,90 +610,120 @@ async fn upsert_artist(
             image_url = COALESCE(EXCLUDED.image_url, artists.image_url),
             genres = EXCLUDED.genres,
             mbid = COALESCE(EXCLUDED.mbid, artists.mbid),
+            metadata = artists.metadata - $8,
             updated_at = NOW()
         RETURNING (xmax = 0) AS inserted
         "#,
     )
-    .bind(name)
-    .bind(sort_name)
-    .bind(biography)
-    .bind(image_url)
-    .bind(genres)
+    .bind(&artist.name)
+    .bind(&artist.sort_name)
+    .bind(&artist.biography)
+    .bind(&artist.image_url)
+    .bind(&artist.genres)
     .bind(lidarr_id_i32)
-    .bind(mbid)
+    .bind(artist.mbid)
+    .bind(MISSING_METADATA_KEY)
     .fetch_one(db)
     .await?;
 
     let inserted: bool = row.get("inserted");
 
     if inserted {
-        tracing::debug!("Created artist: {} (lidarr_id: {})", name, lidarr_id);
+        tracing::debug!(
+            "Created artist: {} (lidarr_id: {})",
+            artist.name,
+            artist.lidarr_id
+        );
     } else {
-        tracing::debug!("Updated artist: {} (lidarr_id: {})", name, lidarr_id);
+        tracing::debug!(
+            "Updated artist: {} (lidarr_id: {})",
+            artist.name,
+            artist.lidarr_id
+        );
     }
 
     Ok(inserted)
 }
 
-/// Check for new releases from monitored artists
-async fn check_new_releases(
-    state: &AppState,
-    lidarr_url: &str,
-    api_key: &str,
-    artist_paths: &HashMap<i64, String>,
-) -> WorkerResult<()> {
-    tracing::debug!("Checking for new releases");
-
-    // Fetch all albums from Lidarr
-    let albums = fetch_all_albums(state, lidarr_url, api_key).await?;
+/// Update album metadata from Lidarr, linking the album if needed
+async fn update_album(db: &sqlx::PgPool, album_id: Uuid, album: &PlannedAlbum) -> WorkerResult<()> {
+    let lidarr_id_i32 = i32::try_from(album.lidarr_id).map_err(|_| {
+        WorkerError::InvalidJobData(format!(
+            "Lidarr album ID out of i32 range: {}",
+            album.lidarr_id
+        ))
+    })?;
 
-    // Filter to albums that have tracks on disk
-    let albums_with_files: Vec<&LidarrAlbum> = albums
-        .iter()
-        .filter(|a| a.statistics.track_file_count > 0)
-        .collect();
+    sqlx::query(
+        r#"
+        UPDATE albums SET
+            lidarr_id = $1,
+            release_date = COALESCE($2, release_date),
+            genres = CASE WHEN array_length($3::text[], 1) > 0 THEN $3 ELSE genres END,
+            album_type = $4::album_type,
+            mbid = COALESCE($5, mbid),
+            total_tracks = $6,
+            metadata = metadata - $7,
+            updated_at = NOW()
+        WHERE id = $8
+        "#,
+    )
+    .bind(lidarr_id_i32)
+    .bind(album.release_date)
+    .bind(&album.genres)
+    .bind(album.album_type)
+    .bind(album.mbid)
+    .bind(album.total_tracks)
+    .bind(MISSING_METADATA_KEY)
+    .bind(album_id)
+    .execute(db)
+    .await?;
 
-    tracing::info!(
-        "Found {} albums with tracks on disk",
-        albums_with_files.len()
+    tracing::debug!(
+        "Updated album '{}' from Lidarr (lidarr_id: {})",
+        album.title,
+        album.lidarr_id
     );
 
-    // Get local albums with lidarr_id to see what's already imported
-    let local_album_ids: HashSet<i64> = get_local_lidarr_album_ids(&state.db).await?;
-
-    // Find albums that are in Lidarr but not yet in our database
-    let mut new_album_count = 0;
-    let mut scan_paths: HashSet<String> = HashSet::new();
-
-    for album in &albums_with_files {
-        if !local_album_ids.contains(&album.id) {
-            new_album_count += 1;
+    Ok(())
+}
 
-            // Use cached artist path (no additional API call needed)
-            if let Some(path) = artist_paths.get(&album.artist_id) {
-                scan_paths.insert(path.clone());
-            }
-        }
+/// Flag rows that Lidarr no longer reports
+///
+/// Rows are kept (their files may still be on disk); the flag is cleared the
+/// next time Lidarr reports them again.
+async fn mark_missing(
+    db: &sqlx::PgPool,
+    table: &'static str,
+    entries: &[MissingEntry],
+) -> WorkerResult<()> {
+    if entries.is_empty() {
+        return Ok(());
     }
 
-    tracing::info!(
-        "Found {} new albums to import across {} artist paths",
-        new_album_count,
-        scan_paths.len()
+    let ids: Vec<Uuid> = entries.iter().map(|entry| entry.id).collect();
+    let sql = format!(
+        "UPDATE {} SET metadata = jsonb_set(metadata, ARRAY[$1], 'true'::jsonb), updated_at = NOW() WHERE id = ANY($2)",
+        table
     );
+    sqlx::query(&sql)
+        .bind(MISSING_METADATA_KEY)
+        .bind(&ids)
+        .execute(db)
+        .await?;
 
-    // Validate and queue library scans for each artist directory
-    let canonical_library = state
-        .config
-        .music_library_path()
-        .canonicalize()
-        .map_err(|e| {
-            WorkerError::Configuration(format!("Failed to canonicalize library path: {}", e))
-        })?;
+    tracing::info!("Marked {} {} as missing from Lidarr", ids.len(), table);
+
+    Ok(())
+}
+
+/// Validate and queue library scans for artist directories with new albums
+async fn queue_scans(state: &AppState, scan_paths: &[String]) -> WorkerResult<()> {
+    let canonical_roots = canonical_music_roots(state.config.music_roots())?;
 
     for path in scan_paths {
         // Validate path is within music library before queueing
-        let candidate = PathBuf::from(&path);
+        let candidate = PathBuf::from(path);
         let canonical_candidate = match candidate.canonicalize() {
             Ok(p) => p,
             Err(e) => {
@@ -362,11 +736,11 @@ async fn check_new_releases(
             }
         };
 
-        if !canonical_candidate.starts_with(&canonical_library) {
+        if !is_within_roots(&canonical_candidate, &canonical_roots) {
             tracing::warn!(
                 "Skipping Lidarr scan path outside library: {} (library: {:?})",
                 path,
-                canonical_library
+                canonical_roots
             );
             continue;
         }
@@ -376,148 +750,231 @@ async fn check_new_releases(
             force_rescan: false,
         });
 
-        if let Err(e) = enqueue_job(&state.redis, &scan_job).await {
+        if let Err(e) = enqueue_job(state, &scan_job).await {
             tracing::warn!("Failed to queue library scan for {}: {}", path, e);
         } else {
             tracing::info!("Queued library scan for: {}", path);
         }
     }
 
-    // Also sync album metadata for existing albums
-    sync_album_metadata(state, &albums).await?;
-
     Ok(())
 }
 
-/// Get all local album IDs that have a lidarr_id set
-async fn get_local_lidarr_album_ids(db: &sqlx::PgPool) -> WorkerResult<HashSet<i64>> {
-    let rows = sqlx::query("SELECT lidarr_id FROM albums WHERE lidarr_id IS NOT NULL")
-        .fetch_all(db)
-        .await?;
-
-    let ids: HashSet<i64> = rows
-        .iter()
-        .map(|r| r.get::<i32, _>("lidarr_id") as i64)
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use resonance_shared_config::LidarrConfig;
+    use resonance_test_utils::{LidarrAlbumFixture, LidarrArtistFixture, MockLidarrServer};
+
+    /// Fetch both lists from a mock server the way the job does
+    async fn fetch_from(server: &MockLidarrServer) -> (Vec<LidarrArtist>, Vec<LidarrAlbum>) {
+        let http = reqwest::Client::new();
+        let config = LidarrConfig::new(server.url(), server.api_key());
+        let client = LidarrClient::new(&http, &config);
+        let artists = fetch_all_artists(&client).await.unwrap();
+        let albums = fetch_all_albums(&client).await.unwrap();
+        (artists, albums)
+    }
 
-    Ok(ids)
-}
+    /// Local album mirroring a Lidarr fixture exactly
+    fn local_album_from(fixture: &LidarrAlbumFixture) -> LocalAlbum {
+        LocalAlbum {
+            id: Uuid::new_v4(),
+            title: fixture.title.clone(),
+            release_date: fixture
+                .release_date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            album_type: "album".to_string(),
+            genres: fixture.genres.clone(),
+            total_tracks: Some(fixture.statistics.total_track_count),
+            mbid: fixture
+                .foreign_album_id
+                .as_deref()
+                .and_then(|id| Uuid::parse_str(id).ok()),
+            missing: false,
+        }
+    }
 
-/// Sync album metadata from Lidarr to local database
-async fn sync_album_metadata(state: &AppState, albums: &[LidarrAlbum]) -> WorkerResult<()> {
-    let mut updated = 0;
+    /// Local artist mirroring a Lidarr fixture exactly
+    fn local_artist_from(fixture: &LidarrArtistFixture) -> LocalArtist {
+        LocalArtist {
+            id: Uuid::new_v4(),
+            name: fixture.artist_name.clone(),
+            sort_name: fixture.sort_name.clone(),
+            biography: fixture.overview.clone(),
+            image_url: fixture.images.first().map(|img| img.url.clone()),
+            genres: fixture.genres.clone(),
+            mbid: fixture
+                .foreign_artist_id
+                .as_deref()
+                .and_then(|id| Uuid::parse_str(id).ok()),
+            missing: false,
+        }
+    }
 
-    for album in albums {
-        // Try to find existing album by lidarr_id or by matching title and artist
-        let local_album: Option<(Uuid,)> =
-            sqlx::query_as("SELECT id FROM albums WHERE lidarr_id = $1")
-                .bind(album.id as i32)
-                .fetch_optional(&state.db)
-                .await?;
+    fn lidarr_ids(albums: &[PlannedAlbum]) -> Vec<i64> {
+        let mut ids: Vec<i64> = albums.iter().map(|a| a.lidarr_id).collect();
+        ids.sort();
+        ids
+    }
 
-        if let Some((album_uuid,)) = local_album {
-            // Parse release date if available using safe string slicing
-            let release_date = album.release_date.as_ref().and_then(|d| {
-                d.get(..10).and_then(|date_str| {
-                    chrono::NaiveDate::parse_from_str(date_str, "%Y-%m-%d").ok()
-                })
-            });
+    #[test]
+    fn test_default_lidarr_sync_job() {
+        let job = LidarrSyncJob::default();
+        assert!(job.check_new_releases);
+        assert!(job.sync_metadata);
+        assert!(!job.dry_run);
+    }
 
-            // Parse MusicBrainz ID if available
-            let mbid = album
-                .foreign_album_id
-                .as_ref()
-                .and_then(|id| Uuid::parse_str(id).ok());
-
-            // Map album type
-            let album_type = match album.album_type.as_deref() {
-                Some("single") => "single",
-                Some("ep") => "ep",
-                Some("compilation") => "compilation",
-                Some("live") => "live",
-                _ => "album",
-            };
-
-            // Update album metadata
-            sqlx::query(
-                r#"
-                UPDATE albums SET
-                    release_date = COALESCE($1, release_date),
-                    genres = CASE WHEN array_length($2::text[], 1) > 0 THEN $2 ELSE genres END,
-                    album_type = $3::album_type,
-                    mbid = COALESCE($4, mbid),
-                    total_tracks = COALESCE($5, total_tracks),
-                    updated_at = NOW()
-                WHERE id = $6
-                "#,
-            )
-            .bind(release_date)
-            .bind(&album.genres)
-            .bind(album_type)
-            .bind(mbid)
-            .bind(album.statistics.total_track_count)
-            .bind(album_uuid)
-            .execute(&state.db)
-            .await?;
-
-            updated += 1;
-        } else {
-            // Try to match by title and artist name
-            // First, get artist name from Lidarr
-            if let Some(artist) = get_artist_by_lidarr_id(&state.db, album.artist_id).await? {
-                // Try to find album by title and artist
-                let found: Option<(Uuid,)> = sqlx::query_as(
-                    "SELECT id FROM albums WHERE LOWER(title) = LOWER($1) AND artist_id = $2",
-                )
-                .bind(&album.title)
-                .bind(artist)
-                .fetch_optional(&state.db)
-                .await?;
-
-                if let Some((album_uuid,)) = found {
-                    // Link album to Lidarr
-                    sqlx::query("UPDATE albums SET lidarr_id = $1 WHERE id = $2")
-                        .bind(album.id as i32)
-                        .bind(album_uuid)
-                        .execute(&state.db)
-                        .await?;
-
-                    tracing::debug!(
-                        "Linked album '{}' to Lidarr (lidarr_id: {})",
-                        album.title,
-                        album.id
-                    );
-                    updated += 1;
-                }
-            }
-        }
+    #[test]
+    fn test_job_deserializes_without_dry_run() {
+        let job: LidarrSyncJob =
+            serde_json::from_str(r#"{"check_new_releases": true, "sync_metadata": false}"#)
+                .unwrap();
+        assert!(!job.dry_run);
     }
 
-    if updated > 0 {
-        tracing::info!("Updated {} album records with Lidarr metadata", updated);
+    #[tokio::test]
+    async fn test_plan_classifies_new_changed_and_missing_albums() {
+        let server = MockLidarrServer::start().await;
+        let queen = LidarrArtistFixture::monitored(1, "Queen");
+
+        let new_album = LidarrAlbumFixture::with_tracks(10, "Innuendo", 1, 12);
+        let unchanged = LidarrAlbumFixture::with_tracks(11, "A Night at the Opera", 1, 12);
+        let mut changed = LidarrAlbumFixture::with_tracks(12, "News of the World", 1, 11);
+        let not_on_disk = LidarrAlbumFixture::without_tracks(13, "Made in Heaven", 1);
+        let unlinked = LidarrAlbumFixture::with_tracks(14, "Jazz", 1, 13);
+
+        let mut local = LocalLibrary::default();
+        local.artists.insert(1, local_artist_from(&queen));
+        local.albums.insert(11, local_album_from(&unchanged));
+        local.albums.insert(12, local_album_from(&changed));
+        let gone = local_album_from(&LidarrAlbumFixture::with_tracks(99, "Hot Space", 1, 11));
+        local.albums.insert(99, gone.clone());
+        let jazz = local_album_from(&unlinked);
+        local
+            .unlinked_albums
+            .insert((1, "jazz".to_string()), jazz.clone());
+
+        // Lidarr now reports a different release date and track count
+        changed.release_date = Some("1977-10-28".to_string());
+        changed.statistics.total_track_count = 12;
+
+        server.mock_artists_success(vec![queen]).await;
+        server
+            .mock_albums_success(vec![new_album, unchanged, changed, not_on_disk, unlinked])
+            .await;
+        let (artists, albums) = fetch_from(&server).await;
+
+        let plan = build_plan(&LidarrSyncJob::default(), &artists, &albums, &local);
+
+        assert_eq!(lidarr_ids(&plan.albums_to_add), vec![10]);
+        assert_eq!(lidarr_ids(&plan.albums_to_update), vec![12, 14]);
+        assert_eq!(
+            plan.albums_missing,
+            vec![MissingEntry {
+                id: gone.id,
+                lidarr_id: 99,
+                name: "Hot Space".to_string(),
+            }]
+        );
+        assert_eq!(plan.scan_paths, vec!["/music/queen".to_string()]);
+
+        let linked = plan
+            .albums_to_update
+            .iter()
+            .find(|a| a.lidarr_id == 14)
+            .unwrap();
+        assert_eq!(linked.local_id, Some(jazz.id));
+        let updated = plan
+            .albums_to_update
+            .iter()
+            .find(|a| a.lidarr_id == 12)
+            .unwrap();
+        assert_eq!(updated.release_date, NaiveDate::from_ymd_opt(1977, 10, 28));
+        assert_eq!(updated.total_tracks, 12);
     }
 
-    Ok(())
-}
+    #[tokio::test]
+    async fn test_plan_classifies_artists() {
+        let server = MockLidarrServer::start().await;
+        let existing = LidarrArtistFixture::monitored(1, "Queen");
+        let mut renamed = LidarrArtistFixture::monitored(2, "The Beatles");
+        let new_artist = LidarrArtistFixture::monitored(3, "Radiohead");
+        let unmonitored = LidarrArtistFixture::unmonitored(4, "Muse");
+
+        let mut local = LocalLibrary::default();
+        local.artists.insert(1, local_artist_from(&existing));
+        local.artists.insert(2, local_artist_from(&renamed));
+        let gone = local_artist_from(&LidarrArtistFixture::monitored(5, "Blur"));
+        local.artists.insert(5, gone.clone());
+
+        renamed.artist_name = "Beatles, The".to_string();
+
+        server
+            .mock_artists_success(vec![existing, renamed, new_artist, unmonitored])
+            .await;
+        server.mock_albums_empty().await;
+        let (artists, albums) = fetch_from(&server).await;
+
+        let plan = build_plan(&LidarrSyncJob::default(), &artists, &albums, &local);
+
+        let added: Vec<i64> = plan.artists_to_add.iter().map(|a| a.lidarr_id).collect();
+        let updated: Vec<i64> = plan.artists_to_update.iter().map(|a| a.lidarr_id).collect();
+        assert_eq!(added, vec![3]);
+        assert_eq!(updated, vec![2]);
+        assert_eq!(plan.artists_missing.len(), 1);
+        assert_eq!(plan.artists_missing[0].id, gone.id);
+    }
 
-/// Get artist UUID by Lidarr ID
-async fn get_artist_by_lidarr_id(db: &sqlx::PgPool, lidarr_id: i64) -> WorkerResult<Option<Uuid>> {
-    let result: Option<(Uuid,)> = sqlx::query_as("SELECT id FROM artists WHERE lidarr_id = $1")
-        .bind(lidarr_id as i32)
-        .fetch_optional(db)
-        .await?;
+    #[tokio::test]
+    async fn test_plan_respects_job_flags_and_missing_markers() {
+        let server = MockLidarrServer::start().await;
+        let queen = LidarrArtistFixture::monitored(1, "Queen");
+        let album = LidarrAlbumFixture::with_tracks(10, "Innuendo", 1, 12);
+
+        let mut local = LocalLibrary::default();
+        let mut flagged = local_album_from(&album);
+        flagged.missing = true;
+        local.albums.insert(10, flagged);
+        let mut already_missing =
+            local_album_from(&LidarrAlbumFixture::with_tracks(99, "Hot Space", 1, 11));
+        already_missing.missing = true;
+        local.albums.insert(99, already_missing);
+
+        server.mock_artists_success(vec![queen]).await;
+        server.mock_albums_success(vec![album]).await;
+        let (artists, albums) = fetch_from(&server).await;
+
+        let releases_only = LidarrSyncJob {
+            sync_metadata: false,
+            ..Default::default()
+        };
+        let plan = build_plan(&releases_only, &artists, &albums, &local);
 
-    Ok(result.map(|(id,)| id))
-}
+        // Reappearing albums are updated to clear the marker; flagged ones aren't re-flagged
+        assert!(plan.artists_to_add.is_empty());
+        assert_eq!(lidarr_ids(&plan.albums_to_update), vec![10]);
+        assert!(plan.albums_missing.is_empty());
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        let metadata_only = LidarrSyncJob {
+            check_new_releases: false,
+            ..Default::default()
+        };
+        let plan = build_plan(&metadata_only, &artists, &[], &local);
+        assert_eq!(plan.artists_to_add.len(), 1);
+        assert!(plan.albums_to_update.is_empty());
+    }
 
     #[test]
-    fn test_default_lidarr_sync_job() {
-        let job = LidarrSyncJob::default();
-        assert!(job.check_new_releases);
-        assert!(job.sync_metadata);
+    fn test_empty_plan() {
+        let plan = build_plan(
+            &LidarrSyncJob::default(),
+            &[],
+            &[],
+            &LocalLibrary::default(),
+        );
+        assert!(plan.is_empty());
     }
 }
@@ -0,0 +1,375 @@
+//! Integrated loudness measurement (EBU R128 / ITU-R BS.1770)
+//!
+//! Each channel is K-weighted (a high shelf modelling the head followed by a
+//! high-pass), squared, and accumulated into 100 ms blocks. Integrated
+//! loudness averages overlapping 400 ms gating blocks that pass an absolute
+//! gate at -70 LUFS and a relative gate 10 LU below the absolute-gated level.
+//! Loudness range (EBU Tech 3342) is the spread between the 10th and 95th
+//! percentiles of 3 s short-term loudness values, gated at -70 LUFS and
+//! 20 LU below their level.
+//!
+//! Tracks too short for a single gating block (or short-term window), or that
+//! are silent throughout, have no defined value and yield `None`.
+
+use symphonia::core::audio::Channels;
+
+/// Length of one accumulation block in milliseconds
+const BLOCK_MS: usize = 100;
+
+/// 100 ms blocks per 400 ms gating block (75% overlap between neighbours)
+const GATING_BLOCKS: usize = 4;
+
+/// 100 ms blocks per 3 s short-term window used for loudness range
+const SHORT_TERM_BLOCKS: usize = 30;
+
+/// Gating blocks at or below this loudness are ignored
+const ABSOLUTE_GATE_LUFS: f64 = -70.0;
+
+/// Relative gate for integrated loudness, in LU below the ungated level
+const INTEGRATED_RELATIVE_GATE_LU: f64 = -10.0;
+
+/// Relative gate for loudness range, in LU below the ungated level
+const RANGE_RELATIVE_GATE_LU: f64 = -20.0;
+
+/// Weight of surround channels in the channel sum
+const SURROUND_WEIGHT: f64 = 1.41;
+
+/// Integrated loudness and loudness range of a track
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Loudness {
+    /// Gated integrated loudness in LUFS
+    pub integrated: Option<f32>,
+    /// Loudness range in LU
+    pub range: Option<f32>,
+}
+
+/// BS.1770 weight of each channel, in interleaved order
+///
+/// Front and centre channels count once, surround channels 1.41 times, and
+/// the LFE channel is left out.
+pub fn channel_weights(channels: Channels) -> Vec<f64> {
+    channels
+        .iter()
+        .map(|channel| {
+            if channel == Channels::LFE1 || channel == Channels::LFE2 {
+                0.0
+            } else if channel == Channels::REAR_LEFT
+                || channel == Channels::REAR_RIGHT
+                || channel == Channels::SIDE_LEFT
+                || channel == Channels::SIDE_RIGHT
+            {
+                SURROUND_WEIGHT
+            } else {
+                1.0
+            }
+        })
+        .collect()
+}
+
+/// Second-order IIR section (transposed direct form II)
+#[derive(Debug, Clone)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    fn process(&mut self, x: f64) -> f64 {
+        let y = self.b[0] * x + self.z1;
+        self.z1 = self.b[1] * x - self.a[0] * y + self.z2;
+        self.z2 = self.b[2] * x - self.a[1] * y;
+        y
+    }
+}
+
+/// BS.1770 K-weighting filter for one channel
+///
+/// Coefficients are derived for the actual sample rate rather than the
+/// 48 kHz tables in the standard.
+#[derive(Debug, Clone)]
+struct KWeighting {
+    shelf: Biquad,
+    high_pass: Biquad,
+}
+
+impl KWeighting {
+    fn new(sample_rate: u32) -> Self {
+        let fs = f64::from(sample_rate.max(1));
+
+        // Stage 1: high shelf, +4 dB above ~1.7 kHz
+        let f0 = 1681.974450955533;
+        let gain_db = 3.999843853973347;
+        let q = 0.7071752369554196;
+        let k = (std::f64::consts::PI * f0 / fs).tan();
+        let vh = 10f64.powf(gain_db / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z1: 0.0,
+            z2: 0.0,
+        };
+
+        // Stage 2: high-pass at ~38 Hz
+        let f0 = 38.13547087602444;
+        let q = 0.5003270373238773;
+        let k = (std::f64::consts::PI * f0 / fs).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let high_pass = Biquad {
+            b: [1.0, -2.0, 1.0],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+            z1: 0.0,
+            z2: 0.0,
+        };
+
+        Self { shelf, high_pass }
+    }
+
+    fn process(&mut self, sample: f32) -> f64 {
+        self.high_pass
+            .process(self.shelf.process(f64::from(sample)))
+    }
+}
+
+/// Accumulates interleaved samples into 100 ms K-weighted energy blocks
+#[derive(Debug)]
+pub struct LoudnessMeter {
+    filters: Vec<KWeighting>,
+    weights: Vec<f64>,
+    /// Frames (samples per channel) in one 100 ms block
+    block_frames: usize,
+    /// Channel of the next pushed sample
+    channel: usize,
+    /// Weighted sum of squares in the current block
+    energy: f64,
+    frames: usize,
+    /// Weighted mean square of each complete block
+    blocks: Vec<f64>,
+}
+
+impl LoudnessMeter {
+    /// Create a meter for interleaved audio with one weight per channel
+    pub fn new(sample_rate: u32, weights: Vec<f64>) -> Self {
+        let weights = if weights.is_empty() {
+            vec![1.0]
+        } else {
+            weights
+        };
+        Self {
+            filters: vec![KWeighting::new(sample_rate); weights.len()],
+            weights,
+            block_frames: (sample_rate as usize * BLOCK_MS / 1000).max(1),
+            channel: 0,
+            energy: 0.0,
+            frames: 0,
+            blocks: Vec::new(),
+        }
+    }
+
+    /// Add the next interleaved sample
+    pub fn push(&mut self, sample: f32) {
+        let filtered = self.filters[self.channel].process(sample);
+        self.energy += self.weights[self.channel] * filtered * filtered;
+
+        self.channel += 1;
+        if self.channel == self.weights.len() {
+            self.channel = 0;
+            self.frames += 1;
+            if self.frames == self.block_frames {
+                self.blocks.push(self.energy / self.frames as f64);
+                self.energy = 0.0;
+                self.frames = 0;
+            }
+        }
+    }
+
+    /// Compute loudness from the complete blocks, dropping a trailing
+    /// partial block
+    pub fn finish(self) -> Loudness {
+        Loudness {
+            integrated: integrated_loudness(&self.blocks).map(|l| l as f32),
+            range: loudness_range(&self.blocks).map(|l| l as f32),
+        }
+    }
+}
+
+/// Loudness in LUFS of a weighted mean square
+fn to_lufs(mean_square: f64) -> f64 {
+    -0.691 + 10.0 * mean_square.log10()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Mean square of each window of `len` consecutive blocks
+fn windows(blocks: &[f64], len: usize) -> Vec<f64> {
+    blocks
+        .windows(len)
+        .map(|window| window.iter().sum::<f64>() / len as f64)
+        .collect()
+}
+
+/// Keep windows above the absolute gate and `relative_gate_lu` below their
+/// mean level
+fn gate(windows: Vec<f64>, relative_gate_lu: f64) -> Vec<f64> {
+    let above_absolute: Vec<f64> = windows
+        .into_iter()
+        .filter(|&z| to_lufs(z) > ABSOLUTE_GATE_LUFS)
+        .collect();
+    if above_absolute.is_empty() {
+        return above_absolute;
+    }
+
+    let threshold = to_lufs(mean(&above_absolute)) + relative_gate_lu;
+    above_absolute
+        .into_iter()
+        .filter(|&z| to_lufs(z) > threshold)
+        .collect()
+}
+
+/// Gated integrated loudness of 100 ms blocks
+fn integrated_loudness(blocks: &[f64]) -> Option<f64> {
+    let gated = gate(windows(blocks, GATING_BLOCKS), INTEGRATED_RELATIVE_GATE_LU);
+    if gated.is_empty() {
+        return None;
+    }
+    Some(to_lufs(mean(&gated)))
+}
+
+/// Loudness range (L95 - L10 of gated short-term loudness) of 100 ms blocks
+fn loudness_range(blocks: &[f64]) -> Option<f64> {
+    let gated = gate(windows(blocks, SHORT_TERM_BLOCKS), RANGE_RELATIVE_GATE_LU);
+    if gated.is_empty() {
+        return None;
+    }
+
+    let mut levels: Vec<f64> = gated.into_iter().map(to_lufs).collect();
+    levels.sort_by(f64::total_cmp);
+    let percentile = |p: f64| levels[((levels.len() - 1) as f64 * p).round() as usize];
+    Some(percentile(0.95) - percentile(0.10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 48000;
+
+    /// Interleaved stereo 1 kHz sine with the given peak level in dBFS
+    fn stereo_sine(peak_dbfs: f32, seconds: f32) -> Vec<f32> {
+        let amplitude = 10f32.powf(peak_dbfs / 20.0);
+        let frames = (SAMPLE_RATE as f32 * seconds) as usize;
+        (0..frames)
+            .flat_map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                let s = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                [s, s]
+            })
+            .collect()
+    }
+
+    fn measure(samples: &[f32]) -> Loudness {
+        let mut meter = LoudnessMeter::new(SAMPLE_RATE, vec![1.0, 1.0]);
+        samples.iter().for_each(|&s| meter.push(s));
+        meter.finish()
+    }
+
+    #[test]
+    fn test_calibrated_sine_reads_minus_23_lufs() {
+        // EBU Tech 3341: a stereo 1 kHz sine at -23 dBFS reads -23 LUFS
+        let loudness = measure(&stereo_sine(-23.0, 20.0));
+        let integrated = loudness.integrated.unwrap();
+        assert!(
+            (integrated + 23.0).abs() < 0.1,
+            "expected -23 LUFS, got {}",
+            integrated
+        );
+        // A steady tone has no loudness range
+        assert!(loudness.range.unwrap() < 0.1);
+    }
+
+    #[test]
+    fn test_calibrated_sine_at_other_sample_rates() {
+        for sample_rate in [44100, 96000] {
+            let amplitude = 10f32.powf(-23.0 / 20.0);
+            let mut meter = LoudnessMeter::new(sample_rate, vec![1.0, 1.0]);
+            for i in 0..sample_rate as usize * 10 {
+                let t = i as f32 / sample_rate as f32;
+                let s = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+                meter.push(s);
+                meter.push(s);
+            }
+            let integrated = meter.finish().integrated.unwrap();
+            assert!(
+                (integrated + 23.0).abs() < 0.1,
+                "{} Hz: expected -23 LUFS, got {}",
+                sample_rate,
+                integrated
+            );
+        }
+    }
+
+    #[test]
+    fn test_loudness_range_of_two_levels() {
+        // EBU Tech 3342 case 1: 20 s at -20 dBFS then 20 s at -30 dBFS
+        let mut samples = stereo_sine(-20.0, 20.0);
+        samples.extend(stereo_sine(-30.0, 20.0));
+
+        let range = measure(&samples).range.unwrap();
+        assert!((range - 10.0).abs() < 1.0, "expected 10 LU, got {}", range);
+    }
+
+    #[test]
+    fn test_silence_is_gated_out() {
+        // Silence between tones doesn't drag the integrated level down
+        let mut samples = stereo_sine(-23.0, 5.0);
+        samples.extend(vec![0.0; SAMPLE_RATE as usize * 2 * 10]);
+        samples.extend(stereo_sine(-23.0, 5.0));
+        let integrated = measure(&samples).integrated.unwrap();
+        // Gating blocks straddling a tone edge pass the relative gate and
+        // pull the level down slightly
+        assert!((integrated + 23.0).abs() < 0.2, "got {}", integrated);
+
+        assert_eq!(
+            measure(&vec![0.0; SAMPLE_RATE as usize * 2 * 5]),
+            Loudness::default()
+        );
+    }
+
+    #[test]
+    fn test_short_tracks_have_no_loudness() {
+        // Shorter than one 400 ms gating block
+        assert_eq!(measure(&stereo_sine(-23.0, 0.3)), Loudness::default());
+
+        // Long enough for integrated loudness but not a 3 s short-term window
+        let loudness = measure(&stereo_sine(-23.0, 1.0));
+        assert!(loudness.integrated.is_some());
+        assert!(loudness.range.is_none());
+    }
+
+    #[test]
+    fn test_channel_weights() {
+        assert_eq!(
+            channel_weights(Channels::FRONT_LEFT | Channels::FRONT_RIGHT),
+            vec![1.0, 1.0]
+        );
+        let surround = Channels::FRONT_LEFT
+            | Channels::FRONT_RIGHT
+            | Channels::FRONT_CENTRE
+            | Channels::LFE1
+            | Channels::REAR_LEFT
+            | Channels::REAR_RIGHT;
+        assert_eq!(
+            channel_weights(surround),
+            vec![1.0, 1.0, 1.0, 0.0, SURROUND_WEIGHT, SURROUND_WEIGHT]
+        );
+    }
+}
@@ -2,13 +2,17 @@
 //!
 //! This module contains scheduled tasks including:
 //! - Library scanning and metadata updates
-//! - Audio feature extraction
-//! - AI embedding generation
+//! - Audio feature extraction (including crossfade points)
+//! - AI embedding generation (single track and checkpointed batch backfill)
 //! - Weekly Discover playlist creation
 //! - Taste-clustered playlist generation
+//! - Top-N similar track precomputation
 //! - Smart prefetch for autoplay
+//! - Skip feedback that down-weights tracks a user keeps skipping
 //! - Lidarr integration sync
 //! - Search indexing for Meilisearch
+//! - Artist biography and tag enrichment from Last.fm
+//! - Embedded cover art extraction for albums without art
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -18,16 +22,33 @@ use tokio::sync::broadcast;
 use crate::error::{WorkerError, WorkerResult};
 use crate::AppState;
 
+use backoff::{wait_for_tick, IdleBackoff, Wake};
+
+pub mod analysis_runs;
+pub mod artist_enrichment;
+mod backoff;
 pub mod clustering;
+pub mod cover_art_extraction;
+pub mod duplicates;
+pub mod embedding_batch;
 pub mod embedding_generation;
+pub mod embedding_reduction;
+pub mod fade_points;
 pub mod feature_extraction;
+pub mod fingerprint;
+pub mod genre_suggestion;
 pub mod key_detection;
 pub mod library_scan;
+pub mod lidarr_api;
 pub mod lidarr_sync;
+pub mod loudness;
 pub mod mood_detection;
 pub mod prefetch;
+pub mod resample;
 pub mod rhythm_analysis;
 pub mod search_indexing;
+pub mod similarity_precompute;
+mod skip_feedback;
 pub mod spectral;
 pub mod weekly_playlist;
 
@@ -60,6 +81,12 @@ pub enum Job {
     /// Generate AI embeddings for a track
     EmbeddingGeneration(embedding_generation::EmbeddingGenerationJob),
 
+    /// Backfill AI embeddings for all tracks missing them, with checkpointing
+    EmbeddingBatch(embedding_batch::EmbeddingJob),
+
+    /// Backfill reduced-dimension description embeddings for fast search
+    EmbeddingReduction(embedding_reduction::EmbeddingReductionJob),
+
     /// Detect mood and generate AI description for a track
     MoodDetection(mood_detection::MoodDetectionJob),
 
@@ -69,11 +96,36 @@ pub enum Job {
     /// Sync with Lidarr for new releases
     LidarrSync(lidarr_sync::LidarrSyncJob),
 
+    /// Precompute each track's top similar tracks
+    SimilarityPrecompute(similarity_precompute::SimilarityPrecomputeJob),
+
     /// Prefetch tracks for autoplay
     Prefetch(prefetch::PrefetchJob),
 
     /// Index content in Meilisearch for full-text search
     SearchIndexing(search_indexing::SearchIndexingJob),
+
+    /// Fill in artist biographies and tags from Last.fm
+    ArtistEnrichment(artist_enrichment::ArtistEnrichmentJob),
+
+    /// Store cover art embedded in audio files for albums without art
+    CoverArtExtraction(cover_art_extraction::CoverArtExtractionJob),
+}
+
+impl Job {
+    /// Whether the job reads or writes embedding vectors, which only exist
+    /// when the database has the pgvector extension
+    pub fn needs_pgvector(&self) -> bool {
+        matches!(
+            self,
+            Self::EmbeddingGeneration(_)
+                | Self::EmbeddingBatch(_)
+                | Self::EmbeddingReduction(_)
+                | Self::WeeklyPlaylist(_)
+                | Self::SimilarityPrecompute(_)
+                | Self::Prefetch(_)
+        )
+    }
 }
 
 /// Redis queue keys
@@ -97,23 +149,71 @@ impl JobRunner {
 
     /// Run the job processing loop
     pub async fn run(mut self) -> WorkerResult<()> {
-        let poll_interval = Duration::from_secs(self.state.config.poll_interval_secs);
+        let mut backoff = IdleBackoff::new(
+            Duration::from_secs(self.state.config.poll_interval_secs),
+            Duration::from_secs(self.state.config.max_poll_interval_secs),
+        );
 
         tracing::info!(
-            "Starting job runner with {} second poll interval",
-            self.state.config.poll_interval_secs
+            "Starting job runner with {} second poll interval (up to {} seconds when idle)",
+            self.state.config.poll_interval_secs,
+            self.state.config.max_poll_interval_secs
         );
 
+        match library_scan::fail_interrupted_scan_requests(&self.state.db).await {
+            Ok(0) => {}
+            Ok(count) => tracing::warn!(
+                "Marked {} interrupted library scan requests as failed",
+                count
+            ),
+            Err(e) => tracing::error!("Error resetting interrupted library scans: {}", e),
+        }
+        match feature_extraction::fail_interrupted_extraction_requests(&self.state.db).await {
+            Ok(0) => {}
+            Ok(count) => tracing::warn!(
+                "Marked {} interrupted feature extraction requests as failed",
+                count
+            ),
+            Err(e) => tracing::error!("Error resetting interrupted feature extractions: {}", e),
+        }
+        match embedding_batch::requeue_interrupted_regenerations(&self.state.db).await {
+            Ok(0) => {}
+            Ok(count) => tracing::warn!(
+                "Requeued {} interrupted embedding regeneration requests",
+                count
+            ),
+            Err(e) => tracing::error!(
+                "Error requeueing interrupted embedding regenerations: {}",
+                e
+            ),
+        }
+        if let Some(ollama) = self.state.ollama.as_ref() {
+            match embedding_batch::count_stale_embeddings(&self.state.db, ollama).await {
+                Ok(0) => {}
+                Ok(count) => tracing::warn!(
+                    "{} track embeddings were built by a different model or template; the embedding backfill will rebuild them",
+                    count
+                ),
+                Err(e) => tracing::error!("Error checking for stale embeddings: {}", e),
+            }
+        }
+
         loop {
-            tokio::select! {
-                _ = self.shutdown_rx.recv() => {
+            let wake = wait_for_tick(
+                backoff.interval(),
+                &self.state.job_enqueued,
+                &mut self.shutdown_rx,
+            )
+            .await;
+            match wake {
+                Wake::Shutdown => {
                     tracing::info!("Job runner received shutdown signal");
                     break;
                 }
-                _ = tokio::time::sleep(poll_interval) => {
-                    if let Err(e) = self.process_pending_jobs().await {
-                        tracing::error!("Error processing jobs: {}", e);
-                    }
+                Wake::Enqueued => backoff.reset(),
+                Wake::Tick => {
+                    let found_work = self.tick().await;
+                    backoff.record(found_work);
                 }
             }
         }
@@ -122,8 +222,41 @@ impl JobRunner {
         Ok(())
     }
 
-    /// Process pending jobs from the queue
-    async fn process_pending_jobs(&self) -> WorkerResult<()> {
+    /// Poll every source of work once, returning whether any had work
+    async fn tick(&self) -> bool {
+        let mut found_work = false;
+
+        match library_scan::process_scan_request(&self.state).await {
+            Ok(claimed) => found_work |= claimed,
+            Err(e) => tracing::error!("Error processing library scan requests: {}", e),
+        }
+        match feature_extraction::process_extraction_request(&self.state).await {
+            Ok(claimed) => found_work |= claimed,
+            Err(e) => tracing::error!("Error processing feature extraction requests: {}", e),
+        }
+        if self.state.pgvector {
+            let mut shutdown = self.shutdown_rx.resubscribe();
+            match embedding_batch::process_regeneration_request(&self.state, &mut shutdown).await {
+                Ok(claimed) => found_work |= claimed,
+                Err(e) => {
+                    tracing::error!("Error processing embedding regeneration requests: {}", e)
+                }
+            }
+        }
+        match search_indexing::process_index_outbox(&self.state).await {
+            Ok(applied) => found_work |= applied > 0,
+            Err(e) => tracing::error!("Error applying search index updates: {}", e),
+        }
+        match self.process_pending_jobs().await {
+            Ok(processed) => found_work |= processed,
+            Err(e) => tracing::error!("Error processing jobs: {}", e),
+        }
+
+        found_work
+    }
+
+    /// Process one pending job from the queue, returning whether there was one
+    async fn process_pending_jobs(&self) -> WorkerResult<bool> {
         let mut conn = self.state.redis.get_multiplexed_async_connection().await?;
 
         // Try to pop a job from the pending queue
@@ -132,6 +265,7 @@ impl JobRunner {
             .query_async(&mut conn)
             .await?;
 
+        let found = job_data.is_some();
         if let Some(data) = job_data {
             // Move job to processing queue
             let _: i64 = redis::cmd("RPUSH")
@@ -195,11 +329,19 @@ impl JobRunner {
             }
         }
 
-        Ok(())
+        Ok(found)
     }
 
     /// Execute a specific job
     async fn execute_job(&self, job: &Job) -> WorkerResult<()> {
+        if job.needs_pgvector() && !self.state.pgvector {
+            tracing::warn!(
+                ?job,
+                "Skipping job: the pgvector extension is not installed"
+            );
+            return Ok(());
+        }
+
         match job {
             Job::LibraryScan(payload) => library_scan::execute(&self.state, payload).await,
             Job::FeatureExtraction(payload) => {
@@ -208,19 +350,39 @@ impl JobRunner {
             Job::EmbeddingGeneration(payload) => {
                 embedding_generation::execute(&self.state, payload).await
             }
+            Job::EmbeddingBatch(payload) => {
+                let mut shutdown = self.shutdown_rx.resubscribe();
+                embedding_batch::execute(&self.state, payload, &mut shutdown).await
+            }
+            Job::EmbeddingReduction(payload) => {
+                embedding_reduction::execute(&self.state, payload).await
+            }
             Job::MoodDetection(payload) => mood_detection::execute(&self.state, payload).await,
             Job::WeeklyPlaylist(payload) => weekly_playlist::execute(&self.state, payload).await,
-            Job::LidarrSync(payload) => lidarr_sync::execute(&self.state, payload).await,
+            Job::LidarrSync(payload) => {
+                lidarr_sync::execute(&self.state, payload).await.map(|_| ())
+            }
+            Job::SimilarityPrecompute(payload) => {
+                similarity_precompute::execute(&self.state, payload).await
+            }
             Job::Prefetch(payload) => prefetch::execute(&self.state, payload).await,
             Job::SearchIndexing(payload) => search_indexing::execute(&self.state, payload).await,
+            Job::ArtistEnrichment(payload) => {
+                artist_enrichment::execute(&self.state, payload).await
+            }
+            Job::CoverArtExtraction(payload) => {
+                cover_art_extraction::execute(&self.state, payload).await
+            }
         }
     }
 }
 
 /// Helper to enqueue a job
-#[allow(dead_code)]
-pub async fn enqueue_job(redis: &redis::Client, job: &Job) -> WorkerResult<()> {
-    let mut conn = redis.get_multiplexed_async_connection().await?;
+///
+/// Also wakes this worker's job runner, so it doesn't sleep out an idle
+/// backoff before picking the job up.
+pub async fn enqueue_job(state: &AppState, job: &Job) -> WorkerResult<()> {
+    let mut conn = state.redis.get_multiplexed_async_connection().await?;
     let data = serde_json::to_string(job)?;
 
     let _: i64 = redis::cmd("RPUSH")
@@ -229,6 +391,7 @@ pub async fn enqueue_job(redis: &redis::Client, job: &Job) -> WorkerResult<()> {
         .query_async(&mut conn)
         .await?;
 
+    state.job_enqueued.notify_one();
     tracing::debug!("Enqueued job: {:?}", job);
     Ok(())
 }
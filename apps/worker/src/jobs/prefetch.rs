@@ -4,10 +4,14 @@
 //! for faster streaming and reduced database load. Uses pgvector
 //! embeddings and audio features for intelligent track prediction.
 
+use std::time::Duration;
+
+use resonance_shared_config::ttl_with_jitter;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::error::{WorkerError, WorkerResult};
+use crate::jobs::skip_feedback::{MAX_PENALIZED_SKIPS, QUICK_SKIP_DECAY, SKIP_HISTORY_DAYS};
 use crate::AppState;
 
 /// Prefetch job payload
@@ -173,6 +177,8 @@ pub async fn execute(state: &AppState, job: &PrefetchJob) -> WorkerResult<()> {
 /// - Acoustic similarity (30%): Audio feature Euclidean distance
 /// - Categorical similarity (20%): Genre, mood, and AI tag overlap
 ///
+/// Tracks the user keeps quick skipping are down-weighted (see `skip_feedback`).
+///
 /// Falls back to audio features + tags only if embeddings aren't available.
 async fn predict_next_tracks(
     state: &AppState,
@@ -304,17 +310,27 @@ async fn predict_by_combined_similarity(
             FROM embedding_scores e
             FULL OUTER JOIN feature_scores f ON e.id = f.id
             FULL OUTER JOIN tag_scores g ON COALESCE(e.id, f.id) = g.id
+        ),
+        quick_skips AS (
+            SELECT track_id, COUNT(*) AS skips
+            FROM track_skips
+            WHERE user_id = $2
+              AND quick
+              AND skipped_at > NOW() - make_interval(days => $9)
+            GROUP BY track_id
         )
         SELECT cs.id
         FROM combined_scores cs
         JOIN tracks t ON t.id = cs.id
+        LEFT JOIN quick_skips qs ON qs.track_id = cs.id
         WHERE NOT EXISTS (
             SELECT 1 FROM listening_history lh
             WHERE lh.user_id = $2
               AND lh.played_at > NOW() - INTERVAL '24 hours'
               AND lh.track_id = cs.id
         )
-        ORDER BY cs.combined_score DESC
+        -- Down-weight tracks the user keeps skipping
+        ORDER BY cs.combined_score * POWER($10, LEAST(COALESCE(qs.skips, 0), $11)::float) DESC
         LIMIT $3
         "#,
     )
@@ -326,6 +342,9 @@ async fn predict_by_combined_similarity(
     .bind(WEIGHT_CATEGORICAL)
     .bind(LOUDNESS_OFFSET)
     .bind(BPM_NORMALIZATION_FACTOR)
+    .bind(SKIP_HISTORY_DAYS)
+    .bind(QUICK_SKIP_DECAY)
+    .bind(MAX_PENALIZED_SKIPS)
     .fetch_all(&state.db)
     .await?;
 
@@ -393,16 +412,27 @@ async fn predict_by_features(
               AND t.audio_features->>'valence' IS NOT NULL
               AND t.audio_features->>'danceability' IS NOT NULL
               AND t.audio_features->>'bpm' IS NOT NULL
+        ),
+        quick_skips AS (
+            SELECT track_id, COUNT(*) AS skips
+            FROM track_skips
+            WHERE user_id = $2
+              AND quick
+              AND skipped_at > NOW() - make_interval(days => $8)
+            GROUP BY track_id
         )
         SELECT fs.id
         FROM feature_scores fs
+        LEFT JOIN quick_skips qs ON qs.track_id = fs.id
         WHERE NOT EXISTS (
             SELECT 1 FROM listening_history lh
             WHERE lh.user_id = $2
               AND lh.played_at > NOW() - INTERVAL '24 hours'
               AND lh.track_id = fs.id
         )
-        ORDER BY (fs.feature_score * $6 + fs.tag_score * $7) DESC
+        -- Down-weight tracks the user keeps skipping
+        ORDER BY (fs.feature_score * $6 + fs.tag_score * $7)
+            * POWER($9, LEAST(COALESCE(qs.skips, 0), $10)::float) DESC
         LIMIT $3
         "#,
     )
@@ -413,6 +443,9 @@ async fn predict_by_features(
     .bind(BPM_NORMALIZATION_FACTOR)
     .bind(WEIGHT_FALLBACK_FEATURE)
     .bind(WEIGHT_FALLBACK_TAGS)
+    .bind(SKIP_HISTORY_DAYS)
+    .bind(QUICK_SKIP_DECAY)
+    .bind(MAX_PENALIZED_SKIPS)
     .fetch_all(&state.db)
     .await?;
 
@@ -423,7 +456,7 @@ async fn predict_by_features(
 ///
 /// Prefetch data is typically consumed within seconds to minutes.
 /// A shorter TTL reduces memory waste while still supporting pause-resume scenarios.
-const CACHE_TTL_SECONDS: i64 = 30 * 60; // 30 minutes
+const CACHE_TTL_SECONDS: u64 = 30 * 60; // 30 minutes
 
 /// Cache track metadata in Redis for quick access during playback.
 ///
@@ -471,6 +504,7 @@ async fn cache_tracks(state: &AppState, user_id: Uuid, track_ids: &[Uuid]) -> Wo
     // Use Redis pipeline for efficient multi-set
     let mut conn = state.redis.get_multiplexed_async_connection().await?;
     let mut pipe = redis::pipe();
+    let jitter_pct = state.config.redis().ttl_jitter_pct;
 
     for track in &tracks {
         let cache_key = format!("prefetch:{}:{}", user_id, track.id);
@@ -481,9 +515,12 @@ async fn cache_tracks(state: &AppState, user_id: Uuid, track_ids: &[Uuid]) -> Wo
             ))
         })?;
 
+        // Jitter per key so a prefetched batch doesn't expire all at once
+        let ttl = ttl_with_jitter(Duration::from_secs(CACHE_TTL_SECONDS), jitter_pct);
+
         pipe.cmd("SETEX")
             .arg(&cache_key)
-            .arg(CACHE_TTL_SECONDS)
+            .arg(ttl.as_secs().max(1))
             .arg(json);
     }
 
@@ -0,0 +1,148 @@
+//! Sample-rate normalization for audio analysis
+//!
+//! Frame-based analyzers work in samples, so the same recording decoded at
+//! 96kHz and 44.1kHz yields different zero-crossing rates, FFT bin widths and
+//! onset timings. Resampling the analysis buffer to one reference rate before
+//! analysis keeps stored features comparable across tracks.
+
+use rubato::{FftFixedIn, Resampler};
+
+/// Reference sample rate for audio analysis (Hz)
+pub const DEFAULT_ANALYSIS_SAMPLE_RATE: u32 = 44_100;
+
+/// Input frames fed to the resampler per call
+const CHUNK_SIZE: usize = 4096;
+
+/// FFT sub-chunks per input chunk; more gives lower latency at some CPU cost
+const SUB_CHUNKS: usize = 2;
+
+/// Resample mono samples from `from_rate` to `to_rate`
+///
+/// Returns the input unchanged when the rates already match. The output is
+/// aligned with the input (the resampler's delay is trimmed) and holds
+/// `len * to_rate / from_rate` samples, rounded up.
+pub fn resample_mono(samples: &[f32], from_rate: u32, to_rate: u32) -> Result<Vec<f32>, String> {
+    if from_rate == to_rate || samples.is_empty() {
+        return Ok(samples.to_vec());
+    }
+
+    let mut resampler = FftFixedIn::<f32>::new(
+        from_rate as usize,
+        to_rate as usize,
+        CHUNK_SIZE,
+        SUB_CHUNKS,
+        1,
+    )
+    .map_err(|e| format!("Failed to create resampler: {}", e))?;
+
+    let expected_len = (samples.len() as u64 * to_rate as u64).div_ceil(from_rate as u64) as usize;
+    let delay = resampler.output_delay();
+    let mut output = Vec::with_capacity(expected_len + delay + CHUNK_SIZE);
+
+    let mut chunks = samples.chunks_exact(CHUNK_SIZE);
+    for chunk in chunks.by_ref() {
+        let out = resampler
+            .process(&[chunk], None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        output.extend_from_slice(&out[0]);
+    }
+
+    let remainder = chunks.remainder();
+    if !remainder.is_empty() {
+        let out = resampler
+            .process_partial(Some(&[remainder]), None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        output.extend_from_slice(&out[0]);
+    }
+
+    // Flush the samples still held back by the resampler's delay
+    while output.len() < expected_len + delay {
+        let out = resampler
+            .process_partial::<&[f32]>(None, None)
+            .map_err(|e| format!("Resampling failed: {}", e))?;
+        if out[0].is_empty() {
+            break;
+        }
+        output.extend_from_slice(&out[0]);
+    }
+
+    output.drain(..delay.min(output.len()));
+    output.truncate(expected_len);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::spectral::analyze_spectral_features;
+    use std::f32::consts::PI;
+
+    /// A tone with a few harmonics so rolloff and flatness are non-trivial
+    fn harmonic_tone(sample_rate: u32, seconds: f32) -> Vec<f32> {
+        let n = (sample_rate as f32 * seconds) as usize;
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / sample_rate as f32;
+                [(220.0, 0.6), (440.0, 0.3), (1320.0, 0.15), (3520.0, 0.05)]
+                    .iter()
+                    .map(|(freq, amp)| amp * (2.0 * PI * freq * t).sin())
+                    .sum()
+            })
+            .collect()
+    }
+
+    fn assert_close(actual: f32, expected: f32, tolerance: f32, what: &str) {
+        let diff = (actual - expected).abs();
+        assert!(
+            diff <= tolerance * expected.abs().max(1e-6),
+            "{}: {} vs {} (diff {})",
+            what,
+            actual,
+            expected,
+            diff
+        );
+    }
+
+    #[test]
+    fn test_resample_matching_rate_is_passthrough() {
+        let samples = vec![0.1, -0.2, 0.3];
+        assert_eq!(resample_mono(&samples, 44_100, 44_100).unwrap(), samples);
+        assert!(resample_mono(&[], 96_000, 44_100).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_resample_output_length() {
+        let samples = harmonic_tone(96_000, 1.0);
+        let resampled = resample_mono(&samples, 96_000, 44_100).unwrap();
+        assert_eq!(resampled.len(), 44_100);
+    }
+
+    #[test]
+    fn test_resampled_features_match_native_rate() {
+        let native = harmonic_tone(44_100, 3.0);
+        let hires = harmonic_tone(96_000, 3.0);
+        let resampled = resample_mono(&hires, 96_000, 44_100).unwrap();
+
+        let expected = analyze_spectral_features(&native, 44_100);
+        let actual = analyze_spectral_features(&resampled, 44_100);
+
+        assert_close(
+            actual.centroid_mean,
+            expected.centroid_mean,
+            0.02,
+            "centroid",
+        );
+        assert_close(actual.rolloff_mean, expected.rolloff_mean, 0.05, "rolloff");
+        assert_close(actual.zcr_mean, expected.zcr_mean, 0.02, "zcr");
+        assert_close(
+            actual.hf_energy_ratio,
+            expected.hf_energy_ratio,
+            0.05,
+            "hf energy ratio",
+        );
+
+        // Without normalization the per-sample ZCR is off by the rate ratio
+        let unnormalized = analyze_spectral_features(&hires, 96_000);
+        assert!((unnormalized.zcr_mean - expected.zcr_mean).abs() > 0.2 * expected.zcr_mean);
+    }
+}
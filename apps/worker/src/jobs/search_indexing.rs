@@ -11,13 +11,20 @@
 //! - `DeleteTrack` - Remove a track from the index
 //! - `DeleteAlbum` - Remove an album from the index
 //! - `DeleteArtist` - Remove an artist from the index
+//!
+//! ## Incremental Updates
+//! Track writes record an event in the `search_index_outbox` table in the
+//! same transaction (see [`queue_track_index_update`]). The worker drains the
+//! outbox on every poll with [`process_index_outbox`], so the index follows
+//! the library without waiting for a full rebuild.
 
 use chrono::{DateTime, NaiveDate, Utc};
 use meilisearch_sdk::client::Client;
 use meilisearch_sdk::settings::Settings;
 use meilisearch_sdk::task_info::TaskInfo;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{debug, info, instrument, warn};
@@ -33,6 +40,9 @@ use crate::AppState;
 /// to handle large libraries gracefully without excessive memory usage.
 const BATCH_SIZE: i64 = 1000;
 
+/// Maximum number of outbox events applied per poll
+const OUTBOX_BATCH_SIZE: i64 = 500;
+
 // ==================== Index Names ====================
 
 /// Index name constants
@@ -776,6 +786,113 @@ async fn delete_document(client: &Client, index_name: &str, id: &Uuid) -> Worker
     Ok(())
 }
 
+// ==================== Incremental Updates ====================
+
+/// Change recorded for a track in `search_index_outbox`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexAction {
+    /// Re-index the track's document
+    Upsert,
+    /// Remove the track's document
+    Delete,
+}
+
+impl IndexAction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Upsert => "upsert",
+            Self::Delete => "delete",
+        }
+    }
+}
+
+/// Record that a track's search document needs updating or removing
+///
+/// Run this on the transaction that writes the track, so the event commits
+/// or rolls back with the change it describes.
+pub async fn queue_track_index_update(
+    conn: &mut PgConnection,
+    track_id: Uuid,
+    action: IndexAction,
+) -> WorkerResult<()> {
+    sqlx::query("INSERT INTO search_index_outbox (track_id, action) VALUES ($1, $2)")
+        .bind(track_id)
+        .bind(action.as_str())
+        .execute(conn)
+        .await?;
+    Ok(())
+}
+
+/// Apply the oldest pending search index updates from the outbox
+///
+/// Events are claimed and deleted in one transaction that only commits once
+/// Meilisearch has accepted the changes, so a failed update is retried on the
+/// next poll. Returns the number of events applied.
+pub async fn process_index_outbox(state: &AppState) -> WorkerResult<usize> {
+    let mut tx = state.db.begin().await?;
+
+    let events: Vec<(i64, Uuid, String)> = sqlx::query_as(
+        r#"
+        DELETE FROM search_index_outbox
+        WHERE id IN (
+            SELECT id FROM search_index_outbox
+            ORDER BY id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING id, track_id, action
+        "#,
+    )
+    .bind(OUTBOX_BATCH_SIZE)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    if events.is_empty() {
+        return Ok(0);
+    }
+
+    let (upserts, deletes) = collapse_outbox_events(events.clone());
+    let client = Client::new(
+        state.config.meilisearch_url(),
+        Some(state.config.meilisearch_key()),
+    );
+
+    index_tracks_by_id(&client, &state.db, &upserts).await?;
+    for track_id in &deletes {
+        delete_document(&client, indexes::TRACKS, track_id).await?;
+    }
+
+    tx.commit().await?;
+    debug!(
+        events = events.len(),
+        upserts = upserts.len(),
+        deletes = deletes.len(),
+        "Applied search index updates"
+    );
+    Ok(events.len())
+}
+
+/// Reduce outbox events to the tracks to re-index and the tracks to remove
+///
+/// Only a track's latest event counts: a track updated and then deleted is
+/// only removed.
+fn collapse_outbox_events(mut events: Vec<(i64, Uuid, String)>) -> (Vec<Uuid>, Vec<Uuid>) {
+    events.sort_by_key(|(id, _, _)| *id);
+
+    let mut latest: HashMap<Uuid, bool> = HashMap::new();
+    let mut order = Vec::new();
+    for (_, track_id, action) in events {
+        if latest
+            .insert(track_id, action == IndexAction::Delete.as_str())
+            .is_none()
+        {
+            order.push(track_id);
+        }
+    }
+
+    order.into_iter().partition(|track_id| !latest[track_id])
+}
+
 /// Wait for a Meilisearch task to complete
 async fn wait_for_task(client: &Client, task: TaskInfo) -> WorkerResult<()> {
     task.wait_for_completion(client, None, Some(Duration::from_secs(60)))
@@ -788,6 +905,24 @@ async fn wait_for_task(client: &Client, task: TaskInfo) -> WorkerResult<()> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_collapse_outbox_events_keeps_latest_action() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let events = vec![
+            (4, a, "delete".to_string()),
+            (1, a, "upsert".to_string()),
+            (2, b, "upsert".to_string()),
+            (3, b, "upsert".to_string()),
+            (5, c, "delete".to_string()),
+            (6, c, "upsert".to_string()),
+        ];
+
+        let (upserts, deletes) = collapse_outbox_events(events);
+
+        assert_eq!(upserts, vec![b, c]);
+        assert_eq!(deletes, vec![a]);
+    }
+
     #[test]
     fn test_batch_size_is_reasonable() {
         // Batch size should be positive and reasonable for large libraries
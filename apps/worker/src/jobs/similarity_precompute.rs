@@ -0,0 +1,458 @@
+//! Similarity precomputation job
+//!
+//! Stores each track's top combined-similarity neighbors in
+//! `track_similarities` so the API can serve similar-track lookups with a
+//! single indexed read. Scores mirror the API's combined method: semantic
+//! (description embedding cosine), acoustic (normalized audio feature
+//! distance) and categorical (weighted genre/mood/tag Jaccard), blended with
+//! the configured weights.
+//!
+//! Database triggers delete a track's rows when its features, tags or
+//! embeddings change, so by default the job only fills tracks without rows.
+//! Tracks are processed in id order and each track commits on its own.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::{WorkerError, WorkerResult};
+use crate::AppState;
+
+/// Maximum neighbors stored per track (matches the API's similarity limit)
+const MAX_NEIGHBORS: i32 = 100;
+
+/// Similarity precomputation job payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimilarityPrecomputeJob {
+    /// Number of neighbors stored per track
+    #[serde(default = "default_neighbors")]
+    pub neighbors: i32,
+
+    /// Number of source tracks loaded per batch
+    #[serde(default = "default_batch_size")]
+    pub batch_size: i64,
+
+    /// Recompute every track instead of only tracks without stored neighbors
+    #[serde(default)]
+    pub recompute_all: bool,
+
+    /// Weights for the semantic, acoustic and categorical scores
+    #[serde(default)]
+    pub weights: SimilarityWeights,
+}
+
+fn default_neighbors() -> i32 {
+    50
+}
+
+fn default_batch_size() -> i64 {
+    200
+}
+
+impl Default for SimilarityPrecomputeJob {
+    fn default() -> Self {
+        Self {
+            neighbors: default_neighbors(),
+            batch_size: default_batch_size(),
+            recompute_all: false,
+            weights: SimilarityWeights::default(),
+        }
+    }
+}
+
+/// Weights of the combined similarity score (defaults match the API)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimilarityWeights {
+    pub semantic: f64,
+    pub acoustic: f64,
+    pub categorical: f64,
+}
+
+impl Default for SimilarityWeights {
+    fn default() -> Self {
+        Self {
+            semantic: 0.5,
+            acoustic: 0.3,
+            categorical: 0.2,
+        }
+    }
+}
+
+/// Per-dimension scores for one neighbor
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct NeighborScores {
+    semantic: f64,
+    acoustic: f64,
+    categorical: f64,
+}
+
+impl NeighborScores {
+    fn combined(&self, weights: &SimilarityWeights) -> f64 {
+        self.semantic * weights.semantic
+            + self.acoustic * weights.acoustic
+            + self.categorical * weights.categorical
+    }
+}
+
+/// Neighbor id and score for one similarity dimension
+#[derive(Debug, sqlx::FromRow)]
+struct DimensionMatch {
+    track_id: Uuid,
+    score: Option<f64>,
+}
+
+/// Execute the similarity precomputation job
+pub async fn execute(state: &AppState, job: &SimilarityPrecomputeJob) -> WorkerResult<()> {
+    validate(job)?;
+
+    let mut after = Uuid::nil();
+    let (mut computed, mut failed) = (0usize, 0usize);
+
+    loop {
+        let track_ids: Vec<Uuid> = sqlx::query_scalar(
+            r#"
+            SELECT t.id
+            FROM tracks t
+            WHERE t.id > $1
+              AND ($2 OR NOT EXISTS (
+                  SELECT 1 FROM track_similarities ts WHERE ts.track_id = t.id
+              ))
+            ORDER BY t.id
+            LIMIT $3
+            "#,
+        )
+        .bind(after)
+        .bind(job.recompute_all)
+        .bind(job.batch_size)
+        .fetch_all(&state.db)
+        .await?;
+
+        let Some(&last) = track_ids.last() else {
+            break;
+        };
+        after = last;
+
+        for &track_id in &track_ids {
+            match precompute_track(&state.db, track_id, job).await {
+                Ok(_) => computed += 1,
+                Err(e) => {
+                    tracing::warn!(track_id = %track_id, error = %e, "Skipping similarity precomputation");
+                    failed += 1;
+                }
+            }
+        }
+
+        tracing::debug!(computed, failed, "Similarity precomputation batch stored");
+
+        if (track_ids.len() as i64) < job.batch_size {
+            break;
+        }
+    }
+
+    tracing::info!(computed, failed, "Similarity precomputation finished");
+
+    Ok(())
+}
+
+fn validate(job: &SimilarityPrecomputeJob) -> WorkerResult<()> {
+    if !(1..=MAX_NEIGHBORS).contains(&job.neighbors) {
+        return Err(WorkerError::InvalidPayload(format!(
+            "neighbors must be between 1 and {}, got {}",
+            MAX_NEIGHBORS, job.neighbors
+        )));
+    }
+    if job.batch_size < 1 {
+        return Err(WorkerError::InvalidPayload(format!(
+            "batch_size must be at least 1, got {}",
+            job.batch_size
+        )));
+    }
+    Ok(())
+}
+
+/// Compute and store one track's neighbors, replacing any stored rows
+///
+/// Returns the number of neighbors stored.
+async fn precompute_track(
+    db: &PgPool,
+    track_id: Uuid,
+    job: &SimilarityPrecomputeJob,
+) -> WorkerResult<usize> {
+    // Fetch extra candidates per dimension so the merge sees tracks that rank
+    // well overall without leading any single dimension
+    let fetch_limit = i64::from(job.neighbors) * 3;
+
+    let (semantic, acoustic, categorical) = tokio::join!(
+        semantic_matches(db, track_id, fetch_limit),
+        acoustic_matches(db, track_id, fetch_limit),
+        categorical_matches(db, track_id, fetch_limit),
+    );
+
+    let dimension = |name: &str, result: Result<Vec<DimensionMatch>, sqlx::Error>| {
+        result.unwrap_or_else(|e| {
+            tracing::warn!(track_id = %track_id, error = %e, "{} similarity lookup failed", name);
+            Vec::new()
+        })
+    };
+    let neighbors = merge_neighbors(
+        dimension("Semantic", semantic),
+        dimension("Acoustic", acoustic),
+        dimension("Categorical", categorical),
+        &job.weights,
+        job.neighbors as usize,
+    );
+
+    let mut ids = Vec::with_capacity(neighbors.len());
+    let (mut semantic, mut acoustic, mut categorical, mut scores) =
+        (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+    for (id, s) in &neighbors {
+        ids.push(*id);
+        semantic.push(s.semantic);
+        acoustic.push(s.acoustic);
+        categorical.push(s.categorical);
+        scores.push(s.combined(&job.weights));
+    }
+
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM track_similarities WHERE track_id = $1")
+        .bind(track_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query(
+        r#"
+        INSERT INTO track_similarities
+            (track_id, similar_track_id, semantic_score, acoustic_score, categorical_score, score)
+        SELECT $1, n.similar_track_id, n.semantic, n.acoustic, n.categorical, n.score
+        FROM UNNEST($2::uuid[], $3::float8[], $4::float8[], $5::float8[], $6::float8[])
+            AS n(similar_track_id, semantic, acoustic, categorical, score)
+        "#,
+    )
+    .bind(track_id)
+    .bind(&ids)
+    .bind(&semantic)
+    .bind(&acoustic)
+    .bind(&categorical)
+    .bind(&scores)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    Ok(neighbors.len())
+}
+
+/// Merge per-dimension matches into the top `limit` neighbors by combined score
+fn merge_neighbors(
+    semantic: Vec<DimensionMatch>,
+    acoustic: Vec<DimensionMatch>,
+    categorical: Vec<DimensionMatch>,
+    weights: &SimilarityWeights,
+    limit: usize,
+) -> Vec<(Uuid, NeighborScores)> {
+    let mut merged: HashMap<Uuid, NeighborScores> = HashMap::new();
+
+    let mut merge = |matches: Vec<DimensionMatch>, set: fn(&mut NeighborScores, f64)| {
+        for m in matches {
+            let score = m.score.unwrap_or(0.0).clamp(0.0, 1.0);
+            set(merged.entry(m.track_id).or_default(), score);
+        }
+    };
+    merge(semantic, |s, score| s.semantic = score);
+    merge(acoustic, |s, score| s.acoustic = score);
+    merge(categorical, |s, score| s.categorical = score);
+
+    let mut neighbors: Vec<(Uuid, NeighborScores)> = merged.into_iter().collect();
+    neighbors.sort_by(|a, b| {
+        b.1.combined(weights)
+            .partial_cmp(&a.1.combined(weights))
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.0.cmp(&b.0))
+    });
+    neighbors.truncate(limit);
+    neighbors
+}
+
+/// Nearest tracks by description embedding cosine similarity
+async fn semantic_matches(
+    db: &PgPool,
+    track_id: Uuid,
+    limit: i64,
+) -> Result<Vec<DimensionMatch>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT te.track_id,
+               1.0 - (te.description_embedding <=> source.description_embedding) AS score
+        FROM track_embeddings te
+        JOIN track_embeddings source ON source.track_id = $1
+        WHERE te.track_id != $1
+          AND te.description_embedding IS NOT NULL
+          AND source.description_embedding IS NOT NULL
+        ORDER BY te.description_embedding <=> source.description_embedding
+        LIMIT $2
+        "#,
+    )
+    .bind(track_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+/// Nearest tracks by normalized audio feature distance
+async fn acoustic_matches(
+    db: &PgPool,
+    track_id: Uuid,
+    limit: i64,
+) -> Result<Vec<DimensionMatch>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH source_track AS (
+            SELECT
+                (audio_features->>'energy')::float AS energy,
+                (audio_features->>'loudness')::float AS loudness,
+                (audio_features->>'valence')::float AS valence,
+                (audio_features->>'danceability')::float AS danceability,
+                (audio_features->>'bpm')::float AS bpm
+            FROM tracks
+            WHERE id = $1
+              AND audio_features->>'energy' IS NOT NULL
+        ),
+        track_distances AS (
+            SELECT
+                t.id AS track_id,
+                -- Distance over the dimensions both tracks have, scaled to the
+                -- full five so tracks missing disabled analyzers aren't favored
+                (
+                    SELECT SQRT(AVG(d) * 5)
+                    FROM (VALUES
+                        (POWER((t.audio_features->>'energy')::float - src.energy, 2)),
+                        (POWER(((t.audio_features->>'loudness')::float + 60) / 60 - (src.loudness + 60) / 60, 2)),
+                        (POWER((t.audio_features->>'valence')::float - src.valence, 2)),
+                        (POWER((t.audio_features->>'danceability')::float - src.danceability, 2)),
+                        (POWER(((t.audio_features->>'bpm')::float - src.bpm) / 200, 2))
+                    ) AS dims(d)
+                ) AS distance
+            FROM tracks t
+            CROSS JOIN source_track src
+            WHERE t.id != $1
+              AND t.audio_features->>'energy' IS NOT NULL
+        )
+        SELECT track_id, GREATEST(0, 1.0 - (distance / 2.0)) AS score
+        FROM track_distances
+        ORDER BY distance ASC
+        LIMIT $2
+        "#,
+    )
+    .bind(track_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+/// Tracks sharing genres, moods or tags, by weighted Jaccard similarity
+async fn categorical_matches(
+    db: &PgPool,
+    track_id: Uuid,
+    limit: i64,
+) -> Result<Vec<DimensionMatch>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        WITH source_track AS (
+            SELECT genres, ai_mood, ai_tags
+            FROM tracks
+            WHERE id = $1
+        )
+        SELECT
+            t.id AS track_id,
+            (
+                COALESCE((SELECT COUNT(*) FROM UNNEST(t.genres) g WHERE g = ANY(src.genres)), 0) +
+                COALESCE((SELECT COUNT(*) FROM UNNEST(t.ai_mood) m WHERE m = ANY(src.ai_mood)), 0) * 2 +
+                COALESCE((SELECT COUNT(*) FROM UNNEST(t.ai_tags) tg WHERE tg = ANY(src.ai_tags)), 0)
+            )::float / GREATEST(1,
+                COALESCE((SELECT COUNT(*) FROM (SELECT UNNEST(t.genres) UNION SELECT UNNEST(src.genres)) u), 0) +
+                COALESCE((SELECT COUNT(*) FROM (SELECT UNNEST(t.ai_mood) UNION SELECT UNNEST(src.ai_mood)) u), 0) * 2 +
+                COALESCE((SELECT COUNT(*) FROM (SELECT UNNEST(t.ai_tags) UNION SELECT UNNEST(src.ai_tags)) u), 0)
+            ) AS score
+        FROM tracks t
+        CROSS JOIN source_track src
+        WHERE t.id != $1
+          AND (
+              t.genres && src.genres OR
+              t.ai_mood && src.ai_mood OR
+              t.ai_tags && src.ai_tags
+          )
+        ORDER BY score DESC
+        LIMIT $2
+        "#,
+    )
+    .bind(track_id)
+    .bind(limit)
+    .fetch_all(db)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched(track_id: Uuid, score: f64) -> DimensionMatch {
+        DimensionMatch {
+            track_id,
+            score: Some(score),
+        }
+    }
+
+    #[test]
+    fn test_merge_neighbors_combines_dimensions() {
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let weights = SimilarityWeights::default();
+
+        let neighbors = merge_neighbors(
+            vec![matched(a, 0.9), matched(b, 0.2)],
+            vec![matched(a, 0.5), matched(c, 1.0)],
+            vec![matched(b, 1.0)],
+            &weights,
+            10,
+        );
+
+        assert_eq!(neighbors.len(), 3);
+        assert_eq!(neighbors[0].0, a);
+        let scores = neighbors[0].1;
+        assert_eq!(scores.semantic, 0.9);
+        assert_eq!(scores.acoustic, 0.5);
+        assert_eq!(scores.categorical, 0.0);
+        assert!((scores.combined(&weights) - 0.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merge_neighbors_respects_limit_and_clamps() {
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let semantic = ids.iter().map(|&id| matched(id, 1.4)).collect();
+
+        let neighbors = merge_neighbors(
+            semantic,
+            Vec::new(),
+            Vec::new(),
+            &SimilarityWeights::default(),
+            2,
+        );
+
+        assert_eq!(neighbors.len(), 2);
+        assert!(neighbors.iter().all(|(_, s)| s.semantic == 1.0));
+    }
+
+    #[test]
+    fn test_payload_defaults_and_validation() {
+        let job: SimilarityPrecomputeJob = serde_json::from_str("{}").unwrap();
+        assert_eq!(job.neighbors, 50);
+        assert!(!job.recompute_all);
+        assert_eq!(job.weights, SimilarityWeights::default());
+        assert!(validate(&job).is_ok());
+
+        let too_many = SimilarityPrecomputeJob {
+            neighbors: MAX_NEIGHBORS + 1,
+            ..Default::default()
+        };
+        assert!(validate(&too_many).is_err());
+    }
+}
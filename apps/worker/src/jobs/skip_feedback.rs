@@ -0,0 +1,17 @@
+//! Down-weighting of tracks a user keeps skipping
+//!
+//! The API records quick skips (a track abandoned within its first 30
+//! seconds) in `track_skips`. Recommendation queries multiply a candidate's
+//! score by `QUICK_SKIP_DECAY ^ min(quick skips, MAX_PENALIZED_SKIPS)`,
+//! counting only the user's quick skips from the last `SKIP_HISTORY_DAYS`, so
+//! a track skipped once drops a little and one skipped repeatedly sinks
+//! without ever being banned outright.
+
+/// How far back quick skips count against a track (days)
+pub const SKIP_HISTORY_DAYS: i32 = 90;
+
+/// Score multiplier applied per quick skip
+pub const QUICK_SKIP_DECAY: f64 = 0.7;
+
+/// Quick skips beyond this many don't lower the score further
+pub const MAX_PENALIZED_SKIPS: i32 = 5;
@@ -309,6 +309,15 @@ pub struct SpectralFeatures {
     pub hf_energy_ratio: f32,
     /// Energy in vocal frequency band (300-3000 Hz)
     pub vocal_band_energy: f32,
+    /// Ratio of peak amplitude to RMS (1.0 for a square wave, higher for
+    /// more dynamic material; 0.0 for silence)
+    pub crest_factor: f32,
+    /// Spread of per-frame loudness in dB between the loud (95th
+    /// percentile) and quiet (10th percentile) frames, ignoring silence
+    pub short_term_dynamic_range: f32,
+    /// RMS level normalized to 0-1 (RMS × 3, capped), on the same scale as
+    /// the stored `energy` feature
+    pub energy: f32,
 }
 
 /// Analyze spectral features of audio samples
@@ -323,10 +332,15 @@ pub fn analyze_spectral_features(samples: &[f32], sample_rate: u32) -> SpectralF
     let frame_size = analyzer.frame_size();
     let hop_size = analyzer.hop_size();
 
+    let crest_factor = crest_factor(samples);
+    let energy = normalized_energy(samples);
+
     if samples.len() < frame_size {
         // Not enough samples for even one frame
         return SpectralFeatures {
             zcr_mean: zero_crossing_rate(samples),
+            crest_factor,
+            energy,
             ..Default::default()
         };
     }
@@ -340,6 +354,7 @@ pub fn analyze_spectral_features(samples: &[f32], sample_rate: u32) -> SpectralF
     let mut hf_energies: Vec<f32> = Vec::new();
     let mut total_energies: Vec<f32> = Vec::new();
     let mut vocal_energies: Vec<f32> = Vec::new();
+    let mut frame_levels_db: Vec<f32> = Vec::new();
 
     let mut prev_spectrum: Option<Vec<f32>> = None;
     let mut frame_start = 0;
@@ -358,6 +373,11 @@ pub fn analyze_spectral_features(samples: &[f32], sample_rate: u32) -> SpectralF
         // Zero crossing rate for this frame
         zcrs.push(zero_crossing_rate(frame));
 
+        // Time-domain loudness of this frame, skipping silence
+        if let Some(level) = frame_level_db(frame) {
+            frame_levels_db.push(level);
+        }
+
         // Spectral flux (if we have a previous spectrum)
         if let Some(ref prev) = prev_spectrum {
             fluxes.push(analyzer.spectral_flux(prev, &spectrum));
@@ -413,7 +433,159 @@ pub fn analyze_spectral_features(samples: &[f32], sample_rate: u32) -> SpectralF
         spectral_flux_mean,
         hf_energy_ratio,
         vocal_band_energy,
+        crest_factor,
+        short_term_dynamic_range: short_term_dynamic_range(&mut frame_levels_db),
+        energy,
+    }
+}
+
+/// RMS level normalized to 0-1: typical music RMS is 0.1-0.3, so RMS is
+/// scaled by 3 and capped
+fn normalized_energy(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let sum_squared: f64 = samples.iter().map(|&s| f64::from(s) * f64::from(s)).sum();
+    let rms = (sum_squared / samples.len() as f64).sqrt() as f32;
+    (rms * 3.0).min(1.0)
+}
+
+/// Frames quieter than this are treated as silence for dynamic range
+const SILENCE_FLOOR_DB: f32 = -70.0;
+
+/// Peak amplitude divided by RMS amplitude
+///
+/// Heavily limited masters sit close to 1.0 (a square wave is exactly 1.0,
+/// a sine wave ~1.41); dynamic recordings have much higher values. Returns
+/// 0.0 for empty or silent input.
+pub fn crest_factor(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let mut peak = 0.0f32;
+    let mut sum_squared = 0.0f64;
+    for &sample in samples {
+        peak = peak.max(sample.abs());
+        sum_squared += (sample as f64) * (sample as f64);
+    }
+
+    let rms = (sum_squared / samples.len() as f64).sqrt() as f32;
+    if rms > f32::EPSILON {
+        peak / rms
+    } else {
+        0.0
+    }
+}
+
+/// RMS level of a frame in dBFS, or `None` if it is below the silence floor
+fn frame_level_db(frame: &[f32]) -> Option<f32> {
+    if frame.is_empty() {
+        return None;
+    }
+    let mean_square =
+        frame.iter().map(|&s| (s as f64) * (s as f64)).sum::<f64>() / frame.len() as f64;
+    let level = 10.0 * mean_square.log10() as f32;
+    (level.is_finite() && level > SILENCE_FLOOR_DB).then_some(level)
+}
+
+/// Difference in dB between the 95th and 10th percentile frame levels
+///
+/// Returns 0.0 when fewer than two frames are above the silence floor.
+fn short_term_dynamic_range(levels_db: &mut [f32]) -> f32 {
+    if levels_db.len() < 2 {
+        return 0.0;
+    }
+    levels_db.sort_by(|a, b| a.total_cmp(b));
+    let percentile = |p: f32| levels_db[((levels_db.len() - 1) as f32 * p).round() as usize];
+    percentile(0.95) - percentile(0.10)
+}
+
+/// Number of MFCCs kept per frame (the conventional 13 for timbre)
+pub const DEFAULT_MFCC_COEFFICIENTS: usize = 13;
+
+/// Number of triangular mel filters the spectrum is pooled into
+const MEL_FILTERS: usize = 26;
+
+fn hz_to_mel(hz: f32) -> f32 {
+    2595.0 * (1.0 + hz / 700.0).log10()
+}
+
+fn mel_to_hz(mel: f32) -> f32 {
+    700.0 * (10f32.powf(mel / 2595.0) - 1.0)
+}
+
+/// Mean mel-frequency cepstral coefficients over all frames
+///
+/// A compact timbre fingerprint: each frame's power spectrum is pooled into
+/// [`MEL_FILTERS`] mel bands, log-compressed and decorrelated with a DCT-II,
+/// keeping the first `num_coefficients`. Returns an empty vector when there
+/// is not enough audio for one frame.
+pub fn compute_mfcc_means(samples: &[f32], sample_rate: u32, num_coefficients: usize) -> Vec<f32> {
+    let mut analyzer = SpectralAnalyzer::new(sample_rate);
+    let frame_size = analyzer.frame_size();
+    let hop_size = analyzer.hop_size();
+    if samples.len() < frame_size || num_coefficients == 0 {
+        return Vec::new();
+    }
+
+    // Filter edges evenly spaced on the mel scale, as spectrum bin positions
+    let bins = frame_size / 2 + 1;
+    let max_mel = hz_to_mel(sample_rate as f32 / 2.0);
+    let edges: Vec<f32> = (0..MEL_FILTERS + 2)
+        .map(|i| {
+            let hz = mel_to_hz(max_mel * i as f32 / (MEL_FILTERS + 1) as f32);
+            hz * frame_size as f32 / sample_rate as f32
+        })
+        .collect();
+
+    let num_coefficients = num_coefficients.min(MEL_FILTERS);
+    let mut sums = vec![0.0f64; num_coefficients];
+    let mut frames = 0usize;
+    let mut log_energies = [0.0f32; MEL_FILTERS];
+
+    let mut frame_start = 0;
+    while frame_start + frame_size <= samples.len() {
+        let spectrum = analyzer.compute_spectrum(&samples[frame_start..frame_start + frame_size]);
+
+        for (m, log_energy) in log_energies.iter_mut().enumerate() {
+            let (left, center, right) = (edges[m], edges[m + 1], edges[m + 2]);
+            let energy: f32 = spectrum
+                .iter()
+                .take(bins)
+                .enumerate()
+                .map(|(bin, &magnitude)| {
+                    let bin = bin as f32;
+                    let weight = if bin > left && bin <= center {
+                        (bin - left) / (center - left)
+                    } else if bin > center && bin < right {
+                        (right - bin) / (right - center)
+                    } else {
+                        0.0
+                    };
+                    weight * magnitude * magnitude
+                })
+                .sum();
+            *log_energy = (energy + 1e-10).ln();
+        }
+
+        for (k, sum) in sums.iter_mut().enumerate() {
+            let coefficient: f32 = log_energies
+                .iter()
+                .enumerate()
+                .map(|(m, &e)| {
+                    e * (std::f32::consts::PI * k as f32 * (m as f32 + 0.5) / MEL_FILTERS as f32)
+                        .cos()
+                })
+                .sum();
+            *sum += coefficient as f64;
+        }
+
+        frames += 1;
+        frame_start += hop_size;
     }
+
+    sums.iter().map(|&s| (s / frames as f64) as f32).collect()
 }
 
 /// Calculate mean of a slice
@@ -1497,6 +1669,21 @@ mod tests {
         assert!(features.zcr_mean > 0.0);
     }
 
+    #[test]
+    fn test_analyze_spectral_features_energy() {
+        // A quiet sine: RMS = 0.1 / sqrt(2), scaled by 3
+        let quiet: Vec<f32> = generate_sine(440.0, 44100, 44100)
+            .iter()
+            .map(|s| s * 0.1)
+            .collect();
+        let features = analyze_spectral_features(&quiet, 44100);
+        assert!((features.energy - 0.3 / 2f32.sqrt()).abs() < 1e-3);
+
+        // Full scale caps at 1.0
+        let loud = generate_sine(440.0, 44100, 44100);
+        assert_eq!(analyze_spectral_features(&loud, 44100).energy, 1.0);
+    }
+
     #[test]
     fn test_band_energy() {
         let sample_rate = 44100u32;
@@ -2212,4 +2399,94 @@ mod tests {
         let analyzer_large = SpectralAnalyzer::with_params(sample_rate, large_frame, 1024);
         assert_eq!(analyzer_large.frame_size(), large_frame);
     }
+
+    #[test]
+    fn test_mfcc_means() {
+        let sample_rate = 44100;
+        let tone = generate_sine(440.0, sample_rate, sample_rate as usize);
+        let noise = generate_noise(sample_rate as usize, 7);
+
+        let tone_mfcc = compute_mfcc_means(&tone, sample_rate, DEFAULT_MFCC_COEFFICIENTS);
+        let noise_mfcc = compute_mfcc_means(&noise, sample_rate, DEFAULT_MFCC_COEFFICIENTS);
+
+        assert_eq!(tone_mfcc.len(), DEFAULT_MFCC_COEFFICIENTS);
+        assert!(tone_mfcc.iter().all(|c| c.is_finite()));
+        // A pure tone and broadband noise have clearly different timbre
+        let distance: f32 = tone_mfcc
+            .iter()
+            .zip(&noise_mfcc)
+            .map(|(a, b)| (a - b).powi(2))
+            .sum::<f32>()
+            .sqrt();
+        assert!(distance > 1.0, "MFCC distance too small: {}", distance);
+
+        assert!(compute_mfcc_means(&tone[..100], sample_rate, 13).is_empty());
+    }
+
+    #[test]
+    fn test_crest_factor_compressed_vs_dynamic() {
+        let sample_rate = 44100;
+        let n = sample_rate as usize * 4;
+
+        // Hard-clipped sine: loud, flat and nearly square
+        let compressed: Vec<f32> = generate_sine(220.0, sample_rate, n)
+            .iter()
+            .map(|s| (s * 10.0).clamp(-0.9, 0.9))
+            .collect();
+
+        // Same tone alternating between loud and quiet half-second sections
+        let section = sample_rate as usize / 2;
+        let dynamic: Vec<f32> = generate_sine(220.0, sample_rate, n)
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                if (i / section).is_multiple_of(2) {
+                    s * 0.9
+                } else {
+                    s * 0.05
+                }
+            })
+            .collect();
+
+        let compressed_features = analyze_spectral_features(&compressed, sample_rate);
+        let dynamic_features = analyze_spectral_features(&dynamic, sample_rate);
+
+        assert!(
+            compressed_features.crest_factor < 1.2,
+            "Clipped sine crest factor should be near 1, got {}",
+            compressed_features.crest_factor
+        );
+        assert!(
+            dynamic_features.crest_factor > compressed_features.crest_factor + 0.5,
+            "Dynamic crest {} should exceed compressed crest {}",
+            dynamic_features.crest_factor,
+            compressed_features.crest_factor
+        );
+
+        assert!(
+            compressed_features.short_term_dynamic_range < 1.0,
+            "Constant-level signal should have ~0 dB short-term range, got {}",
+            compressed_features.short_term_dynamic_range
+        );
+        // 0.9 vs 0.05 amplitude is ~25 dB
+        assert!(
+            (dynamic_features.short_term_dynamic_range - 25.1).abs() < 2.0,
+            "Expected ~25 dB short-term range, got {}",
+            dynamic_features.short_term_dynamic_range
+        );
+    }
+
+    #[test]
+    fn test_crest_factor_silence_is_zero() {
+        let silence = vec![0.0f32; 44100];
+
+        let features = analyze_spectral_features(&silence, 44100);
+
+        assert_eq!(features.crest_factor, 0.0);
+        assert_eq!(features.short_term_dynamic_range, 0.0);
+        assert_eq!(crest_factor(&[]), 0.0);
+        // A sine wave's crest factor is sqrt(2)
+        let sine = generate_sine(440.0, 44100, 44100);
+        assert!((crest_factor(&sine) - std::f32::consts::SQRT_2).abs() < 0.01);
+    }
 }
@@ -18,6 +18,7 @@ use crate::error::{WorkerError, WorkerResult};
 use crate::jobs::clustering::{
     cluster_user_taste_with_metadata, TasteCluster, TrackClusterMetadata,
 };
+use crate::jobs::skip_feedback::{MAX_PENALIZED_SKIPS, QUICK_SKIP_DECAY, SKIP_HISTORY_DAYS};
 use crate::AppState;
 
 // =============================================================================
@@ -305,7 +306,8 @@ async fn get_seed_tracks(state: &AppState, user_id: Uuid) -> WorkerResult<Vec<Uu
 /// Find tracks similar to seed tracks using pgvector embeddings
 ///
 /// Uses the average embedding of all seed tracks to find similar tracks,
-/// filtering out recently played tracks and the seeds themselves.
+/// filtering out recently played tracks and the seeds themselves. Tracks the
+/// user keeps quick skipping are down-weighted (see `skip_feedback`).
 ///
 /// NOTE: This uses only description_embedding for similarity. The cluster-based
 /// playlists (via find_similar_tracks_for_cluster) use the full combined similarity
@@ -340,14 +342,25 @@ async fn find_similar_tracks_for_seeds(
             FROM listening_history
             WHERE user_id = $2
               AND played_at > NOW() - make_interval(days => $3)
+        ),
+        quick_skips AS (
+            SELECT track_id, COUNT(*) AS skips
+            FROM track_skips
+            WHERE user_id = $2
+              AND quick
+              AND skipped_at > NOW() - make_interval(days => $5)
+            GROUP BY track_id
         )
         SELECT te.track_id as id
         FROM track_embeddings te
         CROSS JOIN seed_embeddings se
+        LEFT JOIN quick_skips qs ON qs.track_id = te.track_id
         WHERE te.track_id != ALL($1)
           AND NOT EXISTS (SELECT 1 FROM recently_played rp WHERE rp.track_id = te.track_id)
           AND te.description_embedding IS NOT NULL
-        ORDER BY te.description_embedding <=> se.avg_embedding
+        -- Cosine similarity, down-weighted for tracks the user keeps skipping
+        ORDER BY GREATEST(0.0, 1.0 - (te.description_embedding <=> se.avg_embedding))
+            * POWER($6, LEAST(COALESCE(qs.skips, 0), $7)::float) DESC
         LIMIT $4
         "#,
     )
@@ -355,6 +368,9 @@ async fn find_similar_tracks_for_seeds(
     .bind(user_id)
     .bind(RECENTLY_PLAYED_DAYS)
     .bind(limit as i64)
+    .bind(SKIP_HISTORY_DAYS)
+    .bind(QUICK_SKIP_DECAY)
+    .bind(MAX_PENALIZED_SKIPS)
     .fetch_all(&state.db)
     .await?;
 
@@ -628,7 +644,7 @@ async fn get_listening_history_embeddings(
 }
 
 /// Parse a pgvector string representation "[0.1, 0.2, ...]" into Vec<f32>
-fn parse_pgvector_string(s: &str) -> WorkerResult<Vec<f32>> {
+pub(crate) fn parse_pgvector_string(s: &str) -> WorkerResult<Vec<f32>> {
     // Remove brackets and split by comma
     let trimmed = s.trim();
     let inner = trimmed
@@ -7,20 +7,22 @@
 //! - Weekly playlist generation
 //! - Lidarr integration sync
 //! - Smart prefetch for autoplay
+//! - Artist enrichment from Last.fm
 
 use std::sync::Arc;
 
 use anyhow::Result;
+use resonance_lastfm_client::LastfmClient;
 use resonance_ollama_client::OllamaClient;
 use sqlx::postgres::PgPoolOptions;
 use tokio::signal;
-use tokio::sync::broadcast;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tokio::sync::{broadcast, Notify};
 use url::Url;
 
 mod config;
 mod error;
 mod jobs;
+mod migrations;
 
 use config::Config;
 use jobs::JobRunner;
@@ -42,24 +44,30 @@ pub struct AppState {
     /// Ollama AI client for embeddings and generation (optional - AI jobs fail if unavailable)
     pub ollama: Option<OllamaClient>,
 
+    /// Last.fm client for artist enrichment (optional - requires LASTFM_API_KEY)
+    pub lastfm: Option<LastfmClient>,
+
     /// Application configuration
     pub config: Config,
+
+    /// Whether the database has pgvector; embedding jobs are skipped without it
+    pub pgvector: bool,
+
+    /// Wakes the job runner early when a job is enqueued
+    pub job_enqueued: Arc<Notify>,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| "resonance_worker=debug,sqlx=warn".into()),
-        )
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
     // Load environment variables
     dotenvy::dotenv().ok();
 
+    // Initialize tracing
+    resonance_shared_config::init_tracing(
+        "resonance_worker=debug,sqlx=warn",
+        resonance_shared_config::LogFormat::from_env(),
+    );
+
     tracing::info!("Starting Resonance worker");
 
     // Load configuration
@@ -70,7 +78,11 @@ async fn main() -> Result<()> {
         redact_url_password(config.database_url())
     );
     tracing::debug!("Redis URL: {}", redact_url_password(config.redis_url()));
-    tracing::debug!("Music library path: {:?}", config.music_library_path());
+    tracing::debug!("Music library roots: {:?}", config.music_roots());
+    tracing::debug!("Feature flags: {:?}", config.features());
+
+    // Fail fast if no music library root is readable
+    config.common.validate_music_roots()?;
 
     // Initialize database connection pool
     let db = PgPoolOptions::new()
@@ -79,6 +91,18 @@ async fn main() -> Result<()> {
         .await?;
     tracing::info!("Connected to PostgreSQL");
 
+    // Migrate in case the worker starts before the API
+    tracing::info!("Running database migrations...");
+    let pgvector = migrations::run(&db).await?;
+    if pgvector {
+        tracing::info!("Migrations completed successfully");
+    } else {
+        tracing::warn!(
+            "Migrations completed; the pgvector extension is not available, so embedding, \
+             similarity and playlist jobs will be skipped"
+        );
+    }
+
     // Initialize Redis client
     let redis = redis::Client::open(config.redis_url())?;
     // Test Redis connection
@@ -113,13 +137,31 @@ async fn main() -> Result<()> {
         }
     };
 
+    // Initialize Last.fm client for artist enrichment (optional)
+    let lastfm = match LastfmClient::from_env() {
+        Ok(client) => {
+            tracing::info!("Initialized Last.fm client");
+            Some(client)
+        }
+        Err(e) => {
+            tracing::info!(
+                error = %e,
+                "Last.fm client not configured - artist enrichment disabled. Set LASTFM_API_KEY to enable."
+            );
+            None
+        }
+    };
+
     // Create application state
     let state = Arc::new(AppState {
         db,
         redis,
         http_client,
         ollama,
+        lastfm,
         config: config.clone(),
+        pgvector,
+        job_enqueued: Arc::new(Notify::new()),
     });
 
     // Create shutdown signal channel
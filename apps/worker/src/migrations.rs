@@ -0,0 +1,96 @@
+//! Database migrations
+//!
+//! The worker shares the API's migrations and applies them with the same
+//! runner, [`resonance_shared_config::run_migrations`]. See the API's
+//! `migrations` module for how pgvector is made optional.
+
+use resonance_shared_config::MigrationSet;
+use sqlx::migrate::{MigrateError, Migrator};
+use sqlx::PgPool;
+
+/// Apply the core migrations, then the pgvector migrations if possible
+///
+/// Returns whether pgvector is installed afterwards.
+pub async fn run(pool: &PgPool) -> Result<bool, MigrateError> {
+    let set = MigrationSet {
+        core: core(),
+        without_pgvector: without_pgvector(),
+        pgvector: pgvector(),
+    };
+    resonance_shared_config::run_migrations(pool, &set).await
+}
+
+fn core() -> Migrator {
+    let mut migrator = sqlx::migrate!("../api/migrations");
+    migrator.set_ignore_missing(true);
+    migrator
+}
+
+fn without_pgvector() -> Migrator {
+    sqlx::migrate!("../api/migrations/without-pgvector")
+}
+
+fn pgvector() -> Migrator {
+    let mut migrator = sqlx::migrate!("../api/migrations/pgvector");
+    migrator.set_ignore_missing(true);
+    migrator
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrators_take_advisory_lock() {
+        // The API and worker migrate concurrently on startup and rely on the
+        // migrator's advisory lock to take turns
+        assert!(core().locking);
+        assert!(pgvector().locking);
+    }
+
+    #[test]
+    fn test_migration_sets_are_disjoint() {
+        // Both sets record into the same _sqlx_migrations table
+        let core = core();
+        let pgvector = pgvector();
+        assert!(!pgvector.migrations.is_empty());
+        for migration in pgvector.iter() {
+            assert!(
+                core.iter().all(|m| m.version != migration.version),
+                "version {} is in both migration sets",
+                migration.version
+            );
+        }
+    }
+
+    #[test]
+    fn test_variants_replace_core_migrations() {
+        let core = core();
+        for variant in without_pgvector().iter() {
+            assert!(
+                core.version_exists(variant.version),
+                "variant {} has no core migration to replace",
+                variant.version
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_core_migrations_with_variants_do_not_need_pgvector() {
+        let set = MigrationSet {
+            core: core(),
+            without_pgvector: without_pgvector(),
+            pgvector: pgvector(),
+        };
+        for migration in set.core_without_pgvector().await.unwrap().iter() {
+            let sql = migration.sql.to_lowercase();
+            assert!(
+                !sql.contains(" vector(")
+                    && !sql.contains("\"vector\"")
+                    && !sql.contains("using hnsw"),
+                "migration {} needs pgvector; add a variant to migrations/without-pgvector",
+                migration.version
+            );
+        }
+    }
+}
@@ -8,7 +8,9 @@
 
 mod common;
 
-use resonance_ollama_client::{validate_embedding_dimension, EMBEDDING_DIMENSION};
+use resonance_ollama_client::{
+    check_embedding_dimension, validate_embedding_dimension, OllamaError, EMBEDDING_DIMENSION,
+};
 use serde_json::json;
 use wiremock::matchers::{method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
@@ -180,6 +182,22 @@ fn test_embedding_dimension_validation_wrong() {
     assert!(result.is_err());
 }
 
+/// Test dimension validation against a configured (non-default) dimension
+#[test]
+fn test_embedding_dimension_validation_configured() {
+    let embedding: Vec<f32> = (0..1024).map(|i| i as f32 * 0.001).collect();
+    assert!(check_embedding_dimension(&embedding, 1024).is_ok());
+
+    let result = check_embedding_dimension(&embedding, EMBEDDING_DIMENSION);
+    assert!(matches!(
+        result,
+        Err(OllamaError::DimensionMismatch {
+            expected: EMBEDDING_DIMENSION,
+            actual: 1024
+        })
+    ));
+}
+
 /// Test timeout handling with delayed mock response
 #[tokio::test]
 async fn test_embedding_generation_timeout_handling() {
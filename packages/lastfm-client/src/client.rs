@@ -2,14 +2,18 @@
 
 use std::fmt;
 use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
 
 use reqwest::Client;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
 use tracing::{debug, instrument, warn};
 
 use crate::error::{LastfmError, LastfmResult};
 use crate::models::{
-    ArtistTag, ErrorResponse, SimilarArtist, SimilarArtistsResponse, TopTagsResponse,
+    ArtistInfo, ArtistInfoResponse, ArtistTag, ErrorResponse, SimilarArtist,
+    SimilarArtistsResponse, TopTagsResponse,
 };
 
 /// Last.fm API base URL
@@ -33,12 +37,49 @@ const DEFAULT_MAX_RETRIES: u32 = 3;
 /// Base delay for exponential backoff (milliseconds)
 const RETRY_BASE_DELAY_MS: u64 = 100;
 
+/// Default request rate (Last.fm asks clients to stay below 5 requests/second)
+const DEFAULT_REQUESTS_PER_SECOND: u32 = 5;
+
 /// Last.fm API client
 #[derive(Clone)]
 pub struct LastfmClient {
     http_client: Client,
     api_key: String,
+    base_url: String,
     max_retries: u32,
+    rate_limiter: RateLimiter,
+}
+
+/// Spaces requests evenly so the client never exceeds a fixed request rate
+///
+/// Shared between clones of the client, so all callers draw from one budget.
+#[derive(Clone)]
+struct RateLimiter {
+    min_interval: Duration,
+    next_slot: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            min_interval: Duration::from_secs(1) / requests_per_second.max(1),
+            next_slot: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Wait until the next request slot is available and claim it
+    async fn acquire(&self) {
+        let mut next_slot = self.next_slot.lock().await;
+        let now = Instant::now();
+        let slot = match *next_slot {
+            Some(slot) if slot > now => {
+                tokio::time::sleep_until(slot).await;
+                slot
+            }
+            _ => now,
+        };
+        *next_slot = Some(slot + self.min_interval);
+    }
 }
 
 /// API key validation status
@@ -56,7 +97,9 @@ impl fmt::Debug for LastfmClient {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("LastfmClient")
             .field("api_key", &"[REDACTED]")
+            .field("base_url", &self.base_url)
             .field("max_retries", &self.max_retries)
+            .field("min_request_interval", &self.rate_limiter.min_interval)
             .finish()
     }
 }
@@ -83,10 +126,26 @@ impl LastfmClient {
         Ok(Self {
             http_client,
             api_key,
+            base_url: LASTFM_API_URL.to_string(),
             max_retries: DEFAULT_MAX_RETRIES,
+            rate_limiter: RateLimiter::new(DEFAULT_REQUESTS_PER_SECOND),
         })
     }
 
+    /// Use a different API endpoint (e.g. a proxy or a mock server in tests)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Limit outgoing requests to at most `requests_per_second`
+    ///
+    /// Defaults to 5 requests per second. Retries count against the limit.
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = RateLimiter::new(requests_per_second);
+        self
+    }
+
     /// Create a Last.fm client from environment variable
     ///
     /// Reads `LASTFM_API_KEY` from the environment.
@@ -151,9 +210,11 @@ impl LastfmClient {
 
     /// Make an API request and handle common error cases
     async fn make_request(&self, params: &[(&str, &str)]) -> LastfmResult<String> {
+        self.rate_limiter.acquire().await;
+
         let response = self
             .http_client
-            .get(LASTFM_API_URL)
+            .get(&self.base_url)
             .query(params)
             .send()
             .await
@@ -296,6 +357,54 @@ impl LastfmClient {
         Ok(tags)
     }
 
+    /// Get artist details (biography, MusicBrainz ID, listener stats)
+    ///
+    /// # Arguments
+    /// * `artist_name` - The artist name to look up
+    ///
+    /// # Errors
+    /// - `LastfmError::InvalidInput` - If the artist name is empty or too long
+    /// - `LastfmError::ArtistNotFound` - If the artist is not found
+    /// - `LastfmError::Api` - If Last.fm returns an error
+    /// - `LastfmError::Http` - If the HTTP request fails
+    #[instrument(skip(self))]
+    pub async fn get_artist_info(&self, artist_name: &str) -> LastfmResult<ArtistInfo> {
+        let artist_name = Self::validate_artist_name(artist_name)?;
+
+        debug!(artist = %artist_name, "Fetching artist info from Last.fm");
+
+        let text = self
+            .with_retry(|| async {
+                self.make_request(&[
+                    ("method", "artist.getInfo"),
+                    ("artist", artist_name),
+                    ("api_key", &self.api_key),
+                    ("format", "json"),
+                    ("autocorrect", "1"),
+                ])
+                .await
+            })
+            .await?;
+
+        // Check for API error response
+        if let Some(error) = self.parse_api_error(&text, artist_name) {
+            return Err(error);
+        }
+
+        // Parse as success response
+        let response: ArtistInfoResponse = serde_json::from_str(&text)?;
+        let info: ArtistInfo = response.artist.into();
+
+        debug!(
+            artist = %artist_name,
+            has_bio = info.bio.is_some(),
+            has_mbid = info.mbid.is_some(),
+            "Found artist info"
+        );
+
+        Ok(info)
+    }
+
     /// Check if the API key is valid by making a simple request
     ///
     /// Returns `ApiKeyStatus` indicating whether the key is valid, invalid,
@@ -394,6 +503,101 @@ mod tests {
         assert!(artist.mbid.is_none());
     }
 
+    #[test]
+    fn test_artist_info_strips_read_more_link() {
+        use crate::models::RawArtistInfo;
+
+        let raw: RawArtistInfo = serde_json::from_str(
+            r#"{
+                "name": "Radiohead",
+                "mbid": "a74b1b7f-71a5-4011-9441-d0b5e4122711",
+                "url": "https://www.last.fm/music/Radiohead",
+                "stats": {"listeners": "1000", "playcount": "5000"},
+                "bio": {
+                    "summary": "Short bio",
+                    "content": "English rock band. <a href=\"https://www.last.fm/music/Radiohead\">Read more on Last.fm</a>"
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let info: ArtistInfo = raw.into();
+        assert_eq!(info.bio.as_deref(), Some("English rock band."));
+        assert_eq!(info.listeners, Some(1000));
+        assert_eq!(info.playcount, Some(5000));
+        assert!(info.mbid.is_some());
+    }
+
+    #[test]
+    fn test_artist_info_empty_bio_becomes_none() {
+        use crate::models::RawArtistInfo;
+
+        let raw: RawArtistInfo = serde_json::from_str(
+            r#"{
+                "name": "Unknown",
+                "mbid": "",
+                "bio": {"summary": " <a href=\"https://www.last.fm/music/Unknown\">Read more on Last.fm</a>", "content": ""}
+            }"#,
+        )
+        .unwrap();
+
+        let info: ArtistInfo = raw.into();
+        assert!(info.bio.is_none());
+        assert!(info.mbid.is_none());
+        assert!(info.url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_get_artist_info_from_mock_server() {
+        use wiremock::matchers::{method, query_param};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("method", "artist.getInfo"))
+            .and(query_param("artist", "Radiohead"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "artist": {
+                    "name": "Radiohead",
+                    "mbid": "a74b1b7f-71a5-4011-9441-d0b5e4122711",
+                    "bio": {"summary": "Band from Abingdon.", "content": "Band from Abingdon."}
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("artist", "Nobody"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "error": 6,
+                "message": "The artist you supplied could not be found"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = LastfmClient::new("test_api_key")
+            .unwrap()
+            .with_base_url(server.uri());
+
+        let info = client.get_artist_info("Radiohead").await.unwrap();
+        assert_eq!(info.name, "Radiohead");
+        assert_eq!(info.bio.as_deref(), Some("Band from Abingdon."));
+
+        let missing = client.get_artist_info("Nobody").await;
+        assert!(matches!(missing, Err(LastfmError::ArtistNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests() {
+        let limiter = RateLimiter::new(20);
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire().await;
+        }
+        // First request is immediate, the next two wait 50ms each
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
     #[test]
     fn test_error_is_retryable() {
         assert!(LastfmError::Timeout.is_retryable());
@@ -3,6 +3,10 @@
 //! This crate provides a client for the Last.fm API, enabling:
 //! - Similar artist discovery
 //! - Artist tag retrieval
+//! - Artist biography and MusicBrainz ID lookup
+//!
+//! Requests are rate limited (5 per second by default, see
+//! [`LastfmClient::with_rate_limit`]) and shared across clones of the client.
 //!
 //! # Example
 //!
@@ -37,4 +41,4 @@ mod models;
 
 pub use client::{ApiKeyStatus, LastfmClient};
 pub use error::{LastfmError, LastfmResult};
-pub use models::{ArtistTag, SimilarArtist};
+pub use models::{ArtistInfo, ArtistTag, SimilarArtist};
@@ -26,6 +26,23 @@ pub struct ArtistTag {
     pub url: Option<String>,
 }
 
+/// Artist details from Last.fm
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtistInfo {
+    /// Artist name (as corrected by Last.fm)
+    pub name: String,
+    /// MusicBrainz ID (if available)
+    pub mbid: Option<String>,
+    /// URL to Last.fm artist page
+    pub url: Option<String>,
+    /// Biography text, without the trailing "Read more on Last.fm" link
+    pub bio: Option<String>,
+    /// Number of unique listeners
+    pub listeners: Option<u64>,
+    /// Total number of plays
+    pub playcount: Option<u64>,
+}
+
 // Internal response types for deserialization
 
 #[derive(Debug, Deserialize)]
@@ -127,6 +144,76 @@ impl From<RawArtistTag> for ArtistTag {
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub(crate) struct ArtistInfoResponse {
+    pub artist: RawArtistInfo,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RawArtistInfo {
+    pub name: String,
+    #[serde(default)]
+    pub mbid: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub stats: Option<RawArtistStats>,
+    #[serde(default)]
+    pub bio: Option<RawArtistBio>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RawArtistStats {
+    pub listeners: Option<String>,
+    pub playcount: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct RawArtistBio {
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// Strip the "Read more on Last.fm" link Last.fm appends to every biography
+fn clean_bio(text: &str) -> Option<String> {
+    let text = match text.rfind("<a href=\"https://www.last.fm") {
+        Some(index) => &text[..index],
+        None => text,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+impl From<RawArtistInfo> for ArtistInfo {
+    fn from(raw: RawArtistInfo) -> Self {
+        // Prefer the full biography, falling back to the summary
+        let bio = raw.bio.and_then(|bio| {
+            bio.content
+                .as_deref()
+                .and_then(clean_bio)
+                .or_else(|| bio.summary.as_deref().and_then(clean_bio))
+        });
+        let (listeners, playcount) = match raw.stats {
+            Some(stats) => (
+                stats.listeners.and_then(|s| s.parse().ok()),
+                stats.playcount.and_then(|s| s.parse().ok()),
+            ),
+            None => (None, None),
+        };
+
+        Self {
+            name: raw.name,
+            mbid: raw.mbid.filter(|s| !s.is_empty()),
+            url: raw.url.filter(|s| !s.is_empty()),
+            bio,
+            listeners,
+            playcount,
+        }
+    }
+}
+
 /// Last.fm API error response
 #[derive(Debug, Deserialize)]
 pub(crate) struct ErrorResponse {
@@ -0,0 +1,307 @@
+//! AVIF decoding
+//!
+//! `image` only decodes AVIF through the system dav1d library, so AVIF cover
+//! art is decoded here instead: `avif-parse` extracts the AV1 payloads from
+//! the ISO-BMFF container and `re_rav1d`, a pure-Rust port of dav1d, decodes
+//! them. Nothing needs to be installed on the build or runtime host.
+//!
+//! Pixels are converted to 8-bit RGB(A) whatever the source bit depth, which
+//! is all palettes and thumbnails need. Chroma is upsampled by repeating
+//! samples rather than interpolating, for the same reason.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, RgbImage, RgbaImage};
+use re_rav1d::dav1d::pixel::{MatrixCoefficients, YUVRange};
+use re_rav1d::dav1d::{self, Decoder, Picture, PixelLayout, PlanarImageComponent, Settings};
+use thiserror::Error;
+
+/// Errors from decoding an AVIF image
+#[derive(Error, Debug)]
+pub(crate) enum AvifError {
+    /// The container is malformed or has no primary image
+    #[error("invalid AVIF container: {0:?}")]
+    Container(avif_parse::Error),
+
+    /// The AV1 bitstream could not be decoded
+    #[error("failed to decode AV1 image: {0}")]
+    Decode(#[from] dav1d::Error),
+
+    /// A top-level box runs past the end of the data
+    #[error("AVIF image is truncated")]
+    Truncated,
+
+    /// Alpha planes must be monochrome
+    #[error("AVIF alpha plane is not monochrome")]
+    AlphaLayout,
+}
+
+impl From<avif_parse::Error> for AvifError {
+    fn from(e: avif_parse::Error) -> Self {
+        Self::Container(e)
+    }
+}
+
+/// Read the image's dimensions from its AV1 sequence header without decoding
+pub(crate) fn dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let metadata = parse(bytes).ok()?.primary_item_metadata().ok()?;
+    Some((
+        metadata.max_frame_width.get(),
+        metadata.max_frame_height.get(),
+    ))
+}
+
+/// Decode an AVIF image to 8-bit RGB, or RGBA if it has an alpha channel
+pub(crate) fn decode(bytes: &[u8]) -> Result<DynamicImage, AvifError> {
+    let data = parse(bytes)?;
+
+    let color = decode_av1(data.primary_item.to_vec())?;
+    let alpha = match data.alpha_item {
+        Some(item) => Some(decode_av1(item.to_vec())?),
+        None => None,
+    };
+
+    let (width, height) = (color.width(), color.height());
+    let converter = YuvConverter::new(&color);
+
+    let Some(alpha) = alpha else {
+        return Ok(DynamicImage::ImageRgb8(RgbImage::from_fn(
+            width,
+            height,
+            |x, y| image::Rgb(converter.rgb(x, y)),
+        )));
+    };
+
+    if alpha.pixel_layout() != PixelLayout::I400 {
+        return Err(AvifError::AlphaLayout);
+    }
+    let alpha_plane = PlaneReader::new(&alpha, PlanarImageComponent::Y);
+    let alpha_range = SampleRange::new(alpha.bit_depth(), alpha.color_range());
+
+    Ok(DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        width,
+        height,
+        |x, y| {
+            let a = alpha_range
+                .luma(alpha_plane.sample(x.min(alpha.width() - 1), y.min(alpha.height() - 1)));
+            let mut rgb = converter.rgb_f32(x, y);
+            if data.premultiplied_alpha && a > 0.0 {
+                rgb = rgb.map(|c| c / a);
+            }
+            let [r, g, b] = rgb.map(to_u8);
+            image::Rgba([r, g, b, to_u8(a)])
+        },
+    )))
+}
+
+/// Parse the container, rejecting truncated data up front
+///
+/// `avif-parse` debug-asserts that every box is read to its end, so in debug
+/// builds a truncated file would panic instead of returning an error.
+fn parse(bytes: &[u8]) -> Result<avif_parse::AvifData, AvifError> {
+    if !boxes_complete(bytes) {
+        return Err(AvifError::Truncated);
+    }
+    Ok(avif_parse::read_avif(&mut Cursor::new(bytes))?)
+}
+
+/// Check that each top-level ISO-BMFF box fits within `bytes`
+fn boxes_complete(bytes: &[u8]) -> bool {
+    let mut rest = bytes;
+    while !rest.is_empty() {
+        let Some(header) = rest.get(..8) else {
+            return false;
+        };
+        let size = match u32::from_be_bytes([header[0], header[1], header[2], header[3]]) {
+            // The last box, running to the end of the file
+            0 => return true,
+            // 64-bit size after the box type
+            1 => match rest.get(8..16) {
+                Some(large) => u64::from_be_bytes(large.try_into().unwrap_or_default()),
+                None => return false,
+            },
+            size => u64::from(size),
+        };
+        match usize::try_from(size) {
+            Ok(size) if size >= 8 && size <= rest.len() => rest = &rest[size..],
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Decode a single AV1 frame
+fn decode_av1(obus: Vec<u8>) -> Result<Picture, dav1d::Error> {
+    let mut settings = Settings::new();
+    // Covers are small; threads would cost more than they save
+    settings.set_n_threads(1);
+    settings.set_max_frame_delay(1);
+
+    let mut decoder = Decoder::with_settings(&settings)?;
+    decoder.send_data(obus, None, None, None)?;
+
+    loop {
+        match decoder.get_picture() {
+            Err(dav1d::Error::Again) => match decoder.send_pending_data() {
+                Ok(()) | Err(dav1d::Error::Again) => {}
+                Err(e) => return Err(e),
+            },
+            result => return result,
+        }
+    }
+}
+
+/// Reads samples from one plane of a decoded picture
+struct PlaneReader {
+    data: dav1d::Plane,
+    stride: usize,
+    high_bit_depth: bool,
+}
+
+impl PlaneReader {
+    fn new(picture: &Picture, component: PlanarImageComponent) -> Self {
+        Self {
+            data: picture.plane(component),
+            stride: picture.stride(component) as usize,
+            high_bit_depth: picture.bit_depth() > 8,
+        }
+    }
+
+    /// The raw sample at `(x, y)` in plane coordinates
+    fn sample(&self, x: u32, y: u32) -> u16 {
+        let row = y as usize * self.stride;
+        if self.high_bit_depth {
+            let i = row + x as usize * 2;
+            u16::from_ne_bytes([self.data[i], self.data[i + 1]])
+        } else {
+            u16::from(self.data[row + x as usize])
+        }
+    }
+}
+
+/// Maps raw samples of a given bit depth and range to `0.0..=1.0` (luma) or
+/// `-0.5..=0.5` (chroma)
+#[derive(Clone, Copy)]
+struct SampleRange {
+    bit_depth: usize,
+    full: bool,
+}
+
+impl SampleRange {
+    fn new(bit_depth: usize, range: YUVRange) -> Self {
+        Self {
+            bit_depth,
+            full: matches!(range, YUVRange::Full),
+        }
+    }
+
+    fn luma(self, sample: u16) -> f32 {
+        let sample = f32::from(sample);
+        if self.full {
+            sample / ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            let scale = (1u32 << (self.bit_depth - 8)) as f32;
+            (sample - 16.0 * scale) / (219.0 * scale)
+        }
+    }
+
+    fn chroma(self, sample: u16) -> f32 {
+        let sample = f32::from(sample) - (1u32 << (self.bit_depth - 1)) as f32;
+        if self.full {
+            sample / ((1u32 << self.bit_depth) - 1) as f32
+        } else {
+            sample / (224.0 * (1u32 << (self.bit_depth - 8)) as f32)
+        }
+    }
+}
+
+/// Converts a decoded picture's YUV samples to RGB
+struct YuvConverter {
+    y: PlaneReader,
+    u: Option<PlaneReader>,
+    v: Option<PlaneReader>,
+    range: SampleRange,
+    /// Chroma subsampling shifts (horizontal, vertical)
+    shift: (u32, u32),
+    /// Luma weights of red and blue, or `None` for the identity matrix (GBR)
+    weights: Option<(f32, f32)>,
+}
+
+impl YuvConverter {
+    fn new(picture: &Picture) -> Self {
+        let layout = picture.pixel_layout();
+        let chroma = (layout != PixelLayout::I400).then(|| {
+            (
+                PlaneReader::new(picture, PlanarImageComponent::U),
+                PlaneReader::new(picture, PlanarImageComponent::V),
+            )
+        });
+        let (u, v) = chroma.unzip();
+
+        let shift = match layout {
+            PixelLayout::I420 => (1, 1),
+            PixelLayout::I422 => (1, 0),
+            PixelLayout::I400 | PixelLayout::I444 => (0, 0),
+        };
+
+        // Unspecified and unsupported matrices fall back to BT.601
+        let weights = match picture.matrix_coefficients() {
+            MatrixCoefficients::Identity if layout == PixelLayout::I444 => None,
+            MatrixCoefficients::BT709 => Some((0.2126, 0.0722)),
+            MatrixCoefficients::BT470M => Some((0.30, 0.11)),
+            MatrixCoefficients::ST240M => Some((0.212, 0.087)),
+            MatrixCoefficients::BT2020NonConstantLuminance
+            | MatrixCoefficients::BT2020ConstantLuminance => Some((0.2627, 0.0593)),
+            _ => Some((0.299, 0.114)),
+        };
+
+        Self {
+            y: PlaneReader::new(picture, PlanarImageComponent::Y),
+            u,
+            v,
+            range: SampleRange::new(picture.bit_depth(), picture.color_range()),
+            shift,
+            weights,
+        }
+    }
+
+    fn rgb(&self, x: u32, y: u32) -> [u8; 3] {
+        self.rgb_f32(x, y).map(to_u8)
+    }
+
+    /// The pixel at `(x, y)` as unclamped RGB in `0.0..=1.0`
+    fn rgb_f32(&self, x: u32, y: u32) -> [f32; 3] {
+        let (cx, cy) = (x >> self.shift.0, y >> self.shift.1);
+        let chroma = |plane: &Option<PlaneReader>| {
+            plane
+                .as_ref()
+                .map_or(0.0, |p| self.range.chroma(p.sample(cx, cy)))
+        };
+        let (luma, cb, cr) = (
+            self.range.luma(self.y.sample(x, y)),
+            chroma(&self.u),
+            chroma(&self.v),
+        );
+
+        match self.weights {
+            // Identity stores G, B and R in the Y, U and V planes
+            None => [
+                self.range
+                    .luma(self.v.as_ref().map_or(0, |p| p.sample(x, y))),
+                luma,
+                self.range
+                    .luma(self.u.as_ref().map_or(0, |p| p.sample(x, y))),
+            ],
+            Some((kr, kb)) => {
+                let r = luma + 2.0 * (1.0 - kr) * cr;
+                let b = luma + 2.0 * (1.0 - kb) * cb;
+                let g = (luma - kr * r - kb * b) / (1.0 - kr - kb);
+                [r, g, b]
+            }
+        }
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
@@ -0,0 +1,631 @@
+//! Cover art color palette extraction and resizing
+//!
+//! Extracts a color palette from album artwork for the visualizer and
+//! album-themed UI, and produces square thumbnails for the art route. The image is decoded, downsampled, and quantized with a
+//! median-cut algorithm; the resulting swatches are ranked by population to
+//! pick the dominant colors, and a readable text color is chosen by WCAG
+//! contrast against the primary color.
+//!
+//! The image format is detected from its magic bytes, never the file
+//! extension: JPEG, PNG, GIF, BMP, WebP and AVIF. Artwork in a recognized
+//! format that cannot be decoded falls back to a neutral placeholder palette
+//! and thumbnail.
+//!
+//! Extraction and resizing are CPU-bound, so async callers should run them on
+//! a blocking thread (e.g. `tokio::task::spawn_blocking`).
+
+use std::io::Cursor;
+
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, ImageReader, Rgb, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::avif;
+use crate::error::CoverArtError;
+
+/// Images are downsampled to fit within this size before quantization
+const SAMPLE_SIZE: u32 = 64;
+
+/// Number of median-cut boxes to quantize into
+const PALETTE_SIZE: usize = 8;
+
+/// Pixels with alpha below this value are ignored
+const MIN_ALPHA: u8 = 128;
+
+/// Minimum Euclidean RGB distance for swatches to count as distinct colors
+const MIN_COLOR_DISTANCE: f64 = 48.0;
+
+/// Thumbnail sizes (in pixels, square) that can be requested from the art route
+pub const ART_SIZES: [u32; 4] = [64, 128, 256, 512];
+
+/// JPEG quality for resized cover art
+const THUMBNAIL_QUALITY: u8 = 85;
+
+/// Neutral color used for artwork that cannot be decoded
+const PLACEHOLDER_RGB: [u8; 3] = [0x3a, 0x3a, 0x4a];
+
+/// ISO-BMFF brands identifying AVIF images and sequences
+const AVIF_BRANDS: [&[u8; 4]; 2] = [b"avif", b"avis"];
+
+/// Cover art color palette extracted for visualizer
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CoverArtColors {
+    /// Primary dominant color
+    pub primary: Option<String>,
+    /// Secondary color
+    pub secondary: Option<String>,
+    /// Accent color
+    pub accent: Option<String>,
+    /// Most vibrant color
+    pub vibrant: Option<String>,
+    /// Most muted color
+    pub muted: Option<String>,
+    /// Readable text color over the primary color
+    pub text: Option<String>,
+}
+
+/// An RGB color with the number of sampled pixels it represents
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Swatch {
+    rgb: [u8; 3],
+    population: usize,
+}
+
+impl Swatch {
+    fn hex(&self) -> String {
+        to_hex(self.rgb)
+    }
+
+    fn distance(&self, other: &Swatch) -> f64 {
+        self.rgb
+            .iter()
+            .zip(other.rgb.iter())
+            .map(|(a, b)| (f64::from(*a) - f64::from(*b)).powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// HSL saturation in the range 0.0-1.0
+    fn saturation(&self) -> f64 {
+        let [r, g, b] = self.rgb.map(|c| f64::from(c) / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let lightness = (max + min) / 2.0;
+        if max == min {
+            0.0
+        } else {
+            (max - min) / (1.0 - (2.0 * lightness - 1.0).abs())
+        }
+    }
+}
+
+/// Detect an image format from its magic bytes
+///
+/// Returns `None` if the bytes don't start with a known image signature.
+pub fn detect_format(bytes: &[u8]) -> Option<ImageFormat> {
+    match bytes {
+        [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+        [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(ImageFormat::WebP),
+        _ if is_avif(bytes) => Some(ImageFormat::Avif),
+        _ => None,
+    }
+}
+
+/// Read an image's pixel dimensions from its header without decoding it
+///
+/// Returns `None` if the bytes are not a recognized image or the header is
+/// unreadable.
+pub fn cover_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    match detect_format(bytes)? {
+        ImageFormat::Avif => avif::dimensions(bytes),
+        format => ImageReader::with_format(Cursor::new(bytes), format)
+            .into_dimensions()
+            .ok(),
+    }
+}
+
+/// Check for an ISO-BMFF `ftyp` box naming an AVIF brand
+///
+/// The brand may be the major brand or any of the compatible brands.
+fn is_avif(bytes: &[u8]) -> bool {
+    if bytes.len() < 16 || &bytes[4..8] != b"ftyp" {
+        return false;
+    }
+    let box_len = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+    let box_end = box_len.clamp(16, bytes.len());
+
+    // Major brand, then compatible brands after the 4-byte minor version
+    std::iter::once(&bytes[8..12])
+        .chain(bytes[16..box_end].chunks_exact(4))
+        .any(|brand| AVIF_BRANDS.iter().any(|avif| brand == *avif))
+}
+
+/// Decoded cover art, or a recognized image that could not be decoded
+enum LoadedCover {
+    Decoded(DynamicImage),
+    Undecodable,
+}
+
+/// Decode cover art bytes using the format detected from their magic bytes
+///
+/// Empty input and bytes that are not a recognized image format are
+/// errors. A recognized format that fails to decode (corrupt, or using
+/// features the decoder lacks) is reported as `Undecodable` so callers can
+/// fall back to a placeholder.
+fn load_cover(image_bytes: &[u8]) -> Result<LoadedCover, CoverArtError> {
+    if image_bytes.is_empty() {
+        return Err(CoverArtError::Empty);
+    }
+
+    let format = detect_format(image_bytes).ok_or(CoverArtError::UnsupportedFormat)?;
+
+    let decoded = match format {
+        ImageFormat::Avif => avif::decode(image_bytes).map_err(|e| e.to_string()),
+        _ => image::load_from_memory_with_format(image_bytes, format).map_err(|e| e.to_string()),
+    };
+
+    match decoded {
+        Ok(image) => Ok(LoadedCover::Decoded(image)),
+        Err(e) => {
+            tracing::warn!(
+                format = ?format,
+                error = %e,
+                "Failed to decode cover art, using placeholder"
+            );
+            Ok(LoadedCover::Undecodable)
+        }
+    }
+}
+
+/// Palette used for artwork that cannot be decoded
+pub fn placeholder_colors() -> CoverArtColors {
+    palette_from_swatches(&[Swatch {
+        rgb: PLACEHOLDER_RGB,
+        population: 1,
+    }])
+}
+
+/// Extract a color palette from encoded image bytes (JPEG, PNG, GIF, BMP, WebP, AVIF)
+///
+/// Returns an error if the image is empty, not a recognized image format, or
+/// fully transparent. Recognized images that cannot be decoded get the
+/// [`placeholder_colors`] palette.
+pub fn extract_colors(image_bytes: &[u8]) -> Result<CoverArtColors, CoverArtError> {
+    let image = match load_cover(image_bytes)? {
+        LoadedCover::Decoded(image) => image,
+        LoadedCover::Undecodable => return Ok(placeholder_colors()),
+    };
+
+    let pixels: Vec<[u8; 3]> = image
+        .resize(SAMPLE_SIZE, SAMPLE_SIZE, FilterType::Triangle)
+        .to_rgba8()
+        .pixels()
+        .filter(|p| p.0[3] >= MIN_ALPHA)
+        .map(|p| [p.0[0], p.0[1], p.0[2]])
+        .collect();
+
+    if pixels.is_empty() {
+        return Err(CoverArtError::NoOpaquePixels);
+    }
+
+    Ok(palette_from_swatches(&median_cut(pixels, PALETTE_SIZE)))
+}
+
+/// Resize cover art to a `size`x`size` JPEG thumbnail
+///
+/// Non-square images are scaled to cover the square and center-cropped.
+/// Returns an error if the image is empty or not a recognized image format.
+/// Recognized images that cannot be decoded produce a solid placeholder
+/// thumbnail.
+pub fn resize_cover(image_bytes: &[u8], size: u32) -> Result<Vec<u8>, CoverArtError> {
+    // JPEG has no alpha channel
+    let thumbnail = match load_cover(image_bytes)? {
+        LoadedCover::Decoded(image) => image
+            .resize_to_fill(size, size, FilterType::Lanczos3)
+            .to_rgb8(),
+        LoadedCover::Undecodable => RgbImage::from_pixel(size, size, Rgb(PLACEHOLDER_RGB)),
+    };
+
+    let mut bytes = Vec::new();
+    thumbnail
+        .write_with_encoder(JpegEncoder::new_with_quality(
+            &mut Cursor::new(&mut bytes),
+            THUMBNAIL_QUALITY,
+        ))
+        .map_err(|e| CoverArtError::Encode(e.to_string()))?;
+
+    Ok(bytes)
+}
+
+/// Build the final palette from quantized swatches
+fn palette_from_swatches(swatches: &[Swatch]) -> CoverArtColors {
+    let mut ranked = swatches.to_vec();
+    ranked.sort_by_key(|s| std::cmp::Reverse(s.population));
+
+    // Keep the most populous swatch of each visually distinct color
+    let mut distinct: Vec<Swatch> = Vec::new();
+    for swatch in ranked {
+        if distinct
+            .iter()
+            .all(|kept| kept.distance(&swatch) >= MIN_COLOR_DISTANCE)
+        {
+            distinct.push(swatch);
+        }
+    }
+
+    let Some(primary) = distinct.first().copied() else {
+        return CoverArtColors::default();
+    };
+
+    let vibrant = distinct
+        .iter()
+        .max_by(|a, b| a.saturation().total_cmp(&b.saturation()));
+    let muted = distinct
+        .iter()
+        .min_by(|a, b| a.saturation().total_cmp(&b.saturation()));
+
+    CoverArtColors {
+        primary: Some(primary.hex()),
+        secondary: distinct.get(1).map(Swatch::hex),
+        accent: distinct.get(2).map(Swatch::hex),
+        vibrant: vibrant.map(|s| s.hex()),
+        muted: muted.map(|s| s.hex()),
+        text: Some(to_hex(readable_text_color(primary.rgb))),
+    }
+}
+
+/// Quantize pixels into at most `max_colors` swatches using median cut
+///
+/// Repeatedly splits the box with the widest channel range at the median of
+/// that channel until there are enough boxes or no box can be split.
+fn median_cut(pixels: Vec<[u8; 3]>, max_colors: usize) -> Vec<Swatch> {
+    let mut boxes: Vec<Vec<[u8; 3]>> = vec![pixels];
+
+    while boxes.len() < max_colors {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .map(|(i, b)| {
+                let (channel, range) = widest_channel(b);
+                (i, channel, range)
+            })
+            .filter(|(_, _, range)| *range > 0)
+            .max_by_key(|(_, _, range)| *range);
+
+        let Some((index, channel, _)) = widest else {
+            break;
+        };
+
+        let mut to_split = boxes.swap_remove(index);
+        to_split.sort_unstable_by_key(|p| p[channel]);
+        let upper = to_split.split_off(to_split.len() / 2);
+        boxes.push(to_split);
+        boxes.push(upper);
+    }
+
+    boxes
+        .into_iter()
+        .filter(|b| !b.is_empty())
+        .map(|b| {
+            let mut sums = [0u64; 3];
+            for p in &b {
+                for (sum, c) in sums.iter_mut().zip(p.iter()) {
+                    *sum += u64::from(*c);
+                }
+            }
+            let n = b.len() as u64;
+            Swatch {
+                rgb: sums.map(|s| ((s + n / 2) / n) as u8),
+                population: b.len(),
+            }
+        })
+        .collect()
+}
+
+/// Find the RGB channel with the largest value range in a set of pixels
+fn widest_channel(pixels: &[[u8; 3]]) -> (usize, u8) {
+    (0..3)
+        .map(|channel| {
+            let (min, max) = pixels.iter().fold((u8::MAX, u8::MIN), |(min, max), p| {
+                (min.min(p[channel]), max.max(p[channel]))
+            });
+            (channel, max.saturating_sub(min))
+        })
+        .max_by_key(|(_, range)| *range)
+        .unwrap_or((0, 0))
+}
+
+/// WCAG relative luminance of an sRGB color
+fn relative_luminance(rgb: [u8; 3]) -> f64 {
+    let [r, g, b] = rgb.map(|c| {
+        let c = f64::from(c) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    });
+    0.2126 * r + 0.7152 * g + 0.0722 * b
+}
+
+/// WCAG contrast ratio between two colors (1.0-21.0)
+fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if la > lb { (la, lb) } else { (lb, la) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Pick black or white, whichever contrasts more with the background
+fn readable_text_color(background: [u8; 3]) -> [u8; 3] {
+    const WHITE: [u8; 3] = [255, 255, 255];
+    const BLACK: [u8; 3] = [0, 0, 0];
+    if contrast_ratio(background, WHITE) >= contrast_ratio(background, BLACK) {
+        WHITE
+    } else {
+        BLACK
+    }
+}
+
+fn to_hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 16x12 image: left 10 columns red (200, 40, 30), the rest blue (30, 60, 190)
+    const WEBP_SAMPLE: &[u8] = include_bytes!("../tests/fixtures/cover.webp");
+    /// Same image as `WEBP_SAMPLE`, lossy AVIF
+    const AVIF_SAMPLE: &[u8] = include_bytes!("../tests/fixtures/cover.avif");
+
+    fn encode_png(image: RgbImage) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+            .expect("encoding test image should succeed");
+        bytes
+    }
+
+    fn parse_hex(hex: &str) -> [u8; 3] {
+        let hex = hex.trim_start_matches('#');
+        [0, 2, 4].map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+    }
+
+    #[test]
+    fn test_solid_color_image() {
+        let bytes = encode_png(RgbImage::from_pixel(32, 32, Rgb([26, 26, 46])));
+
+        let colors = extract_colors(&bytes).unwrap();
+
+        assert_eq!(colors.primary.as_deref(), Some("#1a1a2e"));
+        assert_eq!(colors.secondary, None);
+        assert_eq!(colors.vibrant.as_deref(), Some("#1a1a2e"));
+        assert_eq!(colors.muted.as_deref(), Some("#1a1a2e"));
+        // Dark background needs light text
+        assert_eq!(colors.text.as_deref(), Some("#ffffff"));
+    }
+
+    #[test]
+    fn test_light_background_gets_dark_text() {
+        let bytes = encode_png(RgbImage::from_pixel(16, 16, Rgb([250, 240, 200])));
+
+        let colors = extract_colors(&bytes).unwrap();
+
+        assert_eq!(colors.text.as_deref(), Some("#000000"));
+    }
+
+    #[test]
+    fn test_dominant_color_is_most_common() {
+        // Left three quarters blue, right quarter yellow
+        let image = RgbImage::from_fn(64, 64, |x, _| {
+            if x < 48 {
+                Rgb([20, 40, 200])
+            } else {
+                Rgb([240, 220, 20])
+            }
+        });
+
+        let colors = extract_colors(&encode_png(image)).unwrap();
+
+        let primary = parse_hex(colors.primary.as_deref().unwrap());
+        assert!(primary[2] > 150 && primary[0] < 80, "primary {:?}", primary);
+
+        let secondary = parse_hex(colors.secondary.as_deref().unwrap());
+        assert!(
+            secondary[0] > 150 && secondary[1] > 150 && secondary[2] < 100,
+            "secondary {:?}",
+            secondary
+        );
+    }
+
+    #[test]
+    fn test_gradient_image() {
+        // Horizontal gradient from red to blue
+        let image = RgbImage::from_fn(64, 64, |x, _| {
+            let t = x as f64 / 63.0;
+            Rgb([(255.0 * (1.0 - t)) as u8, 0, (255.0 * t) as u8])
+        });
+
+        let colors = extract_colors(&encode_png(image)).unwrap();
+
+        // Every extracted color should lie on the red-blue gradient
+        for hex in [
+            &colors.primary,
+            &colors.secondary,
+            &colors.accent,
+            &colors.vibrant,
+            &colors.muted,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            let [r, g, b] = parse_hex(hex);
+            assert!(g < 16, "{} should have no green", hex);
+            assert!(
+                u16::from(r) + u16::from(b) > 200,
+                "{} should be saturated",
+                hex
+            );
+        }
+        assert!(colors.secondary.is_some());
+        assert!(colors.accent.is_some());
+    }
+
+    #[test]
+    fn test_vibrant_and_muted() {
+        // Half saturated orange, half gray
+        let image = RgbImage::from_fn(32, 32, |x, _| {
+            if x < 16 {
+                Rgb([250, 120, 10])
+            } else {
+                Rgb([128, 128, 128])
+            }
+        });
+
+        let colors = extract_colors(&encode_png(image)).unwrap();
+
+        assert_eq!(colors.vibrant.as_deref(), Some("#fa780a"));
+        assert_eq!(colors.muted.as_deref(), Some("#808080"));
+    }
+
+    #[test]
+    fn test_corrupt_image_is_rejected() {
+        let result = extract_colors(b"definitely not an image");
+        assert_eq!(result, Err(CoverArtError::UnsupportedFormat));
+    }
+
+    #[test]
+    fn test_empty_image_is_rejected() {
+        let result = extract_colors(&[]);
+        assert_eq!(result, Err(CoverArtError::Empty));
+    }
+
+    #[test]
+    fn test_resize_cover_crops_to_square_jpeg() {
+        let bytes = encode_png(RgbImage::from_pixel(300, 200, Rgb([200, 30, 30])));
+
+        let thumbnail = resize_cover(&bytes, 64).unwrap();
+
+        assert_eq!(image::guess_format(&thumbnail).unwrap(), ImageFormat::Jpeg);
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 64));
+    }
+
+    #[test]
+    fn test_resize_cover_rejects_corrupt_image() {
+        let result = resize_cover(b"not an image", 128);
+        assert_eq!(result, Err(CoverArtError::UnsupportedFormat));
+    }
+
+    #[test]
+    fn test_cover_dimensions_from_header() {
+        let png = encode_png(RgbImage::from_pixel(30, 20, Rgb([1, 2, 3])));
+
+        assert_eq!(cover_dimensions(&png), Some((30, 20)));
+        assert_eq!(cover_dimensions(WEBP_SAMPLE), Some((16, 12)));
+        assert_eq!(cover_dimensions(b"definitely not an image"), None);
+    }
+
+    #[test]
+    fn test_detect_format_by_magic_bytes() {
+        let png = encode_png(RgbImage::from_pixel(4, 4, Rgb([1, 2, 3])));
+        let jpeg = resize_cover(&png, 64).unwrap();
+
+        assert_eq!(detect_format(&png), Some(ImageFormat::Png));
+        assert_eq!(detect_format(&jpeg), Some(ImageFormat::Jpeg));
+        assert_eq!(detect_format(b"GIF89a\x01\x00"), Some(ImageFormat::Gif));
+        assert_eq!(detect_format(WEBP_SAMPLE), Some(ImageFormat::WebP));
+        assert_eq!(detect_format(AVIF_SAMPLE), Some(ImageFormat::Avif));
+        assert_eq!(detect_format(b"definitely not an image"), None);
+    }
+
+    #[test]
+    fn test_detect_avif_by_compatible_brand() {
+        let mut header = Vec::new();
+        header.extend_from_slice(&24u32.to_be_bytes());
+        header.extend_from_slice(b"ftypmif1\0\0\0\0mif1avif");
+
+        assert_eq!(detect_format(&header), Some(ImageFormat::Avif));
+
+        // Other ISO-BMFF files (e.g. M4A audio) are not images
+        header[8..12].copy_from_slice(b"M4A ");
+        header[20..24].copy_from_slice(b"isom");
+        assert_eq!(detect_format(&header), None);
+    }
+
+    #[test]
+    fn test_webp_cover_decodes() {
+        let LoadedCover::Decoded(image) = load_cover(WEBP_SAMPLE).unwrap() else {
+            panic!("WebP sample should decode");
+        };
+        assert_eq!((image.width(), image.height()), (16, 12));
+
+        let colors = extract_colors(WEBP_SAMPLE).unwrap();
+        assert_ne!(colors, CoverArtColors::default());
+        assert_ne!(colors, placeholder_colors());
+        assert_eq!(colors.primary.as_deref(), Some("#c8281e"));
+        assert_eq!(colors.secondary.as_deref(), Some("#1e3cbe"));
+
+        let thumbnail = resize_cover(WEBP_SAMPLE, 64).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 64));
+    }
+
+    #[test]
+    fn test_avif_cover_decodes() {
+        let LoadedCover::Decoded(image) = load_cover(AVIF_SAMPLE).unwrap() else {
+            panic!("AVIF sample should decode");
+        };
+        assert_eq!((image.width(), image.height()), (16, 12));
+
+        // Lossy encoding shifts colors slightly
+        let colors = extract_colors(AVIF_SAMPLE).unwrap();
+        assert_ne!(colors, CoverArtColors::default());
+        assert_ne!(colors, placeholder_colors());
+        let primary = parse_hex(colors.primary.as_deref().unwrap());
+        assert!(primary[0] > 150 && primary[2] < 80, "primary {:?}", primary);
+        let secondary = parse_hex(colors.secondary.as_deref().unwrap());
+        assert!(
+            secondary[2] > 150 && secondary[0] < 80,
+            "secondary {:?}",
+            secondary
+        );
+
+        assert_eq!(cover_dimensions(AVIF_SAMPLE), Some((16, 12)));
+        let thumbnail = resize_cover(AVIF_SAMPLE, 64).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (64, 64));
+    }
+
+    #[test]
+    fn test_truncated_avif_uses_placeholder() {
+        // Cut short inside the AV1 payload, as an interrupted download would be
+        let truncated = &AVIF_SAMPLE[..AVIF_SAMPLE.len() - 8];
+        assert_eq!(detect_format(truncated), Some(ImageFormat::Avif));
+        assert_eq!(extract_colors(truncated).unwrap(), placeholder_colors());
+    }
+
+    #[test]
+    fn test_undecodable_image_uses_placeholder() {
+        // A valid PNG signature followed by garbage
+        let mut truncated = encode_png(RgbImage::from_pixel(8, 8, Rgb([9, 9, 9])));
+        truncated.truncate(20);
+
+        let colors = extract_colors(&truncated).unwrap();
+        assert_eq!(colors, placeholder_colors());
+        assert_eq!(colors.primary.as_deref(), Some("#3a3a4a"));
+        assert_eq!(colors.text.as_deref(), Some("#ffffff"));
+    }
+
+    #[test]
+    fn test_contrast_ratio_bounds() {
+        assert!((contrast_ratio([0, 0, 0], [255, 255, 255]) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio([100, 100, 100], [100, 100, 100]) - 1.0).abs() < f64::EPSILON);
+    }
+}
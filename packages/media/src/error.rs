@@ -0,0 +1,23 @@
+//! Media error types
+
+use thiserror::Error;
+
+/// Errors from reading cover art images
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum CoverArtError {
+    /// The image has no bytes
+    #[error("cover art image is empty")]
+    Empty,
+
+    /// The bytes are not a recognized image format
+    #[error("unsupported or corrupt cover art image")]
+    UnsupportedFormat,
+
+    /// Every pixel is (nearly) transparent, so there are no colors to sample
+    #[error("cover art image has no opaque pixels")]
+    NoOpaquePixels,
+
+    /// A resized image could not be encoded
+    #[error("failed to encode cover art: {0}")]
+    Encode(String),
+}
@@ -0,0 +1,17 @@
+//! Media helpers shared by Resonance services
+//!
+//! Cover art decoding, palettes and thumbnails, and fuzzy matching of artist,
+//! album and track names. Kept out of `resonance-shared-config` so that
+//! configuration consumers don't pull in the image codecs.
+
+mod avif;
+mod cover_art;
+mod error;
+mod name_match;
+
+pub use cover_art::{
+    cover_dimensions, detect_format, extract_colors, placeholder_colors, resize_cover,
+    CoverArtColors, ART_SIZES,
+};
+pub use error::CoverArtError;
+pub use name_match::{best_match, normalize_name, similarity};
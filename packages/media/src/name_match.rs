@@ -0,0 +1,184 @@
+//! Fuzzy matching of artist, album and track names
+//!
+//! External sources (playlist files, Lidarr) rarely spell names exactly the
+//! way they are tagged in the library. Names are normalized first so that
+//! case, accents, punctuation and "feat." credits don't count as differences,
+//! then compared with Jaro-Winkler similarity.
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+
+/// Words that introduce a featured-artist credit
+const FEATURE_MARKERS: &[&str] = &["feat", "ft", "featuring"];
+
+/// Normalize a name for comparison
+///
+/// Lowercases, strips diacritics, drops parenthetical or bracketed parts and
+/// anything after a "feat."/"ft."/"featuring" credit, removes punctuation and
+/// collapses whitespace. A name that is entirely parenthetical keeps its
+/// contents rather than normalizing to an empty string.
+pub fn normalize_name(name: &str) -> String {
+    let folded: String = name
+        .nfd()
+        .filter(|c| !is_combining_mark(*c))
+        .flat_map(char::to_lowercase)
+        .collect();
+
+    let normalized = collapse_words(&strip_brackets(&folded));
+    if normalized.is_empty() {
+        collapse_words(&folded)
+    } else {
+        normalized
+    }
+}
+
+/// Similarity of two names in `0.0..=1.0`, after normalization
+pub fn similarity(a: &str, b: &str) -> f32 {
+    normalized_similarity(&normalize_name(a), &normalize_name(b))
+}
+
+/// Find the candidate most similar to `query`
+///
+/// Returns the candidate and its score, or `None` if no candidate scores at
+/// least `threshold`. Ties go to the earliest candidate.
+pub fn best_match<'a, T: AsRef<str>>(
+    query: &str,
+    candidates: &'a [T],
+    threshold: f32,
+) -> Option<(&'a T, f32)> {
+    let query = normalize_name(query);
+
+    candidates
+        .iter()
+        .map(|candidate| {
+            let score = normalized_similarity(&query, &normalize_name(candidate.as_ref()));
+            (candidate, score)
+        })
+        .filter(|(_, score)| *score >= threshold)
+        .fold(None, |best: Option<(&T, f32)>, current| match best {
+            Some(best) if best.1 >= current.1 => Some(best),
+            _ => Some(current),
+        })
+}
+
+fn normalized_similarity(a: &str, b: &str) -> f32 {
+    strsim::jaro_winkler(a, b) as f32
+}
+
+/// Remove text inside (), [] and {} including nested brackets
+fn strip_brackets(s: &str) -> String {
+    let mut depth = 0usize;
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '(' | '[' | '{' => {
+                depth += 1;
+                out.push(' ');
+            }
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => out.push(c),
+            _ => {}
+        }
+    }
+
+    out
+}
+
+/// Split on punctuation and whitespace, cut off featured-artist credits and
+/// join the remaining words with single spaces
+fn collapse_words(s: &str) -> String {
+    let cleaned: String = s
+        .chars()
+        .filter(|c| !matches!(c, '\'' | '\u{2019}'))
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let mut words = Vec::new();
+    for (i, word) in cleaned.split_whitespace().enumerate() {
+        if i > 0 && FEATURE_MARKERS.contains(&word) {
+            break;
+        }
+        words.push(word);
+    }
+
+    words.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_strips_diacritics_and_case() {
+        assert_eq!(normalize_name("Beyoncé"), "beyonce");
+        assert_eq!(normalize_name("Sigur Rós"), "sigur ros");
+        assert_eq!(normalize_name("MOTÖRHEAD"), "motorhead");
+        assert_eq!(similarity("Beyoncé", "beyonce"), 1.0);
+    }
+
+    #[test]
+    fn test_normalize_strips_feat_and_parentheticals() {
+        assert_eq!(normalize_name("Song (feat. X)"), "song");
+        assert_eq!(normalize_name("Song feat. X"), "song");
+        assert_eq!(normalize_name("Song ft. X & Y"), "song");
+        assert_eq!(normalize_name("Song Featuring X"), "song");
+        assert_eq!(normalize_name("Song [Remastered 2011]"), "song");
+        assert_eq!(normalize_name("Album (Deluxe (Bonus) Edition)"), "album");
+        assert_eq!(similarity("Song (feat. X)", "Song"), 1.0);
+    }
+
+    #[test]
+    fn test_normalize_punctuation_and_whitespace() {
+        assert_eq!(
+            normalize_name("  Don't   Stop\tMe-Now!  "),
+            "dont stop me now"
+        );
+        assert_eq!(normalize_name("AC/DC"), "ac dc");
+        // A leading "Feat" is part of the name, not a credit
+        assert_eq!(normalize_name("Feat"), "feat");
+    }
+
+    #[test]
+    fn test_normalize_keeps_fully_parenthetical_names() {
+        assert_eq!(normalize_name("(Untitled)"), "untitled");
+        assert_eq!(normalize_name(""), "");
+    }
+
+    #[test]
+    fn test_similarity_ranges() {
+        assert_eq!(similarity("Radiohead", "radiohead"), 1.0);
+        assert!(similarity("Radiohead", "Radiohed") > 0.9);
+        assert!(similarity("Radiohead", "Metallica") < 0.6);
+    }
+
+    #[test]
+    fn test_best_match_picks_highest_score() {
+        let candidates = ["Beyonce", "Beyoncé Knowles", "Bey"];
+
+        let (name, score) = best_match("Beyoncé", &candidates, 0.8).unwrap();
+
+        assert_eq!(*name, "Beyonce");
+        assert_eq!(score, 1.0);
+    }
+
+    #[test]
+    fn test_best_match_respects_threshold() {
+        let candidates = vec!["Radiohed".to_string(), "Metallica".to_string()];
+
+        let (name, score) = best_match("Radiohead", &candidates, 0.9).unwrap();
+        assert_eq!(name, "Radiohed");
+        assert!(score >= 0.9);
+
+        assert!(best_match("Radiohead", &candidates, 0.99).is_none());
+        assert!(best_match("Radiohead", &[] as &[&str], 0.0).is_none());
+    }
+
+    #[test]
+    fn test_best_match_ties_go_to_first_candidate() {
+        let candidates = ["Song (Live)", "Song [Remastered]"];
+
+        let (name, _) = best_match("Song", &candidates, 0.9).unwrap();
+
+        assert_eq!(*name, "Song (Live)");
+    }
+}
@@ -8,9 +8,10 @@ use bytes::Bytes;
 use futures_util::Stream;
 use reqwest::Client;
 use resonance_shared_config::OllamaConfig;
-use tracing::{debug, warn};
+use tracing::{debug, error, info, warn};
 
-use crate::error::{OllamaError, OllamaResult};
+use crate::check_embedding_dimension;
+use crate::error::{parse_error_message, OllamaError, OllamaResult};
 use crate::models::{
     ChatMessage, ChatRequest, ChatResponse, ChatStreamChunk, EmbeddingRequest, EmbeddingResponse,
     GenerateOptions, GenerateRequest, GenerateResponse, ListModelsResponse,
@@ -23,6 +24,15 @@ const MAX_ERROR_BODY_SIZE: usize = 1000;
 const DEFAULT_RETRY_ATTEMPTS: u32 = 3;
 const DEFAULT_RETRY_BASE_DELAY_MS: u64 = 500;
 
+/// Prompt sent to each model when warming up
+const WARM_UP_PROMPT: &str = "ping";
+
+/// Minimum delay before retrying while a model is loading
+///
+/// Loading a model takes seconds, so retrying at the base delay would only
+/// burn attempts before the model is ready.
+const MODEL_LOADING_RETRY_DELAY_MS: u64 = 2000;
+
 /// Ollama API client with retry logic and connection pooling
 #[derive(Debug, Clone)]
 pub struct OllamaClient {
@@ -48,12 +58,18 @@ impl OllamaClient {
             .build()
             .map_err(OllamaError::HttpError)?;
 
-        Ok(Self {
+        let client = Self {
             http_client,
             config: config.clone(),
             retry_attempts: DEFAULT_RETRY_ATTEMPTS,
             retry_base_delay_ms: DEFAULT_RETRY_BASE_DELAY_MS,
-        })
+        };
+
+        if config.warm_up {
+            client.spawn_warm_up();
+        }
+
+        Ok(client)
     }
 
     /// Create a client with custom HTTP client (for testing)
@@ -78,6 +94,49 @@ impl OllamaClient {
         &self.config
     }
 
+    /// Dimension embeddings from this client are validated against
+    pub fn embedding_dimension(&self) -> usize {
+        self.config.embedding_dimension
+    }
+
+    /// Load the chat and embedding models in the background
+    ///
+    /// Sends a one-token generation and a short embedding request without
+    /// retries. Failures are logged and otherwise ignored; nothing waits on
+    /// the result. Needs a Tokio runtime, and is skipped with a warning
+    /// without one.
+    fn spawn_warm_up(&self) {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            warn!("No Tokio runtime available; skipping Ollama model warm-up");
+            return;
+        };
+
+        let client = self.clone();
+        runtime.spawn(async move { client.warm_up().await });
+    }
+
+    /// Issue one tiny request against each configured model
+    async fn warm_up(&self) {
+        let chat_options = GenerateOptions {
+            num_predict: Some(1),
+            ..Default::default()
+        };
+        let (chat, embedding) = tokio::join!(
+            self.generate_internal(WARM_UP_PROMPT, Some(chat_options)),
+            self.generate_embedding_internal(WARM_UP_PROMPT)
+        );
+
+        for (model, result) in [
+            (&self.config.model, chat.map(|_| ())),
+            (&self.config.embedding_model, embedding.map(|_| ())),
+        ] {
+            match result {
+                Ok(()) => info!(model = %model, "Ollama model warmed up"),
+                Err(e) => warn!(model = %model, error = %e, "Ollama model warm-up failed"),
+            }
+        }
+    }
+
     /// Execute an async operation with retry logic
     async fn with_retry<T, F, Fut>(&self, operation: F) -> OllamaResult<T>
     where
@@ -100,7 +159,10 @@ impl OllamaClient {
                         return Err(e);
                     } else if attempt < self.retry_attempts - 1 {
                         // Retryable error, not last attempt - wait and retry
-                        let delay = self.retry_base_delay_ms * 2_u64.pow(attempt);
+                        let mut delay = self.retry_base_delay_ms * 2_u64.pow(attempt);
+                        if matches!(e, OllamaError::ModelLoading(_)) {
+                            delay = delay.max(MODEL_LOADING_RETRY_DELAY_MS);
+                        }
                         warn!(
                             attempt = attempt + 1,
                             max_attempts = self.retry_attempts,
@@ -110,6 +172,10 @@ impl OllamaClient {
                         );
                         tokio::time::sleep(Duration::from_millis(delay)).await;
                         last_error = Some(e);
+                    } else if matches!(e, OllamaError::ModelLoading(_)) {
+                        // Still loading after every attempt - keep the error typed
+                        // so callers can tell users to try again shortly
+                        return Err(e);
                     } else {
                         // Retryable error on last attempt - exit loop to return RetriesExhausted
                         last_error = Some(e);
@@ -174,10 +240,10 @@ impl OllamaClient {
         if !response.status().is_success() {
             let status = response.status();
             let body = Self::truncate_error_body(response.text().await.unwrap_or_default());
-            return Err(OllamaError::ApiError(format!(
-                "Status {}: {}",
-                status, body
-            )));
+            return Err(OllamaError::Api {
+                status: status.as_u16(),
+                message: parse_error_message(&body),
+            });
         }
 
         let list: ListModelsResponse = response.json().await?;
@@ -204,7 +270,7 @@ impl OllamaClient {
 
         let response = self
             .http_client
-            .post(self.config.embeddings_url())
+            .post(self.config.embedding_url())
             .json(&request)
             .send()
             .await
@@ -222,20 +288,30 @@ impl OllamaClient {
             let status = response.status();
             let body = Self::truncate_error_body(response.text().await.unwrap_or_default());
 
-            if body.contains("model") && body.contains("not found") {
-                return Err(OllamaError::ModelNotFound(
-                    self.config.embedding_model.clone(),
-                ));
-            }
-
-            return Err(OllamaError::ApiError(format!(
-                "Status {}: {}",
-                status, body
-            )));
+            return Err(OllamaError::from_response(
+                status.as_u16(),
+                &body,
+                &self.config.embedding_model,
+            ));
         }
 
         let embedding_response: EmbeddingResponse = response.json().await?;
-        Ok(embedding_response.embedding)
+        let embedding = embedding_response.embedding;
+
+        if let Err(e) = check_embedding_dimension(&embedding, self.config.embedding_dimension) {
+            error!(
+                model = %self.config.embedding_model,
+                configured_dimension = self.config.embedding_dimension,
+                actual_dimension = embedding.len(),
+                "Embedding model returned {} dims but {} are configured; \
+                 set EMBEDDING_DIMENSION to match the model (and the pgvector columns)",
+                embedding.len(),
+                self.config.embedding_dimension
+            );
+            return Err(e);
+        }
+
+        Ok(embedding)
     }
 
     /// Generate embeddings for text with retry logic
@@ -262,9 +338,14 @@ impl OllamaClient {
 
     /// Generate embeddings for multiple texts concurrently
     ///
+    /// The returned embeddings are in the same order as `texts`, however the
+    /// requests complete, so callers can zip them back to their inputs. Fails
+    /// with the first error to complete, abandoning any requests in flight.
+    ///
     /// # Arguments
     /// * `texts` - List of texts to generate embeddings for
-    /// * `concurrency` - Maximum concurrent requests (recommend 3-5 for Ollama)
+    /// * `concurrency` - Maximum concurrent requests (recommend 3-5 for Ollama);
+    ///   0 is treated as 1
     pub async fn generate_embeddings_batch(
         &self,
         texts: Vec<String>,
@@ -274,24 +355,76 @@ impl OllamaClient {
 
         // Ensure concurrency is at least 1 to prevent buffer_unordered from hanging
         let concurrency = concurrency.max(1);
+        let total = texts.len();
 
         debug!(
-            count = texts.len(),
+            count = total,
             concurrency = concurrency,
             "Generating batch embeddings"
         );
 
-        let results: Vec<OllamaResult<Vec<f32>>> = stream::iter(texts.into_iter().enumerate())
+        let mut results_stream = stream::iter(texts.into_iter().enumerate())
             .map(|(i, text)| async move {
                 debug!(index = i, "Processing embedding");
-                self.generate_embedding(&text).await
+                (i, self.generate_embedding(&text).await)
             })
-            .buffer_unordered(concurrency)
-            .collect()
-            .await;
+            .buffer_unordered(concurrency);
+
+        // Requests complete in any order; slot each result back at its index
+        let mut embeddings: Vec<Option<Vec<f32>>> = vec![None; total];
+        while let Some((i, result)) = results_stream.next().await {
+            embeddings[i] = Some(result?);
+        }
+
+        Ok(embeddings
+            .into_iter()
+            .map(|embedding| embedding.expect("every text yields a result"))
+            .collect())
+    }
+
+    /// Generate embeddings for multiple texts concurrently, reporting progress
+    ///
+    /// Unlike [`generate_embeddings_batch`](Self::generate_embeddings_batch),
+    /// a failed text does not fail the batch: one result is returned per text,
+    /// in input order. `on_progress` is called with `(completed, total)` after
+    /// each result.
+    ///
+    /// # Arguments
+    /// * `texts` - List of texts to generate embeddings for
+    /// * `concurrency` - Maximum concurrent requests (recommend 3-5 for Ollama)
+    /// * `on_progress` - Progress callback
+    pub async fn generate_embeddings_batch_with_progress<F>(
+        &self,
+        texts: Vec<String>,
+        concurrency: usize,
+        mut on_progress: F,
+    ) -> Vec<OllamaResult<Vec<f32>>>
+    where
+        F: FnMut(usize, usize),
+    {
+        use futures_util::stream::{self, StreamExt};
 
-        // Collect results, propagating first error
-        results.into_iter().collect()
+        let concurrency = concurrency.max(1);
+        let total = texts.len();
+
+        debug!(
+            count = total,
+            concurrency = concurrency,
+            "Generating batch embeddings with progress"
+        );
+
+        // `buffered` (not `buffer_unordered`) keeps results in input order
+        let mut results_stream = stream::iter(texts)
+            .map(|text| async move { self.generate_embedding(&text).await })
+            .buffered(concurrency);
+
+        let mut results = Vec::with_capacity(total);
+        while let Some(result) = results_stream.next().await {
+            results.push(result);
+            on_progress(results.len(), total);
+        }
+
+        results
     }
 
     /// Internal text generation (single request, no retry)
@@ -333,14 +466,11 @@ impl OllamaClient {
             let status = response.status();
             let body = Self::truncate_error_body(response.text().await.unwrap_or_default());
 
-            if body.contains("model") && body.contains("not found") {
-                return Err(OllamaError::ModelNotFound(self.config.model.clone()));
-            }
-
-            return Err(OllamaError::ApiError(format!(
-                "Status {}: {}",
-                status, body
-            )));
+            return Err(OllamaError::from_response(
+                status.as_u16(),
+                &body,
+                &self.config.model,
+            ));
         }
 
         let generate_response: GenerateResponse = response.json().await?;
@@ -418,14 +548,11 @@ impl OllamaClient {
             let status = response.status();
             let body = Self::truncate_error_body(response.text().await.unwrap_or_default());
 
-            if body.contains("model") && body.contains("not found") {
-                return Err(OllamaError::ModelNotFound(self.config.model.clone()));
-            }
-
-            return Err(OllamaError::ApiError(format!(
-                "Status {}: {}",
-                status, body
-            )));
+            return Err(OllamaError::from_response(
+                status.as_u16(),
+                &body,
+                &self.config.model,
+            ));
         }
 
         let chat_response: ChatResponse = response.json().await?;
@@ -530,14 +657,11 @@ impl OllamaClient {
             let status = response.status();
             let body = Self::truncate_error_body(response.text().await.unwrap_or_default());
 
-            if body.contains("model") && body.contains("not found") {
-                return Err(OllamaError::ModelNotFound(self.config.model.clone()));
-            }
-
-            return Err(OllamaError::ApiError(format!(
-                "Status {}: {}",
-                status, body
-            )));
+            return Err(OllamaError::from_response(
+                status.as_u16(),
+                &body,
+                &self.config.model,
+            ));
         }
 
         // Get the bytes stream from reqwest and transform it to parse NDJSON
@@ -724,6 +848,7 @@ where
 mod tests {
     use super::*;
     use futures_util::StreamExt;
+    use resonance_test_utils::MockOllamaServer;
     use wiremock::matchers::{method, path};
     use wiremock::{Mock, MockServer, ResponseTemplate};
 
@@ -733,9 +858,11 @@ mod tests {
             url: server_url.to_string(),
             model: "test-model".to_string(),
             embedding_model: "test-embed".to_string(),
+            embedding_dimension: 3,
             timeout_secs: 30,
             max_tokens: 1024,
             temperature: 0.7,
+            warm_up: false,
         }
     }
 
@@ -941,12 +1068,34 @@ mod tests {
         let result = client.chat_stream(messages, None).await;
 
         match result {
-            Err(OllamaError::ApiError(_)) => {} // expected
-            Err(e) => panic!("Expected ApiError, got: {:?}", e),
+            Err(OllamaError::Api { status: 500, .. }) => {} // expected
+            Err(e) => panic!("Expected Api error, got: {:?}", e),
             Ok(_) => panic!("Expected error, got Ok"),
         }
     }
 
+    #[tokio::test]
+    async fn test_chat_model_loading_is_typed_error() {
+        let server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_json(serde_json::json!({"error": "loading model"})),
+            )
+            .mount(&server)
+            .await;
+
+        let config = test_config(&server.uri());
+        let client = OllamaClient::new(&config).unwrap().with_retry_config(1, 10);
+
+        let result = client.chat(vec![ChatMessage::user("test")]).await;
+
+        assert!(matches!(result, Err(OllamaError::ModelLoading(_))));
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_chat_stream_model_not_found() {
         let server = MockServer::start().await;
@@ -1205,4 +1354,215 @@ not valid json
 
         assert!(ndjson_stream.next().await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_batch_with_progress_keeps_order_and_failures() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("POST"))
+            .and(path("/api/embeddings"))
+            .and(body_partial_json(serde_json::json!({ "prompt": "bad" })))
+            .respond_with(ResponseTemplate::new(400).set_body_string("invalid input"))
+            .mount(&mock_server)
+            .await;
+        for (text, value) in [("one", 1.0), ("two", 2.0)] {
+            Mock::given(method("POST"))
+                .and(path("/api/embeddings"))
+                .and(body_partial_json(serde_json::json!({ "prompt": text })))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "embedding": [value, value, value] })),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = OllamaClient::new(&test_config(&mock_server.uri()))
+            .unwrap()
+            .with_retry_config(1, 1);
+
+        let mut progress = Vec::new();
+        let results = client
+            .generate_embeddings_batch_with_progress(
+                vec!["one".to_string(), "bad".to_string(), "two".to_string()],
+                2,
+                |done, total| progress.push((done, total)),
+            )
+            .await;
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &vec![1.0; 3]);
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &vec![2.0; 3]);
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_keeps_input_order_with_varied_latency() {
+        use wiremock::matchers::body_partial_json;
+
+        let mock_server = MockServer::start().await;
+
+        // Earlier texts respond slowest, so completion order is reversed
+        let texts: Vec<String> = (0..5).map(|i| format!("text-{}", i)).collect();
+        for (i, text) in texts.iter().enumerate() {
+            Mock::given(method("POST"))
+                .and(path("/api/embeddings"))
+                .and(body_partial_json(serde_json::json!({ "prompt": text })))
+                .respond_with(
+                    ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "embedding": [i as f32, 0.0, 0.0] }))
+                        .set_delay(std::time::Duration::from_millis(200 - 40 * i as u64)),
+                )
+                .mount(&mock_server)
+                .await;
+        }
+
+        let client = OllamaClient::new(&test_config(&mock_server.uri()))
+            .unwrap()
+            .with_retry_config(1, 1);
+
+        let embeddings = client
+            .generate_embeddings_batch(texts.clone(), texts.len())
+            .await
+            .unwrap();
+
+        let firsts: Vec<f32> = embeddings.iter().map(|e| e[0]).collect();
+        assert_eq!(firsts, vec![0.0, 1.0, 2.0, 3.0, 4.0]);
+    }
+
+    #[tokio::test]
+    async fn test_batch_zero_concurrency_runs_sequentially() {
+        let mock_server = MockServer::start().await;
+        mount_embedding(&mock_server, 3).await;
+
+        let client = OllamaClient::new(&test_config(&mock_server.uri()))
+            .unwrap()
+            .with_retry_config(1, 1);
+
+        let embeddings = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            client.generate_embeddings_batch(vec!["a".to_string(), "b".to_string()], 0),
+        )
+        .await
+        .expect("batch with zero concurrency should not hang")
+        .unwrap();
+
+        assert_eq!(embeddings, vec![vec![0.5; 3], vec![0.5; 3]]);
+    }
+
+    /// Mount an embeddings endpoint returning a vector of `dimension` values
+    async fn mount_embedding(mock_server: &MockServer, dimension: usize) {
+        Mock::given(method("POST"))
+            .and(path("/api/embeddings"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "embedding": vec![0.5; dimension] })),
+            )
+            .mount(mock_server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_embedding_matching_configured_dimension() {
+        let mock_server = MockServer::start().await;
+        mount_embedding(&mock_server, 1024).await;
+
+        let config = OllamaConfig {
+            embedding_dimension: 1024,
+            ..test_config(&mock_server.uri())
+        };
+        let client = OllamaClient::new(&config).unwrap();
+
+        assert_eq!(client.embedding_dimension(), 1024);
+        let embedding = client.generate_embedding("hello").await.unwrap();
+        assert_eq!(embedding.len(), 1024);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_mismatching_configured_dimension() {
+        let mock_server = MockServer::start().await;
+        // A 768-dim model while 1024 dims are configured
+        mount_embedding(&mock_server, 768).await;
+
+        let config = OllamaConfig {
+            embedding_dimension: 1024,
+            ..test_config(&mock_server.uri())
+        };
+        let client = OllamaClient::new(&config).unwrap().with_retry_config(3, 1);
+
+        let result = client.generate_embedding("hello").await;
+        assert!(matches!(
+            result,
+            Err(OllamaError::DimensionMismatch {
+                expected: 1024,
+                actual: 768
+            })
+        ));
+        // Not retried: the model will keep returning the same dimension
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+    }
+
+    /// Client config for a MockOllamaServer, with its default models
+    fn warm_up_config(server: &MockOllamaServer, warm_up: bool) -> OllamaConfig {
+        OllamaConfig {
+            warm_up,
+            ..OllamaConfig::with_url(server.url())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_requests_issued_when_enabled() {
+        let server = MockOllamaServer::start().await;
+        server.mock_generate_success("pong").await;
+        server.mock_embeddings_success().await;
+
+        let _client = OllamaClient::new(&warm_up_config(&server, true)).unwrap();
+
+        // Construction returns immediately; the requests happen in the background
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while server.generate_calls() == 0 || server.embedding_calls() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("warm-up requests were not issued");
+
+        assert_eq!(server.generate_calls(), 1);
+        assert_eq!(server.embedding_calls(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_skipped_when_disabled() {
+        let server = MockOllamaServer::start().await;
+        server.mock_generate_success("pong").await;
+        server.mock_embeddings_success().await;
+
+        let _client = OllamaClient::new(&warm_up_config(&server, false)).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(server.generate_calls(), 0);
+        assert_eq!(server.embedding_calls(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_warm_up_failure_does_not_block_construction() {
+        let server = MockOllamaServer::start().await;
+        server.mock_generate_failure(500, "boom").await;
+        server.mock_embeddings_model_not_found().await;
+
+        let client = OllamaClient::new(&warm_up_config(&server, true));
+        assert!(client.is_ok());
+    }
+
+    #[test]
+    fn test_warm_up_without_runtime_is_skipped() {
+        let config = OllamaConfig {
+            warm_up: true,
+            ..OllamaConfig::default()
+        };
+        assert!(OllamaClient::new(&config).is_ok());
+    }
 }
@@ -1,5 +1,6 @@
 //! Error types for Ollama client
 
+use serde::Deserialize;
 use thiserror::Error;
 
 /// Errors that can occur when interacting with Ollama
@@ -13,14 +14,29 @@ pub enum OllamaError {
     #[error("JSON error: {0}")]
     JsonError(#[from] serde_json::Error),
 
-    /// Ollama API returned an error
+    /// Ollama API error without an HTTP status (e.g. a broken response stream)
     #[error("Ollama API error: {0}")]
     ApiError(String),
 
+    /// Ollama answered with an unsuccessful status
+    ///
+    /// `message` is the `error` field of Ollama's JSON error body, or the raw
+    /// body when it isn't JSON.
+    #[error("Ollama API error (status {status}): {message}")]
+    Api { status: u16, message: String },
+
     /// Model not found or not pulled
     #[error("Model not found: {0}. Try running 'ollama pull {0}'")]
     ModelNotFound(String),
 
+    /// Model is still being loaded into memory (503 while Ollama warms up)
+    #[error("Model {0} is loading, try again shortly")]
+    ModelLoading(String),
+
+    /// The input is longer than the model's context window
+    #[error("Input exceeds the context length of model {model}: {message}")]
+    ContextLengthExceeded { model: String, message: String },
+
     /// Request timeout
     #[error("Request timed out after {0} seconds")]
     Timeout(u64),
@@ -42,19 +58,63 @@ pub enum OllamaError {
     RetriesExhausted { attempts: u32, last_error: String },
 }
 
+/// Ollama's JSON error body, e.g. `{"error":"model 'mistral' not found"}`
+#[derive(Deserialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// The error message from an Ollama error body
+///
+/// Returns the `error` field of a JSON body, or the trimmed raw body when it
+/// isn't JSON in that shape.
+pub fn parse_error_message(body: &str) -> String {
+    match serde_json::from_str::<ErrorBody>(body) {
+        Ok(parsed) => parsed.error,
+        Err(_) => body.trim().to_string(),
+    }
+}
+
 impl OllamaError {
+    /// Classify an unsuccessful Ollama response for a request to `model`
+    ///
+    /// Recognizes a model that is still loading (503 with a "loading model"
+    /// message), a missing model, and input longer than the context window;
+    /// anything else becomes [`OllamaError::Api`].
+    pub fn from_response(status: u16, body: &str, model: &str) -> Self {
+        let message = parse_error_message(body);
+        if is_model_loading_response(status, &message) {
+            return OllamaError::ModelLoading(model.to_string());
+        }
+
+        let lower = message.to_ascii_lowercase();
+        if lower.contains("model") && lower.contains("not found") {
+            return OllamaError::ModelNotFound(model.to_string());
+        }
+        if lower.contains("context length") || lower.contains("context window") {
+            return OllamaError::ContextLengthExceeded {
+                model: model.to_string(),
+                message,
+            };
+        }
+        OllamaError::Api { status, message }
+    }
+
     /// Check if this error is retryable (transient)
     ///
     /// Only retry on:
     /// - Timeouts
     /// - Connection refused
+    /// - A model that is still loading
     /// - HTTP transport errors (connect, timeout)
     /// - Server errors (5xx) and rate limiting (429)
     ///
     /// Does NOT retry on client errors (4xx except 429).
     pub fn is_retryable(&self) -> bool {
         match self {
-            OllamaError::Timeout(_) | OllamaError::ConnectionRefused(_) => true,
+            OllamaError::Timeout(_)
+            | OllamaError::ConnectionRefused(_)
+            | OllamaError::ModelLoading(_) => true,
             OllamaError::HttpError(e) => {
                 // Retry on transport issues
                 if e.is_timeout() || e.is_connect() {
@@ -68,5 +128,109 @@ impl OllamaError {
     }
 }
 
+/// Whether a response means Ollama is still loading the model
+///
+/// Ollama answers 503 while a model is being loaded into memory, with a body
+/// mentioning the load (e.g. `{"error":"loading model"}`).
+pub fn is_model_loading_response(status: u16, body: &str) -> bool {
+    if status != 503 {
+        return false;
+    }
+    let body = body.to_ascii_lowercase();
+    body.contains("loading model") || body.contains("model is loading")
+}
+
 /// Result type for Ollama operations
 pub type OllamaResult<T> = Result<T, OllamaError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_loading_detection() {
+        assert!(is_model_loading_response(
+            503,
+            r#"{"error":"loading model"}"#
+        ));
+        assert!(is_model_loading_response(
+            503,
+            "Model is loading, please wait"
+        ));
+        assert!(!is_model_loading_response(
+            503,
+            r#"{"error":"server busy"}"#
+        ));
+        assert!(!is_model_loading_response(
+            500,
+            r#"{"error":"loading model"}"#
+        ));
+    }
+
+    #[test]
+    fn test_from_response() {
+        assert!(matches!(
+            OllamaError::from_response(503, r#"{"error":"loading model"}"#, "mistral"),
+            OllamaError::ModelLoading(ref m) if m == "mistral"
+        ));
+        assert!(matches!(
+            OllamaError::from_response(404, "model 'mistral' not found", "mistral"),
+            OllamaError::ModelNotFound(_)
+        ));
+        assert!(matches!(
+            OllamaError::from_response(503, "server busy", "mistral"),
+            OllamaError::Api { status: 503, ref message } if message == "server busy"
+        ));
+        assert!(OllamaError::ModelLoading("mistral".to_string()).is_retryable());
+    }
+
+    #[test]
+    fn test_parse_error_message() {
+        assert_eq!(
+            parse_error_message(r#"{"error":"model 'mistral' not found"}"#),
+            "model 'mistral' not found"
+        );
+        assert_eq!(parse_error_message("  Bad Gateway\n"), "Bad Gateway");
+        // JSON without an `error` field is kept as is
+        assert_eq!(
+            parse_error_message(r#"{"status":"x"}"#),
+            r#"{"status":"x"}"#
+        );
+        assert_eq!(parse_error_message(""), "");
+    }
+
+    #[test]
+    fn test_from_response_parses_ollama_error_payloads() {
+        assert!(matches!(
+            OllamaError::from_response(
+                404,
+                r#"{"error":"model \"llama3\" not found, try pulling it first"}"#,
+                "llama3"
+            ),
+            OllamaError::ModelNotFound(ref m) if m == "llama3"
+        ));
+        assert!(matches!(
+            OllamaError::from_response(503, r#"{"error":"loading model"}"#, "llama3"),
+            OllamaError::ModelLoading(ref m) if m == "llama3"
+        ));
+        assert!(matches!(
+            OllamaError::from_response(
+                400,
+                r#"{"error":"the input length exceeds the context length"}"#,
+                "nomic-embed-text"
+            ),
+            OllamaError::ContextLengthExceeded { ref model, ref message }
+                if model == "nomic-embed-text"
+                    && message == "the input length exceeds the context length"
+        ));
+        assert!(matches!(
+            OllamaError::from_response(400, r#"{"error":"invalid options: temperature"}"#, "llama3"),
+            OllamaError::Api { status: 400, ref message } if message == "invalid options: temperature"
+        ));
+        // Non-JSON bodies fall back to the raw text
+        assert!(matches!(
+            OllamaError::from_response(502, "<html>Bad Gateway</html>", "llama3"),
+            OllamaError::Api { status: 502, ref message } if message == "<html>Bad Gateway</html>"
+        ));
+    }
+}
@@ -54,23 +54,33 @@
 mod client;
 mod error;
 mod models;
+mod projection;
 
 pub use client::OllamaClient;
-pub use error::{OllamaError, OllamaResult};
+pub use error::{is_model_loading_response, parse_error_message, OllamaError, OllamaResult};
 pub use models::{
     ChatMessage, ChatRequest, ChatResponse, ChatRole, ChatStreamChunk, EmbeddingRequest,
     EmbeddingResponse, EnergyLevel, GenerateOptions, GenerateRequest, GenerateResponse,
     ListModelsResponse, ModelInfo, MoodAnalysis, Valence,
 };
+pub use projection::{reduce_embedding, REDUCED_EMBEDDING_DIMENSION};
 
 /// Expected embedding dimension for nomic-embed-text
-pub const EMBEDDING_DIMENSION: usize = 768;
+///
+/// This is the default; the dimension actually in use is
+/// `OllamaConfig::embedding_dimension` (see [`OllamaClient::embedding_dimension`]).
+pub const EMBEDDING_DIMENSION: usize = resonance_shared_config::DEFAULT_EMBEDDING_DIMENSION;
 
-/// Validate that an embedding has the expected dimension
+/// Validate that an embedding has the default [`EMBEDDING_DIMENSION`]
 pub fn validate_embedding_dimension(embedding: &[f32]) -> Result<(), OllamaError> {
-    if embedding.len() != EMBEDDING_DIMENSION {
+    check_embedding_dimension(embedding, EMBEDDING_DIMENSION)
+}
+
+/// Validate that an embedding has the `expected` dimension
+pub fn check_embedding_dimension(embedding: &[f32], expected: usize) -> Result<(), OllamaError> {
+    if embedding.len() != expected {
         return Err(OllamaError::DimensionMismatch {
-            expected: EMBEDDING_DIMENSION,
+            expected,
             actual: embedding.len(),
         });
     }
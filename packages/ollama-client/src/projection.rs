@@ -0,0 +1,189 @@
+//! Dimensionality reduction for embeddings
+//!
+//! Full 768-dim embeddings are projected down to [`REDUCED_EMBEDDING_DIMENSION`]
+//! dims with a fixed sparse random projection (Achlioptas): each matrix entry
+//! is +1 or -1 with probability 1/6 each and 0 otherwise. Random projections
+//! approximately preserve cosine similarity, so the reduced vectors can cheaply
+//! shortlist candidates that are then re-ranked with the full embedding.
+//!
+//! The matrix is derived from a fixed seed, so the worker (storing reduced
+//! track embeddings) and the API (reducing query embeddings) always agree.
+//! Changing [`PROJECTION_SEED`] invalidates every stored reduced embedding.
+
+use std::sync::OnceLock;
+
+use crate::{validate_embedding_dimension, OllamaError, EMBEDDING_DIMENSION};
+
+/// Dimension of reduced embeddings
+pub const REDUCED_EMBEDDING_DIMENSION: usize = 128;
+
+/// Seed for the projection matrix
+const PROJECTION_SEED: u64 = 0x5245_534f_4e41_4e43;
+
+/// Projection matrix, `REDUCED_EMBEDDING_DIMENSION` rows of `EMBEDDING_DIMENSION`
+fn projection_matrix() -> &'static [i8] {
+    static MATRIX: OnceLock<Vec<i8>> = OnceLock::new();
+    MATRIX.get_or_init(|| {
+        (0..REDUCED_EMBEDDING_DIMENSION * EMBEDDING_DIMENSION)
+            .map(
+                |index| match splitmix64(PROJECTION_SEED ^ index as u64) % 6 {
+                    0 => 1,
+                    1 => -1,
+                    _ => 0,
+                },
+            )
+            .collect()
+    })
+}
+
+/// SplitMix64 finalizer, a stable hash from entry index to matrix value
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Project a full embedding down to [`REDUCED_EMBEDDING_DIMENSION`] dims
+///
+/// The result is L2-normalized. Non-finite input values are treated as zero;
+/// an all-zero embedding reduces to an all-zero vector.
+///
+/// # Errors
+/// Returns `OllamaError::DimensionMismatch` if `embedding` is not
+/// [`EMBEDDING_DIMENSION`] long
+pub fn reduce_embedding(embedding: &[f32]) -> Result<Vec<f32>, OllamaError> {
+    validate_embedding_dimension(embedding)?;
+
+    let mut reduced: Vec<f32> = projection_matrix()
+        .chunks_exact(EMBEDDING_DIMENSION)
+        .map(|row| {
+            row.iter()
+                .zip(embedding)
+                .filter(|(sign, value)| **sign != 0 && value.is_finite())
+                .map(|(sign, value)| f32::from(*sign) * value)
+                .sum()
+        })
+        .collect();
+
+    let norm = reduced.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        reduced.iter_mut().for_each(|v| *v /= norm);
+    }
+
+    Ok(reduced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    /// Deterministic pseudo-random value in -1.0..1.0
+    fn noise(seed: u64) -> f32 {
+        (splitmix64(seed) >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    }
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+        let norm_a = a.iter().map(|v| v * v).sum::<f32>().sqrt();
+        let norm_b = b.iter().map(|v| v * v).sum::<f32>().sqrt();
+        dot / (norm_a * norm_b)
+    }
+
+    fn top_k(query: &[f32], vectors: &[Vec<f32>], indices: &[usize], k: usize) -> Vec<usize> {
+        let mut scored: Vec<(usize, f32)> = indices
+            .iter()
+            .map(|&i| (i, cosine(query, &vectors[i])))
+            .collect();
+        scored.sort_by(|a, b| b.1.total_cmp(&a.1));
+        scored.into_iter().take(k).map(|(i, _)| i).collect()
+    }
+
+    /// 1000 embeddings in 20 clusters of varying spread
+    fn seeded_embeddings() -> Vec<Vec<f32>> {
+        let centroids: Vec<Vec<f32>> = (0..20u64)
+            .map(|c| {
+                (0..EMBEDDING_DIMENSION as u64)
+                    .map(|d| noise(c * 10_000 + d))
+                    .collect()
+            })
+            .collect();
+
+        (0..1000u64)
+            .map(|i| {
+                let centroid = &centroids[(i % 20) as usize];
+                let spread = 0.5 + (i % 7) as f32 * 0.1;
+                centroid
+                    .iter()
+                    .enumerate()
+                    .map(|(d, c)| c + spread * noise(1_000_000 + i * 1_000 + d as u64))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reduce_embedding_dimension_and_norm() {
+        let embedding: Vec<f32> = (0..EMBEDDING_DIMENSION as u64).map(noise).collect();
+        let reduced = reduce_embedding(&embedding).unwrap();
+
+        assert_eq!(reduced.len(), REDUCED_EMBEDDING_DIMENSION);
+        let norm = reduced.iter().map(|v| v * v).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_reduce_embedding_is_deterministic() {
+        let embedding: Vec<f32> = (0..EMBEDDING_DIMENSION as u64).map(noise).collect();
+        assert_eq!(
+            reduce_embedding(&embedding).unwrap(),
+            reduce_embedding(&embedding).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_reduce_embedding_rejects_wrong_dimension() {
+        assert!(matches!(
+            reduce_embedding(&[0.1, 0.2]),
+            Err(OllamaError::DimensionMismatch {
+                expected: EMBEDDING_DIMENSION,
+                actual: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_reduce_embedding_zero_vector() {
+        let reduced = reduce_embedding(&[0.0; EMBEDDING_DIMENSION]).unwrap();
+        assert!(reduced.iter().all(|v| *v == 0.0));
+    }
+
+    #[test]
+    fn test_reduced_shortlist_reranked_matches_exact_search() {
+        let embeddings = seeded_embeddings();
+        let reduced: Vec<Vec<f32>> = embeddings
+            .iter()
+            .map(|e| reduce_embedding(e).unwrap())
+            .collect();
+        let all: Vec<usize> = (0..embeddings.len()).collect();
+
+        for q in [3u64, 17, 250] {
+            let query: Vec<f32> = embeddings[q as usize]
+                .iter()
+                .enumerate()
+                .map(|(d, v)| v + 0.3 * noise(9_000_000 + q * 1_000 + d as u64))
+                .collect();
+            let reduced_query = reduce_embedding(&query).unwrap();
+
+            let exact: HashSet<usize> = top_k(&query, &embeddings, &all, 10).into_iter().collect();
+            let shortlist = top_k(&reduced_query, &reduced, &all, 100);
+            let fast: HashSet<usize> = top_k(&query, &embeddings, &shortlist, 10)
+                .into_iter()
+                .collect();
+
+            let overlap = exact.intersection(&fast).count();
+            assert!(overlap >= 8, "query {}: overlap {}/10", q, overlap);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Cache expiry helpers
+
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Default TTL jitter, as a percentage of the base TTL
+pub const DEFAULT_TTL_JITTER_PCT: f32 = 10.0;
+
+/// Spread a cache TTL randomly by up to `jitter_pct` percent either way
+///
+/// Entries written together with the same TTL would otherwise all expire at
+/// once and be recomputed in a burst. `jitter_pct` is clamped to `0..=100`;
+/// zero (or a zero `base`) returns `base` unchanged.
+pub fn ttl_with_jitter(base: Duration, jitter_pct: f32) -> Duration {
+    let jitter = f64::from(jitter_pct.clamp(0.0, 100.0)) / 100.0;
+    if jitter == 0.0 || base.is_zero() {
+        return base;
+    }
+
+    let factor = rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter);
+    base.mul_f64(factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_ttl_within_bounds_and_varies() {
+        let base = Duration::from_secs(600);
+        let ttls: Vec<Duration> = (0..200)
+            .map(|_| ttl_with_jitter(base, DEFAULT_TTL_JITTER_PCT))
+            .collect();
+
+        for ttl in &ttls {
+            assert!(*ttl >= Duration::from_secs(540), "{:?} below bound", ttl);
+            assert!(*ttl <= Duration::from_secs(660), "{:?} above bound", ttl);
+        }
+
+        let distinct: HashSet<Duration> = ttls.into_iter().collect();
+        assert!(distinct.len() > 1, "TTLs should vary across calls");
+    }
+
+    #[test]
+    fn test_zero_jitter_returns_base() {
+        let base = Duration::from_secs(60);
+        assert_eq!(ttl_with_jitter(base, 0.0), base);
+        assert_eq!(ttl_with_jitter(base, -5.0), base);
+        assert_eq!(ttl_with_jitter(Duration::ZERO, 10.0), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_jitter_clamped_to_full_range() {
+        let base = Duration::from_secs(100);
+        for _ in 0..100 {
+            assert!(ttl_with_jitter(base, 500.0) <= Duration::from_secs(200));
+        }
+    }
+}
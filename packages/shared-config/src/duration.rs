@@ -0,0 +1,118 @@
+//! Human-readable duration parsing
+
+use std::env;
+use std::time::Duration;
+
+use crate::error::{ConfigError, ConfigResult};
+
+/// Parse a duration such as `"30s"`, `"15m"`, `"12h"`, `"7d"` or `"2w"`
+///
+/// A bare number is taken as seconds. The value must be a positive whole
+/// number followed by at most one unit; anything else is an error describing
+/// what was wrong.
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err("duration is empty".to_string());
+    }
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{}' does not start with a number", value))?;
+    if number == 0 {
+        return Err(format!("'{}' must be greater than zero", value));
+    }
+
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 24 * 3600,
+        "w" => 7 * 24 * 3600,
+        _ => {
+            return Err(format!(
+                "unknown unit '{}' in '{}' (expected s, m, h, d or w)",
+                unit, value
+            ))
+        }
+    };
+
+    number
+        .checked_mul(multiplier)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("'{}' is too large", value))
+}
+
+/// Parse a duration environment variable, using `default` when it is unset
+pub fn parse_duration_env(name: &str, default: &str) -> ConfigResult<Duration> {
+    let value = env::var(name).unwrap_or_else(|_| default.to_string());
+    parse_duration(&value).map_err(|e| ConfigError::InvalidValue(name.to_string(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parses_each_unit() {
+        assert_eq!(parse_duration("30s"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("15m"), Ok(Duration::from_secs(15 * 60)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 3600)));
+        assert_eq!(parse_duration("7d"), Ok(Duration::from_secs(7 * 24 * 3600)));
+        assert_eq!(
+            parse_duration("2w"),
+            Ok(Duration::from_secs(14 * 24 * 3600))
+        );
+    }
+
+    #[test]
+    fn test_bare_number_is_seconds() {
+        assert_eq!(parse_duration("900"), Ok(Duration::from_secs(900)));
+        assert_eq!(parse_duration(" 45 "), Ok(Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn test_rejects_invalid_values() {
+        for value in [
+            "", "   ", "invalid", "15x", "m", "-5m", "0s", "1.5h", "15 m", "7dd",
+        ] {
+            assert!(
+                parse_duration(value).is_err(),
+                "'{}' should be rejected",
+                value
+            );
+        }
+        assert!(parse_duration("99999999999999999w").is_err());
+    }
+
+    #[test]
+    fn test_env_error_names_variable() {
+        let name = "RESONANCE_TEST_DURATION_INVALID";
+        env::set_var(name, "soon");
+        let err = parse_duration_env(name, "15m").unwrap_err();
+        env::remove_var(name);
+
+        match err {
+            ConfigError::InvalidValue(var, message) => {
+                assert_eq!(var, name);
+                assert!(message.contains("soon"));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env_default_when_unset() {
+        let name = "RESONANCE_TEST_DURATION_UNSET";
+        env::remove_var(name);
+        assert_eq!(
+            parse_duration_env(name, "7d").unwrap(),
+            Duration::from_secs(7 * 24 * 3600)
+        );
+    }
+}
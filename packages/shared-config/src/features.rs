@@ -0,0 +1,147 @@
+//! Feature flags
+//!
+//! Optional features can be switched on or off per deployment without a
+//! code change. Each flag is read from a `FEATURE_<NAME>` environment
+//! variable (`true`/`false`, `1`/`0`, `yes`/`no` or `on`/`off`); unset flags
+//! keep their default.
+
+use std::env;
+
+use crate::{ConfigError, ConfigResult};
+
+/// Per-deployment switches for optional features
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlags {
+    semantic_search: bool,
+    chat_quotas: bool,
+}
+
+impl Default for FeatureFlags {
+    fn default() -> Self {
+        Self {
+            semantic_search: true,
+            chat_quotas: true,
+        }
+    }
+}
+
+impl FeatureFlags {
+    /// Load feature flags from environment variables
+    ///
+    /// Environment variables:
+    /// - `FEATURE_SEMANTIC_SEARCH` (default: true)
+    /// - `FEATURE_CHAT_QUOTAS` (default: true)
+    pub fn from_env() -> ConfigResult<Self> {
+        Self::from_lookup(|name| env::var(name).ok())
+    }
+
+    /// Load feature flags using `lookup` to read each variable
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> ConfigResult<Self> {
+        let defaults = Self::default();
+        let flag = |name: &str, default: bool| match lookup(name) {
+            Some(value) => parse_flag(name, &value),
+            None => Ok(default),
+        };
+
+        Ok(Self {
+            semantic_search: flag("FEATURE_SEMANTIC_SEARCH", defaults.semantic_search)?,
+            chat_quotas: flag("FEATURE_CHAT_QUOTAS", defaults.chat_quotas)?,
+        })
+    }
+
+    /// Whether natural-language semantic search is offered
+    pub fn semantic_search(&self) -> bool {
+        self.semantic_search
+    }
+
+    /// Whether per-user chat message quotas are enforced
+    pub fn chat_quotas(&self) -> bool {
+        self.chat_quotas
+    }
+
+    /// Set whether semantic search is offered
+    pub fn with_semantic_search(mut self, enabled: bool) -> Self {
+        self.semantic_search = enabled;
+        self
+    }
+
+    /// Set whether chat quotas are enforced
+    pub fn with_chat_quotas(mut self, enabled: bool) -> Self {
+        self.chat_quotas = enabled;
+        self
+    }
+}
+
+/// Parse a boolean flag value, rejecting anything unrecognized
+fn parse_flag(name: &str, value: &str) -> ConfigResult<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(ConfigError::InvalidValue(
+            name.to_string(),
+            format!("expected true or false, got '{}'", value),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn flags_from(vars: &[(&str, &str)]) -> ConfigResult<FeatureFlags> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        FeatureFlags::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults_when_absent() {
+        let flags = flags_from(&[]).unwrap();
+
+        assert_eq!(flags, FeatureFlags::default());
+        assert!(flags.semantic_search());
+        assert!(flags.chat_quotas());
+    }
+
+    #[test]
+    fn test_parses_flags_from_env() {
+        let flags = flags_from(&[
+            ("FEATURE_SEMANTIC_SEARCH", "0"),
+            ("FEATURE_CHAT_QUOTAS", " Off "),
+        ])
+        .unwrap();
+
+        assert!(!flags.semantic_search());
+        assert!(!flags.chat_quotas());
+    }
+
+    #[test]
+    fn test_unset_flags_keep_defaults() {
+        let flags = flags_from(&[("FEATURE_CHAT_QUOTAS", "no")]).unwrap();
+
+        assert!(flags.semantic_search());
+        assert!(!flags.chat_quotas());
+    }
+
+    #[test]
+    fn test_rejects_invalid_value() {
+        let err = flags_from(&[("FEATURE_CHAT_QUOTAS", "sometimes")]).unwrap_err();
+
+        assert!(
+            matches!(err, ConfigError::InvalidValue(ref name, _) if name == "FEATURE_CHAT_QUOTAS")
+        );
+    }
+
+    #[test]
+    fn test_builder_overrides() {
+        let flags = FeatureFlags::default()
+            .with_semantic_search(false)
+            .with_chat_quotas(false);
+
+        assert!(!flags.semantic_search());
+        assert!(!flags.chat_quotas());
+    }
+}
@@ -3,20 +3,35 @@
 //! This crate provides common configuration types used by both the API
 //! and worker services, ensuring consistency across the application.
 
+mod cache;
 mod database;
+mod duration;
 mod error;
+mod features;
 mod lidarr;
+mod logging;
+mod migrations;
 mod ollama;
 mod redis;
+mod secret;
 
+pub use cache::{ttl_with_jitter, DEFAULT_TTL_JITTER_PCT};
 pub use database::DatabaseConfig;
+pub use duration::{parse_duration, parse_duration_env};
 pub use error::{ConfigError, ConfigResult};
+pub use features::FeatureFlags;
 pub use lidarr::LidarrConfig;
-pub use ollama::OllamaConfig;
+pub use logging::{init_tracing, LogFormat};
+pub use migrations::{pgvector_available, pgvector_installed, run_migrations, MigrationSet};
+pub use ollama::{OllamaConfig, DEFAULT_EMBEDDING_DIMENSION};
 pub use redis::RedisConfig;
+pub use secret::Redacted;
 
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Music library root used when neither library variable is set
+const DEFAULT_MUSIC_LIBRARY_PATH: &str = "/music";
 
 /// Common configuration shared between all services
 #[derive(Debug, Clone)]
@@ -27,8 +42,11 @@ pub struct CommonConfig {
     /// Redis configuration
     pub redis: RedisConfig,
 
-    /// Path to music library
-    pub music_library_path: PathBuf,
+    /// Music library root directories (never empty)
+    pub music_library_paths: Vec<PathBuf>,
+
+    /// Directory cover art extracted from audio files is written to
+    pub extracted_art_path: PathBuf,
 
     /// Lidarr integration configuration (optional)
     pub lidarr: Option<LidarrConfig>,
@@ -36,6 +54,9 @@ pub struct CommonConfig {
     /// Ollama AI configuration
     pub ollama: OllamaConfig,
 
+    /// Optional features enabled for this deployment
+    pub features: FeatureFlags,
+
     /// Environment mode (development, staging, production)
     pub environment: Environment,
 
@@ -92,11 +113,18 @@ impl CommonConfig {
         Ok(Self {
             database: DatabaseConfig::from_env()?,
             redis: RedisConfig::from_env()?,
-            music_library_path: PathBuf::from(
-                env::var("MUSIC_LIBRARY_PATH").unwrap_or_else(|_| "/music".to_string()),
+            music_library_paths: parse_music_roots(
+                env::var("MUSIC_LIBRARY_PATHS").ok().as_deref(),
+                env::var("MUSIC_LIBRARY_PATH").ok().as_deref(),
             ),
+            extracted_art_path: env::var("EXTRACTED_ART_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| env::temp_dir().join("resonance-extracted-art")),
             lidarr: LidarrConfig::from_env().ok(),
             ollama: OllamaConfig::from_env()?,
+            features: FeatureFlags::from_env()?,
             environment: env::var("ENVIRONMENT")
                 .unwrap_or_else(|_| "development".to_string())
                 .parse()
@@ -111,6 +139,79 @@ impl CommonConfig {
     pub fn has_lidarr(&self) -> bool {
         self.lidarr.is_some()
     }
+
+    /// Music library root directories, in configured order
+    pub fn music_roots(&self) -> &[PathBuf] {
+        &self.music_library_paths
+    }
+
+    /// Validate that at least one music library root exists and is readable
+    ///
+    /// Roots that are missing or unreadable are reported in the error; a
+    /// library spread over several mounts still starts if one mount is down.
+    pub fn validate_music_roots(&self) -> ConfigResult<()> {
+        validate_music_roots(&self.music_library_paths)
+    }
+}
+
+/// Parse music library roots from `MUSIC_LIBRARY_PATHS` and `MUSIC_LIBRARY_PATH`
+///
+/// `paths` is a list in the platform's `PATH` format (`:`-separated on Unix,
+/// `;`-separated on Windows). If it is unset or contains no entries, `single`
+/// is used as the only root, falling back to `/music`.
+pub fn parse_music_roots(paths: Option<&str>, single: Option<&str>) -> Vec<PathBuf> {
+    let roots: Vec<PathBuf> = paths
+        .map(|paths| {
+            env::split_paths(paths)
+                .filter(|path| !path.as_os_str().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if !roots.is_empty() {
+        return roots;
+    }
+
+    let single = single
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .unwrap_or(DEFAULT_MUSIC_LIBRARY_PATH);
+    vec![PathBuf::from(single)]
+}
+
+/// Validate that at least one of `roots` is a readable directory
+pub fn validate_music_roots(roots: &[PathBuf]) -> ConfigResult<()> {
+    let problems: Vec<String> = roots
+        .iter()
+        .filter_map(|root| {
+            check_readable_dir(root)
+                .err()
+                .map(|reason| format!("{} ({})", root.display(), reason))
+        })
+        .collect();
+
+    if problems.len() < roots.len() {
+        return Ok(());
+    }
+
+    Err(ConfigError::InvalidValue(
+        "MUSIC_LIBRARY_PATHS".to_string(),
+        if problems.is_empty() {
+            "no music library roots configured".to_string()
+        } else {
+            format!("no readable music library root: {}", problems.join(", "))
+        },
+    ))
+}
+
+/// Check that a path is a directory whose entries can be listed
+fn check_readable_dir(path: &Path) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err("not a directory".to_string());
+    }
+    std::fs::read_dir(path)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
 }
 
 /// Helper function to get a required environment variable
@@ -180,6 +281,58 @@ mod tests {
         assert_eq!(format!("{}", Environment::Development), "development");
     }
 
+    #[test]
+    fn test_parse_single_music_root() {
+        assert_eq!(
+            parse_music_roots(None, Some("/srv/music")),
+            vec![PathBuf::from("/srv/music")]
+        );
+        assert_eq!(parse_music_roots(None, None), vec![PathBuf::from("/music")]);
+        assert_eq!(
+            parse_music_roots(Some(""), Some(" ")),
+            vec![PathBuf::from("/music")]
+        );
+    }
+
+    #[test]
+    fn test_parse_multiple_music_roots() {
+        let paths = env::join_paths(["/mnt/a/music", "/mnt/b/music"]).unwrap();
+
+        let roots = parse_music_roots(paths.to_str(), Some("/ignored"));
+
+        assert_eq!(
+            roots,
+            vec![PathBuf::from("/mnt/a/music"), PathBuf::from("/mnt/b/music")]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_parse_music_roots_skips_empty_entries() {
+        assert_eq!(
+            parse_music_roots(Some("/mnt/a::/mnt/b:"), None),
+            vec![PathBuf::from("/mnt/a"), PathBuf::from("/mnt/b")]
+        );
+    }
+
+    #[test]
+    fn test_validate_music_roots() {
+        let existing = env::temp_dir();
+        let missing = existing.join("resonance-missing-music-root-7f3a");
+
+        assert!(validate_music_roots(&[missing.clone(), existing]).is_ok());
+
+        let err = validate_music_roots(&[missing]).unwrap_err();
+        assert!(
+            matches!(err, ConfigError::InvalidValue(ref name, _) if name == "MUSIC_LIBRARY_PATHS")
+        );
+        assert!(err
+            .to_string()
+            .contains("resonance-missing-music-root-7f3a"));
+
+        assert!(validate_music_roots(&[]).is_err());
+    }
+
     #[test]
     fn test_environment_checks() {
         assert!(Environment::Production.is_production());
@@ -2,6 +2,7 @@
 
 use crate::{get_required_env, parse_env, ConfigError, ConfigResult};
 use std::env;
+use std::time::Duration;
 
 /// Lidarr music library manager configuration
 #[derive(Debug, Clone)]
@@ -17,6 +18,13 @@ pub struct LidarrConfig {
 
     /// Request timeout in seconds
     pub timeout_secs: u64,
+
+    /// Maximum attempts for idempotent requests that fail transiently
+    /// (5xx responses, connection errors); mutating requests are never retried
+    pub retry_attempts: u32,
+
+    /// Base delay in milliseconds for exponential backoff between retries
+    pub retry_base_delay_ms: u64,
 }
 
 impl LidarrConfig {
@@ -49,6 +57,8 @@ impl LidarrConfig {
             api_key,
             sync_interval_secs: parse_env("LIDARR_SYNC_INTERVAL", 3600)?, // Default: 1 hour
             timeout_secs: parse_env("LIDARR_TIMEOUT", 30)?,
+            retry_attempts: parse_env("LIDARR_RETRY_ATTEMPTS", 3)?,
+            retry_base_delay_ms: parse_env("LIDARR_RETRY_BASE_DELAY_MS", 500)?,
         })
     }
 
@@ -64,9 +74,24 @@ impl LidarrConfig {
             api_key: api_key.into(),
             sync_interval_secs: 3600,
             timeout_secs: 30,
+            retry_attempts: 3,
+            retry_base_delay_ms: 500,
         }
     }
 
+    /// Set the retry policy for idempotent requests
+    pub fn with_retry_config(mut self, attempts: u32, base_delay_ms: u64) -> Self {
+        self.retry_attempts = attempts;
+        self.retry_base_delay_ms = base_delay_ms;
+        self
+    }
+
+    /// Backoff delay before the given retry (1-based): `base * 2^(retry - 1)`
+    pub fn retry_delay(&self, retry: u32) -> Duration {
+        let factor = 2u64.saturating_pow(retry.saturating_sub(1));
+        Duration::from_millis(self.retry_base_delay_ms.saturating_mul(factor))
+    }
+
     /// Get the full URL for the API endpoint
     pub fn api_url(&self, path: &str) -> String {
         let base = self.url.trim_end_matches('/');
@@ -93,6 +118,17 @@ mod tests {
         assert_eq!(config.url, "http://lidarr:8686");
         assert_eq!(config.api_key, "test-api-key");
         assert_eq!(config.sync_interval_secs, 3600);
+        assert_eq!(config.retry_attempts, 3);
+        assert_eq!(config.retry_base_delay_ms, 500);
+    }
+
+    #[test]
+    fn test_retry_delay_backoff() {
+        let config = LidarrConfig::new("http://lidarr:8686", "key").with_retry_config(4, 100);
+        assert_eq!(config.retry_attempts, 4);
+        assert_eq!(config.retry_delay(1), Duration::from_millis(100));
+        assert_eq!(config.retry_delay(2), Duration::from_millis(200));
+        assert_eq!(config.retry_delay(3), Duration::from_millis(400));
     }
 
     #[test]
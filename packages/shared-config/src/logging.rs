@@ -0,0 +1,108 @@
+//! Log output configuration
+//!
+//! `LOG_FORMAT` selects between the human-readable formatter (`pretty`, the
+//! default) and one JSON object per line (`json`) for log ingestion.
+//! Verbosity still comes from `RUST_LOG`.
+
+use std::str::FromStr;
+
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+
+use crate::ConfigError;
+
+/// Log output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// Human-readable, multi-field lines
+    #[default]
+    Pretty,
+    /// One JSON object per event
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            other => Err(ConfigError::InvalidValue(
+                "LOG_FORMAT".to_string(),
+                format!("expected 'pretty' or 'json', got '{}'", other),
+            )),
+        }
+    }
+}
+
+impl LogFormat {
+    /// Load the log format from `LOG_FORMAT`
+    ///
+    /// Unknown values fall back to pretty. The warning goes to stderr because
+    /// this runs before the tracing subscriber exists.
+    pub fn from_env() -> Self {
+        let (format, warning) = Self::from_env_value(std::env::var("LOG_FORMAT").ok().as_deref());
+        if let Some(warning) = warning {
+            eprintln!("WARN {}", warning);
+        }
+        format
+    }
+
+    /// Parse an optional `LOG_FORMAT` value, defaulting to pretty
+    ///
+    /// Returns a warning message alongside the fallback for invalid values.
+    pub fn from_env_value(value: Option<&str>) -> (Self, Option<String>) {
+        match value.filter(|v| !v.trim().is_empty()).map(str::parse) {
+            None => (Self::default(), None),
+            Some(Ok(format)) => (format, None),
+            Some(Err(e)) => (Self::default(), Some(format!("{}; using pretty logs", e))),
+        }
+    }
+}
+
+/// Install the global tracing subscriber
+///
+/// `log_level` is the filter used when `RUST_LOG` is unset.
+pub fn init_tracing(log_level: &str, log_format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| log_level.into());
+    let registry = tracing_subscriber::registry().with(filter);
+
+    match log_format {
+        LogFormat::Pretty => registry.with(tracing_subscriber::fmt::layer()).init(),
+        LogFormat::Json => registry
+            .with(tracing_subscriber::fmt::layer().json())
+            .init(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!("pretty".parse::<LogFormat>().unwrap(), LogFormat::Pretty);
+        assert_eq!("json".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert_eq!(" JSON ".parse::<LogFormat>().unwrap(), LogFormat::Json);
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_log_format_from_env_value() {
+        assert_eq!(LogFormat::from_env_value(None), (LogFormat::Pretty, None));
+        assert_eq!(
+            LogFormat::from_env_value(Some("")),
+            (LogFormat::Pretty, None)
+        );
+        assert_eq!(
+            LogFormat::from_env_value(Some("json")),
+            (LogFormat::Json, None)
+        );
+
+        let (format, warning) = LogFormat::from_env_value(Some("xml"));
+        assert_eq!(format, LogFormat::Pretty);
+        let warning = warning.expect("invalid value should warn");
+        assert!(warning.contains("LOG_FORMAT"));
+        assert!(warning.contains("xml"));
+    }
+}
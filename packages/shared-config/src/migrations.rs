@@ -0,0 +1,166 @@
+//! Database migration runner
+//!
+//! The API and worker apply the same migrations on startup. pgvector is
+//! optional, but the shipped core migrations create the extension and a few
+//! vector columns, and their checksums are recorded in every existing
+//! database, so they cannot change. Instead, each core migration that needs
+//! pgvector has a vector-free variant under the same version. On a server
+//! without pgvector the variants' SQL runs in place of the originals, keeping
+//! the originals' checksums, and the vector schema is added later by the
+//! idempotent pgvector migrations once the extension becomes available.
+//!
+//! `sqlx::migrate!` resolves its path relative to the crate that invokes it,
+//! so each binary builds its own [`MigrationSet`] and passes it to
+//! [`run_migrations`].
+
+use futures_core::future::BoxFuture;
+use sqlx::error::BoxDynError;
+use sqlx::migrate::{MigrateError, Migration, MigrationSource, Migrator};
+use sqlx::PgPool;
+
+/// The migrations a service applies on startup
+pub struct MigrationSet {
+    /// Migrations every database gets
+    pub core: Migrator,
+    /// Vector-free variants of the core migrations that need pgvector
+    pub without_pgvector: Migrator,
+    /// Migrations that need pgvector, all idempotent
+    pub pgvector: Migrator,
+}
+
+impl MigrationSet {
+    /// The core migrations with their vector-free variants swapped in
+    ///
+    /// Each variant keeps the checksum of the migration it replaces, so a
+    /// database migrated either way validates against both.
+    pub async fn core_without_pgvector(&self) -> Result<Migrator, MigrateError> {
+        let migrations = self
+            .core
+            .iter()
+            .map(|migration| {
+                match self
+                    .without_pgvector
+                    .iter()
+                    .find(|variant| variant.version == migration.version)
+                {
+                    Some(variant) => Migration {
+                        sql: variant.sql.clone(),
+                        ..migration.clone()
+                    },
+                    None => migration.clone(),
+                }
+            })
+            .collect();
+
+        let mut migrator = Migrator::new(Resolved(migrations)).await?;
+        migrator.set_ignore_missing(true);
+        Ok(migrator)
+    }
+}
+
+/// Apply the core migrations, then the pgvector migrations if possible
+///
+/// Returns whether pgvector is installed afterwards. Without the extension
+/// the core migrations run with their vector-free variants and the pgvector
+/// migrations are skipped. If the server offers pgvector but creating it
+/// fails (e.g. the database role may not create extensions) the error is
+/// logged and the service runs without it.
+///
+/// Each migrator holds a Postgres advisory lock while it runs, so the API and
+/// worker, which both migrate on startup, take turns.
+pub async fn run_migrations(pool: &PgPool, set: &MigrationSet) -> Result<bool, MigrateError> {
+    if !pgvector_available(pool).await? {
+        set.core_without_pgvector().await?.run(pool).await?;
+        return Ok(false);
+    }
+
+    match set.core.run(pool).await {
+        Ok(()) => {}
+        Err(e) if !pgvector_installed(pool).await? => {
+            tracing::error!(error = %e, "Could not create the pgvector extension");
+            set.core_without_pgvector().await?.run(pool).await?;
+            return Ok(false);
+        }
+        Err(e) => return Err(e),
+    }
+
+    match set.pgvector.run(pool).await {
+        Ok(()) => Ok(true),
+        Err(e) if !pgvector_installed(pool).await? => {
+            tracing::error!(error = %e, "Could not create the pgvector extension");
+            Ok(false)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Check whether the database server offers the pgvector extension
+///
+/// True when it is installed or could be created with `CREATE EXTENSION`.
+pub async fn pgvector_available(db: &PgPool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS (SELECT 1 FROM pg_available_extensions WHERE name = 'vector')",
+    )
+    .fetch_one(db)
+    .await
+}
+
+/// Check whether the pgvector extension is installed in the database
+pub async fn pgvector_installed(db: &PgPool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = 'vector')")
+        .fetch_one(db)
+        .await
+}
+
+/// Migrations that are already loaded
+#[derive(Debug)]
+struct Resolved(Vec<Migration>);
+
+impl MigrationSource<'static> for Resolved {
+    fn resolve(self) -> BoxFuture<'static, Result<Vec<Migration>, BoxDynError>> {
+        Box::pin(async move { Ok(self.0) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::migrate::MigrationType;
+    use std::borrow::Cow;
+
+    fn migration(version: i64, sql: &'static str) -> Migration {
+        Migration::new(
+            version,
+            Cow::Borrowed("test"),
+            MigrationType::Simple,
+            Cow::Borrowed(sql),
+            false,
+        )
+    }
+
+    async fn migrator(migrations: Vec<Migration>) -> Migrator {
+        Migrator::new(Resolved(migrations)).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_variants_keep_original_checksums() {
+        let core = vec![
+            migration(1, "CREATE EXTENSION vector;"),
+            migration(2, "CREATE TABLE t ();"),
+        ];
+        let set = MigrationSet {
+            core: migrator(core.clone()).await,
+            without_pgvector: migrator(vec![migration(1, "SELECT 1;")]).await,
+            pgvector: migrator(vec![]).await,
+        };
+
+        let swapped = set.core_without_pgvector().await.unwrap();
+        let swapped: Vec<_> = swapped.iter().collect();
+
+        assert_eq!(swapped.len(), 2);
+        assert_eq!(swapped[0].sql, "SELECT 1;");
+        assert_eq!(swapped[0].checksum, core[0].checksum);
+        assert_eq!(swapped[1].sql, core[1].sql);
+        assert_eq!(swapped[1].checksum, core[1].checksum);
+    }
+}
@@ -2,6 +2,9 @@
 
 use crate::{get_env_or_default, parse_env, ConfigResult};
 
+/// Embedding dimension of the default embedding model (nomic-embed-text)
+pub const DEFAULT_EMBEDDING_DIMENSION: usize = 768;
+
 /// Ollama AI service configuration
 #[derive(Debug, Clone)]
 pub struct OllamaConfig {
@@ -14,6 +17,12 @@ pub struct OllamaConfig {
     /// Embedding model for vector search (e.g., nomic-embed-text)
     pub embedding_model: String,
 
+    /// Dimension of vectors produced by the embedding model
+    ///
+    /// Must match the pgvector embedding columns; change both together when
+    /// switching embedding models.
+    pub embedding_dimension: usize,
+
     /// Request timeout in seconds
     pub timeout_secs: u64,
 
@@ -22,6 +31,11 @@ pub struct OllamaConfig {
 
     /// Temperature for generation (0.0 - 1.0)
     pub temperature: f32,
+
+    /// Load the chat and embedding models when a client is created
+    ///
+    /// Avoids the cold-start delay on the first real request.
+    pub warm_up: bool,
 }
 
 impl OllamaConfig {
@@ -31,9 +45,11 @@ impl OllamaConfig {
             url: get_env_or_default("OLLAMA_URL", "http://localhost:11434"),
             model: get_env_or_default("OLLAMA_MODEL", "mistral"),
             embedding_model: get_env_or_default("EMBEDDING_MODEL", "nomic-embed-text"),
+            embedding_dimension: parse_env("EMBEDDING_DIMENSION", DEFAULT_EMBEDDING_DIMENSION)?,
             timeout_secs: parse_env("OLLAMA_TIMEOUT", 60)?,
             max_tokens: parse_env("OLLAMA_MAX_TOKENS", 2048)?,
             temperature: parse_env("OLLAMA_TEMPERATURE", 0.7)?,
+            warm_up: parse_env("OLLAMA_WARM_UP", false)?,
         })
     }
 
@@ -43,25 +59,37 @@ impl OllamaConfig {
             url: url.into(),
             model: "mistral".to_string(),
             embedding_model: "nomic-embed-text".to_string(),
+            embedding_dimension: DEFAULT_EMBEDDING_DIMENSION,
             timeout_secs: 60,
             max_tokens: 2048,
             temperature: 0.7,
+            warm_up: false,
         }
     }
 
+    /// Join an API path onto the base URL
+    ///
+    /// Trailing slashes on the base URL are dropped so that `http://host/` and
+    /// `http://host` both produce `http://host/api/...`, and any path prefix
+    /// (e.g. Ollama behind a reverse proxy at `/ollama`) is preserved.
+    fn endpoint_url(&self, path: &str) -> String {
+        let base = self.url.trim().trim_end_matches('/');
+        format!("{}/api/{}", base, path.trim_start_matches('/'))
+    }
+
     /// Get the full URL for the generation endpoint
     pub fn generate_url(&self) -> String {
-        format!("{}/api/generate", self.url.trim_end_matches('/'))
+        self.endpoint_url("generate")
     }
 
     /// Get the full URL for the embeddings endpoint
-    pub fn embeddings_url(&self) -> String {
-        format!("{}/api/embeddings", self.url.trim_end_matches('/'))
+    pub fn embedding_url(&self) -> String {
+        self.endpoint_url("embeddings")
     }
 
     /// Get the full URL for the chat endpoint
     pub fn chat_url(&self) -> String {
-        format!("{}/api/chat", self.url.trim_end_matches('/'))
+        self.endpoint_url("chat")
     }
 }
 
@@ -71,9 +99,11 @@ impl Default for OllamaConfig {
             url: "http://localhost:11434".to_string(),
             model: "mistral".to_string(),
             embedding_model: "nomic-embed-text".to_string(),
+            embedding_dimension: DEFAULT_EMBEDDING_DIMENSION,
             timeout_secs: 60,
             max_tokens: 2048,
             temperature: 0.7,
+            warm_up: false,
         }
     }
 }
@@ -88,6 +118,8 @@ mod tests {
         assert_eq!(config.url, "http://localhost:11434");
         assert_eq!(config.model, "mistral");
         assert_eq!(config.embedding_model, "nomic-embed-text");
+        assert_eq!(config.embedding_dimension, DEFAULT_EMBEDDING_DIMENSION);
+        assert!(!config.warm_up);
     }
 
     #[test]
@@ -101,7 +133,7 @@ mod tests {
         let config = OllamaConfig::default();
         assert_eq!(config.generate_url(), "http://localhost:11434/api/generate");
         assert_eq!(
-            config.embeddings_url(),
+            config.embedding_url(),
             "http://localhost:11434/api/embeddings"
         );
         assert_eq!(config.chat_url(), "http://localhost:11434/api/chat");
@@ -111,5 +143,40 @@ mod tests {
     fn test_endpoint_urls_with_trailing_slash() {
         let config = OllamaConfig::with_url("http://localhost:11434/");
         assert_eq!(config.generate_url(), "http://localhost:11434/api/generate");
+        assert_eq!(
+            config.embedding_url(),
+            "http://localhost:11434/api/embeddings"
+        );
+        assert_eq!(config.chat_url(), "http://localhost:11434/api/chat");
+    }
+
+    #[test]
+    fn test_endpoint_urls_with_multiple_trailing_slashes() {
+        let config = OllamaConfig::with_url("http://localhost:11434//");
+        assert_eq!(config.chat_url(), "http://localhost:11434/api/chat");
+    }
+
+    #[test]
+    fn test_endpoint_urls_with_path_prefix() {
+        let config = OllamaConfig::with_url("https://example.com/ollama");
+        assert_eq!(
+            config.generate_url(),
+            "https://example.com/ollama/api/generate"
+        );
+        assert_eq!(
+            config.embedding_url(),
+            "https://example.com/ollama/api/embeddings"
+        );
+        assert_eq!(config.chat_url(), "https://example.com/ollama/api/chat");
+    }
+
+    #[test]
+    fn test_endpoint_urls_with_path_prefix_and_trailing_slash() {
+        let config = OllamaConfig::with_url("https://example.com/ollama/");
+        assert_eq!(config.chat_url(), "https://example.com/ollama/api/chat");
+        assert_eq!(
+            config.embedding_url(),
+            "https://example.com/ollama/api/embeddings"
+        );
     }
 }
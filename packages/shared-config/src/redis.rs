@@ -1,66 +1,76 @@
 //! Redis configuration types
 
-use crate::{get_env_or_default, parse_env, ConfigResult};
+use crate::{get_env_or_default, parse_env, ConfigResult, Redacted, DEFAULT_TTL_JITTER_PCT};
 
 /// Redis configuration
 #[derive(Debug, Clone)]
 pub struct RedisConfig {
-    /// Redis connection URL
-    pub url: String,
+    /// Redis connection URL (redacted because it may embed a password)
+    pub url: Redacted<String>,
 
     /// Optional password for Redis authentication
-    pub password: Option<String>,
+    pub password: Option<Redacted<String>>,
 
     /// Connection pool size
     pub pool_size: u32,
 
     /// Connection timeout in seconds
     pub connect_timeout_secs: u64,
+
+    /// Random spread applied to cache TTLs, in percent either way
+    pub ttl_jitter_pct: f32,
 }
 
 impl RedisConfig {
     /// Load Redis configuration from environment variables
     pub fn from_env() -> ConfigResult<Self> {
         Ok(Self {
-            url: get_env_or_default("REDIS_URL", "redis://localhost:6379"),
+            url: get_env_or_default("REDIS_URL", "redis://localhost:6379").into(),
             password: std::env::var("REDIS_PASSWORD")
                 .ok()
-                .filter(|s| !s.is_empty()),
+                .filter(|s| !s.is_empty())
+                .map(Redacted::new),
             pool_size: parse_env("REDIS_POOL_SIZE", 10)?,
             connect_timeout_secs: parse_env("REDIS_CONNECT_TIMEOUT", 5)?,
+            ttl_jitter_pct: parse_env("CACHE_TTL_JITTER_PCT", DEFAULT_TTL_JITTER_PCT)?,
         })
     }
 
     /// Create a configuration with a custom URL (useful for testing)
     pub fn with_url(url: impl Into<String>) -> Self {
         Self {
-            url: url.into(),
+            url: Redacted::new(url.into()),
             password: None,
             pool_size: 10,
             connect_timeout_secs: 5,
+            ttl_jitter_pct: DEFAULT_TTL_JITTER_PCT,
         }
     }
 
     /// Build the full connection URL including password if set
+    ///
+    /// The result contains the password in plain text; do not log it.
     pub fn connection_url(&self) -> String {
+        let url = self.url.expose();
         if let Some(ref password) = self.password {
-            // Parse URL and insert password
-            if let Some(at_pos) = self.url.find("://") {
-                let (scheme, rest) = self.url.split_at(at_pos + 3);
-                return format!("{}:{}@{}", scheme.trim_end_matches("://"), password, rest);
+            // Parse URL and insert password (redis://:password@host)
+            if let Some(at_pos) = url.find("://") {
+                let (scheme, rest) = url.split_at(at_pos + 3);
+                return format!("{}:{}@{}", scheme, password.expose(), rest);
             }
         }
-        self.url.clone()
+        url.to_string()
     }
 }
 
 impl Default for RedisConfig {
     fn default() -> Self {
         Self {
-            url: "redis://localhost:6379".to_string(),
+            url: "redis://localhost:6379".into(),
             password: None,
             pool_size: 10,
             connect_timeout_secs: 5,
+            ttl_jitter_pct: DEFAULT_TTL_JITTER_PCT,
         }
     }
 }
@@ -72,15 +82,16 @@ mod tests {
     #[test]
     fn test_default_config() {
         let config = RedisConfig::default();
-        assert_eq!(config.url, "redis://localhost:6379");
+        assert_eq!(config.url.expose(), "redis://localhost:6379");
         assert!(config.password.is_none());
         assert_eq!(config.pool_size, 10);
+        assert_eq!(config.ttl_jitter_pct, DEFAULT_TTL_JITTER_PCT);
     }
 
     #[test]
     fn test_with_url() {
         let config = RedisConfig::with_url("redis://custom:6380");
-        assert_eq!(config.url, "redis://custom:6380");
+        assert_eq!(config.url.expose(), "redis://custom:6380");
     }
 
     #[test]
@@ -88,4 +99,22 @@ mod tests {
         let config = RedisConfig::default();
         assert_eq!(config.connection_url(), "redis://localhost:6379");
     }
+
+    #[test]
+    fn test_connection_url_with_password() {
+        let config = RedisConfig {
+            password: Some(Redacted::from("s3cret")),
+            ..Default::default()
+        };
+        assert_eq!(config.connection_url(), "redis://:s3cret@localhost:6379");
+    }
+
+    #[test]
+    fn test_debug_redacts_password() {
+        let mut config = RedisConfig::with_url("redis://:s3cret@localhost:6379");
+        config.password = Some(Redacted::from("s3cret"));
+        let debug = format!("{:?}", config);
+        assert!(!debug.contains("s3cret"));
+        assert!(debug.contains("password: Some(****)"));
+    }
 }
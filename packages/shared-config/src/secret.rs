@@ -0,0 +1,104 @@
+//! Secret values that are redacted when formatted
+
+use std::fmt;
+
+/// Placeholder printed instead of a secret value
+const REDACTED: &str = "****";
+
+/// A secret value whose `Debug` and `Display` output is always `****`
+///
+/// Wrap passwords, tokens, and URLs with embedded credentials in this type so
+/// they cannot leak through `{:?}` on a config struct or a stray log field.
+/// Use [`Redacted::expose`] at the few places that need the real value.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Redacted<T>(T);
+
+impl<T> Redacted<T> {
+    /// Wrap a secret value
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Unwrap the secret value
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: AsRef<str>> Redacted<T> {
+    /// Get the secret value
+    pub fn expose(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl From<String> for Redacted<String> {
+    fn from(value: String) -> Self {
+        Self(value)
+    }
+}
+
+impl From<&str> for Redacted<String> {
+    fn from(value: &str) -> Self {
+        Self(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debug_is_redacted() {
+        let secret = Redacted::from("hunter2");
+        assert_eq!(format!("{:?}", secret), "****");
+    }
+
+    #[test]
+    fn test_display_is_redacted() {
+        let secret = Redacted::from("hunter2");
+        assert_eq!(format!("{}", secret), "****");
+        assert_eq!(secret.to_string(), "****");
+    }
+
+    #[test]
+    fn test_redacted_inside_struct_debug() {
+        #[derive(Debug)]
+        #[allow(dead_code)]
+        struct Credentials {
+            user: String,
+            password: Redacted<String>,
+        }
+
+        let credentials = Credentials {
+            user: "admin".to_string(),
+            password: Redacted::from("hunter2"),
+        };
+        let debug = format!("{:?}", credentials);
+        assert!(debug.contains("admin"));
+        assert!(debug.contains("****"));
+        assert!(!debug.contains("hunter2"));
+
+        let optional = Some(Redacted::from("hunter2"));
+        assert_eq!(format!("{:?}", optional), "Some(****)");
+    }
+
+    #[test]
+    fn test_expose_returns_original() {
+        let secret = Redacted::new("hunter2".to_string());
+        assert_eq!(secret.expose(), "hunter2");
+        assert_eq!(secret.into_inner(), "hunter2");
+    }
+}
@@ -145,6 +145,71 @@ impl MockLidarrServer {
             .await;
     }
 
+    /// Mount a mock for `/api/v1/system/status` reporting `version`
+    pub async fn mock_system_status(&self, version: &str) {
+        self.mock_system_status_delayed(version, std::time::Duration::ZERO)
+            .await;
+    }
+
+    /// Mount a `/api/v1/system/status` mock that answers after `delay`
+    ///
+    /// Useful for simulating a slow or hung instance.
+    pub async fn mock_system_status_delayed(&self, version: &str, delay: std::time::Duration) {
+        Mock::given(method("GET"))
+            .and(path("/api/v1/system/status"))
+            .and(header("X-Api-Key", self.api_key.as_str()))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_delay(delay)
+                    .set_body_json(json!({
+                        "appName": "Lidarr",
+                        "version": version
+                    })),
+            )
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Mount a mock answering the first `failures` artist/album requests with 503
+    ///
+    /// Mounted with priority over the regular list mocks, so once the failures
+    /// are used up requests fall through to e.g. [`Self::mock_artists_success`].
+    pub async fn mock_transient_errors(&self, failures: u64) {
+        Mock::given(method("GET"))
+            .and(path_regex("/api/v1/(artist|album)"))
+            .and(header("X-Api-Key", self.api_key.as_str()))
+            .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+                "error": "Service temporarily unavailable"
+            })))
+            .up_to_n_times(failures)
+            .with_priority(1)
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Mount a mock for `POST /api/v1/command` that always fails with 502
+    pub async fn mock_command_error(&self) {
+        Mock::given(method("POST"))
+            .and(path("/api/v1/command"))
+            .and(header("X-Api-Key", self.api_key.as_str()))
+            .respond_with(ResponseTemplate::new(502).set_body_json(json!({
+                "error": "Bad gateway"
+            })))
+            .mount(&self.server)
+            .await;
+    }
+
+    /// Number of requests received for `http_method` and `request_path`
+    pub async fn request_count(&self, http_method: &str, request_path: &str) -> usize {
+        self.server
+            .received_requests()
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|r| r.method.to_string() == http_method && r.url.path() == request_path)
+            .count()
+    }
+
     /// Mount a mock for rate limiting
     pub async fn mock_rate_limit(&self) {
         Mock::given(method("GET"))
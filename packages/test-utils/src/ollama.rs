@@ -227,6 +227,20 @@ impl MockOllamaServer {
             .await;
     }
 
+    /// Mount a mock for a model that is still loading
+    ///
+    /// Ollama answers 503 with a "loading model" error while it loads a model
+    /// into memory; this applies to embedding, generation, and chat requests.
+    pub async fn mock_model_loading(&self) {
+        Mock::given(method("POST"))
+            .and(path_regex("/api/(embeddings|generate|chat)"))
+            .respond_with(ResponseTemplate::new(503).set_body_json(json!({
+                "error": "loading model"
+            })))
+            .mount(&self.server)
+            .await;
+    }
+
     /// Get embedding call count
     pub fn embedding_calls(&self) -> usize {
         self.embedding_call_count.load(Ordering::SeqCst)